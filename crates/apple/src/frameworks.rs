@@ -0,0 +1,278 @@
+// This file has been automatically generated by `objc2`'s `header-translator`.
+// DO NOT EDIT
+
+#[cfg(feature = "ARKit")]
+pub use objc2_ar_kit as ARKit;
+
+#[cfg(feature = "AVFAudio")]
+pub use objc2_avf_audio as AVFAudio;
+
+#[cfg(feature = "AVFoundation")]
+pub use objc2_av_foundation as AVFoundation;
+
+#[cfg(feature = "AVKit")]
+pub use objc2_av_kit as AVKit;
+
+#[cfg(feature = "Accessibility")]
+pub use objc2_accessibility as Accessibility;
+
+#[cfg(feature = "Accounts")]
+pub use objc2_accounts as Accounts;
+
+#[cfg(feature = "AdServices")]
+pub use objc2_ad_services as AdServices;
+
+#[cfg(feature = "AdSupport")]
+pub use objc2_ad_support as AdSupport;
+
+#[cfg(feature = "AppKit")]
+pub use objc2_app_kit as AppKit;
+
+#[cfg(feature = "AppTrackingTransparency")]
+pub use objc2_app_tracking_transparency as AppTrackingTransparency;
+
+#[cfg(feature = "AudioToolbox")]
+pub use objc2_audio_toolbox as AudioToolbox;
+
+#[cfg(feature = "AuthenticationServices")]
+pub use objc2_authentication_services as AuthenticationServices;
+
+#[cfg(feature = "AutomaticAssessmentConfiguration")]
+pub use objc2_automatic_assessment_configuration as AutomaticAssessmentConfiguration;
+
+#[cfg(feature = "Automator")]
+pub use objc2_automator as Automator;
+
+#[cfg(feature = "BackgroundAssets")]
+pub use objc2_background_assets as BackgroundAssets;
+
+#[cfg(feature = "BackgroundTasks")]
+pub use objc2_background_tasks as BackgroundTasks;
+
+#[cfg(feature = "BusinessChat")]
+pub use objc2_business_chat as BusinessChat;
+
+#[cfg(feature = "CallKit")]
+pub use objc2_call_kit as CallKit;
+
+#[cfg(feature = "ClassKit")]
+pub use objc2_class_kit as ClassKit;
+
+#[cfg(feature = "CloudKit")]
+pub use objc2_cloud_kit as CloudKit;
+
+#[cfg(feature = "Contacts")]
+pub use objc2_contacts as Contacts;
+
+#[cfg(feature = "ContactsUI")]
+pub use objc2_contacts_ui as ContactsUI;
+
+#[cfg(feature = "CoreAudioTypes")]
+pub use objc2_core_audio_types as CoreAudioTypes;
+
+#[cfg(feature = "CoreBluetooth")]
+pub use objc2_core_bluetooth as CoreBluetooth;
+
+#[cfg(feature = "CoreData")]
+pub use objc2_core_data as CoreData;
+
+#[cfg(feature = "CoreFoundation")]
+pub use objc2_core_foundation as CoreFoundation;
+
+#[cfg(feature = "CoreGraphics")]
+pub use objc2_core_graphics as CoreGraphics;
+
+#[cfg(feature = "CoreImage")]
+pub use objc2_core_image as CoreImage;
+
+#[cfg(feature = "CoreLocation")]
+pub use objc2_core_location as CoreLocation;
+
+#[cfg(feature = "CoreML")]
+pub use objc2_core_ml as CoreML;
+
+#[cfg(feature = "CoreMedia")]
+pub use objc2_core_media as CoreMedia;
+
+#[cfg(feature = "CoreMotion")]
+pub use objc2_core_motion as CoreMotion;
+
+#[cfg(feature = "CoreText")]
+pub use objc2_core_text as CoreText;
+
+#[cfg(feature = "CoreVideo")]
+pub use objc2_core_video as CoreVideo;
+
+#[cfg(feature = "CoreWLAN")]
+pub use objc2_core_wlan as CoreWLAN;
+
+#[cfg(feature = "DataDetection")]
+pub use objc2_data_detection as DataDetection;
+
+#[cfg(feature = "DeviceCheck")]
+pub use objc2_device_check as DeviceCheck;
+
+#[cfg(feature = "EventKit")]
+pub use objc2_event_kit as EventKit;
+
+#[cfg(feature = "EventKitUI")]
+pub use objc2_event_kit_ui as EventKitUI;
+
+#[cfg(feature = "ExceptionHandling")]
+pub use objc2_exception_handling as ExceptionHandling;
+
+#[cfg(feature = "ExtensionKit")]
+pub use objc2_extension_kit as ExtensionKit;
+
+#[cfg(feature = "ExternalAccessory")]
+pub use objc2_external_accessory as ExternalAccessory;
+
+#[cfg(feature = "FileProvider")]
+pub use objc2_file_provider as FileProvider;
+
+#[cfg(feature = "FileProviderUI")]
+pub use objc2_file_provider_ui as FileProviderUI;
+
+#[cfg(feature = "FinderSync")]
+pub use objc2_finder_sync as FinderSync;
+
+#[cfg(feature = "Foundation")]
+pub use objc2_foundation as Foundation;
+
+#[cfg(feature = "GameController")]
+pub use objc2_game_controller as GameController;
+
+#[cfg(feature = "GameKit")]
+pub use objc2_game_kit as GameKit;
+
+#[cfg(feature = "HealthKit")]
+pub use objc2_health_kit as HealthKit;
+
+#[cfg(feature = "IOSurface")]
+pub use objc2_io_surface as IOSurface;
+
+#[cfg(feature = "IdentityLookup")]
+pub use objc2_identity_lookup as IdentityLookup;
+
+#[cfg(feature = "InputMethodKit")]
+pub use objc2_input_method_kit as InputMethodKit;
+
+#[cfg(feature = "LinkPresentation")]
+pub use objc2_link_presentation as LinkPresentation;
+
+#[cfg(feature = "LocalAuthentication")]
+pub use objc2_local_authentication as LocalAuthentication;
+
+#[cfg(feature = "LocalAuthenticationEmbeddedUI")]
+pub use objc2_local_authentication_embedded_ui as LocalAuthenticationEmbeddedUI;
+
+#[cfg(feature = "MLCompute")]
+pub use objc2_ml_compute as MLCompute;
+
+#[cfg(feature = "MailKit")]
+pub use objc2_mail_kit as MailKit;
+
+#[cfg(feature = "MapKit")]
+pub use objc2_map_kit as MapKit;
+
+#[cfg(feature = "MediaPlayer")]
+pub use objc2_media_player as MediaPlayer;
+
+#[cfg(feature = "Metal")]
+pub use objc2_metal as Metal;
+
+#[cfg(feature = "MetalFX")]
+pub use objc2_metal_fx as MetalFX;
+
+#[cfg(feature = "MetalKit")]
+pub use objc2_metal_kit as MetalKit;
+
+#[cfg(feature = "MetalPerformanceShaders")]
+pub use objc2_metal_performance_shaders as MetalPerformanceShaders;
+
+#[cfg(feature = "MetalPerformanceShadersGraph")]
+pub use objc2_metal_performance_shaders_graph as MetalPerformanceShadersGraph;
+
+#[cfg(feature = "MetricKit")]
+pub use objc2_metric_kit as MetricKit;
+
+#[cfg(feature = "MultipeerConnectivity")]
+pub use objc2_multipeer_connectivity as MultipeerConnectivity;
+
+#[cfg(feature = "NaturalLanguage")]
+pub use objc2_natural_language as NaturalLanguage;
+
+#[cfg(feature = "NearbyInteraction")]
+pub use objc2_nearby_interaction as NearbyInteraction;
+
+#[cfg(feature = "NetworkExtension")]
+pub use objc2_network_extension as NetworkExtension;
+
+#[cfg(feature = "OSAKit")]
+pub use objc2_osa_kit as OSAKit;
+
+#[cfg(feature = "OpenDirectory")]
+pub use objc2_open_directory as OpenDirectory;
+
+#[cfg(feature = "Photos")]
+pub use objc2_photos as Photos;
+
+#[cfg(feature = "PhotosUI")]
+pub use objc2_photos_ui as PhotosUI;
+
+#[cfg(feature = "QuartzCore")]
+pub use objc2_quartz_core as QuartzCore;
+
+#[cfg(feature = "ScreenCaptureKit")]
+pub use objc2_screen_capture_kit as ScreenCaptureKit;
+
+#[cfg(feature = "ScreenSaver")]
+pub use objc2_screen_saver as ScreenSaver;
+
+#[cfg(feature = "SensitiveContentAnalysis")]
+pub use objc2_sensitive_content_analysis as SensitiveContentAnalysis;
+
+#[cfg(feature = "ServiceManagement")]
+pub use objc2_service_management as ServiceManagement;
+
+#[cfg(feature = "Social")]
+pub use objc2_social as Social;
+
+#[cfg(feature = "SoundAnalysis")]
+pub use objc2_sound_analysis as SoundAnalysis;
+
+#[cfg(feature = "Speech")]
+pub use objc2_speech as Speech;
+
+#[cfg(feature = "StoreKit")]
+pub use objc2_store_kit as StoreKit;
+
+#[cfg(feature = "Symbols")]
+pub use objc2_symbols as Symbols;
+
+#[cfg(feature = "SystemConfiguration")]
+pub use objc2_system_configuration as SystemConfiguration;
+
+#[cfg(feature = "SystemExtensions")]
+pub use objc2_system_extensions as SystemExtensions;
+
+#[cfg(feature = "UIKit")]
+pub use objc2_ui_kit as UIKit;
+
+#[cfg(feature = "UniformTypeIdentifiers")]
+pub use objc2_uniform_type_identifiers as UniformTypeIdentifiers;
+
+#[cfg(feature = "UserNotifications")]
+pub use objc2_user_notifications as UserNotifications;
+
+#[cfg(feature = "Virtualization")]
+pub use objc2_virtualization as Virtualization;
+
+#[cfg(feature = "Vision")]
+pub use objc2_vision as Vision;
+
+#[cfg(feature = "WebKit")]
+pub use objc2_web_kit as WebKit;
+
+#[cfg(feature = "simd")]
+pub use objc2_simd as simd;
@@ -0,0 +1,55 @@
+//! # Bindings to Apple's frameworks
+//!
+//! This crate re-exports the [framework crates] under a single namespace,
+//! for applications that use many frameworks and do not want to keep track
+//! of a dependency for each of them.
+//!
+//! [framework crates]: https://docs.rs/objc2/latest/objc2/topics/about_generated/index.html
+//!
+//! Each framework is re-exported as a module with the same name as the
+//! framework, e.g. `objc2-app-kit` is available as `apple::AppKit`, and is
+//! enabled with a Cargo feature of the same name. Enabling a framework
+//! enables all of the framework crate's features; if compile times are a
+//! concern, depend on the framework crate directly instead, and only enable
+//! the features you need.
+//!
+//! Commonly used items from [`objc2`] and the enabled frameworks are
+//! available in the [`prelude`].
+//!
+//!
+//! ## Example
+//!
+//! ```console
+//! $ cargo add apple --features=Foundation,AppKit
+//! ```
+//!
+//! ```ignore
+//! use apple::prelude::*;
+//! use apple::AppKit::NSApplicationActivationPolicy;
+//!
+//! let mtm = MainThreadMarker::new().unwrap();
+//! let app = NSApplication::sharedApplication(mtm);
+//! app.setActivationPolicy(NSApplicationActivationPolicy::Regular);
+//!
+//! let title = ns_string!("Hello from Rust");
+//! println!("{title}");
+//! ```
+#![no_std]
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+// Update in Cargo.toml as well.
+#![doc(html_root_url = "https://docs.rs/apple/0.2.2")]
+
+#[doc(no_inline)]
+pub use objc2;
+
+#[cfg(feature = "block2")]
+#[doc(no_inline)]
+pub use block2;
+
+#[cfg(feature = "dispatch2")]
+#[doc(no_inline)]
+pub use dispatch2;
+
+include!("frameworks.rs");
+
+pub mod prelude;
@@ -0,0 +1,42 @@
+//! Commonly used items, intended to be glob imported.
+//!
+//! ```
+//! use apple::prelude::*;
+//! ```
+//!
+//! Items from a framework are only included if the framework is enabled.
+#[doc(no_inline)]
+pub use objc2::rc::{autoreleasepool, Retained, Weak};
+#[doc(no_inline)]
+pub use objc2::runtime::{AnyClass, AnyObject, NSObject, NSObjectProtocol, ProtocolObject, Sel};
+#[doc(no_inline)]
+pub use objc2::{
+    class, define_class, extern_class, extern_methods, extern_protocol, msg_send, sel,
+    AllocAnyThread, ClassType, DefinedClass, MainThreadMarker, MainThreadOnly, Message,
+    ProtocolType,
+};
+
+#[cfg(feature = "block2")]
+#[doc(no_inline)]
+pub use block2::{Block, RcBlock, StackBlock};
+
+#[cfg(feature = "CoreFoundation")]
+#[doc(no_inline)]
+pub use crate::CoreFoundation::{CFRetained, CFType, CGFloat, CGPoint, CGRect, CGSize};
+
+#[cfg(feature = "Foundation")]
+#[doc(no_inline)]
+pub use crate::Foundation::{
+    ns_string, NSArray, NSCopying, NSData, NSDictionary, NSError, NSMutableArray, NSMutableCopying,
+    NSMutableDictionary, NSMutableString, NSNumber, NSSet, NSString, NSURL,
+};
+
+#[cfg(feature = "AppKit")]
+#[doc(no_inline)]
+pub use crate::AppKit::{
+    NSApplication, NSApplicationDelegate, NSView, NSViewController, NSWindow, NSWindowDelegate,
+};
+
+#[cfg(feature = "UIKit")]
+#[doc(no_inline)]
+pub use crate::UIKit::{UIApplication, UIApplicationDelegate, UIView, UIViewController, UIWindow};
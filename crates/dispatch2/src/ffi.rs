@@ -114,6 +114,33 @@ pub static DISPATCH_QUEUE_CONCURRENT: &dispatch_queue_attr_s = {
     unsafe { &_dispatch_queue_attr_concurrent }
 };
 
+/// A dispatch source that monitors a file descriptor for pending bytes available to be read.
+pub static DISPATCH_SOURCE_TYPE_READ: &dispatch_source_type_s = {
+    // Safety: immutable external definition
+    unsafe { &_dispatch_source_type_read }
+};
+/// A dispatch source that monitors a file descriptor for available buffer space to write bytes.
+pub static DISPATCH_SOURCE_TYPE_WRITE: &dispatch_source_type_s = {
+    // Safety: immutable external definition
+    unsafe { &_dispatch_source_type_write }
+};
+/// A dispatch source that monitors the current process for signals.
+pub static DISPATCH_SOURCE_TYPE_SIGNAL: &dispatch_source_type_s = {
+    // Safety: immutable external definition
+    unsafe { &_dispatch_source_type_signal }
+};
+/// A dispatch source that submits the event handler based on a timer.
+pub static DISPATCH_SOURCE_TYPE_TIMER: &dispatch_source_type_s = {
+    // Safety: immutable external definition
+    unsafe { &_dispatch_source_type_timer }
+};
+/// A dispatch source that monitors an external process for events.
+#[cfg(target_vendor = "apple")]
+pub static DISPATCH_SOURCE_TYPE_PROC: &dispatch_source_type_s = {
+    // Safety: immutable external definition
+    unsafe { &_dispatch_source_type_proc }
+};
+
 pub const DISPATCH_APPLY_AUTO: dispatch_queue_t = core::ptr::null_mut();
 pub const DISPATCH_TARGET_QUEUE_DEFAULT: dispatch_queue_t = core::ptr::null_mut();
 pub const DISPATCH_CURRENT_QUEUE_LABEL: dispatch_queue_t = core::ptr::null_mut();
@@ -45,6 +45,7 @@ pub mod object;
 mod once;
 pub mod queue;
 pub mod semaphore;
+pub mod source;
 mod utils;
 
 /// Wait error.
@@ -98,3 +99,4 @@ pub use self::object::*;
 pub use self::once::*;
 pub use self::queue::*;
 pub use self::semaphore::*;
+pub use self::source::*;
@@ -0,0 +1,402 @@
+//! Dispatch source definition.
+
+use alloc::boxed::Box;
+use core::ffi::{c_int, c_void};
+use core::time::Duration;
+
+use super::ffi::*;
+use super::object::DispatchObject;
+use super::queue::Queue;
+
+/// Error returned by [Source::timer] and [Source::set_timer].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum SourceTimerError {
+    /// The given start time will result in an overflow when converting to dispatch time.
+    TimeOverflow,
+}
+
+/// The context of a source, containing the event handler.
+struct Context<F> {
+    source: dispatch_source_t,
+    handler: F,
+}
+
+extern "C" fn event_wrapper<F>(context: *mut c_void)
+where
+    F: Fn(usize),
+{
+    // Safety: The context is a `Context<F>`, which lives until the source is finalized.
+    let context = unsafe { &*context.cast::<Context<F>>() };
+
+    // Safety: The source is alive while its event handler is running.
+    let data = unsafe { dispatch_source_get_data(context.source) };
+
+    (context.handler)(data as usize);
+}
+
+extern "C" fn finalizer_wrapper<F>(context: *mut c_void) {
+    // Safety: The context was created from a Box, and the finalizer is run exactly once.
+    drop(unsafe { Box::from_raw(context.cast::<Context<F>>()) });
+}
+
+/// Dispatch source.
+///
+/// A source monitors a system event, such as a file descriptor becoming
+/// readable, and submits its event handler to a [Queue] when the event
+/// occurs. The handler is never run concurrently with itself.
+///
+/// Events that occur while the handler is running, or while the source is
+/// suspended, are coalesced, and the handler is given the accumulated data
+/// of the events; see the constructors for what the data means for each
+/// kind of source.
+///
+/// The source is cancelled when dropped, after which the handler is no
+/// longer submitted. It may still be running when the source is dropped.
+#[derive(Debug)]
+pub struct Source {
+    dispatch_object: DispatchObject<dispatch_source_s>,
+    is_timer: bool,
+}
+
+impl Source {
+    /// Create a new active [Source], and submit the handler to the [Queue]
+    /// for each event.
+    ///
+    /// Returns None if the handle or mask is invalid.
+    ///
+    /// # Safety
+    ///
+    /// - `source_type`, `handle` and `mask` must be valid for each other.
+    unsafe fn new<F>(
+        source_type: dispatch_source_type_t,
+        handle: usize,
+        mask: usize,
+        queue: &Queue,
+        handler: F,
+        configure: impl FnOnce(dispatch_source_t),
+    ) -> Option<Self>
+    where
+        F: Fn(usize) + Send + 'static,
+    {
+        // Safety: Upheld by the caller, and the queue cannot be null.
+        let object =
+            unsafe { dispatch_source_create(source_type, handle as _, mask as _, queue.as_raw()) };
+
+        if object.is_null() {
+            return None;
+        }
+
+        // Safety: object cannot be null.
+        let dispatch_object = unsafe { DispatchObject::new_owned(object.cast()) };
+
+        let context = Box::into_raw(Box::new(Context {
+            source: object,
+            handler,
+        }))
+        .cast();
+
+        // Safety: The context is only accessed by the event handler and the
+        // finalizer, which are wrapped to avoid ABI incompatibility. The
+        // source is inactive, so neither can run yet.
+        unsafe {
+            dispatch_set_context(object.cast(), context);
+            dispatch_set_finalizer_f(object.cast(), finalizer_wrapper::<F>);
+            dispatch_source_set_event_handler_f(object, event_wrapper::<F>);
+        }
+
+        configure(object);
+
+        let mut dispatch_object = dispatch_object;
+        dispatch_object.activate();
+
+        Some(Source {
+            dispatch_object,
+            is_timer: core::ptr::eq(source_type, DISPATCH_SOURCE_TYPE_TIMER),
+        })
+    }
+
+    /// Create a [Source] that monitors a file descriptor for pending data to
+    /// read.
+    ///
+    /// The handler is given an estimate of the number of bytes available to
+    /// read.
+    ///
+    /// # Safety
+    ///
+    /// - `fd` must be a valid file descriptor, and it must not be closed
+    ///   until the source is cancelled.
+    pub unsafe fn read<F>(fd: c_int, queue: &Queue, handler: F) -> Self
+    where
+        F: Fn(usize) + Send + 'static,
+    {
+        let source_type = DISPATCH_SOURCE_TYPE_READ as *const _ as *mut _;
+
+        // Safety: The handle is a file descriptor, and the mask is unused.
+        unsafe { Self::new(source_type, fd as usize, 0, queue, handler, |_| {}) }
+            .expect("dispatch_source_create shouldn't fail!")
+    }
+
+    /// Create a [Source] that monitors a file descriptor for available
+    /// buffer space to write.
+    ///
+    /// The handler is given an estimate of the available buffer space.
+    ///
+    /// # Safety
+    ///
+    /// - `fd` must be a valid file descriptor, and it must not be closed
+    ///   until the source is cancelled.
+    pub unsafe fn write<F>(fd: c_int, queue: &Queue, handler: F) -> Self
+    where
+        F: Fn(usize) + Send + 'static,
+    {
+        let source_type = DISPATCH_SOURCE_TYPE_WRITE as *const _ as *mut _;
+
+        // Safety: The handle is a file descriptor, and the mask is unused.
+        unsafe { Self::new(source_type, fd as usize, 0, queue, handler, |_| {}) }
+            .expect("dispatch_source_create shouldn't fail!")
+    }
+
+    /// Create a [Source] that monitors the current process for signals.
+    ///
+    /// The handler is given the number of signals received since it was
+    /// last run.
+    ///
+    /// Returns None if the signal number is invalid.
+    ///
+    /// Unlike signal handlers, the handler may run any code. The source does
+    /// not stop other signal handlers from running, so the default action of
+    /// the signal should usually be ignored with `signal(sig, SIG_IGN)`.
+    pub fn signal<F>(signal: c_int, queue: &Queue, handler: F) -> Option<Self>
+    where
+        F: Fn(usize) + Send + 'static,
+    {
+        let source_type = DISPATCH_SOURCE_TYPE_SIGNAL as *const _ as *mut _;
+
+        // Safety: The handle is a signal number, and the mask is unused.
+        unsafe { Self::new(source_type, signal as usize, 0, queue, handler, |_| {}) }
+    }
+
+    /// Create a [Source] that monitors a process for the given events.
+    ///
+    /// The handler is given the events that occurred since it was last run.
+    ///
+    /// Returns None if the process does not exist.
+    #[cfg(target_vendor = "apple")]
+    pub fn process<F>(
+        pid: c_int,
+        events: dispatch_source_proc_flags_t,
+        queue: &Queue,
+        handler: F,
+    ) -> Option<Self>
+    where
+        F: Fn(dispatch_source_proc_flags_t) + Send + 'static,
+    {
+        let source_type = DISPATCH_SOURCE_TYPE_PROC as *const _ as *mut _;
+
+        // Safety: The handle is a process ID, and the mask is a set of process
+        // events.
+        unsafe {
+            Self::new(
+                source_type,
+                pid as usize,
+                events.0 as usize,
+                queue,
+                move |data| handler(dispatch_source_proc_flags_t(data as _)),
+                |_| {},
+            )
+        }
+    }
+
+    /// Create a timer [Source] that first fires after `start`, and then
+    /// every `interval`, or only once if [None].
+    ///
+    /// The handler is given the number of times the timer has fired since it
+    /// was last run.
+    ///
+    /// The system may delay the timer by up to `leeway` to save power.
+    ///
+    /// # Errors
+    ///
+    /// Return [SourceTimerError::TimeOverflow] if the passed `start` is too big.
+    pub fn timer<F>(
+        start: Duration,
+        interval: Option<Duration>,
+        leeway: Duration,
+        queue: &Queue,
+        handler: F,
+    ) -> Result<Self, SourceTimerError>
+    where
+        F: Fn(usize) + Send + 'static,
+    {
+        let start = dispatch_time_t::try_from(start).map_err(|_| SourceTimerError::TimeOverflow)?;
+
+        let source_type = DISPATCH_SOURCE_TYPE_TIMER as *const _ as *mut _;
+
+        let configure = |object| {
+            // Safety: The source is a timer source.
+            unsafe { set_timer(object, start, interval, leeway) }
+        };
+
+        // Safety: The handle and mask are unused.
+        let source = unsafe { Self::new(source_type, 0, 0, queue, handler, configure) }
+            .expect("dispatch_source_create shouldn't fail!");
+
+        Ok(source)
+    }
+
+    /// Reschedule a timer [Source].
+    ///
+    /// See [Source::timer] for the meaning of the arguments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not a timer source.
+    ///
+    /// # Errors
+    ///
+    /// Return [SourceTimerError::TimeOverflow] if the passed `start` is too big.
+    pub fn set_timer(
+        &self,
+        start: Duration,
+        interval: Option<Duration>,
+        leeway: Duration,
+    ) -> Result<(), SourceTimerError> {
+        let start = dispatch_time_t::try_from(start).map_err(|_| SourceTimerError::TimeOverflow)?;
+
+        assert!(self.is_timer, "set_timer is only valid for timer sources");
+
+        // Safety: The source is a timer source.
+        unsafe { set_timer(self.as_raw(), start, interval, leeway) };
+
+        Ok(())
+    }
+
+    /// Suspend the invocation of the handler until the returned guard is
+    /// dropped.
+    ///
+    /// Events that occur while suspended are coalesced and delivered once
+    /// the source is resumed.
+    pub fn suspend(&self) -> SourceSuspendGuard<'_> {
+        self.dispatch_object.suspend();
+
+        SourceSuspendGuard(self)
+    }
+
+    /// Cancel the [Source], preventing further invocations of the handler.
+    ///
+    /// This is also done when the [Source] is dropped.
+    pub fn cancel(&self) {
+        // Safety: The source cannot be null.
+        unsafe { dispatch_source_cancel(self.as_raw()) };
+    }
+
+    /// Whether the [Source] has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        // Safety: The source cannot be null.
+        unsafe { dispatch_source_testcancel(self.as_raw()) != 0 }
+    }
+
+    /// Get the handle that the [Source] monitors, such as a file descriptor
+    /// or signal number.
+    pub fn handle(&self) -> usize {
+        // Safety: The source cannot be null.
+        unsafe { dispatch_source_get_handle(self.as_raw()) as usize }
+    }
+
+    /// Get the raw [dispatch_source_t] value.
+    ///
+    /// # Safety
+    ///
+    /// - Object shouldn't be released manually.
+    /// - The context, finalizer and event handler of the object must not be changed.
+    pub const unsafe fn as_raw(&self) -> dispatch_source_t {
+        // SAFETY: Upheld by caller.
+        unsafe { self.dispatch_object.as_raw() }
+    }
+}
+
+impl Drop for Source {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// # Safety
+///
+/// - `source` must be a timer source.
+unsafe fn set_timer(
+    source: dispatch_source_t,
+    start: dispatch_time_t,
+    interval: Option<Duration>,
+    leeway: Duration,
+) {
+    let interval = interval.map_or(DISPATCH_TIME_FOREVER.0, |interval| {
+        u64::try_from(interval.as_nanos()).unwrap_or(u64::MAX)
+    });
+    let leeway = u64::try_from(leeway.as_nanos()).unwrap_or(u64::MAX);
+
+    // Safety: Upheld by caller.
+    unsafe { dispatch_source_set_timer(source, start, interval, leeway) };
+}
+
+/// Dispatch source suspension guard.
+///
+/// The [Source] is resumed when this is dropped.
+#[derive(Debug)]
+pub struct SourceSuspendGuard<'a>(&'a Source);
+
+impl Drop for SourceSuspendGuard<'_> {
+    fn drop(&mut self) {
+        self.0.dispatch_object.resume();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::QueueAttribute;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_timer() {
+        let queue = Queue::new("com.github.madsmtm.objc2.source", QueueAttribute::Serial);
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let source = {
+            let count = Arc::clone(&count);
+            Source::timer(
+                Duration::ZERO,
+                Some(Duration::from_millis(1)),
+                Duration::ZERO,
+                &queue,
+                move |fired| {
+                    count.fetch_add(fired, Ordering::SeqCst);
+                },
+            )
+            .unwrap()
+        };
+
+        for _ in 0..1000 {
+            if count.load(Ordering::SeqCst) >= 3 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(count.load(Ordering::SeqCst) >= 3);
+
+        source.cancel();
+        assert!(source.is_cancelled());
+    }
+
+    #[test]
+    #[should_panic = "set_timer is only valid for timer sources"]
+    fn test_set_timer_on_signal() {
+        let queue = Queue::new("com.github.madsmtm.objc2.source", QueueAttribute::Serial);
+        let source = Source::signal(15, &queue, |_| {}).unwrap();
+        let _ = source.set_timer(Duration::ZERO, None, Duration::ZERO);
+    }
+}
@@ -172,6 +172,13 @@ pub struct LibraryConfig {
     #[serde(rename = "custom-lib-rs")]
     #[serde(default)]
     pub custom_lib_rs: bool,
+    /// Hand-written modules in `src/` that extend the generated bindings.
+    ///
+    /// These are declared and re-exported from the generated `lib.rs`, which
+    /// avoids having to use `custom-lib-rs` just for adding a few helpers.
+    #[serde(rename = "extra-files")]
+    #[serde(default)]
+    pub extra_files: BTreeMap<String, ExtraFile>,
     #[serde(default)]
     pub modulemap: Option<String>,
     #[serde(rename = "is-library")]
@@ -235,6 +242,32 @@ pub struct LibraryConfig {
     pub typedef_data: HashMap<String, TypedefData>,
 }
 
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ExtraFile {
+    /// The Cargo features that the module requires.
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// The items that the module exports.
+    #[serde(default)]
+    pub exports: Vec<String>,
+}
+
+impl ExtraFile {
+    pub fn cfg(&self) -> Option<String> {
+        let features: Vec<_> = self
+            .features
+            .iter()
+            .map(|feature| format!("feature = {feature:?}"))
+            .collect();
+        match &*features {
+            [] => None,
+            [feature] => Some(format!("#[cfg({feature})]")),
+            features => Some(format!("#[cfg(all({}))]", features.join(", "))),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
 pub struct Example {
@@ -150,6 +150,10 @@ impl Library {
             true,
         )?;
 
+        if self.data.custom_lib_rs && !self.data.extra_files.is_empty() {
+            error!("extra-files must be declared manually when using custom-lib-rs");
+        }
+
         if !self.data.custom_lib_rs {
             // Output `src/lib.rs`. Truncates if the file exists.
             let mut lib_rs = fs::File::create(crate_dir.join("src").join("lib.rs"))?;
@@ -185,9 +189,53 @@ impl Library {
             writeln!(lib_rs, "#[cfg(feature = \"std\")]")?;
             writeln!(lib_rs, "extern crate std;")?;
             writeln!(lib_rs)?;
-            writeln!(lib_rs, "mod generated;")?;
-            writeln!(lib_rs, "#[allow(unused_imports, unreachable_pub)]")?;
-            writeln!(lib_rs, "pub use self::generated::*;")?;
+
+            // Declare the modules in alphabetical order, like `rustfmt` would.
+            let mut modules: Vec<_> = self.data.extra_files.iter().map(Some).collect();
+            modules.push(None);
+            modules.sort_by_key(|module| module.map_or("generated", |(name, _)| &**name));
+
+            for module in &modules {
+                match module {
+                    Some((name, file)) => {
+                        if let Some(cfg) = file.cfg() {
+                            writeln!(lib_rs, "{cfg}")?;
+                        }
+                        writeln!(lib_rs, "mod {name};")?;
+                    }
+                    None => writeln!(lib_rs, "mod generated;")?,
+                }
+            }
+            if self
+                .data
+                .extra_files
+                .values()
+                .any(|file| !file.exports.is_empty())
+            {
+                writeln!(lib_rs)?;
+            }
+            for module in &modules {
+                match module {
+                    Some((name, file)) => {
+                        if file.exports.is_empty() {
+                            continue;
+                        }
+                        if let Some(cfg) = file.cfg() {
+                            writeln!(lib_rs, "{cfg}")?;
+                        }
+                        if let [export] = &*file.exports {
+                            writeln!(lib_rs, "pub use self::{name}::{export};")?;
+                        } else {
+                            let exports = file.exports.join(", ");
+                            writeln!(lib_rs, "pub use self::{name}::{{{exports}}};")?;
+                        }
+                    }
+                    None => {
+                        writeln!(lib_rs, "#[allow(unused_imports, unreachable_pub)]")?;
+                        writeln!(lib_rs, "pub use self::generated::*;")?;
+                    }
+                }
+            }
             lib_rs.flush()?;
         }
 
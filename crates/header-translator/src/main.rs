@@ -16,7 +16,7 @@ use tracing_tree::HierarchicalLayer;
 
 use header_translator::{
     global_analysis, run_cargo_fmt, Config, Context, EntryExt, Library, LibraryConfig, Location,
-    MacroEntity, MacroLocation, PlatformCfg, Stmt,
+    MacroEntity, MacroLocation, PlatformCfg, Stmt, VERSION,
 };
 
 type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
@@ -152,6 +152,8 @@ fn main() -> Result<(), BoxError> {
 
     update_list(workspace_dir, &config)?;
 
+    update_meta_crate(workspace_dir, &config)?;
+
     Ok(())
 }
 
@@ -680,6 +682,96 @@ fn update_list(workspace_dir: &Path, config: &Config) -> io::Result<()> {
     Ok(())
 }
 
+fn update_meta_crate(workspace_dir: &Path, config: &Config) -> io::Result<()> {
+    let _span = info_span!("updating apple meta-crate").entered();
+
+    let crate_dir = workspace_dir.join("crates").join("apple");
+
+    // Write re-exports
+    let mut s = String::new();
+    writeln!(
+        &mut s,
+        "// This file has been automatically generated by `objc2`'s `header-translator`."
+    )
+    .unwrap();
+    writeln!(&mut s, "// DO NOT EDIT").unwrap();
+    for (name, library) in config.to_parse() {
+        if library.is_library {
+            continue; // Re-exported manually
+        }
+        writeln!(&mut s).unwrap();
+        writeln!(&mut s, "#[cfg(feature = \"{name}\")]").unwrap();
+        writeln!(
+            &mut s,
+            "pub use {} as {name};",
+            library.krate.replace('-', "_")
+        )
+        .unwrap();
+    }
+    fs::write(crate_dir.join("src").join("frameworks.rs"), s)?;
+
+    // Add a feature for each framework
+    let mut f = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(crate_dir.join("Cargo.toml"))?;
+    let mut cargo_toml: toml_edit::DocumentMut =
+        io::read_to_string(&f)?.parse().expect("invalid apple toml");
+
+    let features = cargo_toml["features"].as_table_mut().unwrap();
+    let mut std =
+        toml_edit::Array::from_iter(["alloc", "objc2/std", "block2?/std", "dispatch2?/std"]);
+    let mut alloc =
+        toml_edit::Array::from_iter(["objc2/alloc", "block2?/alloc", "dispatch2?/alloc"]);
+    let mut all = toml_edit::Array::from_iter(["block2", "dispatch2"]);
+    for (name, library) in config.to_parse() {
+        if library.is_library {
+            continue;
+        }
+        let krate = &library.krate;
+        std.push(format!("{krate}?/std"));
+        alloc.push(format!("{krate}?/alloc"));
+        all.push(name);
+        features[name] =
+            toml_edit::Array::from_iter([format!("dep:{krate}"), format!("{krate}/all")]).into();
+    }
+    for array in [&mut std, &mut alloc, &mut all] {
+        // Inserting into array removes decor, so set it afterwards
+        for item in array.iter_mut() {
+            item.decor_mut().set_prefix("\n    ");
+        }
+        array.set_trailing("\n");
+        array.set_trailing_comma(true);
+    }
+    features["std"] = std.into();
+    features["alloc"] = alloc.into();
+    features["all"] = all.into();
+
+    let dependencies = cargo_toml["dependencies"].as_table_mut().unwrap();
+    for (_, library) in config.to_parse() {
+        if library.is_library {
+            continue;
+        }
+        let krate = &library.krate;
+        dependencies[krate] = toml_edit::InlineTable::from_iter([
+            (
+                "path",
+                toml_edit::Value::from(format!("../../framework-crates/{krate}")),
+            ),
+            ("version", toml_edit::Value::from(VERSION)),
+            ("default-features", toml_edit::Value::from(false)),
+            ("optional", toml_edit::Value::from(true)),
+        ])
+        .into();
+    }
+
+    f.set_len(0)?;
+    f.seek(io::SeekFrom::Start(0))?;
+    f.write_all(cargo_toml.to_string().as_bytes())?;
+
+    Ok(())
+}
+
 fn update_test_metadata<'a>(
     test_crate_dir: &Path,
     libraries: impl IntoIterator<Item = &'a LibraryConfig> + Clone,
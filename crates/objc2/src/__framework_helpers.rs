@@ -0,0 +1,73 @@
+//! Helpers for hand-written extensions in framework crates.
+//!
+//! Unlike [`__framework_prelude`](crate::__framework_prelude), this is not
+//! glob-imported into generated code, so it can contain functions without
+//! risking name clashes.
+
+// Note: While this is not public, it is still a breaking change to remove
+// entries in here, since framework crates rely on it.
+
+use core::ptr::NonNull;
+
+use crate::rc::{autoreleasepool_leaking, Retained};
+use crate::runtime::__nsstring::nsstring_to_str;
+use crate::runtime::NSObject;
+
+/// Cast a pointer to an array of retained objects to the array pointer
+/// type that methods taking `NSArray`-like buffers expect.
+#[inline]
+pub fn retained_ptr_cast<T: ?Sized>(objects: *mut Retained<T>) -> *mut NonNull<T> {
+    // SAFETY: `Retained<T>` has the same memory layout as `NonNull<T>`, and
+    // stronger guarantees.
+    objects.cast()
+}
+
+/// Same as [`retained_ptr_cast`], for `*const` pointers.
+#[inline]
+pub fn retained_ptr_cast_const<T: ?Sized>(objects: *const Retained<T>) -> *mut NonNull<T> {
+    retained_ptr_cast(objects as *mut Retained<T>)
+}
+
+/// Same as [`retained_ptr_cast_const`], for arrays of references.
+#[inline]
+pub fn ref_ptr_cast_const<T: ?Sized>(objects: *const &T) -> *mut NonNull<T> {
+    // SAFETY: `&T` has the same memory layout as `NonNull<T>`, and stronger
+    // guarantees.
+    (objects as *mut &T).cast()
+}
+
+/// Call the closure with the contents of the string, converted to UTF-8.
+///
+/// The converted string only lives in an autorelease pool for the duration
+/// of the call, so this can be used e.g. from `Display` impls without
+/// requiring the caller to manage a pool.
+///
+///
+/// # Safety
+///
+/// The object must be an instance of `NSString`.
+#[inline]
+pub unsafe fn with_nsstring_str<R>(string: &NSObject, f: impl FnOnce(&str) -> R) -> R {
+    // SAFETY:
+    // - The caller upholds that the object is a `NSString`.
+    // - We control the scope in which the string is alive, so we know
+    //   it is not moved outside the current autorelease pool.
+    autoreleasepool_leaking(|pool| f(unsafe { nsstring_to_str(string, pool) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ptr_casts() {
+        let obj = NSObject::new();
+        let objects = [obj.clone(), obj.clone()];
+        let ptr = retained_ptr_cast_const(objects.as_ptr());
+        assert_eq!(unsafe { *ptr.add(1) }, NonNull::from(&*obj));
+
+        let refs = [&*obj];
+        let ptr = ref_ptr_cast_const(refs.as_ptr());
+        assert_eq!(unsafe { *ptr }, NonNull::from(&*obj));
+    }
+}
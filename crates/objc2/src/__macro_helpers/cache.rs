@@ -1,10 +1,15 @@
 use core::ffi::{c_char, c_void, CStr};
+use core::mem;
 use core::ptr;
 use core::str;
-use core::sync::atomic::{AtomicPtr, Ordering};
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
 
 use crate::ffi;
-use crate::runtime::{AnyClass, Sel};
+use crate::rc::Retained;
+use crate::runtime::{AnyClass, AnyObject, Imp, MessageReceiver, Sel};
+use crate::Message;
+
+use super::{ConvertArguments, ConvertReturn, MsgSend};
 
 /// Allows storing a [`Sel`] in a static and lazily loading it.
 #[derive(Debug)]
@@ -99,6 +104,294 @@ impl CachedClass {
             unsafe { self.fetch(name.as_ptr().cast()) }
         }
     }
+
+    #[cold]
+    unsafe fn try_fetch(&self, name: *const c_char) -> Option<&'static AnyClass> {
+        let ptr: *const AnyClass = unsafe { ffi::objc_getClass(name) }.cast();
+        // Storing NULL is fine, we'll just try again next time (the class
+        // may have been loaded in the meantime).
+        self.ptr.store(ptr as *mut AnyClass, Ordering::Relaxed);
+        unsafe { ptr.as_ref() }
+    }
+
+    /// Returns the cached class. If no class is yet cached, tries to get one
+    /// with the given name and stores it, or returns `None` if the class is
+    /// not available.
+    #[inline]
+    pub unsafe fn try_get(&self, name: &str) -> Option<&'static AnyClass> {
+        let ptr = self.ptr.load(Ordering::Relaxed);
+        if let Some(cls) = unsafe { ptr.as_ref() } {
+            Some(cls)
+        } else {
+            // SAFETY: Checked by caller
+            unsafe { self.try_fetch(name.as_ptr().cast()) }
+        }
+    }
+}
+
+/// Allows storing the address of a symbol in a static and lazily looking it
+/// up.
+#[derive(Debug)]
+pub struct CachedSymbol {
+    ptr: AtomicPtr<c_void>,
+}
+
+#[cfg(not(windows))]
+extern "C" {
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+}
+
+// Search all images loaded into the process.
+#[cfg(any(target_vendor = "apple", target_os = "freebsd"))]
+const RTLD_DEFAULT: *mut c_void = -2isize as *mut c_void;
+#[cfg(not(any(target_vendor = "apple", target_os = "freebsd", windows)))]
+const RTLD_DEFAULT: *mut c_void = ptr::null_mut();
+
+impl CachedSymbol {
+    /// Constructs a new [`CachedSymbol`].
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            ptr: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    #[cold]
+    unsafe fn fetch(&self, name: *const c_char) -> *mut c_void {
+        // SAFETY: Input is a non-null, NUL-terminated C-string pointer.
+        #[cfg(not(windows))]
+        let ptr = unsafe { dlsym(RTLD_DEFAULT, name) };
+        // TODO: Use `GetProcAddress` on Windows.
+        #[cfg(windows)]
+        let ptr = {
+            let _ = name;
+            ptr::null_mut()
+        };
+        // Like in `CachedClass`, storing NULL means that we'll try again.
+        self.ptr.store(ptr, Ordering::Relaxed);
+        ptr
+    }
+
+    /// Returns the cached address of the symbol, or looks it up if not yet
+    /// cached. Returns NULL if the symbol could not be found.
+    #[inline]
+    pub unsafe fn get(&self, name: &str) -> *mut c_void {
+        // `Relaxed` should be fine since `dlsym` is thread-safe.
+        let ptr = self.ptr.load(Ordering::Relaxed);
+        if ptr.is_null() {
+            // SAFETY: Checked by caller
+            unsafe { self.fetch(name.as_ptr().cast()) }
+        } else {
+            ptr
+        }
+    }
+}
+
+/// Allows storing a [`Retained`] object in a static and lazily creating it.
+///
+/// Used for `#[cached]` methods in `extern_methods!`.
+#[derive(Debug)]
+pub struct CachedRetained {
+    ptr: AtomicPtr<AnyObject>,
+}
+
+impl CachedRetained {
+    /// Constructs a new [`CachedRetained`].
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            ptr: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    // Mark as cold since this should only ever be called once (or maybe twice
+    // if running on multiple threads).
+    #[cold]
+    fn fetch<T: Message>(&self, f: impl FnOnce() -> Retained<T>) -> Retained<T> {
+        // The cache owns one reference to the object, which is never
+        // released.
+        let ptr = Retained::into_raw(f());
+        match self.ptr.compare_exchange(
+            ptr::null_mut(),
+            ptr.cast(),
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            // SAFETY: The pointer came from `Retained` above.
+            Ok(_) => unsafe { Retained::retain(ptr) }.unwrap(),
+            Err(existing) => {
+                // Another thread was faster, use the object that it cached.
+                // SAFETY: The pointer came from `Retained` above, and we
+                // have not given out any other references to it.
+                drop(unsafe { Retained::from_raw(ptr) });
+                // SAFETY: Checked by caller of `get`.
+                unsafe { Retained::retain(existing.cast()) }.unwrap()
+            }
+        }
+    }
+
+    /// Returns the cached object. If no object is yet cached, creates one
+    /// with the given closure and stores it.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The same `T` must be used every time the cache is accessed, and `T`
+    /// must be safe to share between the threads that access the cache.
+    #[inline]
+    pub unsafe fn get<T: Message>(&self, f: impl FnOnce() -> Retained<T>) -> Retained<T> {
+        let ptr = self.ptr.load(Ordering::Acquire);
+        if ptr.is_null() {
+            self.fetch(f)
+        } else {
+            // SAFETY: The object is kept alive by the cache, and is of type
+            // `T` (checked by caller).
+            unsafe { Retained::retain(ptr.cast()) }.unwrap()
+        }
+    }
+}
+
+/// Incremented by [`flush_imp_caches`] to invalidate every [`CachedImp`].
+///
+/// [`flush_imp_caches`]: crate::runtime::flush_imp_caches
+pub(crate) static IMP_CACHE_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Allows storing the implementation of a method in a static, such that it
+/// can be called directly instead of going through `objc_msgSend`.
+///
+/// Used for `#[cached_imp]` methods in `extern_methods!`.
+///
+/// Only the implementation for the first class that the method is sent to
+/// is cached. Messages to instances of other classes (including subclasses,
+/// and classes created by key-value observing) are sent normally.
+#[derive(Debug)]
+pub struct CachedImp {
+    class: AtomicPtr<AnyClass>,
+    imp: AtomicPtr<c_void>,
+    generation: AtomicUsize,
+}
+
+impl CachedImp {
+    /// Constructs a new [`CachedImp`].
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            class: AtomicPtr::new(ptr::null_mut()),
+            imp: AtomicPtr::new(ptr::null_mut()),
+            // Never matches the global generation.
+            generation: AtomicUsize::new(usize::MAX),
+        }
+    }
+
+    #[cold]
+    fn update(&self, cls: &AnyClass, sel: Sel) {
+        // Load the generation before looking up the method, such that if the
+        // caches are flushed in the meantime, we look it up again next time.
+        let generation = IMP_CACHE_GENERATION.load(Ordering::Acquire);
+        let cls_ptr = cls as *const AnyClass as *mut AnyClass;
+        match self.class.compare_exchange(
+            ptr::null_mut(),
+            cls_ptr,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => {}
+            Err(existing) if existing == cls_ptr => {}
+            // The call site is polymorphic, don't cache anything.
+            Err(_) => return,
+        }
+        // Methods that are forwarded, or resolved dynamically, are not
+        // cached either.
+        if let Some(method) = cls.instance_method(sel) {
+            self.imp
+                .store(method.implementation() as *mut c_void, Ordering::Relaxed);
+            self.generation.store(generation, Ordering::Release);
+        }
+    }
+
+    /// Returns the cached implementation of the method, if the receiver is
+    /// of the class that the cache is for.
+    ///
+    /// Returns `None` if the message should be sent normally, and caches the
+    /// implementation for next time if possible.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The receiver must be a valid object or NULL.
+    #[inline]
+    unsafe fn get(&self, receiver: *mut AnyObject, sel: Sel) -> Option<Imp> {
+        // Messages to `nil` are sent normally.
+        let cls = unsafe { receiver.as_ref() }?.class();
+        if ptr::eq(self.class.load(Ordering::Relaxed), cls)
+            && self.generation.load(Ordering::Acquire)
+                == IMP_CACHE_GENERATION.load(Ordering::Relaxed)
+        {
+            let imp = self.imp.load(Ordering::Relaxed);
+            // SAFETY: The generation only matches once a (non-NULL)
+            // implementation has been stored.
+            Some(unsafe { mem::transmute::<*mut c_void, Imp>(imp) })
+        } else {
+            self.update(cls, sel);
+            None
+        }
+    }
+}
+
+/// A receiver whose messages are sent by calling the implementation stored
+/// in a [`CachedImp`] directly.
+///
+/// Used for `#[cached_imp]` methods in `extern_methods!`.
+#[derive(Debug)]
+pub struct CachedImpReceiver<'a, T> {
+    receiver: T,
+    cache: &'a CachedImp,
+}
+
+impl<'a, T: MsgSend> CachedImpReceiver<'a, T> {
+    #[inline]
+    pub fn new(receiver: T, cache: &'a CachedImp) -> Self {
+        Self { receiver, cache }
+    }
+}
+
+impl<T: MsgSend> MsgSend for CachedImpReceiver<'_, T> {
+    type Inner = T::Inner;
+    const MAIN_THREAD_ONLY: bool = T::MAIN_THREAD_ONLY;
+
+    #[inline]
+    fn into_raw_receiver(self) -> *mut AnyObject {
+        self.receiver.into_raw_receiver()
+    }
+
+    #[inline]
+    #[track_caller]
+    unsafe fn send_message<A, R>(self, sel: Sel, args: A) -> R
+    where
+        A: ConvertArguments,
+        R: ConvertReturn,
+    {
+        let (args, stored) = A::__into_arguments(args);
+        let cache = self.cache;
+        let receiver = self.into_raw_receiver();
+        #[cfg(feature = "thread-checker")]
+        if Self::MAIN_THREAD_ONLY {
+            crate::runtime::check_main_thread(receiver, sel);
+        }
+
+        // SAFETY: The receiver is valid or NULL, and the rest is upheld by
+        // the caller. The implementation is that of the method that the
+        // message would have been sent to.
+        let result = match unsafe { cache.get(receiver, sel) } {
+            Some(imp) => unsafe { crate::runtime::send_message_imp(imp, receiver, sel, args) },
+            None => unsafe { MessageReceiver::send_message(receiver, sel, args) },
+        };
+
+        // SAFETY: Same as in `MsgSend::send_message`.
+        unsafe { A::__process_after_message_send(stored) };
+
+        R::__from_return(result)
+    }
 }
 
 #[cfg(test)]
@@ -109,4 +402,24 @@ mod tests {
     fn test_not_found() {
         let _ = crate::class!(NonExistentClass);
     }
+
+    #[test]
+    fn test_class_available() {
+        use crate::runtime::NSObject;
+        use crate::ClassType;
+
+        assert!(crate::class_available!(NSObject));
+        assert!(crate::class_available!("NSObject"));
+        assert!(!crate::class_available!(NonExistentClass));
+        assert!(!crate::class_available!("NonExistentClass"));
+        assert!(NSObject::is_available());
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_symbol_available() {
+        assert!(crate::symbol_available!(objc_getClass));
+        assert!(crate::symbol_available!("objc_getClass"));
+        assert!(!crate::symbol_available!(NonExistentSymbol));
+    }
 }
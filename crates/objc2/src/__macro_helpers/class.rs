@@ -109,4 +109,24 @@ mod tests {
         #[name = "NSObject"]
         struct OnlyMainSubExplicit;
     );
+
+    // The thread kind is available to the `"thread-checker"` feature.
+    const _: () = {
+        use crate::__macro_helpers::MsgSend;
+        use crate::rc::Retained;
+        use crate::runtime::AnyClass;
+        use crate::Message;
+
+        assert!(!<NSObject as Message>::__MAIN_THREAD_ONLY);
+        assert!(!<SetAnyThread as Message>::__MAIN_THREAD_ONLY);
+        assert!(<OnlyMain as Message>::__MAIN_THREAD_ONLY);
+        assert!(<OnlyMainSubDefault as Message>::__MAIN_THREAD_ONLY);
+        assert!(<OnlyMainSubExplicit as Message>::__MAIN_THREAD_ONLY);
+
+        assert!(!<&NSObject as MsgSend>::MAIN_THREAD_ONLY);
+        assert!(<&OnlyMain as MsgSend>::MAIN_THREAD_ONLY);
+        assert!(<*mut OnlyMain as MsgSend>::MAIN_THREAD_ONLY);
+        assert!(<&Retained<OnlyMain> as MsgSend>::MAIN_THREAD_ONLY);
+        assert!(!<&AnyClass as MsgSend>::MAIN_THREAD_ONLY);
+    };
 }
@@ -141,8 +141,10 @@ pub(crate) fn setup_dealloc<T: DefinedClass>(builder: &mut ClassBuilder)
 where
     T::Super: ClassType,
 {
-    // Add dealloc if the class or the ivars need dropping.
-    if mem::needs_drop::<T>() || mem::needs_drop::<T::Ivars>() {
+    // Add dealloc if the class or the ivars need dropping, or if instances
+    // are being tracked.
+    if mem::needs_drop::<T>() || mem::needs_drop::<T::Ivars>() || cfg!(feature = "unstable-testing")
+    {
         let func: unsafe extern "C-unwind" fn(_, _) = dealloc::<T>;
         // SAFETY: The function signature is correct, and method contract is
         // upheld inside `dealloc`.
@@ -167,9 +169,17 @@ where
     #[cold]
     fn cold_path() {}
 
-    // SAFETY: `dealloc` is only registered when there is a need for dropping,
-    // and hence a need for a drop flag.
-    let drop_flag = unsafe { *ptr_to_drop_flag(this) };
+    #[cfg(feature = "unstable-testing")]
+    crate::testing::untrack(this.as_ptr().cast());
+
+    let drop_flag = if T::HAS_DROP_FLAG {
+        // SAFETY: Just checked that the drop flag is available.
+        unsafe { *ptr_to_drop_flag(this) }
+    } else {
+        // `dealloc` is only registered without a drop flag when tracking
+        // instances, in which case nothing below needs the drop flag.
+        DropFlag::Allocated
+    };
 
     if mem::needs_drop::<T>() {
         match drop_flag {
@@ -355,6 +365,9 @@ pub(crate) unsafe fn initialize_ivars<T: DefinedClass>(ptr: NonNull<T>, val: T::
     //   pointer is not shared elsewhere.
     unsafe { ptr_to_ivar(ptr).as_ptr().write(val) };
 
+    #[cfg(feature = "unstable-testing")]
+    crate::testing::track(ptr.as_ptr().cast(), T::NAME);
+
     // Write to drop flag that we've initialized the instance variables.
     //
     // Note: We intentionally only do this _after_ writing to the ivars,
@@ -395,6 +408,32 @@ pub(crate) unsafe fn set_finalized<T: DefinedClass>(ptr: NonNull<T>) {
     }
 }
 
+/// Move the instance variables out of the object, and mark it such that
+/// neither the instance variables nor the object itself are dropped in
+/// `dealloc`.
+///
+/// # Safety
+///
+/// The pointer must be valid and finalized, and the instance variables must
+/// not be accessed afterwards.
+#[inline]
+#[track_caller]
+pub(crate) unsafe fn take_ivars<T: DefinedClass>(ptr: NonNull<T>) -> T::Ivars {
+    // SAFETY: The pointer is valid and initialized, as ensured by caller.
+    let ivars = unsafe { get_initialized_ivar_ptr(ptr).as_ptr().read() };
+
+    if T::HAS_DROP_FLAG {
+        // SAFETY: Just checked that the drop flag is available.
+        //
+        // Resetting the drop flag to its initial state makes `dealloc` skip
+        // dropping both the ivars (which we now own) and the object (whose
+        // `Drop` implementation may access the ivars).
+        unsafe { ptr_to_drop_flag(ptr).write(DropFlag::Allocated) }
+    }
+
+    ivars
+}
+
 /// # Safety
 ///
 /// The pointer must be valid and the instance variables must be initialized.
@@ -601,6 +640,10 @@ mod tests {
     }
 
     #[test]
+    #[cfg_attr(
+        feature = "unstable-testing",
+        ignore = "`dealloc` is always generated when tracking instances"
+    )]
     fn test_no_generated_dealloc_if_not_needed() {
         #[allow(unused)]
         struct Ivar {
@@ -5,9 +5,10 @@ pub use core::convert::AsRef;
 pub use core::default::Default;
 pub use core::fmt;
 pub use core::hash::{Hash, Hasher};
+pub use core::iter::{Extend, FromIterator, IntoIterator};
 pub use core::marker::{PhantomData, Sized};
 pub use core::mem::{size_of, ManuallyDrop, MaybeUninit};
-pub use core::ops::Deref;
+pub use core::ops::{self, Deref};
 pub use core::option::Option::{self, None, Some};
 pub use core::primitive::{bool, isize, str, u8};
 pub use core::{compile_error, concat, panic, stringify};
@@ -29,7 +30,10 @@ mod os_version;
 mod sync_unsafe_cell;
 mod writeback;
 
-pub use self::cache::{CachedClass, CachedSel};
+pub(crate) use self::cache::IMP_CACHE_GENERATION;
+pub use self::cache::{
+    CachedClass, CachedImp, CachedImpReceiver, CachedRetained, CachedSel, CachedSymbol,
+};
 pub use self::class::{DoesNotImplDrop, MainThreadOnlyDoesNotImplSendSync, ValidThreadKind};
 pub use self::common_selectors::{alloc_sel, dealloc_sel, init_sel, new_sel};
 pub use self::convert::{ConvertArgument, ConvertArguments, ConvertReturn, TupleExtender};
@@ -11,6 +11,9 @@ use super::{ConvertArguments, ConvertReturn, TupleExtender};
 pub trait MsgSend: Sized {
     type Inner: ?Sized + RefEncode;
 
+    /// Whether the receiver may only be used from the main thread.
+    const MAIN_THREAD_ONLY: bool;
+
     fn into_raw_receiver(self) -> *mut AnyObject;
 
     #[inline]
@@ -21,9 +24,14 @@ pub trait MsgSend: Sized {
         R: ConvertReturn,
     {
         let (args, stored) = A::__into_arguments(args);
+        let receiver = self.into_raw_receiver();
+        #[cfg(feature = "thread-checker")]
+        if Self::MAIN_THREAD_ONLY {
+            crate::runtime::check_main_thread(receiver, sel);
+        }
 
         // SAFETY: Upheld by caller
-        let result = unsafe { MessageReceiver::send_message(self.into_raw_receiver(), sel, args) };
+        let result = unsafe { MessageReceiver::send_message(receiver, sel, args) };
 
         // TODO: If we want `objc_retainAutoreleasedReturnValue` to
         // work, we must not do any work before it has been run; so
@@ -46,11 +54,15 @@ pub trait MsgSend: Sized {
         R: ConvertReturn,
     {
         let (args, stored) = A::__into_arguments(args);
+        let receiver = self.into_raw_receiver();
+        #[cfg(feature = "thread-checker")]
+        if Self::MAIN_THREAD_ONLY {
+            crate::runtime::check_main_thread(receiver, sel);
+        }
 
         // SAFETY: Upheld by caller
-        let result = unsafe {
-            MessageReceiver::send_super_message(self.into_raw_receiver(), superclass, sel, args)
-        };
+        let result =
+            unsafe { MessageReceiver::send_super_message(receiver, superclass, sel, args) };
 
         // SAFETY: Same as in send_message above.
         unsafe { A::__process_after_message_send(stored) };
@@ -155,6 +167,7 @@ unsafe fn encountered_error<E: Message>(err: *mut E) -> Retained<E> {
 
 impl<T: MessageReceiver> MsgSend for T {
     type Inner = T::__Inner;
+    const MAIN_THREAD_ONLY: bool = T::__MAIN_THREAD_ONLY;
 
     #[inline]
     fn into_raw_receiver(self) -> *mut AnyObject {
@@ -164,6 +177,7 @@ impl<T: MessageReceiver> MsgSend for T {
 
 impl<T: ?Sized + Message> MsgSend for &Retained<T> {
     type Inner = T;
+    const MAIN_THREAD_ONLY: bool = T::__MAIN_THREAD_ONLY;
 
     #[inline]
     fn into_raw_receiver(self) -> *mut AnyObject {
@@ -173,6 +187,7 @@ impl<T: ?Sized + Message> MsgSend for &Retained<T> {
 
 impl<T: ?Sized + Message> MsgSend for ManuallyDrop<Retained<T>> {
     type Inner = T;
+    const MAIN_THREAD_ONLY: bool = T::__MAIN_THREAD_ONLY;
 
     #[inline]
     fn into_raw_receiver(self) -> *mut AnyObject {
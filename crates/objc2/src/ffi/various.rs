@@ -13,15 +13,46 @@ use crate::{
     runtime::{Bool, Ivar},
 };
 
-// /// Remember that this is non-null!
-// #[cfg(any(doc, all(target_vendor = "apple", not(all(target_os = "macos", target_arch = "x86")))))]
-// type objc_hook_getClass =
-//     unsafe extern "C" fn(name: *const c_char, out_cls: *mut *const crate::runtime::AnyClass) -> Bool;
+/// Remember that this is non-null!
+#[cfg(any(
+    doc,
+    all(
+        target_vendor = "apple",
+        not(all(target_os = "macos", target_arch = "x86"))
+    )
+))]
+pub type objc_hook_getClass = unsafe extern "C" fn(
+    name: *const c_char,
+    out_cls: *mut *const crate::runtime::AnyClass,
+) -> Bool;
+
+/// Remember that this is non-null!
+#[cfg(any(
+    doc,
+    all(
+        target_vendor = "apple",
+        not(all(target_os = "macos", target_arch = "x86"))
+    )
+))]
+pub type objc_hook_lazyClassNamer =
+    unsafe extern "C" fn(cls: *const crate::runtime::AnyClass) -> *const c_char;
+
+// Defined in GNUStep's `hooks.h`.
 //
-// /// Remember that this is non-null!
-// #[cfg(any(doc, all(target_vendor = "apple", not(all(target_os = "macos", target_arch = "x86")))))]
-// type objc_hook_lazyClassNamer =
-//     unsafe extern "C" fn(cls: *const crate::runtime::AnyClass) -> *const c_char;
+// These are plain global variables instead of setter functions, so changing
+// them is not thread-safe.
+#[cfg(any(doc, feature = "gnustep-1-7"))]
+extern "C" {
+    /// Called by `objc_getClass` and friends when a class could not be found.
+    pub static mut _objc_lookup_class:
+        Option<unsafe extern "C" fn(name: *const c_char) -> *mut crate::runtime::AnyClass>;
+
+    /// Called to get the implementation to use when a message is sent to an
+    /// object that doesn't respond to it.
+    pub static mut __objc_msg_forward2: Option<
+        unsafe extern "C" fn(receiver: *mut AnyObject, sel: crate::runtime::Sel) -> Option<Imp>,
+    >;
+}
 
 extern_c_unwind! {
     // Instead of being able to change this, it's a weak symbol on GNUStep.
@@ -83,19 +114,20 @@ extern_c! {
     pub fn objc_sync_enter(obj: *mut AnyObject) -> c_int;
     pub fn objc_sync_exit(obj: *mut AnyObject) -> c_int;
 
+    // Not linked directly, since they're newer than our minimum deployment
+    // target; `runtime::hooks` instead looks them up at runtime.
+    //
     // Available in macOS 10.14.4
-    // /// Remember that this is non-null!
     // #[cfg(any(doc, all(target_vendor = "apple", not(all(target_os = "macos", target_arch = "x86")))))]
     // pub fn objc_setHook_getClass(
     //     new_value: objc_hook_getClass,
-    //     out_old_value: *mut objc_hook_getClass,
+    //     out_old_value: *mut Option<objc_hook_getClass>,
     // );
     // Available in macOS 11
-    // /// Remember that this is non-null!
     // #[cfg(any(doc, all(target_vendor = "apple", not(all(target_os = "macos", target_arch = "x86")))))]
     // pub fn objc_setHook_lazyClassNamer(
     //     new_value: objc_hook_lazyClassNamer,
-    //     out_old_value: *mut objc_hook_lazyClassNamer,
+    //     out_old_value: *mut Option<objc_hook_lazyClassNamer>,
     // );
 
     // #[deprecated = "not recommended"]
@@ -198,8 +198,10 @@ macro_rules! __hash_idents {
     };
 }
 
-// Note: While this is not public, it is still a breaking change to change,
-// since framework crates rely on it.
+// Note: While these are not public, it is still a breaking change to change,
+// since framework crates rely on them.
+#[doc(hidden)]
+pub mod __framework_helpers;
 #[doc(hidden)]
 pub mod __framework_prelude;
 #[doc(hidden)]
@@ -210,10 +212,14 @@ pub mod exception;
 pub mod ffi;
 mod macros;
 mod main_thread_marker;
+#[cfg(feature = "objc")]
+pub mod objc_interop;
 pub mod rc;
 pub mod runtime;
 #[cfg(test)]
 mod test_utils;
+#[cfg(feature = "unstable-testing")]
+pub mod testing;
 mod top_level_traits;
 #[cfg(any(docsrs, doc, doctest, test))]
 pub mod topics;
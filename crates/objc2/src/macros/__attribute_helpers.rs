@@ -47,8 +47,9 @@ macro_rules! __extract_and_apply_cfg_attributes {
     };
 }
 
-/// Extract `#[method(...)]` or `#[method_id(...)]` and the `#[optional]`
-/// attribute, and send it to another macro.
+/// Extract `#[method(...)]` or `#[method_id(...)]` and the `#[optional]`,
+/// `#[cached]` and `#[cached_imp]` attributes, and send them to another
+/// macro.
 ///
 /// This will ensure that there is one and only one of the method attributes
 /// present.
@@ -76,6 +77,9 @@ macro_rules! __extract_and_apply_cfg_attributes {
 ///
 /// 4. The remaining attributes.
 ///    ($(#[$($m_checked:tt)*])*)
+///
+/// 5. The `cached` or `cached_imp` attribute, if any.
+///    ($(#[cached])? $(#[cached_imp])?)
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __extract_custom_attributes {
@@ -92,6 +96,7 @@ macro_rules! __extract_custom_attributes {
             () // retain semantics
             () // optional
             () // checked
+            () // cached
 
             ($out_macro)
             $($macro_args)*
@@ -111,6 +116,7 @@ macro_rules! __extract_custom_attributes_inner {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
 
         ($out_macro:path)
         $($macro_args:tt)*
@@ -127,6 +133,7 @@ macro_rules! __extract_custom_attributes_inner {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
 
         ($out_macro:path)
         $($macro_args:tt)*
@@ -139,6 +146,7 @@ macro_rules! __extract_custom_attributes_inner {
             ($($retain_semantics)*)
             ($($m_optional)*)
             ($($m_checked)*)
+            ($($m_cached)*)
         }
     };
 
@@ -153,6 +161,7 @@ macro_rules! __extract_custom_attributes_inner {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
 
         ($out_macro:path)
         $($macro_args:tt)*
@@ -164,6 +173,7 @@ macro_rules! __extract_custom_attributes_inner {
             ($($retain_semantics)*)
             ($($m_optional)*)
             ($($m_checked)*)
+            ($($m_cached)*)
 
             ($out_macro)
             $($macro_args)*
@@ -179,6 +189,7 @@ macro_rules! __extract_custom_attributes_inner {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
 
         ($out_macro:path)
         $($macro_args:tt)*
@@ -197,6 +208,7 @@ macro_rules! __extract_custom_attributes_inner {
         ()
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
 
         ($out_macro:path)
         $($macro_args:tt)*
@@ -208,6 +220,7 @@ macro_rules! __extract_custom_attributes_inner {
             ($retain_semantics)
             ($($m_optional)*)
             ($($m_checked)*)
+            ($($m_cached)*)
 
             ($out_macro)
             $($macro_args)*
@@ -224,6 +237,7 @@ macro_rules! __extract_custom_attributes_inner {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
 
         ($out_macro:path)
         $($macro_args:tt)*
@@ -235,6 +249,7 @@ macro_rules! __extract_custom_attributes_inner {
             ($($retain_semantics)*)
             ($($m_optional)*)
             ($($m_checked)*)
+            ($($m_cached)*)
 
             ($out_macro)
             $($macro_args)*
@@ -250,6 +265,7 @@ macro_rules! __extract_custom_attributes_inner {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
 
         ($out_macro:path)
         $($macro_args:tt)*
@@ -268,6 +284,7 @@ macro_rules! __extract_custom_attributes_inner {
         // If no existing `optional` attributes exist
         ()
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
 
         ($out_macro:path)
         $($macro_args:tt)*
@@ -279,6 +296,7 @@ macro_rules! __extract_custom_attributes_inner {
             // Add optional attribute
             (#[optional])
             ($($m_checked)*)
+            ($($m_cached)*)
 
             ($out_macro)
             $($macro_args)*
@@ -294,6 +312,7 @@ macro_rules! __extract_custom_attributes_inner {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
 
         ($out_macro:path)
         $($macro_args:tt)*
@@ -301,6 +320,100 @@ macro_rules! __extract_custom_attributes_inner {
         $crate::__macro_helpers::compile_error!("cannot specify the `optional` attribute twice");
     };
 
+    // `cached` attribute
+    {
+        (
+            #[cached]
+            $($rest:tt)*
+        )
+        ($($m_method:tt)*)
+        ($($retain_semantics:tt)*)
+        ($($m_optional:tt)*)
+        ($($m_checked:tt)*)
+        // If no existing `cached` attributes exist
+        ()
+
+        ($out_macro:path)
+        $($macro_args:tt)*
+    } => {
+        $crate::__extract_custom_attributes_inner! {
+            ($($rest)*)
+            ($($m_method)*)
+            ($($retain_semantics)*)
+            ($($m_optional)*)
+            ($($m_checked)*)
+            // Add cached attribute
+            (#[cached])
+
+            ($out_macro)
+            $($macro_args)*
+        }
+    };
+    // Duplicate `cached` attributes
+    {
+        (
+            #[cached]
+            $($rest:tt)*
+        )
+        ($($m_method:tt)*)
+        ($($retain_semantics:tt)*)
+        ($($m_optional:tt)*)
+        ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
+
+        ($out_macro:path)
+        $($macro_args:tt)*
+    } => {
+        $crate::__macro_helpers::compile_error!("cannot specify the `cached` attribute twice");
+    };
+
+    // `cached_imp` attribute
+    {
+        (
+            #[cached_imp]
+            $($rest:tt)*
+        )
+        ($($m_method:tt)*)
+        ($($retain_semantics:tt)*)
+        ($($m_optional:tt)*)
+        ($($m_checked:tt)*)
+        // If no existing `cached` nor `cached_imp` attributes exist
+        ()
+
+        ($out_macro:path)
+        $($macro_args:tt)*
+    } => {
+        $crate::__extract_custom_attributes_inner! {
+            ($($rest)*)
+            ($($m_method)*)
+            ($($retain_semantics)*)
+            ($($m_optional)*)
+            ($($m_checked)*)
+            // Add cached_imp attribute
+            (#[cached_imp])
+
+            ($out_macro)
+            $($macro_args)*
+        }
+    };
+    // Duplicate `cached_imp` attributes
+    {
+        (
+            #[cached_imp]
+            $($rest:tt)*
+        )
+        ($($m_method:tt)*)
+        ($($retain_semantics:tt)*)
+        ($($m_optional:tt)*)
+        ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
+
+        ($out_macro:path)
+        $($macro_args:tt)*
+    } => {
+        $crate::__macro_helpers::compile_error!("cannot specify the `cached`/`cached_imp` attribute twice");
+    };
+
     // Other attributes
     {
         (
@@ -311,6 +424,7 @@ macro_rules! __extract_custom_attributes_inner {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
 
         ($out_macro:path)
         $($macro_args:tt)*
@@ -326,6 +440,7 @@ macro_rules! __extract_custom_attributes_inner {
                 // been consuming the attributes from the front.
                 #[$($checked)*]
             )
+            ($($m_cached)*)
 
             ($out_macro)
             $($macro_args)*
@@ -853,3 +968,19 @@ macro_rules! __handle_duplicate {
         ));
     };
 }
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __attribute_no_cached {
+    () => {};
+    (#[cached]) => {
+        $crate::__macro_helpers::compile_error!(
+            "`#[cached]` is only supported in `extern_methods!`"
+        )
+    };
+    (#[cached_imp]) => {
+        $crate::__macro_helpers::compile_error!(
+            "`#[cached_imp]` is only supported in `extern_methods!`"
+        )
+    };
+}
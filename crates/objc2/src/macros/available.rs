@@ -57,9 +57,11 @@
 ///    build script or at `const` time.
 ///
 /// 2. Check at runtime that a class, method or symbol is available, using
-///    e.g. [`AnyClass::get`], [`respondsToSelector`] or [weak linking].
+///    e.g. [`class_available!`], [`respondsToSelector`] or
+///    [`symbol_available!`] (in lieu of [weak linking]).
 ///
-/// [`AnyClass::get`]: crate::runtime::AnyClass::get
+/// [`class_available!`]: crate::class_available
+/// [`symbol_available!`]: crate::symbol_available
 /// [`respondsToSelector`]: crate::runtime::NSObjectProtocol::respondsToSelector
 /// [weak linking]: https://github.com/rust-lang/rust/issues/29603
 ///
@@ -191,3 +193,93 @@ macro_rules! __available_version {
         )*))
     };
 }
+
+/// Check if a class is available at runtime.
+///
+/// This is useful when using a class that was introduced in a newer OS
+/// version than your deployment target, where [`class!`] would panic (or
+/// fail to link) if the class does not exist. The class can be given as an
+/// identifier or as a string literal.
+///
+/// For classes declared with [`extern_class!`], you can also use
+/// [`ClassType::is_available`].
+///
+/// The lookup is cached once the class is found.
+///
+/// [`class!`]: crate::class
+/// [`extern_class!`]: crate::extern_class
+/// [`ClassType::is_available`]: crate::ClassType::is_available
+///
+///
+/// # Examples
+///
+/// ```
+/// use objc2::class_available;
+///
+/// assert!(class_available!(NSObject));
+/// assert!(!class_available!("NonExistentClass"));
+/// ```
+#[macro_export]
+macro_rules! class_available {
+    ($name:ident) => {
+        $crate::__class_available_inner!($crate::__macro_helpers::stringify!($name))
+    };
+    ($name:literal) => {
+        $crate::__class_available_inner!($name)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __class_available_inner {
+    ($name:expr) => {{
+        static CACHED_CLASS: $crate::__macro_helpers::CachedClass =
+            $crate::__macro_helpers::CachedClass::new();
+        #[allow(unused_unsafe)]
+        unsafe { CACHED_CLASS.try_get($crate::__macro_helpers::concat!($name, '\0')) }.is_some()
+    }};
+}
+
+/// Check if a C symbol, such as a function or a static, is available at
+/// runtime.
+///
+/// Rust does not yet support weak linking, so referencing a symbol that was
+/// introduced in a newer OS version than your deployment target will fail
+/// when the program is loaded on older versions. Instead, you can check for
+/// the symbol with this macro (which uses `dlsym`), and call it through a
+/// function pointer obtained in the same way.
+///
+/// The symbol can be given as an identifier or as a string literal. The
+/// lookup is cached once the symbol is found.
+///
+/// This currently always returns `false` on Windows.
+///
+///
+/// # Examples
+///
+/// ```
+/// use objc2::symbol_available;
+///
+/// assert!(symbol_available!(objc_getClass));
+/// assert!(!symbol_available!("NonExistentSymbol"));
+/// ```
+#[macro_export]
+macro_rules! symbol_available {
+    ($name:ident) => {
+        $crate::__symbol_available_inner!($crate::__macro_helpers::stringify!($name))
+    };
+    ($name:literal) => {
+        $crate::__symbol_available_inner!($name)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __symbol_available_inner {
+    ($name:expr) => {{
+        static CACHED_SYMBOL: $crate::__macro_helpers::CachedSymbol =
+            $crate::__macro_helpers::CachedSymbol::new();
+        #[allow(unused_unsafe)]
+        !unsafe { CACHED_SYMBOL.get($crate::__macro_helpers::concat!($name, '\0')) }.is_null()
+    }};
+}
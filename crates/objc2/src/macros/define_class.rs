@@ -186,6 +186,9 @@
 /// The methods work exactly as normal, they're only put "under" the protocol
 /// definition to make things easier to read.
 ///
+/// If the protocol has associated types, these must be specified with
+/// `type Name = Type;` before the methods.
+///
 /// Putting attributes on the `impl` item such as `cfg`, `allow`, `doc`,
 /// `deprecated` and so on is supported.
 ///
@@ -843,15 +846,14 @@ macro_rules! __define_class_output_impls {
 
         $($rest:tt)*
     ) => {
-        // SAFETY: Upheld by caller
-        $(#[$m])*
-        unsafe impl $protocol for $for {}
+        $crate::__define_class_extract_associated_types! {
+            ($($methods)*)
+            ()
 
-        $(#[$m])*
-        impl $for {
-            $crate::__define_class_output_methods! {
-                $($methods)*
-            }
+            ($crate::__define_class_output_protocol_impl)
+            ($(#[$m])*)
+            ($protocol)
+            ($for)
         }
 
         $crate::__define_class_output_impls!{
@@ -881,6 +883,75 @@ macro_rules! __define_class_output_impls {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_class_output_protocol_impl {
+    (
+        ($(#[$m:meta])*)
+        ($protocol:ident)
+        ($for:ty)
+        ($($associated_type:ident = $associated_type_ty:ty,)*)
+        ($($methods:tt)*)
+    ) => {
+        // SAFETY: Upheld by caller
+        $(#[$m])*
+        unsafe impl $protocol for $for {
+            $(
+                type $associated_type = $associated_type_ty;
+            )*
+        }
+
+        $(#[$m])*
+        impl $for {
+            $crate::__define_class_output_methods! {
+                $($methods)*
+            }
+        }
+    };
+}
+
+/// tt-munch the associated types at the start of a protocol implementation.
+///
+/// Calls the output macro with the given arguments, followed by the
+/// associated types, and the remaining methods.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_class_extract_associated_types {
+    // Associated type
+    {
+        (
+            type $name:ident = $ty:ty;
+
+            $($rest:tt)*
+        )
+        ($($associated_types:tt)*)
+
+        $($args:tt)*
+    } => {
+        $crate::__define_class_extract_associated_types! {
+            ($($rest)*)
+            ($($associated_types)* $name = $ty,)
+
+            $($args)*
+        }
+    };
+
+    // No more associated types
+    {
+        ($($methods:tt)*)
+        ($($associated_types:tt)*)
+
+        ($out_macro:path)
+        $($out_args:tt)*
+    } => {
+        $out_macro! {
+            $($out_args)*
+            ($($associated_types)*)
+            ($($methods)*)
+        }
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __define_class_output_methods {
@@ -960,25 +1031,14 @@ macro_rules! __define_class_register_impls {
         $crate::__extract_and_apply_cfg_attributes! {
             ($(#[$($m)*])*)
 
-            // Implement protocol
-            #[allow(unused_mut)]
-            let mut __objc2_protocol_builder = $builder.add_protocol_methods::<dyn $protocol>();
-
-            // In case the user's function is marked `deprecated`
-            #[allow(deprecated)]
-            // In case the user did not specify any methods
-            #[allow(unused_unsafe)]
-            // SAFETY: Upheld by caller
-            unsafe {
-                $crate::__define_class_register_methods! {
-                    (__objc2_protocol_builder)
+            $crate::__define_class_extract_associated_types! {
+                ($($methods)*)
+                ()
 
-                    $($methods)*
-                }
+                ($crate::__define_class_register_protocol)
+                ($builder)
+                ($protocol)
             }
-
-            // Finished creating protocol; get error message if any
-            __objc2_protocol_builder.finish();
         }
 
         $crate::__define_class_register_impls! {
@@ -1022,6 +1082,39 @@ macro_rules! __define_class_register_impls {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_class_register_protocol {
+    (
+        ($builder:ident)
+        ($protocol:ident)
+        ($($associated_type:ident = $associated_type_ty:ty,)*)
+        ($($methods:tt)*)
+    ) => {
+        // Implement protocol
+        #[allow(unused_mut)]
+        let mut __objc2_protocol_builder = $builder.add_protocol_methods::<
+            dyn $protocol<$($associated_type = $associated_type_ty),*>
+        >();
+
+        // In case the user's function is marked `deprecated`
+        #[allow(deprecated)]
+        // In case the user did not specify any methods
+        #[allow(unused_unsafe)]
+        // SAFETY: Upheld by caller
+        unsafe {
+            $crate::__define_class_register_methods! {
+                (__objc2_protocol_builder)
+
+                $($methods)*
+            }
+        }
+
+        // Finished creating protocol; get error message if any
+        __objc2_protocol_builder.finish();
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __define_class_register_methods {
@@ -1127,6 +1220,7 @@ macro_rules! __define_class_method_out {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($__m_cached:tt)*)
     } => {
         $crate::__define_class_rewrite_params! {
             ($($params_rest)*)
@@ -1376,12 +1470,14 @@ macro_rules! __define_class_register_out {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
     } => {
         $crate::__extract_and_apply_cfg_attributes! {
             ($($m_checked)*)
 
             $crate::__define_class_invalid_selectors!(#[$method_or_method_id($($sel)*)]);
             $crate::__extern_methods_no_optional!($($m_optional)*);
+            $crate::__attribute_no_cached!($($m_cached)*);
 
             $builder.$builder_method(
                 $crate::sel!($($sel)*),
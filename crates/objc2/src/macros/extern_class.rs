@@ -313,6 +313,14 @@ macro_rules! __extern_class_inner {
                 }, $crate::__hash_idents!($class))
             }
 
+            #[inline]
+            fn is_available() -> $crate::__macro_helpers::bool {
+                $crate::__class_available_inner!($crate::__fallback_if_not_set! {
+                    ($($name)*)
+                    ($crate::__macro_helpers::stringify!($class))
+                })
+            }
+
             #[inline]
             fn as_super(&self) -> &Self::Super {
                 &self.__superclass
@@ -403,7 +411,10 @@ macro_rules! __extern_class_impl_traits {
         // a subclass of one of `NSObject`, `NSProxy` or some other class that
         // ensures this (e.g. the object itself is not a root class).
         $($attr_impl)*
-        unsafe impl $($after_impl)* $crate::Message for $($for)* {}
+        unsafe impl $($after_impl)* $crate::Message for $($for)* {
+            const __MAIN_THREAD_ONLY: $crate::__macro_helpers::bool =
+                <<Self as $crate::ClassType>::ThreadKind as $crate::ThreadKind>::__MAIN_THREAD_ONLY;
+        }
 
         // SAFETY: An instance can always be _used_ in exactly the same way as
         // its superclasses (though not necessarily _constructed_ in the same
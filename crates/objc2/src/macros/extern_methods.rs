@@ -32,6 +32,31 @@
 /// a textual match on `MainThreadMarker`; so you must use that exact
 /// identifier.
 ///
+/// Class methods without arguments declared with `#[method_id(...)]` can
+/// additionally be marked with `#[cached]`. This is intended for class
+/// properties that return an immutable singleton, such as
+/// `NSColor.whiteColor`: the object is then only retrieved the first time the
+/// function is called, and later calls return the same object without
+/// sending a message. The return type of such methods must be
+/// `Retained<T>`.
+///
+/// Methods declared with `#[method(...)]` can instead be marked with
+/// `#[cached_imp]`. The first time such a method is called, the
+/// implementation of the method on the class of the receiver is looked up
+/// and stored in a static, and later calls on instances of that same class
+/// then call the implementation directly, bypassing `objc_msgSend`. This can
+/// be useful for monomorphic methods that are called in a tight loop, such
+/// as once per element per frame. Calls on instances of other classes are
+/// sent normally, as are calls to methods that the class does not implement
+/// itself (e.g. forwarded methods). The cached implementations are
+/// invalidated with [`runtime::flush_imp_caches`], which is done
+/// automatically when swizzling with [`Method::set_implementation`] or
+/// [`Method::exchange_implementation`].
+///
+/// [`runtime::flush_imp_caches`]: crate::runtime::flush_imp_caches
+/// [`Method::set_implementation`]: crate::runtime::Method::set_implementation
+/// [`Method::exchange_implementation`]: crate::runtime::Method::exchange_implementation
+///
 /// Putting other attributes on the method such as `cfg`, `allow`, `doc`,
 /// `deprecated` and so on is supported. However, note that `cfg_attr` may not
 /// work correctly, due to implementation difficulty - if you have a concrete
@@ -59,6 +84,20 @@
 /// `#[method_id(...)]` attribute upholds the safety guarantees described in
 /// the [`msg_send_id!`] macro, _or_ are marked `unsafe`.
 ///
+/// Methods marked with `#[cached]` must additionally return the same
+/// object each time they are called, and the object must be safe to use
+/// from any thread (as it is shared between all callers). The attribute may
+/// not be used in generic `impl`s, since the cache is shared between all
+/// instantiations of the method.
+///
+/// If the method that a `#[cached_imp]` method dispatches to is changed by
+/// other means than the functions on [`Method`], such as by swizzling with
+/// the functions in [`ffi`], [`runtime::flush_imp_caches`] must be called
+/// before the method is called again.
+///
+/// [`Method`]: crate::runtime::Method
+/// [`ffi`]: crate::ffi
+///
 ///
 /// # Examples
 ///
@@ -299,6 +338,7 @@ macro_rules! __extern_methods_method_out {
         ()
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ()
     } => {
         $($m_checked)*
         $($function_start)*
@@ -336,6 +376,7 @@ macro_rules! __extern_methods_method_out {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ()
     } => {
         $($m_checked)*
         $($function_start)*
@@ -358,6 +399,150 @@ macro_rules! __extern_methods_method_out {
             }
         }
     };
+
+    // #[method_id(...)] #[cached] on a class method without arguments
+    {
+        ($($function_start:tt)*)
+        ($($where:ty : $bound:path ,)*)
+
+        (add_class_method)
+        ($receiver:expr)
+        ($__receiver_ty:ty)
+        ($($__params_prefix:tt)*)
+        ()
+
+        (#[method_id($($sel:tt)*)])
+        ($($retain_semantics:tt)*)
+        ($($m_optional:tt)*)
+        ($($m_checked:tt)*)
+        (#[cached])
+    } => {
+        $($m_checked)*
+        $($function_start)*
+        where
+            $($where : $bound,)*
+        {
+            $crate::__extern_methods_no_optional!($($m_optional)*);
+
+            static __OBJC2_CACHED: $crate::__macro_helpers::CachedRetained =
+                $crate::__macro_helpers::CachedRetained::new();
+
+            #[allow(unused_unsafe)]
+            unsafe {
+                __OBJC2_CACHED.get(|| {
+                    $crate::__method_msg_send_id! {
+                        ($receiver)
+                        ($($sel)*)
+                        ()
+
+                        ()
+                        ()
+                        ($($retain_semantics)*)
+                    }
+                })
+            }
+        }
+    };
+
+    // #[method(...)] #[cached_imp]
+    {
+        ($($function_start:tt)*)
+        ($($where:ty : $bound:path ,)*)
+
+        ($__builder_method:ident)
+        ($receiver:expr)
+        ($__receiver_ty:ty)
+        ($($__params_prefix:tt)*)
+        ($($params_rest:tt)*)
+
+        (#[method($($sel:tt)*)])
+        ()
+        ($($m_optional:tt)*)
+        ($($m_checked:tt)*)
+        (#[cached_imp])
+    } => {
+        $($m_checked)*
+        $($function_start)*
+        where
+            $($where : $bound,)*
+        {
+            $crate::__extern_methods_no_optional!($($m_optional)*);
+
+            static __OBJC2_CACHED_IMP: $crate::__macro_helpers::CachedImp =
+                $crate::__macro_helpers::CachedImp::new();
+
+            #[allow(unused_unsafe)]
+            unsafe {
+                $crate::__method_msg_send! {
+                    ($crate::__macro_helpers::CachedImpReceiver::new(
+                        $receiver,
+                        &__OBJC2_CACHED_IMP,
+                    ))
+                    ($($sel)*)
+                    ($($params_rest)*)
+
+                    ()
+                    ()
+                }
+            }
+        }
+    };
+
+    // Invalid use of #[cached_imp]
+    {
+        ($($function_start:tt)*)
+        ($($where:ty : $bound:path ,)*)
+
+        ($__builder_method:ident)
+        ($__receiver:expr)
+        ($__receiver_ty:ty)
+        ($($__params_prefix:tt)*)
+        ($($__params_rest:tt)*)
+
+        ($($__m_method:tt)*)
+        ($($__retain_semantics:tt)*)
+        ($($__m_optional:tt)*)
+        ($($m_checked:tt)*)
+        (#[cached_imp])
+    } => {
+        $($m_checked)*
+        $($function_start)*
+        where
+            $($where : $bound,)*
+        {
+            $crate::__macro_helpers::compile_error!(
+                "`#[cached_imp]` is only supported on `#[method(...)]` methods"
+            )
+        }
+    };
+
+    // Invalid use of #[cached]
+    {
+        ($($function_start:tt)*)
+        ($($where:ty : $bound:path ,)*)
+
+        ($__builder_method:ident)
+        ($__receiver:expr)
+        ($__receiver_ty:ty)
+        ($($__params_prefix:tt)*)
+        ($($__params_rest:tt)*)
+
+        ($($__m_method:tt)*)
+        ($($__retain_semantics:tt)*)
+        ($($__m_optional:tt)*)
+        ($($m_checked:tt)*)
+        (#[cached])
+    } => {
+        $($m_checked)*
+        $($function_start)*
+        where
+            $($where : $bound,)*
+        {
+            $crate::__macro_helpers::compile_error!(
+                "`#[cached]` is only supported on `#[method_id(...)]` class methods without arguments"
+            )
+        }
+    };
 }
 
 #[doc(hidden)]
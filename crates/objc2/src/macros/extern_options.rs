@@ -0,0 +1,482 @@
+/// Create a type-safe set of options, for use as a bitmask argument in
+/// [`extern_methods!`] and [`msg_send!`].
+///
+/// Objective-C uses `NS_OPTIONS` to declare the flags that can be combined
+/// into an integer bitmask. If such a parameter is declared as a plain
+/// integer like `NSUInteger` in Rust, nothing prevents passing flags from the
+/// wrong options set, or an arbitrary integer. This macro instead creates a
+/// newtype around the integer, whose flags can only be combined with flags
+/// from the same set.
+///
+/// The syntax is similar to the [`bitflags`] crate, and the generated type
+/// has a similar API, with a constant for each flag, methods like
+/// [`contains`], [`union`] and [`insert`], and the usual bitwise operators.
+///
+/// [`extern_methods!`]: crate::extern_methods
+/// [`msg_send!`]: crate::msg_send
+/// [`bitflags`]: https://docs.rs/bitflags
+/// [`contains`]: #contains
+/// [`union`]: #union
+/// [`insert`]: #insert
+///
+///
+/// # Specification
+///
+/// The type is declared as a `struct` with the underlying integer type after
+/// the name, followed by a `const` item for each flag. The value of each
+/// flag is an expression of the integer type, which may refer to the bits of
+/// other flags with e.g. `Self::A.bits()`.
+///
+/// The struct is `#[repr(transparent)]` over the integer, and implements
+/// [`Encode`] and [`RefEncode`] with the integer's encoding, so that it can
+/// be used in place of the integer in method signatures. It also implements
+/// `Clone`, `Copy`, `PartialEq`, `Eq`, `Hash`, `PartialOrd`, `Ord`,
+/// `Default` (the empty set) and `Debug`, which prints the names of the set
+/// flags.
+///
+/// The bitwise operators `|`, `&`, `^`, `-` (difference) and `!`
+/// (complement), and their assigning variants, are only implemented between
+/// values of the same type.
+///
+/// Bits that do not correspond to a known flag are retained, since Apple may
+/// add new flags in newer OS versions.
+///
+/// [`Encode`]: crate::Encode
+/// [`RefEncode`]: crate::RefEncode
+///
+///
+/// # Examples
+///
+/// Declare the options for `-[NSString compare:options:]`, and use them in a
+/// method.
+///
+/// ```
+/// use objc2::ffi::{NSInteger, NSUInteger};
+/// use objc2::runtime::NSObject;
+/// use objc2::{extern_class, extern_methods, extern_options};
+///
+/// extern_options!(
+///     /// Options for comparing strings.
+///     pub struct NSStringCompareOptions: NSUInteger {
+///         const CaseInsensitive = 1 << 0;
+///         const Literal = 1 << 1;
+///         const Backwards = 1 << 2;
+///         const Numeric = 1 << 6;
+///     }
+/// );
+///
+/// extern_class!(
+///     #[unsafe(super(NSObject))]
+///     pub struct NSString;
+/// );
+///
+/// extern_methods!(
+///     unsafe impl NSString {
+///         // The compiler ensures that only `NSStringCompareOptions` can be
+///         // passed as the mask.
+///         #[method(compare:options:)]
+///         pub fn compare_options(
+///             &self,
+///             string: &NSString,
+///             mask: NSStringCompareOptions,
+///         ) -> NSInteger;
+///     }
+/// );
+///
+/// let options = NSStringCompareOptions::CaseInsensitive | NSStringCompareOptions::Numeric;
+/// assert!(options.contains(NSStringCompareOptions::Numeric));
+/// assert!(!options.contains(NSStringCompareOptions::Literal));
+/// assert_eq!(options.bits(), 0b100_0001);
+/// assert_eq!(
+///     format!("{options:?}"),
+///     "NSStringCompareOptions(CaseInsensitive | Numeric)",
+/// );
+/// ```
+///
+/// Combining flags from different options sets fails to compile.
+///
+/// ```compile_fail
+/// use objc2::extern_options;
+/// use objc2::ffi::NSUInteger;
+///
+/// extern_options!(
+///     pub struct NSFooOptions: NSUInteger {
+///         const A = 1 << 0;
+///     }
+/// );
+///
+/// extern_options!(
+///     pub struct NSBarOptions: NSUInteger {
+///         const B = 1 << 0;
+///     }
+/// );
+///
+/// let _ = NSFooOptions::A | NSBarOptions::B;
+/// ```
+#[doc(alias = "NS_OPTIONS")]
+#[macro_export]
+macro_rules! extern_options {
+    (
+        $(#[$m:meta])*
+        $v:vis struct $name:ident: $ty:ty {
+            $(
+                $(#[$field_m:meta])*
+                const $field:ident = $value:expr;
+            )*
+        }
+    ) => {
+        $(#[$m])*
+        #[repr(transparent)]
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        $v struct $name($ty);
+
+        #[allow(non_upper_case_globals, dead_code)]
+        impl $name {
+            $(
+                $(#[$field_m])*
+                pub const $field: Self = Self($value);
+            )*
+
+            /// The empty set, with no flags set.
+            #[inline]
+            pub const fn empty() -> Self {
+                Self(0)
+            }
+
+            /// The set with all known flags set.
+            #[inline]
+            pub const fn all() -> Self {
+                Self(0 $(| Self::$field.0)*)
+            }
+
+            /// The underlying bits of the set.
+            #[inline]
+            pub const fn bits(self) -> $ty {
+                self.0
+            }
+
+            /// Create a set from the given bits, returning [`None`] if any
+            /// of them do not correspond to a known flag.
+            #[inline]
+            pub const fn from_bits(bits: $ty) -> $crate::__macro_helpers::Option<Self> {
+                if bits & !Self::all().0 == 0 {
+                    $crate::__macro_helpers::Some(Self(bits))
+                } else {
+                    $crate::__macro_helpers::None
+                }
+            }
+
+            /// Create a set from the given bits, removing any bits that do
+            /// not correspond to a known flag.
+            #[inline]
+            pub const fn from_bits_truncate(bits: $ty) -> Self {
+                Self(bits & Self::all().0)
+            }
+
+            /// Create a set from the given bits, retaining any bits that do
+            /// not correspond to a known flag.
+            #[inline]
+            pub const fn from_bits_retain(bits: $ty) -> Self {
+                Self(bits)
+            }
+
+            /// Whether no flags are set.
+            #[inline]
+            pub const fn is_empty(self) -> bool {
+                self.0 == 0
+            }
+
+            /// Whether all known flags are set.
+            #[inline]
+            pub const fn is_all(self) -> bool {
+                self.0 & Self::all().0 == Self::all().0
+            }
+
+            /// Whether all flags in `other` are set in `self`.
+            #[inline]
+            pub const fn contains(self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            /// Whether any flags in `other` are set in `self`.
+            #[inline]
+            pub const fn intersects(self, other: Self) -> bool {
+                self.0 & other.0 != 0
+            }
+
+            /// The flags set in either `self` or `other`.
+            #[inline]
+            #[must_use]
+            pub const fn union(self, other: Self) -> Self {
+                Self(self.0 | other.0)
+            }
+
+            /// The flags set in both `self` and `other`.
+            #[inline]
+            #[must_use]
+            pub const fn intersection(self, other: Self) -> Self {
+                Self(self.0 & other.0)
+            }
+
+            /// The flags set in `self` but not in `other`.
+            #[inline]
+            #[must_use]
+            pub const fn difference(self, other: Self) -> Self {
+                Self(self.0 & !other.0)
+            }
+
+            /// The flags set in exactly one of `self` and `other`.
+            #[inline]
+            #[must_use]
+            pub const fn symmetric_difference(self, other: Self) -> Self {
+                Self(self.0 ^ other.0)
+            }
+
+            /// The known flags that are not set in `self`.
+            #[inline]
+            #[must_use]
+            pub const fn complement(self) -> Self {
+                Self(!self.0 & Self::all().0)
+            }
+
+            /// Set the flags in `other`.
+            #[inline]
+            pub fn insert(&mut self, other: Self) {
+                *self = self.union(other);
+            }
+
+            /// Unset the flags in `other`.
+            #[inline]
+            pub fn remove(&mut self, other: Self) {
+                *self = self.difference(other);
+            }
+
+            /// Toggle the flags in `other`.
+            #[inline]
+            pub fn toggle(&mut self, other: Self) {
+                *self = self.symmetric_difference(other);
+            }
+
+            /// Set or unset the flags in `other`, depending on `value`.
+            #[inline]
+            pub fn set(&mut self, other: Self, value: bool) {
+                if value {
+                    self.insert(other);
+                } else {
+                    self.remove(other);
+                }
+            }
+        }
+
+        impl $crate::__macro_helpers::Default for $name {
+            #[inline]
+            fn default() -> Self {
+                Self::empty()
+            }
+        }
+
+        $crate::__extern_options_binary_op! {
+            $name;
+            BitOr, bitor, BitOrAssign, bitor_assign => union;
+            BitAnd, bitand, BitAndAssign, bitand_assign => intersection;
+            BitXor, bitxor, BitXorAssign, bitxor_assign => symmetric_difference;
+            Sub, sub, SubAssign, sub_assign => difference;
+        }
+
+        impl $crate::__macro_helpers::ops::Not for $name {
+            type Output = Self;
+
+            #[inline]
+            fn not(self) -> Self {
+                self.complement()
+            }
+        }
+
+        impl $crate::__macro_helpers::Extend<$name> for $name {
+            fn extend<I: $crate::__macro_helpers::IntoIterator<Item = Self>>(&mut self, iter: I) {
+                for item in iter {
+                    self.insert(item);
+                }
+            }
+        }
+
+        impl $crate::__macro_helpers::FromIterator<$name> for $name {
+            fn from_iter<I: $crate::__macro_helpers::IntoIterator<Item = Self>>(iter: I) -> Self {
+                let mut this = Self::empty();
+                $crate::__macro_helpers::Extend::extend(&mut this, iter);
+                this
+            }
+        }
+
+        impl $crate::__macro_helpers::fmt::Debug for $name {
+            #[allow(unused_mut)]
+            fn fmt(&self, f: &mut $crate::__macro_helpers::fmt::Formatter<'_>) -> $crate::__macro_helpers::fmt::Result {
+                f.write_str($crate::__macro_helpers::concat!($crate::__macro_helpers::stringify!($name), "("))?;
+                let mut first = true;
+                let mut remaining = self.0;
+                $(
+                    if Self::$field.0 != 0 && remaining & Self::$field.0 != 0 && self.contains(Self::$field) {
+                        if !first {
+                            f.write_str(" | ")?;
+                        }
+                        first = false;
+                        f.write_str($crate::__macro_helpers::stringify!($field))?;
+                        remaining &= !Self::$field.0;
+                    }
+                )*
+                if remaining != 0 || first {
+                    if !first {
+                        f.write_str(" | ")?;
+                    }
+                    write!(f, "{:#x}", remaining)?;
+                }
+                f.write_str(")")
+            }
+        }
+
+        // SAFETY: The type is `#[repr(transparent)]` over the integer.
+        unsafe impl $crate::Encode for $name {
+            const ENCODING: $crate::Encoding = <$ty as $crate::Encode>::ENCODING;
+        }
+
+        // SAFETY: Same as above.
+        unsafe impl $crate::RefEncode for $name {
+            const ENCODING_REF: $crate::Encoding = <$ty as $crate::RefEncode>::ENCODING_REF;
+        }
+    };
+}
+
+/// Helper for implementing the binary operators in [`extern_options!`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __extern_options_binary_op {
+    (
+        $name:ident;
+        $(
+            $trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident => $method:ident;
+        )*
+    ) => {
+        $(
+            impl $crate::__macro_helpers::ops::$trait for $name {
+                type Output = Self;
+
+                #[inline]
+                fn $fn(self, other: Self) -> Self {
+                    self.$method(other)
+                }
+            }
+
+            impl $crate::__macro_helpers::ops::$assign_trait for $name {
+                #[inline]
+                fn $assign_fn(&mut self, other: Self) {
+                    *self = self.$method(other);
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    // The options types are private.
+    #![allow(unreachable_pub)]
+
+    use alloc::format;
+    use alloc::vec;
+
+    use crate::encode::{Encode, RefEncode};
+    use crate::ffi::{NSInteger, NSUInteger};
+
+    extern_options!(
+        struct Options: NSUInteger {
+            const A = 1 << 0;
+            const B = 1 << 1;
+            const C = 1 << 3;
+            const AB = Self::A.bits() | Self::B.bits();
+        }
+    );
+
+    extern_options!(
+        /// Signed, like some options in Core Foundation.
+        struct SignedOptions: NSInteger {
+            const X = 1 << 0;
+            const Y = 1 << 1;
+        }
+    );
+
+    #[test]
+    fn operations() {
+        let mut options = Options::A | Options::C;
+        assert!(options.contains(Options::A));
+        assert!(!options.contains(Options::AB));
+        assert!(options.intersects(Options::AB));
+        assert_eq!(options.bits(), 0b1001);
+
+        options |= Options::B;
+        assert!(options.is_all());
+        assert_eq!(options - Options::AB, Options::C);
+        assert_eq!(options & Options::AB, Options::AB);
+        assert_eq!(options ^ Options::A, Options::B | Options::C);
+        assert_eq!(!Options::AB, Options::C);
+
+        options.remove(Options::A);
+        options.set(Options::C, false);
+        assert_eq!(options, Options::B);
+        options.toggle(Options::AB);
+        assert_eq!(options, Options::A);
+
+        assert_eq!(Options::default(), Options::empty());
+        assert!(Options::empty().is_empty());
+        assert_eq!(Options::all().bits(), 0b1011);
+        assert_eq!(
+            vec![Options::A, Options::C]
+                .into_iter()
+                .collect::<Options>(),
+            Options::A | Options::C,
+        );
+
+        let signed = SignedOptions::X | SignedOptions::Y;
+        assert_eq!(signed.bits(), 3);
+        assert_eq!(!signed, SignedOptions::empty());
+    }
+
+    #[test]
+    fn unknown_bits() {
+        assert_eq!(Options::from_bits(0b11), Some(Options::AB));
+        assert_eq!(Options::from_bits(0b111), None);
+        assert_eq!(Options::from_bits_truncate(0b111), Options::AB);
+
+        let options = Options::from_bits_retain(0b111);
+        assert_eq!(options.bits(), 0b111);
+        assert!(!options.is_empty());
+        assert_eq!(options.complement(), Options::C);
+    }
+
+    #[test]
+    fn debug() {
+        assert_eq!(format!("{:?}", Options::empty()), "Options(0x0)");
+        assert_eq!(format!("{:?}", Options::A), "Options(A)");
+        assert_eq!(format!("{:?}", Options::A | Options::C), "Options(A | C)");
+        // Multi-bit flags are printed when matched first.
+        assert_eq!(format!("{:?}", Options::AB), "Options(A | B)");
+        assert_eq!(
+            format!("{:?}", Options::from_bits_retain(0b10100)),
+            "Options(0x14)",
+        );
+        assert_eq!(
+            format!("{:?}", Options::from_bits_retain(0b10001)),
+            "Options(A | 0x10)",
+        );
+    }
+
+    #[test]
+    fn encoding() {
+        assert_eq!(Options::ENCODING, NSUInteger::ENCODING);
+        assert_eq!(Options::ENCODING_REF, NSUInteger::ENCODING_REF);
+        assert_eq!(SignedOptions::ENCODING, NSInteger::ENCODING);
+        assert_eq!(
+            core::mem::size_of::<Options>(),
+            core::mem::size_of::<NSUInteger>()
+        );
+    }
+}
@@ -45,6 +45,12 @@
 /// This currently doesn't have any effect, but probably will have one in the
 /// future when implementing protocols in [`define_class!`].
 ///
+/// Methods returning `instancetype` can use `Retained<Self>`. Protocols that
+/// return objects whose type depends on the implementing class can instead
+/// declare associated types with `type Name: Bounds;` before the methods,
+/// and use these in method signatures as `Self::Name`. The protocol is then
+/// referred to as `dyn MyProtocol<Name = Type>`, see the example below.
+///
 /// This macro otherwise shares similarities with [`extern_class!`] and
 /// [`extern_methods!`].
 ///
@@ -142,6 +148,54 @@
 /// // from it as we specified.
 /// ```
 ///
+/// Create a protocol whose methods return objects of a type chosen by the
+/// implementer, instead of the type-erased `AnyObject`.
+///
+/// ```
+/// use objc2::rc::Retained;
+/// use objc2::runtime::{NSObject, NSObjectProtocol, ProtocolObject};
+/// use objc2::{define_class, extern_protocol, msg_send_id, ClassType, Message};
+///
+/// extern_protocol!(
+///     /// A data source that can produce new items.
+///     //
+///     // SAFETY: The name and methods are correctly specified.
+///     #[name = "MyItemSource"]
+///     pub unsafe trait MyItemSource: NSObjectProtocol {
+///         /// The type of the items.
+///         type Item: Message;
+///
+///         #[method_id(newItem)]
+///         fn newItem(&self) -> Retained<Self::Item>;
+///     }
+/// );
+///
+/// define_class!(
+///     #[unsafe(super(NSObject))]
+///     #[name = "MyObjectSource"]
+///     struct MyObjectSource;
+///
+///     unsafe impl NSObjectProtocol for MyObjectSource {}
+///
+///     unsafe impl MyItemSource for MyObjectSource {
+///         type Item = NSObject;
+///
+///         #[method_id(newItem)]
+///         fn new_item(&self) -> Retained<NSObject> {
+///             NSObject::new()
+///         }
+///     }
+/// );
+///
+/// let source: Retained<MyObjectSource> = unsafe { msg_send_id![MyObjectSource::class(), new] };
+/// // The item is statically known to be an `NSObject`.
+/// let item: Retained<NSObject> = source.newItem();
+///
+/// // The associated type is also kept when using the protocol object.
+/// let source: &ProtocolObject<dyn MyItemSource<Item = NSObject>> = ProtocolObject::from_ref(&*source);
+/// let item: Retained<NSObject> = source.newItem();
+/// ```
+///
 /// See the source code of `objc2-foundation` for many more examples.
 #[doc(alias = "@protocol")]
 #[macro_export]
@@ -153,12 +207,73 @@ macro_rules! extern_protocol {
             $($methods:tt)*
         }
     ) => {
-        $crate::__extract_struct_attributes! {
+        $crate::__extern_protocol_extract_associated_types! {
+            ($($methods)*)
+            ()
+
             ($(#[$($attrs)*])*)
+            ($protocol)
+            ($v unsafe trait $protocol $(: $conforms_to $(+ $conforms_to_rest)*)?)
+        }
+    };
+}
+
+/// tt-munch the associated types at the start of the protocol.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __extern_protocol_extract_associated_types {
+    // Associated type
+    {
+        (
+            $(#[$($m:tt)*])*
+            type $name:ident $(: $($bound:ident)::+ $(+ $($bounds:ident)::+)*)?;
+
+            $($rest:tt)*
+        )
+        ($($associated_types:tt)*)
+
+        $($args:tt)*
+    } => {
+        $crate::__extern_protocol_extract_associated_types! {
+            ($($rest)*)
+            (
+                $($associated_types)*
+                {
+                    ($(#[$($m)*])*)
+                    $name
+                    ($(: $($bound)::+ $(+ $($bounds)::+)*)?)
+                }
+            )
+
+            $($args)*
+        }
+    };
+
+    // No more associated types, output the protocol
+    {
+        ($($methods:tt)*)
+        ($({
+            ($($type_attrs:tt)*)
+            $name:ident
+            ($($type_bounds:tt)*)
+        })*)
+
+        ($($attrs:tt)*)
+        ($protocol:ident)
+        ($($trait_start:tt)*)
+    } => {
+        $crate::__extract_struct_attributes! {
+            ($($attrs)*)
 
             ($crate::__inner_extern_protocol)
             ($protocol)
-            ($v unsafe trait $protocol $(: $conforms_to $(+ $conforms_to_rest)*)? {
+            ($($name ($($type_bounds)*))*)
+            ($($trait_start)* {
+                $(
+                    $($type_attrs)*
+                    type $name $($type_bounds)*;
+                )*
+
                 $crate::__extern_protocol_rewrite_methods! {
                     $($methods)*
                 }
@@ -172,6 +287,7 @@ macro_rules! extern_protocol {
 macro_rules! __inner_extern_protocol {
     (
         ($protocol:ident)
+        ($($associated_type:ident ($($associated_type_bounds:tt)*))*)
         ($protocol_definition:item)
 
         ($($superclasses:tt)*)
@@ -189,12 +305,21 @@ macro_rules! __inner_extern_protocol {
         unsafe impl<T> $protocol for $crate::runtime::ProtocolObject<T>
         where
             T: ?$crate::__macro_helpers::Sized + $protocol
-        {}
+        {
+            $(
+                type $associated_type = <T as $protocol>::$associated_type;
+            )*
+        }
 
         // SAFETY: The specified name is ensured by caller to be a protocol,
         // and is correctly defined.
+        //
+        // The associated types do not affect the protocol on the
+        // Objective-C side, so this is implemented for all of them.
         $($attr_impl)*
-        unsafe impl $crate::ProtocolType for dyn $protocol {
+        unsafe impl<$($associated_type $($associated_type_bounds)*),*> $crate::ProtocolType
+            for dyn $protocol<$($associated_type = $associated_type),*>
+        {
             const NAME: &'static $crate::__macro_helpers::str = $crate::__fallback_if_not_set! {
                 ($($name)*)
                 ($crate::__macro_helpers::stringify!($protocol))
@@ -205,9 +330,13 @@ macro_rules! __inner_extern_protocol {
         // SAFETY: Anything that implements the protocol is valid to convert
         // to `ProtocolObject<dyn [PROTO]>`.
         $($attr_impl)*
-        unsafe impl<T> $crate::runtime::ImplementedBy<T> for dyn $protocol
+        unsafe impl<T, $($associated_type $($associated_type_bounds)*),*>
+            $crate::runtime::ImplementedBy<T>
+            for dyn $protocol<$($associated_type = $associated_type),*>
         where
-            T: ?$crate::__macro_helpers::Sized + $crate::Message + $protocol
+            T: ?$crate::__macro_helpers::Sized
+                + $crate::Message
+                + $protocol<$($associated_type = $associated_type),*>
         {
             const __INNER: () = ();
         }
@@ -321,6 +450,16 @@ macro_rules! __extern_protocol_rewrite_methods {
             $($rest)*
         }
     };
+
+    // Misplaced associated type, output a better error message.
+    {
+        $(#[$($m:tt)*])*
+        type $($rest:tt)*
+    } => {
+        $crate::__macro_helpers::compile_error!(
+            "associated types must be declared before the methods in extern_protocol!"
+        );
+    };
 }
 
 #[doc(hidden)]
@@ -341,6 +480,7 @@ macro_rules! __extern_protocol_method_out {
         ()
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
     } => {
         $($m_checked)*
         $($function_start)*
@@ -348,6 +488,8 @@ macro_rules! __extern_protocol_method_out {
             Self: $crate::__macro_helpers::Sized + $crate::Message
             $(, $where : $bound)*
         {
+            $crate::__attribute_no_cached!($($m_cached)*);
+
             #[allow(unused_unsafe)]
             unsafe {
                 $crate::__method_msg_send! {
@@ -377,6 +519,7 @@ macro_rules! __extern_protocol_method_out {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
     } => {
         $($m_checked)*
         $($function_start)*
@@ -384,6 +527,8 @@ macro_rules! __extern_protocol_method_out {
             Self: $crate::__macro_helpers::Sized + $crate::Message
             $(, $where : $bound)*
         {
+            $crate::__attribute_no_cached!($($m_cached)*);
+
             #[allow(unused_unsafe)]
             unsafe {
                 $crate::__method_msg_send_id! {
@@ -414,6 +559,7 @@ macro_rules! __extern_protocol_method_out {
         ()
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
     } => {
         $($m_checked)*
         $($function_start)*
@@ -421,6 +567,8 @@ macro_rules! __extern_protocol_method_out {
             Self: $crate::__macro_helpers::Sized + $crate::ClassType
             $(, $where : $bound)*
         {
+            $crate::__attribute_no_cached!($($m_cached)*);
+
             #[allow(unused_unsafe)]
             unsafe {
                 $crate::__method_msg_send! {
@@ -450,6 +598,7 @@ macro_rules! __extern_protocol_method_out {
         ($($retain_semantics:tt)*)
         ($($m_optional:tt)*)
         ($($m_checked:tt)*)
+        ($($m_cached:tt)*)
     } => {
         $($m_checked)*
         $($function_start)*
@@ -457,6 +606,8 @@ macro_rules! __extern_protocol_method_out {
             Self: $crate::__macro_helpers::Sized + $crate::ClassType
             $(, $where : $bound)*
         {
+            $crate::__attribute_no_cached!($($m_cached)*);
+
             #[allow(unused_unsafe)]
             unsafe {
                 $crate::__method_msg_send_id! {
@@ -475,7 +626,9 @@ macro_rules! __extern_protocol_method_out {
 
 #[cfg(test)]
 mod tests {
-    use crate::{extern_protocol, ProtocolType};
+    use crate::rc::Retained;
+    use crate::runtime::{NSObject, NSObjectProtocol, ProtocolObject};
+    use crate::{define_class, msg_send_id, ClassType, Message, ProtocolType};
 
     #[test]
     fn explicit_name() {
@@ -489,4 +642,71 @@ mod tests {
         assert_eq!(proto.name().to_str().unwrap(), "NSObject");
         assert_eq!(<dyn Foo>::NAME, "NSObject");
     }
+
+    extern_protocol!(
+        #[allow(clippy::missing_safety_doc)]
+        #[name = "AssociatedTypeTestProtocol"]
+        unsafe trait WithAssociatedType: NSObjectProtocol {
+            /// The type of the item.
+            type Item: Message;
+            type Other: NSObjectProtocol + Message;
+
+            #[method_id(item)]
+            fn item(&self) -> Retained<Self::Item>;
+
+            #[method_id(other)]
+            fn other_class() -> Retained<Self::Other>;
+
+            #[method_id(copyOf:)]
+            fn copy_of(&self, other: &Self) -> Retained<Self>;
+        }
+    );
+
+    define_class!(
+        #[unsafe(super(NSObject))]
+        #[name = "AssociatedTypeTestClass"]
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct AssociatedTypeTest;
+
+        unsafe impl NSObjectProtocol for AssociatedTypeTest {}
+
+        unsafe impl WithAssociatedType for AssociatedTypeTest {
+            type Item = NSObject;
+            type Other = Self;
+
+            #[method_id(item)]
+            fn _item(&self) -> Retained<NSObject> {
+                NSObject::new()
+            }
+
+            #[method_id(other)]
+            fn _other() -> Retained<Self> {
+                unsafe { msg_send_id![Self::class(), new] }
+            }
+
+            #[method_id(copyOf:)]
+            fn _copy_of(&self, other: &Self) -> Retained<Self> {
+                other.retain()
+            }
+        }
+    );
+
+    #[test]
+    fn associated_types() {
+        let obj = AssociatedTypeTest::other_class();
+        let _item: Retained<NSObject> = obj.item();
+        let copy: Retained<AssociatedTypeTest> = obj.copy_of(&obj);
+        assert_eq!(copy, obj);
+
+        let proto: &ProtocolObject<
+            dyn WithAssociatedType<Item = NSObject, Other = AssociatedTypeTest>,
+        > = ProtocolObject::from_ref(&*obj);
+        let _item: Retained<NSObject> = proto.item();
+        let _nsobject: &ProtocolObject<dyn NSObjectProtocol> = ProtocolObject::from_ref(proto);
+
+        assert_eq!(
+            <dyn WithAssociatedType<Item = NSObject, Other = NSObject>>::NAME,
+            "AssociatedTypeTestProtocol",
+        );
+    }
 }
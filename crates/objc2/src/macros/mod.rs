@@ -8,6 +8,7 @@ mod define_class;
 mod extern_category;
 mod extern_class;
 mod extern_methods;
+mod extern_options;
 mod extern_protocol;
 
 /// Gets a reference to an [`AnyClass`] from the given name.
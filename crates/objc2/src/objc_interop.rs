@@ -0,0 +1,223 @@
+//! # Interoperability with the legacy `objc` crate.
+//!
+//! This module allows incrementally migrating code that uses the `objc`
+//! crate (version 0.2), or crates built on top of it such as `cocoa`, to
+//! `objc2`.
+//!
+//! The types in `objc` have the same layout as the corresponding types in
+//! `objc2`, so converting between them is free:
+//!
+//! | `objc`                         | `objc2`                 |
+//! | ------------------------------ | ----------------------- |
+//! | `runtime::Object`              | [`AnyObject`]           |
+//! | `runtime::Class`               | [`AnyClass`]            |
+//! | `runtime::Sel`                 | [`Sel`]                 |
+//! | `rc::StrongPtr`                | [`Retained<AnyObject>`] |
+//! | `*mut Object` / `cocoa`'s `id` | `*mut AnyObject`        |
+//!
+//! Additionally, `objc`'s `Object` and `Class` implement [`Message`] and
+//! [`RefEncode`], so raw pointers from `objc` (and thus `cocoa`'s `id`) can
+//! be used directly as receivers and arguments in [`msg_send!`], and
+//! `objc2`'s types implement `objc::Encode` and `objc::Message`, so they
+//! can be used with `objc::msg_send!`.
+//!
+//! This module is only available when the `"objc"` Cargo feature is
+//! enabled.
+//!
+//! [`msg_send!`]: crate::msg_send
+//!
+//!
+//! ## Example
+//!
+//! Call a method using `objc2` on an object created with `cocoa`.
+//!
+//! ```no_run
+//! use objc::rc::StrongPtr;
+//! use objc2::rc::Retained;
+//! use objc2::msg_send;
+//!
+//! # let obj: StrongPtr = unimplemented!();
+//! // let obj: StrongPtr = ...; // E.g. from `cocoa`
+//! let obj = Retained::from_strong_ptr(obj).expect("object was nil");
+//! let hash: usize = unsafe { msg_send![&obj, hash] };
+//!
+//! // And pass it back to `objc`.
+//! let obj: StrongPtr = obj.into();
+//! ```
+use core::mem::ManuallyDrop;
+
+use objc::rc::StrongPtr;
+use objc::runtime::{Class, Object};
+
+use crate::encode::{Encode, Encoding, RefEncode};
+use crate::rc::Retained;
+use crate::runtime::{AnyClass, AnyObject, Sel};
+use crate::Message;
+
+// SAFETY: `Object` is an Objective-C object.
+unsafe impl RefEncode for Object {
+    const ENCODING_REF: Encoding = Encoding::Object;
+}
+
+// SAFETY: `Object` is an Objective-C object, and `objc` does not allow
+// mutably accessing it (other than through `UnsafeCell`-like ivars).
+unsafe impl Message for Object {}
+
+// SAFETY: `Class` is an Objective-C class.
+unsafe impl RefEncode for Class {
+    const ENCODING_REF: Encoding = Encoding::Class;
+}
+
+// SAFETY: Classes are objects, and are immutable (same as `AnyClass`).
+unsafe impl Message for Class {}
+
+// SAFETY: `objc::runtime::Sel` is a pointer to an Objective-C selector.
+unsafe impl Encode for objc::runtime::Sel {
+    const ENCODING: Encoding = Encoding::Sel;
+}
+
+// SAFETY: `AnyObject` is an Objective-C object.
+unsafe impl objc::Message for AnyObject {}
+
+// SAFETY: `AnyClass` is an Objective-C class.
+unsafe impl objc::Message for AnyClass {}
+
+// SAFETY: The encoding is correct.
+unsafe impl objc::Encode for Sel {
+    fn encode() -> objc::Encoding {
+        // SAFETY: The encoding is valid.
+        unsafe { objc::Encoding::from_str(":") }
+    }
+}
+
+// SAFETY: The encoding is correct.
+unsafe impl objc::Encode for &AnyObject {
+    fn encode() -> objc::Encoding {
+        // SAFETY: The encoding is valid.
+        unsafe { objc::Encoding::from_str("@") }
+    }
+}
+
+impl From<objc::runtime::Sel> for Sel {
+    #[inline]
+    fn from(sel: objc::runtime::Sel) -> Self {
+        // SAFETY: The pointer is a valid selector.
+        unsafe { Sel::from_ptr(sel.as_ptr()) }.expect("selector must not be NULL")
+    }
+}
+
+impl From<Sel> for objc::runtime::Sel {
+    #[inline]
+    fn from(sel: Sel) -> Self {
+        // SAFETY: The pointer is a valid selector.
+        unsafe { objc::runtime::Sel::from_ptr(sel.as_ptr()) }
+    }
+}
+
+impl AsRef<AnyObject> for Object {
+    #[inline]
+    fn as_ref(&self) -> &AnyObject {
+        // SAFETY: The types have the same layout, and both represent
+        // Objective-C objects.
+        unsafe { &*(self as *const Object).cast::<AnyObject>() }
+    }
+}
+
+impl AsRef<Object> for AnyObject {
+    #[inline]
+    fn as_ref(&self) -> &Object {
+        // SAFETY: Same as above.
+        unsafe { &*(self as *const AnyObject).cast::<Object>() }
+    }
+}
+
+impl AsRef<AnyClass> for Class {
+    #[inline]
+    fn as_ref(&self) -> &AnyClass {
+        // SAFETY: The types have the same layout, and both represent
+        // Objective-C classes.
+        unsafe { &*(self as *const Class).cast::<AnyClass>() }
+    }
+}
+
+impl AsRef<Class> for AnyClass {
+    #[inline]
+    fn as_ref(&self) -> &Class {
+        // SAFETY: Same as above.
+        unsafe { &*(self as *const AnyClass).cast::<Class>() }
+    }
+}
+
+/// Convert a retained object to a `StrongPtr`, without retaining it again.
+impl<T: Message> From<Retained<T>> for StrongPtr {
+    #[inline]
+    fn from(obj: Retained<T>) -> Self {
+        let ptr = Retained::into_raw(obj);
+        // SAFETY: The pointer is a valid object, and we transfer our +1
+        // retain count to the `StrongPtr`.
+        unsafe { StrongPtr::new(ptr.cast()) }
+    }
+}
+
+impl Retained<AnyObject> {
+    /// Convert a `StrongPtr` from the `objc` crate to a retained object,
+    /// without retaining it again.
+    ///
+    /// Returns `None` if the pointer was NULL.
+    ///
+    /// Use [`Retained::downcast`] afterwards to convert it to a more
+    /// specific class.
+    #[inline]
+    pub fn from_strong_ptr(obj: StrongPtr) -> Option<Self> {
+        let obj = ManuallyDrop::new(obj);
+        let ptr: *mut Object = **obj;
+        // SAFETY: `StrongPtr` holds a +1 retain count to a valid object (or
+        // NULL), which we take over.
+        unsafe { Retained::from_raw(ptr.cast()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::NSObject;
+    use crate::{msg_send, sel, ClassType};
+
+    #[test]
+    fn strong_ptr_roundtrip() {
+        let obj = NSObject::new();
+        let ptr: *const NSObject = &*obj;
+        let strong: StrongPtr = obj.into();
+        assert_eq!(*strong, ptr as *mut Object);
+
+        let obj = Retained::from_strong_ptr(strong).unwrap();
+        assert_eq!(Retained::as_ptr(&obj), ptr.cast());
+        assert_eq!(Retained::strong_count(&obj), 1);
+
+        // SAFETY: The pointer is NULL.
+        let strong = unsafe { StrongPtr::new(core::ptr::null_mut()) };
+        assert!(Retained::from_strong_ptr(strong).is_none());
+    }
+
+    #[test]
+    fn sel_roundtrip() {
+        let sel = sel!(hash);
+        let objc_sel: objc::runtime::Sel = sel.into();
+        assert_eq!(objc_sel.name(), "hash");
+        assert_eq!(Sel::from(objc_sel), sel);
+    }
+
+    #[test]
+    fn msg_send_raw_object() {
+        let obj = NSObject::new();
+        let raw: *mut Object = Retained::as_ptr(&obj) as *mut Object;
+        let expected: usize = unsafe { msg_send![&obj, hash] };
+        let actual: usize = unsafe { msg_send![raw, hash] };
+        assert_eq!(expected, actual);
+
+        let obj: &AnyObject = &obj;
+        let obj: &Object = obj.as_ref();
+        let obj: &AnyObject = obj.as_ref();
+        assert_eq!(obj.class(), NSObject::class());
+    }
+}
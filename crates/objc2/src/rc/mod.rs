@@ -69,7 +69,7 @@ pub use self::retained::Retained;
 pub use self::retained_traits::{DefaultRetained, RetainedFromIterator, RetainedIntoIterator};
 #[cfg(test)]
 pub(crate) use self::test_object::{RcTestObject, ThreadTestData};
-pub use self::weak::Weak;
+pub use self::weak::{Weak, WeakSlot};
 // Same as above.
 #[allow(deprecated)]
 pub use self::weak::WeakId;
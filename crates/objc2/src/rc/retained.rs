@@ -7,7 +7,7 @@ use core::ptr::{self, NonNull};
 
 use super::AutoreleasePool;
 use crate::runtime::{objc_release_fast, objc_retain_fast, AnyObject};
-use crate::{ffi, ClassType, DowncastTarget, Message};
+use crate::{ffi, ClassType, DefinedClass, DowncastTarget, Message};
 
 /// A reference counted pointer type for Objective-C objects.
 ///
@@ -261,12 +261,49 @@ impl<T: ?Sized + Message> Retained<T> {
     ///
     /// The pointer is valid for at least as long as the `Retained` is held.
     ///
+    /// Objects are never moved, so the pointer is the same as the address of
+    /// the reference returned from [`Deref`], and stays the same for as long
+    /// as the object is alive, including across clones of the `Retained`.
+    ///
     /// This is an associated method, and must be called as `Retained::as_ptr(obj)`.
     #[inline]
     pub fn as_ptr(this: &Self) -> *const T {
         this.ptr.as_ptr()
     }
 
+    /// Returns `true` if the two `Retained`s point to the same object.
+    ///
+    /// This is similar to [`Arc::ptr_eq`], and compares the objects'
+    /// identity instead of their contents, which is what [`PartialEq`] on
+    /// `Retained` does.
+    ///
+    /// This is an associated method, and must be called as
+    /// `Retained::ptr_eq(a, b)`.
+    ///
+    /// [`Arc::ptr_eq`]: std::sync::Arc::ptr_eq
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2::rc::Retained;
+    /// use objc2::runtime::NSObject;
+    ///
+    /// let obj = NSObject::new();
+    /// let same = obj.clone();
+    /// let other = NSObject::new();
+    /// assert!(Retained::ptr_eq(&obj, &same));
+    /// assert!(!Retained::ptr_eq(&obj, &other));
+    /// ```
+    #[inline]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        // Compare addresses only, ignoring any metadata.
+        ptr::eq(
+            Self::as_ptr(this).cast::<u8>(),
+            Self::as_ptr(other).cast::<u8>(),
+        )
+    }
+
     #[inline]
     pub(crate) fn consume_as_ptr_option(this: Option<Self>) -> *mut T
     where
@@ -689,6 +726,48 @@ impl<T: Message> Retained<T> {
     pub fn autorelease_return(this: Self) -> *mut T {
         Self::autorelease_return_option(Some(this))
     }
+
+    /// The current retain count of the object.
+    ///
+    /// This is similar to [`Arc::strong_count`], but only useful for
+    /// debugging; the retain count of Objective-C objects is not reliable:
+    /// - Pending autoreleases are included in the count.
+    /// - Objects that are never deallocated, such as tagged pointers and
+    ///   constant strings, usually return [`NSUInteger::MAX`].
+    /// - Classes may override `retainCount` to return something else.
+    /// - Another thread may retain or release the object at any time, or
+    ///   load it from a weak reference, which does not count towards it.
+    ///
+    /// So do not use this to make decisions about ownership; in particular,
+    /// a count of `1` does not mean that this is the only reference.
+    ///
+    /// This is an associated method, and must be called as
+    /// `Retained::strong_count(obj)`.
+    ///
+    /// [`Arc::strong_count`]: std::sync::Arc::strong_count
+    /// [`NSUInteger::MAX`]: usize::MAX
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2::rc::Retained;
+    /// use objc2::runtime::NSObject;
+    ///
+    /// let obj = NSObject::new();
+    /// assert_eq!(Retained::strong_count(&obj), 1);
+    /// let obj2 = obj.clone();
+    /// assert_eq!(Retained::strong_count(&obj), 2);
+    /// drop(obj2);
+    /// assert_eq!(Retained::strong_count(&obj), 1);
+    /// ```
+    #[doc(alias = "retainCount")]
+    #[inline]
+    pub fn strong_count(this: &Self) -> usize {
+        let obj: &T = this;
+        // SAFETY: `retainCount` takes no arguments, and returns `NSUInteger`.
+        unsafe { crate::msg_send![obj, retainCount] }
+    }
 }
 
 impl<T: ClassType + 'static> Retained<T>
@@ -711,6 +790,56 @@ where
     }
 }
 
+impl<T: DefinedClass> Retained<T> {
+    /// Move the instance variables out of the object, if this is the only
+    /// reference to it.
+    ///
+    /// This is similar to [`Arc::try_unwrap`], and allows recovering the
+    /// state of an object declared with [`define_class!`] once it is no
+    /// longer shared, e.g. when it was only used as a delegate for a
+    /// finished operation.
+    ///
+    /// On success, the object is released, and neither its instance
+    /// variables nor the object itself (if it implements [`Drop`]) are
+    /// dropped in `dealloc`.
+    ///
+    /// This is an associated method, and must be called as
+    /// `Retained::try_into_ivars(obj)`.
+    ///
+    /// [`Arc::try_unwrap`]: std::sync::Arc::try_unwrap
+    /// [`define_class!`]: crate::define_class
+    ///
+    ///
+    /// # Errors
+    ///
+    /// If the object's retain count (see [`Retained::strong_count`]) is not
+    /// `1`, the `Retained` is returned back as the [`Err`] type.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The retain count alone cannot tell whether the object is still in use,
+    /// so you must ensure that:
+    /// - There are no weak references to the object.
+    /// - There are no unretained references to the object (such as from
+    ///   `assign` or `unsafe_unretained` properties, e.g. delegates).
+    /// - The class, and its superclasses, do not override `retain`,
+    ///   `release` or `retainCount`, nor access the instance variables in
+    ///   `dealloc`.
+    #[inline]
+    pub unsafe fn try_into_ivars(this: Self) -> Result<T::Ivars, Self> {
+        if Self::strong_count(&this) != 1 {
+            return Err(this);
+        }
+        // SAFETY: The object is finalized, since we have a `Retained` to it,
+        // and the caller ensures that the ivars are not accessed afterwards,
+        // since this is the only reference, and we release it below.
+        let ivars = unsafe { crate::__macro_helpers::defined_ivars::take_ivars(this.ptr) };
+        drop(this);
+        Ok(ivars)
+    }
+}
+
 // TODO: Add ?Sized bound
 impl<T: Message> Clone for Retained<T> {
     /// Retain the object, increasing its reference count.
@@ -984,6 +1113,55 @@ mod tests {
         expected.assert_current();
     }
 
+    #[test]
+    fn test_ptr_eq_and_strong_count() {
+        let obj = RcTestObject::new();
+        let other = RcTestObject::new();
+        assert_eq!(Retained::strong_count(&obj), 1);
+
+        let cloned = obj.clone();
+        assert!(Retained::ptr_eq(&obj, &cloned));
+        assert!(!Retained::ptr_eq(&obj, &other));
+        assert_eq!(Retained::as_ptr(&obj), &*cloned as *const RcTestObject);
+        assert_eq!(Retained::strong_count(&obj), 2);
+
+        drop(cloned);
+        assert_eq!(Retained::strong_count(&obj), 1);
+    }
+
+    #[test]
+    fn test_try_into_ivars() {
+        use alloc::rc::Rc;
+
+        use crate::{msg_send_id, AllocAnyThread, DefinedClass};
+
+        define_class!(
+            #[unsafe(super(NSObject))]
+            #[name = "TestTryIntoIvars"]
+            #[ivars = Rc<()>]
+            struct TestTryIntoIvars;
+        );
+
+        let data = Rc::new(());
+        let obj = TestTryIntoIvars::alloc().set_ivars(Rc::clone(&data));
+        let obj: Retained<TestTryIntoIvars> = unsafe { msg_send_id![super(obj), init] };
+        assert_eq!(Rc::strong_count(obj.ivars()), 2);
+
+        let cloned = obj.clone();
+        let Err(obj) = (unsafe { Retained::try_into_ivars(obj) }) else {
+            panic!("object was unique");
+        };
+        drop(cloned);
+
+        let Ok(ivars) = (unsafe { Retained::try_into_ivars(obj) }) else {
+            panic!("object was shared");
+        };
+        // The ivars were moved out, and not dropped in `dealloc`.
+        assert_eq!(Rc::strong_count(&data), 2);
+        drop(ivars);
+        assert_eq!(Rc::strong_count(&data), 1);
+    }
+
     #[repr(C)]
     struct MyObject<'a> {
         inner: NSObject,
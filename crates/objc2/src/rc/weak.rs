@@ -1,7 +1,8 @@
 use alloc::boxed::Box;
 use core::cell::UnsafeCell;
 use core::fmt;
-use core::marker::PhantomData;
+use core::marker::{PhantomData, PhantomPinned};
+use core::mem::ManuallyDrop;
 use core::ptr;
 use std::panic::{RefUnwindSafe, UnwindSafe};
 
@@ -9,6 +10,188 @@ use super::Retained;
 use crate::runtime::AnyObject;
 use crate::{ffi, Message};
 
+/// A raw weak reference slot, the building block of [`Weak`].
+///
+/// This is a thin wrapper around the runtime's weak reference storage: the
+/// runtime registers the _address_ of the slot in a side table, and zeroes
+/// it when the referenced object is deallocated. This means that the slot
+/// must not be moved while it is initialized (except through
+/// [`WeakSlot::move_to`]), which is why all operations on it are `unsafe`
+/// and operate on raw pointers.
+///
+/// [`Weak`] is implemented as a `Box<WeakSlot<T>>`, and should be preferred
+/// whenever possible. Use this type if you need to store weak references
+/// inline in your own data structures, for example in a weak-keyed hash
+/// map, without an allocation per reference (see also [`Weak::into_raw`]
+/// and [`Weak::from_raw`]).
+///
+///
+/// # Example
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use objc2::rc::WeakSlot;
+/// use objc2::runtime::NSObject;
+///
+/// let obj = NSObject::new();
+///
+/// let mut slot = MaybeUninit::<WeakSlot<NSObject>>::uninit();
+/// // SAFETY: The slot is valid for writes, and is not moved while it is
+/// // initialized.
+/// unsafe { WeakSlot::init(slot.as_mut_ptr(), Some(&obj)) };
+///
+/// let loaded = unsafe { WeakSlot::load(slot.as_ptr()) };
+/// assert_eq!(loaded.as_deref(), Some(&*obj));
+///
+/// drop(loaded);
+/// drop(obj);
+/// // The object is deallocated, so loading gives `None`.
+/// # if cfg!(not(feature = "gnustep-1-7")) {
+/// assert!(unsafe { WeakSlot::load(slot.as_ptr()) }.is_none());
+/// # }
+///
+/// // SAFETY: The slot is initialized, and not used after this.
+/// unsafe { WeakSlot::destroy(slot.as_mut_ptr()) };
+/// ```
+#[repr(transparent)]
+pub struct WeakSlot<T: ?Sized> {
+    /// Loading may modify the pointer through a shared reference, so we use
+    /// an `UnsafeCell` to get a `*mut` without the slot being mutable.
+    ///
+    /// Remember that any thread may actually modify the inner value
+    /// concurrently, but as long as we only use it through the
+    /// `objc_XXXWeak` functions, all access is behind a lock.
+    ptr: UnsafeCell<*mut AnyObject>,
+    /// Inherits variance, dropck and various marker traits from
+    /// `Retained<T>`.
+    item: PhantomData<Retained<T>>,
+    /// The runtime relies on the address of the slot.
+    _pinned: PhantomPinned,
+}
+
+impl<T: ?Sized> WeakSlot<T> {
+    #[inline]
+    fn raw(slot: *const Self) -> *mut *mut AnyObject {
+        // SAFETY: `WeakSlot` is `repr(transparent)` over the `UnsafeCell`,
+        // which is in turn `repr(transparent)` over the pointer.
+        slot.cast_mut().cast()
+    }
+
+    /// Destroy the slot, unregistering it from the runtime.
+    ///
+    /// The slot is left uninitialized afterwards.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The slot must be initialized.
+    #[doc(alias = "objc_destroyWeak")]
+    #[inline]
+    pub unsafe fn destroy(slot: *mut Self) {
+        // SAFETY: Upheld by the caller.
+        unsafe { ffi::objc_destroyWeak(Self::raw(slot)) }
+    }
+
+    /// Initialize `dst` to point to the same object as `src`, and destroy
+    /// `src`.
+    ///
+    /// This is more efficient than copying and destroying the source.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// `src` must be initialized, and `dst` must be uninitialized and valid
+    /// for writes. `src` is left uninitialized afterwards.
+    #[doc(alias = "objc_moveWeak")]
+    #[inline]
+    pub unsafe fn move_to(src: *mut Self, dst: *mut Self) {
+        // SAFETY: Upheld by the caller.
+        unsafe { ffi::objc_moveWeak(Self::raw(dst), Self::raw(src)) }
+    }
+
+    /// Initialize `dst` to point to the same object as `src`.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// `src` must be initialized, and `dst` must be uninitialized and valid
+    /// for writes.
+    #[doc(alias = "objc_copyWeak")]
+    #[inline]
+    pub unsafe fn copy_to(src: *const Self, dst: *mut Self) {
+        // SAFETY: Upheld by the caller.
+        unsafe { ffi::objc_copyWeak(Self::raw(dst), Self::raw(src)) }
+    }
+}
+
+impl<T: Message> WeakSlot<T> {
+    /// Initialize the slot to point to the given object, or to nothing.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The slot must be valid for writes, and must not already be
+    /// initialized (or if it is, it must have been destroyed first).
+    ///
+    /// The slot must not be moved while it is initialized, and must be
+    /// destroyed with [`WeakSlot::destroy`] before it is deallocated.
+    #[doc(alias = "objc_initWeak")]
+    #[inline]
+    pub unsafe fn init(slot: *mut Self, obj: Option<&T>) {
+        let obj: *const T = obj.map_or(ptr::null(), |obj| obj);
+        // SAFETY: Upheld by the caller, and the object is valid or NULL.
+        let _ = unsafe { ffi::objc_initWeak(Self::raw(slot), obj.cast_mut().cast()) };
+    }
+
+    /// Load the object into a [`Retained`] if it still exists.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The slot must be initialized.
+    #[doc(alias = "objc_loadWeakRetained")]
+    #[inline]
+    pub unsafe fn load(slot: *const Self) -> Option<Retained<T>> {
+        // SAFETY: Upheld by the caller.
+        let obj = unsafe { ffi::objc_loadWeakRetained(Self::raw(slot)) };
+        // SAFETY: The object has +1 retain count, and the slot stores an
+        // object of type `T`.
+        unsafe { Retained::from_raw(obj.cast()) }
+    }
+
+    /// Change the object that an initialized slot points to.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The slot must be initialized.
+    #[doc(alias = "objc_storeWeak")]
+    #[inline]
+    pub unsafe fn store(slot: *const Self, obj: Option<&T>) {
+        let obj: *const T = obj.map_or(ptr::null(), |obj| obj);
+        // SAFETY: Upheld by the caller, and the object is valid or NULL.
+        let _ = unsafe { ffi::objc_storeWeak(Self::raw(slot), obj.cast_mut().cast()) };
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for WeakSlot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(WeakSlot)")
+    }
+}
+
+// SAFETY: Same as `Weak<T>`; all access goes through the runtime's lock.
+unsafe impl<T: ?Sized + Sync + Send> Sync for WeakSlot<T> {}
+
+// SAFETY: Same as `Weak<T>`.
+unsafe impl<T: ?Sized + Sync + Send> Send for WeakSlot<T> {}
+
+// Same as `Weak<T>`.
+impl<T: ?Sized + RefUnwindSafe> RefUnwindSafe for WeakSlot<T> {}
+
+// Same as `Weak<T>`.
+impl<T: ?Sized + RefUnwindSafe> UnwindSafe for WeakSlot<T> {}
+
 /// A weak pointer to an Objective-C reference counted object.
 ///
 /// The object is allowed to be deallocated while the weak pointer is alive,
@@ -34,19 +217,11 @@ pub struct Weak<T: ?Sized> {
     /// We give the runtime the address to this box, so that it can modify it
     /// even if the `Weak` is moved.
     ///
-    /// Loading may modify the pointer through a shared reference, so we use
-    /// an UnsafeCell to get a *mut without self being mutable.
-    ///
-    /// Remember that any thread may actually modify the inner value
-    /// concurrently, but as long as we only use it through the `objc_XXXWeak`
-    /// methods, all access is behind a lock.
+    /// Weak inherits variance, dropck and various marker traits from
+    /// `Retained<T>` through this.
     ///
-    /// TODO: Verify the need for UnsafeCell?
     /// TODO: Investigate if we can avoid some allocations using `Pin`.
-    inner: Box<UnsafeCell<*mut AnyObject>>,
-    /// Weak inherits variance, dropck and various marker traits from
-    /// `Retained<T>`.
-    item: PhantomData<Retained<T>>,
+    inner: Box<WeakSlot<T>>,
 }
 
 /// Fully-deprecated type-alias to [`Weak`].
@@ -88,13 +263,23 @@ impl<T: Message> Weak<T> {
     ///
     /// The object must be valid or null.
     unsafe fn new_inner(obj: *const T) -> Self {
-        let inner = Box::new(UnsafeCell::new(ptr::null_mut()));
-        // SAFETY: `ptr` will never move, and the caller verifies `obj`
-        let _ = unsafe { ffi::objc_initWeak(inner.get(), (obj as *mut T).cast()) };
-        Self {
-            inner,
+        let inner = Self::alloc_slot();
+        let slot = Box::into_raw(inner);
+        // SAFETY: The slot will never move (until it's destroyed in `Drop`),
+        // and the caller verifies `obj`.
+        unsafe { WeakSlot::init(slot, obj.as_ref()) };
+        // SAFETY: The pointer came from `Box::into_raw` above.
+        let inner = unsafe { Box::from_raw(slot) };
+        Self { inner }
+    }
+
+    /// Allocate an uninitialized slot.
+    fn alloc_slot() -> Box<WeakSlot<T>> {
+        Box::new(WeakSlot {
+            ptr: UnsafeCell::new(ptr::null_mut()),
             item: PhantomData,
-        }
+            _pinned: PhantomPinned,
+        })
     }
 
     /// Load the object into an [`Retained`] if it still exists.
@@ -106,21 +291,63 @@ impl<T: Message> Weak<T> {
     #[doc(alias = "objc_loadWeakRetained")]
     #[inline]
     pub fn load(&self) -> Option<Retained<T>> {
-        let ptr = self.inner.get();
-        let obj = unsafe { ffi::objc_loadWeakRetained(ptr) }.cast();
-        // SAFETY: The object has +1 retain count
-        unsafe { Retained::from_raw(obj) }
+        // SAFETY: The slot is initialized.
+        unsafe { WeakSlot::load(&*self.inner) }
     }
 
     // TODO: Add `autorelease(&self, pool) -> Option<&T>` using `objc_loadWeak`?
 }
 
+impl<T: ?Sized> Weak<T> {
+    /// Consume the weak pointer, returning a pointer to the underlying
+    /// [`WeakSlot`].
+    ///
+    /// The slot is still registered with the runtime, and must eventually be
+    /// converted back with [`Weak::from_raw`] to avoid leaking it. In the
+    /// meantime, it can be used with the unsafe functions on [`WeakSlot`]
+    /// (except for [`WeakSlot::destroy`] and [`WeakSlot::move_to`]).
+    ///
+    /// This is useful for storing weak references in type-erased
+    /// collections, such as weak-keyed hash maps.
+    #[inline]
+    pub fn into_raw(this: Self) -> *mut WeakSlot<T> {
+        let this = ManuallyDrop::new(this);
+        // SAFETY: We don't touch `this` after reading out of it.
+        let inner = unsafe { ptr::read(&this.inner) };
+        Box::into_raw(inner)
+    }
+
+    /// Construct a weak pointer from a pointer returned by
+    /// [`Weak::into_raw`].
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The pointer must have been returned from [`Weak::into_raw`], and
+    /// must not be used after this.
+    #[inline]
+    pub unsafe fn from_raw(ptr: *mut WeakSlot<T>) -> Self {
+        // SAFETY: Upheld by the caller.
+        let inner = unsafe { Box::from_raw(ptr) };
+        Self { inner }
+    }
+
+    /// A pointer to the underlying [`WeakSlot`].
+    ///
+    /// The slot is valid for as long as the `Weak` is alive.
+    #[inline]
+    pub fn as_slot(this: &Self) -> *const WeakSlot<T> {
+        &*this.inner
+    }
+}
+
 impl<T: ?Sized> Drop for Weak<T> {
     /// Destroys the weak pointer.
     #[doc(alias = "objc_destroyWeak")]
     #[inline]
     fn drop(&mut self) {
-        unsafe { ffi::objc_destroyWeak(self.inner.get()) }
+        // SAFETY: The slot is initialized, and is not used after this.
+        unsafe { WeakSlot::destroy(&mut *self.inner) }
     }
 }
 
@@ -129,12 +356,13 @@ impl<T: Message> Clone for Weak<T> {
     /// Make a clone of the weak pointer that points to the same object.
     #[doc(alias = "objc_copyWeak")]
     fn clone(&self) -> Self {
-        let ptr = Box::new(UnsafeCell::new(ptr::null_mut()));
-        unsafe { ffi::objc_copyWeak(ptr.get(), self.inner.get()) };
-        Self {
-            inner: ptr,
-            item: PhantomData,
-        }
+        let slot = Box::into_raw(Self::alloc_slot());
+        // SAFETY: Our slot is initialized, and the new slot is not, and will
+        // not move until it's destroyed in `Drop`.
+        unsafe { WeakSlot::copy_to(&*self.inner, slot) };
+        // SAFETY: The pointer came from `Box::into_raw` above.
+        let inner = unsafe { Box::from_raw(slot) };
+        Self { inner }
     }
 }
 
@@ -269,6 +497,44 @@ mod tests {
         drop(weak);
     }
 
+    #[test]
+    fn test_weak_slot_move() {
+        let obj = RcTestObject::new();
+        let mut expected = ThreadTestData::current();
+
+        let mut src = mem::MaybeUninit::<WeakSlot<RcTestObject>>::uninit();
+        let mut dst = mem::MaybeUninit::<WeakSlot<RcTestObject>>::uninit();
+        unsafe { WeakSlot::init(src.as_mut_ptr(), Some(&obj)) };
+        unsafe { WeakSlot::move_to(src.as_mut_ptr(), dst.as_mut_ptr()) };
+        expected.assert_current();
+
+        let strong = unsafe { WeakSlot::load(dst.as_ptr()) }.unwrap();
+        expected.try_retain += 1;
+        expected.assert_current();
+        assert!(ptr::eq(&*strong, &*obj));
+        drop(strong);
+        expected.release += 1;
+
+        unsafe { WeakSlot::store(dst.as_ptr(), None) };
+        assert!(unsafe { WeakSlot::load(dst.as_ptr()) }.is_none());
+        unsafe { WeakSlot::destroy(dst.as_mut_ptr()) };
+        expected.assert_current();
+    }
+
+    #[test]
+    fn test_weak_into_raw() {
+        let obj = RcTestObject::new();
+        let weak = Weak::from(&obj);
+        let slot = Weak::into_raw(weak);
+
+        let strong = unsafe { WeakSlot::load(slot) }.unwrap();
+        assert!(ptr::eq(&*strong, &*obj));
+
+        let weak = unsafe { Weak::from_raw(slot) };
+        assert_eq!(Weak::as_slot(&weak), slot.cast_const());
+        drop(weak);
+    }
+
     #[repr(C)]
     struct MyObject<'a> {
         inner: NSObject,
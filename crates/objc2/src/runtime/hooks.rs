@@ -0,0 +1,549 @@
+//! Safe registration of the runtime's hook points.
+//!
+//! The runtime only has a single slot for each hook, and expects each hook
+//! to call the previously installed one if it didn't handle the request
+//! itself. We install a single trampoline per hook the first time a Rust
+//! hook is registered, and let that trampoline run all registered Rust hooks
+//! (in reverse registration order) before falling back to the previous hook.
+//!
+//! Hooks cannot be unregistered, since there's no way to know whether the
+//! runtime (or another thread) is currently executing them.
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+struct Node<F: ?Sized> {
+    next: *const Node<F>,
+    hook: Box<F>,
+}
+
+/// A lock-free, append-only list of hooks.
+///
+/// We intentionally avoid a `Mutex` here, since the hooks may themselves
+/// call back into the runtime (e.g. a class lookup hook may register a new
+/// class, which may in turn look up its superclass).
+struct HookList<F: ?Sized> {
+    head: AtomicPtr<Node<F>>,
+}
+
+impl<F: ?Sized> HookList<F> {
+    const fn new() -> Self {
+        Self {
+            head: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    fn push(&self, hook: Box<F>) {
+        let node = Box::into_raw(Box::new(Node {
+            next: ptr::null(),
+            hook,
+        }));
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            // SAFETY: The node is not yet shared with other threads.
+            unsafe { (*node).next = head };
+            match self
+                .head
+                .compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed)
+            {
+                Ok(_) => return,
+                Err(new_head) => head = new_head,
+            }
+        }
+    }
+
+    /// Find the first hook (starting from the most recently registered) that
+    /// returns `Some`.
+    fn find_map<R>(&self, mut f: impl FnMut(&F) -> Option<R>) -> Option<R> {
+        let mut node = self.head.load(Ordering::Acquire).cast_const();
+        // SAFETY: Nodes are leaked, and never modified after being
+        // published, so they're valid for the rest of the program.
+        while let Some(current) = unsafe { node.as_ref() } {
+            if let Some(res) = f(&current.hook) {
+                return Some(res);
+            }
+            node = current.next;
+        }
+        None
+    }
+}
+
+// SAFETY: The hooks are required to be `Send + Sync`.
+unsafe impl<F: ?Sized + Send + Sync> Sync for HookList<F> {}
+
+/// The hook that was installed before our trampoline.
+///
+/// Function pointers cannot be stored in atomics directly, so this stores
+/// the function pointer `F` as a data pointer.
+struct Previous<F> {
+    ptr: AtomicPtr<()>,
+    p: PhantomData<F>,
+}
+
+impl<F: Copy> Previous<F> {
+    const fn new() -> Self {
+        assert!(mem::size_of::<F>() == mem::size_of::<*mut ()>());
+        Self {
+            ptr: AtomicPtr::new(ptr::null_mut()),
+            p: PhantomData,
+        }
+    }
+
+    fn get(&self) -> Option<F> {
+        let ptr = self.ptr.load(Ordering::Acquire);
+        // SAFETY: The pointer was stored from an `F`.
+        (!ptr.is_null()).then(|| unsafe { mem::transmute_copy::<*mut (), F>(&ptr) })
+    }
+
+    #[cfg(any(doc, feature = "gnustep-1-7"))]
+    fn set(&self, previous: Option<F>) {
+        let ptr = match previous {
+            // SAFETY: `F` is a function pointer, which has the same size as
+            // a data pointer (checked in `new`).
+            Some(f) => unsafe { mem::transmute_copy::<F, *mut ()>(&f) },
+            None => ptr::null_mut(),
+        };
+        self.ptr.store(ptr, Ordering::Release);
+    }
+
+    /// A pointer that the runtime can write the previous hook into.
+    #[cfg(any(doc, not(feature = "gnustep-1-7")))]
+    fn as_out_ptr(&self) -> *mut Option<F> {
+        // `Option<F>` has the same layout as a nullable pointer.
+        self.ptr.as_ptr().cast()
+    }
+}
+
+// SAFETY: The function pointer is only accessed atomically.
+unsafe impl<F> Sync for Previous<F> {}
+
+/// Look up a runtime function that isn't available on all of the
+/// deployment targets that we support, and thus cannot be linked directly.
+///
+///
+/// # Safety
+///
+/// `name` must be NUL-terminated, and the symbol must have the signature `F`.
+#[cfg(any(
+    doc,
+    all(
+        target_vendor = "apple",
+        not(all(target_os = "macos", target_arch = "x86"))
+    )
+))]
+unsafe fn resolve<F: Copy>(symbol: &crate::__macro_helpers::CachedSymbol, name: &str) -> Option<F> {
+    use core::ffi::c_void;
+
+    assert!(mem::size_of::<F>() == mem::size_of::<*mut c_void>());
+    // SAFETY: Checked by caller.
+    let ptr = unsafe { symbol.get(name) };
+    // SAFETY: Checked by caller.
+    (!ptr.is_null()).then(|| unsafe { mem::transmute_copy::<*mut c_void, F>(&ptr) })
+}
+
+/// Run the given closure, aborting if it panics.
+///
+/// The runtime is not prepared for hooks to unwind into it.
+fn abort_on_panic<R>(f: impl FnOnce() -> R) -> R {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(res) => res,
+        Err(_) => {
+            std::eprintln!("panicked inside Objective-C runtime hook, aborting");
+            std::process::abort()
+        }
+    }
+}
+
+#[cfg(any(
+    doc,
+    feature = "gnustep-1-7",
+    all(
+        target_vendor = "apple",
+        not(all(target_os = "macos", target_arch = "x86"))
+    )
+))]
+mod get_class {
+    use alloc::boxed::Box;
+    use core::ffi::{c_char, CStr};
+    use std::sync::Once;
+
+    #[cfg(not(feature = "gnustep-1-7"))]
+    use super::resolve;
+    use super::{abort_on_panic, HookList, Previous};
+    #[cfg(not(feature = "gnustep-1-7"))]
+    use crate::__macro_helpers::CachedSymbol;
+    use crate::ffi;
+    use crate::runtime::AnyClass;
+
+    type Hook = dyn Fn(&CStr) -> Option<&'static AnyClass> + Send + Sync;
+
+    static HOOKS: HookList<Hook> = HookList::new();
+    static INSTALL: Once = Once::new();
+
+    #[cfg(not(feature = "gnustep-1-7"))]
+    static PREVIOUS: Previous<ffi::objc_hook_getClass> = Previous::new();
+
+    #[cfg(not(feature = "gnustep-1-7"))]
+    type SetHook = unsafe extern "C" fn(
+        new_value: ffi::objc_hook_getClass,
+        out_old_value: *mut Option<ffi::objc_hook_getClass>,
+    );
+
+    /// `objc_setHook_getClass`, if available.
+    #[cfg(not(feature = "gnustep-1-7"))]
+    fn set_hook() -> Option<SetHook> {
+        static SYMBOL: CachedSymbol = CachedSymbol::new();
+        // SAFETY: The name is NUL-terminated, and the signature matches.
+        unsafe { resolve(&SYMBOL, "objc_setHook_getClass\0") }
+    }
+
+    #[cfg(not(feature = "gnustep-1-7"))]
+    unsafe extern "C" fn trampoline(
+        name: *const c_char,
+        out_cls: *mut *const AnyClass,
+    ) -> crate::runtime::Bool {
+        // SAFETY: The runtime passes a valid C-string.
+        let cls = abort_on_panic(|| HOOKS.find_map(|hook| hook(unsafe { CStr::from_ptr(name) })));
+        if let Some(cls) = cls {
+            // SAFETY: The runtime passes a valid out pointer.
+            unsafe { *out_cls = cls };
+            return crate::runtime::Bool::YES;
+        }
+        // `PREVIOUS` is written by the runtime before the trampoline is
+        // installed, and never modified afterwards.
+        match PREVIOUS.get() {
+            Some(previous) => unsafe { previous(name, out_cls) },
+            None => crate::runtime::Bool::NO,
+        }
+    }
+
+    #[cfg(feature = "gnustep-1-7")]
+    static PREVIOUS: Previous<unsafe extern "C" fn(name: *const c_char) -> *mut AnyClass> =
+        Previous::new();
+
+    #[cfg(feature = "gnustep-1-7")]
+    unsafe extern "C" fn trampoline(name: *const c_char) -> *mut AnyClass {
+        // SAFETY: The runtime passes a valid C-string.
+        let cls = abort_on_panic(|| HOOKS.find_map(|hook| hook(unsafe { CStr::from_ptr(name) })));
+        if let Some(cls) = cls {
+            let cls: *const AnyClass = cls;
+            return cls.cast_mut();
+        }
+        // `PREVIOUS` is written before the trampoline is installed, and
+        // never modified afterwards.
+        match PREVIOUS.get() {
+            Some(previous) => unsafe { previous(name) },
+            None => core::ptr::null_mut(),
+        }
+    }
+
+    pub(crate) fn register(hook: Box<Hook>) -> bool {
+        #[cfg(not(feature = "gnustep-1-7"))]
+        let Some(set_hook) = set_hook() else {
+            return false;
+        };
+
+        // Push before installing, such that the hook is visible as soon as
+        // the trampoline is.
+        HOOKS.push(hook);
+        INSTALL.call_once(|| {
+            #[cfg(not(feature = "gnustep-1-7"))]
+            // SAFETY: The trampoline has the correct signature, and the
+            // runtime writes the old value before installing the new one.
+            unsafe {
+                set_hook(trampoline, PREVIOUS.as_out_ptr())
+            };
+            #[cfg(feature = "gnustep-1-7")]
+            // SAFETY: The trampoline has the correct signature.
+            //
+            // GNUStep stores the hook in a plain global, so this may race
+            // with other code that modifies the hook without going through
+            // us; there's nothing we can do about that.
+            unsafe {
+                PREVIOUS.set(ffi::_objc_lookup_class);
+                ffi::_objc_lookup_class = Some(trampoline);
+            };
+        });
+        true
+    }
+}
+
+#[cfg(any(
+    doc,
+    all(
+        target_vendor = "apple",
+        not(all(target_os = "macos", target_arch = "x86"))
+    )
+))]
+mod lazy_class_namer {
+    use alloc::boxed::Box;
+    use core::ffi::{c_char, CStr};
+    use std::sync::Once;
+
+    use super::{abort_on_panic, resolve, HookList, Previous};
+    use crate::__macro_helpers::CachedSymbol;
+    use crate::ffi;
+    use crate::runtime::AnyClass;
+
+    type Hook = dyn Fn(&AnyClass) -> Option<&'static CStr> + Send + Sync;
+
+    static HOOKS: HookList<Hook> = HookList::new();
+    static INSTALL: Once = Once::new();
+    static PREVIOUS: Previous<ffi::objc_hook_lazyClassNamer> = Previous::new();
+
+    type SetHook = unsafe extern "C" fn(
+        new_value: ffi::objc_hook_lazyClassNamer,
+        out_old_value: *mut Option<ffi::objc_hook_lazyClassNamer>,
+    );
+
+    /// `objc_setHook_lazyClassNamer`, if available.
+    fn set_hook() -> Option<SetHook> {
+        static SYMBOL: CachedSymbol = CachedSymbol::new();
+        // SAFETY: The name is NUL-terminated, and the signature matches.
+        unsafe { resolve(&SYMBOL, "objc_setHook_lazyClassNamer\0") }
+    }
+
+    unsafe extern "C" fn trampoline(cls: *const AnyClass) -> *const c_char {
+        // SAFETY: The runtime passes a valid class.
+        let name = abort_on_panic(|| HOOKS.find_map(|hook| hook(unsafe { &*cls })));
+        if let Some(name) = name {
+            return name.as_ptr();
+        }
+        // `PREVIOUS` is written by the runtime before the trampoline is
+        // installed, and never modified afterwards.
+        match PREVIOUS.get() {
+            Some(previous) => unsafe { previous(cls) },
+            None => core::ptr::null(),
+        }
+    }
+
+    pub(crate) fn register(hook: Box<Hook>) -> bool {
+        let Some(set_hook) = set_hook() else {
+            return false;
+        };
+
+        HOOKS.push(hook);
+        INSTALL.call_once(|| {
+            // SAFETY: The trampoline has the correct signature, and the
+            // runtime writes the old value before installing the new one.
+            unsafe { set_hook(trampoline, PREVIOUS.as_out_ptr()) };
+        });
+        true
+    }
+}
+
+#[cfg(any(doc, feature = "gnustep-1-7"))]
+mod forward {
+    use alloc::boxed::Box;
+    use std::sync::Once;
+
+    use super::{abort_on_panic, HookList, Previous};
+    use crate::ffi;
+    use crate::runtime::{AnyObject, Imp, Sel};
+
+    type Hook = dyn Fn(&AnyObject, Sel) -> Option<Imp> + Send + Sync;
+
+    static HOOKS: HookList<Hook> = HookList::new();
+    static INSTALL: Once = Once::new();
+    static PREVIOUS: Previous<unsafe extern "C" fn(*mut AnyObject, Sel) -> Option<Imp>> =
+        Previous::new();
+
+    unsafe extern "C" fn trampoline(receiver: *mut AnyObject, sel: Sel) -> Option<Imp> {
+        // SAFETY: The runtime only forwards messages sent to non-nil
+        // receivers.
+        let imp = abort_on_panic(|| HOOKS.find_map(|hook| hook(unsafe { &*receiver }, sel)));
+        if imp.is_some() {
+            return imp;
+        }
+        // `PREVIOUS` is written before the trampoline is installed, and
+        // never modified afterwards.
+        match PREVIOUS.get() {
+            Some(previous) => unsafe { previous(receiver, sel) },
+            None => None,
+        }
+    }
+
+    pub(crate) fn register(hook: Box<Hook>) {
+        HOOKS.push(hook);
+        INSTALL.call_once(|| {
+            // SAFETY: The trampoline has the correct signature.
+            //
+            // See `get_class::register` for caveats.
+            unsafe {
+                PREVIOUS.set(ffi::__objc_msg_forward2);
+                ffi::__objc_msg_forward2 = Some(trampoline);
+            };
+        });
+    }
+}
+
+/// Register a hook that is consulted when a class could not be found.
+///
+/// The hook is called with the name of the class, and can return a class to
+/// use for that name, which enables providing classes lazily, e.g. by
+/// creating them with [`ClassBuilder`] on first use. If the hook returns
+/// [`None`], the next hook is tried, and finally the hook that was
+/// installed before the first call to this function (if any).
+///
+/// Hooks are tried in reverse registration order, and cannot be
+/// unregistered.
+///
+/// This uses `objc_setHook_getClass` on Apple platforms (available since
+/// macOS 10.14.4 / iOS 12.2), and `_objc_lookup_class` on GNUStep.
+///
+/// Returns `false` if the runtime does not support the hook (i.e. when
+/// running on older Apple OS versions), in which case the hook is dropped.
+///
+/// [`ClassBuilder`]: crate::runtime::ClassBuilder
+///
+///
+/// # Panics
+///
+/// The process is aborted if the hook panics, since the runtime cannot
+/// handle unwinding out of hooks.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use objc2::runtime::{register_class_lookup_hook, AnyClass, ClassBuilder, NSObject};
+/// use objc2::ClassType;
+///
+/// let installed = register_class_lookup_hook(|name| {
+///     if name != c"MyLazyClass" {
+///         return None;
+///     }
+///     let builder = ClassBuilder::new(name, NSObject::class())?;
+///     Some(builder.register())
+/// });
+/// assert!(installed, "requires macOS 10.14.4 or newer");
+///
+/// let cls = AnyClass::get(c"MyLazyClass").unwrap();
+/// assert_eq!(cls.name(), c"MyLazyClass");
+/// ```
+#[cfg(any(
+    doc,
+    feature = "gnustep-1-7",
+    all(
+        target_vendor = "apple",
+        not(all(target_os = "macos", target_arch = "x86"))
+    )
+))]
+#[doc(alias = "objc_setHook_getClass")]
+#[doc(alias = "_objc_lookup_class")]
+pub fn register_class_lookup_hook(
+    hook: impl Fn(&core::ffi::CStr) -> Option<&'static super::AnyClass> + Send + Sync + 'static,
+) -> bool {
+    get_class::register(Box::new(hook))
+}
+
+/// Register a hook that provides the name of Swift classes whose names are
+/// generated lazily.
+///
+/// If the hook returns [`None`], the next hook is tried, and finally the
+/// hook that was installed before the first call to this function (if any).
+///
+/// Hooks are tried in reverse registration order, and cannot be
+/// unregistered.
+///
+/// This uses `objc_setHook_lazyClassNamer`, which is available since
+/// macOS 11 / iOS 14.
+///
+/// Returns `false` if the runtime does not support the hook (i.e. when
+/// running on older OS versions), in which case the hook is dropped.
+///
+///
+/// # Panics
+///
+/// The process is aborted if the hook panics, since the runtime cannot
+/// handle unwinding out of hooks.
+#[cfg(any(
+    doc,
+    all(
+        target_vendor = "apple",
+        not(all(target_os = "macos", target_arch = "x86"))
+    )
+))]
+#[doc(alias = "objc_setHook_lazyClassNamer")]
+pub fn register_lazy_class_namer(
+    hook: impl Fn(&super::AnyClass) -> Option<&'static core::ffi::CStr> + Send + Sync + 'static,
+) -> bool {
+    lazy_class_namer::register(Box::new(hook))
+}
+
+/// Register a hook that provides the implementation to use when an object
+/// does not respond to a selector.
+///
+/// This allows implementing custom message forwarding without going through
+/// `-forwardInvocation:`. If the hook returns [`None`], the next hook is
+/// tried, and finally the forwarding handler that was installed before the
+/// first call to this function.
+///
+/// Hooks are tried in reverse registration order, and cannot be
+/// unregistered.
+///
+/// This uses `__objc_msg_forward2`, and is only available on GNUStep; Apple's
+/// runtime instead forwards through `objc_setForwardHandler`, which does not
+/// support retrieving the previous handler.
+///
+///
+/// # Panics
+///
+/// The process is aborted if the hook panics, since the runtime cannot
+/// handle unwinding out of hooks.
+///
+///
+/// # Safety
+///
+/// The returned implementation must have a signature compatible with the
+/// message that was sent, which can usually be determined from the selector
+/// (and e.g. the method signature returned by the receiver's
+/// `-methodSignatureForSelector:`).
+#[cfg(any(doc, feature = "gnustep-1-7"))]
+#[doc(alias = "__objc_msg_forward2")]
+pub unsafe fn register_forwarding_hook(
+    hook: impl Fn(&super::AnyObject, super::Sel) -> Option<super::Imp> + Send + Sync + 'static,
+) {
+    forward::register(Box::new(hook));
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(
+        feature = "gnustep-1-7",
+        all(
+            target_vendor = "apple",
+            not(all(target_os = "macos", target_arch = "x86"))
+        )
+    ))]
+    #[test]
+    fn test_class_lookup_hook() {
+        use alloc::ffi::CString;
+
+        use super::register_class_lookup_hook;
+        use crate::runtime::{AnyClass, ClassBuilder, NSObject};
+        use crate::ClassType;
+
+        let name = CString::new("TestClassLookupHook").unwrap();
+        let other = CString::new("TestClassLookupHookOther").unwrap();
+        assert!(AnyClass::get(&name).is_none());
+
+        let installed = register_class_lookup_hook(|name| {
+            if name.to_bytes() != b"TestClassLookupHook" {
+                return None;
+            }
+            Some(ClassBuilder::new(name, NSObject::class())?.register())
+        });
+        assert!(installed);
+        // Registered later, so tried first; must fall through to the above.
+        assert!(register_class_lookup_hook(|_| None));
+
+        let cls = AnyClass::get(&name).unwrap();
+        assert_eq!(cls.name(), &*name);
+        assert_eq!(AnyClass::get(&name), Some(cls));
+        assert!(AnyClass::get(&other).is_none());
+    }
+}
@@ -1,7 +1,7 @@
 use core::ptr::NonNull;
 
 use crate::encode::{EncodeArguments, EncodeReturn, RefEncode};
-use crate::runtime::{AnyClass, AnyObject, Sel};
+use crate::runtime::{AnyClass, AnyObject, Imp, Sel};
 use crate::Message;
 
 /// Wrap the given closure in `exception::catch` if the `catch-all` feature is
@@ -316,7 +316,9 @@ fn msg_send_check_class(
     args: &[crate::encode::Encoding],
     ret: &crate::encode::Encoding,
 ) {
-    use crate::verify::{verify_method_signature, Inner, VerificationError};
+    use crate::verify::{
+        call_verification_hook, verify_method_signature, Inner, VerificationError,
+    };
 
     let err = if let Some(method) = cls.instance_method(sel) {
         if let Err(err) = verify_method_signature(method, args, ret) {
@@ -327,7 +329,9 @@ fn msg_send_check_class(
     } else {
         VerificationError::from(Inner::MethodNotFound)
     };
+    let err = err.with_context(cls, sel);
 
+    call_verification_hook(&err);
     panic_verify(cls, sel, &err);
 }
 
@@ -346,6 +350,63 @@ fn panic_verify(cls: &AnyClass, sel: Sel, err: &crate::runtime::VerificationErro
     )
 }
 
+/// Call the implementation of a method directly, instead of going through
+/// `objc_msgSend`.
+///
+/// Used by `#[cached_imp]` methods in `extern_methods!`.
+///
+///
+/// # Safety
+///
+/// The implementation must be the one that a message with the given
+/// selector to the receiver would be dispatched to, and the arguments and
+/// return type must match it.
+#[inline]
+#[track_caller]
+pub(crate) unsafe fn send_message_imp<A: EncodeArguments, R: EncodeReturn>(
+    imp: Imp,
+    receiver: *mut AnyObject,
+    sel: Sel,
+    args: A,
+) -> R {
+    // SAFETY: Upheld by caller.
+    conditional_try!(|| unsafe { A::__invoke(imp, receiver, sel, args) })
+}
+
+/// Abort if not on the main thread.
+///
+/// `dealloc` is allowed, since the last reference to an object may be
+/// released by Objective-C code on any thread.
+#[cfg(feature = "thread-checker")]
+#[inline]
+#[track_caller]
+pub(crate) fn check_main_thread(receiver: *mut AnyObject, sel: Sel) {
+    if crate::MainThreadMarker::new().is_none() && sel != crate::sel!(dealloc) {
+        main_thread_violation(receiver, sel);
+    }
+}
+
+#[cfg(feature = "thread-checker")]
+#[cold]
+#[track_caller]
+fn main_thread_violation(receiver: *mut AnyObject, sel: Sel) -> ! {
+    use std::eprintln;
+
+    // SAFETY: The receiver is valid or NULL.
+    let cls = match unsafe { receiver.as_ref() } {
+        Some(obj) => obj.class().name().to_string_lossy(),
+        None => "nil".into(),
+    };
+    let thread = std::thread::current();
+    eprintln!(
+        "main thread checker: -[{cls} {sel}] called on thread {:?} at {}, but {cls} may only be used from the main thread",
+        thread.name().unwrap_or("<unnamed>"),
+        core::panic::Location::caller(),
+    );
+    eprintln!("{}", std::backtrace::Backtrace::force_capture());
+    std::process::abort()
+}
+
 mod private {
     pub trait Sealed {}
 }
@@ -366,6 +427,9 @@ pub unsafe trait MessageReceiver: private::Sealed + Sized {
     #[doc(hidden)]
     fn __as_raw_receiver(self) -> *mut AnyObject;
 
+    #[doc(hidden)]
+    const __MAIN_THREAD_ONLY: bool = false;
+
     /// Sends a message to the receiver with the given selector and arguments.
     ///
     /// This should be used instead of the [`performSelector:`] family of
@@ -422,6 +486,10 @@ pub unsafe trait MessageReceiver: private::Sealed + Sized {
             let obj = unsafe { receiver.as_ref() };
             msg_send_check(obj, sel, A::ENCODINGS, &R::ENCODING_RETURN);
         }
+        #[cfg(feature = "thread-checker")]
+        if Self::__MAIN_THREAD_ONLY {
+            check_main_thread(receiver, sel);
+        }
 
         // SAFETY: Upheld by caller
         conditional_try!(|| unsafe { msg_send_primitive::send(receiver, sel, args) })
@@ -465,6 +533,10 @@ pub unsafe trait MessageReceiver: private::Sealed + Sized {
             }
             msg_send_check_class(superclass, sel, A::ENCODINGS, &R::ENCODING_RETURN);
         }
+        #[cfg(feature = "thread-checker")]
+        if Self::__MAIN_THREAD_ONLY {
+            check_main_thread(receiver, sel);
+        }
 
         // SAFETY: Upheld by caller
         conditional_try!(|| unsafe {
@@ -479,6 +551,7 @@ pub unsafe trait MessageReceiver: private::Sealed + Sized {
 impl<T: ?Sized + Message> private::Sealed for *const T {}
 unsafe impl<T: ?Sized + Message> MessageReceiver for *const T {
     type __Inner = T;
+    const __MAIN_THREAD_ONLY: bool = T::__MAIN_THREAD_ONLY;
 
     #[inline]
     fn __as_raw_receiver(self) -> *mut AnyObject {
@@ -489,6 +562,7 @@ unsafe impl<T: ?Sized + Message> MessageReceiver for *const T {
 impl<T: ?Sized + Message> private::Sealed for *mut T {}
 unsafe impl<T: ?Sized + Message> MessageReceiver for *mut T {
     type __Inner = T;
+    const __MAIN_THREAD_ONLY: bool = T::__MAIN_THREAD_ONLY;
 
     #[inline]
     fn __as_raw_receiver(self) -> *mut AnyObject {
@@ -499,6 +573,7 @@ unsafe impl<T: ?Sized + Message> MessageReceiver for *mut T {
 impl<T: ?Sized + Message> private::Sealed for NonNull<T> {}
 unsafe impl<T: ?Sized + Message> MessageReceiver for NonNull<T> {
     type __Inner = T;
+    const __MAIN_THREAD_ONLY: bool = T::__MAIN_THREAD_ONLY;
 
     #[inline]
     fn __as_raw_receiver(self) -> *mut AnyObject {
@@ -509,6 +584,7 @@ unsafe impl<T: ?Sized + Message> MessageReceiver for NonNull<T> {
 impl<T: ?Sized + Message> private::Sealed for &T {}
 unsafe impl<T: ?Sized + Message> MessageReceiver for &T {
     type __Inner = T;
+    const __MAIN_THREAD_ONLY: bool = T::__MAIN_THREAD_ONLY;
 
     #[inline]
     fn __as_raw_receiver(self) -> *mut AnyObject {
@@ -30,6 +30,7 @@ use core::ptr::{self, NonNull};
 pub mod __nsstring;
 mod bool;
 mod define;
+mod hooks;
 mod malloc;
 mod message_receiver;
 mod method_encoding_iter;
@@ -37,6 +38,7 @@ mod method_implementation;
 mod nsobject;
 mod nsproxy;
 mod nszone;
+mod preregister;
 mod protocol_object;
 mod retain_release_fast;
 
@@ -54,12 +56,38 @@ pub use self::nsproxy::NSProxy as __NSProxy;
 
 pub use self::bool::Bool;
 pub use self::define::{ClassBuilder, ProtocolBuilder};
+#[cfg(any(
+    doc,
+    feature = "gnustep-1-7",
+    all(
+        target_vendor = "apple",
+        not(all(target_os = "macos", target_arch = "x86"))
+    )
+))]
+pub use self::hooks::register_class_lookup_hook;
+#[cfg(any(doc, feature = "gnustep-1-7"))]
+pub use self::hooks::register_forwarding_hook;
+#[cfg(any(
+    doc,
+    all(
+        target_vendor = "apple",
+        not(all(target_os = "macos", target_arch = "x86"))
+    )
+))]
+pub use self::hooks::register_lazy_class_namer;
+#[cfg(feature = "thread-checker")]
+pub(crate) use self::message_receiver::check_main_thread;
+pub(crate) use self::message_receiver::send_message_imp;
 pub use self::message_receiver::MessageReceiver;
 pub use self::method_implementation::MethodImplementation;
 pub use self::nsobject::{NSObject, NSObjectProtocol};
 pub use self::nszone::NSZone;
+pub use self::preregister::preregister;
 pub use self::protocol_object::{ImplementedBy, ProtocolObject};
-pub use crate::verify::VerificationError;
+pub use crate::verify::{
+    set_verification_hook, take_verification_hook, VerificationError, VerificationErrorKind,
+    VerificationHook,
+};
 
 #[allow(deprecated)]
 pub use crate::ffi::{BOOL, NO, YES};
@@ -612,7 +640,10 @@ impl Method {
     pub unsafe fn set_implementation(&self, imp: Imp) -> Imp {
         // SAFETY: The new impl is not NULL, and the rest is upheld by the
         // caller.
-        unsafe { ffi::method_setImplementation(self.as_mut_ptr(), imp).expect("null IMP") }
+        let imp =
+            unsafe { ffi::method_setImplementation(self.as_mut_ptr(), imp).expect("null IMP") };
+        flush_imp_caches();
+        imp
     }
 
     /// Exchange the implementation of two methods.
@@ -656,10 +687,32 @@ impl Method {
         // match when debug assertions are enabled?
 
         // SAFETY: Verified by caller
-        unsafe { ffi::method_exchangeImplementations(self.as_mut_ptr(), other.as_mut_ptr()) }
+        unsafe { ffi::method_exchangeImplementations(self.as_mut_ptr(), other.as_mut_ptr()) };
+        flush_imp_caches();
     }
 }
 
+/// Invalidate the method implementations cached by `#[cached_imp]` methods
+/// in [`extern_methods!`].
+///
+/// This is done automatically by [`Method::set_implementation`] and
+/// [`Method::exchange_implementation`], but must be called manually after
+/// changing which method a message is dispatched to by other means, e.g.
+/// after swizzling with the functions in [`ffi`], after adding a method to a
+/// class with `class_addMethod`, or after loading a bundle with categories
+/// that override existing methods.
+///
+/// Calls that are in progress on other threads while the caches are flushed
+/// may still use the previous implementation.
+///
+/// [`extern_methods!`]: crate::extern_methods
+/// [`ffi`]: crate::ffi
+#[inline]
+pub fn flush_imp_caches() {
+    crate::__macro_helpers::IMP_CACHE_GENERATION
+        .fetch_add(1, core::sync::atomic::Ordering::Release);
+}
+
 standard_pointer_impls!(Method);
 
 impl fmt::Debug for Method {
@@ -973,8 +1026,11 @@ impl AnyClass {
         A: EncodeArguments,
         R: EncodeReturn,
     {
-        let method = self.instance_method(sel).ok_or(Inner::MethodNotFound)?;
-        verify_method_signature(method, A::ENCODINGS, &R::ENCODING_RETURN)
+        let res = match self.instance_method(sel) {
+            Some(method) => verify_method_signature(method, A::ENCODINGS, &R::ENCODING_RETURN),
+            None => Err(Inner::MethodNotFound.into()),
+        };
+        res.map_err(|err| err.with_context(self, sel))
     }
 }
 
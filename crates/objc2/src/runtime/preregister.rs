@@ -0,0 +1,124 @@
+//! Eager registration of selectors and classes.
+use alloc::vec::Vec;
+use core::ffi::CStr;
+
+use crate::ffi;
+use crate::runtime::Sel;
+
+/// Register a list of selectors and look up a list of classes up front.
+///
+/// The [`sel!`] and [`class!`] macros register their selector or look up
+/// their class lazily the first time each call site is executed. On most
+/// runtimes this is cheap, but the first registration of a selector takes a
+/// global lock in the runtime (and on GNUstep, looking up a class for the
+/// first time may also have to resolve it), which can show up as latency
+/// spikes when many call sites run for the first time at once, e.g. when a
+/// plugin is loaded, or when the first window is shown.
+///
+/// Calling this function during startup moves that work to a point of your
+/// choosing; each call site still caches the result on first use, but that
+/// then only does an uncontended lookup.
+///
+/// Returns the names of the classes that could not be found.
+///
+/// [`sel!`]: crate::sel
+/// [`class!`]: crate::class
+///
+///
+/// # Panics
+///
+/// Panics if any of the names contain a NUL byte.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::runtime::preregister;
+///
+/// let missing = preregister(
+///     &["init", "alloc", "description", "isEqual:"],
+///     &["NSObject", "NSDoesNotExist"],
+/// );
+/// assert_eq!(missing, ["NSDoesNotExist"]);
+/// ```
+///
+/// The lists can also be generated by a build script, e.g. by scanning your
+/// sources for the selectors that you use, and writing them to a file in
+/// `OUT_DIR`:
+///
+/// ```ignore
+/// // In `build.rs`
+/// let selectors: Vec<String> = find_used_selectors();
+/// let out_dir = std::env::var("OUT_DIR").unwrap();
+/// std::fs::write(
+///     format!("{out_dir}/selectors.rs"),
+///     format!("pub static SELECTORS: &[&str] = &{selectors:?};"),
+/// ).unwrap();
+///
+/// // In `main.rs`
+/// include!(concat!(env!("OUT_DIR"), "/selectors.rs"));
+///
+/// fn main() {
+///     objc2::runtime::preregister(SELECTORS, &[]);
+///     // ...
+/// }
+/// ```
+#[doc(alias = "sel_registerName")]
+#[doc(alias = "objc_getClass")]
+pub fn preregister<'a>(selectors: &[&str], classes: &[&'a str]) -> Vec<&'a str> {
+    // Reuse a single buffer for the NUL-terminated names.
+    let mut buf = Vec::new();
+
+    for name in selectors {
+        let _ = Sel::register(c_name(&mut buf, name));
+    }
+
+    let mut missing = Vec::new();
+    for name in classes {
+        // Use `objc_getClass` like `class!` does, so that class lookup hooks
+        // get a chance to run.
+        let cls = unsafe { ffi::objc_getClass(c_name(&mut buf, name).as_ptr()) };
+        if cls.is_null() {
+            missing.push(*name);
+        }
+    }
+    missing
+}
+
+fn c_name<'b>(buf: &'b mut Vec<u8>, name: &str) -> &'b CStr {
+    buf.clear();
+    buf.extend_from_slice(name.as_bytes());
+    buf.push(0);
+    CStr::from_bytes_with_nul(buf)
+        .unwrap_or_else(|_| panic!("name {name:?} must not contain NUL bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{class, sel};
+
+    #[test]
+    fn registers() {
+        let missing = preregister(
+            &["someSelectorThatIsNotUsedElsewhere:", "init"],
+            &["NSObject", "ThisClassDoesNotExist"],
+        );
+        assert_eq!(missing, ["ThisClassDoesNotExist"]);
+        assert_eq!(
+            Sel::register(c_str("someSelectorThatIsNotUsedElsewhere:\0")),
+            sel!(someSelectorThatIsNotUsedElsewhere:),
+        );
+        let _ = class!(NSObject);
+    }
+
+    #[test]
+    #[should_panic = "must not contain NUL bytes"]
+    fn interior_nul() {
+        preregister(&["a\0b"], &[]);
+    }
+
+    fn c_str(s: &str) -> &CStr {
+        CStr::from_bytes_with_nul(s.as_bytes()).unwrap()
+    }
+}
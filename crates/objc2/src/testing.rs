@@ -0,0 +1,370 @@
+//! # Support for testing code that uses defined classes.
+//!
+//! Leaking an object (for example because of a reference cycle, or because
+//! it was retained by a framework and never released) usually goes
+//! unnoticed, since nothing crashes. This module helps with catching such
+//! lifetime bugs in tests, by tracking the instances of classes created with
+//! [`define_class!`] and checking that they have all been deallocated once
+//! the test finishes.
+//!
+//! Only instances whose instance variables were initialized with
+//! [`Allocated::set_ivars`] are tracked, i.e. the instances created from
+//! Rust. Instances are tracked by the thread that created them, but may be
+//! deallocated on any thread.
+//!
+//! This requires the `"unstable-testing"` feature, which makes every defined
+//! class implement `dealloc`, and as such has a small runtime cost. It is
+//! intended to be enabled in `dev-dependencies` only.
+//!
+//! [`define_class!`]: crate::define_class
+//! [`Allocated::set_ivars`]: crate::rc::Allocated::set_ivars
+//!
+//!
+//! # Example
+//!
+//! ```
+//! use objc2::rc::Retained;
+//! use objc2::runtime::NSObject;
+//! use objc2::{define_class, msg_send_id, AllocAnyThread};
+//!
+//! define_class!(
+//!     #[unsafe(super(NSObject))]
+//!     #[name = "TestingExampleObject"]
+//!     struct MyObject;
+//! );
+//!
+//! impl MyObject {
+//!     fn new() -> Retained<Self> {
+//!         let this = Self::alloc().set_ivars(());
+//!         unsafe { msg_send_id![super(this), init] }
+//!     }
+//! }
+//!
+//! objc2::testing::with_leak_check(|| {
+//!     let obj = MyObject::new();
+//!     // Autoreleased objects are released before checking for leaks.
+//!     let _ = Retained::autorelease_ptr(obj);
+//! });
+//! ```
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt;
+use std::sync::{Mutex, PoisonError};
+use std::thread_local;
+
+use crate::rc::autoreleasepool;
+
+/// The instances tracked by a single [`LeakTracker`].
+#[derive(Default)]
+struct Scope {
+    /// Map from the address of each live instance to its class name.
+    live: Mutex<BTreeMap<usize, &'static str>>,
+}
+
+/// Map from the address of each tracked instance to the scope tracking it.
+static OWNERS: Mutex<BTreeMap<usize, Arc<Scope>>> = Mutex::new(BTreeMap::new());
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<Scope>>> = const { RefCell::new(None) };
+    static TEARDOWN: RefCell<Vec<Box<dyn FnOnce()>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Called when the instance variables of an instance are initialized.
+pub(crate) fn track(ptr: *const (), name: &'static str) {
+    let Some(scope) = CURRENT.with(|current| current.borrow().clone()) else {
+        return;
+    };
+    let addr = ptr as usize;
+    scope
+        .live
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(addr, name);
+    OWNERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(addr, scope);
+}
+
+/// Called when an instance is deallocated.
+pub(crate) fn untrack(ptr: *const ()) {
+    let addr = ptr as usize;
+    let scope = OWNERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&addr);
+    if let Some(scope) = scope {
+        scope
+            .live
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&addr);
+    }
+}
+
+/// Register a closure to be run when the innermost [`LeakTracker`] on the
+/// current thread finishes, before checking for leaks.
+///
+/// This is useful for tearing down state that keeps objects alive, such as
+/// removing observers from notification centers. The closures are run in
+/// reverse order of registration.
+///
+/// If no tracker is active, the closure is dropped without being run.
+pub fn on_teardown(f: impl FnOnce() + 'static) {
+    if CURRENT.with(|current| current.borrow().is_none()) {
+        return;
+    }
+    TEARDOWN.with(|teardown| teardown.borrow_mut().push(Box::new(f)));
+}
+
+fn split_off_at<T>(vec: &mut Vec<T>, at: usize) -> Vec<T> {
+    if vec.len() > at {
+        vec.split_off(at)
+    } else {
+        Vec::new()
+    }
+}
+
+fn run_teardown(from: usize) {
+    loop {
+        let f = TEARDOWN.with(|teardown| {
+            let mut teardown = teardown.borrow_mut();
+            if teardown.len() > from {
+                teardown.pop()
+            } else {
+                None
+            }
+        });
+        match f {
+            Some(f) => f(),
+            None => break,
+        }
+    }
+}
+
+/// Tracks the instances of defined classes created on the current thread
+/// while it is alive.
+///
+/// Trackers can be nested, in which case instances are only tracked by the
+/// innermost tracker.
+///
+/// See also [`with_leak_check`], which wraps the tracked code in an
+/// autorelease pool.
+pub struct LeakTracker {
+    scope: Arc<Scope>,
+    previous: Option<Arc<Scope>>,
+    teardown_len: usize,
+}
+
+impl LeakTracker {
+    /// Start tracking instances created on the current thread.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        let scope = Arc::new(Scope::default());
+        let previous = CURRENT.with(|current| current.replace(Some(scope.clone())));
+        let teardown_len = TEARDOWN.with(|teardown| teardown.borrow().len());
+        Self {
+            scope,
+            previous,
+            teardown_len,
+        }
+    }
+
+    /// The number of tracked instances that are still alive.
+    pub fn live_count(&self) -> usize {
+        self.scope
+            .live
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// The class names of the tracked instances that are still alive, along
+    /// with how many instances of each class are alive.
+    pub fn live_instances(&self) -> Vec<(&'static str, usize)> {
+        let mut counts = BTreeMap::<&'static str, usize>::new();
+        for name in self
+            .scope
+            .live
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+        {
+            *counts.entry(name).or_default() += 1;
+        }
+        counts.into_iter().collect()
+    }
+
+    /// Stop tracking, run the closures registered with [`on_teardown`], and
+    /// assert that all tracked instances have been deallocated.
+    ///
+    /// Note that instances that are still in an autorelease pool are not
+    /// deallocated yet, so this should be called outside any pools created
+    /// while tracking.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the tracked instances are still alive.
+    #[track_caller]
+    pub fn assert_all_deallocated(self) {
+        run_teardown(self.teardown_len);
+        let live = self.live_instances();
+        drop(self);
+        if !live.is_empty() {
+            panic!("{}", LeakReport(live));
+        }
+    }
+}
+
+impl Drop for LeakTracker {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = self.previous.take());
+        // Discard teardown closures that were not run (e.g. because of a
+        // panic). They are dropped outside the borrow, since dropping them
+        // may deallocate objects.
+        let teardown =
+            TEARDOWN.with(|teardown| split_off_at(&mut teardown.borrow_mut(), self.teardown_len));
+        drop(teardown);
+        // Stop associating the remaining instances with this scope.
+        let live = core::mem::take(
+            &mut *self
+                .scope
+                .live
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        let mut owners = OWNERS.lock().unwrap_or_else(PoisonError::into_inner);
+        for addr in live.keys() {
+            owners.remove(addr);
+        }
+    }
+}
+
+impl fmt::Debug for LeakTracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LeakTracker")
+            .field("live_instances", &self.live_instances())
+            .finish_non_exhaustive()
+    }
+}
+
+struct LeakReport(Vec<(&'static str, usize)>);
+
+impl fmt::Display for LeakReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total: usize = self.0.iter().map(|(_, count)| count).sum();
+        let classes: Vec<String> = self
+            .0
+            .iter()
+            .map(|(name, count)| format!("{name} ({count})"))
+            .collect();
+        write!(
+            f,
+            "{total} instance(s) of defined classes were not deallocated: {}",
+            classes.join(", "),
+        )
+    }
+}
+
+/// Run the closure in a fresh autorelease pool, and assert that all the
+/// instances of defined classes that it created have been deallocated once
+/// the pool has been drained.
+///
+/// Closures registered with [`on_teardown`] while running the closure are
+/// run after the pool has been drained, but before checking for leaks.
+///
+/// See [the module documentation](self) for details.
+///
+///
+/// # Panics
+///
+/// Panics if any of the created instances are still alive.
+#[track_caller]
+pub fn with_leak_check<R>(f: impl FnOnce() -> R) -> R {
+    let tracker = LeakTracker::new();
+    let ret = autoreleasepool(|_| f());
+    tracker.assert_all_deallocated();
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::rc::Retained;
+    use crate::runtime::NSObject;
+    use crate::{define_class, msg_send_id, AllocAnyThread};
+
+    define_class!(
+        #[unsafe(super(NSObject))]
+        #[name = "__RcTestLeakTrackerObject"]
+        struct Tracked;
+    );
+
+    impl Tracked {
+        fn new() -> Retained<Self> {
+            let this = Self::alloc().set_ivars(());
+            unsafe { msg_send_id![super(this), init] }
+        }
+    }
+
+    #[test]
+    fn test_no_leaks() {
+        with_leak_check(|| {
+            let obj = Tracked::new();
+            let _ = Retained::autorelease_ptr(obj.clone());
+            drop(obj);
+        });
+    }
+
+    #[test]
+    fn test_leak_detected() {
+        let tracker = LeakTracker::new();
+        let obj = Tracked::new();
+        let leaked = Tracked::new();
+        assert_eq!(tracker.live_count(), 2);
+        drop(obj);
+        assert_eq!(tracker.live_instances(), [("__RcTestLeakTrackerObject", 1)]);
+
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            tracker.assert_all_deallocated();
+        }));
+        let msg = *res.unwrap_err().downcast::<String>().unwrap();
+        assert_eq!(
+            msg,
+            "1 instance(s) of defined classes were not deallocated: __RcTestLeakTrackerObject (1)"
+        );
+
+        // Not tracked anymore.
+        drop(leaked);
+    }
+
+    #[test]
+    fn test_nested_and_teardown() {
+        let outer = LeakTracker::new();
+        let kept = Tracked::new();
+
+        let ran = Rc::new(Cell::new(false));
+        with_leak_check(|| {
+            let obj = Tracked::new();
+            let ran = ran.clone();
+            on_teardown(move || {
+                drop(obj);
+                ran.set(true);
+            });
+        });
+        assert!(ran.get());
+
+        assert_eq!(outer.live_count(), 1);
+        drop(kept);
+        outer.assert_all_deallocated();
+    }
+}
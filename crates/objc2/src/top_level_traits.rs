@@ -117,6 +117,12 @@ pub unsafe trait Message: RefEncode {
         // and objc_retain always returns the same value.
         unsafe { obj.unwrap_unchecked() }
     }
+
+    /// Whether instances may only be used from the main thread.
+    ///
+    /// Used by the `"thread-checker"` feature.
+    #[doc(hidden)]
+    const __MAIN_THREAD_ONLY: bool = false;
 }
 
 /// Marks types that represent specific classes.
@@ -254,6 +260,21 @@ pub unsafe trait ClassType: Message {
     /// that defines the class.
     fn class() -> &'static AnyClass;
 
+    /// Whether the class is available at runtime.
+    ///
+    /// This is useful when targeting older OS versions, where a class may
+    /// not yet exist, since [`ClassType::class`] would then panic. The result
+    /// is cached when the class is found.
+    ///
+    /// Classes defined in Rust are always available. See also the
+    /// [`class_available!`] macro.
+    ///
+    /// [`class_available!`]: crate::class_available
+    #[inline]
+    fn is_available() -> bool {
+        true
+    }
+
     /// Get an immutable reference to the superclass.
     // Note: It'd be safe to provide a default impl using transmute here if
     // we wanted to!
@@ -574,16 +595,21 @@ pub trait ThreadKind: private::SealedThreadKind {
     // To mark `ThreadKind` as dyn-incompatible for now.
     #[doc(hidden)]
     const __DYN_INCOMPATIBLE: ();
+
+    #[doc(hidden)]
+    const __MAIN_THREAD_ONLY: bool;
 }
 
 impl private::SealedThreadKind for dyn AllocAnyThread + '_ {}
 impl ThreadKind for dyn AllocAnyThread + '_ {
     const __DYN_INCOMPATIBLE: () = ();
+    const __MAIN_THREAD_ONLY: bool = false;
 }
 
 impl private::SealedThreadKind for dyn MainThreadOnly + '_ {}
 impl ThreadKind for dyn MainThreadOnly + '_ {
     const __DYN_INCOMPATIBLE: () = ();
+    const __MAIN_THREAD_ONLY: bool = true;
 }
 
 #[cfg(test)]
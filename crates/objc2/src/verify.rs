@@ -1,9 +1,16 @@
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ffi::CStr;
 use core::fmt;
 use core::hash::Hash;
 use std::error::Error;
+use std::sync::RwLock;
 
 use crate::encode::{Encoding, EncodingBox};
-use crate::runtime::{EncodingParseError, Method};
+use crate::runtime::{AnyClass, EncodingParseError, Method, Sel};
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub(crate) enum Inner {
@@ -41,36 +48,193 @@ impl fmt::Display for Inner {
 /// Failed verifying selector on a class.
 ///
 /// This is returned in the error case of [`AnyClass::verify_sel`], see that
-/// for details.
+/// for details, and is passed to the hook registered with
+/// [`set_verification_hook`] when a message send fails verification.
 ///
 /// This implements [`Error`], and a description of the error can be retrieved
-/// using [`fmt::Display`].
+/// using [`fmt::Display`]. The details of the error can be inspected with
+/// [`kind`](Self::kind), [`sel`](Self::sel) and
+/// [`class_chain`](Self::class_chain).
 ///
 /// [`AnyClass::verify_sel`]: crate::runtime::AnyClass::verify_sel
 #[derive(Debug, PartialEq, Eq, Hash)]
-pub struct VerificationError(Inner);
+pub struct VerificationError(Box<Details>);
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct Details {
+    inner: Inner,
+    sel: Option<Sel>,
+    class_chain: Vec<CString>,
+}
+
+impl VerificationError {
+    /// Attach the class and selector that were being verified.
+    pub(crate) fn with_context(mut self, cls: &AnyClass, sel: Sel) -> Self {
+        self.0.sel = Some(sel);
+        self.0.class_chain.clear();
+        let mut cls = Some(cls);
+        while let Some(current) = cls {
+            self.0.class_chain.push(current.name().to_owned());
+            cls = current.superclass();
+        }
+        self
+    }
+
+    /// The kind of verification failure, along with the mismatched
+    /// encodings, if any.
+    pub fn kind(&self) -> VerificationErrorKind<'_> {
+        match &self.0.inner {
+            Inner::MethodNotFound => VerificationErrorKind::MethodNotFound,
+            Inner::EncodingParseError(_) => VerificationErrorKind::EncodingParseError,
+            Inner::MismatchedReturn(expected, found) => {
+                VerificationErrorKind::MismatchedReturn { expected, found }
+            }
+            Inner::MismatchedArgumentsCount(expected, found) => {
+                VerificationErrorKind::MismatchedArgumentsCount {
+                    expected: *expected,
+                    found: *found,
+                }
+            }
+            Inner::MismatchedArgument(index, expected, found) => {
+                VerificationErrorKind::MismatchedArgument {
+                    index: *index,
+                    expected,
+                    found,
+                }
+            }
+        }
+    }
+
+    /// The selector that failed verification, if known.
+    pub fn sel(&self) -> Option<Sel> {
+        self.0.sel
+    }
+
+    /// The names of the class that the selector was verified against,
+    /// followed by the names of its superclasses.
+    ///
+    /// For class methods, these are the names of the metaclasses.
+    ///
+    /// This is empty if the class is not known.
+    pub fn class_chain(&self) -> impl ExactSizeIterator<Item = &CStr> + '_ {
+        self.0.class_chain.iter().map(|name| &**name)
+    }
+}
 
 impl From<EncodingParseError> for VerificationError {
     fn from(e: EncodingParseError) -> Self {
-        Self(Inner::EncodingParseError(e))
+        Self::from(Inner::EncodingParseError(e))
     }
 }
 
 impl From<Inner> for VerificationError {
     fn from(inner: Inner) -> Self {
-        Self(inner)
+        Self(Box::new(Details {
+            inner,
+            sel: None,
+            class_chain: Vec::new(),
+        }))
     }
 }
 
 impl fmt::Display for VerificationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Delegate to inner
-        fmt::Display::fmt(&self.0, f)
+        fmt::Display::fmt(&self.0.inner, f)
     }
 }
 
 impl Error for VerificationError {}
 
+/// The different ways that verifying a selector can fail.
+///
+/// See [`VerificationError::kind`].
+///
+/// The "expected" encodings are the ones declared by the method, while the
+/// "found" encodings are the ones from the Rust types that were used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum VerificationErrorKind<'a> {
+    /// The class does not have a method with the given selector.
+    MethodNotFound,
+    /// The method's type encoding could not be parsed.
+    EncodingParseError,
+    /// The return type did not match.
+    MismatchedReturn {
+        /// The return type declared by the method.
+        expected: &'a EncodingBox,
+        /// The return type that was used.
+        found: &'a Encoding,
+    },
+    /// The number of arguments did not match.
+    MismatchedArgumentsCount {
+        /// The number of arguments that the method takes.
+        expected: usize,
+        /// The number of arguments that were given.
+        found: usize,
+    },
+    /// The type of an argument did not match.
+    MismatchedArgument {
+        /// The index of the mismatched argument.
+        index: usize,
+        /// The argument type declared by the method.
+        expected: &'a EncodingBox,
+        /// The argument type that was used.
+        found: &'a Encoding,
+    },
+}
+
+/// A hook registered with [`set_verification_hook`].
+pub type VerificationHook = Arc<dyn Fn(&VerificationError) + Send + Sync + 'static>;
+
+static HOOK: RwLock<Option<VerificationHook>> = RwLock::new(None);
+
+/// Register a hook that is called when a message send fails verification.
+///
+/// When `debug_assertions` are enabled, `msg_send!` and friends verify that
+/// the types used match the type encoding of the method before sending the
+/// message, and panic if they don't. The hook is called with the details of
+/// the failure just before that panic happens, which allows e.g. test
+/// harnesses to collect and assert on the failures programmatically.
+///
+/// The message is never sent after a failed verification, since that may be
+/// unsound, so the panic still happens afterwards.
+///
+/// This replaces any previously registered hook.
+///
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use objc2::runtime::set_verification_hook;
+///
+/// set_verification_hook(Arc::new(|err| {
+///     eprintln!("verification of {:?} failed: {err}", err.sel());
+/// }));
+/// ```
+pub fn set_verification_hook(hook: VerificationHook) {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(hook);
+}
+
+/// Unregister the hook registered with [`set_verification_hook`], and
+/// return it.
+pub fn take_verification_hook() -> Option<VerificationHook> {
+    HOOK.write().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Call the registered verification hook, if any.
+#[cfg(debug_assertions)]
+pub(crate) fn call_verification_hook(err: &VerificationError) {
+    // Clone the hook out of the lock, such that the hook itself may set or
+    // take the hook without deadlocking.
+    let hook = HOOK.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(hook) = hook {
+        hook(err);
+    }
+}
+
 /// Relaxed version of `Encoding::equivalent_to_box` that allows
 /// `*mut c_void` and `*const c_void` to be used in place of other pointers,
 /// and allows signed types where unsigned types are excepted.
@@ -160,7 +324,7 @@ mod tests {
     use crate::runtime::Sel;
     use crate::test_utils;
     use crate::{msg_send, sel};
-    use alloc::string::ToString;
+    use alloc::string::{String, ToString};
     use core::ffi::c_void;
     use core::panic::{RefUnwindSafe, UnwindSafe};
 
@@ -226,6 +390,64 @@ mod tests {
         assert_eq!(err.to_string(), "expected 2 arguments, but 3 were given");
     }
 
+    #[test]
+    fn test_verify_message_error_details() {
+        let cls = test_utils::custom_class();
+
+        let err = cls.verify_sel::<(), ()>(sel!(setFoo)).unwrap_err();
+        assert_eq!(err.kind(), VerificationErrorKind::MethodNotFound);
+        assert_eq!(err.sel(), Some(sel!(setFoo)));
+        let chain: Vec<_> = err.class_chain().collect();
+        assert_eq!(chain.first().unwrap().to_str().unwrap(), "CustomObject");
+        assert_eq!(chain.last().unwrap().to_str().unwrap(), "NSObject");
+
+        let err = cls.verify_sel::<(Sel,), ()>(sel!(setFoo:)).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            VerificationErrorKind::MismatchedArgument {
+                index: 0,
+                expected: &EncodingBox::UInt,
+                found: &Encoding::Sel,
+            }
+        );
+
+        let err = cls.verify_sel::<(u32, i8), ()>(sel!(setFoo:)).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            VerificationErrorKind::MismatchedArgumentsCount {
+                expected: 1,
+                found: 2
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_verification_hook() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::sync::{Arc, Mutex};
+
+        static FAILURES: Mutex<Vec<(Option<Sel>, String)>> = Mutex::new(Vec::new());
+
+        set_verification_hook(Arc::new(|err| {
+            FAILURES.lock().unwrap().push((err.sel(), err.to_string()));
+            // Hooks may unregister themselves.
+            assert!(take_verification_hook().is_some());
+        }));
+
+        let obj = test_utils::custom_object();
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            let _: u64 = unsafe { msg_send![&obj, getNSInteger] };
+        }));
+        assert!(res.is_err());
+        assert!(take_verification_hook().is_none());
+
+        let failures = FAILURES.lock().unwrap();
+        assert!(failures
+            .iter()
+            .any(|(sel, _)| *sel == Some(sel!(getNSInteger))));
+    }
+
     #[test]
     #[cfg(debug_assertions)]
     #[should_panic = "invalid message send to -[CustomObject foo]: expected return to have type code 'I', but found '^i'"]
@@ -1,12 +1,13 @@
 #![deny(deprecated, unreachable_code)]
 use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicUsize, Ordering};
 use std::cell::UnsafeCell;
 use std::marker::PhantomData;
 use std::panic::{RefUnwindSafe, UnwindSafe};
 
 use objc2::rc::Retained;
 use objc2::runtime::NSObject;
-use objc2::{define_class, extern_methods, sel, ClassType, MainThreadOnly};
+use objc2::{define_class, extern_methods, msg_send_id, sel, ClassType, MainThreadOnly};
 use static_assertions::{assert_impl_all, assert_not_impl_any};
 
 // Test that adding the `deprecated` attribute does not mean that warnings
@@ -589,3 +590,110 @@ fn test_auto_traits() {
     assert_impl_all!(InheritsNSObjectWithNonUnwindSafeIvar: Send, Sync);
     assert_not_impl_any!(InheritsNSObjectWithNonUnwindSafeIvar: Unpin, UnwindSafe, RefUnwindSafe);
 }
+
+static CACHED_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    #[name = "CachedClassProperty"]
+    struct CachedClassProperty;
+
+    unsafe impl CachedClassProperty {
+        #[method_id(shared)]
+        fn __shared() -> Retained<NSObject> {
+            CACHED_CALLS.fetch_add(1, Ordering::Relaxed);
+            NSObject::new()
+        }
+    }
+);
+
+extern_methods!(
+    unsafe impl CachedClassProperty {
+        #[method_id(shared)]
+        #[cached]
+        fn shared() -> Retained<NSObject>;
+    }
+);
+
+#[test]
+fn cached_class_method() {
+    let first = CachedClassProperty::shared();
+    let second = CachedClassProperty::shared();
+    assert!(ptr::eq(&*first, &*second));
+    assert_eq!(CACHED_CALLS.load(Ordering::Relaxed), 1);
+}
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    #[name = "CachedImpClass"]
+    struct CachedImpClass;
+
+    unsafe impl CachedImpClass {
+        #[method(value)]
+        fn __value(&self) -> usize {
+            1
+        }
+
+        #[method(otherValue)]
+        fn __other_value(&self) -> usize {
+            2
+        }
+
+        #[method(add:)]
+        fn __add(&self, x: usize) -> usize {
+            x + 1
+        }
+    }
+);
+
+define_class!(
+    #[unsafe(super(CachedImpClass, NSObject))]
+    #[name = "CachedImpSubclass"]
+    struct CachedImpSubclass;
+
+    unsafe impl CachedImpSubclass {
+        #[method(value)]
+        fn __value(&self) -> usize {
+            3
+        }
+    }
+);
+
+extern_methods!(
+    unsafe impl CachedImpClass {
+        #[method_id(new)]
+        fn new() -> Retained<Self>;
+
+        #[method(value)]
+        #[cached_imp]
+        fn value(&self) -> usize;
+
+        #[method(add:)]
+        #[cached_imp]
+        fn add(&self, x: usize) -> usize;
+    }
+);
+
+#[test]
+fn cached_imp() {
+    let obj = CachedImpClass::new();
+    assert_eq!(obj.value(), 1);
+    assert_eq!(obj.value(), 1);
+    assert_eq!(obj.add(41), 42);
+    assert_eq!(obj.add(1), 2);
+
+    // Subclasses are dispatched normally.
+    let sub: Retained<CachedImpSubclass> = unsafe { msg_send_id![CachedImpSubclass::class(), new] };
+    assert_eq!(sub.value(), 3);
+    assert_eq!(sub.add(1), 2);
+    assert_eq!(obj.value(), 1);
+
+    // Swizzling invalidates the cache.
+    let cls = CachedImpClass::class();
+    let value = cls.instance_method(sel!(value)).unwrap();
+    let other_value = cls.instance_method(sel!(otherValue)).unwrap();
+    unsafe { value.exchange_implementation(other_value) };
+    assert_eq!(obj.value(), 2);
+    unsafe { value.exchange_implementation(other_value) };
+    assert_eq!(obj.value(), 1);
+}
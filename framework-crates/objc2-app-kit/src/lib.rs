@@ -64,16 +64,111 @@ mod application;
 mod generated;
 #[cfg(feature = "NSImage")]
 mod image;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSGraphics",
+    feature = "NSScreen",
+    feature = "objc2-core-foundation"
+))]
+mod screen;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSApplication",
+    feature = "NSPasteboard",
+    feature = "NSResponder"
+))]
+mod services;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSResponder",
+    feature = "NSSplitView",
+    feature = "NSSplitViewController",
+    feature = "NSSplitViewItem",
+    feature = "NSView",
+    feature = "NSViewController",
+    feature = "objc2-core-foundation"
+))]
+mod split_view_controller;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSResponder",
+    feature = "NSTabView",
+    feature = "NSTabViewController",
+    feature = "NSTabViewItem",
+    feature = "NSView",
+    feature = "NSViewController"
+))]
+mod tab_view_controller;
 #[cfg(feature = "NSText")]
 mod text;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSEvent",
+    feature = "NSResponder",
+    feature = "NSTrackingArea",
+    feature = "NSView",
+    feature = "objc2-core-foundation"
+))]
+mod tracking_area;
+#[cfg(all(feature = "NSResponder", feature = "NSWindow"))]
+mod window;
 
 #[cfg(feature = "NSApplication")]
 pub use self::application::*;
 pub use self::generated::*;
 #[cfg(feature = "NSImage")]
 pub use self::image::*;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSGraphics",
+    feature = "NSScreen",
+    feature = "objc2-core-foundation"
+))]
+pub use self::screen::NSScreenInfo;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSApplication",
+    feature = "NSPasteboard",
+    feature = "NSResponder"
+))]
+pub use self::services::{
+    register_services_provider, NSServiceDescription, NSServiceHandlers, NSServicesProviderHandle,
+    NS_SERVICE_MESSAGE,
+};
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSResponder",
+    feature = "NSSplitView",
+    feature = "NSSplitViewController",
+    feature = "NSSplitViewItem",
+    feature = "NSView",
+    feature = "NSViewController",
+    feature = "objc2-core-foundation"
+))]
+pub use self::split_view_controller::{NSSplitViewControllerBuilder, NSSplitViewItemOptions};
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSResponder",
+    feature = "NSTabView",
+    feature = "NSTabViewController",
+    feature = "NSTabViewItem",
+    feature = "NSView",
+    feature = "NSViewController"
+))]
+pub use self::tab_view_controller::{NSTabViewControllerBuilder, NSTabViewControllerTab};
 #[cfg(feature = "NSText")]
 pub use self::text::*;
+#[cfg(all(
+    feature = "alloc",
+    feature = "NSEvent",
+    feature = "NSResponder",
+    feature = "NSTrackingArea",
+    feature = "NSView",
+    feature = "objc2-core-foundation"
+))]
+pub use self::tracking_area::{NSTrackingAreaHandle, NSTrackingAreaHandlers};
+#[cfg(all(feature = "NSResponder", feature = "NSWindow"))]
+pub use self::window::NSWindowLevelKind;
 
 // MacTypes.h
 #[allow(unused)]
@@ -0,0 +1,183 @@
+//! Typed screen information.
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use objc2::runtime::NSObjectProtocol;
+use objc2::{sel, MainThreadMarker};
+use objc2_core_foundation::CGFloat;
+use objc2_foundation::{ns_string, NSEdgeInsets, NSNumber, NSPoint, NSRect, NSSize, NSValue};
+
+use crate::NSScreen;
+
+/// A snapshot of the parameters of a screen.
+///
+/// Unlike [`NSScreen`], this can be sent to other threads, and compared to
+/// detect changes.
+///
+/// See [`NSScreen::info`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct NSScreenInfo {
+    /// The display ID of the screen, see [`NSScreen::display_id`].
+    pub display_id: Option<u32>,
+    /// The localized name of the screen, see [`NSScreen::name`].
+    pub name: Option<String>,
+    /// The frame of the screen in global screen coordinates, in points.
+    pub frame: NSRect,
+    /// The part of the frame that is not covered by the menu bar or Dock.
+    pub visible_frame: NSRect,
+    /// The number of pixels per point.
+    pub backing_scale_factor: CGFloat,
+    /// The insets of the area that is not covered by e.g. the camera
+    /// housing, see [`NSScreen::safe_area_insets`].
+    pub safe_area_insets: NSEdgeInsets,
+}
+
+/// Screen information.
+impl NSScreen {
+    /// The Core Graphics display ID of the screen, usable with
+    /// `CGDirectDisplayID` functions.
+    ///
+    /// This is the `NSScreenNumber` entry of the
+    /// [device description](Self::deviceDescription), which is always
+    /// present in practice.
+    #[doc(alias = "NSScreenNumber")]
+    #[doc(alias = "CGDirectDisplayID")]
+    pub fn display_id(&self) -> Option<u32> {
+        let description = self.deviceDescription();
+        let number = description.objectForKey(ns_string!("NSScreenNumber"))?;
+        let number = number.downcast::<NSNumber>().ok()?;
+        Some(number.as_u32())
+    }
+
+    /// The resolution of the screen in dots per inch.
+    ///
+    /// Note that this is nominally 72 DPI times the
+    /// [backing scale factor](Self::backingScaleFactor), and does not
+    /// reflect the physical size of the screen.
+    #[doc(alias = "NSDeviceResolution")]
+    pub fn resolution(&self) -> Option<NSSize> {
+        let description = self.deviceDescription();
+        let value = description.objectForKey(unsafe { crate::NSDeviceResolution })?;
+        value.downcast::<NSValue>().ok()?.get_size()
+    }
+
+    /// The localized name of the screen, e.g. "Built-in Retina Display".
+    ///
+    /// Returns [`None`] on macOS versions before 10.15.
+    #[doc(alias = "localizedName")]
+    pub fn name(&self) -> Option<String> {
+        if self.respondsToSelector(sel!(localizedName)) {
+            Some(unsafe { self.localizedName() }.to_string())
+        } else {
+            None
+        }
+    }
+
+    /// The distances from the edges of the screen that are not obscured by
+    /// e.g. the camera housing.
+    ///
+    /// This is zero on screens without such obstructions, and on macOS
+    /// versions before 12.0.
+    #[doc(alias = "safeAreaInsets")]
+    pub fn safe_area_insets(&self) -> NSEdgeInsets {
+        if self.respondsToSelector(sel!(safeAreaInsets)) {
+            unsafe { self.safeAreaInsets() }
+        } else {
+            NSEdgeInsets {
+                top: 0.0,
+                left: 0.0,
+                bottom: 0.0,
+                right: 0.0,
+            }
+        }
+    }
+
+    /// The part of the [frame](Self::frame) that is not obscured, see
+    /// [`safe_area_insets`](Self::safe_area_insets).
+    pub fn safe_frame(&self) -> NSRect {
+        let frame = self.frame();
+        let insets = self.safe_area_insets();
+        NSRect::new(
+            NSPoint::new(frame.origin.x + insets.left, frame.origin.y + insets.bottom),
+            NSSize::new(
+                frame.size.width - insets.left - insets.right,
+                frame.size.height - insets.top - insets.bottom,
+            ),
+        )
+    }
+
+    /// The color space of the screen.
+    #[cfg(feature = "NSColorSpace")]
+    #[doc(alias = "colorSpace")]
+    pub fn color_space(&self) -> Option<objc2::rc::Retained<crate::NSColorSpace>> {
+        unsafe { self.colorSpace() }
+    }
+
+    /// Whether the screen can show colors in the given display gamut, e.g.
+    /// to decide whether to use wide-gamut assets.
+    #[doc(alias = "canRepresentDisplayGamut:")]
+    pub fn supports_display_gamut(&self, gamut: crate::NSDisplayGamut) -> bool {
+        unsafe { self.canRepresentDisplayGamut(gamut) }
+    }
+
+    /// Take a snapshot of the parameters of the screen.
+    pub fn info(&self) -> NSScreenInfo {
+        NSScreenInfo {
+            display_id: self.display_id(),
+            name: self.name(),
+            frame: self.frame(),
+            visible_frame: self.visibleFrame(),
+            backing_scale_factor: self.backingScaleFactor(),
+            safe_area_insets: self.safe_area_insets(),
+        }
+    }
+
+    /// Take a snapshot of the parameters of all screens.
+    ///
+    /// The first screen is the one containing the menu bar, whose origin is
+    /// the origin of the global screen coordinate space.
+    pub fn all_info(mtm: MainThreadMarker) -> Vec<NSScreenInfo> {
+        Self::screens(mtm)
+            .iter()
+            .map(|screen| screen.info())
+            .collect()
+    }
+}
+
+#[cfg(all(feature = "std", feature = "block2", feature = "NSApplication"))]
+impl NSScreen {
+    /// Receive the parameters of all screens whenever a screen is added,
+    /// removed or reconfigured, as a stream.
+    ///
+    /// Notifications are posted on the main thread, so the stream should be
+    /// polled asynchronously there, or received on another thread.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2::MainThreadMarker;
+    /// use objc2_app_kit::NSScreen;
+    ///
+    /// let mtm = MainThreadMarker::new().unwrap();
+    /// let changes = NSScreen::parameter_changes(mtm);
+    ///
+    /// std::thread::spawn(move || {
+    ///     while let Some(screens) = changes.recv() {
+    ///         println!("screens changed: {screens:?}");
+    ///     }
+    /// });
+    /// ```
+    #[doc(alias = "NSApplicationDidChangeScreenParametersNotification")]
+    pub fn parameter_changes(
+        _mtm: MainThreadMarker,
+    ) -> objc2_foundation::NSNotificationStream<Vec<NSScreenInfo>> {
+        let center = unsafe { objc2_foundation::NSNotificationCenter::defaultCenter() };
+        let name = unsafe { crate::NSApplicationDidChangeScreenParametersNotification };
+        center.notifications(name, None, |_| {
+            // The notification is posted on the main thread.
+            let mtm = MainThreadMarker::new().expect("screen parameters changed off main thread");
+            NSScreen::all_info(mtm)
+        })
+    }
+}
@@ -0,0 +1,375 @@
+//! Providing services to other applications through the Services menu.
+//!
+//! Services are declared statically in the `NSServices` key of the
+//! application's `Info.plist`, see [`NSServiceDescription`]. When the user
+//! invokes a service, AppKit sends a message to the application's services
+//! provider, which is set up with [`NSApplication::register_services`].
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::ptr;
+
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2::{define_class, msg_send_id, DefinedClass, MainThreadMarker, MainThreadOnly, Message};
+use objc2_foundation::{NSObject, NSString};
+
+use crate::{
+    NSApplication, NSPasteboard, NSPasteboardTypeString, NSRegisterServicesProvider,
+    NSUnregisterServicesProvider,
+};
+
+type Handler = Box<dyn Fn(&NSPasteboard) -> Result<(), String> + 'static>;
+
+/// The message that AppKit sends to the services provider.
+///
+/// This is the value of the `NSMessage` key of every service in the
+/// `Info.plist`.
+pub const NS_SERVICE_MESSAGE: &str = "performRustService";
+
+/// The closures to call when a service is invoked.
+///
+/// Each service is identified by the `NSUserData` string that it was
+/// declared with in the `Info.plist`.
+///
+/// See [`NSApplication::register_services`].
+#[derive(Default)]
+pub struct NSServiceHandlers {
+    handlers: Vec<(String, Handler)>,
+}
+
+impl NSServiceHandlers {
+    /// Create an empty set of handlers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call the given closure when the service with the given `NSUserData`
+    /// is invoked.
+    ///
+    /// The closure receives the pasteboard containing the data that the
+    /// user selected, in one of the service's `NSSendTypes`. If the service
+    /// has `NSReturnTypes`, the closure should write the result back to the
+    /// same pasteboard.
+    ///
+    /// Returning an error aborts the service, and shows the message to the
+    /// user.
+    pub fn service(
+        mut self,
+        user_data: &str,
+        f: impl Fn(&NSPasteboard) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.handlers.retain(|(existing, _)| existing != user_data);
+        self.handlers.push((user_data.to_owned(), Box::new(f)));
+        self
+    }
+
+    /// Call the given closure with the selected text when the service with
+    /// the given `NSUserData` is invoked, and replace the selection with the
+    /// returned text.
+    ///
+    /// The service should be declared with [`NSServiceDescription::text`].
+    pub fn text_service(
+        self,
+        user_data: &str,
+        f: impl Fn(&str) -> Result<String, String> + 'static,
+    ) -> Self {
+        self.service(user_data, move |pasteboard| {
+            let ty = unsafe { NSPasteboardTypeString };
+            let input = unsafe { pasteboard.stringForType(ty) }
+                .ok_or_else(|| "The pasteboard does not contain any text.".to_owned())?;
+            let output = f(&input.to_string())?;
+            unsafe { pasteboard.clearContents() };
+            if unsafe { pasteboard.setString_forType(&NSString::from_str(&output), ty) } {
+                Ok(())
+            } else {
+                Err("Could not write the result to the pasteboard.".to_owned())
+            }
+        })
+    }
+
+    fn get(&self, user_data: &str) -> Option<&Handler> {
+        self.handlers
+            .iter()
+            .find(|(existing, _)| existing == user_data)
+            .map(|(_, handler)| handler)
+    }
+}
+
+impl fmt::Debug for NSServiceHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSServiceHandlers")
+            .field(
+                "services",
+                &self
+                    .handlers
+                    .iter()
+                    .map(|(user_data, _)| user_data)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+define_class!(
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - `ServicesProvider` does not implement `Drop`.
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "__RustNSServicesProvider"]
+    #[ivars = NSServiceHandlers]
+    struct ServicesProvider;
+
+    unsafe impl ServicesProvider {
+        // Must match `NS_SERVICE_MESSAGE`.
+        #[method(performRustService:userData:error:)]
+        fn perform_service(
+            &self,
+            pasteboard: &NSPasteboard,
+            user_data: Option<&NSString>,
+            error: *mut *mut NSString,
+        ) {
+            let user_data = user_data.map(|s| s.to_string()).unwrap_or_default();
+            let res = match self.ivars().get(&user_data) {
+                Some(handler) => handler(pasteboard),
+                None => Err(alloc::format!("Unknown service {user_data:?}.")),
+            };
+            if let Err(message) = res {
+                if !error.is_null() {
+                    let message = NSString::from_str(&message);
+                    // SAFETY: The error pointer is an out parameter, which
+                    // expects an autoreleased string.
+                    unsafe { *error = Retained::autorelease_ptr(message) };
+                }
+            }
+        }
+    }
+);
+
+impl ServicesProvider {
+    fn new(handlers: NSServiceHandlers, mtm: MainThreadMarker) -> Retained<Self> {
+        let this = Self::alloc(mtm).set_ivars(handlers);
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+#[derive(Debug)]
+enum Registration {
+    Application(Retained<NSApplication>),
+    Named(Retained<NSString>),
+}
+
+/// A services provider registered with
+/// [`NSApplication::register_services`] or [`register_services_provider`].
+///
+/// The provider is unregistered, and the handlers are released, when this
+/// is dropped.
+pub struct NSServicesProviderHandle {
+    registration: Registration,
+    provider: Retained<ServicesProvider>,
+}
+
+impl Drop for NSServicesProviderHandle {
+    fn drop(&mut self) {
+        match &self.registration {
+            Registration::Application(app) => {
+                let provider: &AnyObject = &self.provider;
+                // Only unset the provider if it has not been replaced since.
+                if unsafe { app.servicesProvider() }
+                    .is_some_and(|current| ptr::eq(&*current, provider))
+                {
+                    unsafe { app.setServicesProvider(None) };
+                }
+            }
+            Registration::Named(name) => unsafe { NSUnregisterServicesProvider(name) },
+        }
+    }
+}
+
+impl fmt::Debug for NSServicesProviderHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSServicesProviderHandle")
+            .field("registration", &self.registration)
+            .field("handlers", self.provider.ivars())
+            .finish()
+    }
+}
+
+impl NSApplication {
+    /// Handle the services declared in the `Info.plist` with the given
+    /// closures.
+    ///
+    /// This replaces any previously set services provider. The services are
+    /// handled until the returned handle is dropped.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use objc2_app_kit::{NSApplication, NSServiceHandlers};
+    ///
+    /// let app = NSApplication::sharedApplication(mtm);
+    /// let _services = app.register_services(
+    ///     NSServiceHandlers::new()
+    ///         .text_service("uppercase", |text| Ok(text.to_uppercase())),
+    /// );
+    /// app.run();
+    /// ```
+    #[doc(alias = "setServicesProvider:")]
+    pub fn register_services(&self, handlers: NSServiceHandlers) -> NSServicesProviderHandle {
+        let provider = ServicesProvider::new(handlers, self.mtm());
+        let provider_ref: &AnyObject = &provider;
+        unsafe { self.setServicesProvider(Some(provider_ref)) };
+        NSServicesProviderHandle {
+            registration: Registration::Application(self.retain()),
+            provider,
+        }
+    }
+}
+
+/// Handle services under the given `NSPortName`, for processes that are not
+/// an `NSApplication`.
+///
+/// Applications should use [`NSApplication::register_services`] instead.
+#[doc(alias = "NSRegisterServicesProvider")]
+pub fn register_services_provider(
+    port_name: &str,
+    handlers: NSServiceHandlers,
+    mtm: MainThreadMarker,
+) -> NSServicesProviderHandle {
+    let name = NSString::from_str(port_name);
+    let provider = ServicesProvider::new(handlers, mtm);
+    let provider_ref: &AnyObject = &provider;
+    unsafe { NSRegisterServicesProvider(Some(provider_ref), &name) };
+    NSServicesProviderHandle {
+        registration: Registration::Named(name),
+        provider,
+    }
+}
+
+/// The declaration of a service in the `NSServices` array of the
+/// `Info.plist`.
+///
+/// Services are discovered by the system without launching the
+/// application, so they must be declared statically. Use
+/// [`to_info_plist`](Self::to_info_plist) to generate the declaration.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NSServiceDescription {
+    /// The title of the service in the Services menu (`NSMenuItem`).
+    pub menu_item: String,
+    /// The string identifying the service to the handler (`NSUserData`).
+    pub user_data: String,
+    /// The pasteboard types that the service accepts (`NSSendTypes`).
+    pub send_types: Vec<String>,
+    /// The pasteboard types that the service returns (`NSReturnTypes`).
+    ///
+    /// Empty if the service does not return anything.
+    pub return_types: Vec<String>,
+    /// The key equivalent of the service, used together with Command
+    /// (`NSKeyEquivalent`).
+    pub key_equivalent: Option<char>,
+}
+
+impl NSServiceDescription {
+    /// A service with the given menu title and user data, that does not
+    /// accept or return any data.
+    pub fn new(menu_item: &str, user_data: &str) -> Self {
+        Self {
+            menu_item: menu_item.to_owned(),
+            user_data: user_data.to_owned(),
+            send_types: Vec::new(),
+            return_types: Vec::new(),
+            key_equivalent: None,
+        }
+    }
+
+    /// A service that replaces the selected text, as handled by
+    /// [`NSServiceHandlers::text_service`].
+    pub fn text(menu_item: &str, user_data: &str) -> Self {
+        let ty = "public.utf8-plain-text".to_owned();
+        Self {
+            send_types: alloc::vec![ty.clone()],
+            return_types: alloc::vec![ty],
+            ..Self::new(menu_item, user_data)
+        }
+    }
+
+    /// The entry in the `NSServices` array of the `Info.plist`, in XML
+    /// property list format.
+    ///
+    /// `port_name` is the name of the application, or the name given to
+    /// [`register_services_provider`].
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_app_kit::NSServiceDescription;
+    ///
+    /// let service = NSServiceDescription::text("Make Uppercase", "uppercase");
+    /// let plist = service.to_info_plist("MyApp");
+    /// assert!(plist.contains("<key>NSMessage</key>"));
+    /// assert!(plist.contains("<string>uppercase</string>"));
+    /// ```
+    pub fn to_info_plist(&self, port_name: &str) -> String {
+        fn string(out: &mut String, indent: &str, s: &str) {
+            out.push_str(indent);
+            out.push_str("<string>");
+            for c in s.chars() {
+                match c {
+                    '&' => out.push_str("&amp;"),
+                    '<' => out.push_str("&lt;"),
+                    '>' => out.push_str("&gt;"),
+                    c => out.push(c),
+                }
+            }
+            out.push_str("</string>\n");
+        }
+
+        fn key(out: &mut String, indent: &str, key: &str) {
+            let _ = writeln!(out, "{indent}<key>{key}</key>");
+        }
+
+        fn menu_dict(out: &mut String, value: &str) {
+            out.push_str("    <dict>\n");
+            key(out, "        ", "default");
+            string(out, "        ", value);
+            out.push_str("    </dict>\n");
+        }
+
+        fn types(out: &mut String, types: &[String]) {
+            out.push_str("    <array>\n");
+            for ty in types {
+                string(out, "        ", ty);
+            }
+            out.push_str("    </array>\n");
+        }
+
+        let mut out = String::from("<dict>\n");
+        key(&mut out, "    ", "NSMenuItem");
+        menu_dict(&mut out, &self.menu_item);
+        key(&mut out, "    ", "NSMessage");
+        string(&mut out, "    ", NS_SERVICE_MESSAGE);
+        key(&mut out, "    ", "NSPortName");
+        string(&mut out, "    ", port_name);
+        key(&mut out, "    ", "NSUserData");
+        string(&mut out, "    ", &self.user_data);
+        if !self.send_types.is_empty() {
+            key(&mut out, "    ", "NSSendTypes");
+            types(&mut out, &self.send_types);
+        }
+        if !self.return_types.is_empty() {
+            key(&mut out, "    ", "NSReturnTypes");
+            types(&mut out, &self.return_types);
+        }
+        if let Some(c) = self.key_equivalent {
+            key(&mut out, "    ", "NSKeyEquivalent");
+            menu_dict(&mut out, c.encode_utf8(&mut [0; 4]));
+        }
+        out.push_str("</dict>\n");
+        out
+    }
+}
@@ -0,0 +1,264 @@
+//! Programmatic construction of split view controllers.
+use alloc::vec::Vec;
+
+use objc2::rc::Retained;
+use objc2::{MainThreadMarker, Message};
+use objc2_core_foundation::CGFloat;
+
+#[cfg(feature = "NSLayoutConstraint")]
+use crate::NSLayoutPriority;
+use crate::{
+    NSSplitViewController, NSSplitViewDividerStyle, NSSplitViewItem, NSSplitViewItemBehavior,
+    NSSplitViewItemCollapseBehavior, NSViewController,
+};
+
+/// The layout of a single pane in a split view controller.
+///
+/// Options that are left as [`None`] use the defaults of the item's
+/// [behavior](Self::behavior).
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct NSSplitViewItemOptions {
+    /// The standard behavior of the pane, which determines how the
+    /// `NSSplitViewItem` is created.
+    pub behavior: NSSplitViewItemBehavior,
+    /// Whether the pane is initially collapsed.
+    pub collapsed: Option<bool>,
+    /// Whether the user can collapse the pane.
+    pub can_collapse: Option<bool>,
+    /// How collapsing the pane affects the window and the other panes.
+    pub collapse_behavior: Option<NSSplitViewItemCollapseBehavior>,
+    /// The minimum width (or height, for horizontal split views).
+    pub minimum_thickness: Option<CGFloat>,
+    /// The maximum width (or height, for horizontal split views).
+    pub maximum_thickness: Option<CGFloat>,
+    /// The fraction of the split view that the pane prefers to fill.
+    pub preferred_thickness_fraction: Option<CGFloat>,
+    /// The priority with which the pane holds its size when the split view
+    /// is resized.
+    #[cfg(feature = "NSLayoutConstraint")]
+    pub holding_priority: Option<NSLayoutPriority>,
+}
+
+impl Default for NSSplitViewItemOptions {
+    fn default() -> Self {
+        Self {
+            behavior: NSSplitViewItemBehavior::Default,
+            collapsed: None,
+            can_collapse: None,
+            collapse_behavior: None,
+            minimum_thickness: None,
+            maximum_thickness: None,
+            preferred_thickness_fraction: None,
+            #[cfg(feature = "NSLayoutConstraint")]
+            holding_priority: None,
+        }
+    }
+}
+
+impl NSSplitViewItemOptions {
+    /// Options for a pane with the default behavior.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Options for a sidebar pane.
+    #[doc(alias = "sidebarWithViewController:")]
+    pub fn sidebar() -> Self {
+        Self::new().behavior(NSSplitViewItemBehavior::Sidebar)
+    }
+
+    /// Options for a content list pane, such as the list of messages in a
+    /// mail client.
+    #[doc(alias = "contentListWithViewController:")]
+    pub fn content_list() -> Self {
+        Self::new().behavior(NSSplitViewItemBehavior::ContentList)
+    }
+
+    /// Options for an inspector pane.
+    #[doc(alias = "inspectorWithViewController:")]
+    pub fn inspector() -> Self {
+        Self::new().behavior(NSSplitViewItemBehavior::Inspector)
+    }
+
+    /// Set the standard behavior of the pane.
+    pub fn behavior(mut self, behavior: NSSplitViewItemBehavior) -> Self {
+        self.behavior = behavior;
+        self
+    }
+
+    /// Set whether the pane is initially collapsed.
+    pub fn collapsed(mut self, collapsed: bool) -> Self {
+        self.collapsed = Some(collapsed);
+        self
+    }
+
+    /// Set whether the user can collapse the pane.
+    pub fn can_collapse(mut self, can_collapse: bool) -> Self {
+        self.can_collapse = Some(can_collapse);
+        self
+    }
+
+    /// Set how collapsing the pane affects the window and the other panes.
+    pub fn collapse_behavior(mut self, collapse_behavior: NSSplitViewItemCollapseBehavior) -> Self {
+        self.collapse_behavior = Some(collapse_behavior);
+        self
+    }
+
+    /// Set the minimum and maximum thickness of the pane.
+    pub fn thickness(mut self, minimum: CGFloat, maximum: CGFloat) -> Self {
+        self.minimum_thickness = Some(minimum);
+        self.maximum_thickness = Some(maximum);
+        self
+    }
+
+    /// Set the minimum thickness of the pane.
+    pub fn minimum_thickness(mut self, thickness: CGFloat) -> Self {
+        self.minimum_thickness = Some(thickness);
+        self
+    }
+
+    /// Set the maximum thickness of the pane.
+    pub fn maximum_thickness(mut self, thickness: CGFloat) -> Self {
+        self.maximum_thickness = Some(thickness);
+        self
+    }
+
+    /// Set the fraction of the split view that the pane prefers to fill.
+    pub fn preferred_thickness_fraction(mut self, fraction: CGFloat) -> Self {
+        self.preferred_thickness_fraction = Some(fraction);
+        self
+    }
+
+    /// Set the priority with which the pane holds its size when the split
+    /// view is resized.
+    ///
+    /// The pane with the lowest priority is resized first.
+    #[cfg(feature = "NSLayoutConstraint")]
+    pub fn holding_priority(mut self, priority: NSLayoutPriority) -> Self {
+        self.holding_priority = Some(priority);
+        self
+    }
+
+    fn create_item(&self, view_controller: &NSViewController) -> Retained<NSSplitViewItem> {
+        let item = unsafe {
+            match self.behavior {
+                NSSplitViewItemBehavior::Sidebar => {
+                    NSSplitViewItem::sidebarWithViewController(view_controller)
+                }
+                NSSplitViewItemBehavior::ContentList => {
+                    NSSplitViewItem::contentListWithViewController(view_controller)
+                }
+                NSSplitViewItemBehavior::Inspector => {
+                    NSSplitViewItem::inspectorWithViewController(view_controller)
+                }
+                _ => NSSplitViewItem::splitViewItemWithViewController(view_controller),
+            }
+        };
+
+        unsafe {
+            if let Some(can_collapse) = self.can_collapse {
+                item.setCanCollapse(can_collapse);
+            }
+            if let Some(collapse_behavior) = self.collapse_behavior {
+                item.setCollapseBehavior(collapse_behavior);
+            }
+            if let Some(thickness) = self.minimum_thickness {
+                item.setMinimumThickness(thickness);
+            }
+            if let Some(thickness) = self.maximum_thickness {
+                item.setMaximumThickness(thickness);
+            }
+            if let Some(fraction) = self.preferred_thickness_fraction {
+                item.setPreferredThicknessFraction(fraction);
+            }
+            #[cfg(feature = "NSLayoutConstraint")]
+            if let Some(priority) = self.holding_priority {
+                item.setHoldingPriority(priority);
+            }
+            // Set last, so that the view controller's view isn't loaded
+            // while the item is collapsed.
+            if let Some(collapsed) = self.collapsed {
+                item.setCollapsed(collapsed);
+            }
+        }
+
+        item
+    }
+}
+
+/// A builder for assembling an [`NSSplitViewController`] from child view
+/// controllers, without a nib or storyboard.
+///
+///
+/// # Example
+///
+/// ```ignore
+/// use objc2_app_kit::{NSSplitViewControllerBuilder, NSSplitViewItemOptions};
+///
+/// let split_view_controller = NSSplitViewControllerBuilder::new()
+///     .item(&sidebar, NSSplitViewItemOptions::sidebar().thickness(180.0, 320.0))
+///     .item(&content, NSSplitViewItemOptions::new().minimum_thickness(400.0))
+///     .build(mtm);
+/// ```
+#[derive(Debug, Default)]
+pub struct NSSplitViewControllerBuilder {
+    vertical: Option<bool>,
+    divider_style: Option<NSSplitViewDividerStyle>,
+    items: Vec<(Retained<NSViewController>, NSSplitViewItemOptions)>,
+}
+
+impl NSSplitViewControllerBuilder {
+    /// Create a builder for a split view controller without any panes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether the dividers are vertical, i.e. whether the panes are
+    /// laid out side by side.
+    ///
+    /// Split view controllers are vertical by default.
+    #[doc(alias = "setVertical:")]
+    pub fn vertical(mut self, vertical: bool) -> Self {
+        self.vertical = Some(vertical);
+        self
+    }
+
+    /// Set the style of the dividers between the panes.
+    #[doc(alias = "setDividerStyle:")]
+    pub fn divider_style(mut self, divider_style: NSSplitViewDividerStyle) -> Self {
+        self.divider_style = Some(divider_style);
+        self
+    }
+
+    /// Add a pane showing the given view controller after the previously
+    /// added panes.
+    #[doc(alias = "addSplitViewItem:")]
+    pub fn item(
+        mut self,
+        view_controller: &NSViewController,
+        options: NSSplitViewItemOptions,
+    ) -> Self {
+        self.items.push((view_controller.retain(), options));
+        self
+    }
+
+    /// Create the split view controller.
+    pub fn build(self, mtm: MainThreadMarker) -> Retained<NSSplitViewController> {
+        let controller = unsafe { NSSplitViewController::new(mtm) };
+        unsafe {
+            let split_view = controller.splitView();
+            if let Some(vertical) = self.vertical {
+                split_view.setVertical(vertical);
+            }
+            if let Some(divider_style) = self.divider_style {
+                split_view.setDividerStyle(divider_style);
+            }
+        }
+        for (view_controller, options) in &self.items {
+            let item = options.create_item(view_controller);
+            unsafe { controller.addSplitViewItem(&item) };
+        }
+        controller
+    }
+}
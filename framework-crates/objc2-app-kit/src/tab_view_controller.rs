@@ -0,0 +1,250 @@
+//! Programmatic construction of tab view controllers.
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cell::OnceCell;
+use core::fmt;
+
+use objc2::rc::Retained;
+use objc2::runtime::NSObjectProtocol;
+use objc2::{
+    define_class, msg_send, msg_send_id, DefinedClass, MainThreadMarker, MainThreadOnly, Message,
+};
+use objc2_foundation::{NSInteger, NSObject, NSString};
+
+#[cfg(feature = "NSImage")]
+use crate::NSImage;
+use crate::{
+    NSResponder, NSTabView, NSTabViewController, NSTabViewControllerTabStyle, NSTabViewItem,
+    NSViewController, NSViewControllerTransitionOptions,
+};
+
+type SelectionHandler = Box<dyn Fn(usize, &NSTabViewItem) + 'static>;
+
+/// A single tab in a tab view controller.
+#[derive(Debug)]
+pub struct NSTabViewControllerTab {
+    view_controller: Retained<NSViewController>,
+    label: Option<Retained<NSString>>,
+    #[cfg(feature = "NSImage")]
+    image: Option<Retained<NSImage>>,
+    tool_tip: Option<Retained<NSString>>,
+}
+
+impl NSTabViewControllerTab {
+    /// A tab showing the given view controller.
+    ///
+    /// By default, the tab is labelled with the title of the view
+    /// controller.
+    #[doc(alias = "tabViewItemWithViewController:")]
+    pub fn new(view_controller: &NSViewController) -> Self {
+        Self {
+            view_controller: view_controller.retain(),
+            label: None,
+            #[cfg(feature = "NSImage")]
+            image: None,
+            tool_tip: None,
+        }
+    }
+
+    /// Set the label of the tab.
+    #[doc(alias = "setLabel:")]
+    pub fn label(mut self, label: &NSString) -> Self {
+        self.label = Some(label.retain());
+        self
+    }
+
+    /// Set the image of the tab.
+    ///
+    /// This is used by the [`Toolbar`] tab style.
+    ///
+    /// [`Toolbar`]: NSTabViewControllerTabStyle::Toolbar
+    #[cfg(feature = "NSImage")]
+    #[doc(alias = "setImage:")]
+    pub fn image(mut self, image: &NSImage) -> Self {
+        self.image = Some(image.retain());
+        self
+    }
+
+    /// Set the tool tip of the tab.
+    #[doc(alias = "setToolTip:")]
+    pub fn tool_tip(mut self, tool_tip: &NSString) -> Self {
+        self.tool_tip = Some(tool_tip.retain());
+        self
+    }
+
+    fn create_item(&self) -> Retained<NSTabViewItem> {
+        let item = unsafe { NSTabViewItem::tabViewItemWithViewController(&self.view_controller) };
+        unsafe {
+            if let Some(label) = &self.label {
+                item.setLabel(label);
+            }
+            #[cfg(feature = "NSImage")]
+            if let Some(image) = &self.image {
+                item.setImage(Some(image));
+            }
+            if let Some(tool_tip) = &self.tool_tip {
+                item.setToolTip(Some(tool_tip));
+            }
+        }
+        item
+    }
+}
+
+define_class!(
+    // SAFETY:
+    // - The superclass NSTabViewController does not have any subclassing
+    //   requirements, other than that overridden delegate methods must call
+    //   `super`, which we do.
+    // - `TabViewController` does not implement `Drop`.
+    #[unsafe(super(NSTabViewController, NSViewController, NSResponder, NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "__RustNSTabViewController"]
+    #[ivars = OnceCell<SelectionHandler>]
+    struct TabViewController;
+
+    unsafe impl TabViewController {
+        #[method(tabView:didSelectTabViewItem:)]
+        fn did_select_tab_view_item(&self, tab_view: &NSTabView, item: Option<&NSTabViewItem>) {
+            let _: () = unsafe {
+                msg_send![super(self), tabView: tab_view, didSelectTabViewItem: item]
+            };
+            let (Some(f), Some(item)) = (self.ivars().get(), item) else {
+                return;
+            };
+            let index: NSInteger = unsafe { tab_view.indexOfTabViewItem(item) };
+            // `NSNotFound` if the item was removed in the meantime.
+            if let Ok(index) = usize::try_from(index) {
+                f(index, item);
+            }
+        }
+    }
+
+    unsafe impl NSObjectProtocol for TabViewController {}
+);
+
+/// A builder for assembling an [`NSTabViewController`] from child view
+/// controllers, without a nib or storyboard.
+///
+///
+/// # Example
+///
+/// ```ignore
+/// use objc2_app_kit::{
+///     NSTabViewControllerBuilder, NSTabViewControllerTab, NSTabViewControllerTabStyle,
+/// };
+/// use objc2_foundation::ns_string;
+///
+/// let tab_view_controller = NSTabViewControllerBuilder::new()
+///     .tab_style(NSTabViewControllerTabStyle::Toolbar)
+///     .tab(NSTabViewControllerTab::new(&general).label(ns_string!("General")))
+///     .tab(NSTabViewControllerTab::new(&advanced).label(ns_string!("Advanced")))
+///     .on_select(|index, _item| println!("selected tab {index}"))
+///     .build(mtm);
+/// ```
+#[derive(Default)]
+pub struct NSTabViewControllerBuilder {
+    tab_style: Option<NSTabViewControllerTabStyle>,
+    transition_options: Option<NSViewControllerTransitionOptions>,
+    tabs: Vec<NSTabViewControllerTab>,
+    selected: Option<usize>,
+    on_select: Option<SelectionHandler>,
+}
+
+impl NSTabViewControllerBuilder {
+    /// Create a builder for a tab view controller without any tabs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how the tabs are presented.
+    #[doc(alias = "setTabStyle:")]
+    pub fn tab_style(mut self, tab_style: NSTabViewControllerTabStyle) -> Self {
+        self.tab_style = Some(tab_style);
+        self
+    }
+
+    /// Set the animation used when switching between tabs.
+    #[doc(alias = "setTransitionOptions:")]
+    pub fn transition_options(mut self, options: NSViewControllerTransitionOptions) -> Self {
+        self.transition_options = Some(options);
+        self
+    }
+
+    /// Add a tab after the previously added tabs.
+    #[doc(alias = "addTabViewItem:")]
+    pub fn tab(mut self, tab: NSTabViewControllerTab) -> Self {
+        self.tabs.push(tab);
+        self
+    }
+
+    /// Set the index of the initially selected tab.
+    ///
+    /// Defaults to the first tab.
+    #[doc(alias = "setSelectedTabViewItemIndex:")]
+    pub fn selected(mut self, index: usize) -> Self {
+        self.selected = Some(index);
+        self
+    }
+
+    /// Call the given closure with the index of the newly selected tab when
+    /// the selection changes.
+    ///
+    /// The closure is not called for the initial selection.
+    #[doc(alias = "tabView:didSelectTabViewItem:")]
+    pub fn on_select(mut self, f: impl Fn(usize, &NSTabViewItem) + 'static) -> Self {
+        self.on_select = Some(Box::new(f));
+        self
+    }
+
+    /// Create the tab view controller.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [selected index](Self::selected) is out of bounds.
+    pub fn build(self, mtm: MainThreadMarker) -> Retained<NSTabViewController> {
+        let this = TabViewController::alloc(mtm).set_ivars(OnceCell::new());
+        let controller: Retained<TabViewController> = unsafe { msg_send_id![super(this), init] };
+
+        unsafe {
+            if let Some(tab_style) = self.tab_style {
+                controller.setTabStyle(tab_style);
+            }
+            if let Some(options) = self.transition_options {
+                controller.setTransitionOptions(options);
+            }
+        }
+        for tab in &self.tabs {
+            let item = tab.create_item();
+            unsafe { controller.addTabViewItem(&item) };
+        }
+        if let Some(index) = self.selected {
+            assert!(
+                index < self.tabs.len(),
+                "selected tab index {index} out of bounds (there are {} tabs)",
+                self.tabs.len(),
+            );
+            unsafe { controller.setSelectedTabViewItemIndex(index as NSInteger) };
+        }
+
+        // Installed after the tabs were added, so that selecting the initial
+        // tab does not call the handler.
+        if let Some(on_select) = self.on_select {
+            let _ = controller.ivars().set(on_select);
+        }
+
+        Retained::into_super(controller)
+    }
+}
+
+impl fmt::Debug for NSTabViewControllerBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSTabViewControllerBuilder")
+            .field("tab_style", &self.tab_style)
+            .field("transition_options", &self.transition_options)
+            .field("tabs", &self.tabs)
+            .field("selected", &self.selected)
+            .field("on_select", &self.on_select.is_some())
+            .finish()
+    }
+}
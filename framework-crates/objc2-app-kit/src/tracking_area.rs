@@ -0,0 +1,231 @@
+//! Closure-based mouse tracking.
+use alloc::boxed::Box;
+use core::fmt;
+
+use objc2::rc::{Retained, Weak};
+use objc2::runtime::AnyObject;
+use objc2::{
+    define_class, msg_send_id, AllocAnyThread, DefinedClass, MainThreadMarker, MainThreadOnly,
+};
+use objc2_foundation::{NSObject, NSRect};
+
+use crate::{NSEvent, NSTrackingArea, NSTrackingAreaOptions, NSView};
+
+type Handler = Box<dyn Fn(&NSEvent) + 'static>;
+
+/// The closures to call when the mouse moves relative to a view.
+///
+/// See [`NSView::add_tracking_handlers`].
+#[derive(Default)]
+pub struct NSTrackingAreaHandlers {
+    mouse_entered: Option<Handler>,
+    mouse_exited: Option<Handler>,
+    mouse_moved: Option<Handler>,
+    cursor_update: Option<Handler>,
+}
+
+impl NSTrackingAreaHandlers {
+    /// Create an empty set of handlers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call the given closure when the mouse enters the view.
+    #[doc(alias = "mouseEntered:")]
+    pub fn mouse_entered(mut self, f: impl Fn(&NSEvent) + 'static) -> Self {
+        self.mouse_entered = Some(Box::new(f));
+        self
+    }
+
+    /// Call the given closure when the mouse exits the view.
+    #[doc(alias = "mouseExited:")]
+    pub fn mouse_exited(mut self, f: impl Fn(&NSEvent) + 'static) -> Self {
+        self.mouse_exited = Some(Box::new(f));
+        self
+    }
+
+    /// Call the given closure when the mouse moves inside the view.
+    #[doc(alias = "mouseMoved:")]
+    pub fn mouse_moved(mut self, f: impl Fn(&NSEvent) + 'static) -> Self {
+        self.mouse_moved = Some(Box::new(f));
+        self
+    }
+
+    /// Call the given closure when the cursor should be updated.
+    ///
+    /// This is where you'd usually call `-[NSCursor set]`.
+    #[doc(alias = "cursorUpdate:")]
+    pub fn cursor_update(mut self, f: impl Fn(&NSEvent) + 'static) -> Self {
+        self.cursor_update = Some(Box::new(f));
+        self
+    }
+
+    /// The tracking options required to receive the events that have
+    /// handlers.
+    fn event_options(&self) -> NSTrackingAreaOptions {
+        let mut options = NSTrackingAreaOptions::empty();
+        if self.mouse_entered.is_some() || self.mouse_exited.is_some() {
+            options |= NSTrackingAreaOptions::MouseEnteredAndExited;
+        }
+        if self.mouse_moved.is_some() {
+            options |= NSTrackingAreaOptions::MouseMoved;
+        }
+        if self.cursor_update.is_some() {
+            options |= NSTrackingAreaOptions::CursorUpdate;
+        }
+        options
+    }
+}
+
+impl fmt::Debug for NSTrackingAreaHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSTrackingAreaHandlers")
+            .field("mouse_entered", &self.mouse_entered.is_some())
+            .field("mouse_exited", &self.mouse_exited.is_some())
+            .field("mouse_moved", &self.mouse_moved.is_some())
+            .field("cursor_update", &self.cursor_update.is_some())
+            .finish()
+    }
+}
+
+define_class!(
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - `TrackingAreaOwner` does not implement `Drop`.
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "__RustNSTrackingAreaOwner"]
+    #[ivars = NSTrackingAreaHandlers]
+    struct TrackingAreaOwner;
+
+    unsafe impl TrackingAreaOwner {
+        #[method(mouseEntered:)]
+        fn mouse_entered(&self, event: &NSEvent) {
+            if let Some(f) = &self.ivars().mouse_entered {
+                f(event);
+            }
+        }
+
+        #[method(mouseExited:)]
+        fn mouse_exited(&self, event: &NSEvent) {
+            if let Some(f) = &self.ivars().mouse_exited {
+                f(event);
+            }
+        }
+
+        #[method(mouseMoved:)]
+        fn mouse_moved(&self, event: &NSEvent) {
+            if let Some(f) = &self.ivars().mouse_moved {
+                f(event);
+            }
+        }
+
+        #[method(cursorUpdate:)]
+        fn cursor_update(&self, event: &NSEvent) {
+            if let Some(f) = &self.ivars().cursor_update {
+                f(event);
+            }
+        }
+    }
+);
+
+impl TrackingAreaOwner {
+    fn new(handlers: NSTrackingAreaHandlers, mtm: MainThreadMarker) -> Retained<Self> {
+        let this = Self::alloc(mtm).set_ivars(handlers);
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+/// A tracking area installed with [`NSView::add_tracking_handlers`].
+///
+/// The tracking area is removed from the view, and the handlers are
+/// released, when this is dropped.
+pub struct NSTrackingAreaHandle {
+    view: Weak<NSView>,
+    area: Retained<NSTrackingArea>,
+    // The tracking area does not retain its owner, so we have to.
+    _owner: Retained<TrackingAreaOwner>,
+}
+
+impl NSTrackingAreaHandle {
+    /// The underlying tracking area.
+    pub fn tracking_area(&self) -> &NSTrackingArea {
+        &self.area
+    }
+}
+
+impl Drop for NSTrackingAreaHandle {
+    fn drop(&mut self) {
+        if let Some(view) = self.view.load() {
+            unsafe { view.removeTrackingArea(&self.area) };
+        }
+    }
+}
+
+impl fmt::Debug for NSTrackingAreaHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSTrackingAreaHandle")
+            .field("area", &self.area)
+            .finish_non_exhaustive()
+    }
+}
+
+impl NSView {
+    /// Call closures when the mouse enters, exits or moves inside the view.
+    ///
+    /// `options` must contain exactly one of the `Active*` options, which
+    /// determines when the handlers are active. The options for the events
+    /// that `handlers` respond to are added automatically.
+    ///
+    /// The tracking area is created with [`InVisibleRect`], so AppKit keeps
+    /// it in sync with the view's visible rectangle as the view is resized
+    /// or scrolled; there is no need to override `updateTrackingAreas`.
+    ///
+    /// The handlers are active until the returned handle is dropped.
+    ///
+    /// [`InVisibleRect`]: NSTrackingAreaOptions::InVisibleRect
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use objc2_app_kit::{NSTrackingAreaHandlers, NSTrackingAreaOptions};
+    ///
+    /// let handle = view.add_tracking_handlers(
+    ///     NSTrackingAreaOptions::ActiveInKeyWindow,
+    ///     NSTrackingAreaHandlers::new()
+    ///         .mouse_entered(|_| println!("entered"))
+    ///         .mouse_exited(|_| println!("exited")),
+    /// );
+    /// ```
+    #[doc(alias = "addTrackingArea:")]
+    #[doc(alias = "initWithRect:options:owner:userInfo:")]
+    pub fn add_tracking_handlers(
+        &self,
+        options: NSTrackingAreaOptions,
+        handlers: NSTrackingAreaHandlers,
+    ) -> NSTrackingAreaHandle {
+        let mtm = self.mtm();
+        let options = options | handlers.event_options() | NSTrackingAreaOptions::InVisibleRect;
+        let owner = TrackingAreaOwner::new(handlers, mtm);
+        let owner_ref: &AnyObject = &owner;
+
+        // The rect is ignored when using `InVisibleRect`.
+        let area = unsafe {
+            NSTrackingArea::initWithRect_options_owner_userInfo(
+                NSTrackingArea::alloc(),
+                NSRect::ZERO,
+                options,
+                Some(owner_ref),
+                None,
+            )
+        };
+        unsafe { self.addTrackingArea(&area) };
+
+        NSTrackingAreaHandle {
+            view: Weak::new(self),
+            area,
+            _owner: owner,
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! Window levels, and the setup of overlay and panel windows.
+#[cfg(all(feature = "NSColor", feature = "NSGraphics"))]
+use objc2::{rc::Retained, MainThreadMarker, MainThreadOnly};
+#[cfg(all(feature = "NSColor", feature = "NSGraphics"))]
+use objc2_foundation::NSRect;
+
+#[cfg(feature = "NSColor")]
+use crate::NSColor;
+#[cfg(all(feature = "NSColor", feature = "NSGraphics"))]
+use crate::{NSBackingStoreType, NSWindowStyleMask};
+use crate::{
+    NSFloatingWindowLevel, NSMainMenuWindowLevel, NSModalPanelWindowLevel, NSNormalWindowLevel,
+    NSPopUpMenuWindowLevel, NSScreenSaverWindowLevel, NSStatusWindowLevel, NSWindow,
+    NSWindowCollectionBehavior, NSWindowLevel,
+};
+
+/// The level of a window, which determines which windows it is shown
+/// above.
+///
+/// The well-known levels are listed in increasing order; windows on a
+/// higher level are shown above windows on a lower one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[doc(alias = "NSWindowLevel")]
+pub enum NSWindowLevelKind {
+    /// The level of ordinary windows.
+    #[doc(alias = "NSNormalWindowLevel")]
+    Normal,
+    /// The level of floating palettes and utility windows.
+    #[doc(alias = "NSFloatingWindowLevel")]
+    Floating,
+    /// The level of modal panels.
+    #[doc(alias = "NSModalPanelWindowLevel")]
+    ModalPanel,
+    /// The level of the menu bar.
+    #[doc(alias = "NSMainMenuWindowLevel")]
+    MainMenu,
+    /// The level of status items, above the menu bar.
+    #[doc(alias = "NSStatusWindowLevel")]
+    Status,
+    /// The level of pop-up menus.
+    #[doc(alias = "NSPopUpMenuWindowLevel")]
+    PopUpMenu,
+    /// The level of screen savers, above everything else.
+    #[doc(alias = "NSScreenSaverWindowLevel")]
+    ScreenSaver,
+    /// Any other level.
+    Other(NSWindowLevel),
+}
+
+impl NSWindowLevelKind {
+    /// The raw level.
+    pub fn level(self) -> NSWindowLevel {
+        match self {
+            Self::Normal => NSNormalWindowLevel,
+            Self::Floating => NSFloatingWindowLevel,
+            Self::ModalPanel => NSModalPanelWindowLevel,
+            Self::MainMenu => NSMainMenuWindowLevel,
+            Self::Status => NSStatusWindowLevel,
+            Self::PopUpMenu => NSPopUpMenuWindowLevel,
+            Self::ScreenSaver => NSScreenSaverWindowLevel,
+            Self::Other(level) => level,
+        }
+    }
+
+    /// Convert a raw level, using [`Other`](Self::Other) if it is not one
+    /// of the well-known levels.
+    ///
+    /// Note that some levels share the same raw value, e.g. the torn-off
+    /// menu level is the same as [`Floating`](Self::Floating).
+    pub fn from_level(level: NSWindowLevel) -> Self {
+        [
+            Self::Normal,
+            Self::Floating,
+            Self::ModalPanel,
+            Self::MainMenu,
+            Self::Status,
+            Self::PopUpMenu,
+            Self::ScreenSaver,
+        ]
+        .into_iter()
+        .find(|kind| kind.level() == level)
+        .unwrap_or(Self::Other(level))
+    }
+}
+
+impl From<NSWindowLevelKind> for NSWindowLevel {
+    fn from(kind: NSWindowLevelKind) -> Self {
+        kind.level()
+    }
+}
+
+impl NSWindowCollectionBehavior {
+    /// The behavior of overlay windows: shown on every space, including
+    /// alongside full-screen windows, and not moved by Mission Control nor
+    /// included in the window cycle.
+    #[allow(non_upper_case_globals)]
+    pub const Overlay: Self = Self(
+        Self::CanJoinAllSpaces.0
+            | Self::FullScreenAuxiliary.0
+            | Self::Stationary.0
+            | Self::IgnoresCycle.0,
+    );
+}
+
+/// Overlay and panel setup.
+impl NSWindow {
+    /// The level of the window.
+    #[doc(alias = "level")]
+    pub fn window_level(&self) -> NSWindowLevelKind {
+        NSWindowLevelKind::from_level(unsafe { self.level() })
+    }
+
+    /// Set the level of the window.
+    #[doc(alias = "setLevel:")]
+    pub fn set_window_level(&self, level: NSWindowLevelKind) {
+        self.setLevel(level.level());
+    }
+
+    /// How the window behaves in spaces, Mission Control, full-screen and
+    /// the window cycle.
+    #[doc(alias = "collectionBehavior")]
+    pub fn collection_behavior(&self) -> NSWindowCollectionBehavior {
+        unsafe { self.collectionBehavior() }
+    }
+
+    /// Set how the window behaves in spaces, Mission Control, full-screen
+    /// and the window cycle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the behavior contains more than one of `FullScreenPrimary`,
+    /// `FullScreenAuxiliary` and `FullScreenNone`, which AppKit would raise
+    /// an exception for.
+    #[doc(alias = "setCollectionBehavior:")]
+    pub fn set_collection_behavior(&self, behavior: NSWindowCollectionBehavior) {
+        let full_screen = behavior.intersection(
+            NSWindowCollectionBehavior::FullScreenPrimary
+                | NSWindowCollectionBehavior::FullScreenAuxiliary
+                | NSWindowCollectionBehavior::FullScreenNone,
+        );
+        assert!(
+            full_screen.bits().count_ones() <= 1,
+            "conflicting full-screen collection behaviors: {behavior:?}",
+        );
+        unsafe { self.setCollectionBehavior(behavior) }
+    }
+
+    /// Whether mouse events pass through the window to the windows below.
+    #[doc(alias = "ignoresMouseEvents")]
+    pub fn is_click_through(&self) -> bool {
+        unsafe { self.ignoresMouseEvents() }
+    }
+
+    /// Set whether mouse events pass through the window to the windows
+    /// below.
+    #[doc(alias = "setIgnoresMouseEvents:")]
+    pub fn set_click_through(&self, click_through: bool) {
+        self.setIgnoresMouseEvents(click_through);
+    }
+
+    /// Make the window background fully transparent, such that only the
+    /// content view is drawn.
+    ///
+    /// This clears the background color, and marks the window as
+    /// non-opaque and without a shadow; the shadow would otherwise be drawn
+    /// around the transparent area.
+    #[cfg(feature = "NSColor")]
+    pub fn set_transparent(&self) {
+        self.setOpaque(false);
+        let clear = unsafe { NSColor::clearColor() };
+        self.setBackgroundColor(Some(&clear));
+        self.setHasShadow(false);
+    }
+
+    /// Create a borderless, transparent window that floats above other
+    /// windows on every space, as used by launchers, HUDs and other
+    /// overlays.
+    ///
+    /// The window:
+    /// - Has no title bar or border.
+    /// - Is [transparent](Self::set_transparent).
+    /// - Is on the given level, usually [`NSWindowLevelKind::Floating`] or
+    ///   [`NSWindowLevelKind::Status`].
+    /// - Uses the [`Overlay`](NSWindowCollectionBehavior::Overlay)
+    ///   collection behavior.
+    /// - Stays visible when the application is deactivated.
+    /// - Is not released when closed, as it is owned by the returned
+    ///   [`Retained`].
+    ///
+    /// The window is not shown; use e.g. `orderFrontRegardless` for that.
+    /// Use [`set_click_through`](Self::set_click_through) to let mouse
+    /// events pass through it.
+    ///
+    /// Note that borderless windows cannot become the key window, so they
+    /// do not receive keyboard input. Use a subclass that overrides
+    /// `canBecomeKeyWindow`, or an `NSPanel` with the `NonactivatingPanel`
+    /// style, if you need that.
+    #[cfg(all(feature = "NSColor", feature = "NSGraphics"))]
+    #[doc(alias = "initWithContentRect:styleMask:backing:defer:")]
+    pub fn new_overlay(
+        mtm: MainThreadMarker,
+        content_rect: NSRect,
+        level: NSWindowLevelKind,
+    ) -> Retained<Self> {
+        let window = unsafe {
+            Self::initWithContentRect_styleMask_backing_defer(
+                Self::alloc(mtm),
+                content_rect,
+                NSWindowStyleMask::Borderless,
+                NSBackingStoreType::Buffered,
+                false,
+            )
+        };
+        // SAFETY: The window is owned by the `Retained`, so it must not be
+        // released again when closed.
+        unsafe { window.setReleasedWhenClosed(false) };
+        window.set_transparent();
+        window.set_window_level(level);
+        window.set_collection_behavior(NSWindowCollectionBehavior::Overlay);
+        unsafe { window.setHidesOnDeactivate(false) };
+        window
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_roundtrip() {
+        for kind in [
+            NSWindowLevelKind::Normal,
+            NSWindowLevelKind::Floating,
+            NSWindowLevelKind::Status,
+            NSWindowLevelKind::ScreenSaver,
+            NSWindowLevelKind::Other(42),
+        ] {
+            assert_eq!(NSWindowLevelKind::from_level(kind.level()), kind);
+        }
+        assert!(NSWindowLevelKind::Status.level() > NSWindowLevelKind::MainMenu.level());
+    }
+}
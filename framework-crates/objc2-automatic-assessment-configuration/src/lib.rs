@@ -16,5 +16,10 @@ extern crate alloc;
 extern crate std;
 
 mod generated;
+#[cfg(feature = "alloc")]
+mod session;
+
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(feature = "alloc")]
+pub use self::session::{AEAssessmentSessionEvent, AEAssessmentSessionHandler};
@@ -0,0 +1,193 @@
+//! Closure-based assessment session events.
+use alloc::boxed::Box;
+use core::fmt;
+
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2::{define_class, msg_send_id, DefinedClass, MainThreadMarker, MainThreadOnly, Message};
+use objc2_foundation::{NSError, NSObject, NSObjectProtocol};
+
+use crate::{AEAssessmentConfiguration, AEAssessmentSession, AEAssessmentSessionDelegate};
+
+/// An event in the lifecycle of an [`AEAssessmentSession`].
+///
+/// See [`AEAssessmentSession::set_event_handler`].
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub enum AEAssessmentSessionEvent<'a> {
+    /// The session began, and the device is now restricted.
+    #[doc(alias = "assessmentSessionDidBegin:")]
+    Began,
+    /// The session failed to begin.
+    #[doc(alias = "assessmentSession:failedToBeginWithError:")]
+    FailedToBegin(&'a NSError),
+    /// The session was interrupted, e.g. because the system lifted the
+    /// restrictions.
+    ///
+    /// The assessment should be stopped, and the session ended.
+    #[doc(alias = "assessmentSession:wasInterruptedWithError:")]
+    Interrupted(&'a NSError),
+    /// The session ended, and the restrictions were lifted.
+    #[doc(alias = "assessmentSessionDidEnd:")]
+    Ended,
+    /// The session was updated to a new configuration.
+    #[doc(alias = "assessmentSessionDidUpdate:")]
+    Updated,
+    /// The session failed to update to the given configuration.
+    #[doc(alias = "assessmentSession:failedToUpdateToConfiguration:error:")]
+    FailedToUpdate(&'a AEAssessmentConfiguration, &'a NSError),
+}
+
+type Handler = Box<dyn Fn(&AEAssessmentSession, AEAssessmentSessionEvent<'_>)>;
+
+define_class!(
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - `SessionDelegate` does not implement `Drop`.
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "__RustAEAssessmentSessionDelegate"]
+    #[ivars = Handler]
+    struct SessionDelegate;
+
+    unsafe impl NSObjectProtocol for SessionDelegate {}
+
+    unsafe impl AEAssessmentSessionDelegate for SessionDelegate {
+        #[method(assessmentSessionDidBegin:)]
+        fn did_begin(&self, session: &AEAssessmentSession) {
+            (self.ivars())(session, AEAssessmentSessionEvent::Began);
+        }
+
+        #[method(assessmentSession:failedToBeginWithError:)]
+        fn failed_to_begin(&self, session: &AEAssessmentSession, error: &NSError) {
+            (self.ivars())(session, AEAssessmentSessionEvent::FailedToBegin(error));
+        }
+
+        #[method(assessmentSession:wasInterruptedWithError:)]
+        fn was_interrupted(&self, session: &AEAssessmentSession, error: &NSError) {
+            (self.ivars())(session, AEAssessmentSessionEvent::Interrupted(error));
+        }
+
+        #[method(assessmentSessionDidEnd:)]
+        fn did_end(&self, session: &AEAssessmentSession) {
+            (self.ivars())(session, AEAssessmentSessionEvent::Ended);
+        }
+
+        #[method(assessmentSessionDidUpdate:)]
+        fn did_update(&self, session: &AEAssessmentSession) {
+            (self.ivars())(session, AEAssessmentSessionEvent::Updated);
+        }
+
+        #[method(assessmentSession:failedToUpdateToConfiguration:error:)]
+        fn failed_to_update(
+            &self,
+            session: &AEAssessmentSession,
+            configuration: &AEAssessmentConfiguration,
+            error: &NSError,
+        ) {
+            (self.ivars())(
+                session,
+                AEAssessmentSessionEvent::FailedToUpdate(configuration, error),
+            );
+        }
+    }
+);
+
+impl SessionDelegate {
+    fn new(handler: Handler, mtm: MainThreadMarker) -> Retained<Self> {
+        let this = Self::alloc(mtm).set_ivars(handler);
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+/// An event handler registered with
+/// [`AEAssessmentSession::set_event_handler`].
+///
+/// The handler is removed from the session when this is dropped, so this
+/// should be kept alive for as long as the session is active.
+#[must_use = "the handler is removed when this is dropped"]
+pub struct AEAssessmentSessionHandler {
+    session: Retained<AEAssessmentSession>,
+    delegate: Retained<SessionDelegate>,
+}
+
+impl AEAssessmentSessionHandler {
+    /// The session that the handler is registered with.
+    pub fn session(&self) -> &AEAssessmentSession {
+        &self.session
+    }
+}
+
+impl Drop for AEAssessmentSessionHandler {
+    fn drop(&mut self) {
+        // Only remove the delegate if it hasn't been replaced.
+        let current = unsafe { self.session.delegate() };
+        let ours: &ProtocolObject<dyn AEAssessmentSessionDelegate> =
+            ProtocolObject::from_ref(&*self.delegate);
+        if current
+            .as_deref()
+            .is_some_and(|current| core::ptr::eq(current, ours))
+        {
+            unsafe { self.session.setDelegate(None) };
+        }
+    }
+}
+
+impl fmt::Debug for AEAssessmentSessionHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AEAssessmentSessionHandler")
+            .field("session", &self.session)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AEAssessmentSession {
+    /// Call the closure whenever the session begins, ends, is interrupted or
+    /// fails.
+    ///
+    /// The session calls its delegate on the main thread, which is why this
+    /// requires a [`MainThreadMarker`]. The session does not retain its
+    /// delegate, so the handler is kept alive by the returned value.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2::{AllocAnyThread, MainThreadMarker};
+    /// use objc2_automatic_assessment_configuration::{
+    ///     AEAssessmentConfiguration, AEAssessmentSession, AEAssessmentSessionEvent,
+    /// };
+    ///
+    /// let mtm = MainThreadMarker::new().unwrap();
+    /// let configuration = unsafe { AEAssessmentConfiguration::new() };
+    /// let session = unsafe {
+    ///     AEAssessmentSession::initWithConfiguration(AEAssessmentSession::alloc(), &configuration)
+    /// };
+    ///
+    /// let _handler = session.set_event_handler(
+    ///     |session, event| match event {
+    ///         AEAssessmentSessionEvent::Began => println!("the assessment can start"),
+    ///         AEAssessmentSessionEvent::Interrupted(error) => {
+    ///             println!("session interrupted: {error}");
+    ///             unsafe { session.end() };
+    ///         }
+    ///         event => println!("{event:?}"),
+    ///     },
+    ///     mtm,
+    /// );
+    /// unsafe { session.begin() };
+    /// ```
+    #[doc(alias = "setDelegate:")]
+    pub fn set_event_handler(
+        &self,
+        handler: impl Fn(&AEAssessmentSession, AEAssessmentSessionEvent<'_>) + 'static,
+        mtm: MainThreadMarker,
+    ) -> AEAssessmentSessionHandler {
+        let delegate = SessionDelegate::new(Box::new(handler), mtm);
+        unsafe { self.setDelegate(Some(ProtocolObject::from_ref(&*delegate))) };
+        AEAssessmentSessionHandler {
+            session: self.retain(),
+            delegate,
+        }
+    }
+}
@@ -16,5 +16,7 @@ extern crate alloc;
 extern crate std;
 
 mod generated;
+#[cfg(feature = "std")]
+mod reporting;
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
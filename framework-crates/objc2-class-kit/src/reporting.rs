@@ -0,0 +1,248 @@
+//! Helpers for reporting progress on assigned activities.
+//!
+//! Reporting usually consists of three steps:
+//! 1. Find the context for the part of the app that the student is in, with
+//!    [`CLSDataStore::contexts_matching_identifier_path`].
+//! 2. Start an activity in it with [`CLSContext::start_activity`], and
+//!    report progress and scores on it.
+//! 3. Stop the activity with [`CLSContext::stop_activity`], and save the
+//!    changes with [`CLSDataStore::save`].
+#[cfg(all(
+    feature = "block2",
+    feature = "CLSContext",
+    feature = "CLSDataStore",
+    feature = "CLSObject"
+))]
+use alloc::vec::Vec;
+#[cfg(all(feature = "block2", feature = "CLSDataStore"))]
+use std::sync::{Mutex, PoisonError};
+
+#[cfg(any(
+    all(feature = "block2", feature = "CLSDataStore"),
+    all(feature = "CLSActivity", feature = "CLSContext", feature = "CLSObject"),
+    all(
+        feature = "CLSActivity",
+        feature = "CLSActivityItem",
+        feature = "CLSObject",
+        feature = "CLSScoreItem"
+    )
+))]
+use objc2::rc::Retained;
+#[cfg(all(
+    feature = "CLSActivity",
+    feature = "CLSActivityItem",
+    feature = "CLSObject",
+    feature = "CLSScoreItem"
+))]
+use objc2::AllocAnyThread;
+#[cfg(all(
+    feature = "block2",
+    feature = "CLSContext",
+    feature = "CLSDataStore",
+    feature = "CLSObject"
+))]
+use objc2_foundation::NSArray;
+#[cfg(all(feature = "block2", feature = "CLSDataStore"))]
+use objc2_foundation::NSError;
+#[cfg(any(
+    all(
+        feature = "block2",
+        feature = "CLSContext",
+        feature = "CLSDataStore",
+        feature = "CLSObject"
+    ),
+    all(
+        feature = "CLSActivity",
+        feature = "CLSActivityItem",
+        feature = "CLSObject",
+        feature = "CLSScoreItem"
+    )
+))]
+use objc2_foundation::NSString;
+
+#[cfg(all(feature = "CLSActivity", feature = "CLSObject"))]
+use crate::CLSActivity;
+#[cfg(any(
+    all(
+        feature = "block2",
+        feature = "CLSContext",
+        feature = "CLSDataStore",
+        feature = "CLSObject"
+    ),
+    all(feature = "CLSActivity", feature = "CLSContext", feature = "CLSObject")
+))]
+use crate::CLSContext;
+#[cfg(all(feature = "block2", feature = "CLSDataStore"))]
+use crate::CLSDataStore;
+#[cfg(all(
+    feature = "CLSActivity",
+    feature = "CLSActivityItem",
+    feature = "CLSObject",
+    feature = "CLSScoreItem"
+))]
+use crate::{CLSActivityItem, CLSScoreItem};
+
+/// Wrap a completion handler that is called at most once in a block.
+#[cfg(all(feature = "block2", feature = "CLSDataStore"))]
+fn once<A, F: FnOnce(A) + Send + 'static>(f: F) -> impl Fn(A) {
+    let f = Mutex::new(Some(f));
+    move |arg| {
+        let f = f.lock().unwrap_or_else(PoisonError::into_inner).take();
+        if let Some(f) = f {
+            f(arg);
+        }
+    }
+}
+
+#[cfg(all(feature = "block2", feature = "CLSDataStore"))]
+impl CLSDataStore {
+    /// Save the changes to all contexts and activities.
+    ///
+    /// The completion handler is called on a background queue once the
+    /// changes have been saved.
+    #[doc(alias = "saveWithCompletion:")]
+    pub fn save(&self, completion: impl FnOnce(Result<(), Retained<NSError>>) + Send + 'static) {
+        let completion = once(completion);
+        let block = block2::RcBlock::new(move |error: *mut NSError| {
+            // SAFETY: The error is either NULL or a valid `NSError`.
+            match unsafe { Retained::retain(error) } {
+                Some(error) => completion(Err(error)),
+                None => completion(Ok(())),
+            }
+        });
+        unsafe { self.saveWithCompletion(Some(&block)) };
+    }
+
+    /// Find the contexts along the given path of identifiers, starting from
+    /// the [main app context].
+    ///
+    /// Contexts along the path that do not exist yet are created with the
+    /// data store's delegate, or are missing from the result if there is no
+    /// delegate. The completion handler is called on a background queue.
+    ///
+    /// [main app context]: Self::mainAppContext
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use objc2_class_kit::CLSDataStore;
+    ///
+    /// let store = unsafe { CLSDataStore::shared() };
+    /// store.contexts_matching_identifier_path(&["math", "algebra", "quiz-1"], |contexts| {
+    ///     let quiz = contexts.unwrap().pop().unwrap();
+    ///     let activity = quiz.start_activity();
+    ///     activity.report_progress(0.5);
+    /// });
+    /// ```
+    #[cfg(all(feature = "CLSContext", feature = "CLSObject"))]
+    #[doc(alias = "contextsMatchingIdentifierPath:completion:")]
+    pub fn contexts_matching_identifier_path(
+        &self,
+        identifier_path: &[&str],
+        completion: impl FnOnce(Result<Vec<Retained<CLSContext>>, Retained<NSError>>) + Send + 'static,
+    ) {
+        let path: Vec<_> = identifier_path
+            .iter()
+            .map(|identifier| NSString::from_str(identifier))
+            .collect();
+        let path = NSArray::from_retained_slice(&path);
+
+        let completion = once(completion);
+        let block = block2::RcBlock::new(
+            move |contexts: core::ptr::NonNull<NSArray<CLSContext>>, error: *mut NSError| {
+                // SAFETY: The error is either NULL or a valid `NSError`, and
+                // the contexts are valid.
+                match unsafe { Retained::retain(error) } {
+                    Some(error) => completion(Err(error)),
+                    None => completion(Ok(unsafe { contexts.as_ref() }.to_vec())),
+                }
+            },
+        );
+        unsafe { self.contextsMatchingIdentifierPath_completion(&path, &block) };
+    }
+}
+
+#[cfg(all(feature = "CLSActivity", feature = "CLSContext", feature = "CLSObject"))]
+impl CLSContext {
+    /// Make the context active, and start its current activity, or a new
+    /// activity if it doesn't have one.
+    ///
+    /// Only one context can be active at a time, so this should be called
+    /// when the student navigates to the part of the app that the context
+    /// represents.
+    #[doc(alias = "becomeActive")]
+    #[doc(alias = "createNewActivity")]
+    pub fn start_activity(&self) -> Retained<CLSActivity> {
+        unsafe {
+            self.becomeActive();
+            let activity = self
+                .currentActivity()
+                .unwrap_or_else(|| self.createNewActivity());
+            activity.start();
+            activity
+        }
+    }
+
+    /// Stop the current activity, if any, and make the context inactive.
+    ///
+    /// This should be called when the student leaves the part of the app
+    /// that the context represents.
+    #[doc(alias = "resignActive")]
+    pub fn stop_activity(&self) {
+        unsafe {
+            if let Some(activity) = self.currentActivity() {
+                activity.stop();
+            }
+            self.resignActive();
+        }
+    }
+}
+
+#[cfg(all(feature = "CLSActivity", feature = "CLSObject"))]
+impl CLSActivity {
+    /// Report how far the student has progressed through the activity,
+    /// between `0.0` and `1.0`.
+    ///
+    /// Values outside of that range are clamped.
+    #[doc(alias = "setProgress:")]
+    pub fn report_progress(&self, progress: f64) {
+        unsafe { self.setProgress(progress.clamp(0.0, 1.0)) };
+    }
+
+    /// Report the score that the student achieved.
+    ///
+    /// The first item reported becomes the [primary activity item], which is
+    /// the one shown to teachers, while subsequent items are added as
+    /// additional items.
+    ///
+    /// [primary activity item]: Self::primaryActivityItem
+    #[cfg(all(feature = "CLSActivityItem", feature = "CLSScoreItem"))]
+    #[doc(alias = "initWithIdentifier:title:score:maxScore:")]
+    pub fn report_score(
+        &self,
+        identifier: &str,
+        title: &str,
+        score: f64,
+        max_score: f64,
+    ) -> Retained<CLSScoreItem> {
+        let item = unsafe {
+            CLSScoreItem::initWithIdentifier_title_score_maxScore(
+                CLSScoreItem::alloc(),
+                &NSString::from_str(identifier),
+                &NSString::from_str(title),
+                score,
+                max_score,
+            )
+        };
+        let activity_item: &CLSActivityItem = &item;
+        unsafe {
+            if self.primaryActivityItem().is_none() {
+                self.setPrimaryActivityItem(Some(activity_item));
+            } else {
+                self.addAdditionalActivityItem(activity_item);
+            }
+        }
+        item
+    }
+}
@@ -0,0 +1,50 @@
+//! Helpers for hand-written extensions in this and other framework crates.
+
+// Note: While this is not public, it is still a breaking change to remove
+// entries in here, since framework crates rely on it.
+
+use core::ffi::c_void;
+use core::ptr::NonNull;
+
+use crate::{CFRetained, CFType, ConcreteType};
+
+/// Convert a reference to the untyped value pointer that CoreFoundation's
+/// collection functions take.
+#[inline]
+pub fn as_value<T>(value: &T) -> *const c_void {
+    let ptr: *const T = value;
+    ptr.cast()
+}
+
+/// Retain a value retrieved from a CoreFoundation collection, checking that
+/// it is of type `T`.
+///
+/// `collection` and `what` are used in the panic message, e.g. `"dictionary"`
+/// and `"key"`.
+///
+///
+/// # Panics
+///
+/// Panics if the pointer is NULL, or if the value is not of type `T`.
+///
+///
+/// # Safety
+///
+/// The pointer must be NULL or a valid CoreFoundation object.
+pub unsafe fn retain_checked<T: ConcreteType>(
+    ptr: *const c_void,
+    collection: &str,
+    what: &str,
+) -> CFRetained<T> {
+    let ptr = NonNull::new(ptr.cast_mut().cast::<CFType>())
+        .unwrap_or_else(|| panic!("{collection} contained NULL {what}"));
+    // SAFETY: Upheld by the caller.
+    let value: &CFType = unsafe { ptr.as_ref() };
+    let value = value.downcast_ref::<T>().unwrap_or_else(|| {
+        panic!(
+            "{collection} contained {what} of wrong type, expected {}",
+            core::any::type_name::<T>(),
+        )
+    });
+    value.retain()
+}
@@ -0,0 +1,247 @@
+//! Allocators that delegate to Rust's allocators.
+use alloc::boxed::Box;
+use core::alloc::{GlobalAlloc, Layout};
+use core::ffi::c_void;
+use core::ptr;
+
+use crate::{
+    CFAllocator, CFAllocatorContext, CFAllocatorCreate, CFIndex, CFOptionFlags, CFRetained,
+};
+
+/// The alignment of the returned blocks, which matches that of `malloc`.
+const ALIGN: usize = 16;
+
+/// Space for storing the size of the block in front of it, since
+/// `CFAllocatorDeallocateCallBack` is not given the size, but
+/// [`GlobalAlloc::dealloc`] requires it.
+const HEADER: usize = ALIGN;
+
+fn layout(size: usize) -> Option<Layout> {
+    Layout::from_size_align(size.checked_add(HEADER)?, ALIGN).ok()
+}
+
+/// Store the size in the header of the block, and return the user part.
+///
+/// # Safety
+///
+/// `block` must be null, or an allocation of at least `HEADER` bytes.
+unsafe fn finish(block: *mut u8, size: usize) -> *mut c_void {
+    if block.is_null() {
+        return ptr::null_mut();
+    }
+    // SAFETY: The block is aligned to `ALIGN` and has room for the header.
+    unsafe { block.cast::<usize>().write(size) };
+    // SAFETY: The block is at least `HEADER` bytes large.
+    unsafe { block.add(HEADER) }.cast()
+}
+
+/// Get the start of the block and its layout from the user part.
+///
+/// # Safety
+///
+/// `ptr` must have been returned by `finish`.
+unsafe fn start(ptr: *mut c_void) -> (*mut u8, Layout) {
+    // SAFETY: Upheld by the caller.
+    let block = unsafe { ptr.cast::<u8>().sub(HEADER) };
+    let size = unsafe { block.cast::<usize>().read() };
+    // The layout was valid when the block was allocated.
+    (block, layout(size).unwrap())
+}
+
+unsafe extern "C-unwind" fn allocate<A: GlobalAlloc>(
+    size: CFIndex,
+    _hint: CFOptionFlags,
+    info: *mut c_void,
+) -> *mut c_void {
+    // SAFETY: The info is the boxed allocator, which lives until the
+    // `CFAllocator` is deallocated.
+    let allocator = unsafe { &*info.cast::<A>() };
+    let Some(layout) = usize::try_from(size)
+        .ok()
+        .filter(|&size| size != 0)
+        .and_then(layout)
+    else {
+        return ptr::null_mut();
+    };
+    // SAFETY: The layout has a non-zero size.
+    let block = unsafe { allocator.alloc(layout) };
+    // SAFETY: The block was allocated with `layout`.
+    unsafe { finish(block, layout.size() - HEADER) }
+}
+
+unsafe extern "C-unwind" fn reallocate<A: GlobalAlloc>(
+    ptr: *mut c_void,
+    new_size: CFIndex,
+    _hint: CFOptionFlags,
+    info: *mut c_void,
+) -> *mut c_void {
+    // SAFETY: Same as in `allocate`.
+    let allocator = unsafe { &*info.cast::<A>() };
+    // CoreFoundation handles null pointers and zero sizes itself, but be
+    // defensive, since returning null leaves the old block untouched.
+    if ptr.is_null() {
+        return ptr::null_mut();
+    }
+    let Some(new_layout) = usize::try_from(new_size)
+        .ok()
+        .filter(|&size| size != 0)
+        .and_then(layout)
+    else {
+        return ptr::null_mut();
+    };
+    // SAFETY: The pointer was returned by `allocate` or `reallocate`.
+    let (block, layout) = unsafe { start(ptr) };
+    // SAFETY: The block was allocated by this allocator with `layout`, and
+    // the new size is non-zero and does not overflow when rounded up.
+    let block = unsafe { allocator.realloc(block, layout, new_layout.size()) };
+    // SAFETY: The block was allocated with the size of `new_layout`.
+    unsafe { finish(block, new_layout.size() - HEADER) }
+}
+
+unsafe extern "C-unwind" fn deallocate<A: GlobalAlloc>(ptr: *mut c_void, info: *mut c_void) {
+    // SAFETY: Same as in `allocate`.
+    let allocator = unsafe { &*info.cast::<A>() };
+    if ptr.is_null() {
+        return;
+    }
+    // SAFETY: The pointer was returned by `allocate` or `reallocate`.
+    let (block, layout) = unsafe { start(ptr) };
+    // SAFETY: The block was allocated by this allocator with `layout`.
+    unsafe { allocator.dealloc(block, layout) };
+}
+
+unsafe extern "C-unwind" fn release<A>(info: *const c_void) {
+    // SAFETY: The info was created with `Box::into_raw` when creating the
+    // allocator, and CoreFoundation releases it exactly once, when the
+    // allocator is deallocated.
+    drop(unsafe { Box::from_raw(info.cast::<A>().cast_mut()) });
+}
+
+/// The allocator registered with `#[global_allocator]`.
+struct RustGlobal;
+
+unsafe impl GlobalAlloc for RustGlobal {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { alloc::alloc::alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { alloc::alloc::dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        unsafe { alloc::alloc::realloc(ptr, layout, new_size) }
+    }
+}
+
+impl CFAllocator {
+    /// Create an allocator that delegates to the given Rust allocator, e.g.
+    /// to account for the memory used by CoreFoundation objects.
+    ///
+    /// The allocator is moved into the `CFAllocator`, and dropped when that
+    /// is deallocated, which happens once all objects created with it have
+    /// been deallocated (as those retain the allocator).
+    ///
+    /// The returned allocator can be passed to the `Create` functions.
+    /// Blocks are aligned to 16 bytes, like with `malloc`.
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "CFData")] {
+    /// use std::alloc::System;
+    /// use objc2_core_foundation::{CFAllocator, CFDataCreate};
+    ///
+    /// let allocator = CFAllocator::from_global_alloc(System);
+    /// let data = unsafe { CFDataCreate(Some(&allocator), [1, 2, 3].as_ptr(), 3) }.unwrap();
+    /// assert_eq!(data.to_vec(), [1, 2, 3]);
+    /// # }
+    /// ```
+    #[doc(alias = "CFAllocatorCreate")]
+    pub fn from_global_alloc<A: GlobalAlloc + Send + Sync + 'static>(
+        allocator: A,
+    ) -> CFRetained<Self> {
+        let info = Box::into_raw(Box::new(allocator));
+        let mut context = CFAllocatorContext {
+            version: 0,
+            info: info.cast(),
+            retain: None,
+            release: Some(release::<A>),
+            copyDescription: None,
+            allocate: Some(allocate::<A>),
+            reallocate: Some(reallocate::<A>),
+            deallocate: Some(deallocate::<A>),
+            preferredSize: None,
+        };
+        // SAFETY: The context is valid, and the allocator is `Send + Sync`,
+        // so the callbacks can be called from any thread.
+        let allocator = unsafe { CFAllocatorCreate(None, &mut context) };
+        let Some(allocator) = allocator else {
+            // SAFETY: The allocator was not created, so we still own the info.
+            drop(unsafe { Box::from_raw(info) });
+            panic!("failed creating CFAllocator");
+        };
+        allocator
+    }
+
+    /// Create an allocator that delegates to Rust's global allocator, i.e.
+    /// the one registered with `#[global_allocator]`.
+    #[doc(alias = "CFAllocatorCreate")]
+    pub fn rust_global() -> CFRetained<Self> {
+        Self::from_global_alloc(RustGlobal)
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "CFData", feature = "std"))]
+mod tests {
+    use std::alloc::System;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{CFDataCreate, CFDataCreateMutable, CFDataIncreaseLength};
+
+    struct Counting(Arc<AtomicUsize>);
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            self.0.fetch_add(layout.size(), Ordering::Relaxed);
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            self.0.fetch_sub(layout.size(), Ordering::Relaxed);
+            unsafe { System.dealloc(ptr, layout) }
+        }
+    }
+
+    #[test]
+    fn counting() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let allocator = CFAllocator::from_global_alloc(Counting(count.clone()));
+
+        let data = unsafe { CFDataCreate(Some(&allocator), [1, 2, 3].as_ptr(), 3) }.unwrap();
+        assert_eq!(data.to_vec(), [1, 2, 3]);
+        assert_ne!(count.load(Ordering::Relaxed), 0);
+
+        let mutable = unsafe { CFDataCreateMutable(Some(&allocator), 0) }.unwrap();
+        unsafe { CFDataIncreaseLength(Some(&mutable), 1000) };
+
+        drop(data);
+        drop(mutable);
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+
+        // The allocator is dropped along with the `CFAllocator`.
+        drop(allocator);
+        assert_eq!(Arc::strong_count(&count), 1);
+    }
+
+    #[test]
+    fn rust_global() {
+        let allocator = CFAllocator::rust_global();
+        let data = unsafe { CFDataCreate(Some(&allocator), [4, 5].as_ptr(), 2) }.unwrap();
+        assert_eq!(data.to_vec(), [4, 5]);
+    }
+}
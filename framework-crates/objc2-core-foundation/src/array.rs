@@ -0,0 +1,457 @@
+//! Typed views of `CFArray` and `CFMutableArray`.
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use crate::__cf_helpers::{as_value, retain_checked};
+use crate::{
+    kCFTypeArrayCallBacks, CFArray, CFArrayAppendValue, CFArrayCreate, CFArrayCreateMutable,
+    CFArrayGetCount, CFArrayGetValueAtIndex, CFArrayInsertValueAtIndex, CFArrayRemoveAllValues,
+    CFArrayRemoveValueAtIndex, CFArraySetValueAtIndex, CFIndex, CFMutableArray, CFRetained, CFType,
+    ConcreteType, Type,
+};
+
+impl CFArray {
+    /// The number of values in the array.
+    #[inline]
+    #[doc(alias = "CFArrayGetCount")]
+    pub fn len(&self) -> usize {
+        unsafe { CFArrayGetCount(self) as _ }
+    }
+
+    /// Whether the array is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// View the array as an array of `T`.
+    ///
+    /// The array is not checked here, instead each value is checked to be of
+    /// type `T` when it is accessed, see [`CFTypedArray::get`].
+    #[inline]
+    pub fn as_typed<T: ConcreteType>(&self) -> &CFTypedArray<T> {
+        let ptr: *const Self = self;
+        // SAFETY: `CFTypedArray` is `#[repr(transparent)]` over `CFArray`,
+        // and does not have any further invariants.
+        unsafe { &*ptr.cast() }
+    }
+}
+
+impl CFMutableArray {
+    /// View the array as a mutable array of `T`.
+    ///
+    /// See [`CFArray::as_typed`].
+    #[inline]
+    pub fn as_typed<T: ConcreteType>(&self) -> &CFTypedMutableArray<T> {
+        let ptr: *const Self = self;
+        // SAFETY: `CFTypedMutableArray` is `#[repr(transparent)]` over
+        // `CFMutableArray`, and does not have any further invariants.
+        unsafe { &*ptr.cast() }
+    }
+}
+
+/// A [`CFArray`] whose values are of type `T`.
+///
+/// CoreFoundation arrays are untyped, so this is only a view: each value is
+/// checked with `CFGetTypeID` when it is accessed. Arrays created with the
+/// constructors here use the `CFType` callbacks, and thus retain their
+/// values.
+///
+/// This can be created from an existing array with [`CFArray::as_typed`], or
+/// from Rust values with [`from_slice`](Self::from_slice) or by collecting
+/// an iterator.
+///
+///
+/// # Examples
+///
+/// ```
+/// use objc2_core_foundation::{CFRetained, CFString, CFTypedArray};
+///
+/// let array: CFRetained<CFTypedArray<CFString>> = ["a", "b"]
+///     .into_iter()
+///     .map(CFString::from_str)
+///     .collect();
+/// assert_eq!(array.len(), 2);
+/// assert_eq!(array.get(1).unwrap().to_string(), "b");
+///
+/// for string in &*array {
+///     println!("{}", &*string);
+/// }
+/// ```
+#[repr(transparent)]
+#[doc(alias = "CFArray")]
+pub struct CFTypedArray<T: ?Sized> {
+    array: CFArray,
+    value: PhantomData<T>,
+}
+
+// SAFETY: The array is a `CFArray`.
+unsafe impl<T: ?Sized> Type for CFTypedArray<T> {}
+
+impl<T: ?Sized> Deref for CFTypedArray<T> {
+    type Target = CFArray;
+
+    #[inline]
+    fn deref(&self) -> &CFArray {
+        &self.array
+    }
+}
+
+impl<T: ?Sized> AsRef<CFArray> for CFTypedArray<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFArray {
+        self
+    }
+}
+
+impl<T: ?Sized> AsRef<CFType> for CFTypedArray<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFType {
+        self // Through Deref
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for CFTypedArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.array, f)
+    }
+}
+
+impl<T: ConcreteType> CFTypedArray<T> {
+    /// Create an array containing the given values.
+    #[doc(alias = "CFArrayCreate")]
+    pub fn from_slice(values: &[&T]) -> CFRetained<Self> {
+        let len = values.len().try_into().expect("slice too large");
+        // `&T` is ABI compatible with `*const c_void`, and `CFArrayCreate`
+        // does not modify the values.
+        let ptr = values.as_ptr().cast_mut().cast::<*const c_void>();
+        let array = unsafe { CFArrayCreate(None, ptr, len, &kCFTypeArrayCallBacks) }
+            .expect("failed creating CFArray");
+        // SAFETY: The array contains values of type `T`.
+        unsafe { CFRetained::cast_unchecked(array) }
+    }
+
+    /// Get the value at the given index, or [`None`] if the index is out of
+    /// bounds.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not of type `T`.
+    #[doc(alias = "CFArrayGetValueAtIndex")]
+    pub fn get(&self, index: usize) -> Option<CFRetained<T>> {
+        if index >= self.len() {
+            return None;
+        }
+        let ptr = unsafe { CFArrayGetValueAtIndex(self, index as CFIndex) };
+        // SAFETY: The array retains its values, so the pointer is valid.
+        Some(unsafe { retain_checked(ptr, "array", "value") })
+    }
+
+    /// An iterator over the values in the array.
+    ///
+    /// The iterator panics if a value is not of type `T`.
+    #[inline]
+    pub fn iter(&self) -> CFTypedArrayIter<'_, T> {
+        CFTypedArrayIter {
+            array: self,
+            index: 0,
+        }
+    }
+
+    /// Copy the values of the array into a new [`Vec`].
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value is not of type `T`.
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<CFRetained<T>> {
+        self.iter().collect()
+    }
+}
+
+impl<'a, T: ConcreteType> IntoIterator for &'a CFTypedArray<T> {
+    type Item = CFRetained<T>;
+    type IntoIter = CFTypedArrayIter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: ConcreteType> FromIterator<CFRetained<T>> for CFRetained<CFTypedArray<T>> {
+    fn from_iter<I: IntoIterator<Item = CFRetained<T>>>(iter: I) -> Self {
+        let array: CFRetained<CFTypedMutableArray<T>> = iter.into_iter().collect();
+        // SAFETY: A mutable array is an array.
+        unsafe { CFRetained::cast_unchecked(array) }
+    }
+}
+
+impl<'a, T: ConcreteType> FromIterator<&'a T> for CFRetained<CFTypedArray<T>> {
+    fn from_iter<I: IntoIterator<Item = &'a T>>(iter: I) -> Self {
+        let array: CFRetained<CFTypedMutableArray<T>> = iter.into_iter().collect();
+        // SAFETY: A mutable array is an array.
+        unsafe { CFRetained::cast_unchecked(array) }
+    }
+}
+
+/// An iterator over the values of a [`CFTypedArray`].
+///
+/// Created by [`CFTypedArray::iter`].
+#[derive(Debug)]
+pub struct CFTypedArrayIter<'a, T: ?Sized> {
+    array: &'a CFTypedArray<T>,
+    index: usize,
+}
+
+impl<T: ConcreteType> Iterator for CFTypedArrayIter<'_, T> {
+    type Item = CFRetained<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<CFRetained<T>> {
+        // The length is checked on every access, in case the array is
+        // mutated while iterating.
+        let value = self.array.get(self.index)?;
+        self.index += 1;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.array.len().saturating_sub(self.index);
+        (len, Some(len))
+    }
+}
+
+impl<T: ConcreteType> ExactSizeIterator for CFTypedArrayIter<'_, T> {}
+
+impl<T: ConcreteType> FusedIterator for CFTypedArrayIter<'_, T> {}
+
+/// A [`CFMutableArray`] whose values are of type `T`.
+///
+/// See [`CFTypedArray`] for details.
+#[repr(transparent)]
+#[doc(alias = "CFMutableArray")]
+pub struct CFTypedMutableArray<T: ?Sized> {
+    array: CFMutableArray,
+    value: PhantomData<T>,
+}
+
+// SAFETY: The array is a `CFMutableArray`.
+unsafe impl<T: ?Sized> Type for CFTypedMutableArray<T> {}
+
+impl<T: ?Sized> Deref for CFTypedMutableArray<T> {
+    type Target = CFTypedArray<T>;
+
+    #[inline]
+    fn deref(&self) -> &CFTypedArray<T> {
+        let ptr: *const Self = self;
+        // SAFETY: A mutable array is an array, and both types are
+        // `#[repr(transparent)]`.
+        unsafe { &*ptr.cast() }
+    }
+}
+
+impl<T: ?Sized> AsRef<CFMutableArray> for CFTypedMutableArray<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFMutableArray {
+        &self.array
+    }
+}
+
+impl<T: ?Sized> AsRef<CFArray> for CFTypedMutableArray<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFArray {
+        self // Through Deref
+    }
+}
+
+impl<T: ?Sized> AsRef<CFType> for CFTypedMutableArray<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFType {
+        self // Through Deref
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for CFTypedMutableArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.array, f)
+    }
+}
+
+impl<T: ConcreteType> CFTypedMutableArray<T> {
+    /// Create a new empty array.
+    #[inline]
+    #[doc(alias = "CFArrayCreateMutable")]
+    pub fn new() -> CFRetained<Self> {
+        Self::with_capacity(0)
+    }
+
+    /// Create a new empty array, that can contain at most `capacity`
+    /// values.
+    ///
+    /// A capacity of zero means that the array is unbounded.
+    #[doc(alias = "CFArrayCreateMutable")]
+    pub fn with_capacity(capacity: usize) -> CFRetained<Self> {
+        let capacity = capacity.try_into().expect("capacity too large");
+        let array = unsafe { CFArrayCreateMutable(None, capacity, &kCFTypeArrayCallBacks) }
+            .expect("failed creating CFMutableArray");
+        // SAFETY: The array is empty.
+        unsafe { CFRetained::cast_unchecked(array) }
+    }
+
+    /// Add a value to the end of the array.
+    #[inline]
+    #[doc(alias = "CFArrayAppendValue")]
+    pub fn push(&self, value: &T) {
+        unsafe { CFArrayAppendValue(Some(&self.array), as_value(value)) };
+    }
+
+    /// Insert a value at the given index, shifting the values after it.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    #[doc(alias = "CFArrayInsertValueAtIndex")]
+    pub fn insert(&self, index: usize, value: &T) {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "insertion index {index} out of bounds of {len}"
+        );
+        unsafe { CFArrayInsertValueAtIndex(Some(&self.array), index as CFIndex, as_value(value)) };
+    }
+
+    /// Replace the value at the given index.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    #[doc(alias = "CFArraySetValueAtIndex")]
+    pub fn set(&self, index: usize, value: &T) {
+        let len = self.len();
+        assert!(index < len, "index {index} out of bounds of {len}");
+        unsafe { CFArraySetValueAtIndex(Some(&self.array), index as CFIndex, as_value(value)) };
+    }
+
+    /// Remove the value at the given index, shifting the values after it.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    #[doc(alias = "CFArrayRemoveValueAtIndex")]
+    pub fn remove(&self, index: usize) {
+        let len = self.len();
+        assert!(index < len, "removal index {index} out of bounds of {len}");
+        unsafe { CFArrayRemoveValueAtIndex(Some(&self.array), index as CFIndex) };
+    }
+
+    /// Remove all values from the array.
+    #[inline]
+    #[doc(alias = "CFArrayRemoveAllValues")]
+    pub fn clear(&self) {
+        unsafe { CFArrayRemoveAllValues(Some(&self.array)) };
+    }
+}
+
+impl<T: ConcreteType> FromIterator<CFRetained<T>> for CFRetained<CFTypedMutableArray<T>> {
+    fn from_iter<I: IntoIterator<Item = CFRetained<T>>>(iter: I) -> Self {
+        let array = CFTypedMutableArray::<T>::new();
+        for value in iter {
+            array.push(&value);
+        }
+        array
+    }
+}
+
+impl<'a, T: ConcreteType> FromIterator<&'a T> for CFRetained<CFTypedMutableArray<T>> {
+    fn from_iter<I: IntoIterator<Item = &'a T>>(iter: I) -> Self {
+        let array = CFTypedMutableArray::<T>::new();
+        for value in iter {
+            array.push(value);
+        }
+        array
+    }
+}
+
+#[cfg(all(test, feature = "CFNumber", feature = "CFString"))]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::{CFNumber, CFString};
+
+    #[test]
+    fn from_slice() {
+        let a = CFString::from_str("a");
+        let b = CFString::from_str("b");
+        let array = CFTypedArray::from_slice(&[&*a, &*b]);
+        assert_eq!(array.len(), 2);
+        assert_eq!(*array.get(0).unwrap(), *a);
+        assert_eq!(*array.get(1).unwrap(), *b);
+        assert!(array.get(2).is_none());
+    }
+
+    #[test]
+    fn collect_and_iterate() {
+        let array: CFRetained<CFTypedArray<CFNumber>> = (0..5).map(CFNumber::new_i32).collect();
+        assert_eq!(array.iter().len(), 5);
+        let values: Vec<_> = array.iter().map(|n| n.as_i32().unwrap()).collect();
+        assert_eq!(values, [0, 1, 2, 3, 4]);
+
+        let empty: CFRetained<CFTypedArray<CFNumber>> = core::iter::empty::<&CFNumber>().collect();
+        assert!(empty.is_empty());
+        assert!(empty.iter().next().is_none());
+    }
+
+    #[test]
+    fn mutate() {
+        let array = CFTypedMutableArray::<CFString>::new();
+        array.push(&CFString::from_str("b"));
+        array.insert(0, &CFString::from_str("a"));
+        array.push(&CFString::from_str("d"));
+        array.set(2, &CFString::from_str("c"));
+        let strings: Vec<_> = array.iter().map(|s| s.to_string()).collect();
+        assert_eq!(strings, ["a", "b", "c"]);
+
+        array.remove(1);
+        assert_eq!(array.to_vec().len(), 2);
+        array.clear();
+        assert!(array.is_empty());
+    }
+
+    #[test]
+    fn untyped() {
+        let array: CFRetained<CFTypedArray<CFString>> =
+            [CFString::from_str("a")].into_iter().collect();
+        let untyped: &CFArray = &array;
+        assert_eq!(untyped.len(), 1);
+        assert_eq!(
+            untyped.as_typed::<CFString>().get(0).unwrap().to_string(),
+            "a"
+        );
+    }
+
+    #[test]
+    #[should_panic = "array contained value of wrong type"]
+    fn wrong_type() {
+        let array = CFTypedArray::from_slice(&[&*CFString::from_str("a")]);
+        let _ = array.as_typed::<CFNumber>().get(0);
+    }
+
+    #[test]
+    #[should_panic = "out of bounds"]
+    fn remove_out_of_bounds() {
+        CFTypedMutableArray::<CFString>::new().remove(0);
+    }
+}
@@ -0,0 +1,404 @@
+//! Typed views of `CFBag` and `CFMutableBag`.
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use crate::__cf_helpers::as_value;
+#[cfg(feature = "alloc")]
+use crate::__cf_helpers::retain_checked;
+use crate::{
+    kCFTypeBagCallBacks, CFBag, CFBagAddValue, CFBagContainsValue, CFBagCreate, CFBagCreateMutable,
+    CFBagGetCount, CFBagGetCountOfValue, CFBagRemoveAllValues, CFBagRemoveValue, CFIndex,
+    CFMutableBag, CFRetained, CFType, ConcreteType, Type,
+};
+
+impl CFBag {
+    /// The number of values in the bag.
+    #[inline]
+    #[doc(alias = "CFBagGetCount")]
+    pub fn len(&self) -> usize {
+        unsafe { CFBagGetCount(self) as _ }
+    }
+
+    /// Whether the bag is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// View the bag as a bag of `T`.
+    ///
+    /// The bag is not checked here, instead each value is checked to be of
+    /// type `T` when it is accessed, see [`CFTypedBag::iter`].
+    #[inline]
+    pub fn as_typed<T: ConcreteType>(&self) -> &CFTypedBag<T> {
+        let ptr: *const Self = self;
+        // SAFETY: `CFTypedBag` is `#[repr(transparent)]` over `CFBag`, and
+        // does not have any further invariants.
+        unsafe { &*ptr.cast() }
+    }
+}
+
+impl CFMutableBag {
+    /// View the bag as a mutable bag of `T`.
+    ///
+    /// See [`CFBag::as_typed`].
+    #[inline]
+    pub fn as_typed<T: ConcreteType>(&self) -> &CFTypedMutableBag<T> {
+        let ptr: *const Self = self;
+        // SAFETY: `CFTypedMutableBag` is `#[repr(transparent)]` over
+        // `CFMutableBag`, and does not have any further invariants.
+        unsafe { &*ptr.cast() }
+    }
+}
+
+/// A [`CFBag`] whose values are of type `T`.
+///
+/// A bag is an unordered collection that, unlike a set, may contain the same
+/// value multiple times.
+///
+/// CoreFoundation bags are untyped, so this is only a view: each value is
+/// checked with `CFGetTypeID` when it is accessed. Bags created with the
+/// constructors here use the `CFType` callbacks, and thus retain their
+/// values, and compare them with `CFEqual` and `CFHash`.
+///
+///
+/// # Examples
+///
+/// ```
+/// use objc2_core_foundation::{CFRetained, CFString, CFTypedBag};
+///
+/// let bag: CFRetained<CFTypedBag<CFString>> = ["a", "b", "a"]
+///     .into_iter()
+///     .map(CFString::from_str)
+///     .collect();
+/// assert_eq!(bag.len(), 3);
+/// assert_eq!(bag.count(&CFString::from_str("a")), 2);
+/// assert!(!bag.contains(&CFString::from_str("c")));
+/// ```
+#[repr(transparent)]
+#[doc(alias = "CFBag")]
+pub struct CFTypedBag<T: ?Sized> {
+    bag: CFBag,
+    value: PhantomData<T>,
+}
+
+// SAFETY: The bag is a `CFBag`.
+unsafe impl<T: ?Sized> Type for CFTypedBag<T> {}
+
+impl<T: ?Sized> Deref for CFTypedBag<T> {
+    type Target = CFBag;
+
+    #[inline]
+    fn deref(&self) -> &CFBag {
+        &self.bag
+    }
+}
+
+impl<T: ?Sized> AsRef<CFBag> for CFTypedBag<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFBag {
+        self
+    }
+}
+
+impl<T: ?Sized> AsRef<CFType> for CFTypedBag<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFType {
+        self // Through Deref
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for CFTypedBag<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.bag, f)
+    }
+}
+
+impl<T: ConcreteType> CFTypedBag<T> {
+    /// Create a bag containing the given values.
+    #[doc(alias = "CFBagCreate")]
+    pub fn from_slice(values: &[&T]) -> CFRetained<Self> {
+        let len = values.len().try_into().expect("slice too large");
+        // `&T` is ABI compatible with `*const c_void`, and `CFBagCreate`
+        // does not modify the values.
+        let ptr = values.as_ptr().cast_mut().cast::<*const c_void>();
+        let bag = unsafe { CFBagCreate(None, ptr, len, &kCFTypeBagCallBacks) }
+            .expect("failed creating CFBag");
+        // SAFETY: The bag contains values of type `T`.
+        unsafe { CFRetained::cast_unchecked(bag) }
+    }
+
+    /// Whether the bag contains the given value.
+    #[inline]
+    #[doc(alias = "CFBagContainsValue")]
+    pub fn contains(&self, value: &T) -> bool {
+        unsafe { CFBagContainsValue(self, as_value(value)) != 0 }
+    }
+
+    /// The number of times the given value occurs in the bag.
+    #[inline]
+    #[doc(alias = "CFBagGetCountOfValue")]
+    pub fn count(&self, value: &T) -> usize {
+        let count: CFIndex = unsafe { CFBagGetCountOfValue(self, as_value(value)) };
+        count as _
+    }
+
+    /// An iterator over the values in the bag, in an unspecified order.
+    ///
+    /// The values are copied out of the bag when this is called, so the
+    /// iterator is not affected by later mutations.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value is not of type `T`.
+    #[cfg(feature = "alloc")]
+    #[doc(alias = "CFBagGetValues")]
+    pub fn iter(&self) -> CFTypedBagIter<T> {
+        let len = self.len();
+        let mut values = Vec::with_capacity(len);
+        unsafe {
+            crate::CFBagGetValues(self, values.as_mut_ptr());
+            // SAFETY: The buffer was large enough, and has been filled.
+            values.set_len(len);
+        }
+        let values: Vec<_> = values
+            .into_iter()
+            // SAFETY: The bag retains its values, so the pointers are valid.
+            .map(|value| unsafe { retain_checked(value, "bag", "value") })
+            .collect();
+        CFTypedBagIter {
+            inner: values.into_iter(),
+        }
+    }
+
+    /// Copy the values of the bag into a new [`Vec`], in an unspecified
+    /// order.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value is not of type `T`.
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<CFRetained<T>> {
+        self.iter().collect()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ConcreteType> IntoIterator for &CFTypedBag<T> {
+    type Item = CFRetained<T>;
+    type IntoIter = CFTypedBagIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: ConcreteType> FromIterator<CFRetained<T>> for CFRetained<CFTypedBag<T>> {
+    fn from_iter<I: IntoIterator<Item = CFRetained<T>>>(iter: I) -> Self {
+        let bag: CFRetained<CFTypedMutableBag<T>> = iter.into_iter().collect();
+        // SAFETY: A mutable bag is a bag.
+        unsafe { CFRetained::cast_unchecked(bag) }
+    }
+}
+
+impl<'a, T: ConcreteType> FromIterator<&'a T> for CFRetained<CFTypedBag<T>> {
+    fn from_iter<I: IntoIterator<Item = &'a T>>(iter: I) -> Self {
+        let bag: CFRetained<CFTypedMutableBag<T>> = iter.into_iter().collect();
+        // SAFETY: A mutable bag is a bag.
+        unsafe { CFRetained::cast_unchecked(bag) }
+    }
+}
+
+/// An iterator over the values of a [`CFTypedBag`].
+///
+/// Created by [`CFTypedBag::iter`].
+#[cfg(feature = "alloc")]
+pub struct CFTypedBagIter<T> {
+    inner: alloc::vec::IntoIter<CFRetained<T>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T> fmt::Debug for CFTypedBagIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CFTypedBagIter")
+            .field("remaining", &self.inner.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Type> Iterator for CFTypedBagIter<T> {
+    type Item = CFRetained<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Type> ExactSizeIterator for CFTypedBagIter<T> {}
+
+#[cfg(feature = "alloc")]
+impl<T: Type> core::iter::FusedIterator for CFTypedBagIter<T> {}
+
+/// A [`CFMutableBag`] whose values are of type `T`.
+///
+/// See [`CFTypedBag`] for details.
+#[repr(transparent)]
+#[doc(alias = "CFMutableBag")]
+pub struct CFTypedMutableBag<T: ?Sized> {
+    bag: CFMutableBag,
+    value: PhantomData<T>,
+}
+
+// SAFETY: The bag is a `CFMutableBag`.
+unsafe impl<T: ?Sized> Type for CFTypedMutableBag<T> {}
+
+impl<T: ?Sized> Deref for CFTypedMutableBag<T> {
+    type Target = CFTypedBag<T>;
+
+    #[inline]
+    fn deref(&self) -> &CFTypedBag<T> {
+        let ptr: *const Self = self;
+        // SAFETY: A mutable bag is a bag, and both types are
+        // `#[repr(transparent)]`.
+        unsafe { &*ptr.cast() }
+    }
+}
+
+impl<T: ?Sized> AsRef<CFMutableBag> for CFTypedMutableBag<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFMutableBag {
+        &self.bag
+    }
+}
+
+impl<T: ?Sized> AsRef<CFBag> for CFTypedMutableBag<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFBag {
+        self // Through Deref
+    }
+}
+
+impl<T: ?Sized> AsRef<CFType> for CFTypedMutableBag<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFType {
+        self // Through Deref
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for CFTypedMutableBag<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.bag, f)
+    }
+}
+
+impl<T: ConcreteType> CFTypedMutableBag<T> {
+    /// Create a new empty bag.
+    #[inline]
+    #[doc(alias = "CFBagCreateMutable")]
+    pub fn new() -> CFRetained<Self> {
+        let bag = unsafe { CFBagCreateMutable(None, 0, &kCFTypeBagCallBacks) }
+            .expect("failed creating CFMutableBag");
+        // SAFETY: The bag is empty.
+        unsafe { CFRetained::cast_unchecked(bag) }
+    }
+
+    /// Add an occurrence of a value to the bag.
+    #[inline]
+    #[doc(alias = "CFBagAddValue")]
+    pub fn insert(&self, value: &T) {
+        unsafe { CFBagAddValue(Some(&self.bag), as_value(value)) };
+    }
+
+    /// Remove one occurrence of a value from the bag, if present.
+    #[inline]
+    #[doc(alias = "CFBagRemoveValue")]
+    pub fn remove(&self, value: &T) {
+        unsafe { CFBagRemoveValue(Some(&self.bag), as_value(value)) };
+    }
+
+    /// Remove all values from the bag.
+    #[inline]
+    #[doc(alias = "CFBagRemoveAllValues")]
+    pub fn clear(&self) {
+        unsafe { CFBagRemoveAllValues(Some(&self.bag)) };
+    }
+}
+
+impl<T: ConcreteType> FromIterator<CFRetained<T>> for CFRetained<CFTypedMutableBag<T>> {
+    fn from_iter<I: IntoIterator<Item = CFRetained<T>>>(iter: I) -> Self {
+        let bag = CFTypedMutableBag::<T>::new();
+        for value in iter {
+            bag.insert(&value);
+        }
+        bag
+    }
+}
+
+impl<'a, T: ConcreteType> FromIterator<&'a T> for CFRetained<CFTypedMutableBag<T>> {
+    fn from_iter<I: IntoIterator<Item = &'a T>>(iter: I) -> Self {
+        let bag = CFTypedMutableBag::<T>::new();
+        for value in iter {
+            bag.insert(value);
+        }
+        bag
+    }
+}
+
+#[cfg(all(test, feature = "CFNumber", feature = "CFString"))]
+mod tests {
+    use alloc::string::{String, ToString};
+
+    use super::*;
+    use crate::{CFNumber, CFString};
+
+    #[test]
+    fn from_slice() {
+        let a = CFString::from_str("a");
+        let b = CFString::from_str("b");
+        let bag = CFTypedBag::from_slice(&[&*a, &*b, &*a]);
+        assert_eq!(bag.len(), 3);
+        assert_eq!(bag.count(&a), 2);
+        assert_eq!(bag.count(&b), 1);
+        assert!(!bag.contains(&CFString::from_str("c")));
+    }
+
+    #[test]
+    fn iterate() {
+        let bag: CFRetained<CFTypedBag<CFString>> = ["x", "y", "x"]
+            .into_iter()
+            .map(CFString::from_str)
+            .collect();
+        let mut values: Vec<String> = bag.iter().map(|s| s.to_string()).collect();
+        values.sort();
+        assert_eq!(values, ["x", "x", "y"]);
+    }
+
+    #[test]
+    fn mutate() {
+        let bag = CFTypedMutableBag::<CFNumber>::new();
+        let one = CFNumber::new_i32(1);
+        bag.insert(&one);
+        bag.insert(&one);
+        assert_eq!(bag.count(&one), 2);
+
+        bag.remove(&one);
+        assert_eq!(bag.count(&one), 1);
+        bag.clear();
+        assert!(bag.is_empty());
+    }
+}
@@ -71,6 +71,35 @@ pub struct CFType {
 }
 
 impl CFType {
+    /// Whether the type is exactly of type `T`.
+    ///
+    /// This compares the dynamic type ID of the object (as returned by
+    /// `CFGetTypeID`) against [`ConcreteType::type_id`].
+    ///
+    /// # Examples
+    ///
+    /// Check the type of a value pulled out of a heterogeneous container.
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "CFString", feature = "CFNumber"))] {
+    /// use objc2_core_foundation::{CFNumber, CFString, CFType};
+    ///
+    /// let string = CFString::from_str("foo");
+    /// let value: &CFType = &string;
+    /// assert!(value.is::<CFString>());
+    /// assert!(!value.is::<CFNumber>());
+    /// # }
+    /// ```
+    #[doc(alias = "CFGetTypeID")]
+    pub fn is<T: ConcreteType>(&self) -> bool {
+        extern "C-unwind" {
+            fn CFGetTypeID(cf: Option<&CFType>) -> CFTypeID;
+        }
+
+        // SAFETY: The pointer is valid.
+        unsafe { CFGetTypeID(Some(self)) == T::type_id() }
+    }
+
     /// Attempt to downcast the type to that of type `T`.
     ///
     /// This is the reference-variant. Use [`CFRetained::downcast`] if you
@@ -82,12 +111,7 @@ impl CFType {
     // Not #[inline], we call two functions here.
     #[doc(alias = "CFGetTypeID")]
     pub fn downcast_ref<T: ConcreteType>(&self) -> Option<&T> {
-        extern "C-unwind" {
-            fn CFGetTypeID(cf: Option<&CFType>) -> CFTypeID;
-        }
-
-        // SAFETY: The pointer is valid.
-        if unsafe { CFGetTypeID(Some(self)) } == T::type_id() {
+        if self.is::<T>() {
             let ptr: *const Self = self;
             let ptr: *const T = ptr.cast();
             // SAFETY: Just checked that the object is a class of type `T`.
@@ -197,3 +221,26 @@ impl From<CFComparisonResult> for Ordering {
         }
     }
 }
+
+#[cfg(test)]
+#[cfg(all(feature = "CFString", feature = "CFNumber"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downcast() {
+        use crate::{CFNumber, CFRetained, CFString};
+
+        let string = CFString::from_str("abc");
+        let value: &CFType = &string;
+        assert!(value.is::<CFString>());
+        assert!(!value.is::<CFNumber>());
+        assert_eq!(value.downcast_ref::<CFString>(), Some(&*string));
+        assert_eq!(value.downcast_ref::<CFNumber>(), None);
+
+        let number: CFRetained<CFType> = CFNumber::new_i32(42).into();
+        let number = number.downcast::<CFString>().unwrap_err();
+        let number = number.downcast::<CFNumber>().unwrap();
+        assert_eq!(number.as_i32(), Some(42));
+    }
+}
@@ -0,0 +1,330 @@
+//! A typed `CFBinaryHeap` that orders its values with a Rust comparator.
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ffi::c_void;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::Deref;
+use core::ptr::{self, NonNull};
+
+use crate::__cf_helpers::as_value;
+use crate::{
+    CFAllocator, CFBinaryHeap, CFBinaryHeapAddValue, CFBinaryHeapCallBacks,
+    CFBinaryHeapCompareContext, CFBinaryHeapContainsValue, CFBinaryHeapCreate,
+    CFBinaryHeapGetCount, CFBinaryHeapGetCountOfValue, CFBinaryHeapGetMinimumIfPresent,
+    CFBinaryHeapRemoveAllValues, CFBinaryHeapRemoveMinimumValue, CFComparisonResult,
+    CFCopyDescription, CFRetained, CFString, CFType, ConcreteType, Type,
+};
+
+/// The comparator, which is stored directly in the `info` of the compare
+/// context. This avoids having to manage the lifetime of a closure, which
+/// `CFBinaryHeapCreate` does not do consistently.
+type Comparator<T> = fn(&T, &T) -> Ordering;
+
+/// Retain a value stored in the heap.
+///
+/// # Safety
+///
+/// The pointer must be a value in a heap of `T`s.
+unsafe fn retain_value<T: Type>(ptr: *const c_void) -> CFRetained<T> {
+    let ptr = NonNull::new(ptr.cast_mut().cast::<T>()).expect("heap contained NULL");
+    // SAFETY: The heap retains its values, and they are of type `T`, since
+    // the heap can only be mutated through `CFTypedBinaryHeap<T>`.
+    unsafe { CFRetained::retain(ptr) }
+}
+
+unsafe extern "C-unwind" fn retain(_: *const CFAllocator, ptr: *const c_void) -> *const c_void {
+    // SAFETY: The value is a valid `CFType`, see `CFTypedBinaryHeap::push`.
+    let value = unsafe { retain_value::<CFType>(ptr) };
+    CFRetained::into_raw(value).as_ptr().cast_const().cast()
+}
+
+unsafe extern "C-unwind" fn release(_: *const CFAllocator, ptr: *const c_void) {
+    let ptr = NonNull::new(ptr.cast_mut().cast::<CFType>()).expect("heap contained NULL");
+    // SAFETY: The heap retained the value in `retain`.
+    drop(unsafe { CFRetained::from_raw(ptr) });
+}
+
+unsafe extern "C-unwind" fn copy_description(ptr: *const c_void) -> *const CFString {
+    // SAFETY: The value is a valid `CFType`.
+    let value = unsafe { ptr.cast::<CFType>().as_ref() };
+    match CFCopyDescription(value) {
+        Some(description) => CFRetained::into_raw(description).as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+unsafe extern "C-unwind" fn compare<T: Type>(
+    a: *const c_void,
+    b: *const c_void,
+    info: *mut c_void,
+) -> CFComparisonResult {
+    // SAFETY: The info was created from a `Comparator<T>` in
+    // `CFTypedBinaryHeap::with_comparator`.
+    let compare = unsafe { mem::transmute::<*mut c_void, Comparator<T>>(info) };
+    // SAFETY: The values are valid `T`s. The value given to e.g.
+    // `CFBinaryHeapContainsValue` may not be in the heap, but is alive for
+    // the duration of the call.
+    let (a, b) = unsafe { (&*a.cast::<T>(), &*b.cast::<T>()) };
+    match compare(a, b) {
+        Ordering::Less => CFComparisonResult::CompareLessThan,
+        Ordering::Equal => CFComparisonResult::CompareEqualTo,
+        Ordering::Greater => CFComparisonResult::CompareGreaterThan,
+    }
+}
+
+impl CFBinaryHeap {
+    /// The number of values in the heap.
+    #[inline]
+    #[doc(alias = "CFBinaryHeapGetCount")]
+    pub fn len(&self) -> usize {
+        unsafe { CFBinaryHeapGetCount(self) as _ }
+    }
+
+    /// Whether the heap is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A [`CFBinaryHeap`] whose values are of type `T`, ordered by a Rust
+/// comparator.
+///
+/// This is a priority queue with the _smallest_ value at the top, unlike
+/// [`std::collections::BinaryHeap`]; use a reversed comparator to get the
+/// largest value instead. The values are retained by the heap.
+///
+/// Unlike [`CFTypedArray`](crate::CFTypedArray) and friends, this is not a
+/// view on an existing heap, since the comparator must be for `T`. It can
+/// only be created with [`new`](Self::new) and
+/// [`with_comparator`](Self::with_comparator).
+///
+///
+/// # Examples
+///
+/// ```
+/// use objc2_core_foundation::{CFNumber, CFTypedBinaryHeap};
+///
+/// let heap = CFTypedBinaryHeap::<CFNumber>::with_comparator(|a, b| b.cmp(a));
+/// for n in [3, 1, 4, 1, 5] {
+///     heap.push(&CFNumber::new_i32(n));
+/// }
+/// assert_eq!(heap.pop().unwrap().as_i32(), Some(5));
+/// assert_eq!(heap.pop().unwrap().as_i32(), Some(4));
+/// assert_eq!(heap.len(), 3);
+/// ```
+#[repr(transparent)]
+#[doc(alias = "CFBinaryHeap")]
+pub struct CFTypedBinaryHeap<T: ?Sized> {
+    heap: CFBinaryHeap,
+    value: PhantomData<T>,
+}
+
+// SAFETY: The heap is a `CFBinaryHeap`.
+unsafe impl<T: ?Sized> Type for CFTypedBinaryHeap<T> {}
+
+impl<T: ?Sized> Deref for CFTypedBinaryHeap<T> {
+    type Target = CFBinaryHeap;
+
+    #[inline]
+    fn deref(&self) -> &CFBinaryHeap {
+        &self.heap
+    }
+}
+
+impl<T: ?Sized> AsRef<CFBinaryHeap> for CFTypedBinaryHeap<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFBinaryHeap {
+        self
+    }
+}
+
+impl<T: ?Sized> AsRef<CFType> for CFTypedBinaryHeap<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFType {
+        self // Through Deref
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for CFTypedBinaryHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.heap, f)
+    }
+}
+
+impl<T: ConcreteType + Ord> CFTypedBinaryHeap<T> {
+    /// Create a new empty heap, ordered by [`Ord`].
+    #[inline]
+    #[doc(alias = "CFBinaryHeapCreate")]
+    pub fn new() -> CFRetained<Self> {
+        Self::with_comparator(T::cmp)
+    }
+}
+
+impl<T: ConcreteType> CFTypedBinaryHeap<T> {
+    /// Create a new empty heap, ordered by the given comparator.
+    ///
+    /// The comparator is a function pointer, and thus cannot capture any
+    /// state. It should not panic, as that would leave the heap in an
+    /// inconsistent state.
+    #[doc(alias = "CFBinaryHeapCreate")]
+    pub fn with_comparator(comparator: Comparator<T>) -> CFRetained<Self> {
+        let callbacks = CFBinaryHeapCallBacks {
+            version: 0,
+            retain: Some(retain),
+            release: Some(release),
+            copyDescription: Some(copy_description),
+            compare: Some(compare::<T>),
+        };
+        let context = CFBinaryHeapCompareContext {
+            version: 0,
+            info: comparator as *mut c_void,
+            retain: None,
+            release: None,
+            copyDescription: None,
+        };
+        // SAFETY: The callbacks are valid, and the comparator is `'static`,
+        // so it does not need to be retained or released.
+        let heap = unsafe { CFBinaryHeapCreate(None, 0, &callbacks, &context) }
+            .expect("failed creating CFBinaryHeap");
+        // SAFETY: The heap is empty.
+        unsafe { CFRetained::cast_unchecked(heap) }
+    }
+
+    /// Add a value to the heap.
+    #[inline]
+    #[doc(alias = "CFBinaryHeapAddValue")]
+    pub fn push(&self, value: &T) {
+        unsafe { CFBinaryHeapAddValue(self, as_value(value)) };
+    }
+
+    /// The smallest value in the heap, or [`None`] if it is empty.
+    #[doc(alias = "CFBinaryHeapGetMinimum")]
+    #[doc(alias = "CFBinaryHeapGetMinimumIfPresent")]
+    pub fn peek(&self) -> Option<CFRetained<T>> {
+        let mut value = ptr::null();
+        let present = unsafe { CFBinaryHeapGetMinimumIfPresent(self, &mut value) };
+        if present != 0 {
+            // SAFETY: The value is in the heap.
+            Some(unsafe { retain_value(value) })
+        } else {
+            None
+        }
+    }
+
+    /// Remove the smallest value from the heap and return it, or [`None`]
+    /// if it is empty.
+    #[doc(alias = "CFBinaryHeapRemoveMinimumValue")]
+    pub fn pop(&self) -> Option<CFRetained<T>> {
+        let value = self.peek()?;
+        unsafe { CFBinaryHeapRemoveMinimumValue(self) };
+        Some(value)
+    }
+
+    /// Whether the heap contains a value that the comparator considers
+    /// equal to the given value.
+    #[inline]
+    #[doc(alias = "CFBinaryHeapContainsValue")]
+    pub fn contains(&self, value: &T) -> bool {
+        unsafe { CFBinaryHeapContainsValue(self, as_value(value)) != 0 }
+    }
+
+    /// The number of values in the heap that the comparator considers equal
+    /// to the given value.
+    #[inline]
+    #[doc(alias = "CFBinaryHeapGetCountOfValue")]
+    pub fn count(&self, value: &T) -> usize {
+        unsafe { CFBinaryHeapGetCountOfValue(self, as_value(value)) as _ }
+    }
+
+    /// Remove all values from the heap.
+    #[inline]
+    #[doc(alias = "CFBinaryHeapRemoveAllValues")]
+    pub fn clear(&self) {
+        unsafe { CFBinaryHeapRemoveAllValues(self) };
+    }
+
+    /// Copy the values of the heap into a new [`Vec`], sorted from smallest
+    /// to largest.
+    #[cfg(feature = "alloc")]
+    #[doc(alias = "CFBinaryHeapGetValues")]
+    pub fn to_sorted_vec(&self) -> Vec<CFRetained<T>> {
+        let len = self.len();
+        let mut values = Vec::with_capacity(len);
+        unsafe {
+            crate::CFBinaryHeapGetValues(self, values.as_mut_ptr());
+            // SAFETY: The buffer was large enough, and has been filled.
+            values.set_len(len);
+        }
+        values
+            .into_iter()
+            // SAFETY: The values are in the heap.
+            .map(|value| unsafe { retain_value(value) })
+            .collect()
+    }
+}
+
+impl<T: ConcreteType + Ord> FromIterator<CFRetained<T>> for CFRetained<CFTypedBinaryHeap<T>> {
+    fn from_iter<I: IntoIterator<Item = CFRetained<T>>>(iter: I) -> Self {
+        let heap = CFTypedBinaryHeap::<T>::new();
+        for value in iter {
+            heap.push(&value);
+        }
+        heap
+    }
+}
+
+impl<'a, T: ConcreteType + Ord> FromIterator<&'a T> for CFRetained<CFTypedBinaryHeap<T>> {
+    fn from_iter<I: IntoIterator<Item = &'a T>>(iter: I) -> Self {
+        let heap = CFTypedBinaryHeap::<T>::new();
+        for value in iter {
+            heap.push(value);
+        }
+        heap
+    }
+}
+
+#[cfg(all(test, feature = "CFNumber", feature = "CFString"))]
+mod tests {
+    use alloc::string::{String, ToString};
+
+    use super::*;
+    use crate::CFNumber;
+
+    #[test]
+    fn min_heap() {
+        let heap: CFRetained<CFTypedBinaryHeap<CFNumber>> =
+            [5, 2, 8, 1].into_iter().map(CFNumber::new_i32).collect();
+        assert_eq!(heap.len(), 4);
+        assert_eq!(heap.peek().unwrap().as_i32(), Some(1));
+        assert_eq!(heap.pop().unwrap().as_i32(), Some(1));
+        assert_eq!(heap.pop().unwrap().as_i32(), Some(2));
+        assert_eq!(heap.len(), 2);
+
+        heap.clear();
+        assert!(heap.peek().is_none());
+        assert!(heap.pop().is_none());
+    }
+
+    #[test]
+    fn custom_comparator() {
+        // Order by length, longest first.
+        let heap = CFTypedBinaryHeap::<CFString>::with_comparator(|a, b| {
+            b.to_string().len().cmp(&a.to_string().len())
+        });
+        for s in ["a", "ccc", "bb", "dd"] {
+            heap.push(&CFString::from_str(s));
+        }
+        assert_eq!(heap.peek().unwrap().to_string(), "ccc");
+        assert!(heap.contains(&CFString::from_str("xx")));
+        assert_eq!(heap.count(&CFString::from_str("xx")), 2);
+        assert!(!heap.contains(&CFString::from_str("xxxx")));
+
+        let values: Vec<String> = heap.to_sorted_vec().iter().map(|s| s.to_string()).collect();
+        assert_eq!(values[0], "ccc");
+        assert_eq!(values[3], "a");
+    }
+}
@@ -5,3 +5,247 @@ type Inner = i16; // SInt16
 
 /// [Apple's documentation](https://developer.apple.com/documentation/corefoundation/cfbundlerefnum?language=objc)
 pub type CFBundleRefNum = Inner;
+
+#[cfg(all(feature = "CFBase", feature = "CFString", feature = "CFURL"))]
+use crate::{CFBundle, CFRetained, CFString, CFType, CFURL};
+
+#[cfg(all(feature = "CFBase", feature = "CFString", feature = "CFURL"))]
+fn optional_string(string: Option<&str>) -> Option<CFRetained<CFString>> {
+    string.map(CFString::from_str)
+}
+
+/// Bundle lookups.
+#[cfg(all(feature = "CFBase", feature = "CFString", feature = "CFURL"))]
+impl CFBundle {
+    /// The bundle that contains the current executable.
+    ///
+    /// This may return a bundle even if the executable is not inside a
+    /// bundle, in which case it refers to the directory of the executable.
+    #[doc(alias = "CFBundleGetMainBundle")]
+    pub fn main() -> Option<CFRetained<Self>> {
+        unsafe { crate::CFBundleGetMainBundle() }
+    }
+
+    /// The already loaded bundle with the given identifier, such as
+    /// `"com.apple.CoreFoundation"`.
+    #[doc(alias = "CFBundleGetBundleWithIdentifier")]
+    pub fn with_identifier(identifier: &str) -> Option<CFRetained<Self>> {
+        let identifier = CFString::from_str(identifier);
+        unsafe { crate::CFBundleGetBundleWithIdentifier(Some(&identifier)) }
+    }
+
+    /// The bundle identifier, from the `CFBundleIdentifier` key in the
+    /// bundle's `Info.plist`.
+    #[doc(alias = "CFBundleGetIdentifier")]
+    pub fn identifier(&self) -> Option<CFRetained<CFString>> {
+        unsafe { crate::CFBundleGetIdentifier(self) }
+    }
+
+    /// The location of the bundle.
+    #[doc(alias = "CFBundleCopyBundleURL")]
+    pub fn url(&self) -> Option<CFRetained<CFURL>> {
+        unsafe { crate::CFBundleCopyBundleURL(self) }
+    }
+
+    /// The location of the bundle's resources directory.
+    ///
+    /// This is `Contents/Resources` in macOS application bundles.
+    #[doc(alias = "CFBundleCopyResourcesDirectoryURL")]
+    pub fn resources_url(&self) -> Option<CFRetained<CFURL>> {
+        unsafe { crate::CFBundleCopyResourcesDirectoryURL(self) }
+    }
+
+    /// The location of the bundle's main executable.
+    #[doc(alias = "CFBundleCopyExecutableURL")]
+    pub fn executable_url(&self) -> Option<CFRetained<CFURL>> {
+        unsafe { crate::CFBundleCopyExecutableURL(self) }
+    }
+
+    /// The location of the executable with the given name in the bundle,
+    /// such as a helper tool.
+    #[doc(alias = "CFBundleCopyAuxiliaryExecutableURL")]
+    pub fn auxiliary_executable_url(&self, name: &str) -> Option<CFRetained<CFURL>> {
+        let name = CFString::from_str(name);
+        unsafe { crate::CFBundleCopyAuxiliaryExecutableURL(self, Some(&name)) }
+    }
+
+    /// Find a resource in the bundle.
+    ///
+    /// `extension` is the file extension of the resource without the leading
+    /// dot, and `subdirectory` is the directory inside the resources
+    /// directory to search in. The resource is looked up in the localized
+    /// resource directories as well.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_core_foundation::CFBundle;
+    ///
+    /// let bundle = CFBundle::main().unwrap();
+    /// // Returns `None`, since the test executable has no resources.
+    /// let url = bundle.resource_url("icon", Some("png"), None);
+    /// # assert!(url.is_none());
+    /// ```
+    #[doc(alias = "CFBundleCopyResourceURL")]
+    pub fn resource_url(
+        &self,
+        name: &str,
+        extension: Option<&str>,
+        subdirectory: Option<&str>,
+    ) -> Option<CFRetained<CFURL>> {
+        let name = CFString::from_str(name);
+        let extension = optional_string(extension);
+        let subdirectory = optional_string(subdirectory);
+        unsafe {
+            crate::CFBundleCopyResourceURL(
+                self,
+                Some(&name),
+                extension.as_deref(),
+                subdirectory.as_deref(),
+            )
+        }
+    }
+
+    /// Find all resources with the given extension in the bundle.
+    ///
+    /// If `extension` is [`None`], all resources in the directory are
+    /// returned.
+    #[cfg(feature = "CFArray")]
+    #[doc(alias = "CFBundleCopyResourceURLsOfType")]
+    pub fn resource_urls(
+        &self,
+        extension: Option<&str>,
+        subdirectory: Option<&str>,
+    ) -> CFRetained<crate::CFTypedArray<CFURL>> {
+        let extension = optional_string(extension);
+        let subdirectory = optional_string(subdirectory);
+        let urls = unsafe {
+            crate::CFBundleCopyResourceURLsOfType(
+                self,
+                extension.as_deref(),
+                subdirectory.as_deref(),
+            )
+        };
+        match urls {
+            // SAFETY: The array contains `CFURL`s.
+            Some(urls) => unsafe { CFRetained::cast_unchecked(urls) },
+            None => crate::CFTypedArray::from_slice(&[]),
+        }
+    }
+
+    /// Get a value from the bundle's `Info.plist`.
+    ///
+    /// Localized values (from `InfoPlist.strings`) are preferred over the
+    /// values in `Info.plist`.
+    #[doc(alias = "CFBundleGetValueForInfoDictionaryKey")]
+    pub fn info_value(&self, key: &str) -> Option<CFRetained<CFType>> {
+        let key = CFString::from_str(key);
+        unsafe { crate::CFBundleGetValueForInfoDictionaryKey(self, Some(&key)) }
+    }
+
+    /// Get a value from the bundle's `Info.plist`, and check that it has the
+    /// expected type.
+    ///
+    /// Returns [`None`] if the key is not present, or if the value has a
+    /// different type.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_core_foundation::{CFBundle, CFString};
+    ///
+    /// let bundle = CFBundle::with_identifier("com.apple.CoreFoundation").unwrap();
+    /// let name = bundle.info_value_as::<CFString>("CFBundleName").unwrap();
+    /// assert_eq!(name.to_string(), "CoreFoundation");
+    /// ```
+    pub fn info_value_as<T: crate::ConcreteType>(&self, key: &str) -> Option<CFRetained<T>> {
+        self.info_value(key)?.downcast().ok()
+    }
+
+    /// Get a string value from the bundle's `Info.plist`.
+    pub fn info_string(&self, key: &str) -> Option<CFRetained<CFString>> {
+        self.info_value_as(key)
+    }
+
+    /// Get a boolean value from the bundle's `Info.plist`.
+    #[cfg(feature = "CFNumber")]
+    pub fn info_bool(&self, key: &str) -> Option<bool> {
+        self.info_value_as::<crate::CFBoolean>(key)
+            .map(|value| value.as_bool())
+    }
+
+    /// Get an integer value from the bundle's `Info.plist`.
+    #[cfg(feature = "CFNumber")]
+    pub fn info_i64(&self, key: &str) -> Option<i64> {
+        self.info_value_as::<crate::CFNumber>(key)?.as_i64()
+    }
+
+    /// The build version of the bundle, from the `CFBundleVersion` key.
+    #[doc(alias = "kCFBundleVersionKey")]
+    pub fn version(&self) -> Option<CFRetained<CFString>> {
+        self.info_string("CFBundleVersion")
+    }
+
+    /// The user-visible version of the bundle, from the
+    /// `CFBundleShortVersionString` key.
+    pub fn short_version(&self) -> Option<CFRetained<CFString>> {
+        self.info_string("CFBundleShortVersionString")
+    }
+
+    /// The name of the bundle, from the `CFBundleName` key.
+    #[doc(alias = "kCFBundleNameKey")]
+    pub fn name(&self) -> Option<CFRetained<CFString>> {
+        self.info_string("CFBundleName")
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "CFArray",
+    feature = "CFBase",
+    feature = "CFNumber",
+    feature = "CFString",
+    feature = "CFURL"
+))]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn main_bundle() {
+        let bundle = CFBundle::main().unwrap();
+        assert!(bundle.url().is_some());
+        assert!(bundle.executable_url().is_some());
+        assert!(bundle.resource_url("does-not-exist", None, None).is_none());
+        assert!(bundle.info_value("DoesNotExist").is_none());
+    }
+
+    #[test]
+    fn system_bundle() {
+        let bundle = CFBundle::with_identifier("com.apple.CoreFoundation").unwrap();
+        assert_eq!(
+            bundle.identifier().unwrap().to_string(),
+            "com.apple.CoreFoundation"
+        );
+        assert_eq!(bundle.name().unwrap().to_string(), "CoreFoundation");
+        assert!(bundle.version().is_some());
+        assert!(bundle.resources_url().is_some());
+
+        // Exists, but is not a boolean.
+        assert_eq!(bundle.info_bool("CFBundleName"), None);
+        assert!(bundle.info_string("CFBundleIdentifier").is_some());
+
+        let plists = bundle.resource_urls(Some("plist"), None);
+        assert!(plists
+            .iter()
+            .all(|url| unsafe { crate::CFURLGetString(&url) }
+                .unwrap()
+                .to_string()
+                .ends_with(".plist")));
+
+        assert!(CFBundle::with_identifier("com.example.does-not-exist").is_none());
+    }
+}
@@ -0,0 +1,319 @@
+#![cfg(all(feature = "CFBase", feature = "CFDate"))]
+use core::ffi::{c_char, c_int};
+
+use crate::{
+    kCFCalendarComponentsWrap, Boolean, CFAbsoluteTime, CFCalendar, CFCalendarCopyCurrent,
+    CFOptionFlags, CFRetained,
+};
+
+// The component functions are variadic, and are therefore not generated.
+extern "C" {
+    fn CFCalendarComposeAbsoluteTime(
+        calendar: &CFCalendar,
+        at: *mut CFAbsoluteTime,
+        component_desc: *const c_char,
+        ...
+    ) -> Boolean;
+    fn CFCalendarDecomposeAbsoluteTime(
+        calendar: &CFCalendar,
+        at: CFAbsoluteTime,
+        component_desc: *const c_char,
+        ...
+    ) -> Boolean;
+    fn CFCalendarAddComponents(
+        calendar: &CFCalendar,
+        at: *mut CFAbsoluteTime,
+        options: CFOptionFlags,
+        component_desc: *const c_char,
+        ...
+    ) -> Boolean;
+    fn CFCalendarGetComponentDifference(
+        calendar: &CFCalendar,
+        start_at: CFAbsoluteTime,
+        result_at: CFAbsoluteTime,
+        options: CFOptionFlags,
+        component_desc: *const c_char,
+        ...
+    ) -> Boolean;
+}
+
+/// Year, month, day, hour, minute and second.
+const COMPONENTS: &[u8] = b"yMdHms\0";
+
+/// The date and time components of an absolute time in a calendar.
+///
+/// When used with [`CFCalendar::add_components`] and
+/// [`CFCalendar::component_difference`], the fields are offsets instead, and
+/// may be negative.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CFCalendarComponents {
+    /// The year, e.g. `2001`.
+    pub year: i32,
+    /// The month, starting at `1`.
+    pub month: i32,
+    /// The day of the month, starting at `1`.
+    pub day: i32,
+    /// The hour, between `0` and `23`.
+    pub hour: i32,
+    /// The minute, between `0` and `59`.
+    pub minute: i32,
+    /// The second, between `0` and `59`.
+    pub second: i32,
+}
+
+impl CFCalendar {
+    /// The user's current calendar.
+    ///
+    /// The calendar uses the user's current time zone, see
+    /// [`CFCalendarSetTimeZone`] to change that.
+    ///
+    /// [`CFCalendarSetTimeZone`]: crate::CFCalendarSetTimeZone
+    #[doc(alias = "CFCalendarCopyCurrent")]
+    pub fn current() -> CFRetained<Self> {
+        unsafe { CFCalendarCopyCurrent() }.expect("failed copying current calendar")
+    }
+
+    /// Split an absolute time into its components, in the calendar's time
+    /// zone.
+    ///
+    /// Fractional seconds are truncated.
+    ///
+    /// Returns `None` if the time cannot be represented in the calendar.
+    #[doc(alias = "CFCalendarDecomposeAbsoluteTime")]
+    pub fn decompose(&self, at: CFAbsoluteTime) -> Option<CFCalendarComponents> {
+        let mut year: c_int = 0;
+        let mut month: c_int = 0;
+        let mut day: c_int = 0;
+        let mut hour: c_int = 0;
+        let mut minute: c_int = 0;
+        let mut second: c_int = 0;
+        // SAFETY: There is an `int *` out-parameter for each character in
+        // the (NUL-terminated) component description.
+        let ok = unsafe {
+            CFCalendarDecomposeAbsoluteTime(
+                self,
+                at,
+                COMPONENTS.as_ptr().cast(),
+                &mut year as *mut c_int,
+                &mut month as *mut c_int,
+                &mut day as *mut c_int,
+                &mut hour as *mut c_int,
+                &mut minute as *mut c_int,
+                &mut second as *mut c_int,
+            )
+        };
+        (ok != 0).then_some(CFCalendarComponents {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    /// Create an absolute time from its components, in the calendar's time
+    /// zone.
+    ///
+    /// Out-of-range components are normalized, e.g. the 32nd of January is
+    /// the 1st of February.
+    ///
+    /// Returns `None` if the components cannot be represented in the
+    /// calendar.
+    #[doc(alias = "CFCalendarComposeAbsoluteTime")]
+    pub fn compose(&self, components: &CFCalendarComponents) -> Option<CFAbsoluteTime> {
+        let mut at: CFAbsoluteTime = 0.0;
+        // SAFETY: There is an `int` argument for each character in the
+        // (NUL-terminated) component description.
+        let ok = unsafe {
+            CFCalendarComposeAbsoluteTime(
+                self,
+                &mut at,
+                COMPONENTS.as_ptr().cast(),
+                components.year as c_int,
+                components.month as c_int,
+                components.day as c_int,
+                components.hour as c_int,
+                components.minute as c_int,
+                components.second as c_int,
+            )
+        };
+        (ok != 0).then_some(at)
+    }
+
+    /// Add the given offsets to an absolute time.
+    ///
+    /// Unlike adding seconds directly to the absolute time, this respects
+    /// the calendar and its time zone; e.g. adding one day across a daylight
+    /// saving time transition keeps the same wall-clock time, and adding one
+    /// month to the 31st of January gives the last day of February.
+    ///
+    /// If `wrap` is `true`, overflow in a component does not carry over to
+    /// larger components (adding 1 hour to 23:30 gives 00:30 on the same
+    /// day).
+    ///
+    /// Returns `None` if the result cannot be represented in the calendar.
+    #[doc(alias = "CFCalendarAddComponents")]
+    pub fn add_components(
+        &self,
+        at: CFAbsoluteTime,
+        offsets: &CFCalendarComponents,
+        wrap: bool,
+    ) -> Option<CFAbsoluteTime> {
+        let mut at = at;
+        let options = if wrap { kCFCalendarComponentsWrap } else { 0 };
+        // SAFETY: There is an `int` argument for each character in the
+        // (NUL-terminated) component description.
+        let ok = unsafe {
+            CFCalendarAddComponents(
+                self,
+                &mut at,
+                options,
+                COMPONENTS.as_ptr().cast(),
+                offsets.year as c_int,
+                offsets.month as c_int,
+                offsets.day as c_int,
+                offsets.hour as c_int,
+                offsets.minute as c_int,
+                offsets.second as c_int,
+            )
+        };
+        (ok != 0).then_some(at)
+    }
+
+    /// The difference between two absolute times, in calendar components.
+    ///
+    /// The difference is expressed using the largest units possible, e.g.
+    /// the difference between the 1st of January and the 3rd of February at
+    /// noon is 1 month, 2 days and 12 hours.
+    ///
+    /// Returns `None` if either time cannot be represented in the calendar.
+    #[doc(alias = "CFCalendarGetComponentDifference")]
+    pub fn component_difference(
+        &self,
+        start: CFAbsoluteTime,
+        end: CFAbsoluteTime,
+    ) -> Option<CFCalendarComponents> {
+        let mut year: c_int = 0;
+        let mut month: c_int = 0;
+        let mut day: c_int = 0;
+        let mut hour: c_int = 0;
+        let mut minute: c_int = 0;
+        let mut second: c_int = 0;
+        // SAFETY: There is an `int *` out-parameter for each character in
+        // the (NUL-terminated) component description.
+        let ok = unsafe {
+            CFCalendarGetComponentDifference(
+                self,
+                start,
+                end,
+                0,
+                COMPONENTS.as_ptr().cast(),
+                &mut year as *mut c_int,
+                &mut month as *mut c_int,
+                &mut day as *mut c_int,
+                &mut hour as *mut c_int,
+                &mut minute as *mut c_int,
+                &mut second as *mut c_int,
+            )
+        };
+        (ok != 0).then_some(CFCalendarComponents {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "CFLocale", feature = "CFTimeZone"))]
+mod tests {
+    use crate::{
+        kCFGregorianCalendar, CFCalendarCreateWithIdentifier, CFCalendarSetTimeZone,
+        CFTimeZoneCreateWithTimeIntervalFromGMT,
+    };
+
+    use super::*;
+
+    fn gregorian(offset_from_gmt: f64) -> CFRetained<CFCalendar> {
+        let calendar =
+            unsafe { CFCalendarCreateWithIdentifier(None, kCFGregorianCalendar) }.unwrap();
+        let tz = unsafe { CFTimeZoneCreateWithTimeIntervalFromGMT(None, offset_from_gmt) };
+        unsafe { CFCalendarSetTimeZone(&calendar, tz.as_deref()) };
+        calendar
+    }
+
+    #[test]
+    fn decompose_reference_date() {
+        let calendar = gregorian(0.0);
+        let components = calendar.decompose(0.0).unwrap();
+        assert_eq!(
+            components,
+            CFCalendarComponents {
+                year: 2001,
+                month: 1,
+                day: 1,
+                hour: 0,
+                minute: 0,
+                second: 0,
+            }
+        );
+        assert_eq!(calendar.compose(&components), Some(0.0));
+
+        // One hour east of GMT.
+        let calendar = gregorian(3600.0);
+        assert_eq!(calendar.decompose(0.0).unwrap().hour, 1);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let calendar = gregorian(0.0);
+        let jan_31 = calendar
+            .compose(&CFCalendarComponents {
+                year: 2001,
+                month: 1,
+                day: 31,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let one_month = CFCalendarComponents {
+            month: 1,
+            ..Default::default()
+        };
+        let feb_28 = calendar.add_components(jan_31, &one_month, false).unwrap();
+        let components = calendar.decompose(feb_28).unwrap();
+        assert_eq!((components.month, components.day), (2, 28));
+
+        let jan_1 = calendar
+            .compose(&CFCalendarComponents {
+                year: 2001,
+                month: 1,
+                day: 1,
+                ..Default::default()
+            })
+            .unwrap();
+        let feb_3_noon = calendar
+            .compose(&CFCalendarComponents {
+                year: 2001,
+                month: 2,
+                day: 3,
+                hour: 12,
+                ..Default::default()
+            })
+            .unwrap();
+        let difference = calendar.component_difference(jan_1, feb_3_noon).unwrap();
+        assert_eq!(
+            difference,
+            CFCalendarComponents {
+                month: 1,
+                day: 2,
+                hour: 12,
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -3,7 +3,7 @@ use core::slice;
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
-use crate::{CFData, CFDataGetBytePtr, CFDataGetLength};
+use crate::{CFData, CFDataGetBytePtr, CFDataGetLength, CFDataGetMutableBytePtr, CFMutableData};
 
 impl CFData {
     /// Creates a new `CFData` from a byte slice.
@@ -87,6 +87,107 @@ impl CFData {
     }
 }
 
+impl CFMutableData {
+    /// Create a new empty data.
+    #[inline]
+    #[cfg(feature = "CFBase")]
+    #[doc(alias = "CFDataCreateMutable")]
+    pub fn new() -> crate::CFRetained<Self> {
+        Self::with_capacity(0)
+    }
+
+    /// Create a new empty data, that can contain at most `capacity` bytes.
+    ///
+    /// A capacity of zero means that the data is unbounded.
+    #[inline]
+    #[cfg(feature = "CFBase")]
+    #[doc(alias = "CFDataCreateMutable")]
+    pub fn with_capacity(capacity: usize) -> crate::CFRetained<Self> {
+        let capacity = capacity.try_into().expect("capacity too large");
+        unsafe { crate::CFDataCreateMutable(None, capacity) }
+            .expect("failed creating CFMutableData")
+    }
+
+    /// Appends the bytes in the slice to the end of the data.
+    #[inline]
+    #[cfg(feature = "CFBase")]
+    #[doc(alias = "CFDataAppendBytes")]
+    pub fn extend_from_slice(&self, bytes: &[u8]) {
+        let len = bytes.len().try_into().expect("buffer too large");
+        // SAFETY: The pointer is valid for `len` bytes. The slice cannot
+        // point into the data itself, as `as_bytes_unchecked` forbids
+        // mutating the data while such a slice is alive.
+        unsafe { crate::CFDataAppendBytes(Some(self), bytes.as_ptr(), len) }
+    }
+
+    /// Shortens the data to `len` bytes.
+    ///
+    /// Does nothing if the data is already shorter than that.
+    #[inline]
+    #[cfg(feature = "CFBase")]
+    #[doc(alias = "CFDataSetLength")]
+    pub fn truncate(&self, len: usize) {
+        if len < self.len() {
+            // The cast cannot truncate, since `len` is less than the
+            // current length.
+            unsafe { crate::CFDataSetLength(Some(self), len as _) }
+        }
+    }
+
+    /// The underlying bytes in the `CFMutableData`, for modifying them in
+    /// place.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The `CFMutableData` must not be resized, and no other references to
+    /// its bytes (such as from [`as_bytes_unchecked`] or another call to
+    /// this method) may exist for the lifetime of the returned slice.
+    ///
+    /// Note that the data may be shared with other code through a retained
+    /// reference, so this is only sound if you know that it isn't (such as
+    /// right after creating it with [`new`]).
+    ///
+    /// [`as_bytes_unchecked`]: CFData::as_bytes_unchecked
+    /// [`new`]: Self::new
+    #[inline]
+    #[allow(clippy::mut_from_ref)]
+    #[doc(alias = "CFDataGetMutableBytePtr")]
+    pub unsafe fn as_mut_slice(&self) -> &mut [u8] {
+        let ptr = unsafe { CFDataGetMutableBytePtr(Some(self)) };
+        if !ptr.is_null() {
+            // SAFETY: The pointer is valid, and caller ensures that the
+            // `CFMutableData` is not resized and not otherwise accessed for
+            // the lifetime of the slice.
+            unsafe { slice::from_raw_parts_mut(ptr, self.len()) }
+        } else {
+            // The bytes pointer may be null for length zero
+            &mut []
+        }
+    }
+}
+
+/// Append to the data with [`std::io`].
+#[cfg(all(feature = "CFBase", feature = "std"))]
+impl std::io::Write for &CFMutableData {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.extend_from_slice(buf);
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,4 +204,38 @@ mod tests {
         assert!(data.is_empty());
         assert_eq!(data.to_vec(), []);
     }
+
+    #[test]
+    #[cfg(all(feature = "CFBase", feature = "alloc"))]
+    fn mutable() {
+        let data = CFMutableData::new();
+        assert!(data.is_empty());
+
+        data.extend_from_slice(&[1, 2, 3]);
+        data.extend_from_slice(&[4]);
+        assert_eq!(data.to_vec(), [1, 2, 3, 4]);
+
+        let bytes = unsafe { data.as_mut_slice() };
+        bytes[1] = 5;
+        assert_eq!(data.to_vec(), [1, 5, 3, 4]);
+
+        data.truncate(10);
+        data.truncate(2);
+        assert_eq!(data.to_vec(), [1, 5]);
+
+        let bounded = CFMutableData::with_capacity(4);
+        assert!(unsafe { bounded.as_mut_slice() }.is_empty());
+        bounded.extend_from_slice(&[1, 2, 3, 4]);
+        assert_eq!(bounded.to_vec(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "CFBase", feature = "std"))]
+    fn write() {
+        use std::io::Write;
+
+        let data = CFMutableData::new();
+        write!(&*data, "{}-{}", 1, 2).unwrap();
+        assert_eq!(data.to_vec(), b"1-2");
+    }
 }
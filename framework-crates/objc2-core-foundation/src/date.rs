@@ -1,7 +1,50 @@
 #![cfg(feature = "CFBase")]
 use core::{cmp::Ordering, ptr};
 
-use crate::{kCFAbsoluteTimeIntervalSince1970, CFDate, CFDateCompare};
+#[cfg(feature = "std")]
+use crate::kCFAbsoluteTimeIntervalSince1970;
+use crate::{CFDate, CFDateCompare};
+
+/// Convert a [`SystemTime`] to a [`CFAbsoluteTime`].
+///
+/// Nanosecond precision may be lost.
+///
+/// [`SystemTime`]: std::time::SystemTime
+/// [`CFAbsoluteTime`]: crate::CFAbsoluteTime
+#[cfg(feature = "std")]
+pub fn absolute_time_from_system_time(time: &std::time::SystemTime) -> crate::CFAbsoluteTime {
+    let since_1970 = match time.duration_since(std::time::UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs_f64(),
+        Err(err) => -err.duration().as_secs_f64(),
+    } as core::ffi::c_double;
+
+    since_1970 - unsafe { kCFAbsoluteTimeIntervalSince1970 }
+}
+
+/// Try to convert a [`CFAbsoluteTime`] to a [`SystemTime`].
+///
+/// Nanosecond precision may be lost.
+///
+/// Returns `None` if the time is not finite, or too large to fit inside
+/// [`SystemTime`].
+///
+/// [`SystemTime`]: std::time::SystemTime
+/// [`CFAbsoluteTime`]: crate::CFAbsoluteTime
+#[cfg(feature = "std")]
+pub fn system_time_from_absolute_time(
+    time: crate::CFAbsoluteTime,
+) -> Option<std::time::SystemTime> {
+    let since_1970 = time + unsafe { kCFAbsoluteTimeIntervalSince1970 };
+
+    // Times before 1970 are negative, which `Duration` can't represent.
+    if since_1970 < 0.0 {
+        let duration = std::time::Duration::try_from_secs_f64(-since_1970).ok()?;
+        std::time::UNIX_EPOCH.checked_sub(duration)
+    } else {
+        let duration = std::time::Duration::try_from_secs_f64(since_1970).ok()?;
+        std::time::UNIX_EPOCH.checked_add(duration)
+    }
+}
 
 impl CFDate {
     /// Create a `CFDate` from a [`SystemTime`].
@@ -11,12 +54,7 @@ impl CFDate {
     /// [`SystemTime`]: std::time::SystemTime
     #[cfg(feature = "std")]
     pub fn from_system_time(time: &std::time::SystemTime) -> crate::CFRetained<Self> {
-        let since_1970 = match time.duration_since(std::time::UNIX_EPOCH) {
-            Ok(duration) => duration.as_secs_f64(),
-            Err(err) => -err.duration().as_secs_f64(),
-        } as core::ffi::c_double;
-
-        let since_2001 = since_1970 - unsafe { kCFAbsoluteTimeIntervalSince1970 };
+        let since_2001 = absolute_time_from_system_time(time);
         unsafe { crate::CFDateCreate(None, since_2001).expect("failed creating CFDate") }
     }
 
@@ -30,10 +68,7 @@ impl CFDate {
     /// [`SystemTime`]: std::time::SystemTime
     #[cfg(feature = "std")]
     pub fn to_system_time(&self) -> Option<std::time::SystemTime> {
-        let since_2001 = unsafe { crate::CFDateGetAbsoluteTime(self) };
-        let since_1970 = (since_2001 + unsafe { kCFAbsoluteTimeIntervalSince1970 }) as f64;
-
-        std::time::UNIX_EPOCH.checked_add(std::time::Duration::try_from_secs_f64(since_1970).ok()?)
+        system_time_from_absolute_time(unsafe { crate::CFDateGetAbsoluteTime(self) })
     }
 }
 
@@ -110,6 +145,25 @@ mod test {
         let _ = CFDate::from_system_time(&time);
     }
 
+    #[test]
+    fn system_time_before_1970() {
+        let time = SystemTime::UNIX_EPOCH
+            .checked_sub(Duration::from_secs(10))
+            .unwrap();
+        let date = CFDate::from_system_time(&time);
+        assert_eq!(date.to_system_time(), Some(time));
+    }
+
+    #[test]
+    fn absolute_time_reference_date() {
+        let reference_date = SystemTime::UNIX_EPOCH
+            .checked_add(Duration::from_secs(978_307_200))
+            .unwrap();
+        assert_eq!(absolute_time_from_system_time(&reference_date), 0.0);
+        assert_eq!(system_time_from_absolute_time(0.0), Some(reference_date));
+        assert_eq!(system_time_from_absolute_time(c_double::NAN), None);
+    }
+
     #[test]
     fn system_time_unrepresentable() {
         let date = unsafe { CFDateCreate(None, c_double::MIN).unwrap() };
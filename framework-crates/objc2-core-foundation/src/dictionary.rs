@@ -0,0 +1,489 @@
+//! Typed views of `CFDictionary` and `CFMutableDictionary`.
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::ptr;
+
+use crate::__cf_helpers::{as_value, retain_checked};
+use crate::{
+    kCFTypeDictionaryKeyCallBacks, kCFTypeDictionaryValueCallBacks, CFDictionary,
+    CFDictionaryContainsKey, CFDictionaryCreate, CFDictionaryCreateMutable, CFDictionaryGetCount,
+    CFDictionaryGetValueIfPresent, CFDictionaryRemoveAllValues, CFDictionaryRemoveValue,
+    CFDictionarySetValue, CFMutableDictionary, CFRetained, CFType, ConcreteType, Type,
+};
+
+impl CFDictionary {
+    /// The number of key-value pairs in the dictionary.
+    #[inline]
+    #[doc(alias = "CFDictionaryGetCount")]
+    pub fn len(&self) -> usize {
+        unsafe { CFDictionaryGetCount(self) as _ }
+    }
+
+    /// Whether the dictionary is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// View the dictionary as a dictionary from `K` to `V`.
+    ///
+    /// The dictionary is not checked here, instead each key and value is
+    /// checked when it is accessed, see [`CFTypedDictionary::get`].
+    #[inline]
+    pub fn as_typed<K: ConcreteType, V: ConcreteType>(&self) -> &CFTypedDictionary<K, V> {
+        let ptr: *const Self = self;
+        // SAFETY: `CFTypedDictionary` is `#[repr(transparent)]` over
+        // `CFDictionary`, and does not have any further invariants.
+        unsafe { &*ptr.cast() }
+    }
+}
+
+impl CFMutableDictionary {
+    /// View the dictionary as a mutable dictionary from `K` to `V`.
+    ///
+    /// See [`CFDictionary::as_typed`].
+    #[inline]
+    pub fn as_typed<K: ConcreteType, V: ConcreteType>(&self) -> &CFTypedMutableDictionary<K, V> {
+        let ptr: *const Self = self;
+        // SAFETY: `CFTypedMutableDictionary` is `#[repr(transparent)]` over
+        // `CFMutableDictionary`, and does not have any further invariants.
+        unsafe { &*ptr.cast() }
+    }
+}
+
+/// A [`CFDictionary`] whose keys are of type `K`, and values of type `V`.
+///
+/// CoreFoundation dictionaries are untyped, so this is only a view: each key
+/// and value is checked with `CFGetTypeID` when it is accessed. Dictionaries
+/// created with the constructors here use the `CFType` callbacks, and thus
+/// retain their keys and values, and compare keys with `CFEqual`.
+///
+/// This is commonly used for passing options to APIs in e.g. CoreText or
+/// ImageIO, which take a `CFDictionary` with `CFString` keys.
+///
+///
+/// # Examples
+///
+/// Create a dictionary from a [`HashMap`].
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use objc2_core_foundation::{CFNumber, CFRetained, CFString, CFTypedDictionary};
+///
+/// let map = HashMap::from([("width", 640), ("height", 480)]);
+///
+/// let dict: CFRetained<CFTypedDictionary<CFString, CFNumber>> = map
+///     .iter()
+///     .map(|(key, value)| (CFString::from_str(key), CFNumber::new_i32(*value)))
+///     .collect();
+///
+/// let width = dict.get(&CFString::from_str("width")).unwrap();
+/// assert_eq!(width.as_i32(), Some(640));
+/// ```
+///
+/// [`HashMap`]: std::collections::HashMap
+#[repr(transparent)]
+#[doc(alias = "CFDictionary")]
+pub struct CFTypedDictionary<K: ?Sized, V: ?Sized> {
+    dictionary: CFDictionary,
+    key: PhantomData<K>,
+    value: PhantomData<V>,
+}
+
+// SAFETY: The dictionary is a `CFDictionary`.
+unsafe impl<K: ?Sized, V: ?Sized> Type for CFTypedDictionary<K, V> {}
+
+impl<K: ?Sized, V: ?Sized> Deref for CFTypedDictionary<K, V> {
+    type Target = CFDictionary;
+
+    #[inline]
+    fn deref(&self) -> &CFDictionary {
+        &self.dictionary
+    }
+}
+
+impl<K: ?Sized, V: ?Sized> AsRef<CFDictionary> for CFTypedDictionary<K, V> {
+    #[inline]
+    fn as_ref(&self) -> &CFDictionary {
+        self
+    }
+}
+
+impl<K: ?Sized, V: ?Sized> AsRef<CFType> for CFTypedDictionary<K, V> {
+    #[inline]
+    fn as_ref(&self) -> &CFType {
+        self // Through Deref
+    }
+}
+
+impl<K: ?Sized, V: ?Sized> fmt::Debug for CFTypedDictionary<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.dictionary, f)
+    }
+}
+
+impl<K: ConcreteType, V: ConcreteType> CFTypedDictionary<K, V> {
+    /// Create a dictionary from the given keys and their corresponding
+    /// values.
+    ///
+    /// If a key is given multiple times, the last value is used.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` and `values` have different lengths.
+    #[doc(alias = "CFDictionaryCreate")]
+    pub fn from_slices(keys: &[&K], values: &[&V]) -> CFRetained<Self> {
+        assert_eq!(
+            keys.len(),
+            values.len(),
+            "keys and values must have the same length"
+        );
+        let len = keys.len().try_into().expect("slice too large");
+        // `&K` and `&V` are ABI compatible with `*const c_void`, and
+        // `CFDictionaryCreate` does not modify the keys or values.
+        let keys = keys.as_ptr().cast_mut().cast::<*const c_void>();
+        let values = values.as_ptr().cast_mut().cast::<*const c_void>();
+        let dictionary = unsafe {
+            CFDictionaryCreate(
+                None,
+                keys,
+                values,
+                len,
+                &kCFTypeDictionaryKeyCallBacks,
+                &kCFTypeDictionaryValueCallBacks,
+            )
+        }
+        .expect("failed creating CFDictionary");
+        // SAFETY: The dictionary contains keys of type `K` and values of
+        // type `V`.
+        unsafe { CFRetained::cast_unchecked(dictionary) }
+    }
+
+    /// Get the value for the given key, or [`None`] if the dictionary does
+    /// not contain the key.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not of type `V`.
+    #[doc(alias = "CFDictionaryGetValue")]
+    #[doc(alias = "CFDictionaryGetValueIfPresent")]
+    pub fn get(&self, key: &K) -> Option<CFRetained<V>> {
+        let mut value = ptr::null();
+        let present = unsafe { CFDictionaryGetValueIfPresent(self, as_value(key), &mut value) };
+        if present != 0 {
+            // SAFETY: The dictionary retains its values, so the pointer is
+            // valid.
+            Some(unsafe { retain_checked(value, "dictionary", "value") })
+        } else {
+            None
+        }
+    }
+
+    /// Whether the dictionary contains the given key.
+    #[inline]
+    #[doc(alias = "CFDictionaryContainsKey")]
+    pub fn contains_key(&self, key: &K) -> bool {
+        unsafe { CFDictionaryContainsKey(self, as_value(key)) != 0 }
+    }
+
+    /// An iterator over the key-value pairs in the dictionary, in an
+    /// unspecified order.
+    ///
+    /// The pairs are copied out of the dictionary when this is called, so
+    /// the iterator is not affected by later mutations.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if a key is not of type `K`, or a value is not of type `V`.
+    #[cfg(feature = "alloc")]
+    #[doc(alias = "CFDictionaryGetKeysAndValues")]
+    pub fn iter(&self) -> CFTypedDictionaryIter<K, V> {
+        let len = self.len();
+        let mut keys = Vec::with_capacity(len);
+        let mut values = Vec::with_capacity(len);
+        unsafe {
+            crate::CFDictionaryGetKeysAndValues(self, keys.as_mut_ptr(), values.as_mut_ptr());
+            // SAFETY: The buffers were large enough, and have been filled.
+            keys.set_len(len);
+            values.set_len(len);
+        }
+        let pairs: Vec<_> = keys
+            .into_iter()
+            .zip(values)
+            // SAFETY: The dictionary retains its keys and values, so the
+            // pointers are valid.
+            .map(|(key, value)| unsafe {
+                (
+                    retain_checked(key, "dictionary", "key"),
+                    retain_checked(value, "dictionary", "value"),
+                )
+            })
+            .collect();
+        CFTypedDictionaryIter {
+            inner: pairs.into_iter(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: ConcreteType, V: ConcreteType> IntoIterator for &CFTypedDictionary<K, V> {
+    type Item = (CFRetained<K>, CFRetained<V>);
+    type IntoIter = CFTypedDictionaryIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: ConcreteType, V: ConcreteType> FromIterator<(CFRetained<K>, CFRetained<V>)>
+    for CFRetained<CFTypedDictionary<K, V>>
+{
+    fn from_iter<I: IntoIterator<Item = (CFRetained<K>, CFRetained<V>)>>(iter: I) -> Self {
+        let dictionary: CFRetained<CFTypedMutableDictionary<K, V>> = iter.into_iter().collect();
+        // SAFETY: A mutable dictionary is a dictionary.
+        unsafe { CFRetained::cast_unchecked(dictionary) }
+    }
+}
+
+impl<'a, K: ConcreteType, V: ConcreteType> FromIterator<(&'a K, &'a V)>
+    for CFRetained<CFTypedDictionary<K, V>>
+{
+    fn from_iter<I: IntoIterator<Item = (&'a K, &'a V)>>(iter: I) -> Self {
+        let dictionary: CFRetained<CFTypedMutableDictionary<K, V>> = iter.into_iter().collect();
+        // SAFETY: A mutable dictionary is a dictionary.
+        unsafe { CFRetained::cast_unchecked(dictionary) }
+    }
+}
+
+/// An iterator over the key-value pairs of a [`CFTypedDictionary`].
+///
+/// Created by [`CFTypedDictionary::iter`].
+#[cfg(feature = "alloc")]
+pub struct CFTypedDictionaryIter<K, V> {
+    inner: alloc::vec::IntoIter<(CFRetained<K>, CFRetained<V>)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V> fmt::Debug for CFTypedDictionaryIter<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CFTypedDictionaryIter")
+            .field("remaining", &self.inner.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: Type, V: Type> Iterator for CFTypedDictionaryIter<K, V> {
+    type Item = (CFRetained<K>, CFRetained<V>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: Type, V: Type> ExactSizeIterator for CFTypedDictionaryIter<K, V> {}
+
+#[cfg(feature = "alloc")]
+impl<K: Type, V: Type> core::iter::FusedIterator for CFTypedDictionaryIter<K, V> {}
+
+/// A [`CFMutableDictionary`] whose keys are of type `K`, and values of type
+/// `V`.
+///
+/// See [`CFTypedDictionary`] for details.
+#[repr(transparent)]
+#[doc(alias = "CFMutableDictionary")]
+pub struct CFTypedMutableDictionary<K: ?Sized, V: ?Sized> {
+    dictionary: CFMutableDictionary,
+    key: PhantomData<K>,
+    value: PhantomData<V>,
+}
+
+// SAFETY: The dictionary is a `CFMutableDictionary`.
+unsafe impl<K: ?Sized, V: ?Sized> Type for CFTypedMutableDictionary<K, V> {}
+
+impl<K: ?Sized, V: ?Sized> Deref for CFTypedMutableDictionary<K, V> {
+    type Target = CFTypedDictionary<K, V>;
+
+    #[inline]
+    fn deref(&self) -> &CFTypedDictionary<K, V> {
+        let ptr: *const Self = self;
+        // SAFETY: A mutable dictionary is a dictionary, and both types are
+        // `#[repr(transparent)]`.
+        unsafe { &*ptr.cast() }
+    }
+}
+
+impl<K: ?Sized, V: ?Sized> AsRef<CFMutableDictionary> for CFTypedMutableDictionary<K, V> {
+    #[inline]
+    fn as_ref(&self) -> &CFMutableDictionary {
+        &self.dictionary
+    }
+}
+
+impl<K: ?Sized, V: ?Sized> AsRef<CFDictionary> for CFTypedMutableDictionary<K, V> {
+    #[inline]
+    fn as_ref(&self) -> &CFDictionary {
+        self // Through Deref
+    }
+}
+
+impl<K: ?Sized, V: ?Sized> AsRef<CFType> for CFTypedMutableDictionary<K, V> {
+    #[inline]
+    fn as_ref(&self) -> &CFType {
+        self // Through Deref
+    }
+}
+
+impl<K: ?Sized, V: ?Sized> fmt::Debug for CFTypedMutableDictionary<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.dictionary, f)
+    }
+}
+
+impl<K: ConcreteType, V: ConcreteType> CFTypedMutableDictionary<K, V> {
+    /// Create a new empty dictionary.
+    #[inline]
+    #[doc(alias = "CFDictionaryCreateMutable")]
+    pub fn new() -> CFRetained<Self> {
+        let dictionary = unsafe {
+            CFDictionaryCreateMutable(
+                None,
+                0,
+                &kCFTypeDictionaryKeyCallBacks,
+                &kCFTypeDictionaryValueCallBacks,
+            )
+        }
+        .expect("failed creating CFMutableDictionary");
+        // SAFETY: The dictionary is empty.
+        unsafe { CFRetained::cast_unchecked(dictionary) }
+    }
+
+    /// Set the value for the given key, replacing any existing value.
+    #[inline]
+    #[doc(alias = "CFDictionarySetValue")]
+    pub fn insert(&self, key: &K, value: &V) {
+        unsafe {
+            CFDictionarySetValue(Some(&self.dictionary), as_value(key), as_value(value));
+        }
+    }
+
+    /// Remove the given key and its value, if present.
+    #[inline]
+    #[doc(alias = "CFDictionaryRemoveValue")]
+    pub fn remove(&self, key: &K) {
+        unsafe { CFDictionaryRemoveValue(Some(&self.dictionary), as_value(key)) };
+    }
+
+    /// Remove all key-value pairs from the dictionary.
+    #[inline]
+    #[doc(alias = "CFDictionaryRemoveAllValues")]
+    pub fn clear(&self) {
+        unsafe { CFDictionaryRemoveAllValues(Some(&self.dictionary)) };
+    }
+}
+
+impl<K: ConcreteType, V: ConcreteType> FromIterator<(CFRetained<K>, CFRetained<V>)>
+    for CFRetained<CFTypedMutableDictionary<K, V>>
+{
+    fn from_iter<I: IntoIterator<Item = (CFRetained<K>, CFRetained<V>)>>(iter: I) -> Self {
+        let dictionary = CFTypedMutableDictionary::<K, V>::new();
+        for (key, value) in iter {
+            dictionary.insert(&key, &value);
+        }
+        dictionary
+    }
+}
+
+impl<'a, K: ConcreteType, V: ConcreteType> FromIterator<(&'a K, &'a V)>
+    for CFRetained<CFTypedMutableDictionary<K, V>>
+{
+    fn from_iter<I: IntoIterator<Item = (&'a K, &'a V)>>(iter: I) -> Self {
+        let dictionary = CFTypedMutableDictionary::<K, V>::new();
+        for (key, value) in iter {
+            dictionary.insert(key, value);
+        }
+        dictionary
+    }
+}
+
+#[cfg(all(test, feature = "CFNumber", feature = "CFString"))]
+mod tests {
+    use alloc::string::{String, ToString};
+
+    use super::*;
+    use crate::{CFNumber, CFString};
+
+    #[test]
+    fn from_slices() {
+        let a = CFString::from_str("a");
+        let b = CFString::from_str("b");
+        let one = CFNumber::new_i32(1);
+        let two = CFNumber::new_i32(2);
+        let dict = CFTypedDictionary::from_slices(&[&*a, &*b], &[&*one, &*two]);
+        assert_eq!(dict.len(), 2);
+        assert_eq!(dict.get(&a).unwrap().as_i32(), Some(1));
+        assert_eq!(dict.get(&b).unwrap().as_i32(), Some(2));
+        assert!(dict.get(&CFString::from_str("c")).is_none());
+        assert!(dict.contains_key(&a));
+    }
+
+    #[test]
+    #[should_panic = "keys and values must have the same length"]
+    fn from_slices_mismatched() {
+        let a = CFString::from_str("a");
+        let _ = CFTypedDictionary::<CFString, CFNumber>::from_slices(&[&*a], &[]);
+    }
+
+    #[test]
+    fn collect_and_iterate() {
+        let dict: CFRetained<CFTypedDictionary<CFString, CFNumber>> = (0..3)
+            .map(|i| (CFString::from_str(&i.to_string()), CFNumber::new_i32(i)))
+            .collect();
+        let mut pairs: Vec<(String, i32)> = dict
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.as_i32().unwrap()))
+            .collect();
+        pairs.sort();
+        assert_eq!(pairs, [("0".into(), 0), ("1".into(), 1), ("2".into(), 2)]);
+    }
+
+    #[test]
+    fn mutate() {
+        let dict = CFTypedMutableDictionary::<CFString, CFNumber>::new();
+        let key = CFString::from_str("key");
+        dict.insert(&key, &CFNumber::new_i32(1));
+        dict.insert(&key, &CFNumber::new_i32(2));
+        assert_eq!(dict.len(), 1);
+        assert_eq!(dict.get(&key).unwrap().as_i32(), Some(2));
+
+        dict.remove(&key);
+        assert!(dict.is_empty());
+        dict.insert(&key, &CFNumber::new_i32(3));
+        dict.clear();
+        assert!(dict.is_empty());
+    }
+
+    #[test]
+    #[should_panic = "dictionary contained value of wrong type"]
+    fn wrong_type() {
+        let key = CFString::from_str("key");
+        let dict = CFTypedDictionary::from_slices(&[&*key], &[&*key]);
+        let _ = dict.as_typed::<CFString, CFNumber>().get(&key);
+    }
+}
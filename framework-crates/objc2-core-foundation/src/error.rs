@@ -1,14 +1,146 @@
-#![cfg(all(feature = "CFBase", feature = "CFString"))]
+#![cfg(feature = "CFBase")]
+#[cfg(feature = "CFString")]
 use core::fmt;
 
-use crate::{CFError, CFErrorCopyDescription};
+use crate::{
+    CFError, CFErrorCopyDescription, CFErrorCopyFailureReason, CFErrorCopyRecoverySuggestion,
+    CFErrorDomain, CFErrorGetCode, CFErrorGetDomain, CFIndex, CFRetained, CFString,
+};
 
+/// Creation methods.
+///
+/// `CFError` is toll-free bridged with `NSError`; when both crates are
+/// enabled, use `NSError::from_cf_error` and `NSError::into_cf_error` to
+/// convert between the two.
+impl CFError {
+    /// Create a new error with the given code in the given domain, and with
+    /// an optional dictionary of user info.
+    ///
+    /// The user info must have `CFString` keys, and is copied.
+    #[cfg(feature = "CFDictionary")]
+    #[doc(alias = "CFErrorCreate")]
+    pub fn new(
+        domain: &CFErrorDomain,
+        code: CFIndex,
+        user_info: Option<&crate::CFDictionary>,
+    ) -> CFRetained<Self> {
+        unsafe { crate::CFErrorCreate(None, Some(domain), code, user_info) }
+            .expect("failed creating CFError")
+    }
+}
+
+/// Accessor methods.
+impl CFError {
+    /// The domain of the error, such as `kCFErrorDomainPOSIX`.
+    #[doc(alias = "CFErrorGetDomain")]
+    pub fn domain(&self) -> CFRetained<CFErrorDomain> {
+        unsafe { CFErrorGetDomain(self) }.expect("error must have a domain")
+    }
+
+    /// The error code, which is interpreted within the context of the
+    /// [domain](Self::domain).
+    #[doc(alias = "CFErrorGetCode")]
+    pub fn code(&self) -> CFIndex {
+        unsafe { CFErrorGetCode(self) }
+    }
+
+    /// The user info dictionary of the error.
+    ///
+    /// This is empty if the error was created without user info.
+    #[cfg(feature = "CFDictionary")]
+    #[doc(alias = "CFErrorCopyUserInfo")]
+    pub fn user_info(&self) -> CFRetained<crate::CFDictionary> {
+        unsafe { crate::CFErrorCopyUserInfo(self) }.expect("error must have user info")
+    }
+
+    /// A human-presentable description of the error.
+    ///
+    /// This is the same as the [`Display`](core::fmt::Display) output.
+    #[doc(alias = "CFErrorCopyDescription")]
+    pub fn localized_description(&self) -> CFRetained<CFString> {
+        unsafe { CFErrorCopyDescription(self) }.expect("error must have a description")
+    }
+
+    /// A human-presentable reason for the failure, if any.
+    #[doc(alias = "CFErrorCopyFailureReason")]
+    pub fn failure_reason(&self) -> Option<CFRetained<CFString>> {
+        unsafe { CFErrorCopyFailureReason(self) }
+    }
+
+    /// A human-presentable suggestion for recovering from the error, if any.
+    #[doc(alias = "CFErrorCopyRecoverySuggestion")]
+    pub fn recovery_suggestion(&self) -> Option<CFRetained<CFString>> {
+        unsafe { CFErrorCopyRecoverySuggestion(self) }
+    }
+}
+
+#[cfg(feature = "CFString")]
 impl fmt::Display for CFError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let desc = unsafe { CFErrorCopyDescription(self) }.unwrap();
+        let desc = self.localized_description();
         write!(f, "{desc}")
     }
 }
 
-#[cfg(feature = "std")] // use core::error::Error from Rust 1.81 once in MSRV.
-impl std::error::Error for CFError {}
+/// The [`source`] is the error stored under `kCFErrorUnderlyingErrorKey`, if
+/// any.
+///
+/// [`source`]: std::error::Error::source
+#[cfg(all(feature = "CFString", feature = "std"))] // use core::error::Error from Rust 1.81 once in MSRV.
+impl std::error::Error for CFError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        #[cfg(feature = "CFDictionary")]
+        {
+            use crate::CFType;
+
+            let user_info = self.user_info();
+            let key = unsafe { crate::kCFErrorUnderlyingErrorKey }?;
+            let underlying: *const CFType =
+                unsafe { crate::CFDictionaryGetValue(&user_info, (key as *const CFString).cast()) }
+                    .cast();
+            // SAFETY: The user info dictionary is immutable and stored in the
+            // error, so the underlying error (if any) lives at least as long
+            // as the error itself.
+            let underlying: &CFType = unsafe { underlying.as_ref() }?;
+            let underlying: &CFError = underlying.downcast_ref()?;
+            Some(underlying)
+        }
+        #[cfg(not(feature = "CFDictionary"))]
+        None
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "CFDictionary", feature = "CFString", feature = "std"))]
+mod tests {
+    use alloc::string::ToString;
+    use std::error::Error;
+
+    use super::*;
+    use crate::{kCFErrorDomainPOSIX, CFDictionary, CFTypedDictionary};
+
+    #[test]
+    fn accessors() {
+        let domain = unsafe { kCFErrorDomainPOSIX }.unwrap();
+        let error = CFError::new(domain, 2, None);
+        assert_eq!(&*error.domain(), domain);
+        assert_eq!(error.code(), 2);
+        assert_eq!(error.user_info().len(), 0);
+        assert_eq!(error.to_string(), error.localized_description().to_string());
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn underlying_error() {
+        let domain = CFString::from_static_str("rs.objc2.core-foundation.test");
+        let underlying = CFError::new(&domain, 1, None);
+        let key = unsafe { crate::kCFErrorUnderlyingErrorKey }.unwrap();
+        let user_info = CFTypedDictionary::from_slices(&[key], &[&*underlying]);
+        let user_info: &CFDictionary = &user_info;
+        let error = CFError::new(&domain, 2, Some(user_info));
+
+        let source = error.source().unwrap();
+        let source: &CFError = source.downcast_ref().unwrap();
+        assert_eq!(source.code(), 1);
+    }
+}
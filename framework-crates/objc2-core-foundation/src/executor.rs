@@ -0,0 +1,594 @@
+//! Running futures on a run loop.
+//!
+//! Each thread has a [`LocalExecutor`], which polls its tasks from a run
+//! loop source in the common modes. This means that tasks make progress
+//! whenever the thread's run loop runs, including while AppKit or UIKit is
+//! handling events, and that futures can be mixed freely with other run
+//! loop based callbacks.
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use alloc::task::Wake;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt;
+use core::future::Future;
+use core::pin::{pin, Pin};
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Poll, Waker};
+use core::time::Duration;
+use std::sync::Mutex;
+
+use crate::{CFRunLoop, CFRunLoopSourceGuard, CFRunLoopSourceSignaler, CFRunLoopTimerGuard};
+
+type LocalFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+std::thread_local! {
+    static EXECUTOR: Rc<Inner> = Rc::new(Inner::new());
+}
+
+/// The state shared with wakers, which may be on other threads.
+struct Shared {
+    /// The ids of the tasks that have been woken.
+    ready: Mutex<Vec<usize>>,
+    signaler: CFRunLoopSourceSignaler,
+}
+
+struct Inner {
+    tasks: RefCell<Vec<Option<LocalFuture>>>,
+    free: RefCell<Vec<usize>>,
+    shared: Arc<Shared>,
+    _source: CFRunLoopSourceGuard,
+}
+
+impl Inner {
+    fn new() -> Self {
+        let source =
+            CFRunLoopSourceGuard::new(&CFRunLoop::current(), CFRunLoop::common_modes(), 0, || {
+                // The thread local may already have been destroyed if the
+                // run loop runs during thread exit.
+                let _ = EXECUTOR.try_with(|executor| executor.poll_ready());
+            });
+        Self {
+            tasks: RefCell::new(Vec::new()),
+            free: RefCell::new(Vec::new()),
+            shared: Arc::new(Shared {
+                ready: Mutex::new(Vec::new()),
+                signaler: source.signaler(),
+            }),
+            _source: source,
+        }
+    }
+
+    fn spawn(&self, future: LocalFuture) {
+        let id = if let Some(id) = self.free.borrow_mut().pop() {
+            self.tasks.borrow_mut()[id] = Some(future);
+            id
+        } else {
+            let mut tasks = self.tasks.borrow_mut();
+            tasks.push(Some(future));
+            tasks.len() - 1
+        };
+        Arc::new(TaskWaker {
+            id,
+            shared: self.shared.clone(),
+        })
+        .wake();
+    }
+
+    fn poll_ready(&self) {
+        let ready = core::mem::take(&mut *self.shared.ready.lock().unwrap());
+        for id in ready {
+            // Take the future out while polling it, such that it can spawn
+            // other tasks, or run the run loop recursively.
+            let Some(mut future) = self.tasks.borrow_mut().get_mut(id).and_then(Option::take)
+            else {
+                // The task completed before it was woken again.
+                continue;
+            };
+            let waker = Waker::from(Arc::new(TaskWaker {
+                id,
+                shared: self.shared.clone(),
+            }));
+            match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+                Poll::Ready(()) => self.free.borrow_mut().push(id),
+                Poll::Pending => self.tasks.borrow_mut()[id] = Some(future),
+            }
+        }
+    }
+}
+
+struct TaskWaker {
+    id: usize,
+    shared: Arc<Shared>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.shared.ready.lock().unwrap().push(self.id);
+        self.shared.signaler.signal();
+    }
+}
+
+/// An executor for futures that runs on the current thread's run loop.
+///
+/// The executor is not [`Send`], so its futures do not need to be either,
+/// and can use thread-bound objects freely. Wakers can be used from any
+/// thread.
+///
+///
+/// # Example
+///
+/// Spawn a task on the main thread of an application, and let the
+/// application's event loop drive it.
+///
+/// ```ignore
+/// use std::time::Duration;
+/// use objc2_core_foundation::{sleep, LocalExecutor};
+///
+/// LocalExecutor::current().spawn(async {
+///     sleep(Duration::from_secs(1)).await;
+///     println!("one second has passed");
+/// }).detach();
+///
+/// // Run the application, e.g. with `NSApplication::run`.
+/// ```
+#[derive(Clone)]
+pub struct LocalExecutor {
+    inner: Rc<Inner>,
+}
+
+impl LocalExecutor {
+    /// The executor of the current thread.
+    pub fn current() -> Self {
+        Self {
+            inner: EXECUTOR.with(Rc::clone),
+        }
+    }
+
+    /// Spawn a task on the executor.
+    ///
+    /// The task is polled the next time the run loop runs in one of the
+    /// common modes. It keeps running even if the returned handle is
+    /// dropped.
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        let (handle, future) = JoinHandle::new(future);
+        self.inner.spawn(Box::pin(future));
+        handle
+    }
+
+    /// Run the thread's run loop in the default mode until the future
+    /// completes.
+    ///
+    /// Other tasks on the executor, as well as other run loop sources, are
+    /// run while waiting.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        let mut future = pin!(future);
+        let waker = Arc::new(BlockOnWaker {
+            woken: AtomicBool::new(true),
+            signaler: self.inner.shared.signaler.clone(),
+        });
+        let context_waker = Waker::from(waker.clone());
+        let mut cx = Context::from_waker(&context_waker);
+        loop {
+            if waker.woken.swap(false, Ordering::SeqCst) {
+                if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                    return output;
+                }
+            }
+            // The executor's source is always added to the run loop, so this
+            // will wait until it, or another source, is handled.
+            CFRunLoop::run_in_mode(CFRunLoop::default_mode(), Duration::MAX, true);
+        }
+    }
+
+    /// Spawn a task on the main thread's executor.
+    ///
+    /// The task is run the next time the main run loop runs in one of the
+    /// common modes, e.g. while `NSApplication` is running.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use objc2_core_foundation::LocalExecutor;
+    ///
+    /// std::thread::spawn(|| {
+    ///     let result = compute();
+    ///     LocalExecutor::run_on_main(async move {
+    ///         // Update the user interface with the result.
+    ///     })
+    ///     .detach();
+    /// });
+    /// ```
+    #[cfg(feature = "block2")]
+    pub fn run_on_main<F>(future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let (handle, future) = JoinHandle::new(future);
+        CFRunLoop::main().scheduler().perform(move || {
+            LocalExecutor::current().inner.spawn(Box::pin(future));
+        });
+        handle
+    }
+}
+
+impl fmt::Debug for LocalExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalExecutor")
+            .field("tasks", &self.inner.tasks.borrow().iter().flatten().count())
+            .finish_non_exhaustive()
+    }
+}
+
+struct BlockOnWaker {
+    woken: AtomicBool,
+    signaler: CFRunLoopSourceSignaler,
+}
+
+impl Wake for BlockOnWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+        // Make `block_on` return from the run loop.
+        self.signaler.signal();
+    }
+}
+
+struct JoinState<T> {
+    output: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// A handle to a task spawned on a [`LocalExecutor`].
+///
+/// Awaiting the handle returns the output of the task. Dropping the handle
+/// lets the task keep running in the background.
+#[must_use = "use `detach` to let the task run in the background"]
+pub struct JoinHandle<T> {
+    state: Arc<Mutex<JoinState<T>>>,
+}
+
+impl<T> JoinHandle<T> {
+    fn new<F>(future: F) -> (Self, impl Future<Output = ()>)
+    where
+        F: Future<Output = T>,
+    {
+        let state = Arc::new(Mutex::new(JoinState {
+            output: None,
+            waker: None,
+        }));
+        let task_state = state.clone();
+        let future = async move {
+            let output = future.await;
+            let mut state = task_state.lock().unwrap();
+            state.output = Some(output);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        };
+        (Self { state }, future)
+    }
+
+    /// Whether the task has completed.
+    pub fn is_finished(&self) -> bool {
+        self.state.lock().unwrap().output.is_some()
+    }
+
+    /// Let the task run in the background.
+    pub fn detach(self) {}
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.lock().unwrap();
+        match state.output.take() {
+            Some(output) => Poll::Ready(output),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinHandle")
+            .field("finished", &self.is_finished())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Wait for the given duration, using a timer on the current thread's run
+/// loop.
+///
+/// The timer is added to the common modes.
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        duration,
+        state: None,
+    }
+}
+
+/// The future returned by [`sleep`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Sleep {
+    duration: Duration,
+    state: Option<(CFRunLoopTimerGuard, Rc<ReadyState>)>,
+}
+
+/// A flag that is set from a run loop callback, along with the waker of the
+/// task waiting for it.
+#[derive(Default)]
+struct ReadyState {
+    ready: core::cell::Cell<bool>,
+    waker: RefCell<Option<Waker>>,
+}
+
+impl ReadyState {
+    fn set_ready(&self) {
+        self.ready.set(true);
+        if let Some(waker) = self.waker.borrow_mut().take() {
+            waker.wake();
+        }
+    }
+
+    fn poll(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.ready.get() {
+            Poll::Ready(())
+        } else {
+            *self.waker.borrow_mut() = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let duration = self.duration;
+        let (_, state) = self.state.get_or_insert_with(|| {
+            let state = Rc::new(ReadyState::default());
+            let timer_state = state.clone();
+            let timer = CFRunLoopTimerGuard::new_local(
+                CFRunLoop::common_modes(),
+                duration,
+                None,
+                move || timer_state.set_ready(),
+            );
+            (timer, state)
+        });
+        state.poll(cx)
+    }
+}
+
+impl fmt::Debug for Sleep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sleep")
+            .field("duration", &self.duration)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "CFFileDescriptor")]
+mod fd {
+    use super::*;
+    use core::ffi::c_void;
+
+    use crate::{
+        kCFFileDescriptorReadCallBack, kCFFileDescriptorWriteCallBack, CFFileDescriptor,
+        CFFileDescriptorContext, CFFileDescriptorCreate, CFFileDescriptorCreateRunLoopSource,
+        CFFileDescriptorEnableCallBacks, CFFileDescriptorInvalidate, CFOptionFlags, CFRetained,
+        CFRunLoopAddSource, CFRunLoopSource, CFRunLoopSourceInvalidate,
+    };
+
+    /// Wait until the file descriptor is readable, using the current
+    /// thread's run loop.
+    ///
+    /// The file descriptor is not closed when the future is dropped.
+    #[doc(alias = "kCFFileDescriptorReadCallBack")]
+    pub fn readable(fd: std::os::fd::RawFd) -> FdReady {
+        FdReady::new(fd, kCFFileDescriptorReadCallBack)
+    }
+
+    /// Wait until the file descriptor is writable, using the current
+    /// thread's run loop.
+    ///
+    /// The file descriptor is not closed when the future is dropped.
+    #[doc(alias = "kCFFileDescriptorWriteCallBack")]
+    pub fn writable(fd: std::os::fd::RawFd) -> FdReady {
+        FdReady::new(fd, kCFFileDescriptorWriteCallBack)
+    }
+
+    /// The future returned by [`readable`] and [`writable`].
+    #[must_use = "futures do nothing unless polled"]
+    pub struct FdReady {
+        fd: std::os::fd::RawFd,
+        callback_types: CFOptionFlags,
+        state: Option<FdState>,
+    }
+
+    struct FdState {
+        descriptor: CFRetained<CFFileDescriptor>,
+        source: CFRetained<CFRunLoopSource>,
+        ready: Rc<ReadyState>,
+    }
+
+    impl FdReady {
+        fn new(fd: std::os::fd::RawFd, callback_types: CFOptionFlags) -> Self {
+            Self {
+                fd,
+                callback_types,
+                state: None,
+            }
+        }
+    }
+
+    unsafe extern "C-unwind" fn callout(
+        _descriptor: *mut CFFileDescriptor,
+        _callback_types: CFOptionFlags,
+        info: *mut c_void,
+    ) {
+        // SAFETY: The info is the ready state, which is alive for as long as
+        // the descriptor is.
+        let ready = unsafe { &*info.cast::<ReadyState>() };
+        ready.set_ready();
+    }
+
+    unsafe extern "C-unwind" fn release(info: *mut c_void) {
+        // SAFETY: The info was created with `Rc::into_raw`, and Core
+        // Foundation releases it exactly once.
+        drop(unsafe { Rc::from_raw(info.cast::<ReadyState>()) });
+    }
+
+    impl FdState {
+        fn new(fd: std::os::fd::RawFd, callback_types: CFOptionFlags) -> Self {
+            let ready = Rc::new(ReadyState::default());
+            let context = CFFileDescriptorContext {
+                version: 0,
+                info: Rc::into_raw(ready.clone()).cast_mut().cast(),
+                retain: None,
+                release: Some(release),
+                copyDescription: None,
+            };
+            // SAFETY: The callout and context are valid, and the context is
+            // copied. The descriptor is only used on this thread, since
+            // `FdReady` is not `Send`.
+            let descriptor =
+                unsafe { CFFileDescriptorCreate(None, fd, false as _, Some(callout), &context) }
+                    .expect("failed creating CFFileDescriptor");
+            let source = unsafe { CFFileDescriptorCreateRunLoopSource(None, Some(&descriptor), 0) }
+                .expect("failed creating CFFileDescriptor run loop source");
+            unsafe {
+                CFRunLoopAddSource(
+                    &CFRunLoop::current(),
+                    Some(&source),
+                    Some(CFRunLoop::common_modes()),
+                )
+            };
+            // Callbacks are disabled again after they have fired once.
+            unsafe { CFFileDescriptorEnableCallBacks(&descriptor, callback_types) };
+            Self {
+                descriptor,
+                source,
+                ready,
+            }
+        }
+    }
+
+    impl Drop for FdState {
+        fn drop(&mut self) {
+            unsafe { CFRunLoopSourceInvalidate(&self.source) };
+            unsafe { CFFileDescriptorInvalidate(&self.descriptor) };
+        }
+    }
+
+    impl Future for FdReady {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            let (fd, callback_types) = (self.fd, self.callback_types);
+            let state = self
+                .state
+                .get_or_insert_with(|| FdState::new(fd, callback_types));
+            state.ready.poll(cx)
+        }
+    }
+
+    impl fmt::Debug for FdReady {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("FdReady")
+                .field("fd", &self.fd)
+                .field("callback_types", &self.callback_types)
+                .finish_non_exhaustive()
+        }
+    }
+}
+
+#[cfg(feature = "CFFileDescriptor")]
+pub use self::fd::{readable, writable, FdReady};
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use core::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn block_on_spawned() {
+        let executor = LocalExecutor::current();
+        let value = Rc::new(Cell::new(0));
+        let value_clone = value.clone();
+        let handle = executor.spawn(async move {
+            value_clone.set(1);
+            2
+        });
+        assert!(!handle.is_finished());
+        assert_eq!(executor.block_on(handle), 2);
+        assert_eq!(value.get(), 1);
+    }
+
+    #[test]
+    fn wake_from_other_thread() {
+        let executor = LocalExecutor::current();
+        let done = Arc::new(AtomicBool::new(false));
+        let mut started = false;
+        executor.block_on(core::future::poll_fn(|cx| {
+            if done.load(Ordering::SeqCst) {
+                return Poll::Ready(());
+            }
+            if !started {
+                started = true;
+                let done = done.clone();
+                let waker = cx.waker().clone();
+                std::thread::spawn(move || {
+                    done.store(true, Ordering::SeqCst);
+                    waker.wake();
+                });
+            }
+            Poll::Pending
+        }));
+    }
+
+    #[test]
+    fn sleep_in_task() {
+        let executor = LocalExecutor::current();
+        let handle = executor.spawn(async {
+            sleep(Duration::from_millis(5)).await;
+            "slept"
+        });
+        assert_eq!(executor.block_on(handle), "slept");
+    }
+
+    #[test]
+    #[cfg(feature = "CFFileDescriptor")]
+    fn readable_socket() {
+        use std::io::Write;
+        use std::os::fd::AsRawFd;
+        use std::os::unix::net::UnixStream;
+
+        let (mut a, b) = UnixStream::pair().unwrap();
+        let executor = LocalExecutor::current();
+        let handle = executor.spawn(readable(b.as_raw_fd()));
+        a.write_all(b"hello").unwrap();
+        executor.block_on(handle);
+    }
+}
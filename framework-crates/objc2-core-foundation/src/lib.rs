@@ -21,39 +21,226 @@ extern crate std;
 #[doc(hidden)]
 pub mod __cf_macro_helpers;
 #[cfg(feature = "CFBase")]
+#[doc(hidden)]
+pub mod __cf_helpers;
+#[cfg(all(feature = "CFBase", feature = "alloc"))]
+mod allocator;
+#[cfg(all(feature = "CFArray", feature = "CFBase"))]
+mod array;
+#[cfg(all(feature = "CFBag", feature = "CFBase"))]
+mod bag;
+#[cfg(feature = "CFBase")]
 mod base;
+#[cfg(all(feature = "CFBase", feature = "CFBinaryHeap"))]
+mod binary_heap;
 #[cfg(feature = "CFBundle")]
 mod bundle;
+#[cfg(feature = "CFCalendar")]
+mod calendar;
 mod cf_type;
 #[cfg(feature = "CFData")]
 mod data;
 #[cfg(feature = "CFDate")]
 mod date;
+#[cfg(all(feature = "CFBase", feature = "CFDictionary"))]
+mod dictionary;
 #[cfg(feature = "CFError")]
 mod error;
+#[cfg(all(feature = "CFBase", feature = "CFDate", feature = "CFRunLoop", feature = "std"))]
+mod executor;
 mod generated;
 #[cfg(feature = "CFCGTypes")]
 mod geometry;
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFMachPort",
+    feature = "CFRunLoop",
+    feature = "alloc"
+))]
+mod mach_port;
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFData",
+    feature = "CFMessagePort",
+    feature = "CFRunLoop",
+    feature = "alloc"
+))]
+mod message_port;
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFDictionary",
+    feature = "CFNotificationCenter",
+    feature = "CFRunLoop",
+    feature = "alloc"
+))]
+mod notification_center;
 #[cfg(feature = "CFNumber")]
 mod number;
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFArray",
+    feature = "CFData",
+    feature = "CFDictionary",
+    feature = "CFError",
+    feature = "CFNumber",
+    feature = "CFPropertyList",
+    feature = "CFString",
+    feature = "alloc"
+))]
+pub mod property_list;
 mod retained;
+#[cfg(all(feature = "CFBase", feature = "CFRunLoop", feature = "alloc"))]
+mod run_loop;
+#[cfg(all(feature = "CFBase", feature = "CFSet"))]
+mod set;
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFData",
+    feature = "CFRunLoop",
+    feature = "CFSocket",
+    feature = "alloc"
+))]
+mod socket;
+#[cfg(all(
+    target_vendor = "apple",
+    feature = "CFArray",
+    feature = "CFBase",
+    feature = "CFDictionary",
+    feature = "CFNumber",
+    feature = "CFStream"
+))]
+mod stream;
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFError",
+    feature = "CFRunLoop",
+    feature = "CFStream",
+    feature = "alloc"
+))]
+mod stream_io;
 #[cfg(feature = "CFString")]
 mod string;
 #[cfg(feature = "CFTimeZone")]
 mod timezone;
 mod type_traits;
+#[cfg(feature = "CFURL")]
+mod url;
 #[cfg(feature = "CFUUID")]
 mod uuid;
 
+#[cfg(all(feature = "CFArray", feature = "CFBase"))]
+pub use self::array::{CFTypedArray, CFTypedArrayIter, CFTypedMutableArray};
+#[cfg(all(feature = "CFBag", feature = "CFBase"))]
+pub use self::bag::{CFTypedBag, CFTypedMutableBag};
+#[cfg(all(feature = "CFBag", feature = "CFBase", feature = "alloc"))]
+pub use self::bag::CFTypedBagIter;
 #[cfg(feature = "CFBase")]
 pub use self::base::*;
+#[cfg(all(feature = "CFBase", feature = "CFBinaryHeap"))]
+pub use self::binary_heap::CFTypedBinaryHeap;
 #[cfg(feature = "CFBundle")]
 pub use self::bundle::CFBundleRefNum;
+#[cfg(all(feature = "CFBase", feature = "CFCalendar", feature = "CFDate"))]
+pub use self::calendar::CFCalendarComponents;
+#[cfg(all(feature = "CFBase", feature = "CFDate", feature = "std"))]
+pub use self::date::{absolute_time_from_system_time, system_time_from_absolute_time};
+#[cfg(all(feature = "CFBase", feature = "CFDictionary"))]
+pub use self::dictionary::{CFTypedDictionary, CFTypedMutableDictionary};
+#[cfg(all(feature = "CFBase", feature = "CFDictionary", feature = "alloc"))]
+pub use self::dictionary::CFTypedDictionaryIter;
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFDate",
+    feature = "CFFileDescriptor",
+    feature = "CFRunLoop",
+    feature = "std"
+))]
+pub use self::executor::{readable, writable, FdReady};
+#[cfg(all(feature = "CFBase", feature = "CFDate", feature = "CFRunLoop", feature = "std"))]
+pub use self::executor::{sleep, JoinHandle, LocalExecutor, Sleep};
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
 #[cfg(feature = "CFCGTypes")]
 pub use self::geometry::*;
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFMachPort",
+    feature = "CFRunLoop",
+    feature = "alloc"
+))]
+pub use self::mach_port::CFMachPortGuard;
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFData",
+    feature = "CFMessagePort",
+    feature = "CFRunLoop",
+    feature = "alloc"
+))]
+pub use self::message_port::{CFMessagePortError, CFMessagePortGuard};
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFDictionary",
+    feature = "CFNotificationCenter",
+    feature = "CFRunLoop",
+    feature = "alloc"
+))]
+pub use self::notification_center::CFNotificationObserverGuard;
+#[cfg(all(feature = "CFBase", feature = "CFNumber"))]
+pub use self::number::CFNumberConversionError;
 pub use self::retained::CFRetained;
+#[cfg(all(feature = "CFBase", feature = "CFDate", feature = "CFRunLoop", feature = "alloc"))]
+pub use self::run_loop::CFRunLoopTimerGuard;
+#[cfg(all(feature = "CFBase", feature = "CFRunLoop", feature = "alloc"))]
+pub use self::run_loop::{
+    CFRunLoopObserverGuard, CFRunLoopScheduler, CFRunLoopSourceGuard, CFRunLoopSourceSignaler,
+};
+#[cfg(all(feature = "CFBase", feature = "CFSet"))]
+pub use self::set::{CFTypedMutableSet, CFTypedSet};
+#[cfg(all(feature = "CFBase", feature = "CFSet", feature = "alloc"))]
+pub use self::set::CFTypedSetIter;
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFData",
+    feature = "CFRunLoop",
+    feature = "CFSocket",
+    feature = "alloc"
+))]
+pub use self::socket::{CFSocketEvent, CFSocketGuard};
+#[cfg(all(
+    target_vendor = "apple",
+    feature = "CFArray",
+    feature = "CFBase",
+    feature = "CFDictionary",
+    feature = "CFNumber",
+    feature = "CFStream"
+))]
+pub use self::stream::{CFStreamPeerName, CFStreamSecurityLevel, CFStreamTLSSettings};
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFError",
+    feature = "CFRunLoop",
+    feature = "CFStream",
+    feature = "alloc"
+))]
+pub use self::stream_io::{bound_stream_pair, CFReadStreamClientGuard, CFWriteStreamClientGuard};
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFError",
+    feature = "CFRunLoop",
+    feature = "CFSocket",
+    feature = "CFStream",
+    feature = "alloc"
+))]
+pub use self::stream_io::socket_stream_pair;
+#[cfg(all(
+    feature = "CFBase",
+    feature = "CFError",
+    feature = "CFRunLoop",
+    feature = "CFStream",
+    feature = "CFString",
+    feature = "alloc"
+))]
+pub use self::stream_io::host_stream_pair;
 pub use self::type_traits::{ConcreteType, Type};
 
 // MacTypes.h
@@ -0,0 +1,224 @@
+//! Safe wrappers around `CFMachPort`.
+//!
+//! Like the run loop guards, the closure given to the port is owned by Core
+//! Foundation, and the guard invalidates the port when dropped.
+use alloc::boxed::Box;
+use core::ffi::c_void;
+use core::fmt;
+use core::slice;
+
+use crate::{
+    CFIndex, CFMachPort, CFMachPortContext, CFMachPortCreate, CFMachPortCreateRunLoopSource,
+    CFMachPortInvalidate, CFMachPortIsValid, CFRetained, CFRunLoop, CFRunLoopAddSource,
+    CFRunLoopMode,
+};
+#[cfg(feature = "libc")]
+use crate::{CFMachPortCreateWithPort, CFMachPortGetPort};
+
+/// Release the boxed closure stored in a context's `info`.
+unsafe extern "C-unwind" fn release<F>(info: *const c_void) {
+    // SAFETY: The info was created with `Box::into_raw` when creating the
+    // port, and Core Foundation releases it exactly once.
+    drop(unsafe { Box::from_raw(info.cast::<F>().cast_mut()) });
+}
+
+/// A Mach port that calls a closure when it receives a message, and which
+/// is invalidated when dropped.
+///
+/// The port is scheduled on a run loop, and the closure is called on that
+/// run loop while it is running in the given mode.
+///
+/// The closure is given the raw bytes of the message, starting with the
+/// `mach_msg_header_t`.
+#[must_use = "the port is invalidated when this is dropped"]
+pub struct CFMachPortGuard {
+    port: CFRetained<CFMachPort>,
+}
+
+impl CFMachPortGuard {
+    /// Create a new Mach port with a receive right, and call the closure
+    /// with each message that it receives.
+    ///
+    /// Returns [`None`] if the port could not be created.
+    #[doc(alias = "CFMachPortCreate")]
+    #[doc(alias = "CFMachPortCreateRunLoopSource")]
+    pub fn new(
+        run_loop: &CFRunLoop,
+        mode: &CFRunLoopMode,
+        handler: impl Fn(&CFMachPort, &[u8]) + Send + 'static,
+    ) -> Option<Self> {
+        // SAFETY: The closure is `Send`, so it is fine to call and drop it
+        // on the run loop's thread.
+        unsafe {
+            Self::new_unchecked(run_loop, mode, handler, |callout, context, should_free| {
+                CFMachPortCreate(None, callout, context, should_free)
+            })
+        }
+    }
+
+    /// Same as [`new`][Self::new], but the port is scheduled on the current
+    /// thread's run loop, which allows the closure to not be `Send`.
+    pub fn new_local(
+        mode: &CFRunLoopMode,
+        handler: impl Fn(&CFMachPort, &[u8]) + 'static,
+    ) -> Option<Self> {
+        // SAFETY: The port is scheduled on the current thread's run loop,
+        // and the guard is not `Send`, so it is also invalidated from this
+        // thread.
+        unsafe {
+            Self::new_unchecked(
+                &CFRunLoop::current(),
+                mode,
+                handler,
+                |callout, context, should_free| {
+                    CFMachPortCreate(None, callout, context, should_free)
+                },
+            )
+        }
+    }
+
+    /// Wrap an existing Mach port, and call the closure with each message
+    /// that it receives.
+    ///
+    /// Returns [`None`] if the port could not be wrapped, including if it is
+    /// already wrapped by another `CFMachPort`.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The port must be a valid Mach port name with a receive right, and the
+    /// right must not be deallocated while the guard is alive.
+    #[cfg(feature = "libc")]
+    #[doc(alias = "CFMachPortCreateWithPort")]
+    #[doc(alias = "CFMachPortCreateRunLoopSource")]
+    pub unsafe fn from_port(
+        run_loop: &CFRunLoop,
+        mode: &CFRunLoopMode,
+        port: u32,
+        handler: impl Fn(&CFMachPort, &[u8]) + Send + 'static,
+    ) -> Option<Self> {
+        // SAFETY: The closure is `Send`, and the caller upholds the safety
+        // requirements of the port.
+        unsafe {
+            Self::new_unchecked(run_loop, mode, handler, |callout, context, should_free| {
+                CFMachPortCreateWithPort(None, port, callout, context, should_free)
+            })
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The closure must be safe to call and drop on the run loop's thread,
+    /// and `create` must create a port with the given callout and context.
+    unsafe fn new_unchecked<F: Fn(&CFMachPort, &[u8]) + 'static>(
+        run_loop: &CFRunLoop,
+        mode: &CFRunLoopMode,
+        handler: F,
+        create: impl FnOnce(
+            crate::CFMachPortCallBack,
+            *mut CFMachPortContext,
+            *mut crate::Boolean,
+        ) -> Option<CFRetained<CFMachPort>>,
+    ) -> Option<Self> {
+        unsafe extern "C-unwind" fn callout<F: Fn(&CFMachPort, &[u8])>(
+            port: *mut CFMachPort,
+            msg: *mut c_void,
+            size: CFIndex,
+            info: *mut c_void,
+        ) {
+            // SAFETY: The info is the closure, which is alive for as long as
+            // the port is.
+            let handler = unsafe { &*info.cast::<F>() };
+            // SAFETY: The port is valid for the duration of the callout.
+            let port = unsafe { &*port };
+            // SAFETY: The message is `size` bytes long, and is valid for the
+            // duration of the callout.
+            let msg = unsafe { slice::from_raw_parts(msg.cast::<u8>(), size as usize) };
+            handler(port, msg);
+        }
+
+        let info = Box::into_raw(Box::new(handler));
+        let mut context = CFMachPortContext {
+            version: 0,
+            info: info.cast(),
+            retain: None,
+            release: Some(release::<F>),
+            copyDescription: None,
+        };
+        let mut should_free_info = 0;
+        let port = create(Some(callout::<F>), &mut context, &mut should_free_info);
+        // If the Mach port is already wrapped, the existing `CFMachPort` is
+        // returned, and our context is ignored.
+        if should_free_info != 0 {
+            // SAFETY: The context was not used, so we still own the info.
+            drop(unsafe { Box::from_raw(info) });
+            return None;
+        }
+        let Some(port) = port else {
+            // SAFETY: The port was not created, so we still own the info.
+            drop(unsafe { Box::from_raw(info) });
+            return None;
+        };
+
+        let source = unsafe { CFMachPortCreateRunLoopSource(None, Some(&port), 0) };
+        let Some(source) = source else {
+            unsafe { CFMachPortInvalidate(&port) };
+            return None;
+        };
+        unsafe { CFRunLoopAddSource(run_loop, Some(&source), Some(mode)) };
+        Some(Self { port })
+    }
+
+    /// The underlying port.
+    pub fn port(&self) -> &CFMachPort {
+        &self.port
+    }
+
+    /// The Mach port name, which other tasks can be given a send right to.
+    #[cfg(feature = "libc")]
+    #[doc(alias = "CFMachPortGetPort")]
+    pub fn mach_port(&self) -> u32 {
+        unsafe { CFMachPortGetPort(&self.port) }
+    }
+
+    /// Whether the port is still valid.
+    #[doc(alias = "CFMachPortIsValid")]
+    pub fn is_valid(&self) -> bool {
+        unsafe { CFMachPortIsValid(&self.port) != 0 }
+    }
+}
+
+impl Drop for CFMachPortGuard {
+    #[doc(alias = "CFMachPortInvalidate")]
+    fn drop(&mut self) {
+        unsafe { CFMachPortInvalidate(&self.port) };
+    }
+}
+
+impl fmt::Debug for CFMachPortGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CFMachPortGuard")
+            .field("port", &self.port)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+#[cfg(target_vendor = "apple")]
+mod tests {
+    use super::*;
+    use crate::Type;
+
+    #[test]
+    fn create_and_invalidate() {
+        let guard =
+            CFMachPortGuard::new_local(CFRunLoop::default_mode(), |_port, _msg| {}).unwrap();
+        assert!(guard.is_valid());
+        #[cfg(feature = "libc")]
+        assert_ne!(guard.mach_port(), 0);
+
+        let port = guard.port().retain();
+        drop(guard);
+        assert!(unsafe { CFMachPortIsValid(&port) } == 0);
+    }
+}
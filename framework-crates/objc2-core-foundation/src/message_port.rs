@@ -0,0 +1,399 @@
+//! Safe wrappers around `CFMessagePort`.
+//!
+//! Like the run loop guards, the closure given to a local port is owned by
+//! Core Foundation, and the guard invalidates the port when dropped.
+use alloc::boxed::Box;
+use core::ffi::c_void;
+use core::fmt;
+#[cfg(feature = "CFDate")]
+use core::ptr;
+#[cfg(feature = "CFDate")]
+use core::ptr::NonNull;
+#[cfg(feature = "CFDate")]
+use core::time::Duration;
+
+#[cfg(feature = "CFDate")]
+use crate::{
+    kCFMessagePortBecameInvalidError, kCFMessagePortIsInvalid, kCFMessagePortReceiveTimeout,
+    kCFMessagePortSendTimeout, kCFMessagePortSuccess, kCFMessagePortTransportError,
+    CFMessagePortSendRequest,
+};
+use crate::{
+    CFData, CFMessagePort, CFMessagePortContext, CFMessagePortCreateLocal,
+    CFMessagePortCreateRemote, CFMessagePortCreateRunLoopSource, CFMessagePortGetName,
+    CFMessagePortInvalidate, CFMessagePortIsRemote, CFMessagePortIsValid, CFRetained, CFRunLoop,
+    CFRunLoopAddSource, CFRunLoopMode, CFString,
+};
+
+/// An error that occurred while sending a message on a [`CFMessagePort`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CFMessagePortError {
+    /// The message could not be sent before the send timeout elapsed.
+    #[doc(alias = "kCFMessagePortSendTimeout")]
+    SendTimeout,
+    /// No reply was received before the receive timeout elapsed.
+    #[doc(alias = "kCFMessagePortReceiveTimeout")]
+    ReceiveTimeout,
+    /// The port was invalid.
+    #[doc(alias = "kCFMessagePortIsInvalid")]
+    IsInvalid,
+    /// An error occurred while sending the message.
+    #[doc(alias = "kCFMessagePortTransportError")]
+    TransportError,
+    /// The port became invalid while waiting for a reply.
+    #[doc(alias = "kCFMessagePortBecameInvalidError")]
+    BecameInvalid,
+    /// An unknown error code.
+    Unknown(i32),
+}
+
+#[cfg(feature = "CFDate")]
+impl CFMessagePortError {
+    #[allow(non_upper_case_globals)]
+    fn result(code: i32) -> Result<(), Self> {
+        Err(match code {
+            kCFMessagePortSuccess => return Ok(()),
+            kCFMessagePortSendTimeout => Self::SendTimeout,
+            kCFMessagePortReceiveTimeout => Self::ReceiveTimeout,
+            kCFMessagePortIsInvalid => Self::IsInvalid,
+            kCFMessagePortTransportError => Self::TransportError,
+            kCFMessagePortBecameInvalidError => Self::BecameInvalid,
+            code => Self::Unknown(code),
+        })
+    }
+}
+
+impl fmt::Display for CFMessagePortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::SendTimeout => write!(f, "timed out sending message"),
+            Self::ReceiveTimeout => write!(f, "timed out waiting for reply"),
+            Self::IsInvalid => write!(f, "message port is invalid"),
+            Self::TransportError => write!(f, "failed sending message"),
+            Self::BecameInvalid => write!(f, "message port became invalid"),
+            Self::Unknown(code) => write!(f, "unknown message port error {code}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")] // use core::error::Error from Rust 1.81 once in MSRV.
+impl std::error::Error for CFMessagePortError {}
+
+impl CFMessagePort {
+    /// Connect to the local port with the given name, which may be in
+    /// another process.
+    ///
+    /// Returns [`None`] if no port with that name exists.
+    #[doc(alias = "CFMessagePortCreateRemote")]
+    pub fn remote(name: &CFString) -> Option<CFRetained<Self>> {
+        unsafe { CFMessagePortCreateRemote(None, Some(name)) }
+    }
+
+    /// The name of the port, if any.
+    #[doc(alias = "CFMessagePortGetName")]
+    pub fn name(&self) -> Option<CFRetained<CFString>> {
+        unsafe { CFMessagePortGetName(self) }
+    }
+
+    /// Whether this is a remote port, i.e. one that messages are sent to.
+    #[doc(alias = "CFMessagePortIsRemote")]
+    pub fn is_remote(&self) -> bool {
+        unsafe { CFMessagePortIsRemote(self) != 0 }
+    }
+
+    /// Whether the port is still valid.
+    #[doc(alias = "CFMessagePortIsValid")]
+    pub fn is_valid(&self) -> bool {
+        unsafe { CFMessagePortIsValid(self) != 0 }
+    }
+
+    /// Send a message to the remote port without waiting for a reply,
+    /// waiting at most `timeout` for the message to be sent.
+    #[cfg(feature = "CFDate")]
+    #[doc(alias = "CFMessagePortSendRequest")]
+    pub fn send(
+        &self,
+        message_id: i32,
+        data: Option<&CFData>,
+        timeout: Duration,
+    ) -> Result<(), CFMessagePortError> {
+        let code = unsafe {
+            CFMessagePortSendRequest(
+                self,
+                message_id,
+                data,
+                timeout.as_secs_f64(),
+                0.0,
+                None,
+                ptr::null_mut(),
+            )
+        };
+        CFMessagePortError::result(code)
+    }
+
+    /// Send a message to the remote port, and wait for the reply.
+    ///
+    /// While waiting, the current thread's run loop is run in `reply_mode`.
+    /// This blocks for at most `send_timeout` while sending the message, and
+    /// then for at most `receive_timeout` while waiting for the reply.
+    ///
+    /// Returns [`None`] if the handler of the local port did not return any
+    /// data.
+    #[cfg(feature = "CFDate")]
+    #[doc(alias = "CFMessagePortSendRequest")]
+    pub fn send_request(
+        &self,
+        message_id: i32,
+        data: Option<&CFData>,
+        send_timeout: Duration,
+        receive_timeout: Duration,
+        reply_mode: &CFRunLoopMode,
+    ) -> Result<Option<CFRetained<CFData>>, CFMessagePortError> {
+        let mut reply: *const CFData = ptr::null();
+        let code = unsafe {
+            CFMessagePortSendRequest(
+                self,
+                message_id,
+                data,
+                send_timeout.as_secs_f64(),
+                receive_timeout.as_secs_f64(),
+                Some(reply_mode),
+                &mut reply,
+            )
+        };
+        CFMessagePortError::result(code)?;
+        // SAFETY: The reply follows the create rule.
+        Ok(NonNull::new(reply.cast_mut()).map(|reply| unsafe { CFRetained::from_raw(reply) }))
+    }
+}
+
+/// Release the boxed closure stored in a context's `info`.
+unsafe extern "C-unwind" fn release<F>(info: *const c_void) {
+    // SAFETY: The info was created with `Box::into_raw` when creating the
+    // port, and Core Foundation releases it exactly once.
+    drop(unsafe { Box::from_raw(info.cast::<F>().cast_mut()) });
+}
+
+/// A local message port that calls a closure when it receives a message,
+/// and which is invalidated when dropped.
+///
+/// The port is scheduled on a run loop, and the closure is called on that
+/// run loop while it is running in the given mode. Other processes (and
+/// threads) can send messages to the port with [`CFMessagePort::remote`].
+///
+/// The closure is given the message ID and data, and the data it returns is
+/// sent back as the reply.
+#[must_use = "the port is invalidated when this is dropped"]
+pub struct CFMessagePortGuard {
+    port: CFRetained<CFMessagePort>,
+}
+
+impl CFMessagePortGuard {
+    /// Create a new local port with the given name, and call the closure
+    /// with each message that it receives.
+    ///
+    /// On macOS, the name of the port must be prefixed by an application
+    /// group identifier when sandboxed.
+    ///
+    /// Returns [`None`] if the port could not be created, including if a
+    /// port with the given name already exists.
+    #[doc(alias = "CFMessagePortCreateLocal")]
+    #[doc(alias = "CFMessagePortCreateRunLoopSource")]
+    pub fn new(
+        run_loop: &CFRunLoop,
+        mode: &CFRunLoopMode,
+        name: Option<&CFString>,
+        handler: impl Fn(&CFMessagePort, i32, Option<&CFData>) -> Option<CFRetained<CFData>>
+            + Send
+            + 'static,
+    ) -> Option<Self> {
+        // SAFETY: The closure is `Send`, so it is fine to call and drop it
+        // on the run loop's thread.
+        unsafe { Self::new_unchecked(run_loop, mode, name, handler) }
+    }
+
+    /// Same as [`new`][Self::new], but the port is scheduled on the current
+    /// thread's run loop, which allows the closure to not be `Send`.
+    pub fn new_local(
+        mode: &CFRunLoopMode,
+        name: Option<&CFString>,
+        handler: impl Fn(&CFMessagePort, i32, Option<&CFData>) -> Option<CFRetained<CFData>> + 'static,
+    ) -> Option<Self> {
+        // SAFETY: The port is scheduled on the current thread's run loop,
+        // and the guard is not `Send`, so it is also invalidated from this
+        // thread.
+        unsafe { Self::new_unchecked(&CFRunLoop::current(), mode, name, handler) }
+    }
+
+    /// # Safety
+    ///
+    /// The closure must be safe to call and drop on the run loop's thread.
+    unsafe fn new_unchecked<
+        F: Fn(&CFMessagePort, i32, Option<&CFData>) -> Option<CFRetained<CFData>> + 'static,
+    >(
+        run_loop: &CFRunLoop,
+        mode: &CFRunLoopMode,
+        name: Option<&CFString>,
+        handler: F,
+    ) -> Option<Self> {
+        unsafe extern "C-unwind" fn callout<
+            F: Fn(&CFMessagePort, i32, Option<&CFData>) -> Option<CFRetained<CFData>>,
+        >(
+            port: *mut CFMessagePort,
+            message_id: i32,
+            data: *const CFData,
+            info: *mut c_void,
+        ) -> *const CFData {
+            // SAFETY: The info is the closure, which is alive for as long as
+            // the port is.
+            let handler = unsafe { &*info.cast::<F>() };
+            // SAFETY: The port and data are valid for the duration of the
+            // callout.
+            let port = unsafe { &*port };
+            let data = unsafe { data.as_ref() };
+            // The reply is released by Core Foundation.
+            match handler(port, message_id, data) {
+                Some(reply) => CFRetained::into_raw(reply).as_ptr(),
+                None => core::ptr::null(),
+            }
+        }
+
+        let info = Box::into_raw(Box::new(handler));
+        let mut context = CFMessagePortContext {
+            version: 0,
+            info: info.cast(),
+            retain: None,
+            release: Some(release::<F>),
+            copyDescription: None,
+        };
+        let mut should_free_info = 0;
+        let port = unsafe {
+            CFMessagePortCreateLocal(
+                None,
+                name,
+                Some(callout::<F>),
+                &mut context,
+                &mut should_free_info,
+            )
+        };
+        // If a local port with the name already exists in this process, it
+        // is returned, and our context is ignored.
+        if should_free_info != 0 {
+            // SAFETY: The context was not used, so we still own the info.
+            drop(unsafe { Box::from_raw(info) });
+            return None;
+        }
+        let Some(port) = port else {
+            // SAFETY: The port was not created, so we still own the info.
+            drop(unsafe { Box::from_raw(info) });
+            return None;
+        };
+
+        let source = unsafe { CFMessagePortCreateRunLoopSource(None, Some(&port), 0) };
+        let Some(source) = source else {
+            unsafe { CFMessagePortInvalidate(&port) };
+            return None;
+        };
+        unsafe { CFRunLoopAddSource(run_loop, Some(&source), Some(mode)) };
+        Some(Self { port })
+    }
+
+    /// The underlying port.
+    pub fn port(&self) -> &CFMessagePort {
+        &self.port
+    }
+}
+
+impl Drop for CFMessagePortGuard {
+    #[doc(alias = "CFMessagePortInvalidate")]
+    fn drop(&mut self) {
+        unsafe { CFMessagePortInvalidate(&self.port) };
+    }
+}
+
+impl fmt::Debug for CFMessagePortGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CFMessagePortGuard")
+            .field("port", &self.port)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(target_vendor = "apple", feature = "CFDate", feature = "std"))]
+mod tests {
+    use alloc::format;
+    use alloc::string::ToString;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::*;
+
+    fn port_name(test: &str) -> CFRetained<CFString> {
+        CFString::from_str(&format!(
+            "objc2-core-foundation.{test}.{}",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn request_reply() {
+        let name = port_name("request_reply");
+        let received = Arc::new(AtomicBool::new(false));
+        let guard = CFMessagePortGuard::new(
+            &CFRunLoop::current(),
+            CFRunLoop::default_mode(),
+            Some(&name),
+            {
+                let received = received.clone();
+                move |_port, message_id, data| {
+                    assert_eq!(message_id, 42);
+                    assert_eq!(data.unwrap().to_vec(), b"ping");
+                    received.store(true, Ordering::Relaxed);
+                    Some(CFData::from_bytes(b"pong"))
+                }
+            },
+        )
+        .unwrap();
+        assert!(!guard.port().is_remote());
+        assert_eq!(guard.port().name().as_deref(), Some(&*name));
+
+        // Creating another port with the same name fails.
+        assert!(CFMessagePortGuard::new_local(
+            CFRunLoop::default_mode(),
+            Some(&name),
+            |_, _, _| None
+        )
+        .is_none());
+
+        let name_str = name.to_string();
+        let sender = thread::spawn(move || {
+            let remote = CFMessagePort::remote(&CFString::from_str(&name_str)).unwrap();
+            assert!(remote.is_remote());
+            let reply = remote
+                .send_request(
+                    42,
+                    Some(&CFData::from_bytes(b"ping")),
+                    Duration::from_secs(5),
+                    Duration::from_secs(5),
+                    CFRunLoop::default_mode(),
+                )
+                .unwrap();
+            reply.unwrap().to_vec()
+        });
+
+        for _ in 0..50 {
+            if received.load(Ordering::Relaxed) {
+                break;
+            }
+            CFRunLoop::run_in_mode(CFRunLoop::default_mode(), Duration::from_millis(100), true);
+        }
+        assert!(received.load(Ordering::Relaxed));
+        assert_eq!(sender.join().unwrap(), b"pong");
+
+        drop(guard);
+        assert!(CFMessagePort::remote(&name).is_none());
+    }
+}
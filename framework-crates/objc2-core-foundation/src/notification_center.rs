@@ -0,0 +1,210 @@
+//! Safe observers on the Darwin and distributed notification centers.
+use alloc::boxed::Box;
+use core::ffi::c_void;
+use core::fmt;
+use core::ptr;
+
+use crate::{
+    CFDictionary, CFNotificationCenter, CFNotificationCenterAddObserver,
+    CFNotificationCenterGetDarwinNotifyCenter, CFNotificationCenterPostNotification,
+    CFNotificationCenterRemoveEveryObserver, CFNotificationName, CFNotificationSuspensionBehavior,
+    CFRetained, CFRunLoop,
+};
+
+impl CFNotificationCenter {
+    /// The Darwin notification center.
+    ///
+    /// This center is system-wide, and is backed by `notify(3)`. It only
+    /// delivers the name of notifications; the user info given when posting
+    /// is ignored.
+    #[doc(alias = "CFNotificationCenterGetDarwinNotifyCenter")]
+    pub fn darwin_notify() -> CFRetained<Self> {
+        unsafe { CFNotificationCenterGetDarwinNotifyCenter() }
+            .expect("failed getting the Darwin notify center")
+    }
+
+    /// The distributed notification center, which delivers notifications
+    /// between processes of the current user.
+    #[cfg(target_os = "macos")]
+    #[doc(alias = "CFNotificationCenterGetDistributedCenter")]
+    pub fn distributed() -> CFRetained<Self> {
+        unsafe { crate::CFNotificationCenterGetDistributedCenter() }
+            .expect("failed getting the distributed notification center")
+    }
+
+    /// Post a notification with the given name to the center.
+    ///
+    /// If `deliver_immediately` is set, the notification is delivered to
+    /// observers of the distributed center even if they are suspended.
+    #[doc(alias = "CFNotificationCenterPostNotification")]
+    pub fn post(
+        &self,
+        name: &CFNotificationName,
+        user_info: Option<&CFDictionary>,
+        deliver_immediately: bool,
+    ) {
+        unsafe {
+            CFNotificationCenterPostNotification(
+                self,
+                Some(name),
+                ptr::null(),
+                user_info,
+                deliver_immediately as crate::Boolean,
+            )
+        }
+    }
+}
+
+type Handler = Box<dyn Fn(&CFNotificationName, Option<&CFDictionary>)>;
+
+/// An observer on the Darwin or distributed notification center, which calls
+/// a closure for each notification and which is removed when dropped.
+///
+/// Both centers deliver notifications on the main thread, so the observer
+/// must be created there, and the closure does not have to be `Send`.
+#[must_use = "the observer is removed when this is dropped"]
+pub struct CFNotificationObserverGuard {
+    center: CFRetained<CFNotificationCenter>,
+    // Double-boxed, to get a thin pointer to use as the observer.
+    handler: *mut Handler,
+}
+
+impl CFNotificationObserverGuard {
+    /// Observe notifications with the given name on the Darwin notification
+    /// center.
+    ///
+    /// The closure is given the name of the notification.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the main thread.
+    #[doc(alias = "CFNotificationCenterAddObserver")]
+    pub fn darwin_notify(
+        name: &CFNotificationName,
+        handler: impl Fn(&CFNotificationName) + 'static,
+    ) -> Self {
+        // The suspension behavior is ignored by the Darwin center.
+        Self::new(
+            CFNotificationCenter::darwin_notify(),
+            Some(name),
+            CFNotificationSuspensionBehavior::DeliverImmediately,
+            Box::new(move |name, _| handler(name)),
+        )
+    }
+
+    /// Observe notifications with the given name, or all notifications if
+    /// [`None`], on the distributed notification center.
+    ///
+    /// The closure is given the name and user info of the notification, and
+    /// `suspension_behavior` controls what happens to notifications that
+    /// are posted while the application is suspended.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the main thread.
+    #[cfg(target_os = "macos")]
+    #[doc(alias = "CFNotificationCenterAddObserver")]
+    pub fn distributed(
+        name: Option<&CFNotificationName>,
+        suspension_behavior: CFNotificationSuspensionBehavior,
+        handler: impl Fn(&CFNotificationName, Option<&CFDictionary>) + 'static,
+    ) -> Self {
+        Self::new(
+            CFNotificationCenter::distributed(),
+            name,
+            suspension_behavior,
+            Box::new(handler),
+        )
+    }
+
+    fn new(
+        center: CFRetained<CFNotificationCenter>,
+        name: Option<&CFNotificationName>,
+        suspension_behavior: CFNotificationSuspensionBehavior,
+        handler: Handler,
+    ) -> Self {
+        unsafe extern "C-unwind" fn callback(
+            _center: *mut CFNotificationCenter,
+            observer: *mut c_void,
+            name: *const CFNotificationName,
+            _object: *const c_void,
+            user_info: *const CFDictionary,
+        ) {
+            // SAFETY: The observer is the handler, which is alive until the
+            // observer is removed.
+            let handler = unsafe { &*observer.cast::<Handler>() };
+            // SAFETY: The name and user info are valid for the duration of
+            // the callback.
+            let Some(name) = (unsafe { name.as_ref() }) else {
+                return;
+            };
+            let user_info = unsafe { user_info.as_ref() };
+            handler(name, user_info);
+        }
+
+        // Notifications are delivered on the main thread, and the guard is
+        // not `Send`, so this ensures that the handler is never called or
+        // dropped on another thread, or called after it has been dropped.
+        assert!(
+            CFRunLoop::current() == CFRunLoop::main(),
+            "notification observers must be created on the main thread",
+        );
+
+        let handler = Box::into_raw(Box::new(handler));
+        unsafe {
+            CFNotificationCenterAddObserver(
+                &center,
+                handler.cast(),
+                Some(callback),
+                name,
+                ptr::null(),
+                suspension_behavior,
+            )
+        };
+        Self { center, handler }
+    }
+}
+
+impl Drop for CFNotificationObserverGuard {
+    #[doc(alias = "CFNotificationCenterRemoveEveryObserver")]
+    fn drop(&mut self) {
+        unsafe { CFNotificationCenterRemoveEveryObserver(&self.center, self.handler.cast()) };
+        // SAFETY: The observer was removed, so the handler is no longer used.
+        drop(unsafe { Box::from_raw(self.handler) });
+    }
+}
+
+impl fmt::Debug for CFNotificationObserverGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CFNotificationObserverGuard")
+            .field("center", &self.center)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(target_vendor = "apple", feature = "CFString", feature = "std"))]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::CFString;
+
+    #[test]
+    fn post_darwin_notify() {
+        let name = CFString::from_static_str("rs.objc2.core-foundation.test");
+        CFNotificationCenter::darwin_notify().post(&name, None, true);
+    }
+
+    #[test]
+    fn observe_off_main_thread() {
+        let res = thread::spawn(|| {
+            let name = CFString::from_static_str("rs.objc2.core-foundation.test");
+            let _guard = CFNotificationObserverGuard::darwin_notify(&name, |_| {});
+        })
+        .join();
+        assert!(res.is_err());
+    }
+}
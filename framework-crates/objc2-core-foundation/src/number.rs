@@ -1,10 +1,11 @@
 #![cfg(feature = "CFBase")]
 use core::cmp::Ordering;
+use core::fmt;
 use core::ptr;
 
 use crate::{
     kCFBooleanFalse, kCFBooleanTrue, CFBoolean, CFBooleanGetValue, CFNumber, CFNumberCompare,
-    CFNumberCreate, CFNumberGetValue, CFNumberType, CFRetained,
+    CFNumberCreate, CFNumberGetValue, CFNumberIsFloatType, CFNumberType, CFRetained,
 };
 
 impl CFBoolean {
@@ -125,6 +126,300 @@ impl Ord for CFNumber {
     }
 }
 
+/// An error returned when converting between [`CFNumber`] and Rust's
+/// numeric types would lose information.
+///
+/// This happens if the value is out of range of the target type, or if a
+/// floating-point value has a fractional part or is not exactly
+/// representable in the target type.
+///
+/// Use the `_lossy` getters such as [`CFNumber::as_i32_lossy`] if truncation
+/// is acceptable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CFNumberConversionError {
+    _priv: (),
+}
+
+impl CFNumberConversionError {
+    const fn new() -> Self {
+        Self { _priv: () }
+    }
+}
+
+impl fmt::Display for CFNumberConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("number conversion would lose information")
+    }
+}
+
+#[cfg(feature = "std")] // use core::error::Error from Rust 1.81 once in MSRV.
+impl std::error::Error for CFNumberConversionError {}
+
+macro_rules! def_get_lossy_fn {
+    {$(
+        $(#[$($m:meta)*])*
+        ($fn_name:ident -> $fn_ret:ty; $type:ident),
+    )*} => {$(
+        $(#[$($m)*])*
+        #[inline]
+        pub fn $fn_name(&self) -> $fn_ret {
+            let mut value: $fn_ret = <$fn_ret>::default();
+            let ptr: *mut $fn_ret = &mut value;
+            // Ignore the return value, we explicitly allow the value to be
+            // truncated or rounded.
+            let _ = unsafe { CFNumberGetValue(self, CFNumberType::$type, ptr.cast()) };
+            value
+        }
+    )*}
+}
+
+/// Lossy conversion methods.
+///
+/// These convert the number to the given type the same way as
+/// `CFNumberGetValue`, i.e. truncating integers and rounding floating-point
+/// numbers if they do not fit in the type.
+impl CFNumber {
+    def_get_lossy_fn! {
+        (as_i8_lossy -> i8; SInt8Type),
+        (as_i16_lossy -> i16; SInt16Type),
+        (as_i32_lossy -> i32; SInt32Type),
+        (as_i64_lossy -> i64; SInt64Type),
+        (as_isize_lossy -> isize; NSIntegerType),
+
+        (as_f32_lossy -> f32; Float32Type),
+        (as_f64_lossy -> f64; Float64Type),
+    }
+
+    #[inline]
+    fn is_float(&self) -> bool {
+        unsafe { CFNumberIsFloatType(self) != 0 }
+    }
+
+    /// The number as an `i64`, if it is an integer.
+    fn as_integer(&self) -> Option<i64> {
+        if self.is_float() {
+            let value = self.as_f64_lossy();
+            // `i64::MAX as f64` rounds up to 2^63, so the upper bound is
+            // exclusive.
+            if value.fract() == 0.0 && (i64::MIN as f64) <= value && value < (i64::MAX as f64) {
+                Some(value as i64)
+            } else {
+                None
+            }
+        } else {
+            Some(self.as_i64_lossy())
+        }
+    }
+
+    fn cmp_integer(&self, other: i128) -> Option<Ordering> {
+        if self.is_float() {
+            self.as_f64_lossy().partial_cmp(&(other as f64))
+        } else {
+            Some(i128::from(self.as_i64_lossy()).cmp(&other))
+        }
+    }
+
+    fn cmp_float(&self, other: f64) -> Option<Ordering> {
+        if self.is_float() {
+            self.as_f64_lossy().partial_cmp(&other)
+        } else {
+            (self.as_i64_lossy() as f64).partial_cmp(&other)
+        }
+    }
+}
+
+macro_rules! impl_from_primitive {
+    ($($ty:ty => $fn_name:ident,)*) => {$(
+        impl From<$ty> for CFRetained<CFNumber> {
+            #[inline]
+            fn from(value: $ty) -> Self {
+                CFNumber::$fn_name(value.into())
+            }
+        }
+    )*};
+}
+
+impl_from_primitive! {
+    i8 => new_i8,
+    i16 => new_i16,
+    i32 => new_i32,
+    i64 => new_i64,
+    isize => new_isize,
+    // Unsigned integers are stored in the next larger signed type.
+    u8 => new_i16,
+    u16 => new_i32,
+    u32 => new_i64,
+    f32 => new_f32,
+    f64 => new_f64,
+}
+
+macro_rules! impl_try_from_unsigned {
+    ($($ty:ty,)*) => {$(
+        /// Fails if the value is larger than `i64::MAX`.
+        impl TryFrom<$ty> for CFRetained<CFNumber> {
+            type Error = CFNumberConversionError;
+
+            #[inline]
+            fn try_from(value: $ty) -> Result<Self, Self::Error> {
+                let value = i64::try_from(value).map_err(|_| CFNumberConversionError::new())?;
+                Ok(CFNumber::new_i64(value))
+            }
+        }
+    )*};
+}
+
+impl_try_from_unsigned! {
+    u64,
+    usize,
+}
+
+macro_rules! impl_try_into_integer {
+    ($($ty:ty,)*) => {$(
+        /// Fails if the number is out of range, or has a fractional part.
+        impl TryFrom<&CFNumber> for $ty {
+            type Error = CFNumberConversionError;
+
+            fn try_from(number: &CFNumber) -> Result<Self, Self::Error> {
+                number
+                    .as_integer()
+                    .and_then(|value| <$ty>::try_from(value).ok())
+                    .ok_or(CFNumberConversionError::new())
+            }
+        }
+
+        impl PartialEq<$ty> for CFNumber {
+            #[inline]
+            fn eq(&self, other: &$ty) -> bool {
+                self.cmp_integer((*other).into()) == Some(Ordering::Equal)
+            }
+        }
+
+        impl PartialOrd<$ty> for CFNumber {
+            #[inline]
+            fn partial_cmp(&self, other: &$ty) -> Option<Ordering> {
+                self.cmp_integer((*other).into())
+            }
+        }
+    )*};
+}
+
+impl_try_into_integer! {
+    i8,
+    i16,
+    i32,
+    i64,
+    u8,
+    u16,
+    u32,
+    u64,
+}
+
+// `isize` and `usize` do not implement `Into<i128>`.
+impl TryFrom<&CFNumber> for isize {
+    type Error = CFNumberConversionError;
+
+    fn try_from(number: &CFNumber) -> Result<Self, Self::Error> {
+        i64::try_from(number)
+            .and_then(|value| isize::try_from(value).map_err(|_| CFNumberConversionError::new()))
+    }
+}
+
+impl TryFrom<&CFNumber> for usize {
+    type Error = CFNumberConversionError;
+
+    fn try_from(number: &CFNumber) -> Result<Self, Self::Error> {
+        u64::try_from(number)
+            .and_then(|value| usize::try_from(value).map_err(|_| CFNumberConversionError::new()))
+    }
+}
+
+/// Fails if the number is an integer that cannot be represented exactly.
+impl TryFrom<&CFNumber> for f64 {
+    type Error = CFNumberConversionError;
+
+    fn try_from(number: &CFNumber) -> Result<Self, Self::Error> {
+        if number.is_float() {
+            // Both `Float32Type` and `Float64Type` fit in an `f64`.
+            Ok(number.as_f64_lossy())
+        } else {
+            let value = number.as_i64_lossy();
+            let float = value as f64;
+            // `i64::MAX as f64` rounds up to 2^63, which is out of range.
+            if float < (i64::MAX as f64) && float as i64 == value {
+                Ok(float)
+            } else {
+                Err(CFNumberConversionError::new())
+            }
+        }
+    }
+}
+
+/// Fails if the number cannot be represented exactly.
+///
+/// NaN is converted to NaN.
+impl TryFrom<&CFNumber> for f32 {
+    type Error = CFNumberConversionError;
+
+    fn try_from(number: &CFNumber) -> Result<Self, Self::Error> {
+        let value = f64::try_from(number)?;
+        let float = value as f32;
+        if f64::from(float) == value || value.is_nan() {
+            Ok(float)
+        } else {
+            Err(CFNumberConversionError::new())
+        }
+    }
+}
+
+impl PartialEq<f32> for CFNumber {
+    #[inline]
+    fn eq(&self, other: &f32) -> bool {
+        self.cmp_float((*other).into()) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd<f32> for CFNumber {
+    #[inline]
+    fn partial_cmp(&self, other: &f32) -> Option<Ordering> {
+        self.cmp_float((*other).into())
+    }
+}
+
+impl PartialEq<f64> for CFNumber {
+    #[inline]
+    fn eq(&self, other: &f64) -> bool {
+        self.cmp_float(*other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd<f64> for CFNumber {
+    #[inline]
+    fn partial_cmp(&self, other: &f64) -> Option<Ordering> {
+        self.cmp_float(*other)
+    }
+}
+
+impl From<bool> for &'static CFBoolean {
+    #[inline]
+    fn from(value: bool) -> Self {
+        CFBoolean::new(value)
+    }
+}
+
+impl From<&CFBoolean> for bool {
+    #[inline]
+    fn from(value: &CFBoolean) -> Self {
+        value.as_bool()
+    }
+}
+
+impl PartialEq<bool> for CFBoolean {
+    #[inline]
+    fn eq(&self, other: &bool) -> bool {
+        self.as_bool() == *other
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -157,4 +452,78 @@ mod tests {
         assert!(CFNumber::new_i32(3) == CFNumber::new_i32(3));
         assert!(CFNumber::new_i32(4) > CFNumber::new_i32(3));
     }
+
+    #[test]
+    fn bool_conversions() {
+        let cftrue: &CFBoolean = true.into();
+        assert_eq!(cftrue, CFBoolean::new(true));
+        assert!(bool::from(cftrue));
+        assert!(!bool::from(<&CFBoolean>::from(false)));
+        assert_eq!(*cftrue, true);
+        assert_ne!(*cftrue, false);
+    }
+
+    #[test]
+    fn from_primitive() {
+        let n = CFRetained::<CFNumber>::from(-5i8);
+        assert_eq!(n.as_i8(), Some(-5));
+        let n = CFRetained::<CFNumber>::from(u32::MAX);
+        assert_eq!(n.as_i64(), Some(u32::MAX as i64));
+        let n = CFRetained::<CFNumber>::from(1.5f64);
+        assert_eq!(n.as_f64(), Some(1.5));
+
+        let n = CFRetained::<CFNumber>::try_from(i64::MAX as u64).unwrap();
+        assert_eq!(n.as_i64(), Some(i64::MAX));
+        assert!(CFRetained::<CFNumber>::try_from(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn try_into_primitive() {
+        let n = CFNumber::new_i32(442);
+        assert_eq!(i32::try_from(&*n), Ok(442));
+        assert_eq!(u16::try_from(&*n), Ok(442));
+        assert_eq!(usize::try_from(&*n), Ok(442));
+        assert_eq!(f32::try_from(&*n), Ok(442.0));
+        assert_eq!(i8::try_from(&*n), Err(CFNumberConversionError::new()));
+        assert_eq!(n.as_i8_lossy(), 442i32 as i8);
+
+        let n = CFNumber::new_i32(-1);
+        assert!(u8::try_from(&*n).is_err());
+        assert_eq!(i64::try_from(&*n), Ok(-1));
+
+        let n = CFNumber::new_f64(2.0);
+        assert_eq!(i32::try_from(&*n), Ok(2));
+        assert_eq!(f32::try_from(&*n), Ok(2.0));
+
+        let n = CFNumber::new_f64(2.5);
+        assert!(i32::try_from(&*n).is_err());
+        assert_eq!(f64::try_from(&*n), Ok(2.5));
+        assert_eq!(n.as_i32_lossy(), 2);
+
+        let n = CFNumber::new_f64(0.1);
+        assert!(f32::try_from(&*n).is_err());
+        assert_eq!(n.as_f32_lossy(), 0.1);
+
+        let n = CFNumber::new_i64(i64::MAX);
+        assert!(f64::try_from(&*n).is_err());
+        assert_eq!(u64::try_from(&*n), Ok(i64::MAX as u64));
+    }
+
+    #[test]
+    fn cmp_primitive() {
+        let n = CFNumber::new_i32(3);
+        assert_eq!(*n, 3i32);
+        assert_eq!(*n, 3u64);
+        assert_eq!(*n, 3.0f64);
+        assert!(*n < 4u8);
+        assert!(*n > -1i64);
+        assert!(*n < 3.5f32);
+        assert_ne!(*n, f64::NAN);
+
+        let n = CFNumber::new_f64(2.5);
+        assert!(*n > 2i32);
+        assert!(*n < 3i32);
+        assert_ne!(*n, 2i32);
+        assert_eq!(*n, 2.5f64);
+    }
 }
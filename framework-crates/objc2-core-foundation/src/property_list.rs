@@ -0,0 +1,948 @@
+//! Reading and writing property lists.
+//!
+//! Property lists are trees of `CFString`, `CFNumber`, `CFBoolean`,
+//! `CFDate`, `CFData`, `CFArray` and `CFDictionary`, and are used for
+//! `Info.plist` files, launchd jobs, entitlements, preferences and so on.
+//!
+//! Use [`CFPropertyList::from_bytes`] and [`CFPropertyList::to_bytes`] to
+//! parse and serialize them. With the `"serde"` feature, you can also convert
+//! between property lists and Rust data structures with [`to_value`] and
+//! [`from_value`], or directly to and from bytes with [`to_vec`] and
+//! [`from_slice`].
+//!
+//! Rust values are mapped as follows:
+//! - Booleans become `CFBoolean`.
+//! - Integers and floats become `CFNumber` (unsigned integers larger than
+//!   `i64::MAX` are not supported).
+//! - Strings and characters become `CFString`.
+//! - Byte buffers (e.g. `serde_bytes`) become `CFData`.
+//! - Sequences, tuples and tuple structs become `CFArray`.
+//! - Maps and structs become `CFDictionary` with `CFString` keys.
+//! - Unit variants become the name of the variant, while other enum
+//!   variants become a dictionary with the name of the variant as the only
+//!   key, following serde's default externally tagged representation.
+//!
+//! Property lists cannot contain null values, so `None` and `()` are
+//! omitted from dictionaries, and cannot be stored at the top level or in
+//! arrays.
+//!
+//! `objc2_foundation::property_list` provides the same conversions for
+//! Foundation objects. Both exist because this crate cannot depend on
+//! `objc2-foundation` (the dependency goes the other way), and must be
+//! usable without the Objective-C runtime, while the Foundation version
+//! works with `NSObject`s directly, without requiring this crate. The two
+//! follow the same mapping (booleans are `NSNumber`s there, which `CFBoolean`
+//! is bridged to), so the results are interchangeable through toll-free
+//! bridging; keep them in sync when changing either.
+//!
+//!
+//! # Example
+//!
+//! Parse a launchd job.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use objc2_core_foundation::property_list;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, Debug)]
+//! #[serde(rename_all = "PascalCase")]
+//! struct Job {
+//!     label: String,
+//!     program_arguments: Vec<String>,
+//!     run_at_load: Option<bool>,
+//! }
+//!
+//! let plist = br#"<?xml version="1.0" encoding="UTF-8"?>
+//! <!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+//! <plist version="1.0">
+//! <dict>
+//!     <key>Label</key>
+//!     <string>com.example.agent</string>
+//!     <key>ProgramArguments</key>
+//!     <array>
+//!         <string>/usr/local/bin/agent</string>
+//!         <string>--verbose</string>
+//!     </array>
+//! </dict>
+//! </plist>"#;
+//!
+//! let job: Job = property_list::from_slice(plist).unwrap();
+//! assert_eq!(job.label, "com.example.agent");
+//! assert_eq!(job.program_arguments.len(), 2);
+//! assert_eq!(job.run_at_load, None);
+//! # }
+//! ```
+use alloc::vec::Vec;
+use core::ptr::{self, NonNull};
+
+use crate::{
+    CFData, CFError, CFPropertyList, CFPropertyListCreateData, CFPropertyListCreateWithData,
+    CFPropertyListFormat, CFPropertyListMutabilityOptions, CFRetained,
+};
+
+fn error_from_raw(error: *mut CFError) -> CFRetained<CFError> {
+    let error = NonNull::new(error).expect("property list function failed without an error");
+    // SAFETY: The error is returned with +1 retain count (it follows the
+    // create rule).
+    unsafe { CFRetained::from_raw(error) }
+}
+
+impl CFPropertyList {
+    /// Parse a property list from the given bytes.
+    ///
+    /// The format (XML, binary or the legacy OpenStep format) is detected
+    /// automatically, and is returned along with the property list.
+    #[doc(alias = "CFPropertyListCreateWithData")]
+    pub fn from_bytes(
+        bytes: &[u8],
+    ) -> Result<(CFRetained<CFPropertyList>, CFPropertyListFormat), CFRetained<CFError>> {
+        let data = CFData::from_bytes(bytes);
+        let mut format = CFPropertyListFormat(0);
+        let mut error = ptr::null_mut();
+        let plist = unsafe {
+            CFPropertyListCreateWithData(
+                None,
+                Some(&data),
+                CFPropertyListMutabilityOptions::Immutable.bits(),
+                &mut format,
+                &mut error,
+            )
+        };
+        match plist {
+            Some(plist) => Ok((plist, format)),
+            None => Err(error_from_raw(error)),
+        }
+    }
+
+    /// Serialize the property list in the given format.
+    ///
+    /// Writing in [`CFPropertyListFormat::OpenStepFormat`] is not supported,
+    /// and will return an error.
+    ///
+    /// This also fails if the property list contains objects that are not
+    /// valid in property lists.
+    #[doc(alias = "CFPropertyListCreateData")]
+    pub fn to_bytes(&self, format: CFPropertyListFormat) -> Result<Vec<u8>, CFRetained<CFError>> {
+        let mut error = ptr::null_mut();
+        let data = unsafe { CFPropertyListCreateData(None, Some(self), format, 0, &mut error) };
+        match data {
+            Some(data) => Ok(data.to_vec()),
+            None => Err(error_from_raw(error)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use self::serde_impl::{from_slice, from_value, to_value, to_vec, Error};
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use core::ffi::c_void;
+    use core::fmt;
+    use core::ptr::NonNull;
+
+    use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+    use serde::ser::{self, Serialize};
+
+    use crate::{
+        kCFTypeArrayCallBacks, kCFTypeDictionaryKeyCallBacks, kCFTypeDictionaryValueCallBacks,
+        CFArray, CFArrayAppendValue, CFArrayCreateMutable, CFArrayGetCount, CFArrayGetValueAtIndex,
+        CFBoolean, CFCopyTypeIDDescription, CFData, CFDictionary, CFDictionaryCreateMutable,
+        CFDictionaryGetKeysAndValues, CFDictionarySetValue, CFGetTypeID, CFIndex, CFMutableArray,
+        CFMutableDictionary, CFNumber, CFNumberIsFloatType, CFPropertyList, CFPropertyListFormat,
+        CFRetained, CFString, CFType, Type,
+    };
+
+    /// An error that occurred while converting to or from a property list.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct Error {
+        message: String,
+    }
+
+    impl Error {
+        fn new(message: impl fmt::Display) -> Self {
+            Self {
+                message: message.to_string(),
+            }
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.message)
+        }
+    }
+
+    // This is `std::error::Error` when `serde`'s `std` feature is enabled.
+    impl serde::de::StdError for Error {}
+
+    impl ser::Error for Error {
+        fn custom<T: fmt::Display>(msg: T) -> Self {
+            Self::new(msg)
+        }
+    }
+
+    impl de::Error for Error {
+        fn custom<T: fmt::Display>(msg: T) -> Self {
+            Self::new(msg)
+        }
+    }
+
+    /// Convert a value to a property list.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value (or one of its elements) is `None` or
+    /// `()` outside of a struct or map, if a map has keys that are not
+    /// strings, if an integer is out of range, or if the value's `Serialize`
+    /// implementation fails.
+    pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<CFRetained<CFPropertyList>, Error> {
+        value
+            .serialize(Serializer)?
+            .ok_or_else(|| Error::new("property lists cannot contain null values"))
+    }
+
+    /// Convert a property list to a value.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the property list does not match the structure of
+    /// the value, or if it contains objects that are not supported (such as
+    /// `CFDate`).
+    pub fn from_value<T: DeserializeOwned>(value: &CFPropertyList) -> Result<T, Error> {
+        T::deserialize(Deserializer { value })
+    }
+
+    /// Serialize a value to a property list in the given format.
+    ///
+    /// See [`to_value`] and [`CFPropertyList::to_bytes`].
+    pub fn to_vec<T: Serialize + ?Sized>(
+        value: &T,
+        format: CFPropertyListFormat,
+    ) -> Result<Vec<u8>, Error> {
+        to_value(value)?.to_bytes(format).map_err(Error::new)
+    }
+
+    /// Deserialize a value from a property list in any format.
+    ///
+    /// See [`CFPropertyList::from_bytes`] and [`from_value`].
+    pub fn from_slice<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+        let (plist, _format) = CFPropertyList::from_bytes(bytes).map_err(Error::new)?;
+        from_value(&plist)
+    }
+
+    fn as_value(value: &CFType) -> *const c_void {
+        let ptr: *const CFType = value;
+        ptr.cast()
+    }
+
+    fn retain_value(ptr: *const c_void) -> CFRetained<CFType> {
+        let ptr = ptr.cast_mut().cast::<CFType>();
+        // SAFETY: Collections created with the `CFType` callbacks (such as
+        // those in property lists) contain valid, non-NULL `CFType`s.
+        unsafe { CFRetained::retain(NonNull::new(ptr).expect("collection contained NULL")) }
+    }
+
+    fn erase<T: Type>(value: CFRetained<T>) -> CFRetained<CFType> {
+        // SAFETY: All CoreFoundation types can be converted to `CFType`.
+        unsafe { CFRetained::cast_unchecked(value) }
+    }
+
+    fn required(value: Option<CFRetained<CFType>>) -> Result<CFRetained<CFType>, Error> {
+        value.ok_or_else(|| {
+            Error::new("property list arrays and enum variants cannot contain null values")
+        })
+    }
+
+    fn new_dictionary() -> CFRetained<CFMutableDictionary> {
+        unsafe {
+            CFDictionaryCreateMutable(
+                None,
+                0,
+                &kCFTypeDictionaryKeyCallBacks,
+                &kCFTypeDictionaryValueCallBacks,
+            )
+        }
+        .expect("failed creating CFDictionary")
+    }
+
+    fn insert(dictionary: &CFMutableDictionary, key: &CFString, value: &CFType) {
+        let key: &CFType = key.as_ref();
+        // SAFETY: The dictionary uses the `CFType` callbacks, and the key and
+        // value are valid `CFType`s.
+        unsafe { CFDictionarySetValue(Some(dictionary), as_value(key), as_value(value)) };
+    }
+
+    /// Serializes to a property list, or [`None`] if the value is `None` or
+    /// `()` (such that it can be omitted from dictionaries).
+    struct Serializer;
+
+    type Output = Option<CFRetained<CFType>>;
+
+    impl ser::Serializer for Serializer {
+        type Ok = Output;
+        type Error = Error;
+        type SerializeSeq = SerializeArray;
+        type SerializeTuple = SerializeArray;
+        type SerializeTupleStruct = SerializeArray;
+        type SerializeTupleVariant = SerializeVariant<SerializeArray>;
+        type SerializeMap = SerializeDictionary;
+        type SerializeStruct = SerializeDictionary;
+        type SerializeStructVariant = SerializeVariant<SerializeDictionary>;
+
+        fn serialize_bool(self, v: bool) -> Result<Output, Error> {
+            Ok(Some(CFBoolean::new(v).retain().into()))
+        }
+
+        fn serialize_i8(self, v: i8) -> Result<Output, Error> {
+            self.serialize_i64(v.into())
+        }
+
+        fn serialize_i16(self, v: i16) -> Result<Output, Error> {
+            self.serialize_i64(v.into())
+        }
+
+        fn serialize_i32(self, v: i32) -> Result<Output, Error> {
+            self.serialize_i64(v.into())
+        }
+
+        fn serialize_i64(self, v: i64) -> Result<Output, Error> {
+            Ok(Some(CFNumber::new_i64(v).into()))
+        }
+
+        fn serialize_u8(self, v: u8) -> Result<Output, Error> {
+            self.serialize_i64(v.into())
+        }
+
+        fn serialize_u16(self, v: u16) -> Result<Output, Error> {
+            self.serialize_i64(v.into())
+        }
+
+        fn serialize_u32(self, v: u32) -> Result<Output, Error> {
+            self.serialize_i64(v.into())
+        }
+
+        fn serialize_u64(self, v: u64) -> Result<Output, Error> {
+            let number = CFRetained::<CFNumber>::try_from(v).map_err(Error::new)?;
+            Ok(Some(number.into()))
+        }
+
+        fn serialize_f32(self, v: f32) -> Result<Output, Error> {
+            self.serialize_f64(v.into())
+        }
+
+        fn serialize_f64(self, v: f64) -> Result<Output, Error> {
+            Ok(Some(CFNumber::new_f64(v).into()))
+        }
+
+        fn serialize_char(self, v: char) -> Result<Output, Error> {
+            self.serialize_str(v.encode_utf8(&mut [0; 4]))
+        }
+
+        fn serialize_str(self, v: &str) -> Result<Output, Error> {
+            Ok(Some(CFString::from_str(v).into()))
+        }
+
+        fn serialize_bytes(self, v: &[u8]) -> Result<Output, Error> {
+            Ok(Some(CFData::from_bytes(v).into()))
+        }
+
+        fn serialize_none(self) -> Result<Output, Error> {
+            Ok(None)
+        }
+
+        fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Output, Error> {
+            value.serialize(self)
+        }
+
+        fn serialize_unit(self) -> Result<Output, Error> {
+            Ok(None)
+        }
+
+        fn serialize_unit_struct(self, _name: &'static str) -> Result<Output, Error> {
+            Ok(None)
+        }
+
+        fn serialize_unit_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            variant: &'static str,
+        ) -> Result<Output, Error> {
+            self.serialize_str(variant)
+        }
+
+        fn serialize_newtype_struct<T: Serialize + ?Sized>(
+            self,
+            _name: &'static str,
+            value: &T,
+        ) -> Result<Output, Error> {
+            value.serialize(self)
+        }
+
+        fn serialize_newtype_variant<T: Serialize + ?Sized>(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            variant: &'static str,
+            value: &T,
+        ) -> Result<Output, Error> {
+            wrap_variant(variant, value.serialize(Serializer)?)
+        }
+
+        fn serialize_seq(self, _len: Option<usize>) -> Result<SerializeArray, Error> {
+            let array = unsafe { CFArrayCreateMutable(None, 0, &kCFTypeArrayCallBacks) }
+                .expect("failed creating CFArray");
+            Ok(SerializeArray(array))
+        }
+
+        fn serialize_tuple(self, len: usize) -> Result<SerializeArray, Error> {
+            self.serialize_seq(Some(len))
+        }
+
+        fn serialize_tuple_struct(
+            self,
+            _name: &'static str,
+            len: usize,
+        ) -> Result<SerializeArray, Error> {
+            self.serialize_seq(Some(len))
+        }
+
+        fn serialize_tuple_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            variant: &'static str,
+            len: usize,
+        ) -> Result<SerializeVariant<SerializeArray>, Error> {
+            Ok(SerializeVariant {
+                variant,
+                inner: self.serialize_seq(Some(len))?,
+            })
+        }
+
+        fn serialize_map(self, _len: Option<usize>) -> Result<SerializeDictionary, Error> {
+            Ok(SerializeDictionary {
+                dictionary: new_dictionary(),
+                key: None,
+            })
+        }
+
+        fn serialize_struct(
+            self,
+            _name: &'static str,
+            len: usize,
+        ) -> Result<SerializeDictionary, Error> {
+            self.serialize_map(Some(len))
+        }
+
+        fn serialize_struct_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            variant: &'static str,
+            len: usize,
+        ) -> Result<SerializeVariant<SerializeDictionary>, Error> {
+            Ok(SerializeVariant {
+                variant,
+                inner: self.serialize_map(Some(len))?,
+            })
+        }
+    }
+
+    struct SerializeArray(CFRetained<CFMutableArray>);
+
+    impl ser::SerializeSeq for SerializeArray {
+        type Ok = Output;
+        type Error = Error;
+
+        fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+            let value = required(value.serialize(Serializer)?)?;
+            // SAFETY: The array uses the `CFType` callbacks, and the value
+            // is a valid `CFType`.
+            unsafe { CFArrayAppendValue(Some(&self.0), as_value(&value)) };
+            Ok(())
+        }
+
+        fn end(self) -> Result<Output, Error> {
+            Ok(Some(erase(self.0)))
+        }
+    }
+
+    impl ser::SerializeTuple for SerializeArray {
+        type Ok = Output;
+        type Error = Error;
+
+        fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+            ser::SerializeSeq::serialize_element(self, value)
+        }
+
+        fn end(self) -> Result<Output, Error> {
+            ser::SerializeSeq::end(self)
+        }
+    }
+
+    impl ser::SerializeTupleStruct for SerializeArray {
+        type Ok = Output;
+        type Error = Error;
+
+        fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+            ser::SerializeSeq::serialize_element(self, value)
+        }
+
+        fn end(self) -> Result<Output, Error> {
+            ser::SerializeSeq::end(self)
+        }
+    }
+
+    struct SerializeDictionary {
+        dictionary: CFRetained<CFMutableDictionary>,
+        key: Option<CFRetained<CFString>>,
+    }
+
+    impl SerializeDictionary {
+        fn insert(&self, key: &CFString, value: Output) {
+            // `None` values are omitted.
+            if let Some(value) = value {
+                insert(&self.dictionary, key, &value);
+            }
+        }
+    }
+
+    impl ser::SerializeMap for SerializeDictionary {
+        type Ok = Output;
+        type Error = Error;
+
+        fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+            let key = key
+                .serialize(Serializer)?
+                .and_then(|key| key.downcast::<CFString>().ok())
+                .ok_or_else(|| Error::new("property list dictionary keys must be strings"))?;
+            self.key = Some(key);
+            Ok(())
+        }
+
+        fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+            let key = self
+                .key
+                .take()
+                .expect("serialize_value called before serialize_key");
+            self.insert(&key, value.serialize(Serializer)?);
+            Ok(())
+        }
+
+        fn end(self) -> Result<Output, Error> {
+            Ok(Some(erase(self.dictionary)))
+        }
+    }
+
+    impl ser::SerializeStruct for SerializeDictionary {
+        type Ok = Output;
+        type Error = Error;
+
+        fn serialize_field<T: Serialize + ?Sized>(
+            &mut self,
+            key: &'static str,
+            value: &T,
+        ) -> Result<(), Error> {
+            self.insert(&CFString::from_str(key), value.serialize(Serializer)?);
+            Ok(())
+        }
+
+        fn end(self) -> Result<Output, Error> {
+            ser::SerializeMap::end(self)
+        }
+    }
+
+    /// Wraps the serialized value in a dictionary with the variant as the
+    /// key.
+    struct SerializeVariant<S> {
+        variant: &'static str,
+        inner: S,
+    }
+
+    fn wrap_variant(variant: &str, value: Output) -> Result<Output, Error> {
+        let value = required(value)?;
+        let dictionary = new_dictionary();
+        insert(&dictionary, &CFString::from_str(variant), &value);
+        Ok(Some(erase(dictionary)))
+    }
+
+    impl ser::SerializeTupleVariant for SerializeVariant<SerializeArray> {
+        type Ok = Output;
+        type Error = Error;
+
+        fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+            ser::SerializeSeq::serialize_element(&mut self.inner, value)
+        }
+
+        fn end(self) -> Result<Output, Error> {
+            wrap_variant(self.variant, ser::SerializeSeq::end(self.inner)?)
+        }
+    }
+
+    impl ser::SerializeStructVariant for SerializeVariant<SerializeDictionary> {
+        type Ok = Output;
+        type Error = Error;
+
+        fn serialize_field<T: Serialize + ?Sized>(
+            &mut self,
+            key: &'static str,
+            value: &T,
+        ) -> Result<(), Error> {
+            ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+        }
+
+        fn end(self) -> Result<Output, Error> {
+            wrap_variant(self.variant, ser::SerializeMap::end(self.inner)?)
+        }
+    }
+
+    fn array_values(array: &CFArray) -> Vec<CFRetained<CFType>> {
+        let len = unsafe { CFArrayGetCount(array) };
+        (0..len)
+            .map(|i: CFIndex| retain_value(unsafe { CFArrayGetValueAtIndex(array, i) }))
+            .collect()
+    }
+
+    type Entry = (CFRetained<CFType>, CFRetained<CFType>);
+
+    fn dictionary_entries(dictionary: &CFDictionary) -> Vec<Entry> {
+        let len = dictionary.len();
+        let mut keys = Vec::with_capacity(len);
+        let mut values = Vec::with_capacity(len);
+        unsafe {
+            CFDictionaryGetKeysAndValues(dictionary, keys.as_mut_ptr(), values.as_mut_ptr());
+            // SAFETY: The buffers were large enough, and have been filled.
+            keys.set_len(len);
+            values.set_len(len);
+        }
+        keys.into_iter()
+            .zip(values)
+            .map(|(key, value)| (retain_value(key), retain_value(value)))
+            .collect()
+    }
+
+    /// Deserializes from a property list.
+    struct Deserializer<'a> {
+        value: &'a CFType,
+    }
+
+    impl Deserializer<'_> {
+        fn unsupported(&self) -> Error {
+            let type_id = CFGetTypeID(Some(self.value));
+            let name = CFCopyTypeIDDescription(type_id).expect("must have description");
+            Error::new(format_args!(
+                "unsupported property list value of type {name}"
+            ))
+        }
+    }
+
+    impl<'de> de::Deserializer<'de> for Deserializer<'_> {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            if let Some(string) = self.value.downcast_ref::<CFString>() {
+                visitor.visit_string(string.to_string())
+            } else if let Some(boolean) = self.value.downcast_ref::<CFBoolean>() {
+                visitor.visit_bool(boolean.as_bool())
+            } else if let Some(number) = self.value.downcast_ref::<CFNumber>() {
+                if unsafe { CFNumberIsFloatType(number) } != 0 {
+                    visitor.visit_f64(number.as_f64_lossy())
+                } else {
+                    visitor.visit_i64(number.as_i64_lossy())
+                }
+            } else if let Some(data) = self.value.downcast_ref::<CFData>() {
+                visitor.visit_byte_buf(data.to_vec())
+            } else if let Some(array) = self.value.downcast_ref::<CFArray>() {
+                visitor.visit_seq(ArrayAccess {
+                    values: array_values(array).into_iter(),
+                })
+            } else if let Some(dictionary) = self.value.downcast_ref::<CFDictionary>() {
+                visitor.visit_map(DictionaryAccess {
+                    entries: dictionary_entries(dictionary).into_iter(),
+                    value: None,
+                })
+            } else {
+                Err(self.unsupported())
+            }
+        }
+
+        fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            // Allow deserializing data as `Vec<u8>`.
+            match self.value.downcast_ref::<CFData>() {
+                Some(data) => visitor.visit_seq(data.to_vec().into_deserializer()),
+                None => self.deserialize_any(visitor),
+            }
+        }
+
+        fn deserialize_tuple<V: Visitor<'de>>(
+            self,
+            _len: usize,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            self.deserialize_seq(visitor)
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            // Missing values are handled by the absence of dictionary keys.
+            visitor.visit_some(self)
+        }
+
+        fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_unit()
+        }
+
+        fn deserialize_unit_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            visitor.visit_unit()
+        }
+
+        fn deserialize_newtype_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            visitor.visit_newtype_struct(self)
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            _variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            if let Some(string) = self.value.downcast_ref::<CFString>() {
+                return visitor.visit_enum(string.to_string().into_deserializer());
+            }
+            if let Some(dictionary) = self.value.downcast_ref::<CFDictionary>() {
+                if let [(key, value)] = &*dictionary_entries(dictionary) {
+                    if let Some(variant) = key.downcast_ref::<CFString>() {
+                        return visitor.visit_enum(EnumAccess {
+                            variant: variant.to_string(),
+                            value,
+                        });
+                    }
+                }
+            }
+            Err(Error::new(
+                "expected a string or a dictionary with a single key for an enum",
+            ))
+        }
+
+        fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_unit()
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf tuple_struct map struct identifier
+        }
+    }
+
+    struct ArrayAccess {
+        values: alloc::vec::IntoIter<CFRetained<CFType>>,
+    }
+
+    impl<'de> de::SeqAccess<'de> for ArrayAccess {
+        type Error = Error;
+
+        fn next_element_seed<T: de::DeserializeSeed<'de>>(
+            &mut self,
+            seed: T,
+        ) -> Result<Option<T::Value>, Error> {
+            match self.values.next() {
+                Some(value) => seed.deserialize(Deserializer { value: &value }).map(Some),
+                None => Ok(None),
+            }
+        }
+
+        fn size_hint(&self) -> Option<usize> {
+            Some(self.values.len())
+        }
+    }
+
+    struct DictionaryAccess {
+        entries: alloc::vec::IntoIter<Entry>,
+        value: Option<CFRetained<CFType>>,
+    }
+
+    impl<'de> de::MapAccess<'de> for DictionaryAccess {
+        type Error = Error;
+
+        fn next_key_seed<K: de::DeserializeSeed<'de>>(
+            &mut self,
+            seed: K,
+        ) -> Result<Option<K::Value>, Error> {
+            match self.entries.next() {
+                Some((key, value)) => {
+                    self.value = Some(value);
+                    seed.deserialize(Deserializer { value: &key }).map(Some)
+                }
+                None => Ok(None),
+            }
+        }
+
+        fn next_value_seed<V: de::DeserializeSeed<'de>>(
+            &mut self,
+            seed: V,
+        ) -> Result<V::Value, Error> {
+            let value = self
+                .value
+                .take()
+                .expect("next_value_seed called before next_key_seed");
+            seed.deserialize(Deserializer { value: &value })
+        }
+
+        fn size_hint(&self) -> Option<usize> {
+            Some(self.entries.len())
+        }
+    }
+
+    struct EnumAccess<'a> {
+        variant: String,
+        value: &'a CFType,
+    }
+
+    impl<'de, 'a> de::EnumAccess<'de> for EnumAccess<'a> {
+        type Error = Error;
+        type Variant = Deserializer<'a>;
+
+        fn variant_seed<V: de::DeserializeSeed<'de>>(
+            self,
+            seed: V,
+        ) -> Result<(V::Value, Deserializer<'a>), Error> {
+            let variant = seed.deserialize(self.variant.into_deserializer())?;
+            Ok((variant, Deserializer { value: self.value }))
+        }
+    }
+
+    impl<'de> de::VariantAccess<'de> for Deserializer<'_> {
+        type Error = Error;
+
+        fn unit_variant(self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(
+            self,
+            seed: T,
+        ) -> Result<T::Value, Error> {
+            seed.deserialize(self)
+        }
+
+        fn tuple_variant<V: Visitor<'de>>(
+            self,
+            _len: usize,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            de::Deserializer::deserialize_seq(self, visitor)
+        }
+
+        fn struct_variant<V: Visitor<'de>>(
+            self,
+            _fields: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            de::Deserializer::deserialize_any(self, visitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &[u8] = br#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<array>
+    <string>abc</string>
+    <integer>42</integer>
+    <true/>
+</array>
+</plist>"#;
+
+    #[test]
+    fn roundtrip_bytes() {
+        let (plist, format) = CFPropertyList::from_bytes(XML).unwrap();
+        assert_eq!(format, CFPropertyListFormat::XMLFormat_v1_0);
+        let array = plist.downcast_ref::<crate::CFArray>().unwrap();
+        assert_eq!(array.len(), 3);
+
+        let binary = plist
+            .to_bytes(CFPropertyListFormat::BinaryFormat_v1_0)
+            .unwrap();
+        assert!(binary.starts_with(b"bplist00"));
+        let (decoded, format) = CFPropertyList::from_bytes(&binary).unwrap();
+        assert_eq!(format, CFPropertyListFormat::BinaryFormat_v1_0);
+        assert_eq!(decoded, plist);
+    }
+
+    #[test]
+    fn invalid() {
+        let _error = CFPropertyList::from_bytes(b"<plist><notatag>").unwrap_err();
+        let _error = CFPropertyList::from_bytes(b"").unwrap_err();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        use alloc::string::String;
+        use alloc::vec;
+        use alloc::vec::Vec;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        enum Kind {
+            Unit,
+            Newtype(i32),
+            Struct { flag: bool },
+        }
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Value {
+            name: String,
+            count: u32,
+            ratio: f64,
+            tags: Vec<String>,
+            missing: Option<String>,
+            kinds: Vec<Kind>,
+        }
+
+        let value = Value {
+            name: "abc".into(),
+            count: 3,
+            ratio: 0.5,
+            tags: vec!["a".into(), "b".into()],
+            missing: None,
+            kinds: vec![Kind::Unit, Kind::Newtype(-1), Kind::Struct { flag: true }],
+        };
+
+        let plist = to_value(&value).unwrap();
+        assert_eq!(from_value::<Value>(&plist).unwrap(), value);
+
+        let bytes = to_vec(&value, CFPropertyListFormat::XMLFormat_v1_0).unwrap();
+        assert_eq!(from_slice::<Value>(&bytes).unwrap(), value);
+
+        let array: (String, i64, bool) = from_slice(XML).unwrap();
+        assert_eq!(array, (String::from("abc"), 42, true));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_errors() {
+        assert!(to_value(&()).is_err());
+        assert!(to_value(&u64::MAX).is_err());
+        assert!(to_value(&[Some(1), None]).is_err());
+        assert!(from_value::<i32>(&to_value("abc").unwrap()).is_err());
+    }
+}
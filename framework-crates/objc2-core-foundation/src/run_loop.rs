@@ -0,0 +1,677 @@
+//! Safe wrappers around run loop observers, timers and sources.
+//!
+//! The closures given to these are owned by Core Foundation, and are
+//! dropped when the underlying object is deallocated. The guard types
+//! invalidate the object when dropped, which removes it from all run loops.
+use alloc::boxed::Box;
+use core::ffi::c_void;
+use core::fmt;
+#[cfg(feature = "CFDate")]
+use core::time::Duration;
+
+use crate::{
+    kCFRunLoopCommonModes, kCFRunLoopDefaultMode, CFIndex, CFRetained, CFRunLoop,
+    CFRunLoopActivity, CFRunLoopAddObserver, CFRunLoopAddSource, CFRunLoopGetCurrent,
+    CFRunLoopGetMain, CFRunLoopIsWaiting, CFRunLoopMode, CFRunLoopObserver,
+    CFRunLoopObserverContext, CFRunLoopObserverCreate, CFRunLoopObserverInvalidate, CFRunLoopRun,
+    CFRunLoopSource, CFRunLoopSourceContext, CFRunLoopSourceCreate, CFRunLoopSourceInvalidate,
+    CFRunLoopSourceSignal, CFRunLoopStop, CFRunLoopWakeUp, Type,
+};
+#[cfg(feature = "CFDate")]
+use crate::{
+    CFAbsoluteTimeGetCurrent, CFRunLoopAddTimer, CFRunLoopRunInMode, CFRunLoopRunResult,
+    CFRunLoopTimer, CFRunLoopTimerContext, CFRunLoopTimerCreate, CFRunLoopTimerInvalidate,
+    CFRunLoopTimerIsValid, CFRunLoopTimerSetNextFireDate, CFRunLoopTimerSetTolerance,
+};
+
+/// Release the boxed closure stored in a context's `info`.
+unsafe extern "C-unwind" fn release<F>(info: *const c_void) {
+    // SAFETY: The info was created with `Box::into_raw` when creating the
+    // object, and Core Foundation releases it exactly once.
+    drop(unsafe { Box::from_raw(info.cast::<F>().cast_mut()) });
+}
+
+/// Run the given closure stored in `info`.
+///
+/// # Safety
+///
+/// The info must be the pointer returned by `Box::into_raw` for a live `F`.
+unsafe fn call<F: Fn()>(info: *mut c_void) {
+    let f = unsafe { &*info.cast::<F>() };
+    f();
+}
+
+impl CFRunLoop {
+    /// The run loop of the current thread.
+    ///
+    /// The run loop is created if it doesn't already exist.
+    #[doc(alias = "CFRunLoopGetCurrent")]
+    pub fn current() -> CFRetained<Self> {
+        unsafe { CFRunLoopGetCurrent() }.expect("failed getting the current run loop")
+    }
+
+    /// The run loop of the main thread.
+    #[doc(alias = "CFRunLoopGetMain")]
+    pub fn main() -> CFRetained<Self> {
+        unsafe { CFRunLoopGetMain() }.expect("failed getting the main run loop")
+    }
+
+    /// The default run loop mode, `kCFRunLoopDefaultMode`.
+    #[doc(alias = "kCFRunLoopDefaultMode")]
+    pub fn default_mode() -> &'static CFRunLoopMode {
+        unsafe { kCFRunLoopDefaultMode }.expect("kCFRunLoopDefaultMode was NULL")
+    }
+
+    /// The pseudo-mode for the set of common modes, `kCFRunLoopCommonModes`.
+    ///
+    /// Adding an observer, timer or source to this mode adds it to all of
+    /// the run loop's common modes, which usually includes the modes used
+    /// while tracking events in AppKit and UIKit.
+    #[doc(alias = "kCFRunLoopCommonModes")]
+    pub fn common_modes() -> &'static CFRunLoopMode {
+        unsafe { kCFRunLoopCommonModes }.expect("kCFRunLoopCommonModes was NULL")
+    }
+
+    /// Run the current thread's run loop in the default mode until it is
+    /// stopped, or has no more sources or timers.
+    #[doc(alias = "CFRunLoopRun")]
+    pub fn run() {
+        unsafe { CFRunLoopRun() }
+    }
+
+    /// Run the current thread's run loop in the given mode, for at most the
+    /// given duration.
+    ///
+    /// If `return_after_source_handled` is set, this returns after the
+    /// first source has been handled.
+    #[cfg(feature = "CFDate")]
+    #[doc(alias = "CFRunLoopRunInMode")]
+    pub fn run_in_mode(
+        mode: &CFRunLoopMode,
+        duration: Duration,
+        return_after_source_handled: bool,
+    ) -> CFRunLoopRunResult {
+        unsafe {
+            CFRunLoopRunInMode(
+                Some(mode),
+                duration.as_secs_f64(),
+                return_after_source_handled as _,
+            )
+        }
+    }
+
+    /// Stop the run loop, which causes the innermost invocation of it to
+    /// return.
+    #[doc(alias = "CFRunLoopStop")]
+    pub fn stop(&self) {
+        unsafe { CFRunLoopStop(self) }
+    }
+
+    /// Wake up the run loop if it is waiting for an event.
+    #[doc(alias = "CFRunLoopWakeUp")]
+    pub fn wake_up(&self) {
+        unsafe { CFRunLoopWakeUp(self) }
+    }
+
+    /// Whether the run loop is waiting for an event.
+    #[doc(alias = "CFRunLoopIsWaiting")]
+    pub fn is_waiting(&self) -> bool {
+        unsafe { CFRunLoopIsWaiting(self) != 0 }
+    }
+
+    /// Schedule the closure to be run once on the run loop, the next time
+    /// it runs in the given mode.
+    ///
+    /// The run loop is woken up if it is waiting. Use
+    /// [`scheduler`][Self::scheduler] to schedule work from other threads.
+    #[cfg(feature = "block2")]
+    #[doc(alias = "CFRunLoopPerformBlock")]
+    pub fn perform(&self, mode: &CFRunLoopMode, f: impl FnOnce() + Send + 'static) {
+        let f = core::cell::Cell::new(Some(f));
+        let block = block2::RcBlock::new(move || {
+            if let Some(f) = f.take() {
+                f();
+            }
+        });
+        // SAFETY: The block is `'static`, and only captures `Send` data.
+        unsafe { crate::CFRunLoopPerformBlock(self, Some(mode), Some(&block)) };
+        self.wake_up();
+    }
+
+    /// Get a handle that can be used to schedule work on the run loop from
+    /// other threads.
+    pub fn scheduler(&self) -> CFRunLoopScheduler {
+        CFRunLoopScheduler {
+            run_loop: self.retain(),
+        }
+    }
+}
+
+/// A handle to a run loop that can be sent to other threads.
+///
+/// See [`CFRunLoop::scheduler`].
+#[derive(Clone)]
+pub struct CFRunLoopScheduler {
+    run_loop: CFRetained<CFRunLoop>,
+}
+
+// SAFETY: The run loop functions used by the scheduler are thread-safe.
+unsafe impl Send for CFRunLoopScheduler {}
+// SAFETY: See above.
+unsafe impl Sync for CFRunLoopScheduler {}
+
+impl CFRunLoopScheduler {
+    /// Schedule the closure to be run once on the run loop, the next time it
+    /// runs in one of the common modes.
+    ///
+    /// See [`CFRunLoop::perform`].
+    #[cfg(feature = "block2")]
+    #[doc(alias = "CFRunLoopPerformBlock")]
+    pub fn perform(&self, f: impl FnOnce() + Send + 'static) {
+        self.run_loop.perform(CFRunLoop::common_modes(), f);
+    }
+
+    /// Wake up the run loop if it is waiting for an event.
+    #[doc(alias = "CFRunLoopWakeUp")]
+    pub fn wake_up(&self) {
+        self.run_loop.wake_up();
+    }
+
+    /// Stop the run loop.
+    #[doc(alias = "CFRunLoopStop")]
+    pub fn stop(&self) {
+        self.run_loop.stop();
+    }
+}
+
+impl fmt::Debug for CFRunLoopScheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CFRunLoopScheduler")
+            .field("run_loop", &self.run_loop)
+            .finish()
+    }
+}
+
+/// A run loop observer that calls a closure, and which is invalidated when
+/// dropped.
+#[must_use = "the observer is invalidated when this is dropped"]
+pub struct CFRunLoopObserverGuard {
+    observer: CFRetained<CFRunLoopObserver>,
+}
+
+impl CFRunLoopObserverGuard {
+    /// Call the closure when the run loop goes through any of the given
+    /// activities in the given mode.
+    ///
+    /// Observers with a lower `order` are called first.
+    #[doc(alias = "CFRunLoopObserverCreate")]
+    #[doc(alias = "CFRunLoopAddObserver")]
+    pub fn new(
+        run_loop: &CFRunLoop,
+        mode: &CFRunLoopMode,
+        activities: CFRunLoopActivity,
+        order: CFIndex,
+        handler: impl Fn(CFRunLoopActivity) + Send + 'static,
+    ) -> Self {
+        // SAFETY: The closure is `Send`, so it is fine to call and drop it
+        // on the run loop's thread.
+        unsafe { Self::new_unchecked(run_loop, mode, activities, order, handler) }
+    }
+
+    /// Same as [`new`][Self::new], but the observer is added to the current
+    /// thread's run loop, which allows the closure to not be `Send`.
+    pub fn new_local(
+        mode: &CFRunLoopMode,
+        activities: CFRunLoopActivity,
+        order: CFIndex,
+        handler: impl Fn(CFRunLoopActivity) + 'static,
+    ) -> Self {
+        // SAFETY: The observer is added to the current thread's run loop,
+        // and the guard is not `Send`, so it is also invalidated from this
+        // thread.
+        unsafe { Self::new_unchecked(&CFRunLoop::current(), mode, activities, order, handler) }
+    }
+
+    /// # Safety
+    ///
+    /// The closure must be safe to call and drop on the run loop's thread.
+    unsafe fn new_unchecked<F: Fn(CFRunLoopActivity) + 'static>(
+        run_loop: &CFRunLoop,
+        mode: &CFRunLoopMode,
+        activities: CFRunLoopActivity,
+        order: CFIndex,
+        handler: F,
+    ) -> Self {
+        unsafe extern "C-unwind" fn callout<F: Fn(CFRunLoopActivity)>(
+            _observer: *mut CFRunLoopObserver,
+            activity: CFRunLoopActivity,
+            info: *mut c_void,
+        ) {
+            // SAFETY: The info is the closure, which is alive for as long as
+            // the observer is.
+            let handler = unsafe { &*info.cast::<F>() };
+            handler(activity);
+        }
+
+        let info = Box::into_raw(Box::new(handler));
+        let mut context = CFRunLoopObserverContext {
+            version: 0,
+            info: info.cast(),
+            retain: None,
+            release: Some(release::<F>),
+            copyDescription: None,
+        };
+        // SAFETY: The callout and context are valid, and the context is
+        // copied by the observer.
+        let observer = unsafe {
+            CFRunLoopObserverCreate(
+                None,
+                activities.0,
+                true as _,
+                order,
+                Some(callout::<F>),
+                &mut context,
+            )
+        };
+        let Some(observer) = observer else {
+            // SAFETY: The observer was not created, so we still own the info.
+            drop(unsafe { Box::from_raw(info) });
+            panic!("failed creating CFRunLoopObserver");
+        };
+        unsafe { CFRunLoopAddObserver(run_loop, Some(&observer), Some(mode)) };
+        Self { observer }
+    }
+
+    /// The underlying observer.
+    pub fn observer(&self) -> &CFRunLoopObserver {
+        &self.observer
+    }
+}
+
+impl Drop for CFRunLoopObserverGuard {
+    #[doc(alias = "CFRunLoopObserverInvalidate")]
+    fn drop(&mut self) {
+        unsafe { CFRunLoopObserverInvalidate(&self.observer) };
+    }
+}
+
+impl fmt::Debug for CFRunLoopObserverGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CFRunLoopObserverGuard")
+            .field("observer", &self.observer)
+            .finish()
+    }
+}
+
+/// A run loop timer that calls a closure, and which is invalidated when
+/// dropped.
+#[cfg(feature = "CFDate")]
+#[must_use = "the timer is invalidated when this is dropped"]
+pub struct CFRunLoopTimerGuard {
+    timer: CFRetained<CFRunLoopTimer>,
+}
+
+#[cfg(feature = "CFDate")]
+impl CFRunLoopTimerGuard {
+    /// Call the closure after the given delay, and then repeatedly with the
+    /// given interval, if any, while the run loop is running in the given
+    /// mode.
+    #[doc(alias = "CFRunLoopTimerCreate")]
+    #[doc(alias = "CFRunLoopAddTimer")]
+    pub fn new(
+        run_loop: &CFRunLoop,
+        mode: &CFRunLoopMode,
+        delay: Duration,
+        interval: Option<Duration>,
+        handler: impl Fn() + Send + 'static,
+    ) -> Self {
+        // SAFETY: The closure is `Send`, so it is fine to call and drop it
+        // on the run loop's thread.
+        unsafe { Self::new_unchecked(run_loop, mode, delay, interval, handler) }
+    }
+
+    /// Same as [`new`][Self::new], but the timer is added to the current
+    /// thread's run loop, which allows the closure to not be `Send`.
+    pub fn new_local(
+        mode: &CFRunLoopMode,
+        delay: Duration,
+        interval: Option<Duration>,
+        handler: impl Fn() + 'static,
+    ) -> Self {
+        // SAFETY: The timer is added to the current thread's run loop, and
+        // the guard is not `Send`, so it is also invalidated from this
+        // thread.
+        unsafe { Self::new_unchecked(&CFRunLoop::current(), mode, delay, interval, handler) }
+    }
+
+    /// # Safety
+    ///
+    /// The closure must be safe to call and drop on the run loop's thread.
+    unsafe fn new_unchecked<F: Fn() + 'static>(
+        run_loop: &CFRunLoop,
+        mode: &CFRunLoopMode,
+        delay: Duration,
+        interval: Option<Duration>,
+        handler: F,
+    ) -> Self {
+        unsafe extern "C-unwind" fn callout<F: Fn()>(
+            _timer: *mut CFRunLoopTimer,
+            info: *mut c_void,
+        ) {
+            // SAFETY: The info is the closure, which is alive for as long as
+            // the timer is.
+            unsafe { call::<F>(info) };
+        }
+
+        let info = Box::into_raw(Box::new(handler));
+        let mut context = CFRunLoopTimerContext {
+            version: 0,
+            info: info.cast(),
+            retain: None,
+            release: Some(release::<F>),
+            copyDescription: None,
+        };
+        let fire_date = unsafe { CFAbsoluteTimeGetCurrent() } + delay.as_secs_f64();
+        // An interval of zero makes the timer fire only once.
+        let interval = interval.map_or(0.0, |interval| interval.as_secs_f64());
+        // SAFETY: The callout and context are valid, and the context is
+        // copied by the timer.
+        let timer = unsafe {
+            CFRunLoopTimerCreate(
+                None,
+                fire_date,
+                interval,
+                0,
+                0,
+                Some(callout::<F>),
+                &mut context,
+            )
+        };
+        let Some(timer) = timer else {
+            // SAFETY: The timer was not created, so we still own the info.
+            drop(unsafe { Box::from_raw(info) });
+            panic!("failed creating CFRunLoopTimer");
+        };
+        unsafe { CFRunLoopAddTimer(run_loop, Some(&timer), Some(mode)) };
+        Self { timer }
+    }
+
+    /// The underlying timer.
+    pub fn timer(&self) -> &CFRunLoopTimer {
+        &self.timer
+    }
+
+    /// Reschedule the timer to fire after the given delay.
+    #[doc(alias = "CFRunLoopTimerSetNextFireDate")]
+    pub fn set_next_fire(&self, delay: Duration) {
+        let fire_date = unsafe { CFAbsoluteTimeGetCurrent() } + delay.as_secs_f64();
+        unsafe { CFRunLoopTimerSetNextFireDate(&self.timer, fire_date) };
+    }
+
+    /// Allow the system to delay firing the timer by up to the given
+    /// tolerance, to improve power usage.
+    #[doc(alias = "CFRunLoopTimerSetTolerance")]
+    pub fn set_tolerance(&self, tolerance: Duration) {
+        unsafe { CFRunLoopTimerSetTolerance(&self.timer, tolerance.as_secs_f64()) };
+    }
+
+    /// Whether the timer is still valid.
+    ///
+    /// Timers without an interval become invalid after they have fired.
+    #[doc(alias = "CFRunLoopTimerIsValid")]
+    pub fn is_valid(&self) -> bool {
+        unsafe { CFRunLoopTimerIsValid(&self.timer) != 0 }
+    }
+}
+
+#[cfg(feature = "CFDate")]
+impl Drop for CFRunLoopTimerGuard {
+    #[doc(alias = "CFRunLoopTimerInvalidate")]
+    fn drop(&mut self) {
+        unsafe { CFRunLoopTimerInvalidate(&self.timer) };
+    }
+}
+
+#[cfg(feature = "CFDate")]
+impl fmt::Debug for CFRunLoopTimerGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CFRunLoopTimerGuard")
+            .field("timer", &self.timer)
+            .finish()
+    }
+}
+
+/// A version 0 run loop source that calls a closure when signalled, and
+/// which is invalidated when dropped.
+///
+/// The source can be signalled from other threads with a
+/// [`CFRunLoopSourceSignaler`].
+#[must_use = "the source is invalidated when this is dropped"]
+pub struct CFRunLoopSourceGuard {
+    signaler: CFRunLoopSourceSignaler,
+}
+
+impl CFRunLoopSourceGuard {
+    /// Call the closure on the run loop while it is running in the given
+    /// mode, whenever the source has been signalled.
+    ///
+    /// Sources with a lower `order` are handled first.
+    #[doc(alias = "CFRunLoopSourceCreate")]
+    #[doc(alias = "CFRunLoopAddSource")]
+    pub fn new<F: Fn() + Send + 'static>(
+        run_loop: &CFRunLoop,
+        mode: &CFRunLoopMode,
+        order: CFIndex,
+        perform: F,
+    ) -> Self {
+        unsafe extern "C-unwind" fn perform_callout<F: Fn()>(info: *mut c_void) {
+            // SAFETY: The info is the closure, which is alive for as long as
+            // the source is.
+            unsafe { call::<F>(info) };
+        }
+
+        let info = Box::into_raw(Box::new(perform));
+        let mut context = CFRunLoopSourceContext {
+            version: 0,
+            info: info.cast(),
+            retain: None,
+            release: Some(release::<F>),
+            copyDescription: None,
+            equal: None,
+            hash: None,
+            schedule: None,
+            cancel: None,
+            perform: Some(perform_callout::<F>),
+        };
+        // SAFETY: The callout and context are valid, and the context is
+        // copied by the source.
+        let source = unsafe { CFRunLoopSourceCreate(None, order, &mut context) };
+        let Some(source) = source else {
+            // SAFETY: The source was not created, so we still own the info.
+            drop(unsafe { Box::from_raw(info) });
+            panic!("failed creating CFRunLoopSource");
+        };
+        unsafe { CFRunLoopAddSource(run_loop, Some(&source), Some(mode)) };
+        Self {
+            signaler: CFRunLoopSourceSignaler {
+                source,
+                run_loop: run_loop.retain(),
+            },
+        }
+    }
+
+    /// The underlying source.
+    pub fn source(&self) -> &CFRunLoopSource {
+        &self.signaler.source
+    }
+
+    /// Signal the source, and wake up the run loop.
+    ///
+    /// See [`CFRunLoopSourceSignaler::signal`].
+    #[doc(alias = "CFRunLoopSourceSignal")]
+    pub fn signal(&self) {
+        self.signaler.signal();
+    }
+
+    /// Get a handle that can be used to signal the source from other
+    /// threads.
+    pub fn signaler(&self) -> CFRunLoopSourceSignaler {
+        self.signaler.clone()
+    }
+}
+
+impl Drop for CFRunLoopSourceGuard {
+    #[doc(alias = "CFRunLoopSourceInvalidate")]
+    fn drop(&mut self) {
+        unsafe { CFRunLoopSourceInvalidate(&self.signaler.source) };
+    }
+}
+
+impl fmt::Debug for CFRunLoopSourceGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CFRunLoopSourceGuard")
+            .field("source", &self.signaler.source)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A handle for signalling a [`CFRunLoopSourceGuard`] from any thread.
+#[derive(Clone)]
+pub struct CFRunLoopSourceSignaler {
+    source: CFRetained<CFRunLoopSource>,
+    run_loop: CFRetained<CFRunLoop>,
+}
+
+// SAFETY: Signalling a source and waking up a run loop is thread-safe, and
+// the source's closure is required to be `Send`, so it is fine if the
+// source is deallocated on another thread.
+unsafe impl Send for CFRunLoopSourceSignaler {}
+// SAFETY: See above.
+unsafe impl Sync for CFRunLoopSourceSignaler {}
+
+impl CFRunLoopSourceSignaler {
+    /// Mark the source as ready to fire, and wake up the run loop.
+    ///
+    /// The source's closure is called the next time the run loop runs in
+    /// the source's mode. Signalling the source multiple times before then
+    /// only calls it once.
+    ///
+    /// Does nothing if the source has been invalidated.
+    #[doc(alias = "CFRunLoopSourceSignal")]
+    pub fn signal(&self) {
+        unsafe { CFRunLoopSourceSignal(&self.source) };
+        self.run_loop.wake_up();
+    }
+}
+
+impl fmt::Debug for CFRunLoopSourceSignaler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CFRunLoopSourceSignaler")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "CFDate"))]
+mod tests {
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+    use core::cell::{Cell, RefCell};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn current_and_main() {
+        let current = CFRunLoop::current();
+        assert_eq!(current, CFRunLoop::current());
+        assert!(!current.is_waiting());
+        let _ = CFRunLoop::main();
+    }
+
+    #[test]
+    fn timer() {
+        let fired = Rc::new(Cell::new(0));
+        let fired_clone = fired.clone();
+        let timer = CFRunLoopTimerGuard::new_local(
+            CFRunLoop::default_mode(),
+            Duration::ZERO,
+            None,
+            move || fired_clone.set(fired_clone.get() + 1),
+        );
+        assert!(timer.is_valid());
+
+        CFRunLoop::run_in_mode(CFRunLoop::default_mode(), Duration::from_millis(10), false);
+        assert_eq!(fired.get(), 1);
+        assert!(!timer.is_valid());
+    }
+
+    #[test]
+    fn observer() {
+        let activities = Rc::new(RefCell::new(Vec::new()));
+        let activities_clone = activities.clone();
+        let observer = CFRunLoopObserverGuard::new_local(
+            CFRunLoop::default_mode(),
+            CFRunLoopActivity::Entry | CFRunLoopActivity::Exit,
+            0,
+            move |activity| activities_clone.borrow_mut().push(activity),
+        );
+        // The run loop returns immediately without notifying observers if
+        // the mode has no sources or timers.
+        let _source =
+            CFRunLoopSourceGuard::new(&CFRunLoop::current(), CFRunLoop::default_mode(), 0, || {});
+
+        CFRunLoop::run_in_mode(CFRunLoop::default_mode(), Duration::ZERO, false);
+        assert_eq!(
+            *activities.borrow(),
+            [CFRunLoopActivity::Entry, CFRunLoopActivity::Exit]
+        );
+
+        drop(observer);
+        CFRunLoop::run_in_mode(CFRunLoop::default_mode(), Duration::ZERO, false);
+        assert_eq!(activities.borrow().len(), 2);
+    }
+
+    #[test]
+    fn source_from_other_thread() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let count_clone = count.clone();
+        let source = CFRunLoopSourceGuard::new(
+            &CFRunLoop::current(),
+            CFRunLoop::default_mode(),
+            0,
+            move || {
+                count_clone.fetch_add(1, Ordering::Relaxed);
+            },
+        );
+
+        let signaler = source.signaler();
+        std::thread::spawn(move || signaler.signal())
+            .join()
+            .unwrap();
+
+        let res = CFRunLoop::run_in_mode(CFRunLoop::default_mode(), Duration::from_secs(1), true);
+        assert_eq!(res, CFRunLoopRunResult::HandledSource);
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "block2")]
+    fn perform_from_other_thread() {
+        let run_loop = CFRunLoop::current();
+        let scheduler = run_loop.scheduler();
+        let count = Arc::new(AtomicUsize::new(0));
+        let count_clone = count.clone();
+        std::thread::spawn(move || {
+            scheduler.perform(move || {
+                count_clone.fetch_add(1, Ordering::Relaxed);
+            });
+        })
+        .join()
+        .unwrap();
+
+        CFRunLoop::run_in_mode(CFRunLoop::default_mode(), Duration::from_millis(10), false);
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,405 @@
+//! Typed views of `CFSet` and `CFMutableSet`.
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use crate::__cf_helpers::as_value;
+#[cfg(feature = "alloc")]
+use crate::__cf_helpers::retain_checked;
+use crate::{
+    kCFTypeSetCallBacks, CFMutableSet, CFRetained, CFSet, CFSetAddValue, CFSetContainsValue,
+    CFSetCreate, CFSetCreateMutable, CFSetGetCount, CFSetRemoveAllValues, CFSetRemoveValue, CFType,
+    ConcreteType, Type,
+};
+
+impl CFSet {
+    /// The number of values in the set.
+    #[inline]
+    #[doc(alias = "CFSetGetCount")]
+    pub fn len(&self) -> usize {
+        unsafe { CFSetGetCount(self) as _ }
+    }
+
+    /// Whether the set is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// View the set as a set of `T`.
+    ///
+    /// The set is not checked here, instead each value is checked to be of
+    /// type `T` when it is accessed, see [`CFTypedSet::iter`].
+    #[inline]
+    pub fn as_typed<T: ConcreteType>(&self) -> &CFTypedSet<T> {
+        let ptr: *const Self = self;
+        // SAFETY: `CFTypedSet` is `#[repr(transparent)]` over `CFSet`, and
+        // does not have any further invariants.
+        unsafe { &*ptr.cast() }
+    }
+}
+
+impl CFMutableSet {
+    /// View the set as a mutable set of `T`.
+    ///
+    /// See [`CFSet::as_typed`].
+    #[inline]
+    pub fn as_typed<T: ConcreteType>(&self) -> &CFTypedMutableSet<T> {
+        let ptr: *const Self = self;
+        // SAFETY: `CFTypedMutableSet` is `#[repr(transparent)]` over
+        // `CFMutableSet`, and does not have any further invariants.
+        unsafe { &*ptr.cast() }
+    }
+}
+
+/// A [`CFSet`] whose values are of type `T`.
+///
+/// CoreFoundation sets are untyped, so this is only a view: each value is
+/// checked with `CFGetTypeID` when it is accessed. Sets created with the
+/// constructors here use the `CFType` callbacks, and thus retain their
+/// values, and compare them with `CFEqual` and `CFHash`.
+///
+///
+/// # Examples
+///
+/// ```
+/// use objc2_core_foundation::{CFRetained, CFString, CFTypedSet};
+///
+/// let set: CFRetained<CFTypedSet<CFString>> = ["a", "b", "a"]
+///     .into_iter()
+///     .map(CFString::from_str)
+///     .collect();
+/// assert_eq!(set.len(), 2);
+/// assert!(set.contains(&CFString::from_str("a")));
+/// assert!(!set.contains(&CFString::from_str("c")));
+/// ```
+#[repr(transparent)]
+#[doc(alias = "CFSet")]
+pub struct CFTypedSet<T: ?Sized> {
+    set: CFSet,
+    value: PhantomData<T>,
+}
+
+// SAFETY: The set is a `CFSet`.
+unsafe impl<T: ?Sized> Type for CFTypedSet<T> {}
+
+impl<T: ?Sized> Deref for CFTypedSet<T> {
+    type Target = CFSet;
+
+    #[inline]
+    fn deref(&self) -> &CFSet {
+        &self.set
+    }
+}
+
+impl<T: ?Sized> AsRef<CFSet> for CFTypedSet<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFSet {
+        self
+    }
+}
+
+impl<T: ?Sized> AsRef<CFType> for CFTypedSet<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFType {
+        self // Through Deref
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for CFTypedSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.set, f)
+    }
+}
+
+impl<T: ConcreteType> CFTypedSet<T> {
+    /// Create a set containing the given values.
+    ///
+    /// Duplicate values are only stored once.
+    #[doc(alias = "CFSetCreate")]
+    pub fn from_slice(values: &[&T]) -> CFRetained<Self> {
+        let len = values.len().try_into().expect("slice too large");
+        // `&T` is ABI compatible with `*const c_void`, and `CFSetCreate`
+        // does not modify the values.
+        let ptr = values.as_ptr().cast_mut().cast::<*const c_void>();
+        let set = unsafe { CFSetCreate(None, ptr, len, &kCFTypeSetCallBacks) }
+            .expect("failed creating CFSet");
+        // SAFETY: The set contains values of type `T`.
+        unsafe { CFRetained::cast_unchecked(set) }
+    }
+
+    /// Whether the set contains the given value.
+    #[inline]
+    #[doc(alias = "CFSetContainsValue")]
+    pub fn contains(&self, value: &T) -> bool {
+        unsafe { CFSetContainsValue(self, as_value(value)) != 0 }
+    }
+
+    /// An iterator over the values in the set, in an unspecified order.
+    ///
+    /// The values are copied out of the set when this is called, so the
+    /// iterator is not affected by later mutations.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value is not of type `T`.
+    #[cfg(feature = "alloc")]
+    #[doc(alias = "CFSetGetValues")]
+    pub fn iter(&self) -> CFTypedSetIter<T> {
+        let len = self.len();
+        let mut values = Vec::with_capacity(len);
+        unsafe {
+            crate::CFSetGetValues(self, values.as_mut_ptr());
+            // SAFETY: The buffer was large enough, and has been filled.
+            values.set_len(len);
+        }
+        let values: Vec<_> = values
+            .into_iter()
+            // SAFETY: The set retains its values, so the pointers are valid.
+            .map(|value| unsafe { retain_checked(value, "set", "value") })
+            .collect();
+        CFTypedSetIter {
+            inner: values.into_iter(),
+        }
+    }
+
+    /// Copy the values of the set into a new [`Vec`], in an unspecified
+    /// order.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value is not of type `T`.
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<CFRetained<T>> {
+        self.iter().collect()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ConcreteType> IntoIterator for &CFTypedSet<T> {
+    type Item = CFRetained<T>;
+    type IntoIter = CFTypedSetIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: ConcreteType> FromIterator<CFRetained<T>> for CFRetained<CFTypedSet<T>> {
+    fn from_iter<I: IntoIterator<Item = CFRetained<T>>>(iter: I) -> Self {
+        let set: CFRetained<CFTypedMutableSet<T>> = iter.into_iter().collect();
+        // SAFETY: A mutable set is a set.
+        unsafe { CFRetained::cast_unchecked(set) }
+    }
+}
+
+impl<'a, T: ConcreteType> FromIterator<&'a T> for CFRetained<CFTypedSet<T>> {
+    fn from_iter<I: IntoIterator<Item = &'a T>>(iter: I) -> Self {
+        let set: CFRetained<CFTypedMutableSet<T>> = iter.into_iter().collect();
+        // SAFETY: A mutable set is a set.
+        unsafe { CFRetained::cast_unchecked(set) }
+    }
+}
+
+/// An iterator over the values of a [`CFTypedSet`].
+///
+/// Created by [`CFTypedSet::iter`].
+#[cfg(feature = "alloc")]
+pub struct CFTypedSetIter<T> {
+    inner: alloc::vec::IntoIter<CFRetained<T>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T> fmt::Debug for CFTypedSetIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CFTypedSetIter")
+            .field("remaining", &self.inner.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Type> Iterator for CFTypedSetIter<T> {
+    type Item = CFRetained<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Type> ExactSizeIterator for CFTypedSetIter<T> {}
+
+#[cfg(feature = "alloc")]
+impl<T: Type> core::iter::FusedIterator for CFTypedSetIter<T> {}
+
+/// A [`CFMutableSet`] whose values are of type `T`.
+///
+/// See [`CFTypedSet`] for details.
+#[repr(transparent)]
+#[doc(alias = "CFMutableSet")]
+pub struct CFTypedMutableSet<T: ?Sized> {
+    set: CFMutableSet,
+    value: PhantomData<T>,
+}
+
+// SAFETY: The set is a `CFMutableSet`.
+unsafe impl<T: ?Sized> Type for CFTypedMutableSet<T> {}
+
+impl<T: ?Sized> Deref for CFTypedMutableSet<T> {
+    type Target = CFTypedSet<T>;
+
+    #[inline]
+    fn deref(&self) -> &CFTypedSet<T> {
+        let ptr: *const Self = self;
+        // SAFETY: A mutable set is a set, and both types are
+        // `#[repr(transparent)]`.
+        unsafe { &*ptr.cast() }
+    }
+}
+
+impl<T: ?Sized> AsRef<CFMutableSet> for CFTypedMutableSet<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFMutableSet {
+        &self.set
+    }
+}
+
+impl<T: ?Sized> AsRef<CFSet> for CFTypedMutableSet<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFSet {
+        self // Through Deref
+    }
+}
+
+impl<T: ?Sized> AsRef<CFType> for CFTypedMutableSet<T> {
+    #[inline]
+    fn as_ref(&self) -> &CFType {
+        self // Through Deref
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for CFTypedMutableSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.set, f)
+    }
+}
+
+impl<T: ConcreteType> CFTypedMutableSet<T> {
+    /// Create a new empty set.
+    #[inline]
+    #[doc(alias = "CFSetCreateMutable")]
+    pub fn new() -> CFRetained<Self> {
+        let set = unsafe { CFSetCreateMutable(None, 0, &kCFTypeSetCallBacks) }
+            .expect("failed creating CFMutableSet");
+        // SAFETY: The set is empty.
+        unsafe { CFRetained::cast_unchecked(set) }
+    }
+
+    /// Add a value to the set.
+    ///
+    /// If an equal value is already present, the set is left unchanged.
+    #[inline]
+    #[doc(alias = "CFSetAddValue")]
+    pub fn insert(&self, value: &T) {
+        unsafe { CFSetAddValue(Some(&self.set), as_value(value)) };
+    }
+
+    /// Remove a value from the set, if present.
+    #[inline]
+    #[doc(alias = "CFSetRemoveValue")]
+    pub fn remove(&self, value: &T) {
+        unsafe { CFSetRemoveValue(Some(&self.set), as_value(value)) };
+    }
+
+    /// Remove all values from the set.
+    #[inline]
+    #[doc(alias = "CFSetRemoveAllValues")]
+    pub fn clear(&self) {
+        unsafe { CFSetRemoveAllValues(Some(&self.set)) };
+    }
+}
+
+impl<T: ConcreteType> FromIterator<CFRetained<T>> for CFRetained<CFTypedMutableSet<T>> {
+    fn from_iter<I: IntoIterator<Item = CFRetained<T>>>(iter: I) -> Self {
+        let set = CFTypedMutableSet::<T>::new();
+        for value in iter {
+            set.insert(&value);
+        }
+        set
+    }
+}
+
+impl<'a, T: ConcreteType> FromIterator<&'a T> for CFRetained<CFTypedMutableSet<T>> {
+    fn from_iter<I: IntoIterator<Item = &'a T>>(iter: I) -> Self {
+        let set = CFTypedMutableSet::<T>::new();
+        for value in iter {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+#[cfg(all(test, feature = "CFNumber", feature = "CFString"))]
+mod tests {
+    use alloc::string::{String, ToString};
+
+    use super::*;
+    use crate::{CFNumber, CFString};
+
+    #[test]
+    fn from_slice() {
+        let a = CFString::from_str("a");
+        let b = CFString::from_str("b");
+        let set = CFTypedSet::from_slice(&[&*a, &*b, &*a]);
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&a));
+        assert!(set.contains(&CFString::from_str("b")));
+        assert!(!set.contains(&CFString::from_str("c")));
+    }
+
+    #[test]
+    fn collect_and_iterate() {
+        let set: CFRetained<CFTypedSet<CFString>> = ["x", "y", "z"]
+            .into_iter()
+            .map(CFString::from_str)
+            .collect();
+        let mut values: Vec<String> = set.iter().map(|s| s.to_string()).collect();
+        values.sort();
+        assert_eq!(values, ["x", "y", "z"]);
+    }
+
+    #[test]
+    fn mutate() {
+        let set = CFTypedMutableSet::<CFNumber>::new();
+        set.insert(&CFNumber::new_i32(1));
+        set.insert(&CFNumber::new_i32(1));
+        set.insert(&CFNumber::new_i32(2));
+        assert_eq!(set.len(), 2);
+
+        set.remove(&CFNumber::new_i32(1));
+        assert!(!set.contains(&CFNumber::new_i32(1)));
+        assert_eq!(set.to_vec().len(), 1);
+        set.clear();
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    #[should_panic = "set contained value of wrong type"]
+    fn wrong_type() {
+        let set = CFTypedSet::from_slice(&[&*CFString::from_str("a")]);
+        let _ = set.as_typed::<CFNumber>().to_vec();
+    }
+}
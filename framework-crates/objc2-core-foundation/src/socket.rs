@@ -0,0 +1,526 @@
+//! Safe wrappers around `CFSocket`.
+//!
+//! Like the run loop guards, the closure given to the socket is owned by
+//! Core Foundation, and the guard invalidates the socket when dropped.
+use alloc::boxed::Box;
+use core::ffi::c_void;
+use core::fmt;
+#[cfg(feature = "CFDate")]
+use core::time::Duration;
+
+use crate::{
+    CFData, CFRetained, CFRunLoop, CFRunLoopAddSource, CFRunLoopMode, CFSocket,
+    CFSocketCallBackType, CFSocketContext, CFSocketCopyAddress, CFSocketCopyPeerAddress,
+    CFSocketCreate, CFSocketCreateRunLoopSource, CFSocketCreateWithNative,
+    CFSocketDisableCallBacks, CFSocketEnableCallBacks, CFSocketError, CFSocketGetContext,
+    CFSocketGetNative, CFSocketInvalidate, CFSocketIsValid, CFSocketNativeHandle,
+    CFSocketSetAddress,
+};
+#[cfg(feature = "CFDate")]
+use crate::{CFSocketConnectToAddress, CFSocketSendData};
+
+/// An event on a socket, given to the closure of a [`CFSocketGuard`].
+///
+/// Which events are delivered is controlled by the callback types given when
+/// creating the guard, and by [`CFSocketGuard::enable_callbacks`].
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub enum CFSocketEvent<'a> {
+    /// Data is available to be read from the socket.
+    #[doc(alias = "kCFSocketReadCallBack")]
+    Readable,
+    /// A new connection was accepted on a listening socket.
+    ///
+    /// The receiver owns the native handle, and is responsible for closing
+    /// it.
+    #[doc(alias = "kCFSocketAcceptCallBack")]
+    Accepted {
+        /// The native handle of the new connection.
+        handle: CFSocketNativeHandle,
+        /// The address of the peer, as a `struct sockaddr`.
+        address: Option<&'a CFData>,
+    },
+    /// Data was read from the socket in the background.
+    ///
+    /// The data is empty if the peer closed the connection.
+    #[doc(alias = "kCFSocketDataCallBack")]
+    Data {
+        /// The data that was read.
+        data: &'a CFData,
+        /// The address that the data was received from, as a
+        /// `struct sockaddr`.
+        address: Option<&'a CFData>,
+    },
+    /// A connection attempt made in the background completed, either
+    /// successfully or with the given `errno`.
+    #[doc(alias = "kCFSocketConnectCallBack")]
+    Connected(Result<(), i32>),
+    /// The socket can accept more data to be written.
+    #[doc(alias = "kCFSocketWriteCallBack")]
+    Writable,
+}
+
+impl<'a> CFSocketEvent<'a> {
+    /// # Safety
+    ///
+    /// The pointers must be the ones given to a `CFSocketCallBack` for the
+    /// given callback type.
+    unsafe fn from_raw(
+        callback_type: CFSocketCallBackType,
+        address: *const CFData,
+        data: *const c_void,
+    ) -> Option<Self> {
+        // SAFETY: The address is a valid `CFData` if non-NULL.
+        let address = unsafe { address.as_ref() };
+        // The callback types are not really flags, so compare them exactly.
+        Some(match callback_type {
+            CFSocketCallBackType::ReadCallBack => Self::Readable,
+            CFSocketCallBackType::AcceptCallBack => Self::Accepted {
+                // SAFETY: The data is a pointer to the native handle.
+                handle: unsafe { *data.cast::<CFSocketNativeHandle>() },
+                address,
+            },
+            CFSocketCallBackType::DataCallBack => Self::Data {
+                // SAFETY: The data is a valid `CFData`.
+                data: unsafe { data.cast::<CFData>().as_ref() }?,
+                address,
+            },
+            CFSocketCallBackType::ConnectCallBack => {
+                // SAFETY: The data is NULL, or a pointer to the error code.
+                Self::Connected(match unsafe { data.cast::<i32>().as_ref() } {
+                    None => Ok(()),
+                    Some(&error) => Err(error),
+                })
+            }
+            CFSocketCallBackType::WriteCallBack => Self::Writable,
+            _ => return None,
+        })
+    }
+}
+
+fn socket_result(error: CFSocketError) -> Result<(), CFSocketError> {
+    if error == CFSocketError::Success {
+        Ok(())
+    } else {
+        Err(error)
+    }
+}
+
+impl fmt::Display for CFSocketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Success => write!(f, "socket operation succeeded"),
+            Self::Timeout => write!(f, "socket operation timed out"),
+            _ => write!(f, "socket operation failed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")] // use core::error::Error from Rust 1.81 once in MSRV.
+impl std::error::Error for CFSocketError {}
+
+/// Release the boxed closure stored in a context's `info`.
+unsafe extern "C-unwind" fn release<F>(info: *const c_void) {
+    // SAFETY: The info was created with `Box::into_raw` when creating the
+    // socket, and Core Foundation releases it exactly once.
+    drop(unsafe { Box::from_raw(info.cast::<F>().cast_mut()) });
+}
+
+/// A socket that calls a closure when events occur on it, and which is
+/// invalidated when dropped.
+///
+/// The socket is scheduled on a run loop, and the closure is called on that
+/// run loop while it is running in the given mode.
+///
+/// Unless disabled with `CFSocketSetSocketFlags`, invalidating the socket
+/// also closes the native socket handle.
+#[must_use = "the socket is invalidated when this is dropped"]
+pub struct CFSocketGuard {
+    socket: CFRetained<CFSocket>,
+}
+
+impl CFSocketGuard {
+    /// Create a new socket, and call the closure when any of the given
+    /// events occur on it.
+    ///
+    /// The protocol family, socket type and protocol are the same as for
+    /// `socket(2)`, e.g. `PF_INET`, `SOCK_STREAM` and `IPPROTO_TCP`.
+    ///
+    /// Returns [`None`] if the socket could not be created.
+    #[doc(alias = "CFSocketCreate")]
+    #[doc(alias = "CFSocketCreateRunLoopSource")]
+    pub fn new(
+        run_loop: &CFRunLoop,
+        mode: &CFRunLoopMode,
+        protocol_family: i32,
+        socket_type: i32,
+        protocol: i32,
+        callbacks: CFSocketCallBackType,
+        handler: impl Fn(&CFSocket, CFSocketEvent<'_>) + Send + 'static,
+    ) -> Option<Self> {
+        // SAFETY: The closure is `Send`, so it is fine to call and drop it
+        // on the run loop's thread.
+        unsafe {
+            Self::new_unchecked(run_loop, mode, handler, |callout, context| {
+                CFSocketCreate(
+                    None,
+                    protocol_family,
+                    socket_type,
+                    protocol,
+                    callbacks.0,
+                    callout,
+                    context,
+                )
+            })
+        }
+    }
+
+    /// Same as [`new`][Self::new], but the socket is scheduled on the
+    /// current thread's run loop, which allows the closure to not be `Send`.
+    pub fn new_local(
+        mode: &CFRunLoopMode,
+        protocol_family: i32,
+        socket_type: i32,
+        protocol: i32,
+        callbacks: CFSocketCallBackType,
+        handler: impl Fn(&CFSocket, CFSocketEvent<'_>) + 'static,
+    ) -> Option<Self> {
+        // SAFETY: The socket is scheduled on the current thread's run loop,
+        // and the guard is not `Send`, so it is also invalidated from this
+        // thread.
+        unsafe {
+            Self::new_unchecked(&CFRunLoop::current(), mode, handler, |callout, context| {
+                CFSocketCreate(
+                    None,
+                    protocol_family,
+                    socket_type,
+                    protocol,
+                    callbacks.0,
+                    callout,
+                    context,
+                )
+            })
+        }
+    }
+
+    /// Wrap an existing native socket, and call the closure when any of the
+    /// given events occur on it.
+    ///
+    /// Returns [`None`] if the socket could not be wrapped, including if it
+    /// is already wrapped by another `CFSocket`.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The handle must be an open socket, and ownership of it is transferred
+    /// to the returned guard, which closes it when dropped.
+    #[doc(alias = "CFSocketCreateWithNative")]
+    #[doc(alias = "CFSocketCreateRunLoopSource")]
+    pub unsafe fn from_native(
+        run_loop: &CFRunLoop,
+        mode: &CFRunLoopMode,
+        handle: CFSocketNativeHandle,
+        callbacks: CFSocketCallBackType,
+        handler: impl Fn(&CFSocket, CFSocketEvent<'_>) + Send + 'static,
+    ) -> Option<Self> {
+        // SAFETY: The closure is `Send`, and the caller upholds the safety
+        // requirements of the handle.
+        unsafe {
+            Self::new_unchecked(run_loop, mode, handler, |callout, context| {
+                CFSocketCreateWithNative(None, handle, callbacks.0, callout, context)
+            })
+        }
+    }
+
+    /// Same as [`from_native`][Self::from_native], but the socket is
+    /// scheduled on the current thread's run loop, which allows the closure
+    /// to not be `Send`.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// Same as [`from_native`][Self::from_native].
+    pub unsafe fn from_native_local(
+        mode: &CFRunLoopMode,
+        handle: CFSocketNativeHandle,
+        callbacks: CFSocketCallBackType,
+        handler: impl Fn(&CFSocket, CFSocketEvent<'_>) + 'static,
+    ) -> Option<Self> {
+        // SAFETY: See `new_local`, and the caller upholds the safety
+        // requirements of the handle.
+        unsafe {
+            Self::new_unchecked(&CFRunLoop::current(), mode, handler, |callout, context| {
+                CFSocketCreateWithNative(None, handle, callbacks.0, callout, context)
+            })
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The closure must be safe to call and drop on the run loop's thread,
+    /// and `create` must create a socket with the given callout and context.
+    unsafe fn new_unchecked<F: Fn(&CFSocket, CFSocketEvent<'_>) + 'static>(
+        run_loop: &CFRunLoop,
+        mode: &CFRunLoopMode,
+        handler: F,
+        create: impl FnOnce(
+            crate::CFSocketCallBack,
+            *const CFSocketContext,
+        ) -> Option<CFRetained<CFSocket>>,
+    ) -> Option<Self> {
+        unsafe extern "C-unwind" fn callout<F: Fn(&CFSocket, CFSocketEvent<'_>)>(
+            socket: *mut CFSocket,
+            callback_type: CFSocketCallBackType,
+            address: *const CFData,
+            data: *const c_void,
+            info: *mut c_void,
+        ) {
+            // SAFETY: The info is the closure, which is alive for as long as
+            // the socket is.
+            let handler = unsafe { &*info.cast::<F>() };
+            // SAFETY: The socket is valid for the duration of the callout.
+            let socket = unsafe { &*socket };
+            // SAFETY: The pointers are passed on from Core Foundation.
+            if let Some(event) = unsafe { CFSocketEvent::from_raw(callback_type, address, data) } {
+                handler(socket, event);
+            }
+        }
+
+        let info = Box::into_raw(Box::new(handler));
+        let context = CFSocketContext {
+            version: 0,
+            info: info.cast(),
+            retain: None,
+            release: Some(release::<F>),
+            copyDescription: None,
+        };
+        let Some(socket) = create(Some(callout::<F>), &context) else {
+            // SAFETY: The socket was not created, so we still own the info.
+            drop(unsafe { Box::from_raw(info) });
+            return None;
+        };
+
+        // `CFSocketCreateWithNative` returns the existing socket if the
+        // native handle is already wrapped, and ignores our context.
+        let mut existing = CFSocketContext {
+            version: 0,
+            info: core::ptr::null_mut(),
+            retain: None,
+            release: None,
+            copyDescription: None,
+        };
+        unsafe { CFSocketGetContext(&socket, &mut existing) };
+        if existing.info != info.cast() {
+            // SAFETY: The context was ignored, so we still own the info.
+            drop(unsafe { Box::from_raw(info) });
+            return None;
+        }
+
+        let source = unsafe { CFSocketCreateRunLoopSource(None, Some(&socket), 0) };
+        let Some(source) = source else {
+            unsafe { CFSocketInvalidate(&socket) };
+            return None;
+        };
+        unsafe { CFRunLoopAddSource(run_loop, Some(&source), Some(mode)) };
+        Some(Self { socket })
+    }
+
+    /// The underlying socket.
+    pub fn socket(&self) -> &CFSocket {
+        &self.socket
+    }
+
+    /// The native socket handle.
+    #[doc(alias = "CFSocketGetNative")]
+    pub fn native(&self) -> CFSocketNativeHandle {
+        unsafe { CFSocketGetNative(&self.socket) }
+    }
+
+    /// Whether the socket is still valid.
+    #[doc(alias = "CFSocketIsValid")]
+    pub fn is_valid(&self) -> bool {
+        unsafe { CFSocketIsValid(&self.socket) != 0 }
+    }
+
+    /// The local address of the socket, as a `struct sockaddr`.
+    #[doc(alias = "CFSocketCopyAddress")]
+    pub fn address(&self) -> Option<CFRetained<CFData>> {
+        unsafe { CFSocketCopyAddress(&self.socket) }
+    }
+
+    /// The address of the peer that the socket is connected to, as a
+    /// `struct sockaddr`.
+    #[doc(alias = "CFSocketCopyPeerAddress")]
+    pub fn peer_address(&self) -> Option<CFRetained<CFData>> {
+        unsafe { CFSocketCopyPeerAddress(&self.socket) }
+    }
+
+    /// Bind the socket to the given address, given as a `struct sockaddr`,
+    /// and start listening on it if it is a stream socket.
+    #[doc(alias = "CFSocketSetAddress")]
+    pub fn set_address(&self, address: &CFData) -> Result<(), CFSocketError> {
+        socket_result(unsafe { CFSocketSetAddress(&self.socket, Some(address)) })
+    }
+
+    /// Connect the socket to the given address, given as a
+    /// `struct sockaddr`.
+    ///
+    /// If `timeout` is [`None`], this does not wait for the connection, and
+    /// the result is instead reported with a [`CFSocketEvent::Connected`]
+    /// event.
+    #[cfg(feature = "CFDate")]
+    #[doc(alias = "CFSocketConnectToAddress")]
+    pub fn connect(
+        &self,
+        address: &CFData,
+        timeout: Option<Duration>,
+    ) -> Result<(), CFSocketError> {
+        let timeout = timeout.map_or(-1.0, |timeout| timeout.as_secs_f64());
+        socket_result(unsafe { CFSocketConnectToAddress(&self.socket, Some(address), timeout) })
+    }
+
+    /// Send the data on the socket, waiting at most `timeout` for it to be
+    /// sent.
+    ///
+    /// The address, given as a `struct sockaddr`, is only used for
+    /// connectionless sockets.
+    #[cfg(feature = "CFDate")]
+    #[doc(alias = "CFSocketSendData")]
+    pub fn send(
+        &self,
+        address: Option<&CFData>,
+        data: &[u8],
+        timeout: Duration,
+    ) -> Result<(), CFSocketError> {
+        let data = CFData::from_bytes(data);
+        socket_result(unsafe {
+            CFSocketSendData(&self.socket, address, Some(&data), timeout.as_secs_f64())
+        })
+    }
+
+    /// Enable delivery of the given events.
+    ///
+    /// Read, accept and data events are re-enabled automatically after
+    /// being delivered, while write and connect events must be re-enabled
+    /// manually.
+    #[doc(alias = "CFSocketEnableCallBacks")]
+    pub fn enable_callbacks(&self, callbacks: CFSocketCallBackType) {
+        unsafe { CFSocketEnableCallBacks(&self.socket, callbacks.0) }
+    }
+
+    /// Disable delivery of the given events.
+    #[doc(alias = "CFSocketDisableCallBacks")]
+    pub fn disable_callbacks(&self, callbacks: CFSocketCallBackType) {
+        unsafe { CFSocketDisableCallBacks(&self.socket, callbacks.0) }
+    }
+}
+
+impl Drop for CFSocketGuard {
+    #[doc(alias = "CFSocketInvalidate")]
+    fn drop(&mut self) {
+        unsafe { CFSocketInvalidate(&self.socket) };
+    }
+}
+
+impl fmt::Debug for CFSocketGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CFSocketGuard")
+            .field("socket", &self.socket)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "CFDate", feature = "std", unix))]
+mod tests {
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+    use core::cell::{Cell, RefCell};
+    use std::io::Write;
+    use std::net::{TcpListener, TcpStream};
+    use std::os::fd::{FromRawFd, IntoRawFd};
+    use std::os::unix::net::UnixStream;
+    use std::thread;
+
+    use super::*;
+
+    fn run_until(done: impl Fn() -> bool) {
+        for _ in 0..50 {
+            if done() {
+                return;
+            }
+            CFRunLoop::run_in_mode(CFRunLoop::default_mode(), Duration::from_millis(100), true);
+        }
+        panic!("timed out waiting for socket event");
+    }
+
+    #[test]
+    fn accept() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let accepted = Rc::new(Cell::new(None));
+        let guard = unsafe {
+            CFSocketGuard::from_native_local(
+                CFRunLoop::default_mode(),
+                listener.into_raw_fd(),
+                CFSocketCallBackType::AcceptCallBack,
+                {
+                    let accepted = accepted.clone();
+                    move |_socket, event| match event {
+                        CFSocketEvent::Accepted { handle, address } => {
+                            assert!(address.is_some());
+                            accepted.set(Some(handle));
+                        }
+                        event => panic!("unexpected event {event:?}"),
+                    }
+                },
+            )
+        }
+        .unwrap();
+        assert!(guard.is_valid());
+        assert!(guard.address().is_some());
+
+        let client = thread::spawn(move || TcpStream::connect(addr).unwrap());
+        run_until(|| accepted.get().is_some());
+        let _client = client.join().unwrap();
+
+        let handle = accepted.get().unwrap();
+        drop(unsafe { TcpStream::from_raw_fd(handle) });
+    }
+
+    #[test]
+    fn data() {
+        let (local, mut remote) = UnixStream::pair().unwrap();
+
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let closed = Rc::new(Cell::new(false));
+        let guard = unsafe {
+            CFSocketGuard::from_native_local(
+                CFRunLoop::default_mode(),
+                local.into_raw_fd(),
+                CFSocketCallBackType::DataCallBack,
+                {
+                    let received = received.clone();
+                    let closed = closed.clone();
+                    move |_socket, event| match event {
+                        CFSocketEvent::Data { data, .. } if data.is_empty() => closed.set(true),
+                        CFSocketEvent::Data { data, .. } => {
+                            received.borrow_mut().extend(data.to_vec());
+                        }
+                        event => panic!("unexpected event {event:?}"),
+                    }
+                },
+            )
+        }
+        .unwrap();
+
+        remote.write_all(b"hello").unwrap();
+        run_until(|| received.borrow().len() == 5);
+        assert_eq!(*received.borrow(), b"hello");
+
+        drop(remote);
+        run_until(|| closed.get());
+        drop(guard);
+    }
+}
@@ -0,0 +1,302 @@
+//! TLS configuration for `CFReadStream` and `CFWriteStream`.
+//!
+//! The `kCFStreamPropertySSLSettings` keys are defined by CFNetwork, and the
+//! peer trust by Security, so these are only available on Apple platforms.
+use core::ffi::c_void;
+use core::ptr;
+
+use crate::{
+    kCFBooleanFalse, kCFBooleanTrue, kCFNull, kCFStreamPropertySocketSecurityLevel,
+    kCFStreamSocketSecurityLevelNegotiatedSSL, kCFStreamSocketSecurityLevelNone,
+    kCFStreamSocketSecurityLevelSSLv2, kCFStreamSocketSecurityLevelSSLv3,
+    kCFStreamSocketSecurityLevelTLSv1, kCFTypeDictionaryKeyCallBacks,
+    kCFTypeDictionaryValueCallBacks, CFArray, CFDictionary, CFDictionaryCreate, CFIndex,
+    CFReadStream, CFReadStreamSetProperty, CFRetained, CFString, CFType, CFWriteStream,
+    CFWriteStreamSetProperty,
+};
+
+#[link(name = "CFNetwork", kind = "framework")]
+extern "C" {
+    static kCFStreamPropertySSLSettings: &'static CFString;
+    static kCFStreamSSLLevel: &'static CFString;
+    static kCFStreamSSLPeerName: &'static CFString;
+    static kCFStreamSSLValidatesCertificateChain: &'static CFString;
+    static kCFStreamSSLIsServer: &'static CFString;
+    static kCFStreamSSLCertificates: &'static CFString;
+    #[cfg(all(feature = "CFData", feature = "alloc"))]
+    static kCFStreamPropertySSLPeerTrust: &'static CFString;
+}
+
+#[cfg(all(feature = "CFData", feature = "alloc"))]
+#[link(name = "Security", kind = "framework")]
+extern "C-unwind" {
+    fn SecTrustGetCertificateCount(trust: &CFType) -> CFIndex;
+    fn SecTrustGetCertificateAtIndex(trust: &CFType, ix: CFIndex) -> Option<&CFType>;
+    fn SecCertificateCopyData(certificate: &CFType) -> Option<core::ptr::NonNull<crate::CFData>>;
+}
+
+/// The security protocol used by a stream.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CFStreamSecurityLevel {
+    /// No security.
+    #[doc(alias = "kCFStreamSocketSecurityLevelNone")]
+    None,
+    /// SSL version 2.
+    #[doc(alias = "kCFStreamSocketSecurityLevelSSLv2")]
+    SSLv2,
+    /// SSL version 3.
+    #[doc(alias = "kCFStreamSocketSecurityLevelSSLv3")]
+    SSLv3,
+    /// TLS version 1.
+    #[doc(alias = "kCFStreamSocketSecurityLevelTLSv1")]
+    TLSv1,
+    /// The highest protocol supported by both peers.
+    #[default]
+    #[doc(alias = "kCFStreamSocketSecurityLevelNegotiatedSSL")]
+    Negotiated,
+}
+
+impl CFStreamSecurityLevel {
+    fn as_cf_string(self) -> &'static CFString {
+        unsafe {
+            match self {
+                Self::None => kCFStreamSocketSecurityLevelNone,
+                Self::SSLv2 => kCFStreamSocketSecurityLevelSSLv2,
+                Self::SSLv3 => kCFStreamSocketSecurityLevelSSLv3,
+                Self::TLSv1 => kCFStreamSocketSecurityLevelTLSv1,
+                Self::Negotiated => kCFStreamSocketSecurityLevelNegotiatedSSL,
+            }
+        }
+    }
+}
+
+/// How the name of the peer is validated during the TLS handshake.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CFStreamPeerName<'a> {
+    /// Validate the peer's certificate against the given name, and send it
+    /// in the Server Name Indication extension.
+    Name(&'a CFString),
+    /// Do not validate the peer's name.
+    ///
+    /// This is equivalent to setting `kCFStreamSSLPeerName` to `kCFNull`.
+    Unchecked,
+}
+
+/// The TLS settings of a stream, i.e. the `kCFStreamPropertySSLSettings`
+/// dictionary.
+///
+/// Settings that are left as [`None`] use the system default.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use objc2_core_foundation::{
+///     CFStreamPeerName, CFStreamSecurityLevel, CFStreamTLSSettings, CFString,
+/// };
+/// # let stream: &objc2_core_foundation::CFReadStream = todo!();
+///
+/// let host = CFString::from_static_str("example.com");
+/// let settings = CFStreamTLSSettings::new()
+///     .level(CFStreamSecurityLevel::Negotiated)
+///     .peer_name(CFStreamPeerName::Name(&host));
+/// assert!(stream.set_tls_settings(&settings));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct CFStreamTLSSettings<'a> {
+    /// The allowed security protocol.
+    #[doc(alias = "kCFStreamSSLLevel")]
+    pub level: Option<CFStreamSecurityLevel>,
+    /// The name that the peer's certificate is validated against.
+    #[doc(alias = "kCFStreamSSLPeerName")]
+    pub peer_name: Option<CFStreamPeerName<'a>>,
+    /// Whether the peer's certificate chain is validated.
+    #[doc(alias = "kCFStreamSSLValidatesCertificateChain")]
+    pub validates_certificate_chain: Option<bool>,
+    /// Whether the stream acts as the server side of the connection.
+    #[doc(alias = "kCFStreamSSLIsServer")]
+    pub is_server: Option<bool>,
+    /// The certificates presented to the peer.
+    ///
+    /// The first element must be a `SecIdentityRef`, and any following
+    /// elements the `SecCertificateRef`s of the rest of the chain.
+    #[doc(alias = "kCFStreamSSLCertificates")]
+    pub certificates: Option<&'a CFArray>,
+}
+
+impl<'a> CFStreamTLSSettings<'a> {
+    /// Create settings where everything uses the system default.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the allowed security protocol.
+    pub fn level(mut self, level: CFStreamSecurityLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Set how the name of the peer is validated.
+    pub fn peer_name(mut self, peer_name: CFStreamPeerName<'a>) -> Self {
+        self.peer_name = Some(peer_name);
+        self
+    }
+
+    /// Set whether the peer's certificate chain is validated.
+    ///
+    /// Disabling this makes the connection vulnerable to
+    /// man-in-the-middle attacks, and should only be done if the chain is
+    /// validated manually afterwards, e.g. with
+    /// [`CFReadStream::peer_certificate_chain`].
+    pub fn validates_certificate_chain(mut self, validates: bool) -> Self {
+        self.validates_certificate_chain = Some(validates);
+        self
+    }
+
+    /// Set whether the stream acts as the server side of the connection.
+    pub fn is_server(mut self, is_server: bool) -> Self {
+        self.is_server = Some(is_server);
+        self
+    }
+
+    /// Set the identity and certificate chain presented to the peer.
+    ///
+    /// See [the field](Self::certificates) for the array's contents.
+    pub fn certificates(mut self, certificates: &'a CFArray) -> Self {
+        self.certificates = Some(certificates);
+        self
+    }
+
+    /// Convert the settings to the dictionary used with
+    /// `kCFStreamPropertySSLSettings`.
+    pub fn to_dictionary(&self) -> CFRetained<CFDictionary> {
+        let bool_value = |value: bool| -> &'static CFType {
+            if value {
+                unsafe { kCFBooleanTrue }.unwrap()
+            } else {
+                unsafe { kCFBooleanFalse }.unwrap()
+            }
+        };
+
+        let entries: [(&CFString, Option<&CFType>); 5] = unsafe {
+            [
+                (
+                    kCFStreamSSLLevel,
+                    self.level.map(|level| level.as_cf_string().as_ref()),
+                ),
+                (
+                    kCFStreamSSLPeerName,
+                    self.peer_name.map(|peer_name| match peer_name {
+                        CFStreamPeerName::Name(name) => name.as_ref(),
+                        CFStreamPeerName::Unchecked => kCFNull.unwrap().as_ref(),
+                    }),
+                ),
+                (
+                    kCFStreamSSLValidatesCertificateChain,
+                    self.validates_certificate_chain.map(bool_value),
+                ),
+                (kCFStreamSSLIsServer, self.is_server.map(bool_value)),
+                (
+                    kCFStreamSSLCertificates,
+                    self.certificates.map(|certificates| certificates.as_ref()),
+                ),
+            ]
+        };
+
+        let mut keys = [ptr::null::<c_void>(); 5];
+        let mut values = [ptr::null::<c_void>(); 5];
+        let mut len = 0;
+        for (key, value) in entries {
+            if let Some(value) = value {
+                keys[len] = (key as *const CFString).cast();
+                values[len] = (value as *const CFType).cast();
+                len += 1;
+            }
+        }
+
+        // SAFETY: The keys and values are valid CF objects, which the
+        // dictionary retains using the `CFType` callbacks.
+        unsafe {
+            CFDictionaryCreate(
+                None,
+                keys.as_mut_ptr(),
+                values.as_mut_ptr(),
+                len as CFIndex,
+                &kCFTypeDictionaryKeyCallBacks,
+                &kCFTypeDictionaryValueCallBacks,
+            )
+        }
+        .expect("failed creating CFDictionary")
+    }
+}
+
+/// Extract the DER-encoded certificates from a `SecTrustRef`.
+#[cfg(all(feature = "CFData", feature = "alloc"))]
+fn certificate_chain(trust: &CFType) -> alloc::vec::Vec<CFRetained<crate::CFData>> {
+    let count = unsafe { SecTrustGetCertificateCount(trust) };
+    (0..count)
+        .filter_map(|ix| unsafe { SecTrustGetCertificateAtIndex(trust, ix) })
+        .filter_map(|certificate| unsafe { SecCertificateCopyData(certificate) })
+        // SAFETY: `SecCertificateCopyData` follows the create rule.
+        .map(|data| unsafe { CFRetained::from_raw(data) })
+        .collect()
+}
+
+macro_rules! impl_tls {
+    ($ty:ident, $set_property:ident, $copy_property:ident) => {
+        impl $ty {
+            /// Configure TLS on the stream.
+            ///
+            /// This must be done before the stream is opened. Returns whether
+            /// the settings were accepted.
+            #[doc(alias = "kCFStreamPropertySSLSettings")]
+            pub fn set_tls_settings(&self, settings: &CFStreamTLSSettings<'_>) -> bool {
+                let dictionary = settings.to_dictionary();
+                unsafe {
+                    $set_property(self, Some(kCFStreamPropertySSLSettings), Some(&dictionary)) != 0
+                }
+            }
+
+            /// Set the security protocol of the stream, without any further
+            /// configuration.
+            #[doc(alias = "kCFStreamPropertySocketSecurityLevel")]
+            pub fn set_security_level(&self, level: CFStreamSecurityLevel) -> bool {
+                unsafe {
+                    $set_property(
+                        self,
+                        Some(kCFStreamPropertySocketSecurityLevel),
+                        Some(level.as_cf_string()),
+                    ) != 0
+                }
+            }
+
+            /// The DER-encoded certificates presented by the peer, starting
+            /// with the leaf certificate.
+            ///
+            /// Returns [`None`] if the TLS handshake has not completed yet,
+            /// e.g. because the stream has not yet been opened.
+            #[cfg(all(feature = "CFData", feature = "alloc"))]
+            #[doc(alias = "kCFStreamPropertySSLPeerTrust")]
+            pub fn peer_certificate_chain(
+                &self,
+            ) -> Option<alloc::vec::Vec<CFRetained<crate::CFData>>> {
+                let trust =
+                    unsafe { crate::$copy_property(self, Some(kCFStreamPropertySSLPeerTrust)) }?;
+                Some(certificate_chain(&trust))
+            }
+        }
+    };
+}
+
+impl_tls!(
+    CFReadStream,
+    CFReadStreamSetProperty,
+    CFReadStreamCopyProperty
+);
+impl_tls!(
+    CFWriteStream,
+    CFWriteStreamSetProperty,
+    CFWriteStreamCopyProperty
+);
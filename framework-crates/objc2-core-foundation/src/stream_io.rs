@@ -0,0 +1,498 @@
+//! Opening, reading from and writing to `CFReadStream` and `CFWriteStream`.
+//!
+//! The closures given to the stream clients are owned by Core Foundation,
+//! and the guard types remove the client and unschedule the stream when
+//! dropped.
+use alloc::boxed::Box;
+use core::ffi::c_void;
+use core::fmt;
+use core::ptr;
+
+use crate::{
+    CFError, CFIndex, CFReadStream, CFReadStreamClose, CFReadStreamCopyError,
+    CFReadStreamGetStatus, CFReadStreamHasBytesAvailable, CFReadStreamOpen, CFReadStreamRead,
+    CFReadStreamScheduleWithRunLoop, CFReadStreamSetClient, CFReadStreamUnscheduleFromRunLoop,
+    CFRetained, CFRunLoop, CFRunLoopMode, CFStreamClientContext, CFStreamCreateBoundPair,
+    CFStreamEventType, CFStreamStatus, CFWriteStream, CFWriteStreamCanAcceptBytes,
+    CFWriteStreamClose, CFWriteStreamCopyError, CFWriteStreamGetStatus, CFWriteStreamOpen,
+    CFWriteStreamScheduleWithRunLoop, CFWriteStreamSetClient, CFWriteStreamUnscheduleFromRunLoop,
+    CFWriteStreamWrite, Type,
+};
+
+/// Release the boxed closure stored in a context's `info`.
+unsafe extern "C-unwind" fn release<F>(info: *mut c_void) {
+    // SAFETY: The info was created with `Box::into_raw` when setting the
+    // client, and Core Foundation releases it exactly once.
+    drop(unsafe { Box::from_raw(info.cast::<F>()) });
+}
+
+macro_rules! impl_stream {
+    (
+        $stream:ident,
+        $guard:ident,
+        $open:ident,
+        $close:ident,
+        $status:ident,
+        $copy_error:ident,
+        $set_client:ident,
+        $schedule:ident,
+        $unschedule:ident $(,)?
+    ) => {
+        impl $stream {
+            /// Open the stream.
+            ///
+            /// Opening may complete in the background, in which case the
+            /// status is [`CFStreamStatus::Opening`] until it is done. Reading
+            /// or writing waits for the stream to be opened.
+            pub fn open(&self) -> Result<(), Option<CFRetained<CFError>>> {
+                if unsafe { $open(self) } != 0 {
+                    Ok(())
+                } else {
+                    Err(self.error())
+                }
+            }
+
+            /// Close the stream.
+            ///
+            /// Streams cannot be reopened after they have been closed.
+            pub fn close(&self) {
+                unsafe { $close(self) }
+            }
+
+            /// The current status of the stream.
+            pub fn status(&self) -> CFStreamStatus {
+                unsafe { $status(self) }
+            }
+
+            /// The error that occurred on the stream, if the status is
+            /// [`CFStreamStatus::Error`].
+            pub fn error(&self) -> Option<CFRetained<CFError>> {
+                unsafe { $copy_error(self) }
+            }
+
+            /// Schedule the stream on the run loop, and call the closure
+            /// there when any of the given events occur, while the run loop
+            /// is running in the given mode.
+            ///
+            /// This replaces any existing client of the stream.
+            pub fn set_client(
+                &self,
+                run_loop: &CFRunLoop,
+                mode: &CFRunLoopMode,
+                events: CFStreamEventType,
+                handler: impl Fn(&$stream, CFStreamEventType) + Send + 'static,
+            ) -> $guard {
+                // SAFETY: The closure is `Send`, so it is fine to call and
+                // drop it on the run loop's thread.
+                unsafe { self.set_client_unchecked(run_loop, mode, events, handler) }
+            }
+
+            /// Same as [`set_client`][Self::set_client], but the stream is
+            /// scheduled on the current thread's run loop, which allows the
+            /// closure to not be `Send`.
+            pub fn set_client_local(
+                &self,
+                mode: &CFRunLoopMode,
+                events: CFStreamEventType,
+                handler: impl Fn(&$stream, CFStreamEventType) + 'static,
+            ) -> $guard {
+                // SAFETY: The stream is scheduled on the current thread's
+                // run loop, and the guard is not `Send`, so the client is
+                // also removed from this thread.
+                unsafe { self.set_client_unchecked(&CFRunLoop::current(), mode, events, handler) }
+            }
+
+            /// # Safety
+            ///
+            /// The closure must be safe to call and drop on the run loop's
+            /// thread.
+            unsafe fn set_client_unchecked<F: Fn(&$stream, CFStreamEventType) + 'static>(
+                &self,
+                run_loop: &CFRunLoop,
+                mode: &CFRunLoopMode,
+                events: CFStreamEventType,
+                handler: F,
+            ) -> $guard {
+                unsafe extern "C-unwind" fn callout<F: Fn(&$stream, CFStreamEventType)>(
+                    stream: *mut $stream,
+                    event: CFStreamEventType,
+                    info: *mut c_void,
+                ) {
+                    // SAFETY: The info is the closure, which is alive for as
+                    // long as it is the client of the stream.
+                    let handler = unsafe { &*info.cast::<F>() };
+                    // SAFETY: The stream is valid for the duration of the
+                    // callout.
+                    handler(unsafe { &*stream }, event);
+                }
+
+                let info = Box::into_raw(Box::new(handler));
+                let mut context = CFStreamClientContext {
+                    version: 0,
+                    info: info.cast(),
+                    retain: None,
+                    release: Some(release::<F>),
+                    copyDescription: None,
+                };
+                // SAFETY: The callout and context are valid, and the context
+                // is copied by the stream.
+                let success =
+                    unsafe { $set_client(self, events.0, Some(callout::<F>), &mut context) };
+                if success == 0 {
+                    // SAFETY: The client was not set, so we still own the
+                    // info.
+                    drop(unsafe { Box::from_raw(info) });
+                    panic!(concat!(
+                        "failed setting the client of ",
+                        stringify!($stream)
+                    ));
+                }
+                unsafe { $schedule(self, Some(run_loop), Some(mode)) };
+                $guard {
+                    stream: self.retain(),
+                    run_loop: run_loop.retain(),
+                    mode: mode.retain(),
+                }
+            }
+        }
+
+        #[doc = concat!("The client of a [`", stringify!($stream), "`], which is removed when")]
+        /// dropped.
+        ///
+        #[doc = concat!("Created by [`", stringify!($stream), "::set_client`].")]
+        #[must_use = "the client is removed when this is dropped"]
+        pub struct $guard {
+            stream: CFRetained<$stream>,
+            run_loop: CFRetained<CFRunLoop>,
+            mode: CFRetained<CFRunLoopMode>,
+        }
+
+        impl $guard {
+            /// The underlying stream.
+            pub fn stream(&self) -> &$stream {
+                &self.stream
+            }
+        }
+
+        impl Drop for $guard {
+            fn drop(&mut self) {
+                unsafe {
+                    $unschedule(&self.stream, Some(&self.run_loop), Some(&self.mode));
+                    // Releases the closure.
+                    $set_client(&self.stream, 0, None, ptr::null_mut());
+                }
+            }
+        }
+
+        impl fmt::Debug for $guard {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($guard))
+                    .field("stream", &self.stream)
+                    .finish_non_exhaustive()
+            }
+        }
+    };
+}
+
+impl_stream!(
+    CFReadStream,
+    CFReadStreamClientGuard,
+    CFReadStreamOpen,
+    CFReadStreamClose,
+    CFReadStreamGetStatus,
+    CFReadStreamCopyError,
+    CFReadStreamSetClient,
+    CFReadStreamScheduleWithRunLoop,
+    CFReadStreamUnscheduleFromRunLoop,
+);
+impl_stream!(
+    CFWriteStream,
+    CFWriteStreamClientGuard,
+    CFWriteStreamOpen,
+    CFWriteStreamClose,
+    CFWriteStreamGetStatus,
+    CFWriteStreamCopyError,
+    CFWriteStreamSetClient,
+    CFWriteStreamScheduleWithRunLoop,
+    CFWriteStreamUnscheduleFromRunLoop,
+);
+
+fn buffer_len(len: usize) -> CFIndex {
+    // Reading or writing less than requested is always allowed.
+    len.min(CFIndex::MAX as usize) as CFIndex
+}
+
+impl CFReadStream {
+    /// Whether data can be read from the stream without blocking.
+    #[doc(alias = "CFReadStreamHasBytesAvailable")]
+    pub fn has_bytes_available(&self) -> bool {
+        unsafe { CFReadStreamHasBytesAvailable(self) != 0 }
+    }
+
+    /// Read data from the stream into the buffer, and return the number of
+    /// bytes read.
+    ///
+    /// This blocks until at least one byte is available. Returns `Ok(0)`
+    /// when the end of the stream has been reached.
+    #[doc(alias = "CFReadStreamRead")]
+    pub fn read(&self, buf: &mut [u8]) -> Result<usize, Option<CFRetained<CFError>>> {
+        let len = buffer_len(buf.len());
+        // SAFETY: The buffer is valid for writing `len` bytes.
+        let read = unsafe { CFReadStreamRead(self, buf.as_mut_ptr(), len) };
+        usize::try_from(read).map_err(|_| self.error())
+    }
+}
+
+impl CFWriteStream {
+    /// Whether data can be written to the stream without blocking.
+    #[doc(alias = "CFWriteStreamCanAcceptBytes")]
+    pub fn can_accept_bytes(&self) -> bool {
+        unsafe { CFWriteStreamCanAcceptBytes(self) != 0 }
+    }
+
+    /// Write data from the buffer to the stream, and return the number of
+    /// bytes written.
+    ///
+    /// This blocks until at least one byte can be written. Returns `Ok(0)`
+    /// if the stream is at its end and cannot accept more bytes, such as
+    /// when the stream writes to a fixed-size buffer.
+    #[doc(alias = "CFWriteStreamWrite")]
+    pub fn write(&self, buf: &[u8]) -> Result<usize, Option<CFRetained<CFError>>> {
+        let len = buffer_len(buf.len());
+        // SAFETY: The buffer is valid for reading `len` bytes.
+        let written = unsafe { CFWriteStreamWrite(self, buf.as_ptr(), len) };
+        usize::try_from(written).map_err(|_| self.error())
+    }
+}
+
+/// Take ownership of the streams created by one of the pair functions.
+fn stream_pair(
+    read_stream: *mut CFReadStream,
+    write_stream: *mut CFWriteStream,
+) -> (CFRetained<CFReadStream>, CFRetained<CFWriteStream>) {
+    let read_stream = ptr::NonNull::new(read_stream).expect("failed creating CFReadStream");
+    let write_stream = ptr::NonNull::new(write_stream).expect("failed creating CFWriteStream");
+    // SAFETY: The streams follow the create rule.
+    unsafe {
+        (
+            CFRetained::from_raw(read_stream),
+            CFRetained::from_raw(write_stream),
+        )
+    }
+}
+
+/// Create a pair of streams, where the data written to the write stream
+/// can be read from the read stream.
+///
+/// The streams use a buffer of the given size, which limits how much data
+/// can be written before it has been read.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2_core_foundation::bound_stream_pair;
+///
+/// let (read_stream, write_stream) = bound_stream_pair(1024);
+/// read_stream.open().unwrap();
+/// write_stream.open().unwrap();
+///
+/// write_stream.write(b"hello").unwrap();
+/// write_stream.close();
+///
+/// let mut buf = [0; 16];
+/// let n = read_stream.read(&mut buf).unwrap();
+/// assert_eq!(&buf[..n], b"hello");
+/// ```
+#[doc(alias = "CFStreamCreateBoundPair")]
+pub fn bound_stream_pair(
+    buffer_size: usize,
+) -> (CFRetained<CFReadStream>, CFRetained<CFWriteStream>) {
+    let buffer_size = buffer_size.try_into().expect("buffer size too large");
+    let mut read_stream = ptr::null_mut();
+    let mut write_stream = ptr::null_mut();
+    // SAFETY: The out pointers are valid for writing.
+    unsafe { CFStreamCreateBoundPair(None, &mut read_stream, &mut write_stream, buffer_size) };
+    stream_pair(read_stream, write_stream)
+}
+
+/// Create a pair of streams for reading from and writing to a connected
+/// native socket.
+///
+/// The socket is not closed when the streams are closed, unless
+/// `kCFStreamPropertyShouldCloseNativeSocket` is set on them.
+///
+///
+/// # Safety
+///
+/// The handle must be a connected socket, which must stay open until both
+/// streams are closed.
+#[cfg(feature = "CFSocket")]
+#[doc(alias = "CFStreamCreatePairWithSocket")]
+pub unsafe fn socket_stream_pair(
+    handle: crate::CFSocketNativeHandle,
+) -> (CFRetained<CFReadStream>, CFRetained<CFWriteStream>) {
+    let mut read_stream = ptr::null_mut();
+    let mut write_stream = ptr::null_mut();
+    // SAFETY: The out pointers are valid for writing, and the caller
+    // upholds the safety requirements of the handle.
+    #[allow(deprecated)]
+    unsafe {
+        crate::CFStreamCreatePairWithSocket(None, handle, &mut read_stream, &mut write_stream)
+    };
+    stream_pair(read_stream, write_stream)
+}
+
+/// Create a pair of streams connected to the given TCP port on the host.
+///
+/// The host may be a host name or an IP address. The connection is made
+/// when either stream is opened, and any errors connecting are reported
+/// through the streams.
+#[cfg(feature = "CFString")]
+#[doc(alias = "CFStreamCreatePairWithSocketToHost")]
+pub fn host_stream_pair(
+    host: &str,
+    port: u16,
+) -> (CFRetained<CFReadStream>, CFRetained<CFWriteStream>) {
+    let host = crate::CFString::from_str(host);
+    let mut read_stream = ptr::null_mut();
+    let mut write_stream = ptr::null_mut();
+    // SAFETY: The out pointers are valid for writing.
+    #[allow(deprecated)]
+    unsafe {
+        crate::CFStreamCreatePairWithSocketToHost(
+            None,
+            Some(&host),
+            port.into(),
+            &mut read_stream,
+            &mut write_stream,
+        )
+    };
+    stream_pair(read_stream, write_stream)
+}
+
+#[cfg(all(feature = "CFString", feature = "std"))]
+mod io {
+    use alloc::string::ToString;
+    use std::io;
+
+    use crate::{
+        kCFErrorDomainPOSIX, CFError, CFErrorGetCode, CFErrorGetDomain, CFReadStream, CFRetained,
+        CFStreamStatus, CFWriteStream,
+    };
+
+    fn io_error(error: Option<CFRetained<CFError>>) -> io::Error {
+        let Some(error) = error else {
+            return io::Error::new(io::ErrorKind::Other, "stream operation failed");
+        };
+        let domain = unsafe { CFErrorGetDomain(&error) };
+        if let (Some(domain), Some(posix)) = (domain, unsafe { kCFErrorDomainPOSIX }) {
+            if *domain == *posix {
+                let code = unsafe { CFErrorGetCode(&error) };
+                if let Ok(code) = code.try_into() {
+                    return io::Error::from_raw_os_error(code);
+                }
+            }
+        }
+        io::Error::new(io::ErrorKind::Other, error.to_string())
+    }
+
+    /// Read from the stream with [`std::io`].
+    ///
+    /// The stream is opened if it has not been opened yet.
+    impl io::Read for &CFReadStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.status() == CFStreamStatus::NotOpen {
+                self.open().map_err(io_error)?;
+            }
+            CFReadStream::read(self, buf).map_err(io_error)
+        }
+    }
+
+    /// Write to the stream with [`std::io`].
+    ///
+    /// The stream is opened if it has not been opened yet.
+    impl io::Write for &CFWriteStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.status() == CFStreamStatus::NotOpen {
+                self.open().map_err(io_error)?;
+            }
+            CFWriteStream::write(self, buf).map_err(io_error)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            // Writes are passed on immediately.
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "CFDate", feature = "CFString", feature = "std"))]
+mod tests {
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+    use core::time::Duration;
+    use super::*;
+
+    #[test]
+    fn io_roundtrip() {
+        use std::io::{Read, Write};
+
+        let (read_stream, write_stream) = bound_stream_pair(1024);
+
+        let mut writer = &*write_stream;
+        writer.write_all(&[42; 100]).unwrap();
+        write_stream.close();
+
+        let mut data = Vec::new();
+        let mut reader = &*read_stream;
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, [42; 100]);
+        assert_eq!(read_stream.status(), CFStreamStatus::AtEnd);
+    }
+
+    #[test]
+    fn client() {
+        let (read_stream, write_stream) = bound_stream_pair(1024);
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let guard = read_stream.set_client_local(
+            CFRunLoop::default_mode(),
+            CFStreamEventType::OpenCompleted
+                | CFStreamEventType::HasBytesAvailable
+                | CFStreamEventType::EndEncountered,
+            {
+                let events = events.clone();
+                let received = received.clone();
+                move |stream, event| {
+                    events.borrow_mut().push(event);
+                    if event == CFStreamEventType::HasBytesAvailable {
+                        let mut buf = [0; 16];
+                        let n = stream.read(&mut buf).unwrap();
+                        received.borrow_mut().extend_from_slice(&buf[..n]);
+                    }
+                }
+            },
+        );
+        read_stream.open().unwrap();
+        write_stream.open().unwrap();
+        write_stream.write(b"hello").unwrap();
+        write_stream.close();
+
+        for _ in 0..50 {
+            if events.borrow().contains(&CFStreamEventType::EndEncountered) {
+                break;
+            }
+            CFRunLoop::run_in_mode(CFRunLoop::default_mode(), Duration::from_millis(100), true);
+        }
+
+        assert_eq!(events.borrow()[0], CFStreamEventType::OpenCompleted);
+        assert!(events.borrow().contains(&CFStreamEventType::EndEncountered));
+        assert_eq!(*received.borrow(), b"hello");
+        drop(guard);
+        read_stream.close();
+    }
+}
@@ -1,4 +1,8 @@
 #![cfg(feature = "CFBase")]
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::string::ToString;
 use core::cmp::Ordering;
 use core::ffi::{c_char, CStr};
 use core::fmt::Write;
@@ -89,7 +93,8 @@ impl CFString {
     /// internally, and can thus mutate the string inside there.
     #[doc(alias = "CFStringGetCStringPtr")]
     // NOTE: This is NOT public, since it's completely broken for differently
-    // encoded strings, see the `as_str_broken` test below.
+    // encoded strings, see the `as_str_broken` test below. Use `as_str`
+    // instead, which guards against that.
     #[allow(dead_code)]
     unsafe fn as_str_unchecked(&self) -> Option<&str> {
         let bytes = unsafe { CFStringGetCStringPtr(self, CFStringEncoding::UTF8) };
@@ -109,6 +114,53 @@ impl CFString {
             unsafe { debug_checked_utf8_unchecked(cstr.to_bytes()) }
         })
     }
+
+    /// Get the [`str`](`prim@str`) representation of this string, avoiding
+    /// a copy when possible.
+    ///
+    /// If the string is stored internally as ASCII (which is usually the
+    /// case for short strings created from Rust or from C string literals),
+    /// this returns a slice pointing directly into that storage. Otherwise,
+    /// the string is converted to UTF-8 and copied into a new [`String`].
+    ///
+    /// Unpaired UTF-16 surrogates are replaced with the replacement
+    /// character, like in the [`Display`](fmt::Display) implementation.
+    ///
+    /// [`String`]: alloc::string::String
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The `CFString` must not be mutated for the lifetime of the returned
+    /// string. Use `CFString::to_string` instead if this is difficult to
+    /// uphold.
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_core_foundation::CFString;
+    ///
+    /// let string = CFString::from_str("foo");
+    /// // SAFETY: The string is not mutated while the `str` is alive.
+    /// assert_eq!(unsafe { string.as_str() }, "foo");
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[doc(alias = "CFStringGetCStringPtr")]
+    pub unsafe fn as_str(&self) -> Cow<'_, str> {
+        // SAFETY: Upheld by the caller.
+        if let Some(s) = unsafe { self.as_str_unchecked() } {
+            // `CFStringGetCStringPtr` ignores the requested encoding for some
+            // strings (see the `as_str_broken` test), so only trust it if
+            // the result is ASCII with the same length as the string, in
+            // which case the UTF-8 and UTF-16 representations correspond.
+            let len = unsafe { CFStringGetLength(self) };
+            if s.is_ascii() && s.len() as isize == len {
+                return Cow::Borrowed(s);
+            }
+        }
+        Cow::Owned(self.to_string())
+    }
 }
 
 impl fmt::Display for CFString {
@@ -194,6 +246,7 @@ impl Ord for CFString {
 
 #[cfg(test)]
 mod tests {
+    use alloc::borrow::Cow;
     use alloc::string::ToString;
 
     use super::*;
@@ -315,6 +368,20 @@ mod tests {
         // But `CFStringGetCStringPtr` completely ignores the UTF-8 conversion
         // we asked it to do, i.e. a huge correctness footgun!
         assert_eq!(unsafe { s.as_str_unchecked() }, Some("e&"));
+
+        // `as_str` detects this, and falls back to copying.
+        assert_eq!(unsafe { s.as_str() }, "♥");
+    }
+
+    #[test]
+    fn as_str() {
+        let s = CFString::from_str("abc");
+        assert_eq!(unsafe { s.as_str() }, "abc");
+        let s = CFString::from_str("a♥😀");
+        assert_eq!(unsafe { s.as_str() }, "a♥😀");
+        assert!(matches!(unsafe { s.as_str() }, Cow::Owned(_)));
+        let s = CFString::from_static_str("");
+        assert_eq!(unsafe { s.as_str() }, "");
     }
 
     #[test]
@@ -0,0 +1,269 @@
+#![cfg(all(feature = "CFBase", feature = "CFError", feature = "CFString"))]
+#[cfg(all(feature = "std", unix))]
+use std::path::{Path, PathBuf};
+
+use crate::{CFError, CFRetained, CFString, CFType, ConcreteType, CFURL};
+
+fn error_from_raw(error: *mut CFError) -> CFRetained<CFError> {
+    let error = core::ptr::NonNull::new(error).expect("CFURL function failed without an error");
+    // SAFETY: The error is returned with +1 retain count (it follows the
+    // create rule).
+    unsafe { CFRetained::from_raw(error) }
+}
+
+/// Conversion to and from file system paths.
+#[cfg(all(feature = "std", unix))]
+impl CFURL {
+    /// Create a file URL from a file system path.
+    ///
+    /// Relative paths are resolved against the current working directory.
+    /// If `is_directory` is `true`, the URL is marked as referring to a
+    /// directory (and will have a trailing slash).
+    ///
+    /// The path does not need to be valid UTF-8.
+    ///
+    /// Returns [`None`] if the path contains a NUL byte.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use objc2_core_foundation::CFURL;
+    ///
+    /// let url = CFURL::from_path(Path::new("/tmp/hello world.txt"), false).unwrap();
+    /// assert_eq!(url.to_path_buf().unwrap(), Path::new("/tmp/hello world.txt"));
+    /// ```
+    #[doc(alias = "CFURLCreateFromFileSystemRepresentation")]
+    pub fn from_path(path: &Path, is_directory: bool) -> Option<CFRetained<Self>> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let bytes = path.as_os_str().as_bytes();
+        if bytes.contains(&0) {
+            return None;
+        }
+        let len = bytes.len().try_into().ok()?;
+        // SAFETY: The buffer is valid for `len` bytes, and the URL copies it.
+        unsafe {
+            crate::CFURLCreateFromFileSystemRepresentation(
+                None,
+                bytes.as_ptr(),
+                len,
+                is_directory as crate::Boolean,
+            )
+        }
+    }
+
+    /// Get the file system path that the URL refers to.
+    ///
+    /// File reference URLs (`file:///.file/id=...`) are resolved to the
+    /// current path of the file they refer to, and percent-encoding is
+    /// decoded.
+    ///
+    /// Returns [`None`] if this is not a file URL, or if it is a file
+    /// reference URL to a file that no longer exists.
+    #[doc(alias = "CFURLGetFileSystemRepresentation")]
+    #[doc(alias = "CFURLCreateFilePathURL")]
+    pub fn to_path_buf(&self) -> Option<PathBuf> {
+        use std::ffi::{CStr, OsStr};
+        use std::os::unix::ffi::OsStrExt;
+        use std::string::ToString;
+
+        let scheme = unsafe { crate::CFURLCopyScheme(self) }?;
+        if !scheme.to_string().eq_ignore_ascii_case("file") {
+            return None;
+        }
+
+        // Resolves file reference URLs, and copies file path URLs.
+        let url =
+            unsafe { crate::CFURLCreateFilePathURL(None, Some(self), core::ptr::null_mut()) }?;
+
+        // `PATH_MAX` on Apple platforms, though paths may be longer than
+        // that, in which case we retry with a larger buffer.
+        let mut buf = std::vec![0u8; 1024];
+        loop {
+            let len = buf.len().try_into().ok()?;
+            // SAFETY: The buffer is valid for writing `len` bytes.
+            let success = unsafe {
+                crate::CFURLGetFileSystemRepresentation(
+                    &url,
+                    true as crate::Boolean,
+                    buf.as_mut_ptr(),
+                    len,
+                )
+            };
+            if success != 0 {
+                let path = CStr::from_bytes_until_nul(&buf).ok()?;
+                return Some(PathBuf::from(OsStr::from_bytes(path.to_bytes())));
+            }
+            // Since this is a file path URL, the only reason for failure is
+            // that the buffer was too small.
+            if buf.len() >= 1024 * 1024 {
+                return None;
+            }
+            buf.resize(buf.len() * 4, 0);
+        }
+    }
+}
+
+/// Resource properties.
+impl CFURL {
+    /// Get the value of a resource property of the file that the URL refers
+    /// to.
+    ///
+    /// `key` is one of the `kCFURL*Key` constants, such as
+    /// [`kCFURLLocalizedNameKey`][crate::kCFURLLocalizedNameKey].
+    ///
+    /// Returns `Ok(None)` if the property is not available for the file.
+    #[doc(alias = "CFURLCopyResourcePropertyForKey")]
+    pub fn resource_value(
+        &self,
+        key: &CFString,
+    ) -> Result<Option<CFRetained<CFType>>, CFRetained<CFError>> {
+        let mut value: *mut CFType = core::ptr::null_mut();
+        let mut error = core::ptr::null_mut();
+        // SAFETY: `value` is a valid location to write a `CFTypeRef`, and
+        // `error` is a valid location to write a `CFErrorRef`.
+        let success = unsafe {
+            crate::CFURLCopyResourcePropertyForKey(
+                self,
+                Some(key),
+                (&mut value as *mut *mut CFType).cast(),
+                &mut error,
+            )
+        };
+        if success != 0 {
+            // SAFETY: The value follows the create rule.
+            Ok(core::ptr::NonNull::new(value).map(|value| unsafe { CFRetained::from_raw(value) }))
+        } else {
+            Err(error_from_raw(error))
+        }
+    }
+
+    /// Get the value of a resource property, and check that it has the
+    /// expected type.
+    ///
+    /// Returns `Ok(None)` if the property is not available for the file, or
+    /// if it is of a different type.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "CFNumber", feature = "std"))] {
+    /// use objc2_core_foundation::{kCFURLIsDirectoryKey, CFBoolean, CFURL};
+    ///
+    /// let url = CFURL::from_path(&std::env::temp_dir(), true).unwrap();
+    /// let key = unsafe { kCFURLIsDirectoryKey }.unwrap();
+    /// let is_directory = url.resource_value_as::<CFBoolean>(key).unwrap().unwrap();
+    /// assert!(is_directory.as_bool());
+    /// # }
+    /// ```
+    pub fn resource_value_as<T: ConcreteType>(
+        &self,
+        key: &CFString,
+    ) -> Result<Option<CFRetained<T>>, CFRetained<CFError>> {
+        Ok(self
+            .resource_value(key)?
+            .and_then(|value| value.downcast().ok()))
+    }
+
+    /// Whether the URL refers to a directory.
+    ///
+    /// Returns an error if the file does not exist.
+    #[cfg(feature = "CFNumber")]
+    #[doc(alias = "kCFURLIsDirectoryKey")]
+    pub fn is_directory(&self) -> Result<bool, CFRetained<CFError>> {
+        let key = unsafe { crate::kCFURLIsDirectoryKey }.unwrap();
+        let value = self.resource_value_as::<crate::CFBoolean>(key)?;
+        Ok(value.is_some_and(|value| value.as_bool()))
+    }
+
+    /// The size of the file in bytes.
+    ///
+    /// Returns `Ok(None)` for directories, which do not have a size.
+    #[cfg(feature = "CFNumber")]
+    #[doc(alias = "kCFURLFileSizeKey")]
+    pub fn file_size(&self) -> Result<Option<u64>, CFRetained<CFError>> {
+        let key = unsafe { crate::kCFURLFileSizeKey }.unwrap();
+        let value = self.resource_value_as::<crate::CFNumber>(key)?;
+        Ok(value
+            .and_then(|value| value.as_i64())
+            .and_then(|size| size.try_into().ok()))
+    }
+
+    /// Whether the file that the URL refers to exists and can be accessed.
+    #[doc(alias = "CFURLResourceIsReachable")]
+    pub fn is_reachable(&self) -> bool {
+        let mut error = core::ptr::null_mut();
+        // SAFETY: `error` is a valid location to write a `CFErrorRef`.
+        let reachable = unsafe { crate::CFURLResourceIsReachable(self, &mut error) } != 0;
+        if !error.is_null() {
+            // Release the error, we only care about whether it failed.
+            let _ = error_from_raw(error);
+        }
+        reachable
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "CFError",
+    feature = "CFNumber",
+    feature = "CFString",
+    feature = "std",
+    unix
+))]
+mod tests {
+    use alloc::format;
+    use alloc::string::ToString;
+    use std::path::Path;
+    use std::{fs, process};
+
+    use super::*;
+
+    #[test]
+    fn path_roundtrip() {
+        let path = Path::new("/tmp/a b/ø.txt");
+        let url = CFURL::from_path(path, false).unwrap();
+        assert_eq!(url.to_path_buf().unwrap(), path);
+
+        assert!(CFURL::from_path(Path::new("/tmp/\0"), false).is_none());
+
+        let string = CFString::from_str("https://example.com/foo");
+        let url = unsafe { crate::CFURLCreateWithString(None, Some(&string), None) }.unwrap();
+        assert_eq!(url.to_path_buf(), None);
+    }
+
+    #[test]
+    fn resource_values() {
+        let dir = std::env::temp_dir().join(format!("objc2-cfurl-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("file.txt");
+        fs::write(&file, b"hello").unwrap();
+
+        let dir_url = CFURL::from_path(&dir, true).unwrap();
+        assert!(dir_url.is_reachable());
+        assert!(dir_url.is_directory().unwrap());
+
+        let file_url = CFURL::from_path(&file, false).unwrap();
+        assert!(!file_url.is_directory().unwrap());
+        assert_eq!(file_url.file_size().unwrap(), Some(5));
+
+        let key = unsafe { crate::kCFURLNameKey }.unwrap();
+        let name = file_url
+            .resource_value_as::<CFString>(key)
+            .unwrap()
+            .unwrap();
+        assert_eq!(name.to_string(), "file.txt");
+        assert!(file_url
+            .resource_value_as::<crate::CFNumber>(key)
+            .unwrap()
+            .is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!file_url.is_reachable());
+        let _error = file_url.is_directory().unwrap_err();
+    }
+}
@@ -0,0 +1,614 @@
+//! Validating bitmap parameters before handing them to CoreGraphics.
+use core::fmt;
+
+use crate::{
+    CGBitmapInfo, CGColorSpace, CGColorSpaceGetModel, CGColorSpaceGetNumberOfComponents,
+    CGColorSpaceModel, CGImageAlphaInfo, CGImagePixelFormat,
+};
+
+/// The dimensions and pixel layout of a bitmap.
+///
+/// `CGBitmapContextCreate` and `CGImageCreate` only support certain
+/// combinations of these parameters, and when given an unsupported
+/// combination they return `NULL` and log a (rather cryptic) message to the
+/// console. This type can check the parameters up front with
+/// [`validate_context`] and [`validate_image`], and report what exactly was
+/// wrong.
+///
+/// [`validate_context`]: Self::validate_context
+/// [`validate_image`]: Self::validate_image
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2_core_graphics::{
+///     CGBitmapInfo, CGBitmapLayout, CGBitmapLayoutError, CGColorSpaceCreateDeviceRGB,
+///     CGImageAlphaInfo,
+/// };
+///
+/// let space = unsafe { CGColorSpaceCreateDeviceRGB() }.unwrap();
+///
+/// // 8-bit RGBA with straight alpha can be used for images...
+/// let bitmap_info = CGBitmapInfo(CGImageAlphaInfo::Last.0);
+/// let layout = CGBitmapLayout::new(64, 64, 8, 32, bitmap_info).unwrap();
+/// assert_eq!(layout.bytes_per_row, 256);
+/// assert_eq!(layout.validate_image(&space), Ok(()));
+///
+/// // ...but not for bitmap contexts, which require premultiplied alpha.
+/// assert!(matches!(
+///     layout.validate_context(Some(&space)),
+///     Err(CGBitmapLayoutError::UnsupportedContextFormat { .. }),
+/// ));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CGBitmapLayout {
+    /// The width of the bitmap, in pixels.
+    pub width: usize,
+    /// The height of the bitmap, in pixels.
+    pub height: usize,
+    /// The number of bits used by each color (and alpha) component.
+    pub bits_per_component: usize,
+    /// The number of bits used by each pixel, including any unused bits.
+    pub bits_per_pixel: usize,
+    /// The number of bytes used by each row, including any padding.
+    pub bytes_per_row: usize,
+    /// The alpha info, byte order and whether the components are
+    /// floating-point.
+    pub bitmap_info: CGBitmapInfo,
+}
+
+/// An error describing why a [`CGBitmapLayout`] is not supported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CGBitmapLayoutError {
+    /// The width or height was zero.
+    Empty,
+    /// The size of a row or of the whole bitmap does not fit in a `usize`.
+    Overflow,
+    /// The number of bits per component is not one of 1, 2, 4, 5, 8, 10, 16
+    /// or 32.
+    InvalidBitsPerComponent(usize),
+    /// The alpha info is not one of the known `CGImageAlphaInfo` values.
+    InvalidAlphaInfo(CGImageAlphaInfo),
+    /// Floating-point components were requested with a size other than 16
+    /// or 32 bits.
+    InvalidFloatComponents {
+        /// The number of bits per component.
+        bits_per_component: usize,
+    },
+    /// A 16-bit or 32-bit byte order was requested, but neither the
+    /// components nor the pixels have that size.
+    InvalidByteOrder {
+        /// The requested byte order.
+        byte_order: CGBitmapInfo,
+        /// The number of bits per component.
+        bits_per_component: usize,
+        /// The number of bits per pixel.
+        bits_per_pixel: usize,
+    },
+    /// The pixels are too small to hold the components of the color space
+    /// and the alpha channel.
+    BitsPerPixelTooSmall {
+        /// The number of bits per pixel.
+        bits_per_pixel: usize,
+        /// The minimum number of bits per pixel.
+        required: usize,
+    },
+    /// The rows are too small to hold `width` pixels.
+    BytesPerRowTooSmall {
+        /// The number of bytes per row.
+        bytes_per_row: usize,
+        /// The minimum number of bytes per row.
+        required: usize,
+    },
+    /// The buffer is too small to hold `height` rows.
+    BufferTooSmall {
+        /// The length of the buffer.
+        len: usize,
+        /// The minimum length of the buffer.
+        required: usize,
+    },
+    /// Bitmaps cannot use the color space.
+    UnsupportedColorSpace(CGColorSpaceModel),
+    /// Bitmap contexts do not support the combination of color space and
+    /// pixel format.
+    ///
+    /// See the "Supported Pixel Formats" table in the [Quartz 2D Programming
+    /// Guide](https://developer.apple.com/library/archive/documentation/GraphicsImaging/Conceptual/drawingwithquartz2d/dq_context/dq_context.html#//apple_ref/doc/uid/TP30001066-CH203-BCIBHHBB)
+    /// for the supported combinations.
+    UnsupportedContextFormat {
+        /// The model of the color space, or [`None`] for alpha-only
+        /// contexts.
+        model: Option<CGColorSpaceModel>,
+        /// The number of bits per component.
+        bits_per_component: usize,
+        /// The number of bits per pixel.
+        bits_per_pixel: usize,
+        /// The alpha info.
+        alpha_info: CGImageAlphaInfo,
+        /// Whether the components are floating-point.
+        float_components: bool,
+    },
+    /// CoreGraphics failed to create the context or image, even though the
+    /// layout was valid.
+    CreationFailed,
+}
+
+fn alpha_info_name(alpha_info: CGImageAlphaInfo) -> &'static str {
+    match alpha_info {
+        CGImageAlphaInfo::None => "kCGImageAlphaNone",
+        CGImageAlphaInfo::PremultipliedLast => "kCGImageAlphaPremultipliedLast",
+        CGImageAlphaInfo::PremultipliedFirst => "kCGImageAlphaPremultipliedFirst",
+        CGImageAlphaInfo::Last => "kCGImageAlphaLast",
+        CGImageAlphaInfo::First => "kCGImageAlphaFirst",
+        CGImageAlphaInfo::NoneSkipLast => "kCGImageAlphaNoneSkipLast",
+        CGImageAlphaInfo::NoneSkipFirst => "kCGImageAlphaNoneSkipFirst",
+        CGImageAlphaInfo::Only => "kCGImageAlphaOnly",
+        _ => "unknown alpha info",
+    }
+}
+
+fn model_name(model: Option<CGColorSpaceModel>) -> &'static str {
+    match model {
+        None => "no color space",
+        Some(CGColorSpaceModel::Monochrome) => "a gray color space",
+        Some(CGColorSpaceModel::RGB) => "an RGB color space",
+        Some(CGColorSpaceModel::CMYK) => "a CMYK color space",
+        Some(CGColorSpaceModel::Lab) => "a Lab color space",
+        Some(CGColorSpaceModel::DeviceN) => "a DeviceN color space",
+        Some(CGColorSpaceModel::Indexed) => "an indexed color space",
+        Some(CGColorSpaceModel::Pattern) => "a pattern color space",
+        Some(CGColorSpaceModel::XYZ) => "an XYZ color space",
+        Some(_) => "an unknown color space",
+    }
+}
+
+impl fmt::Display for CGBitmapLayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Empty => write!(f, "bitmap width and height must be non-zero"),
+            Self::Overflow => write!(f, "bitmap size overflows `usize`"),
+            Self::InvalidBitsPerComponent(bits) => write!(
+                f,
+                "{bits} bits per component is not supported, must be 1, 2, 4, 5, 8, 10, 16 or 32"
+            ),
+            Self::InvalidAlphaInfo(alpha_info) => {
+                write!(f, "invalid alpha info {}", alpha_info.0)
+            }
+            Self::InvalidFloatComponents { bits_per_component } => write!(
+                f,
+                "floating-point components must be 16 or 32 bits, not {bits_per_component}"
+            ),
+            Self::InvalidByteOrder {
+                byte_order,
+                bits_per_component,
+                bits_per_pixel,
+            } => {
+                let size = if byte_order == CGBitmapInfo::ByteOrder16Little
+                    || byte_order == CGBitmapInfo::ByteOrder16Big
+                {
+                    16
+                } else {
+                    32
+                };
+                write!(
+                    f,
+                    "{size}-bit byte order requires {size} bits per component or per pixel, \
+                     but got {bits_per_component} bits per component and {bits_per_pixel} bits per pixel"
+                )
+            }
+            Self::BitsPerPixelTooSmall {
+                bits_per_pixel,
+                required,
+            } => write!(
+                f,
+                "{bits_per_pixel} bits per pixel is too small for the color space and alpha, \
+                 at least {required} are required"
+            ),
+            Self::BytesPerRowTooSmall {
+                bytes_per_row,
+                required,
+            } => write!(
+                f,
+                "{bytes_per_row} bytes per row is too small for the width, \
+                 at least {required} are required"
+            ),
+            Self::BufferTooSmall { len, required } => write!(
+                f,
+                "buffer of {len} bytes is too small for the bitmap, \
+                 at least {required} are required"
+            ),
+            Self::UnsupportedColorSpace(model) => {
+                write!(f, "bitmaps cannot use {}", model_name(Some(model)))
+            }
+            Self::UnsupportedContextFormat {
+                model,
+                bits_per_component,
+                bits_per_pixel,
+                alpha_info,
+                float_components,
+            } => write!(
+                f,
+                "bitmap contexts do not support {} with {bits_per_component} bits per component, \
+                 {bits_per_pixel} bits per pixel and {}{}",
+                model_name(model),
+                alpha_info_name(alpha_info),
+                if float_components {
+                    " with floating-point components"
+                } else {
+                    ""
+                },
+            ),
+            Self::CreationFailed => write!(f, "failed creating bitmap"),
+        }
+    }
+}
+
+#[cfg(feature = "std")] // use core::error::Error from Rust 1.81 once in MSRV.
+impl std::error::Error for CGBitmapLayoutError {}
+
+/// Whether `CGBitmapContextCreate` supports the given pixel format.
+///
+/// Based on the "Supported Pixel Formats" table in the Quartz 2D
+/// Programming Guide.
+fn is_supported_by_context(
+    model: Option<CGColorSpaceModel>,
+    bits_per_component: usize,
+    bits_per_pixel: usize,
+    alpha_info: CGImageAlphaInfo,
+    float_components: bool,
+) -> bool {
+    use CGImageAlphaInfo as Alpha;
+
+    match (model, bits_per_component, bits_per_pixel, float_components) {
+        (None, 8, 8, false) => alpha_info == Alpha::Only,
+        (Some(CGColorSpaceModel::Monochrome), 8, 8, false) => {
+            matches!(alpha_info, Alpha::None | Alpha::Only)
+        }
+        (Some(CGColorSpaceModel::Monochrome), 16, 16, false)
+        | (Some(CGColorSpaceModel::Monochrome), 32, 32, true) => alpha_info == Alpha::None,
+        (Some(CGColorSpaceModel::RGB), 5, 16, false) => alpha_info == Alpha::NoneSkipFirst,
+        (Some(CGColorSpaceModel::RGB), 8, 32, false) => matches!(
+            alpha_info,
+            Alpha::NoneSkipFirst
+                | Alpha::NoneSkipLast
+                | Alpha::PremultipliedFirst
+                | Alpha::PremultipliedLast
+        ),
+        (Some(CGColorSpaceModel::RGB), 16, 64, _)
+        | (Some(CGColorSpaceModel::RGB), 32, 128, true) => {
+            matches!(alpha_info, Alpha::NoneSkipLast | Alpha::PremultipliedLast)
+        }
+        (Some(CGColorSpaceModel::CMYK), 8, 32, false)
+        | (Some(CGColorSpaceModel::CMYK), 16, 64, false)
+        | (Some(CGColorSpaceModel::CMYK), 32, 128, true) => alpha_info == Alpha::None,
+        _ => false,
+    }
+}
+
+impl CGBitmapLayout {
+    /// Create a layout with tightly packed rows.
+    ///
+    /// Use [`with_bytes_per_row`](Self::with_bytes_per_row) to add padding
+    /// to each row.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CGBitmapLayoutError::Overflow`] if the size of a row does
+    /// not fit in a `usize`.
+    pub fn new(
+        width: usize,
+        height: usize,
+        bits_per_component: usize,
+        bits_per_pixel: usize,
+        bitmap_info: CGBitmapInfo,
+    ) -> Result<Self, CGBitmapLayoutError> {
+        let mut layout = Self {
+            width,
+            height,
+            bits_per_component,
+            bits_per_pixel,
+            bytes_per_row: 0,
+            bitmap_info,
+        };
+        layout.bytes_per_row = layout.min_bytes_per_row()?;
+        Ok(layout)
+    }
+
+    /// Set the number of bytes per row.
+    ///
+    /// CoreGraphics is generally faster when this is a multiple of 16 or
+    /// 64.
+    #[must_use]
+    pub fn with_bytes_per_row(self, bytes_per_row: usize) -> Self {
+        Self {
+            bytes_per_row,
+            ..self
+        }
+    }
+
+    /// The alpha info part of the bitmap info.
+    pub fn alpha_info(&self) -> CGImageAlphaInfo {
+        CGImageAlphaInfo((self.bitmap_info & CGBitmapInfo::AlphaInfoMask).0)
+    }
+
+    /// The byte order part of the bitmap info.
+    pub fn byte_order(&self) -> CGBitmapInfo {
+        self.bitmap_info & CGBitmapInfo::ByteOrderMask
+    }
+
+    /// Whether the components are floating-point.
+    pub fn has_float_components(&self) -> bool {
+        self.bitmap_info.contains(CGBitmapInfo::FloatComponents)
+    }
+
+    /// The minimum number of bytes needed to store a row of `width` pixels.
+    pub fn min_bytes_per_row(&self) -> Result<usize, CGBitmapLayoutError> {
+        let bits = self
+            .width
+            .checked_mul(self.bits_per_pixel)
+            .ok_or(CGBitmapLayoutError::Overflow)?;
+        Ok(bits / 8 + (bits % 8 != 0) as usize)
+    }
+
+    /// The number of bytes needed to store the whole bitmap.
+    pub fn buffer_len(&self) -> Result<usize, CGBitmapLayoutError> {
+        self.bytes_per_row
+            .checked_mul(self.height)
+            .ok_or(CGBitmapLayoutError::Overflow)
+    }
+
+    /// Check the parts of the layout that do not depend on the color space.
+    pub(crate) fn validate_common(&self) -> Result<(), CGBitmapLayoutError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(CGBitmapLayoutError::Empty);
+        }
+
+        if !matches!(self.bits_per_component, 1 | 2 | 4 | 5 | 8 | 10 | 16 | 32) {
+            return Err(CGBitmapLayoutError::InvalidBitsPerComponent(
+                self.bits_per_component,
+            ));
+        }
+
+        let alpha_info = self.alpha_info();
+        if alpha_info.0 > CGImageAlphaInfo::Only.0 {
+            return Err(CGBitmapLayoutError::InvalidAlphaInfo(alpha_info));
+        }
+
+        if self.has_float_components() && !matches!(self.bits_per_component, 16 | 32) {
+            return Err(CGBitmapLayoutError::InvalidFloatComponents {
+                bits_per_component: self.bits_per_component,
+            });
+        }
+
+        let byte_order = self.byte_order();
+        let byte_order_size = if byte_order == CGBitmapInfo::ByteOrderDefault {
+            None
+        } else if byte_order == CGBitmapInfo::ByteOrder16Little
+            || byte_order == CGBitmapInfo::ByteOrder16Big
+        {
+            Some(16)
+        } else {
+            Some(32)
+        };
+        if let Some(size) = byte_order_size {
+            if self.bits_per_component != size && self.bits_per_pixel != size {
+                return Err(CGBitmapLayoutError::InvalidByteOrder {
+                    byte_order,
+                    bits_per_component: self.bits_per_component,
+                    bits_per_pixel: self.bits_per_pixel,
+                });
+            }
+        }
+
+        let required = self.min_bytes_per_row()?;
+        if self.bytes_per_row < required {
+            return Err(CGBitmapLayoutError::BytesPerRowTooSmall {
+                bytes_per_row: self.bytes_per_row,
+                required,
+            });
+        }
+        self.buffer_len()?;
+
+        Ok(())
+    }
+
+    /// Check that pixels are large enough for the given number of color
+    /// components (plus alpha).
+    fn validate_components(&self, color_components: usize) -> Result<(), CGBitmapLayoutError> {
+        let components = match self.alpha_info() {
+            CGImageAlphaInfo::Only => 1,
+            // Skipped components may be smaller than the others, e.g. in
+            // 16-bit RGB with 5 bits per component.
+            CGImageAlphaInfo::None
+            | CGImageAlphaInfo::NoneSkipFirst
+            | CGImageAlphaInfo::NoneSkipLast => color_components,
+            _ => color_components + 1,
+        };
+        let required = components * self.bits_per_component;
+        if self.bits_per_pixel < required {
+            return Err(CGBitmapLayoutError::BitsPerPixelTooSmall {
+                bits_per_pixel: self.bits_per_pixel,
+                required,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that the layout can be used with `CGImageCreate` and the given
+    /// color space.
+    #[doc(alias = "CGImageCreate")]
+    pub fn validate_image(&self, space: &CGColorSpace) -> Result<(), CGBitmapLayoutError> {
+        self.validate_common()?;
+
+        let model = unsafe { CGColorSpaceGetModel(Some(space)) };
+        if model == CGColorSpaceModel::Pattern || model == CGColorSpaceModel::Unknown {
+            return Err(CGBitmapLayoutError::UnsupportedColorSpace(model));
+        }
+
+        self.validate_components(unsafe { CGColorSpaceGetNumberOfComponents(Some(space)) })
+    }
+
+    /// Check that the layout can be used with `CGBitmapContextCreate` and
+    /// the given color space (or [`None`] for alpha-only contexts).
+    ///
+    /// Bitmap contexts support far fewer pixel formats than images. Notably,
+    /// they do not support straight (non-premultiplied) alpha.
+    #[doc(alias = "CGBitmapContextCreate")]
+    pub fn validate_context(
+        &self,
+        space: Option<&CGColorSpace>,
+    ) -> Result<(), CGBitmapLayoutError> {
+        self.validate_common()?;
+
+        let model = space.map(|space| unsafe { CGColorSpaceGetModel(Some(space)) });
+        if !is_supported_by_context(
+            model,
+            self.bits_per_component,
+            self.bits_per_pixel,
+            self.alpha_info(),
+            self.has_float_components(),
+        ) {
+            return Err(CGBitmapLayoutError::UnsupportedContextFormat {
+                model,
+                bits_per_component: self.bits_per_component,
+                bits_per_pixel: self.bits_per_pixel,
+                alpha_info: self.alpha_info(),
+                float_components: self.has_float_components(),
+            });
+        }
+
+        match space {
+            Some(space) => {
+                self.validate_components(unsafe { CGColorSpaceGetNumberOfComponents(Some(space)) })
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Create a bitmap context whose memory is managed by CoreGraphics.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layout is not supported by bitmap contexts,
+    /// see [`validate_context`](Self::validate_context).
+    #[cfg(all(feature = "CGBitmapContext", feature = "CGContext"))]
+    #[doc(alias = "CGBitmapContextCreate")]
+    pub fn create_context(
+        &self,
+        space: Option<&CGColorSpace>,
+    ) -> Result<objc2_core_foundation::CFRetained<crate::CGContext>, CGBitmapLayoutError> {
+        // SAFETY: Passing NULL makes CoreGraphics allocate the memory.
+        unsafe { self.create_context_with_data(core::ptr::null_mut(), space) }
+    }
+
+    /// Create a bitmap context that draws into the given memory.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layout is not supported by bitmap contexts,
+    /// see [`validate_context`](Self::validate_context).
+    ///
+    ///
+    /// # Safety
+    ///
+    /// `data` must either be NULL, or be valid for reads and writes of
+    /// [`buffer_len`](Self::buffer_len) bytes for as long as the context is
+    /// alive.
+    #[cfg(all(feature = "CGBitmapContext", feature = "CGContext"))]
+    #[doc(alias = "CGBitmapContextCreate")]
+    pub unsafe fn create_context_with_data(
+        &self,
+        data: *mut core::ffi::c_void,
+        space: Option<&CGColorSpace>,
+    ) -> Result<objc2_core_foundation::CFRetained<crate::CGContext>, CGBitmapLayoutError> {
+        self.validate_context(space)?;
+        unsafe {
+            crate::CGBitmapContextCreate(
+                data,
+                self.width,
+                self.height,
+                self.bits_per_component,
+                self.bytes_per_row,
+                space,
+                self.bitmap_info.0,
+            )
+        }
+        .ok_or(CGBitmapLayoutError::CreationFailed)
+    }
+
+    /// Create an image from a copy of the given pixel data.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layout is not supported by images (see
+    /// [`validate_image`](Self::validate_image)), or if `data` is shorter
+    /// than [`buffer_len`](Self::buffer_len).
+    #[cfg(feature = "CGDataProvider")]
+    #[doc(alias = "CGImageCreate")]
+    pub fn create_image(
+        &self,
+        space: &CGColorSpace,
+        data: &[u8],
+    ) -> Result<objc2_core_foundation::CFRetained<crate::CGImage>, CGBitmapLayoutError> {
+        self.validate_image(space)?;
+        let required = self.buffer_len()?;
+        if data.len() < required {
+            return Err(CGBitmapLayoutError::BufferTooSmall {
+                len: data.len(),
+                required,
+            });
+        }
+
+        let data = objc2_core_foundation::CFData::from_bytes(data);
+        let provider = unsafe { crate::CGDataProviderCreateWithCFData(Some(&data)) }
+            .ok_or(CGBitmapLayoutError::CreationFailed)?;
+        unsafe {
+            crate::CGImageCreate(
+                self.width,
+                self.height,
+                self.bits_per_component,
+                self.bits_per_pixel,
+                self.bytes_per_row,
+                Some(space),
+                self.bitmap_info,
+                Some(&provider),
+                core::ptr::null(),
+                false,
+                crate::CGColorRenderingIntent::RenderingIntentDefault,
+            )
+        }
+        .ok_or(CGBitmapLayoutError::CreationFailed)
+    }
+}
+
+impl CGImagePixelFormat {
+    /// The tightly packed layout of a bitmap with this format.
+    ///
+    /// Note that formats with straight alpha are only supported by images,
+    /// not by bitmap contexts.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CGBitmapLayoutError::Overflow`] if the size of a row does
+    /// not fit in a `usize`.
+    pub fn layout(
+        self,
+        width: usize,
+        height: usize,
+    ) -> Result<CGBitmapLayout, CGBitmapLayoutError> {
+        CGBitmapLayout::new(
+            width,
+            height,
+            self.bits_per_component(),
+            self.bits_per_component() * self.components_per_pixel(),
+            self.bitmap_info(),
+        )
+    }
+}
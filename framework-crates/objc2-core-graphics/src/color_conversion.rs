@@ -0,0 +1,289 @@
+//! Converting pixel buffers between color spaces.
+use core::fmt;
+use core::ptr::NonNull;
+
+use objc2_core_foundation::{CFDictionary, CFRetained};
+
+use crate::{
+    CGBitmapLayout, CGBitmapLayoutError, CGColorBufferFormat, CGColorConversionInfo,
+    CGColorConversionInfoConvertData, CGColorConversionInfoCreate,
+    CGColorConversionInfoCreateWithOptions, CGColorSpace, CGColorSpaceIsHDR,
+    CGColorSpaceUsesExtendedRange, CGColorSpaceUsesITUR_2100TF, CGImage, CGImageGetContentHeadroom,
+};
+
+/// An error that occurred while converting a pixel buffer between color
+/// spaces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CGColorConversionError {
+    /// The source or destination layout is invalid, or its buffer is too
+    /// small.
+    Layout(CGBitmapLayoutError),
+    /// The source and destination have different dimensions.
+    DimensionMismatch {
+        /// The width and height of the source.
+        src: (usize, usize),
+        /// The width and height of the destination.
+        dst: (usize, usize),
+    },
+    /// CoreGraphics failed to convert the data, e.g. because it does not
+    /// support one of the pixel formats.
+    ConversionFailed,
+}
+
+impl fmt::Display for CGColorConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Layout(error) => write!(f, "invalid bitmap layout: {error}"),
+            Self::DimensionMismatch { src, dst } => write!(
+                f,
+                "source is {}x{} pixels, but destination is {}x{} pixels",
+                src.0, src.1, dst.0, dst.1
+            ),
+            Self::ConversionFailed => write!(f, "failed converting pixel data"),
+        }
+    }
+}
+
+#[cfg(feature = "std")] // use core::error::Error from Rust 1.81 once in MSRV.
+impl std::error::Error for CGColorConversionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Layout(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<CGBitmapLayoutError> for CGColorConversionError {
+    fn from(error: CGBitmapLayoutError) -> Self {
+        Self::Layout(error)
+    }
+}
+
+impl CGBitmapLayout {
+    /// The layout as a buffer format for [`CGColorConversionInfo::convert`].
+    fn buffer_format(&self) -> CGColorBufferFormat {
+        CGColorBufferFormat {
+            version: 0,
+            bitmapInfo: self.bitmap_info,
+            bitsPerComponent: self.bits_per_component,
+            bitsPerPixel: self.bits_per_pixel,
+            bytesPerRow: self.bytes_per_row,
+        }
+    }
+
+    /// Check that a buffer of `len` bytes can hold a bitmap with this
+    /// layout.
+    fn validate_buffer(&self, len: usize) -> Result<(), CGBitmapLayoutError> {
+        let required = self.buffer_len()?;
+        if len < required {
+            return Err(CGBitmapLayoutError::BufferTooSmall { len, required });
+        }
+        Ok(())
+    }
+}
+
+impl CGColorConversionInfo {
+    /// Create a conversion between two color spaces.
+    ///
+    /// Returns [`None`] if CoreGraphics does not support converting between
+    /// the color spaces, e.g. if one of them is a pattern color space.
+    #[doc(alias = "CGColorConversionInfoCreate")]
+    pub fn new(src: &CGColorSpace, dst: &CGColorSpace) -> Option<CFRetained<Self>> {
+        unsafe { CGColorConversionInfoCreate(Some(src), Some(dst)) }
+    }
+
+    /// Create a conversion between two color spaces with the given options,
+    /// such as [`kCGColorConversionBlackPointCompensation`].
+    ///
+    /// [`kCGColorConversionBlackPointCompensation`]: crate::kCGColorConversionBlackPointCompensation
+    ///
+    ///
+    /// # Safety
+    ///
+    /// `options` must be a dictionary of valid conversion options.
+    #[doc(alias = "CGColorConversionInfoCreateWithOptions")]
+    pub unsafe fn with_options(
+        src: &CGColorSpace,
+        dst: &CGColorSpace,
+        options: &CFDictionary,
+    ) -> Option<CFRetained<Self>> {
+        unsafe { CGColorConversionInfoCreateWithOptions(src, dst, Some(options)) }
+    }
+
+    /// Create a conversion from HDR content with `source_headroom` to a
+    /// color space that can display up to `target_headroom`, tone mapping
+    /// the content with the given method.
+    ///
+    /// The headrooms are the ratio of the brightest representable value to
+    /// SDR reference white, i.e. `1.0` means no headroom.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns the error reported by CoreGraphics if the conversion could
+    /// not be created.
+    #[cfg(feature = "CGToneMapping")]
+    #[doc(alias = "CGColorConversionInfoCreateForToneMapping")]
+    pub fn for_tone_mapping(
+        src: &CGColorSpace,
+        source_headroom: f32,
+        dst: &CGColorSpace,
+        target_headroom: f32,
+        method: crate::CGToneMapping,
+    ) -> Result<CFRetained<Self>, Option<CFRetained<objc2_core_foundation::CFError>>> {
+        let mut error = core::ptr::null_mut();
+        let info = unsafe {
+            crate::CGColorConversionInfoCreateForToneMapping(
+                src,
+                source_headroom,
+                dst,
+                target_headroom,
+                method,
+                None,
+                &mut error,
+            )
+        };
+        // SAFETY: The error is NULL or a +1 error, as per the "Create" rule.
+        let error = NonNull::new(error).map(|error| unsafe { CFRetained::from_raw(error) });
+        info.ok_or(error)
+    }
+
+    /// Convert the pixels in `src` and write them to `dst`.
+    ///
+    /// The source and destination may use different pixel formats, e.g. to
+    /// convert 8-bit sRGB data to half-float extended linear Display P3,
+    /// but must have the same dimensions.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layouts are invalid, if the buffers are too
+    /// small for them, if their dimensions differ, or if CoreGraphics failed
+    /// converting the data.
+    ///
+    ///
+    /// # Example
+    ///
+    /// Convert a pixel from sRGB to Display P3.
+    ///
+    /// ```
+    /// use objc2_core_graphics::{
+    ///     kCGColorSpaceDisplayP3, kCGColorSpaceSRGB, CGColorConversionInfo,
+    ///     CGColorSpaceCreateWithName, CGImagePixelFormat,
+    /// };
+    ///
+    /// let srgb = unsafe { CGColorSpaceCreateWithName(Some(kCGColorSpaceSRGB)) }.unwrap();
+    /// let p3 = unsafe { CGColorSpaceCreateWithName(Some(kCGColorSpaceDisplayP3)) }.unwrap();
+    /// let info = CGColorConversionInfo::new(&srgb, &p3).unwrap();
+    ///
+    /// let layout = CGImagePixelFormat::Rgba8Premultiplied.layout(1, 1).unwrap();
+    /// let src = [255, 0, 0, 255];
+    /// let mut dst = [0; 4];
+    /// info.convert(&src, &layout, &mut dst, &layout).unwrap();
+    ///
+    /// // Pure sRGB red is inside the P3 gamut, so it is less saturated there.
+    /// assert!(dst[0] < 255 && dst[1] > 0);
+    /// assert_eq!(dst[3], 255);
+    /// ```
+    #[doc(alias = "CGColorConversionInfoConvertData")]
+    pub fn convert(
+        &self,
+        src: &[u8],
+        src_layout: &CGBitmapLayout,
+        dst: &mut [u8],
+        dst_layout: &CGBitmapLayout,
+    ) -> Result<(), CGColorConversionError> {
+        if (src_layout.width, src_layout.height) != (dst_layout.width, dst_layout.height) {
+            return Err(CGColorConversionError::DimensionMismatch {
+                src: (src_layout.width, src_layout.height),
+                dst: (dst_layout.width, dst_layout.height),
+            });
+        }
+        src_layout.validate_common()?;
+        dst_layout.validate_common()?;
+        src_layout.validate_buffer(src.len())?;
+        dst_layout.validate_buffer(dst.len())?;
+
+        // SAFETY: The buffers are large enough for their layouts, and
+        // CoreGraphics only reads from the source.
+        let success = unsafe {
+            CGColorConversionInfoConvertData(
+                self,
+                src_layout.width,
+                src_layout.height,
+                NonNull::new(dst.as_mut_ptr()).unwrap().cast(),
+                dst_layout.buffer_format(),
+                NonNull::new(src.as_ptr().cast_mut()).unwrap().cast(),
+                src_layout.buffer_format(),
+                None,
+            )
+        };
+        if success {
+            Ok(())
+        } else {
+            Err(CGColorConversionError::ConversionFailed)
+        }
+    }
+}
+
+/// HDR queries.
+impl CGColorSpace {
+    /// Whether the color space is an HDR color space, i.e. uses the PQ or
+    /// HLG transfer function, or has extended range.
+    #[doc(alias = "CGColorSpaceIsHDR")]
+    pub fn is_hdr(&self) -> bool {
+        unsafe { CGColorSpaceIsHDR(self) }
+    }
+
+    /// Whether the color space uses one of the ITU-R BT.2100 transfer
+    /// functions (PQ or HLG).
+    #[doc(alias = "CGColorSpaceUsesITUR_2100TF")]
+    pub fn uses_itur_2100_tf(&self) -> bool {
+        unsafe { CGColorSpaceUsesITUR_2100TF(self) }
+    }
+
+    /// Whether the color space allows component values outside of `0.0` to
+    /// `1.0`, such as `kCGColorSpaceExtendedSRGB`.
+    #[doc(alias = "CGColorSpaceUsesExtendedRange")]
+    pub fn uses_extended_range(&self) -> bool {
+        unsafe { CGColorSpaceUsesExtendedRange(self) }
+    }
+}
+
+/// HDR queries.
+impl CGImage {
+    /// The ratio of the brightest value in the image to SDR reference
+    /// white.
+    ///
+    /// This is `1.0` for SDR images, and for HDR images without headroom
+    /// metadata it defaults to [`kCGDefaultHDRImageContentHeadroom`].
+    ///
+    /// [`kCGDefaultHDRImageContentHeadroom`]: crate::kCGDefaultHDRImageContentHeadroom
+    #[doc(alias = "CGImageGetContentHeadroom")]
+    pub fn content_headroom(&self) -> f32 {
+        unsafe { CGImageGetContentHeadroom(Some(self)) }
+    }
+}
+
+/// EDR (extended dynamic range) rendering.
+#[cfg(feature = "CGContext")]
+impl crate::CGContext {
+    /// The headroom above SDR reference white that content drawn into the
+    /// context is tone mapped to.
+    #[doc(alias = "CGContextGetEDRTargetHeadroom")]
+    pub fn edr_target_headroom(&self) -> f32 {
+        unsafe { crate::CGContextGetEDRTargetHeadroom(self) }
+    }
+
+    /// Set the headroom that HDR content drawn into the context is tone
+    /// mapped to.
+    ///
+    /// Returns `false` if the context does not support EDR, or if the
+    /// headroom is invalid (less than `1.0`).
+    #[doc(alias = "CGContextSetEDRTargetHeadroom")]
+    pub fn set_edr_target_headroom(&self, headroom: f32) -> bool {
+        unsafe { crate::CGContextSetEDRTargetHeadroom(self, headroom) }
+    }
+}
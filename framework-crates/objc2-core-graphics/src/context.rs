@@ -0,0 +1,700 @@
+//! Safe drawing methods on `CGContext`.
+use core::ops::Deref;
+use core::ptr::NonNull;
+
+use objc2_core_foundation::{CGAffineTransform, CGFloat, CGPoint, CGRect, CGSize};
+
+use crate::{
+    CGBlendMode, CGContext, CGContextAddArc, CGContextAddCurveToPoint, CGContextAddEllipseInRect,
+    CGContextAddLineToPoint, CGContextAddLines, CGContextAddQuadCurveToPoint, CGContextAddRect,
+    CGContextAddRects, CGContextBeginPath, CGContextBeginTransparencyLayer,
+    CGContextBeginTransparencyLayerWithRect, CGContextClearRect, CGContextClip,
+    CGContextClipToRect, CGContextClipToRects, CGContextClosePath, CGContextConcatCTM,
+    CGContextDrawPath, CGContextEOClip, CGContextEOFillPath, CGContextEndTransparencyLayer,
+    CGContextFillEllipseInRect, CGContextFillPath, CGContextFillRect, CGContextFillRects,
+    CGContextFlush, CGContextGetCTM, CGContextGetClipBoundingBox, CGContextGetPathBoundingBox,
+    CGContextGetPathCurrentPoint, CGContextGetTextPosition, CGContextIsPathEmpty,
+    CGContextMoveToPoint, CGContextRestoreGState, CGContextRotateCTM, CGContextSaveGState,
+    CGContextScaleCTM, CGContextSetAlpha, CGContextSetBlendMode, CGContextSetCharacterSpacing,
+    CGContextSetFontSize, CGContextSetGrayFillColor, CGContextSetGrayStrokeColor,
+    CGContextSetLineDash, CGContextSetLineWidth, CGContextSetRGBFillColor,
+    CGContextSetRGBStrokeColor, CGContextSetShadow, CGContextSetShouldAntialias,
+    CGContextSetTextDrawingMode, CGContextSetTextPosition, CGContextStrokeEllipseInRect,
+    CGContextStrokeLineSegments, CGContextStrokePath, CGContextStrokeRect,
+    CGContextStrokeRectWithWidth, CGContextTranslateCTM, CGPathDrawingMode, CGTextDrawingMode,
+};
+
+/// Restores the graphics state of a context when dropped.
+///
+/// Created with [`CGContext::save_state`].
+#[derive(Debug)]
+#[must_use = "the graphics state is restored when the guard is dropped"]
+pub struct CGContextStateGuard<'a> {
+    context: &'a CGContext,
+}
+
+impl Deref for CGContextStateGuard<'_> {
+    type Target = CGContext;
+
+    #[inline]
+    fn deref(&self) -> &CGContext {
+        self.context
+    }
+}
+
+impl Drop for CGContextStateGuard<'_> {
+    #[inline]
+    #[doc(alias = "CGContextRestoreGState")]
+    fn drop(&mut self) {
+        unsafe { CGContextRestoreGState(Some(self.context)) }
+    }
+}
+
+/// Ends a transparency layer of a context when dropped, compositing its
+/// contents onto the context.
+///
+/// Created with [`CGContext::transparency_layer`].
+#[derive(Debug)]
+#[must_use = "the transparency layer is ended when the guard is dropped"]
+pub struct CGContextTransparencyLayerGuard<'a> {
+    context: &'a CGContext,
+}
+
+impl Deref for CGContextTransparencyLayerGuard<'_> {
+    type Target = CGContext;
+
+    #[inline]
+    fn deref(&self) -> &CGContext {
+        self.context
+    }
+}
+
+impl Drop for CGContextTransparencyLayerGuard<'_> {
+    #[inline]
+    #[doc(alias = "CGContextEndTransparencyLayer")]
+    fn drop(&mut self) {
+        unsafe { CGContextEndTransparencyLayer(Some(self.context)) }
+    }
+}
+
+/// Graphics state.
+impl CGContext {
+    /// Save the graphics state, and restore it when the returned guard is
+    /// dropped.
+    ///
+    /// Changes made through the guard (which dereferences to the context),
+    /// such as transformations, colors and clipping, are undone afterwards.
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+    /// use objc2_core_graphics::CGContext;
+    ///
+    /// fn draw_badge(context: &CGContext) {
+    ///     {
+    ///         let context = context.save_state();
+    ///         context.translate(10.0, 10.0);
+    ///         context.set_fill_rgb(1.0, 0.0, 0.0, 1.0);
+    ///         context.fill_rect(CGRect::new(CGPoint::ZERO, CGSize::new(4.0, 4.0)));
+    ///     }
+    ///     // The translation and fill color are restored here.
+    /// }
+    /// ```
+    #[inline]
+    #[doc(alias = "CGContextSaveGState")]
+    pub fn save_state(&self) -> CGContextStateGuard<'_> {
+        unsafe { CGContextSaveGState(Some(self)) };
+        CGContextStateGuard { context: self }
+    }
+
+    /// Begin a transparency layer, and end it when the returned guard is
+    /// dropped.
+    ///
+    /// Everything drawn into the layer is composited onto the context as a
+    /// whole, e.g. so that a shadow or the global alpha applies to a group
+    /// of shapes instead of to each shape individually.
+    #[inline]
+    #[doc(alias = "CGContextBeginTransparencyLayer")]
+    pub fn transparency_layer(&self) -> CGContextTransparencyLayerGuard<'_> {
+        unsafe { CGContextBeginTransparencyLayer(Some(self), None) };
+        CGContextTransparencyLayerGuard { context: self }
+    }
+
+    /// Same as [`transparency_layer`](Self::transparency_layer), but only
+    /// allocates the layer for the given rectangle.
+    #[inline]
+    #[doc(alias = "CGContextBeginTransparencyLayerWithRect")]
+    pub fn transparency_layer_in_rect(&self, rect: CGRect) -> CGContextTransparencyLayerGuard<'_> {
+        unsafe { CGContextBeginTransparencyLayerWithRect(Some(self), rect, None) };
+        CGContextTransparencyLayerGuard { context: self }
+    }
+
+    /// Translate the user coordinate system.
+    #[inline]
+    #[doc(alias = "CGContextTranslateCTM")]
+    pub fn translate(&self, tx: CGFloat, ty: CGFloat) {
+        unsafe { CGContextTranslateCTM(Some(self), tx, ty) }
+    }
+
+    /// Scale the user coordinate system.
+    #[inline]
+    #[doc(alias = "CGContextScaleCTM")]
+    pub fn scale(&self, sx: CGFloat, sy: CGFloat) {
+        unsafe { CGContextScaleCTM(Some(self), sx, sy) }
+    }
+
+    /// Rotate the user coordinate system by the given angle, in radians.
+    #[inline]
+    #[doc(alias = "CGContextRotateCTM")]
+    pub fn rotate(&self, angle: CGFloat) {
+        unsafe { CGContextRotateCTM(Some(self), angle) }
+    }
+
+    /// Apply the transformation to the user coordinate system.
+    #[inline]
+    #[doc(alias = "CGContextConcatCTM")]
+    pub fn concat_transform(&self, transform: CGAffineTransform) {
+        unsafe { CGContextConcatCTM(Some(self), transform) }
+    }
+
+    /// The current transformation matrix.
+    #[inline]
+    #[doc(alias = "CGContextGetCTM")]
+    pub fn transform(&self) -> CGAffineTransform {
+        unsafe { CGContextGetCTM(Some(self)) }
+    }
+
+    /// Set the width of stroked lines.
+    #[inline]
+    #[doc(alias = "CGContextSetLineWidth")]
+    pub fn set_line_width(&self, width: CGFloat) {
+        unsafe { CGContextSetLineWidth(Some(self), width) }
+    }
+
+    /// Set how the ends of stroked lines are drawn.
+    #[inline]
+    #[cfg(feature = "CGPath")]
+    #[doc(alias = "CGContextSetLineCap")]
+    pub fn set_line_cap(&self, cap: crate::CGLineCap) {
+        unsafe { crate::CGContextSetLineCap(Some(self), cap) }
+    }
+
+    /// Set how the joins between segments of stroked lines are drawn.
+    #[inline]
+    #[cfg(feature = "CGPath")]
+    #[doc(alias = "CGContextSetLineJoin")]
+    pub fn set_line_join(&self, join: crate::CGLineJoin) {
+        unsafe { crate::CGContextSetLineJoin(Some(self), join) }
+    }
+
+    /// Set the dash pattern of stroked lines, as alternating lengths of
+    /// painted and unpainted segments, starting `phase` into the pattern.
+    ///
+    /// An empty slice turns dashing off.
+    #[inline]
+    #[doc(alias = "CGContextSetLineDash")]
+    pub fn set_line_dash(&self, phase: CGFloat, lengths: &[CGFloat]) {
+        unsafe { CGContextSetLineDash(Some(self), phase, lengths.as_ptr(), lengths.len()) }
+    }
+
+    /// Set the opacity that everything is drawn with.
+    #[inline]
+    #[doc(alias = "CGContextSetAlpha")]
+    pub fn set_alpha(&self, alpha: CGFloat) {
+        unsafe { CGContextSetAlpha(Some(self), alpha) }
+    }
+
+    /// Set how drawing is composited onto the existing contents.
+    #[inline]
+    #[doc(alias = "CGContextSetBlendMode")]
+    pub fn set_blend_mode(&self, mode: CGBlendMode) {
+        unsafe { CGContextSetBlendMode(Some(self), mode) }
+    }
+
+    /// Set whether shapes and text are anti-aliased.
+    #[inline]
+    #[doc(alias = "CGContextSetShouldAntialias")]
+    pub fn set_should_antialias(&self, should_antialias: bool) {
+        unsafe { CGContextSetShouldAntialias(Some(self), should_antialias) }
+    }
+
+    /// Draw a shadow below everything that is drawn, in the default shadow
+    /// color (black with 1/3 alpha).
+    #[inline]
+    #[doc(alias = "CGContextSetShadow")]
+    pub fn set_shadow(&self, offset: CGSize, blur: CGFloat) {
+        unsafe { CGContextSetShadow(Some(self), offset, blur) }
+    }
+}
+
+/// Colors.
+impl CGContext {
+    /// Set the fill color to the given color in the device RGB color space.
+    #[inline]
+    #[doc(alias = "CGContextSetRGBFillColor")]
+    pub fn set_fill_rgb(&self, red: CGFloat, green: CGFloat, blue: CGFloat, alpha: CGFloat) {
+        unsafe { CGContextSetRGBFillColor(Some(self), red, green, blue, alpha) }
+    }
+
+    /// Set the stroke color to the given color in the device RGB color
+    /// space.
+    #[inline]
+    #[doc(alias = "CGContextSetRGBStrokeColor")]
+    pub fn set_stroke_rgb(&self, red: CGFloat, green: CGFloat, blue: CGFloat, alpha: CGFloat) {
+        unsafe { CGContextSetRGBStrokeColor(Some(self), red, green, blue, alpha) }
+    }
+
+    /// Set the fill color to the given color in the device gray color
+    /// space.
+    #[inline]
+    #[doc(alias = "CGContextSetGrayFillColor")]
+    pub fn set_fill_gray(&self, gray: CGFloat, alpha: CGFloat) {
+        unsafe { CGContextSetGrayFillColor(Some(self), gray, alpha) }
+    }
+
+    /// Set the stroke color to the given color in the device gray color
+    /// space.
+    #[inline]
+    #[doc(alias = "CGContextSetGrayStrokeColor")]
+    pub fn set_stroke_gray(&self, gray: CGFloat, alpha: CGFloat) {
+        unsafe { CGContextSetGrayStrokeColor(Some(self), gray, alpha) }
+    }
+
+    /// Set the fill color.
+    #[inline]
+    #[cfg(feature = "CGColor")]
+    #[doc(alias = "CGContextSetFillColorWithColor")]
+    pub fn set_fill_color(&self, color: &crate::CGColor) {
+        unsafe { crate::CGContextSetFillColorWithColor(Some(self), Some(color)) }
+    }
+
+    /// Set the stroke color.
+    #[inline]
+    #[cfg(feature = "CGColor")]
+    #[doc(alias = "CGContextSetStrokeColorWithColor")]
+    pub fn set_stroke_color(&self, color: &crate::CGColor) {
+        unsafe { crate::CGContextSetStrokeColorWithColor(Some(self), Some(color)) }
+    }
+}
+
+/// Paths.
+impl CGContext {
+    /// Discard the current path, and start a new one.
+    #[inline]
+    #[doc(alias = "CGContextBeginPath")]
+    pub fn begin_path(&self) {
+        unsafe { CGContextBeginPath(Some(self)) }
+    }
+
+    /// Start a new subpath at the given point.
+    #[inline]
+    #[doc(alias = "CGContextMoveToPoint")]
+    pub fn move_to(&self, point: CGPoint) {
+        unsafe { CGContextMoveToPoint(Some(self), point.x, point.y) }
+    }
+
+    /// Add a straight line from the current point to the given point.
+    #[inline]
+    #[doc(alias = "CGContextAddLineToPoint")]
+    pub fn line_to(&self, point: CGPoint) {
+        unsafe { CGContextAddLineToPoint(Some(self), point.x, point.y) }
+    }
+
+    /// Add a cubic Bézier curve from the current point to `end`.
+    #[inline]
+    #[doc(alias = "CGContextAddCurveToPoint")]
+    pub fn curve_to(&self, control1: CGPoint, control2: CGPoint, end: CGPoint) {
+        unsafe {
+            CGContextAddCurveToPoint(
+                Some(self),
+                control1.x,
+                control1.y,
+                control2.x,
+                control2.y,
+                end.x,
+                end.y,
+            )
+        }
+    }
+
+    /// Add a quadratic Bézier curve from the current point to `end`.
+    #[inline]
+    #[doc(alias = "CGContextAddQuadCurveToPoint")]
+    pub fn quad_curve_to(&self, control: CGPoint, end: CGPoint) {
+        unsafe { CGContextAddQuadCurveToPoint(Some(self), control.x, control.y, end.x, end.y) }
+    }
+
+    /// Add an arc of a circle, with the angles given in radians.
+    #[inline]
+    #[doc(alias = "CGContextAddArc")]
+    pub fn arc(
+        &self,
+        center: CGPoint,
+        radius: CGFloat,
+        start_angle: CGFloat,
+        end_angle: CGFloat,
+        clockwise: bool,
+    ) {
+        unsafe {
+            CGContextAddArc(
+                Some(self),
+                center.x,
+                center.y,
+                radius,
+                start_angle,
+                end_angle,
+                clockwise as _,
+            )
+        }
+    }
+
+    /// Close the current subpath with a straight line to its start.
+    #[inline]
+    #[doc(alias = "CGContextClosePath")]
+    pub fn close_path(&self) {
+        unsafe { CGContextClosePath(Some(self)) }
+    }
+
+    /// Add a rectangle to the path.
+    #[inline]
+    #[doc(alias = "CGContextAddRect")]
+    pub fn add_rect(&self, rect: CGRect) {
+        unsafe { CGContextAddRect(Some(self), rect) }
+    }
+
+    /// Add rectangles to the path.
+    #[inline]
+    #[doc(alias = "CGContextAddRects")]
+    pub fn add_rects(&self, rects: &[CGRect]) {
+        unsafe { CGContextAddRects(Some(self), rects.as_ptr(), rects.len()) }
+    }
+
+    /// Add straight lines between the given points to the path.
+    #[inline]
+    #[doc(alias = "CGContextAddLines")]
+    pub fn add_lines(&self, points: &[CGPoint]) {
+        unsafe { CGContextAddLines(Some(self), points.as_ptr(), points.len()) }
+    }
+
+    /// Add an ellipse that fits inside the rectangle to the path.
+    #[inline]
+    #[doc(alias = "CGContextAddEllipseInRect")]
+    pub fn add_ellipse_in_rect(&self, rect: CGRect) {
+        unsafe { CGContextAddEllipseInRect(Some(self), rect) }
+    }
+
+    /// Add a previously created path to the path.
+    #[inline]
+    #[cfg(feature = "CGPath")]
+    #[doc(alias = "CGContextAddPath")]
+    pub fn add_path(&self, path: &crate::CGPath) {
+        unsafe { crate::CGContextAddPath(Some(self), Some(path)) }
+    }
+
+    /// Whether the current path is empty.
+    #[inline]
+    #[doc(alias = "CGContextIsPathEmpty")]
+    pub fn is_path_empty(&self) -> bool {
+        unsafe { CGContextIsPathEmpty(Some(self)) }
+    }
+
+    /// The current point of the path.
+    #[inline]
+    #[doc(alias = "CGContextGetPathCurrentPoint")]
+    pub fn current_point(&self) -> CGPoint {
+        unsafe { CGContextGetPathCurrentPoint(Some(self)) }
+    }
+
+    /// The smallest rectangle that contains the path, including control
+    /// points of curves.
+    #[inline]
+    #[doc(alias = "CGContextGetPathBoundingBox")]
+    pub fn path_bounding_box(&self) -> CGRect {
+        unsafe { CGContextGetPathBoundingBox(Some(self)) }
+    }
+}
+
+/// Painting.
+impl CGContext {
+    /// Paint the current path with the given mode, and clear it.
+    #[inline]
+    #[doc(alias = "CGContextDrawPath")]
+    pub fn draw_path(&self, mode: CGPathDrawingMode) {
+        unsafe { CGContextDrawPath(Some(self), mode) }
+    }
+
+    /// Fill the current path using the non-zero winding rule, and clear it.
+    #[inline]
+    #[doc(alias = "CGContextFillPath")]
+    pub fn fill_path(&self) {
+        unsafe { CGContextFillPath(Some(self)) }
+    }
+
+    /// Fill the current path using the even-odd rule, and clear it.
+    #[inline]
+    #[doc(alias = "CGContextEOFillPath")]
+    pub fn eo_fill_path(&self) {
+        unsafe { CGContextEOFillPath(Some(self)) }
+    }
+
+    /// Stroke the current path, and clear it.
+    #[inline]
+    #[doc(alias = "CGContextStrokePath")]
+    pub fn stroke_path(&self) {
+        unsafe { CGContextStrokePath(Some(self)) }
+    }
+
+    /// Fill the rectangle.
+    #[inline]
+    #[doc(alias = "CGContextFillRect")]
+    pub fn fill_rect(&self, rect: CGRect) {
+        unsafe { CGContextFillRect(Some(self), rect) }
+    }
+
+    /// Fill the rectangles.
+    #[inline]
+    #[doc(alias = "CGContextFillRects")]
+    pub fn fill_rects(&self, rects: &[CGRect]) {
+        unsafe { CGContextFillRects(Some(self), rects.as_ptr(), rects.len()) }
+    }
+
+    /// Stroke the rectangle.
+    #[inline]
+    #[doc(alias = "CGContextStrokeRect")]
+    pub fn stroke_rect(&self, rect: CGRect) {
+        unsafe { CGContextStrokeRect(Some(self), rect) }
+    }
+
+    /// Stroke the rectangle with the given line width.
+    #[inline]
+    #[doc(alias = "CGContextStrokeRectWithWidth")]
+    pub fn stroke_rect_with_width(&self, rect: CGRect, width: CGFloat) {
+        unsafe { CGContextStrokeRectWithWidth(Some(self), rect, width) }
+    }
+
+    /// Clear the rectangle to transparent black.
+    #[inline]
+    #[doc(alias = "CGContextClearRect")]
+    pub fn clear_rect(&self, rect: CGRect) {
+        unsafe { CGContextClearRect(Some(self), rect) }
+    }
+
+    /// Fill an ellipse that fits inside the rectangle.
+    #[inline]
+    #[doc(alias = "CGContextFillEllipseInRect")]
+    pub fn fill_ellipse_in_rect(&self, rect: CGRect) {
+        unsafe { CGContextFillEllipseInRect(Some(self), rect) }
+    }
+
+    /// Stroke an ellipse that fits inside the rectangle.
+    #[inline]
+    #[doc(alias = "CGContextStrokeEllipseInRect")]
+    pub fn stroke_ellipse_in_rect(&self, rect: CGRect) {
+        unsafe { CGContextStrokeEllipseInRect(Some(self), rect) }
+    }
+
+    /// Stroke line segments between pairs of points.
+    ///
+    /// The points are taken two at a time; a trailing unpaired point is
+    /// ignored.
+    #[inline]
+    #[doc(alias = "CGContextStrokeLineSegments")]
+    pub fn stroke_line_segments(&self, points: &[CGPoint]) {
+        unsafe { CGContextStrokeLineSegments(Some(self), points.as_ptr(), points.len()) }
+    }
+
+    /// Draw the image, scaled to fit the rectangle.
+    #[inline]
+    #[cfg(feature = "CGImage")]
+    #[doc(alias = "CGContextDrawImage")]
+    pub fn draw_image(&self, rect: CGRect, image: &crate::CGImage) {
+        unsafe { crate::CGContextDrawImage(Some(self), rect, Some(image)) }
+    }
+
+    /// Flush the drawing to the destination, e.g. a window.
+    #[inline]
+    #[doc(alias = "CGContextFlush")]
+    pub fn flush(&self) {
+        unsafe { CGContextFlush(Some(self)) }
+    }
+}
+
+/// Clipping.
+impl CGContext {
+    /// Intersect the clipping area with the current path using the
+    /// non-zero winding rule, and clear the path.
+    ///
+    /// The clipping area can only be enlarged again by restoring the
+    /// graphics state, see [`save_state`](Self::save_state).
+    #[inline]
+    #[doc(alias = "CGContextClip")]
+    pub fn clip(&self) {
+        unsafe { CGContextClip(Some(self)) }
+    }
+
+    /// Same as [`clip`](Self::clip), using the even-odd rule.
+    #[inline]
+    #[doc(alias = "CGContextEOClip")]
+    pub fn eo_clip(&self) {
+        unsafe { CGContextEOClip(Some(self)) }
+    }
+
+    /// Intersect the clipping area with the rectangle.
+    #[inline]
+    #[doc(alias = "CGContextClipToRect")]
+    pub fn clip_to_rect(&self, rect: CGRect) {
+        unsafe { CGContextClipToRect(Some(self), rect) }
+    }
+
+    /// Intersect the clipping area with the union of the rectangles.
+    #[inline]
+    #[doc(alias = "CGContextClipToRects")]
+    pub fn clip_to_rects(&self, rects: &[CGRect]) {
+        let ptr = NonNull::from(rects).cast::<CGRect>();
+        unsafe { CGContextClipToRects(Some(self), ptr, rects.len()) }
+    }
+
+    /// The bounding box of the clipping area, in user space.
+    #[inline]
+    #[doc(alias = "CGContextGetClipBoundingBox")]
+    pub fn clip_bounding_box(&self) -> CGRect {
+        unsafe { CGContextGetClipBoundingBox(Some(self)) }
+    }
+}
+
+/// Text.
+impl CGContext {
+    /// Set the font used for drawing glyphs.
+    #[inline]
+    #[cfg(feature = "CGFont")]
+    #[doc(alias = "CGContextSetFont")]
+    pub fn set_font(&self, font: &crate::CGFont) {
+        unsafe { crate::CGContextSetFont(Some(self), Some(font)) }
+    }
+
+    /// Set the font size used for drawing glyphs.
+    #[inline]
+    #[doc(alias = "CGContextSetFontSize")]
+    pub fn set_font_size(&self, size: CGFloat) {
+        unsafe { CGContextSetFontSize(Some(self), size) }
+    }
+
+    /// Set the extra space added between glyphs.
+    #[inline]
+    #[doc(alias = "CGContextSetCharacterSpacing")]
+    pub fn set_character_spacing(&self, spacing: CGFloat) {
+        unsafe { CGContextSetCharacterSpacing(Some(self), spacing) }
+    }
+
+    /// Set whether glyphs are filled, stroked, used for clipping, etc.
+    #[inline]
+    #[doc(alias = "CGContextSetTextDrawingMode")]
+    pub fn set_text_drawing_mode(&self, mode: CGTextDrawingMode) {
+        unsafe { CGContextSetTextDrawingMode(Some(self), mode) }
+    }
+
+    /// Set the position at which text is drawn.
+    #[inline]
+    #[doc(alias = "CGContextSetTextPosition")]
+    pub fn set_text_position(&self, position: CGPoint) {
+        unsafe { CGContextSetTextPosition(Some(self), position.x, position.y) }
+    }
+
+    /// The position at which text is drawn.
+    #[inline]
+    #[doc(alias = "CGContextGetTextPosition")]
+    pub fn text_position(&self) -> CGPoint {
+        unsafe { CGContextGetTextPosition(Some(self)) }
+    }
+
+    /// Draw each glyph at the corresponding position, relative to the text
+    /// position.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if `glyphs` and `positions` have different lengths.
+    #[inline]
+    #[cfg(feature = "CGFont")]
+    #[doc(alias = "CGContextShowGlyphsAtPositions")]
+    pub fn show_glyphs_at_positions(&self, glyphs: &[crate::CGGlyph], positions: &[CGPoint]) {
+        assert_eq!(
+            glyphs.len(),
+            positions.len(),
+            "must have a position for each glyph"
+        );
+        unsafe {
+            crate::CGContextShowGlyphsAtPositions(
+                Some(self),
+                glyphs.as_ptr(),
+                positions.as_ptr(),
+                glyphs.len(),
+            )
+        }
+    }
+}
+
+/// Bitmap contexts.
+#[cfg(all(
+    feature = "alloc",
+    feature = "CGBitmapContext",
+    feature = "CGColorSpace",
+    feature = "CGImage"
+))]
+impl CGContext {
+    /// Create a bitmap context with the given layout, draw into it with
+    /// the closure, and return the pixels.
+    ///
+    /// The returned buffer is [`buffer_len`] bytes long, and laid out
+    /// according to `layout`.
+    ///
+    /// [`buffer_len`]: crate::CGBitmapLayout::buffer_len
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layout is not supported by bitmap contexts,
+    /// see [`CGBitmapLayout::validate_context`].
+    ///
+    /// [`CGBitmapLayout::validate_context`]: crate::CGBitmapLayout::validate_context
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+    /// use objc2_core_graphics::{CGColorSpaceCreateDeviceRGB, CGContext, CGImagePixelFormat};
+    ///
+    /// let space = unsafe { CGColorSpaceCreateDeviceRGB() }.unwrap();
+    /// let layout = CGImagePixelFormat::Rgba8Premultiplied.layout(2, 2).unwrap();
+    /// let pixels = CGContext::with_bitmap_context(&layout, Some(&space), |context| {
+    ///     context.set_fill_rgb(1.0, 0.0, 0.0, 1.0);
+    ///     context.fill_rect(CGRect::new(CGPoint::ZERO, CGSize::new(2.0, 2.0)));
+    /// })
+    /// .unwrap();
+    /// assert_eq!(pixels, [255, 0, 0, 255].repeat(4));
+    /// ```
+    #[doc(alias = "CGBitmapContextCreate")]
+    pub fn with_bitmap_context(
+        layout: &crate::CGBitmapLayout,
+        space: Option<&crate::CGColorSpace>,
+        draw: impl FnOnce(&CGContext),
+    ) -> Result<alloc::vec::Vec<u8>, crate::CGBitmapLayoutError> {
+        let mut data = alloc::vec![0u8; layout.buffer_len()?];
+        {
+            // SAFETY: The buffer is large enough for the layout, and
+            // outlives the context, which is dropped at the end of this
+            // block.
+            //
+            // The closure cannot keep the context alive for longer, since
+            // it only receives a reference.
+            let context =
+                unsafe { layout.create_context_with_data(data.as_mut_ptr().cast(), space) }?;
+            draw(&context);
+            unsafe { CGContextFlush(Some(&context)) };
+        }
+        Ok(data)
+    }
+}
@@ -0,0 +1,266 @@
+//! Creating data providers from Rust memory and readers.
+use alloc::boxed::Box;
+use core::ffi::c_void;
+use core::ptr::{self, NonNull};
+
+use objc2_core_foundation::CFRetained;
+
+use crate::{CGDataProvider, CGDataProviderCreateWithData};
+
+/// Drop the buffer that was leaked into the provider's info pointer.
+unsafe extern "C-unwind" fn release_buffer<T>(
+    info: *mut c_void,
+    _data: NonNull<c_void>,
+    _size: usize,
+) {
+    // SAFETY: The info pointer was created from `Box<T>` in `from_buffer`,
+    // and the data is released exactly once.
+    drop(unsafe { Box::from_raw(info.cast::<T>()) });
+}
+
+/// Creating from memory.
+impl CGDataProvider {
+    /// Create a data provider that reads directly from an owned buffer,
+    /// without copying it.
+    ///
+    /// The buffer can be anything that derefs to bytes, such as a
+    /// `Vec<u8>`, `Box<[u8]>` or `Arc<[u8]>`. It is dropped when the
+    /// provider (and any images using it) is deallocated, which may happen
+    /// on a different thread, hence the `Send` bound.
+    ///
+    ///
+    /// # Examples
+    ///
+    /// Share pixel data between Rust and an image.
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use objc2_core_graphics::{CGDataProvider, CGDataProviderCopyData};
+    ///
+    /// let pixels: Arc<[u8]> = Arc::from([255, 0, 0, 255]);
+    /// let provider = CGDataProvider::from_buffer(pixels.clone());
+    /// assert_eq!(Arc::strong_count(&pixels), 2);
+    ///
+    /// let data = unsafe { CGDataProviderCopyData(Some(&provider)) }.unwrap();
+    /// assert_eq!(data.to_vec(), [255, 0, 0, 255]);
+    ///
+    /// drop(provider);
+    /// assert_eq!(Arc::strong_count(&pixels), 1);
+    /// ```
+    #[doc(alias = "CGDataProviderCreateWithData")]
+    pub fn from_buffer<T>(buffer: T) -> CFRetained<Self>
+    where
+        T: AsRef<[u8]> + Send + 'static,
+    {
+        let buffer = Box::new(buffer);
+        // The bytes stay valid for as long as the buffer is alive, since it
+        // is boxed, and we never hand out mutable access to it.
+        let bytes: &[u8] = (*buffer).as_ref();
+        let (data, size) = (bytes.as_ptr(), bytes.len());
+        let info = Box::into_raw(buffer);
+        // SAFETY: The data is valid until `release_buffer` is called, which
+        // then takes care of deallocating the buffer.
+        unsafe {
+            CGDataProviderCreateWithData(info.cast(), data.cast(), size, Some(release_buffer::<T>))
+        }
+        .expect("failed creating data provider")
+    }
+
+    /// Create a data provider that reads directly from a `'static` byte
+    /// slice, such as one created with `include_bytes!`.
+    #[doc(alias = "CGDataProviderCreateWithData")]
+    pub fn from_static_bytes(bytes: &'static [u8]) -> CFRetained<Self> {
+        // SAFETY: The data is never deallocated, so it needs no release
+        // callback.
+        unsafe {
+            CGDataProviderCreateWithData(ptr::null_mut(), bytes.as_ptr().cast(), bytes.len(), None)
+        }
+        .expect("failed creating data provider")
+    }
+}
+
+#[cfg(all(feature = "std", feature = "libc"))]
+mod sequential {
+    use std::io::{self, Read, Seek, SeekFrom};
+
+    use super::*;
+    use crate::{CGDataProviderCreateSequential, CGDataProviderSequentialCallbacks};
+
+    struct Source<R> {
+        reader: R,
+        /// How to seek in the reader, if it supports that.
+        seek: Option<fn(&mut R, SeekFrom) -> io::Result<u64>>,
+        /// The position in the reader that the data starts at.
+        start: u64,
+        /// The number of bytes read or skipped since the start.
+        consumed: u64,
+        /// Set if rewinding failed, after which we report the end of the
+        /// data instead of reading from the wrong position.
+        failed: bool,
+    }
+
+    impl<R: Read> Source<R> {
+        fn read(&mut self, buf: &mut [u8]) -> usize {
+            if self.failed {
+                return 0;
+            }
+            loop {
+                match self.reader.read(buf) {
+                    Ok(n) => {
+                        self.consumed += n as u64;
+                        return n;
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    // The callback has no way of reporting errors, so treat
+                    // them as the end of the data.
+                    Err(_) => {
+                        self.failed = true;
+                        return 0;
+                    }
+                }
+            }
+        }
+
+        fn skip(&mut self, count: u64) -> u64 {
+            if self.failed {
+                return 0;
+            }
+            let result = match self.seek {
+                Some(seek) => {
+                    seek(&mut self.reader, SeekFrom::Current(count as i64)).map(|_| count)
+                }
+                None => io::copy(&mut (&mut self.reader).take(count), &mut io::sink()),
+            };
+            match result {
+                Ok(n) => {
+                    self.consumed += n;
+                    n
+                }
+                Err(_) => {
+                    self.failed = true;
+                    0
+                }
+            }
+        }
+
+        fn rewind(&mut self) {
+            if self.consumed == 0 {
+                // Already at the start.
+                return;
+            }
+            self.failed = match self.seek {
+                Some(seek) => seek(&mut self.reader, SeekFrom::Start(self.start)).is_err(),
+                // Cannot go back in the stream.
+                None => true,
+            };
+            self.consumed = 0;
+        }
+    }
+
+    unsafe extern "C-unwind" fn get_bytes<R: Read>(
+        info: *mut c_void,
+        buffer: NonNull<c_void>,
+        count: usize,
+    ) -> usize {
+        // SAFETY: The info pointer is a `Source<R>` that is only accessed
+        // by one callback at a time, and the buffer is valid for writing
+        // `count` bytes.
+        let source = unsafe { &mut *info.cast::<Source<R>>() };
+        let buf = unsafe { core::slice::from_raw_parts_mut(buffer.as_ptr().cast::<u8>(), count) };
+        source.read(buf)
+    }
+
+    unsafe extern "C-unwind" fn skip_forward<R: Read>(
+        info: *mut c_void,
+        count: libc::off_t,
+    ) -> libc::off_t {
+        let source = unsafe { &mut *info.cast::<Source<R>>() };
+        source.skip(count.max(0) as u64) as libc::off_t
+    }
+
+    unsafe extern "C-unwind" fn rewind<R: Read>(info: *mut c_void) {
+        let source = unsafe { &mut *info.cast::<Source<R>>() };
+        source.rewind();
+    }
+
+    unsafe extern "C-unwind" fn release_info<R>(info: *mut c_void) {
+        drop(unsafe { Box::from_raw(info.cast::<Source<R>>()) });
+    }
+
+    fn create<R: Read + Send + 'static>(source: Source<R>) -> CFRetained<CGDataProvider> {
+        let callbacks = CGDataProviderSequentialCallbacks {
+            version: 0,
+            getBytes: Some(get_bytes::<R>),
+            skipForward: Some(skip_forward::<R>),
+            rewind: Some(rewind::<R>),
+            releaseInfo: Some(release_info::<R>),
+        };
+        let info = Box::into_raw(Box::new(source));
+        // SAFETY: The callbacks match the type of the info pointer, and
+        // `release_info` takes care of deallocating it.
+        unsafe { CGDataProviderCreateSequential(info.cast(), &callbacks) }
+            .expect("failed creating data provider")
+    }
+
+    /// Creating from readers.
+    impl CGDataProvider {
+        /// Create a data provider that streams its data from the reader.
+        ///
+        /// The reader cannot be rewound, so if CoreGraphics needs to read
+        /// the data more than once, it will see it as empty the second
+        /// time. Prefer
+        /// [`from_seekable_reader`](Self::from_seekable_reader) for readers
+        /// that support seeking, such as files.
+        ///
+        /// Read errors are treated as the end of the data.
+        #[doc(alias = "CGDataProviderCreateSequential")]
+        pub fn from_reader<R>(reader: R) -> CFRetained<Self>
+        where
+            R: Read + Send + 'static,
+        {
+            create(Source {
+                reader,
+                seek: None,
+                start: 0,
+                consumed: 0,
+                failed: false,
+            })
+        }
+
+        /// Create a data provider that streams its data from the reader,
+        /// starting at its current position.
+        ///
+        /// Rewinding seeks back to that position, and skipping seeks
+        /// forwards without reading the skipped data.
+        ///
+        /// Read and seek errors are treated as the end of the data.
+        ///
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::io::Cursor;
+        ///
+        /// use objc2_core_graphics::{CGDataProvider, CGDataProviderCopyData};
+        ///
+        /// let provider = CGDataProvider::from_seekable_reader(Cursor::new(vec![1, 2, 3]));
+        /// let data = unsafe { CGDataProviderCopyData(Some(&provider)) }.unwrap();
+        /// assert_eq!(data.to_vec(), [1, 2, 3]);
+        /// ```
+        #[doc(alias = "CGDataProviderCreateSequential")]
+        pub fn from_seekable_reader<R>(mut reader: R) -> CFRetained<Self>
+        where
+            R: Read + Seek + Send + 'static,
+        {
+            let start = reader.stream_position();
+            create(Source {
+                reader,
+                seek: Some(|reader, pos| reader.seek(pos)),
+                start: *start.as_ref().unwrap_or(&0),
+                consumed: 0,
+                failed: start.is_err(),
+            })
+        }
+    }
+}
@@ -0,0 +1,479 @@
+//! Event taps that call a closure, and typed access to event fields.
+use alloc::rc::Rc;
+use alloc::string::String;
+use core::ffi::c_void;
+use core::fmt;
+use core::ptr::{self, NonNull};
+
+use objc2_core_foundation::{
+    kCFRunLoopCommonModes, CFMachPort, CFMachPortCreateRunLoopSource, CFMachPortInvalidate,
+    CFRetained, CFRunLoopAddSource, CFRunLoopGetCurrent, CFRunLoopSource,
+    CFRunLoopSourceInvalidate, CGPoint,
+};
+
+use crate::{
+    CGEvent, CGEventField, CGEventFlags, CGEventGetDoubleValueField, CGEventGetFlags,
+    CGEventGetIntegerValueField, CGEventGetLocation, CGEventGetTimestamp, CGEventGetType,
+    CGEventKeyboardGetUnicodeString, CGEventMask, CGEventSetFlags, CGEventSetIntegerValueField,
+    CGEventSetLocation, CGEventTapCreate, CGEventTapEnable, CGEventTapIsEnabled,
+    CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement, CGEventTapProxy, CGEventTimestamp,
+    CGEventType,
+};
+
+impl CGEventType {
+    /// The bit for this event type in a [`CGEventMask`].
+    ///
+    /// Combine these with `|` to select the events that an event tap
+    /// receives.
+    #[doc(alias = "CGEventMaskBit")]
+    pub const fn mask(self) -> CGEventMask {
+        // Types that don't fit in the mask, such as the tap disabled
+        // notifications, are always delivered anyhow.
+        if self.0 < CGEventMask::BITS {
+            1 << self.0
+        } else {
+            0
+        }
+    }
+}
+
+/// Typed access to event fields.
+impl CGEvent {
+    /// The type of the event.
+    #[doc(alias = "CGEventGetType")]
+    pub fn event_type(&self) -> CGEventType {
+        unsafe { CGEventGetType(Some(self)) }
+    }
+
+    /// When the event occurred, in nanoseconds since system startup.
+    #[doc(alias = "CGEventGetTimestamp")]
+    pub fn timestamp(&self) -> CGEventTimestamp {
+        unsafe { CGEventGetTimestamp(Some(self)) }
+    }
+
+    /// The location of the mouse, in global display coordinates with the
+    /// origin in the upper-left corner of the main display.
+    #[doc(alias = "CGEventGetLocation")]
+    pub fn location(&self) -> CGPoint {
+        unsafe { CGEventGetLocation(Some(self)) }
+    }
+
+    /// Set the location of the mouse.
+    #[doc(alias = "CGEventSetLocation")]
+    pub fn set_location(&self, location: CGPoint) {
+        unsafe { CGEventSetLocation(Some(self), location) }
+    }
+
+    /// The modifier keys and other flags of the event.
+    #[doc(alias = "CGEventGetFlags")]
+    pub fn flags(&self) -> CGEventFlags {
+        unsafe { CGEventGetFlags(Some(self)) }
+    }
+
+    /// Set the modifier keys and other flags of the event.
+    #[doc(alias = "CGEventSetFlags")]
+    pub fn set_flags(&self, flags: CGEventFlags) {
+        unsafe { CGEventSetFlags(Some(self), flags) }
+    }
+
+    /// The value of an integer field.
+    #[doc(alias = "CGEventGetIntegerValueField")]
+    pub fn integer_field(&self, field: CGEventField) -> i64 {
+        unsafe { CGEventGetIntegerValueField(Some(self), field) }
+    }
+
+    /// Set the value of an integer field.
+    #[doc(alias = "CGEventSetIntegerValueField")]
+    pub fn set_integer_field(&self, field: CGEventField, value: i64) {
+        unsafe { CGEventSetIntegerValueField(Some(self), field, value) }
+    }
+
+    /// The value of a floating-point field.
+    #[doc(alias = "CGEventGetDoubleValueField")]
+    pub fn double_field(&self, field: CGEventField) -> f64 {
+        unsafe { CGEventGetDoubleValueField(Some(self), field) }
+    }
+
+    /// The virtual key code of a key event.
+    #[doc(alias = "kCGKeyboardEventKeycode")]
+    pub fn keycode(&self) -> u16 {
+        self.integer_field(CGEventField::KeyboardEventKeycode) as u16
+    }
+
+    /// Set the virtual key code of a key event, e.g. to remap keys.
+    #[doc(alias = "kCGKeyboardEventKeycode")]
+    pub fn set_keycode(&self, keycode: u16) {
+        self.set_integer_field(CGEventField::KeyboardEventKeycode, keycode.into())
+    }
+
+    /// Whether a key down event was generated by holding down the key.
+    #[doc(alias = "kCGKeyboardEventAutorepeat")]
+    pub fn is_autorepeat(&self) -> bool {
+        self.integer_field(CGEventField::KeyboardEventAutorepeat) != 0
+    }
+
+    /// The text produced by a key event, taking the keyboard layout and
+    /// modifiers into account.
+    #[doc(alias = "CGEventKeyboardGetUnicodeString")]
+    pub fn unicode_string(&self) -> String {
+        // Key events rarely produce more than a few characters.
+        let mut buf = [0u16; 32];
+        let mut len = 0;
+        unsafe {
+            CGEventKeyboardGetUnicodeString(Some(self), buf.len() as _, &mut len, buf.as_mut_ptr())
+        };
+        let len = (len as usize).min(buf.len());
+        String::from_utf16_lossy(&buf[..len])
+    }
+
+    /// The button number of a mouse event, where `0` is the left button,
+    /// `1` the right button, and `2` the middle button.
+    #[doc(alias = "kCGMouseEventButtonNumber")]
+    pub fn mouse_button(&self) -> i64 {
+        self.integer_field(CGEventField::MouseEventButtonNumber)
+    }
+
+    /// The click count of a mouse down or up event, e.g. `2` for a
+    /// double-click.
+    #[doc(alias = "kCGMouseEventClickState")]
+    pub fn click_count(&self) -> i64 {
+        self.integer_field(CGEventField::MouseEventClickState)
+    }
+
+    /// The horizontal and vertical movement of a mouse moved or dragged
+    /// event, in pixels.
+    ///
+    /// Unlike the change in [`location`](Self::location), this is not
+    /// clamped to the edges of the screen.
+    #[doc(alias = "kCGMouseEventDeltaX")]
+    #[doc(alias = "kCGMouseEventDeltaY")]
+    pub fn mouse_delta(&self) -> (i64, i64) {
+        (
+            self.integer_field(CGEventField::MouseEventDeltaX),
+            self.integer_field(CGEventField::MouseEventDeltaY),
+        )
+    }
+
+    /// The horizontal and vertical scroll amount of a scroll wheel event,
+    /// in pixels.
+    #[doc(alias = "kCGScrollWheelEventPointDeltaAxis1")]
+    #[doc(alias = "kCGScrollWheelEventPointDeltaAxis2")]
+    pub fn scroll_delta(&self) -> (f64, f64) {
+        // Axis 1 is the vertical axis.
+        (
+            self.double_field(CGEventField::ScrollWheelEventPointDeltaAxis2),
+            self.double_field(CGEventField::ScrollWheelEventPointDeltaAxis1),
+        )
+    }
+}
+
+/// What an event tap does with an event after the closure has seen it.
+///
+/// Listen-only taps cannot change events, so this is ignored for them.
+#[derive(Debug)]
+pub enum CGEventTapResult {
+    /// Pass the event on, including any changes made to it.
+    Pass,
+    /// Remove the event, such that it is not delivered.
+    Drop,
+    /// Deliver a different event instead.
+    Replace(CFRetained<CGEvent>),
+}
+
+struct State<F> {
+    handler: F,
+    /// The tap, used to re-enable it. Not retained, since it is owned by
+    /// the `CGEventTap`, which outlives the callbacks.
+    port: *const CFMachPort,
+}
+
+unsafe extern "C-unwind" fn callback<F: Fn(CGEventTapProxy, &CGEvent) -> CGEventTapResult>(
+    proxy: CGEventTapProxy,
+    r#type: CGEventType,
+    event: NonNull<CGEvent>,
+    info: *mut c_void,
+) -> *mut CGEvent {
+    // SAFETY: The info is created by `Rc::into_raw` in `CGEventTap::new`,
+    // and stays alive until the tap is dropped. We keep it alive while
+    // calling the handler, in case the handler drops the tap.
+    let state = unsafe {
+        Rc::increment_strong_count(info.cast::<State<F>>());
+        Rc::from_raw(info.cast::<State<F>>())
+    };
+
+    if r#type == CGEventType::TapDisabledByTimeout {
+        // The system disables taps whose callbacks take too long, re-enable
+        // it such that we keep receiving events.
+        unsafe { CGEventTapEnable(&*state.port, true) };
+        return event.as_ptr();
+    }
+    if r#type == CGEventType::TapDisabledByUserInput {
+        return event.as_ptr();
+    }
+
+    // SAFETY: The event is valid for the duration of the callback.
+    match (state.handler)(proxy, unsafe { event.as_ref() }) {
+        CGEventTapResult::Pass => event.as_ptr(),
+        CGEventTapResult::Drop => ptr::null_mut(),
+        // The system releases the returned event when it has been passed
+        // on, so give up our retain of it.
+        CGEventTapResult::Replace(new) => CFRetained::into_raw(new).as_ptr(),
+    }
+}
+
+/// An event tap that calls a closure for each event, and which is removed
+/// when dropped.
+///
+/// The tap is added to the current thread's run loop, which must be running
+/// for the closure to be called. It is re-enabled automatically if the
+/// system disables it because the closure took too long.
+///
+/// Creating taps that observe other applications' events requires the
+/// process to be trusted for accessibility (for active filters) or input
+/// monitoring (for listen-only taps).
+///
+///
+/// # Examples
+///
+/// Swap the caps lock and escape keys.
+///
+/// ```no_run
+/// use objc2_core_foundation::CFRunLoopRun;
+/// use objc2_core_graphics::{
+///     CGEventTap, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement, CGEventTapResult,
+///     CGEventType,
+/// };
+///
+/// const CAPS_LOCK: u16 = 0x39;
+/// const ESCAPE: u16 = 0x35;
+///
+/// let _tap = CGEventTap::new(
+///     CGEventTapLocation::SessionEventTap,
+///     CGEventTapPlacement::HeadInsertEventTap,
+///     CGEventTapOptions::Default,
+///     CGEventType::KeyDown.mask() | CGEventType::KeyUp.mask(),
+///     |_proxy, event| {
+///         match event.keycode() {
+///             CAPS_LOCK => event.set_keycode(ESCAPE),
+///             ESCAPE => event.set_keycode(CAPS_LOCK),
+///             _ => {}
+///         }
+///         CGEventTapResult::Pass
+///     },
+/// )
+/// .expect("failed creating event tap, is the process trusted for accessibility?");
+///
+/// unsafe { CFRunLoopRun() };
+/// ```
+#[doc(alias = "CGEventTapCreate")]
+#[must_use = "the event tap is removed when this is dropped"]
+pub struct CGEventTap {
+    port: CFRetained<CFMachPort>,
+    source: CFRetained<CFRunLoopSource>,
+    /// The `Rc<State<F>>` passed to the callback, type-erased.
+    state: *const c_void,
+    drop_state: unsafe fn(*const c_void),
+}
+
+impl CGEventTap {
+    /// Create an event tap for the given events, and add it to the current
+    /// thread's run loop in the common modes.
+    ///
+    /// The closure receives each event, along with a proxy that can be
+    /// passed to [`CGEventTapPostEvent`] to insert new events after this
+    /// tap.
+    ///
+    /// Returns [`None`] if the tap could not be created, usually because
+    /// the process lacks the required permissions.
+    ///
+    /// [`CGEventTapPostEvent`]: crate::CGEventTapPostEvent
+    #[doc(alias = "CGEventTapCreate")]
+    pub fn new<F>(
+        location: CGEventTapLocation,
+        placement: CGEventTapPlacement,
+        options: CGEventTapOptions,
+        events: CGEventMask,
+        handler: F,
+    ) -> Option<Self>
+    where
+        F: Fn(CGEventTapProxy, &CGEvent) -> CGEventTapResult + 'static,
+    {
+        unsafe fn drop_state<F>(state: *const c_void) {
+            drop(unsafe { Rc::from_raw(state.cast::<State<F>>()) });
+        }
+
+        let state = Rc::into_raw(Rc::new(State {
+            handler,
+            port: ptr::null(),
+        }));
+        // SAFETY: The callback matches the type of the info, which is kept
+        // alive until the tap is invalidated.
+        let port = unsafe {
+            CGEventTapCreate(
+                location,
+                placement,
+                options,
+                events,
+                Some(callback::<F>),
+                state.cast_mut().cast(),
+            )
+        };
+        let Some(port) = port else {
+            // SAFETY: The tap was not created, so we still own the state.
+            unsafe { drop_state::<F>(state.cast()) };
+            return None;
+        };
+        // SAFETY: The callback is only invoked from the run loop source,
+        // which has not been added yet, so nothing accesses the state
+        // concurrently.
+        unsafe { (*state.cast_mut()).port = &*port };
+
+        let source = unsafe { CFMachPortCreateRunLoopSource(None, Some(&port), 0) }
+            .expect("failed creating run loop source for event tap");
+        let run_loop = unsafe { CFRunLoopGetCurrent() }.expect("failed getting current run loop");
+        unsafe { CFRunLoopAddSource(&run_loop, Some(&source), kCFRunLoopCommonModes) };
+
+        Some(Self {
+            port,
+            source,
+            state: state.cast(),
+            drop_state: drop_state::<F>,
+        })
+    }
+
+    /// Whether the tap is currently enabled.
+    #[doc(alias = "CGEventTapIsEnabled")]
+    pub fn is_enabled(&self) -> bool {
+        unsafe { CGEventTapIsEnabled(&self.port) }
+    }
+
+    /// Enable or disable the tap.
+    ///
+    /// Disabled taps do not receive events, and do not slow down event
+    /// delivery.
+    #[doc(alias = "CGEventTapEnable")]
+    pub fn set_enabled(&self, enabled: bool) {
+        unsafe { CGEventTapEnable(&self.port, enabled) }
+    }
+
+    /// The underlying mach port of the tap.
+    pub fn port(&self) -> &CFMachPort {
+        &self.port
+    }
+}
+
+impl Drop for CGEventTap {
+    #[doc(alias = "CFMachPortInvalidate")]
+    fn drop(&mut self) {
+        // Stop receiving events, and remove the source from all run loops.
+        unsafe { CFMachPortInvalidate(&self.port) };
+        unsafe { CFRunLoopSourceInvalidate(&self.source) };
+        // SAFETY: The callback can no longer be invoked, except if it is
+        // the one dropping us, in which case it holds its own reference to
+        // the state. The tap is not `Send`, so this is the thread the
+        // callback runs on.
+        unsafe { (self.drop_state)(self.state) };
+    }
+}
+
+impl fmt::Debug for CGEventTap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CGEventTap")
+            .field("port", &self.port)
+            .field("enabled", &self.is_enabled())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mask() {
+        assert_eq!(CGEventType::Null.mask(), 1);
+        assert_eq!(CGEventType::KeyDown.mask(), 1 << 10);
+        assert_eq!(
+            CGEventType::KeyDown.mask() | CGEventType::KeyUp.mask(),
+            (1 << 10) | (1 << 11)
+        );
+        // Too large to be part of the mask.
+        assert_eq!(CGEventType::TapDisabledByTimeout.mask(), 0);
+        assert_eq!(CGEventType::TapDisabledByUserInput.mask(), 0);
+    }
+
+    /// Invoke the callback directly, as the system would.
+    #[cfg(target_vendor = "apple")]
+    fn invoke<F: Fn(CGEventTapProxy, &CGEvent) -> CGEventTapResult>(
+        r#type: CGEventType,
+        event: &CGEvent,
+        handler: F,
+    ) -> *mut CGEvent {
+        let state = Rc::new(State {
+            handler,
+            port: ptr::null(),
+        });
+        let ret = unsafe {
+            callback::<F>(
+                ptr::null_mut(),
+                r#type,
+                NonNull::from(event),
+                Rc::as_ptr(&state).cast_mut().cast(),
+            )
+        };
+        // The callback must only borrow the state.
+        assert_eq!(Rc::strong_count(&state), 1);
+        ret
+    }
+
+    #[test]
+    #[cfg(target_vendor = "apple")]
+    fn callback_result() {
+        let event = unsafe { crate::CGEventCreate(None) }.unwrap();
+        let event_ptr: *const CGEvent = &*event;
+
+        let ret = invoke(CGEventType::KeyDown, &event, |_, _| CGEventTapResult::Pass);
+        assert_eq!(ret.cast_const(), event_ptr);
+
+        let ret = invoke(CGEventType::KeyDown, &event, |_, _| CGEventTapResult::Drop);
+        assert!(ret.is_null());
+
+        let new = unsafe { crate::CGEventCreate(None) }.unwrap();
+        let new_ptr: *const CGEvent = &*new;
+        let ret = invoke(CGEventType::KeyDown, &event, move |_, _| {
+            CGEventTapResult::Replace(new.clone())
+        });
+        assert_eq!(ret.cast_const(), new_ptr);
+        // Take back the retain that was given to the system.
+        drop(unsafe { CFRetained::from_raw(NonNull::new(ret).unwrap()) });
+
+        // The handler is not called when the tap is disabled by the user.
+        let ret = invoke(
+            CGEventType::TapDisabledByUserInput,
+            &event,
+            |_, _| unreachable!(),
+        );
+        assert_eq!(ret.cast_const(), event_ptr);
+    }
+
+    #[test]
+    #[cfg(target_vendor = "apple")]
+    fn handler_dropped() {
+        let captured = Rc::new(());
+        let handler_captured = captured.clone();
+        let tap = CGEventTap::new(
+            CGEventTapLocation::SessionEventTap,
+            CGEventTapPlacement::HeadInsertEventTap,
+            CGEventTapOptions::ListenOnly,
+            CGEventType::KeyDown.mask(),
+            move |_, _| {
+                let _ = &handler_captured;
+                CGEventTapResult::Pass
+            },
+        );
+        // Creating the tap fails without input monitoring permissions, but
+        // the handler must be dropped in either case.
+        drop(tap);
+        assert_eq!(Rc::strong_count(&captured), 1);
+    }
+}
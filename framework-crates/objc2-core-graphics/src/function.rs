@@ -0,0 +1,109 @@
+//! Functions evaluated by Rust closures.
+use alloc::boxed::Box;
+use core::ffi::c_void;
+use core::ptr::NonNull;
+use core::slice;
+
+use objc2_core_foundation::{CFRetained, CGFloat};
+
+use crate::{CGFunction, CGFunctionCallbacks, CGFunctionCreate};
+
+struct Info<F> {
+    f: F,
+    domain_dimension: usize,
+    range_dimension: usize,
+}
+
+unsafe extern "C-unwind" fn evaluate<F: Fn(&[CGFloat], &mut [CGFloat])>(
+    info: *mut c_void,
+    input: NonNull<CGFloat>,
+    output: NonNull<CGFloat>,
+) {
+    // SAFETY: The info pointer was created from a `Box<Info<F>>` in
+    // `CGFunction::new`, and is valid until `release` is called.
+    let info = unsafe { &*info.cast::<Info<F>>() };
+    // SAFETY: Core Graphics passes an input array with one value per domain
+    // dimension, and an output array with one value per range dimension.
+    let input = unsafe { slice::from_raw_parts(input.as_ptr(), info.domain_dimension) };
+    let output = unsafe { slice::from_raw_parts_mut(output.as_ptr(), info.range_dimension) };
+    (info.f)(input, output);
+}
+
+unsafe extern "C-unwind" fn release<F>(info: *mut c_void) {
+    // SAFETY: Called once, when the function is deallocated.
+    drop(unsafe { Box::from_raw(info.cast::<Info<F>>()) });
+}
+
+impl CGFunction {
+    /// Create a function whose values are computed by the given closure.
+    ///
+    /// The function maps `domain.len()` input values to `range.len()` output
+    /// values, where each element of `domain` and `range` is the `[min, max]`
+    /// interval of the corresponding value. Input values are clipped to the
+    /// domain before being passed to the closure, and the values that the
+    /// closure writes are clipped to the range afterwards.
+    ///
+    /// The closure is dropped when the function is deallocated. Core
+    /// Graphics may evaluate the function on any thread, and may cache its
+    /// results.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if Core Graphics fails to create the function.
+    ///
+    ///
+    /// # Example
+    ///
+    /// A function that maps one input value to an opaque RGB color, for use
+    /// in a shading.
+    ///
+    /// ```no_run
+    /// use objc2_core_graphics::CGFunction;
+    ///
+    /// let function = CGFunction::new(&[[0.0, 1.0]], &[[0.0, 1.0]; 4], |input, output| {
+    ///     let t = input[0];
+    ///     output.copy_from_slice(&[t, 0.0, 1.0 - t, 1.0]);
+    /// });
+    /// ```
+    #[doc(alias = "CGFunctionCreate")]
+    pub fn new<F>(domain: &[[CGFloat; 2]], range: &[[CGFloat; 2]], f: F) -> CFRetained<Self>
+    where
+        F: Fn(&[CGFloat], &mut [CGFloat]) + Send + Sync + 'static,
+    {
+        let info = Box::into_raw(Box::new(Info {
+            f,
+            domain_dimension: domain.len(),
+            range_dimension: range.len(),
+        }));
+        // Core Graphics copies the callbacks.
+        let callbacks = CGFunctionCallbacks {
+            version: 0,
+            evaluate: Some(evaluate::<F>),
+            releaseInfo: Some(release::<F>),
+        };
+        // SAFETY: `[CGFloat; 2]` has the same layout as two consecutive
+        // `CGFloat`s, which is the layout of the domain and range arrays.
+        //
+        // The closure is `Send + Sync`, and only dropped by `release`.
+        let function = unsafe {
+            CGFunctionCreate(
+                info.cast(),
+                domain.len(),
+                domain.as_ptr().cast(),
+                range.len(),
+                range.as_ptr().cast(),
+                &callbacks,
+            )
+        };
+        match function {
+            Some(function) => function,
+            None => {
+                // SAFETY: The function was not created, so `release` will
+                // not be called.
+                drop(unsafe { Box::from_raw(info) });
+                panic!("failed creating CGFunction");
+            }
+        }
+    }
+}
@@ -16,3 +16,444 @@ impl CGBitmapInfo {
         Self::ByteOrder32Little
     };
 }
+
+/// A common pixel layout that a [`CGImage`] can be converted to.
+///
+/// See [`CGImage::convert_to`].
+///
+/// [`CGImage`]: crate::CGImage
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CGImagePixelFormat {
+    /// 8-bit BGRA with premultiplied alpha.
+    ///
+    /// This is the native format on Apple platforms, and what e.g. screen
+    /// captures are usually delivered as.
+    Bgra8Premultiplied,
+    /// 8-bit RGBA with premultiplied alpha.
+    Rgba8Premultiplied,
+    /// 8-bit RGBA with straight (non-premultiplied) alpha.
+    ///
+    /// This is the format most Rust image processing crates expect.
+    Rgba8,
+    /// 16-bit RGBA with premultiplied alpha, in host byte order.
+    Rgba16Premultiplied,
+    /// 16-bit RGBA with straight (non-premultiplied) alpha, in host byte
+    /// order.
+    Rgba16,
+    /// 8-bit grayscale without alpha.
+    Gray8,
+    /// 16-bit grayscale without alpha, in host byte order.
+    Gray16,
+}
+
+impl CGImagePixelFormat {
+    /// The number of bits in each color component.
+    pub const fn bits_per_component(self) -> usize {
+        match self {
+            Self::Bgra8Premultiplied | Self::Rgba8Premultiplied | Self::Rgba8 | Self::Gray8 => 8,
+            Self::Rgba16Premultiplied | Self::Rgba16 | Self::Gray16 => 16,
+        }
+    }
+
+    /// The number of components (including alpha) in each pixel.
+    pub const fn components_per_pixel(self) -> usize {
+        match self {
+            Self::Gray8 | Self::Gray16 => 1,
+            _ => 4,
+        }
+    }
+
+    /// The number of bytes used by each pixel.
+    pub const fn bytes_per_pixel(self) -> usize {
+        self.components_per_pixel() * self.bits_per_component() / 8
+    }
+
+    /// Whether the alpha channel is stored straight (non-premultiplied).
+    pub const fn is_straight_alpha(self) -> bool {
+        matches!(self, Self::Rgba8 | Self::Rgba16)
+    }
+
+    /// The bitmap info describing this format.
+    pub fn bitmap_info(self) -> CGBitmapInfo {
+        let alpha = self.alpha_info(self.is_straight_alpha());
+        CGBitmapInfo(alpha.0) | self.byte_order()
+    }
+
+    fn alpha_info(self, straight: bool) -> crate::CGImageAlphaInfo {
+        use crate::CGImageAlphaInfo;
+        match self {
+            Self::Bgra8Premultiplied => CGImageAlphaInfo::PremultipliedFirst,
+            Self::Gray8 | Self::Gray16 => CGImageAlphaInfo::None,
+            _ if straight => CGImageAlphaInfo::Last,
+            _ => CGImageAlphaInfo::PremultipliedLast,
+        }
+    }
+
+    fn byte_order(self) -> CGBitmapInfo {
+        match self {
+            Self::Bgra8Premultiplied => CGBitmapInfo::ByteOrder32Little,
+            Self::Rgba8Premultiplied | Self::Rgba8 | Self::Gray8 => CGBitmapInfo::ByteOrderDefault,
+            Self::Rgba16Premultiplied | Self::Rgba16 | Self::Gray16 => {
+                CGBitmapInfo::ByteOrder16Host
+            }
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "alloc",
+    feature = "CGBitmapContext",
+    feature = "CGColorSpace",
+    feature = "CGContext",
+    feature = "CGDataProvider"
+))]
+mod conversion {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::ffi::c_void;
+
+    use objc2_core_foundation::{CFData, CFRetained, CGFloat, CGPoint, CGRect, CGSize};
+
+    use super::CGImagePixelFormat;
+    use crate::{
+        kCGColorSpaceGenericGrayGamma2_2, kCGColorSpaceSRGB, CGBitmapContextCreate,
+        CGColorRenderingIntent, CGColorSpace, CGColorSpaceCreateWithName, CGContextDrawImage,
+        CGDataProviderCreateWithCFData, CGImage, CGImageCreate, CGImageGetHeight, CGImageGetWidth,
+    };
+
+    /// Pixel data rendered from a `CGImage`.
+    pub(crate) struct RenderedPixels {
+        pub(crate) data: Vec<u8>,
+        pub(crate) width: usize,
+        pub(crate) height: usize,
+        pub(crate) bytes_per_row: usize,
+    }
+
+    fn color_space(format: CGImagePixelFormat) -> Option<CFRetained<CGColorSpace>> {
+        let name = if format.components_per_pixel() == 1 {
+            unsafe { kCGColorSpaceGenericGrayGamma2_2 }
+        } else {
+            unsafe { kCGColorSpaceSRGB }
+        };
+        unsafe { CGColorSpaceCreateWithName(Some(name)) }
+    }
+
+    /// Convert premultiplied components to straight alpha, in-place.
+    fn unpremultiply_8(data: &mut [u8]) {
+        for pixel in data.chunks_exact_mut(4) {
+            let alpha = pixel[3] as u32;
+            if alpha != 0 && alpha != 255 {
+                for c in &mut pixel[..3] {
+                    *c = ((*c as u32 * 255 + alpha / 2) / alpha).min(255) as u8;
+                }
+            }
+        }
+    }
+
+    /// Same as `unpremultiply_8`, but for host-endian 16-bit components.
+    fn unpremultiply_16(data: &mut [u8]) {
+        for pixel in data.chunks_exact_mut(8) {
+            let get = |pixel: &[u8], i: usize| u16::from_ne_bytes([pixel[2 * i], pixel[2 * i + 1]]);
+            let alpha = get(pixel, 3) as u32;
+            if alpha != 0 && alpha != 0xFFFF {
+                for i in 0..3 {
+                    let c = get(pixel, i) as u32;
+                    let c = ((c * 0xFFFF + alpha / 2) / alpha).min(0xFFFF) as u16;
+                    pixel[2 * i..2 * i + 2].copy_from_slice(&c.to_ne_bytes());
+                }
+            }
+        }
+    }
+
+    impl CGImage {
+        /// Draw the image into a new buffer with the given pixel format.
+        ///
+        /// Rows are tightly packed (no padding).
+        pub(crate) fn render_pixels(&self, format: CGImagePixelFormat) -> Option<RenderedPixels> {
+            let width = unsafe { CGImageGetWidth(Some(self)) };
+            let height = unsafe { CGImageGetHeight(Some(self)) };
+            let bytes_per_row = width.checked_mul(format.bytes_per_pixel())?;
+            let mut data = vec![0u8; bytes_per_row.checked_mul(height)?];
+
+            // Bitmap contexts only support premultiplied alpha, so we draw
+            // premultiplied, and fix up the components afterwards.
+            let bitmap_info = format.alpha_info(false).0 | format.byte_order().0;
+            let space = color_space(format)?;
+            // SAFETY: The buffer is large enough for `height` rows of
+            // `bytes_per_row`, and outlives the context (which is dropped at
+            // the end of this block).
+            {
+                let context = unsafe {
+                    CGBitmapContextCreate(
+                        data.as_mut_ptr().cast::<c_void>(),
+                        width,
+                        height,
+                        format.bits_per_component(),
+                        bytes_per_row,
+                        Some(&space),
+                        bitmap_info,
+                    )
+                }?;
+                let rect = CGRect::new(
+                    CGPoint::ZERO,
+                    CGSize::new(width as CGFloat, height as CGFloat),
+                );
+                unsafe { CGContextDrawImage(Some(&context), rect, Some(self)) };
+            }
+
+            match format {
+                CGImagePixelFormat::Rgba8 => unpremultiply_8(&mut data),
+                CGImagePixelFormat::Rgba16 => unpremultiply_16(&mut data),
+                _ => {}
+            }
+
+            Some(RenderedPixels {
+                data,
+                width,
+                height,
+                bytes_per_row,
+            })
+        }
+
+        /// Create a new image from tightly packed pixels in the given format.
+        pub(crate) fn from_pixels(
+            format: CGImagePixelFormat,
+            width: usize,
+            height: usize,
+            data: &[u8],
+        ) -> Option<CFRetained<CGImage>> {
+            let bytes_per_row = width.checked_mul(format.bytes_per_pixel())?;
+            if bytes_per_row.checked_mul(height)? != data.len() {
+                return None;
+            }
+            let space = color_space(format)?;
+            let data = CFData::from_bytes(data);
+            let provider = unsafe { CGDataProviderCreateWithCFData(Some(&data)) }?;
+            unsafe {
+                CGImageCreate(
+                    width,
+                    height,
+                    format.bits_per_component(),
+                    format.bits_per_component() * format.components_per_pixel(),
+                    bytes_per_row,
+                    Some(&space),
+                    format.bitmap_info(),
+                    Some(&provider),
+                    core::ptr::null(),
+                    false,
+                    CGColorRenderingIntent::RenderingIntentDefault,
+                )
+            }
+        }
+
+        /// Convert the image to a new image with the given pixel layout.
+        ///
+        /// This works by redrawing the image into a bitmap context, so it
+        /// also handles any source layout that CoreGraphics itself can draw
+        /// (e.g. converting from premultiplied BGRA screen captures to the
+        /// straight RGBA that most Rust image crates expect).
+        ///
+        /// Returns `None` if the context or image could not be created, for
+        /// example if the image is too large.
+        ///
+        ///
+        /// # Examples
+        ///
+        /// ```ignore
+        /// use objc2_core_graphics::{CGImage, CGImagePixelFormat};
+        ///
+        /// let image: &CGImage;
+        /// # image = todo!();
+        /// let rgba = image.convert_to(CGImagePixelFormat::Rgba8).unwrap();
+        /// ```
+        #[doc(alias = "CGBitmapContextCreate")]
+        #[doc(alias = "CGContextDrawImage")]
+        pub fn convert_to(&self, format: CGImagePixelFormat) -> Option<CFRetained<CGImage>> {
+            let pixels = self.render_pixels(format)?;
+            debug_assert_eq!(pixels.bytes_per_row * pixels.height, pixels.data.len());
+            Self::from_pixels(format, pixels.width, pixels.height, &pixels.data)
+        }
+
+        /// Create a new image from tightly packed 8-bit RGBA pixels with
+        /// straight (non-premultiplied) alpha, in the sRGB color space.
+        ///
+        /// Returns `None` if `data` is not exactly `width * height * 4`
+        /// bytes long, or if the image could not be created.
+        ///
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use objc2_core_graphics::CGImage;
+        ///
+        /// // A 2x1 image with a red and a half-transparent blue pixel.
+        /// let pixels = [255, 0, 0, 255, 0, 0, 255, 128];
+        /// let image = CGImage::from_rgba8(2, 1, &pixels).unwrap();
+        /// assert_eq!(image.to_rgba8().unwrap(), pixels);
+        /// ```
+        #[doc(alias = "CGImageCreate")]
+        pub fn from_rgba8(width: usize, height: usize, data: &[u8]) -> Option<CFRetained<CGImage>> {
+            Self::from_pixels(CGImagePixelFormat::Rgba8, width, height, data)
+        }
+
+        /// Copy the pixels of the image into tightly packed 8-bit RGBA with
+        /// straight (non-premultiplied) alpha, in the sRGB color space.
+        ///
+        /// The image is redrawn, so this works regardless of the image's
+        /// own pixel layout, alpha info and byte order.
+        ///
+        /// Returns `None` if the bitmap context could not be created, for
+        /// example if the image is too large.
+        #[doc(alias = "CGBitmapContextCreate")]
+        #[doc(alias = "CGContextDrawImage")]
+        pub fn to_rgba8(&self) -> Option<Vec<u8>> {
+            let pixels = self.render_pixels(CGImagePixelFormat::Rgba8)?;
+            Some(pixels.data)
+        }
+
+        /// Convert the image to an [`image::RgbaImage`].
+        ///
+        /// See [`to_rgba8`](Self::to_rgba8) for details.
+        #[cfg(feature = "image")]
+        pub fn to_rgba_image(&self) -> Option<image::RgbaImage> {
+            let pixels = self.render_pixels(CGImagePixelFormat::Rgba8)?;
+            image::RgbaImage::from_raw(
+                pixels.width.try_into().ok()?,
+                pixels.height.try_into().ok()?,
+                pixels.data,
+            )
+        }
+
+        /// Create a new image from an [`image::RgbaImage`].
+        ///
+        /// Other kinds of images can be converted with
+        /// [`image::DynamicImage::to_rgba8`] first.
+        ///
+        /// See [`from_rgba8`](Self::from_rgba8) for details.
+        #[cfg(feature = "image")]
+        pub fn from_rgba_image(image: &image::RgbaImage) -> Option<CFRetained<CGImage>> {
+            Self::from_rgba8(
+                image.width() as usize,
+                image.height() as usize,
+                image.as_raw(),
+            )
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn unpremultiply_8_bit() {
+            let mut data = [
+                // Transparent, left untouched.
+                0, 0, 0, 0, //
+                // Opaque, left untouched.
+                10, 20, 30, 255, //
+                // Half transparent, rounded to nearest.
+                64, 32, 0, 128, //
+                // Invalid, components larger than alpha are clamped.
+                200, 128, 1, 128,
+            ];
+            unpremultiply_8(&mut data);
+            assert_eq!(
+                data,
+                [0, 0, 0, 0, 10, 20, 30, 255, 128, 64, 0, 128, 255, 255, 2, 128]
+            );
+        }
+
+        #[test]
+        fn unpremultiply_16_bit() {
+            fn pixel(components: [u16; 4]) -> Vec<u8> {
+                components.iter().flat_map(|c| c.to_ne_bytes()).collect()
+            }
+
+            let mut data = [
+                pixel([0, 0, 0, 0]),
+                pixel([1000, 2000, 3000, 0xFFFF]),
+                pixel([0x4000, 0x2000, 0, 0x8000]),
+                pixel([0xFFFF, 0x8000, 1, 0x8000]),
+            ]
+            .concat();
+            unpremultiply_16(&mut data);
+            let expected = [
+                pixel([0, 0, 0, 0]),
+                pixel([1000, 2000, 3000, 0xFFFF]),
+                pixel([0x8000, 0x4000, 0, 0x8000]),
+                pixel([0xFFFF, 0xFFFF, 2, 0x8000]),
+            ]
+            .concat();
+            assert_eq!(data, expected);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORMATS: [CGImagePixelFormat; 7] = [
+        CGImagePixelFormat::Bgra8Premultiplied,
+        CGImagePixelFormat::Rgba8Premultiplied,
+        CGImagePixelFormat::Rgba8,
+        CGImagePixelFormat::Rgba16Premultiplied,
+        CGImagePixelFormat::Rgba16,
+        CGImagePixelFormat::Gray8,
+        CGImagePixelFormat::Gray16,
+    ];
+
+    #[test]
+    fn layout() {
+        let layouts: [(usize, usize, usize); 7] = [
+            (8, 4, 4),
+            (8, 4, 4),
+            (8, 4, 4),
+            (16, 4, 8),
+            (16, 4, 8),
+            (8, 1, 1),
+            (16, 1, 2),
+        ];
+        for (format, (bits, components, bytes)) in FORMATS.into_iter().zip(layouts) {
+            assert_eq!(format.bits_per_component(), bits, "{format:?}");
+            assert_eq!(format.components_per_pixel(), components, "{format:?}");
+            assert_eq!(format.bytes_per_pixel(), bytes, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn bitmap_info() {
+        // `kCGImageAlpha*` values.
+        let none = 0;
+        let premultiplied_last = 1;
+        let premultiplied_first = 2;
+        let last = 3;
+        // `kCGBitmapByteOrder*` values.
+        let default = 0;
+        let order16_host = if cfg!(target_endian = "big") {
+            3 << 12
+        } else {
+            1 << 12
+        };
+        let order32_little = 2 << 12;
+
+        let expected = [
+            premultiplied_first | order32_little,
+            premultiplied_last | default,
+            last | default,
+            premultiplied_last | order16_host,
+            last | order16_host,
+            none | default,
+            none | order16_host,
+        ];
+        for (format, expected) in FORMATS.into_iter().zip(expected) {
+            assert_eq!(format.bitmap_info().0, expected, "{format:?}");
+            assert_eq!(
+                format.is_straight_alpha(),
+                expected & 0x1F == last,
+                "{format:?}"
+            );
+        }
+    }
+}
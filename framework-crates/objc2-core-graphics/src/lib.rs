@@ -15,11 +15,73 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(all(feature = "CGColorSpace", feature = "CGImage"))]
+mod bitmap;
+#[cfg(all(
+    feature = "CGColorConversionInfo",
+    feature = "CGColorSpace",
+    feature = "CGImage"
+))]
+mod color_conversion;
+#[cfg(feature = "CGContext")]
+mod context;
+#[cfg(all(feature = "alloc", feature = "CGDataProvider"))]
+mod data_provider;
+#[cfg(all(feature = "alloc", feature = "CGEvent", feature = "CGEventTypes"))]
+mod event_tap;
+#[cfg(all(feature = "alloc", feature = "CGFunction"))]
+mod function;
 mod generated;
 #[cfg(feature = "CGImage")]
 mod image;
+#[cfg(feature = "CGPath")]
+mod path;
+#[cfg(all(
+    feature = "alloc",
+    feature = "CGContext",
+    feature = "CGPDFContext",
+    feature = "CGPDFDocument"
+))]
+mod pdf_context;
+#[cfg(all(
+    feature = "alloc",
+    feature = "CGColorSpace",
+    feature = "CGFunction",
+    feature = "CGShading"
+))]
+mod shading;
+#[cfg(all(feature = "CGColorSpace", feature = "CGImage"))]
+pub use self::bitmap::{CGBitmapLayout, CGBitmapLayoutError};
+#[cfg(all(
+    feature = "CGColorConversionInfo",
+    feature = "CGColorSpace",
+    feature = "CGImage"
+))]
+pub use self::color_conversion::CGColorConversionError;
+#[cfg(feature = "CGContext")]
+pub use self::context::{CGContextStateGuard, CGContextTransparencyLayerGuard};
+#[cfg(all(feature = "alloc", feature = "CGEvent", feature = "CGEventTypes"))]
+pub use self::event_tap::{CGEventTap, CGEventTapResult};
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(feature = "CGImage")]
+pub use self::image::CGImagePixelFormat;
+#[cfg(feature = "CGPath")]
+pub use self::path::CGPathSegment;
+#[cfg(all(
+    feature = "alloc",
+    feature = "CGContext",
+    feature = "CGPDFContext",
+    feature = "CGPDFDocument"
+))]
+pub use self::pdf_context::{CGPDFContextOptions, CGPDFEncryption, CGPDFOptionsError};
+#[cfg(all(
+    feature = "alloc",
+    feature = "CGColorSpace",
+    feature = "CGFunction",
+    feature = "CGShading"
+))]
+pub use self::shading::CGShadingExtend;
 
 /// [Apple's documentation](https://developer.apple.com/documentation/coregraphics/kcgfontindexmax?language=objc)
 #[allow(non_upper_case_globals)]
@@ -0,0 +1,395 @@
+//! Building paths, and iterating over their elements.
+use core::ptr;
+
+use objc2_core_foundation::{CFRetained, CGAffineTransform, CGFloat, CGPoint, CGRect};
+
+use crate::{
+    CGMutablePath, CGPath, CGPathAddArc, CGPathAddArcToPoint, CGPathAddCurveToPoint,
+    CGPathAddEllipseInRect, CGPathAddLineToPoint, CGPathAddLines, CGPathAddPath,
+    CGPathAddQuadCurveToPoint, CGPathAddRect, CGPathAddRelativeArc, CGPathAddRoundedRect,
+    CGPathCloseSubpath, CGPathCreateMutable, CGPathMoveToPoint,
+};
+
+/// A single element of a path.
+///
+/// This is the owned equivalent of [`CGPathElement`], and maps directly onto
+/// the path representations in crates like `kurbo` and `lyon`.
+///
+/// [`CGPathElement`]: crate::CGPathElement
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[doc(alias = "CGPathElement")]
+#[doc(alias = "CGPathElementType")]
+pub enum CGPathSegment {
+    /// Start a new subpath at the point.
+    #[doc(alias = "kCGPathElementMoveToPoint")]
+    MoveTo(CGPoint),
+    /// A straight line from the current point to the point.
+    #[doc(alias = "kCGPathElementAddLineToPoint")]
+    LineTo(CGPoint),
+    /// A quadratic Bézier curve from the current point to `end`.
+    #[doc(alias = "kCGPathElementAddQuadCurveToPoint")]
+    QuadCurveTo {
+        /// The control point.
+        control: CGPoint,
+        /// The end point.
+        end: CGPoint,
+    },
+    /// A cubic Bézier curve from the current point to `end`.
+    #[doc(alias = "kCGPathElementAddCurveToPoint")]
+    CurveTo {
+        /// The first control point.
+        control1: CGPoint,
+        /// The second control point.
+        control2: CGPoint,
+        /// The end point.
+        end: CGPoint,
+    },
+    /// Close the current subpath with a straight line to its start.
+    #[doc(alias = "kCGPathElementCloseSubpath")]
+    Close,
+}
+
+#[cfg(feature = "block2")]
+impl CGPathSegment {
+    /// # Safety
+    ///
+    /// The element's points must be valid for its type.
+    unsafe fn from_element(element: &crate::CGPathElement) -> Option<Self> {
+        use crate::CGPathElementType;
+
+        let point = |i: usize| unsafe { element.points.as_ptr().add(i).read() };
+        Some(match element.r#type {
+            CGPathElementType::MoveToPoint => Self::MoveTo(point(0)),
+            CGPathElementType::AddLineToPoint => Self::LineTo(point(0)),
+            CGPathElementType::AddQuadCurveToPoint => Self::QuadCurveTo {
+                control: point(0),
+                end: point(1),
+            },
+            CGPathElementType::AddCurveToPoint => Self::CurveTo {
+                control1: point(0),
+                control2: point(1),
+                end: point(2),
+            },
+            CGPathElementType::CloseSubpath => Self::Close,
+            _ => return None,
+        })
+    }
+}
+
+fn transform_ptr(transform: Option<&CGAffineTransform>) -> *const CGAffineTransform {
+    transform.map_or(ptr::null(), |transform| transform)
+}
+
+/// Iterating over elements.
+#[cfg(feature = "block2")]
+impl CGPath {
+    /// Call the closure with each element of the path, in order.
+    ///
+    /// Element types that are unknown to this crate are skipped.
+    ///
+    ///
+    /// # Examples
+    ///
+    /// Convert the path to an SVG path string.
+    ///
+    /// ```
+    /// use std::fmt::Write;
+    ///
+    /// use objc2_core_foundation::CGPoint;
+    /// use objc2_core_graphics::{CGMutablePath, CGPathSegment};
+    ///
+    /// let path = CGMutablePath::new();
+    /// path.move_to(CGPoint::new(0.0, 0.0), None);
+    /// path.line_to(CGPoint::new(10.0, 0.0), None);
+    /// path.close_subpath();
+    ///
+    /// let mut svg = String::new();
+    /// path.for_each_segment(|segment| match segment {
+    ///     CGPathSegment::MoveTo(p) => write!(svg, "M{} {}", p.x, p.y).unwrap(),
+    ///     CGPathSegment::LineTo(p) => write!(svg, "L{} {}", p.x, p.y).unwrap(),
+    ///     CGPathSegment::QuadCurveTo { control: c, end: p } => {
+    ///         write!(svg, "Q{} {} {} {}", c.x, c.y, p.x, p.y).unwrap()
+    ///     }
+    ///     CGPathSegment::CurveTo { control1: c1, control2: c2, end: p } => {
+    ///         write!(svg, "C{} {} {} {} {} {}", c1.x, c1.y, c2.x, c2.y, p.x, p.y).unwrap()
+    ///     }
+    ///     CGPathSegment::Close => svg.push('Z'),
+    /// });
+    /// assert_eq!(svg, "M0 0L10 0Z");
+    /// ```
+    #[doc(alias = "CGPathApplyWithBlock")]
+    pub fn for_each_segment(&self, f: impl FnMut(CGPathSegment)) {
+        use core::cell::RefCell;
+        use core::ptr::NonNull;
+
+        use block2::{Block, StackBlock};
+
+        // Iterate over a copy, such that the closure can modify the path if
+        // it is mutable. This is cheap for immutable paths, where it just
+        // retains the path.
+        let path = unsafe { crate::CGPathCreateCopy(Some(self)) }.expect("failed copying path");
+
+        // The block is only called from the current thread, one element at
+        // a time, so it's fine to give it mutable access to the closure.
+        let f = RefCell::new(f);
+        let block = StackBlock::new(|element: NonNull<crate::CGPathElement>| {
+            // SAFETY: The element and its points are valid for the duration
+            // of the call.
+            if let Some(segment) = unsafe { CGPathSegment::from_element(element.as_ref()) } {
+                (f.borrow_mut())(segment);
+            }
+        });
+        let block: &Block<dyn Fn(NonNull<crate::CGPathElement>) + '_> = &block;
+        // SAFETY: The block is only used for the duration of the call, so
+        // it's fine to erase its lifetime.
+        let block = (block as *const Block<_>).cast_mut().cast();
+        unsafe { crate::CGPathApplyWithBlock(&path, block) };
+    }
+
+    /// The elements of the path, in order.
+    ///
+    /// See [`for_each_segment`](Self::for_each_segment) for details.
+    #[cfg(feature = "alloc")]
+    #[doc(alias = "CGPathApplyWithBlock")]
+    pub fn segments(&self) -> alloc::vec::Vec<CGPathSegment> {
+        let mut segments = alloc::vec::Vec::new();
+        self.for_each_segment(|segment| segments.push(segment));
+        segments
+    }
+}
+
+/// Building paths.
+///
+/// The methods that take a `transform` apply it to the given coordinates
+/// before adding them to the path.
+impl CGMutablePath {
+    /// Create a new, empty path.
+    #[doc(alias = "CGPathCreateMutable")]
+    pub fn new() -> CFRetained<Self> {
+        unsafe { CGPathCreateMutable() }
+    }
+
+    /// Create a new path from the given elements.
+    ///
+    /// This is useful for converting paths from other crates, such as
+    /// `kurbo` or `lyon`.
+    pub fn from_segments(segments: impl IntoIterator<Item = CGPathSegment>) -> CFRetained<Self> {
+        let path = Self::new();
+        for segment in segments {
+            path.push_segment(segment, None);
+        }
+        path
+    }
+
+    /// Add an element to the path.
+    pub fn push_segment(&self, segment: CGPathSegment, transform: Option<&CGAffineTransform>) {
+        match segment {
+            CGPathSegment::MoveTo(point) => self.move_to(point, transform),
+            CGPathSegment::LineTo(point) => self.line_to(point, transform),
+            CGPathSegment::QuadCurveTo { control, end } => {
+                self.quad_curve_to(control, end, transform)
+            }
+            CGPathSegment::CurveTo {
+                control1,
+                control2,
+                end,
+            } => self.curve_to(control1, control2, end, transform),
+            CGPathSegment::Close => self.close_subpath(),
+        }
+    }
+
+    /// Start a new subpath at the given point.
+    #[doc(alias = "CGPathMoveToPoint")]
+    pub fn move_to(&self, point: CGPoint, transform: Option<&CGAffineTransform>) {
+        unsafe { CGPathMoveToPoint(Some(self), transform_ptr(transform), point.x, point.y) }
+    }
+
+    /// Add a straight line from the current point to the given point.
+    #[doc(alias = "CGPathAddLineToPoint")]
+    pub fn line_to(&self, point: CGPoint, transform: Option<&CGAffineTransform>) {
+        unsafe { CGPathAddLineToPoint(Some(self), transform_ptr(transform), point.x, point.y) }
+    }
+
+    /// Add a quadratic Bézier curve from the current point to `end`.
+    #[doc(alias = "CGPathAddQuadCurveToPoint")]
+    pub fn quad_curve_to(
+        &self,
+        control: CGPoint,
+        end: CGPoint,
+        transform: Option<&CGAffineTransform>,
+    ) {
+        unsafe {
+            CGPathAddQuadCurveToPoint(
+                Some(self),
+                transform_ptr(transform),
+                control.x,
+                control.y,
+                end.x,
+                end.y,
+            )
+        }
+    }
+
+    /// Add a cubic Bézier curve from the current point to `end`.
+    #[doc(alias = "CGPathAddCurveToPoint")]
+    pub fn curve_to(
+        &self,
+        control1: CGPoint,
+        control2: CGPoint,
+        end: CGPoint,
+        transform: Option<&CGAffineTransform>,
+    ) {
+        unsafe {
+            CGPathAddCurveToPoint(
+                Some(self),
+                transform_ptr(transform),
+                control1.x,
+                control1.y,
+                control2.x,
+                control2.y,
+                end.x,
+                end.y,
+            )
+        }
+    }
+
+    /// Close the current subpath with a straight line to its start.
+    #[doc(alias = "CGPathCloseSubpath")]
+    pub fn close_subpath(&self) {
+        unsafe { CGPathCloseSubpath(Some(self)) }
+    }
+
+    /// Add a rectangle as a new closed subpath.
+    #[doc(alias = "CGPathAddRect")]
+    pub fn add_rect(&self, rect: CGRect, transform: Option<&CGAffineTransform>) {
+        unsafe { CGPathAddRect(Some(self), transform_ptr(transform), rect) }
+    }
+
+    /// Add a rectangle with rounded corners as a new closed subpath.
+    ///
+    /// The corner width and height must be non-negative, and at most half
+    /// the width and height of the rectangle, otherwise CoreGraphics logs an
+    /// error and does not add anything.
+    #[doc(alias = "CGPathAddRoundedRect")]
+    pub fn add_rounded_rect(
+        &self,
+        rect: CGRect,
+        corner_width: CGFloat,
+        corner_height: CGFloat,
+        transform: Option<&CGAffineTransform>,
+    ) {
+        unsafe {
+            CGPathAddRoundedRect(
+                Some(self),
+                transform_ptr(transform),
+                rect,
+                corner_width,
+                corner_height,
+            )
+        }
+    }
+
+    /// Add an ellipse that fits inside the rectangle as a new closed
+    /// subpath.
+    #[doc(alias = "CGPathAddEllipseInRect")]
+    pub fn add_ellipse_in_rect(&self, rect: CGRect, transform: Option<&CGAffineTransform>) {
+        unsafe { CGPathAddEllipseInRect(Some(self), transform_ptr(transform), rect) }
+    }
+
+    /// Add an arc of a circle from `start_angle` to `end_angle`, in radians.
+    ///
+    /// If the path already has a current point, a straight line is added
+    /// from it to the start of the arc.
+    #[doc(alias = "CGPathAddArc")]
+    pub fn add_arc(
+        &self,
+        center: CGPoint,
+        radius: CGFloat,
+        start_angle: CGFloat,
+        end_angle: CGFloat,
+        clockwise: bool,
+        transform: Option<&CGAffineTransform>,
+    ) {
+        unsafe {
+            CGPathAddArc(
+                Some(self),
+                transform_ptr(transform),
+                center.x,
+                center.y,
+                radius,
+                start_angle,
+                end_angle,
+                clockwise,
+            )
+        }
+    }
+
+    /// Add an arc of a circle starting at `start_angle` and spanning
+    /// `delta`, in radians.
+    ///
+    /// The direction of the arc is determined by the sign of `delta`.
+    #[doc(alias = "CGPathAddRelativeArc")]
+    pub fn add_relative_arc(
+        &self,
+        center: CGPoint,
+        radius: CGFloat,
+        start_angle: CGFloat,
+        delta: CGFloat,
+        transform: Option<&CGAffineTransform>,
+    ) {
+        unsafe {
+            CGPathAddRelativeArc(
+                Some(self),
+                transform_ptr(transform),
+                center.x,
+                center.y,
+                radius,
+                start_angle,
+                delta,
+            )
+        }
+    }
+
+    /// Add an arc with the given radius that is tangent to the line from
+    /// the current point to `tangent1`, and to the line from `tangent1` to
+    /// `tangent2`.
+    #[doc(alias = "CGPathAddArcToPoint")]
+    pub fn add_arc_to_point(
+        &self,
+        tangent1: CGPoint,
+        tangent2: CGPoint,
+        radius: CGFloat,
+        transform: Option<&CGAffineTransform>,
+    ) {
+        unsafe {
+            CGPathAddArcToPoint(
+                Some(self),
+                transform_ptr(transform),
+                tangent1.x,
+                tangent1.y,
+                tangent2.x,
+                tangent2.y,
+                radius,
+            )
+        }
+    }
+
+    /// Add straight lines between the given points as a new subpath.
+    #[doc(alias = "CGPathAddLines")]
+    pub fn add_lines(&self, points: &[CGPoint], transform: Option<&CGAffineTransform>) {
+        unsafe {
+            CGPathAddLines(
+                Some(self),
+                transform_ptr(transform),
+                points.as_ptr(),
+                points.len(),
+            )
+        }
+    }
+
+    /// Add the elements of another path.
+    #[doc(alias = "CGPathAddPath")]
+    pub fn add_path(&self, path: &CGPath, transform: Option<&CGAffineTransform>) {
+        unsafe { CGPathAddPath(Some(self), transform_ptr(transform), Some(path)) }
+    }
+}
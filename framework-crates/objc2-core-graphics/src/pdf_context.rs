@@ -0,0 +1,368 @@
+//! Typed options for creating PDF contexts.
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use core::fmt;
+use core::ptr;
+
+use objc2_core_foundation::{
+    kCFTypeDictionaryKeyCallBacks, kCFTypeDictionaryValueCallBacks, CFBoolean, CFDictionary,
+    CFDictionaryCreateMutable, CFDictionarySetValue, CFMutableDictionary, CFNumber, CFRetained,
+    CFString, CGRect, CFURL,
+};
+
+use crate::{
+    kCGPDFContextAccessPermissions, kCGPDFContextAllowsCopying, kCGPDFContextAllowsPrinting,
+    kCGPDFContextAuthor, kCGPDFContextCreator, kCGPDFContextEncryptionKeyLength,
+    kCGPDFContextKeywords, kCGPDFContextOwnerPassword, kCGPDFContextSubject, kCGPDFContextTitle,
+    kCGPDFContextUserPassword, CGContext, CGPDFAccessPermissions, CGPDFContextCreateWithURL,
+};
+
+/// The maximum number of bytes of a password that are used.
+const MAX_PASSWORD_LEN: usize = 32;
+
+/// An error describing why PDF encryption options are invalid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CGPDFOptionsError {
+    /// A password contained non-ASCII characters.
+    NonAsciiPassword,
+    /// A password was longer than 32 bytes, and would have been truncated.
+    PasswordTooLong(usize),
+    /// The encryption key length was not a multiple of 8 between 40 and 128.
+    InvalidKeyLength(usize),
+}
+
+impl fmt::Display for CGPDFOptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonAsciiPassword => f.write_str("PDF password must only contain ASCII"),
+            Self::PasswordTooLong(len) => write!(
+                f,
+                "PDF password was {len} bytes long, but only {MAX_PASSWORD_LEN} bytes are used",
+            ),
+            Self::InvalidKeyLength(bits) => write!(
+                f,
+                "PDF encryption key length must be a multiple of 8 between 40 and 128, but was {bits}",
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")] // use core::error::Error from Rust 1.81 once in MSRV.
+impl std::error::Error for CGPDFOptionsError {}
+
+fn validate_password(password: &str) -> Result<(), CGPDFOptionsError> {
+    if !password.is_ascii() {
+        Err(CGPDFOptionsError::NonAsciiPassword)
+    } else if password.len() > MAX_PASSWORD_LEN {
+        Err(CGPDFOptionsError::PasswordTooLong(password.len()))
+    } else {
+        Ok(())
+    }
+}
+
+/// The encryption of a PDF document.
+///
+/// The owner password gives full access to the document, while the user
+/// password (empty by default) only gives access according to the
+/// [permissions](Self::permissions).
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct CGPDFEncryption {
+    owner_password: String,
+    user_password: String,
+    key_length: usize,
+    permissions: CGPDFAccessPermissions,
+}
+
+impl CGPDFEncryption {
+    /// Encrypt the document with the given owner password.
+    ///
+    /// By default, the key length is 128 bits, and users without the owner
+    /// password are allowed to print the document and copy its content.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the password contains non-ASCII characters, or is
+    /// longer than 32 bytes.
+    #[doc(alias = "kCGPDFContextOwnerPassword")]
+    pub fn new(owner_password: &str) -> Result<Self, CGPDFOptionsError> {
+        validate_password(owner_password)?;
+        Ok(Self {
+            owner_password: owner_password.into(),
+            user_password: String::new(),
+            key_length: 128,
+            permissions: CGPDFAccessPermissions::AllowsLowQualityPrinting
+                | CGPDFAccessPermissions::AllowsHighQualityPrinting
+                | CGPDFAccessPermissions::AllowsContentCopying
+                | CGPDFAccessPermissions::AllowsContentAccessibility,
+        })
+    }
+
+    /// Require the given password to open the document.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Same as [`new`](Self::new).
+    #[doc(alias = "kCGPDFContextUserPassword")]
+    pub fn user_password(mut self, user_password: &str) -> Result<Self, CGPDFOptionsError> {
+        validate_password(user_password)?;
+        self.user_password = user_password.into();
+        Ok(self)
+    }
+
+    /// Set the length of the encryption key, in bits.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the length is not a multiple of 8 between 40 and
+    /// 128.
+    #[doc(alias = "kCGPDFContextEncryptionKeyLength")]
+    pub fn key_length(mut self, bits: usize) -> Result<Self, CGPDFOptionsError> {
+        if !(40..=128).contains(&bits) || bits % 8 != 0 {
+            return Err(CGPDFOptionsError::InvalidKeyLength(bits));
+        }
+        self.key_length = bits;
+        Ok(self)
+    }
+
+    /// Set what users without the owner password are allowed to do.
+    #[doc(alias = "kCGPDFContextAccessPermissions")]
+    pub fn permissions(mut self, permissions: CGPDFAccessPermissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+}
+
+impl fmt::Debug for CGPDFEncryption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Don't leak the passwords in logs.
+        f.debug_struct("CGPDFEncryption")
+            .field("key_length", &self.key_length)
+            .field("permissions", &self.permissions)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The document information and encryption of a PDF context.
+///
+/// This builds the auxiliary info dictionary given to `CGPDFContextCreate`
+/// and `CGPDFContextCreateWithURL`.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2_core_graphics::{
+///     CGPDFAccessPermissions, CGPDFContextOptions, CGPDFEncryption, CGPDFOptionsError,
+/// };
+///
+/// // Passwords are validated up front.
+/// assert_eq!(
+///     CGPDFEncryption::new("pässword"),
+///     Err(CGPDFOptionsError::NonAsciiPassword),
+/// );
+///
+/// let encryption = CGPDFEncryption::new("owner")?
+///     .user_password("user")?
+///     .permissions(CGPDFAccessPermissions::AllowsHighQualityPrinting);
+/// let options = CGPDFContextOptions::new()
+///     .title("Quarterly Report")
+///     .author("Jane Doe")
+///     .keywords(["finance", "2024"])
+///     .encryption(encryption);
+/// let auxiliary_info = options.to_dictionary();
+/// assert_eq!(auxiliary_info.len(), 9);
+/// # Ok::<(), CGPDFOptionsError>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CGPDFContextOptions {
+    title: Option<String>,
+    author: Option<String>,
+    subject: Option<String>,
+    creator: Option<String>,
+    keywords: Vec<String>,
+    encryption: Option<CGPDFEncryption>,
+}
+
+impl CGPDFContextOptions {
+    /// Options without any document information or encryption.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the title of the document.
+    #[doc(alias = "kCGPDFContextTitle")]
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set the author of the document.
+    #[doc(alias = "kCGPDFContextAuthor")]
+    pub fn author(mut self, author: &str) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    /// Set the subject of the document.
+    #[doc(alias = "kCGPDFContextSubject")]
+    pub fn subject(mut self, subject: &str) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Set the name of the application that created the document.
+    #[doc(alias = "kCGPDFContextCreator")]
+    pub fn creator(mut self, creator: &str) -> Self {
+        self.creator = Some(creator.into());
+        self
+    }
+
+    /// Set the keywords of the document.
+    #[doc(alias = "kCGPDFContextKeywords")]
+    pub fn keywords<I>(mut self, keywords: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.keywords = keywords
+            .into_iter()
+            .map(|keyword| keyword.as_ref().into())
+            .collect();
+        self
+    }
+
+    /// Encrypt the document.
+    pub fn encryption(mut self, encryption: CGPDFEncryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    /// Create the auxiliary info dictionary for the options.
+    pub fn to_dictionary(&self) -> CFRetained<CFDictionary> {
+        let dictionary = Dictionary::new();
+        let strings = [
+            (unsafe { kCGPDFContextTitle }, &self.title),
+            (unsafe { kCGPDFContextAuthor }, &self.author),
+            (unsafe { kCGPDFContextSubject }, &self.subject),
+            (unsafe { kCGPDFContextCreator }, &self.creator),
+        ];
+        for (key, value) in strings {
+            if let Some(value) = value {
+                dictionary.set(key, &*CFString::from_str(value));
+            }
+        }
+        if !self.keywords.is_empty() {
+            let keywords = self.keywords.join(", ");
+            dictionary.set(
+                unsafe { kCGPDFContextKeywords },
+                &*CFString::from_str(&keywords),
+            );
+        }
+
+        if let Some(encryption) = &self.encryption {
+            let owner_password = CFString::from_str(&encryption.owner_password);
+            dictionary.set(unsafe { kCGPDFContextOwnerPassword }, &*owner_password);
+            let user_password = CFString::from_str(&encryption.user_password);
+            dictionary.set(unsafe { kCGPDFContextUserPassword }, &*user_password);
+            let key_length = CFRetained::<CFNumber>::from(encryption.key_length as i32);
+            dictionary.set(unsafe { kCGPDFContextEncryptionKeyLength }, &*key_length);
+
+            let permissions = encryption.permissions;
+            let access_permissions = CFRetained::<CFNumber>::from(permissions.0);
+            dictionary.set(
+                unsafe { kCGPDFContextAccessPermissions },
+                &*access_permissions,
+            );
+            // Older versions of macOS only read these.
+            let allows_printing = permissions.intersects(
+                CGPDFAccessPermissions::AllowsLowQualityPrinting
+                    | CGPDFAccessPermissions::AllowsHighQualityPrinting,
+            );
+            dictionary.set(
+                unsafe { kCGPDFContextAllowsPrinting },
+                CFBoolean::new(allows_printing),
+            );
+            let allows_copying = permissions.contains(CGPDFAccessPermissions::AllowsContentCopying);
+            dictionary.set(
+                unsafe { kCGPDFContextAllowsCopying },
+                CFBoolean::new(allows_copying),
+            );
+        }
+
+        dictionary.finish()
+    }
+
+    /// Create a PDF context that writes to the given URL.
+    ///
+    /// `media_box` is the default size of the pages, and defaults to US
+    /// Letter (612 by 792 points).
+    #[doc(alias = "CGPDFContextCreateWithURL")]
+    pub fn create_with_url(
+        &self,
+        url: &CFURL,
+        media_box: Option<CGRect>,
+    ) -> Option<CFRetained<CGContext>> {
+        let media_box = media_box
+            .as_ref()
+            .map_or(ptr::null(), |rect| rect as *const _);
+        let auxiliary_info = self.to_dictionary();
+        // SAFETY: The media box is either NULL or a valid pointer, and the
+        // auxiliary info only contains valid keys and values.
+        unsafe { CGPDFContextCreateWithURL(Some(url), media_box, Some(&auxiliary_info)) }
+    }
+
+    /// Create a PDF context that writes to the given data consumer.
+    ///
+    /// See [`create_with_url`](Self::create_with_url).
+    #[cfg(feature = "CGDataConsumer")]
+    #[doc(alias = "CGPDFContextCreate")]
+    pub fn create_with_consumer(
+        &self,
+        consumer: &crate::CGDataConsumer,
+        media_box: Option<CGRect>,
+    ) -> Option<CFRetained<CGContext>> {
+        let media_box = media_box
+            .as_ref()
+            .map_or(ptr::null(), |rect| rect as *const _);
+        let auxiliary_info = self.to_dictionary();
+        // SAFETY: Same as in `create_with_url`.
+        unsafe { crate::CGPDFContextCreate(Some(consumer), media_box, Some(&auxiliary_info)) }
+    }
+}
+
+/// A `CFMutableDictionary` with `CFString` keys and values of any type.
+struct Dictionary(CFRetained<CFMutableDictionary>);
+
+impl Dictionary {
+    fn new() -> Self {
+        let dictionary = unsafe {
+            CFDictionaryCreateMutable(
+                None,
+                0,
+                &kCFTypeDictionaryKeyCallBacks,
+                &kCFTypeDictionaryValueCallBacks,
+            )
+        };
+        Self(dictionary.expect("failed creating CFMutableDictionary"))
+    }
+
+    fn set<T>(&self, key: &CFString, value: &T) {
+        let key: *const CFString = key;
+        let value: *const T = value;
+        // SAFETY: The dictionary retains CF types, and both the key and the
+        // value are CF types.
+        unsafe {
+            CFDictionarySetValue(Some(&self.0), key.cast::<c_void>(), value.cast::<c_void>())
+        };
+    }
+
+    fn finish(self) -> CFRetained<CFDictionary> {
+        // SAFETY: `CFMutableDictionary` is a subclass of `CFDictionary`.
+        unsafe { CFRetained::cast_unchecked(self.0) }
+    }
+}
@@ -0,0 +1,143 @@
+//! Creating shadings from Rust closures.
+use alloc::vec;
+
+use objc2_core_foundation::{CFRetained, CGFloat, CGPoint};
+
+use crate::{
+    CGColorSpace, CGColorSpaceGetNumberOfComponents, CGFunction, CGShading, CGShadingCreateAxial,
+    CGShadingCreateRadial,
+};
+
+/// Whether a shading extends beyond its starting and ending points.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CGShadingExtend {
+    /// Fill the area before the starting point with the starting color.
+    pub start: bool,
+    /// Fill the area after the ending point with the ending color.
+    pub end: bool,
+}
+
+impl CGShadingExtend {
+    /// Only draw the shading between the starting and ending points.
+    pub const NONE: Self = Self {
+        start: false,
+        end: false,
+    };
+
+    /// Extend the shading in both directions.
+    pub const BOTH: Self = Self {
+        start: true,
+        end: true,
+    };
+}
+
+impl CGFunction {
+    /// Create a function suitable for drawing a gradient in the given color
+    /// space with a [`CGShading`].
+    ///
+    /// The closure is called with a position between `0.0` and `1.0`, and
+    /// must write the color components of the color space followed by an
+    /// alpha value, each between `0.0` and `1.0`.
+    ///
+    /// See [`CGFunction::new`] for details.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_core_foundation::CGPoint;
+    /// use objc2_core_graphics::{
+    ///     CGColorSpaceCreateDeviceRGB, CGFunction, CGShading, CGShadingExtend,
+    /// };
+    ///
+    /// let space = unsafe { CGColorSpaceCreateDeviceRGB() }.unwrap();
+    /// let function = CGFunction::new_gradient(&space, |t, color| {
+    ///     color.copy_from_slice(&[t, 0.0, 1.0 - t, 1.0]);
+    /// });
+    /// let shading = CGShading::new_axial(
+    ///     &space,
+    ///     CGPoint::new(0.0, 0.0),
+    ///     CGPoint::new(100.0, 0.0),
+    ///     &function,
+    ///     CGShadingExtend::BOTH,
+    /// );
+    /// ```
+    pub fn new_gradient<F>(space: &CGColorSpace, f: F) -> CFRetained<Self>
+    where
+        F: Fn(CGFloat, &mut [CGFloat]) + Send + Sync + 'static,
+    {
+        let components = unsafe { CGColorSpaceGetNumberOfComponents(Some(space)) };
+        let range = vec![[0.0, 1.0]; components + 1];
+        Self::new(&[[0.0, 1.0]], &range, move |input, output| {
+            f(input[0], output)
+        })
+    }
+}
+
+impl CGShading {
+    /// Create a shading that varies along the line between two points.
+    ///
+    /// The function must take one input value, and return the color
+    /// components of `space` followed by an alpha value, e.g. one created
+    /// with [`CGFunction::new_gradient`].
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if Core Graphics fails to create the shading, e.g. because the
+    /// function has the wrong number of values.
+    #[doc(alias = "CGShadingCreateAxial")]
+    pub fn new_axial(
+        space: &CGColorSpace,
+        start: CGPoint,
+        end: CGPoint,
+        function: &CGFunction,
+        extend: CGShadingExtend,
+    ) -> CFRetained<Self> {
+        unsafe {
+            CGShadingCreateAxial(
+                Some(space),
+                start,
+                end,
+                Some(function),
+                extend.start,
+                extend.end,
+            )
+        }
+        .expect("failed creating CGShading")
+    }
+
+    /// Create a shading that varies between two circles.
+    ///
+    /// See [`new_axial`](Self::new_axial) for the requirements on the
+    /// function.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if Core Graphics fails to create the shading.
+    #[doc(alias = "CGShadingCreateRadial")]
+    pub fn new_radial(
+        space: &CGColorSpace,
+        start: CGPoint,
+        start_radius: CGFloat,
+        end: CGPoint,
+        end_radius: CGFloat,
+        function: &CGFunction,
+        extend: CGShadingExtend,
+    ) -> CFRetained<Self> {
+        unsafe {
+            CGShadingCreateRadial(
+                Some(space),
+                start,
+                start_radius,
+                end,
+                end_radius,
+                Some(function),
+                extend.start,
+                extend.end,
+            )
+        }
+        .expect("failed creating CGShading")
+    }
+}
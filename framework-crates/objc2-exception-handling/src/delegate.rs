@@ -0,0 +1,382 @@
+//! Routing exceptions from `NSExceptionHandler` to a Rust closure.
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{BitOr, BitOrAssign};
+use core::ptr;
+
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, Bool, NSObjectProtocol};
+use objc2::{define_class, msg_send, msg_send_id, AllocAnyThread, ClassType, DefinedClass};
+use objc2_foundation::{ns_string, NSException, NSObject, NSString, NSUInteger};
+
+use crate::NSExceptionHandler;
+
+/// Which exceptions the default exception handler logs and handles.
+///
+/// Masks can be combined with `|`.
+#[doc(alias = "NSExceptionHandlingMask")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ExceptionMask(pub NSUInteger);
+
+impl ExceptionMask {
+    /// Log uncaught exceptions.
+    #[doc(alias = "NSLogUncaughtExceptionMask")]
+    pub const LOG_UNCAUGHT_EXCEPTION: Self = Self(1 << 0);
+    /// Handle uncaught exceptions.
+    #[doc(alias = "NSHandleUncaughtExceptionMask")]
+    pub const HANDLE_UNCAUGHT_EXCEPTION: Self = Self(1 << 1);
+    /// Log system exceptions, such as invalid memory accesses, that were
+    /// turned into exceptions.
+    #[doc(alias = "NSLogUncaughtSystemExceptionMask")]
+    pub const LOG_UNCAUGHT_SYSTEM_EXCEPTION: Self = Self(1 << 2);
+    /// Handle system exceptions, such as invalid memory accesses, that were
+    /// turned into exceptions.
+    #[doc(alias = "NSHandleUncaughtSystemExceptionMask")]
+    pub const HANDLE_UNCAUGHT_SYSTEM_EXCEPTION: Self = Self(1 << 3);
+    /// Log Objective-C runtime errors, such as messages sent to freed
+    /// objects.
+    #[doc(alias = "NSLogUncaughtRuntimeErrorMask")]
+    pub const LOG_UNCAUGHT_RUNTIME_ERROR: Self = Self(1 << 4);
+    /// Handle Objective-C runtime errors, such as messages sent to freed
+    /// objects.
+    #[doc(alias = "NSHandleUncaughtRuntimeErrorMask")]
+    pub const HANDLE_UNCAUGHT_RUNTIME_ERROR: Self = Self(1 << 5);
+    /// Log exceptions caught by the top-level handler of `NSApplication`.
+    #[doc(alias = "NSLogTopLevelExceptionMask")]
+    pub const LOG_TOP_LEVEL_EXCEPTION: Self = Self(1 << 6);
+    /// Handle exceptions caught by the top-level handler of `NSApplication`.
+    #[doc(alias = "NSHandleTopLevelExceptionMask")]
+    pub const HANDLE_TOP_LEVEL_EXCEPTION: Self = Self(1 << 7);
+    /// Log exceptions that are caught by an exception handler.
+    #[doc(alias = "NSLogOtherExceptionMask")]
+    pub const LOG_OTHER_EXCEPTION: Self = Self(1 << 8);
+    /// Handle exceptions that are caught by an exception handler.
+    #[doc(alias = "NSHandleOtherExceptionMask")]
+    pub const HANDLE_OTHER_EXCEPTION: Self = Self(1 << 9);
+    /// Log and handle every kind of exception.
+    #[doc(alias = "NSLogAndHandleEveryExceptionMask")]
+    pub const LOG_AND_HANDLE_EVERY_EXCEPTION: Self = Self(0x3FF);
+
+    /// Whether all the exceptions in `other` are also in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for ExceptionMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for ExceptionMask {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// The kind of an exception seen by the exception handler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ExceptionKind {
+    /// An exception that was not caught.
+    Uncaught,
+    /// A system exception, such as an invalid memory access.
+    UncaughtSystem,
+    /// An Objective-C runtime error, such as a message sent to a freed
+    /// object.
+    UncaughtRuntimeError,
+    /// An exception caught by the top-level handler of `NSApplication`.
+    TopLevel,
+    /// An exception that is caught by an exception handler.
+    Other,
+}
+
+impl ExceptionKind {
+    fn from_mask(mask: NSUInteger) -> Option<Self> {
+        if mask == 0 {
+            return None;
+        }
+        // The log and handle masks of each kind are adjacent bits.
+        Some(match mask.trailing_zeros() / 2 {
+            0 => Self::Uncaught,
+            1 => Self::UncaughtSystem,
+            2 => Self::UncaughtRuntimeError,
+            3 => Self::TopLevel,
+            4 => Self::Other,
+            _ => return None,
+        })
+    }
+}
+
+/// What the exception handler asks the closure about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExceptionAction {
+    /// Whether the exception should be logged.
+    #[doc(alias = "exceptionHandler:shouldLogException:mask:")]
+    Log,
+    /// Whether the exception should be handled.
+    #[doc(alias = "exceptionHandler:shouldHandleException:mask:")]
+    Handle,
+}
+
+/// An exception seen by the exception handler, given to the closure of
+/// [`ExceptionHandlerGuard`].
+pub struct ExceptionReport<'a> {
+    exception: &'a NSException,
+    action: ExceptionAction,
+    mask: NSUInteger,
+}
+
+impl<'a> ExceptionReport<'a> {
+    /// The exception.
+    pub fn exception(&self) -> &'a NSException {
+        self.exception
+    }
+
+    /// What the exception handler is asking about.
+    pub fn action(&self) -> ExceptionAction {
+        self.action
+    }
+
+    /// The kind of exception, if known.
+    pub fn kind(&self) -> Option<ExceptionKind> {
+        ExceptionKind::from_mask(self.mask)
+    }
+
+    /// The raw mask given by the exception handler, which contains the bit
+    /// for this kind of exception and action.
+    pub fn mask(&self) -> ExceptionMask {
+        ExceptionMask(self.mask)
+    }
+
+    /// The name of the exception, e.g. `NSInvalidArgumentException`.
+    pub fn name(&self) -> String {
+        let name: Retained<NSString> = unsafe { msg_send_id![self.exception, name] };
+        name.to_string()
+    }
+
+    /// The reason that the exception was raised, if any.
+    pub fn reason(&self) -> Option<String> {
+        self.exception.reason().map(|reason| reason.to_string())
+    }
+
+    /// The symbolicated call stack of the point where the exception was
+    /// raised, innermost frame first.
+    #[doc(alias = "callStackSymbols")]
+    pub fn call_stack_symbols(&self) -> Vec<String> {
+        let symbols: Retained<AnyObject> =
+            unsafe { msg_send_id![self.exception, callStackSymbols] };
+        let count: NSUInteger = unsafe { msg_send![&symbols, count] };
+        (0..count)
+            .map(|i| {
+                let symbol: Retained<NSString> =
+                    unsafe { msg_send_id![&symbols, objectAtIndex: i] };
+                symbol.to_string()
+            })
+            .collect()
+    }
+
+    /// The return addresses of the call stack of the point where the
+    /// exception was raised, innermost frame first.
+    ///
+    /// Crash reporters can symbolicate these later.
+    #[doc(alias = "callStackReturnAddresses")]
+    pub fn call_stack_return_addresses(&self) -> Vec<usize> {
+        let addresses: Retained<AnyObject> =
+            unsafe { msg_send_id![self.exception, callStackReturnAddresses] };
+        let count: NSUInteger = unsafe { msg_send![&addresses, count] };
+        (0..count)
+            .map(|i| {
+                let address: Retained<AnyObject> =
+                    unsafe { msg_send_id![&addresses, objectAtIndex: i] };
+                let address: NSUInteger = unsafe { msg_send![&address, unsignedIntegerValue] };
+                address as usize
+            })
+            .collect()
+    }
+
+    /// The stack trace that the exception handler added to the exception's
+    /// user info, as a string of hexadecimal return addresses.
+    #[doc(alias = "NSStackTraceKey")]
+    pub fn stack_trace(&self) -> Option<String> {
+        let user_info: Option<Retained<AnyObject>> =
+            unsafe { msg_send_id![self.exception, userInfo] };
+        let user_info = user_info?;
+        let trace: Option<Retained<NSString>> =
+            unsafe { msg_send_id![&user_info, objectForKey: ns_string!("NSStackTraceKey")] };
+        Some(trace?.to_string())
+    }
+}
+
+impl fmt::Debug for ExceptionReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExceptionReport")
+            .field("exception", &self.exception)
+            .field("action", &self.action)
+            .field("kind", &self.kind())
+            .finish()
+    }
+}
+
+type Handler = Box<dyn Fn(&ExceptionReport<'_>) -> bool + Send + Sync>;
+
+struct Ivars {
+    handler: Handler,
+}
+
+define_class!(
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - `Delegate` does not implement `Drop`.
+    #[unsafe(super(NSObject))]
+    #[name = "RustExceptionHandlerDelegate"]
+    #[ivars = Ivars]
+    struct Delegate;
+
+    // The `NSExceptionHandlerDelegate` informal protocol.
+    unsafe impl Delegate {
+        #[method(exceptionHandler:shouldLogException:mask:)]
+        fn should_log(
+            &self,
+            _handler: &NSExceptionHandler,
+            exception: &NSException,
+            mask: NSUInteger,
+        ) -> Bool {
+            self.call(exception, ExceptionAction::Log, mask)
+        }
+
+        #[method(exceptionHandler:shouldHandleException:mask:)]
+        fn should_handle(
+            &self,
+            _handler: &NSExceptionHandler,
+            exception: &NSException,
+            mask: NSUInteger,
+        ) -> Bool {
+            self.call(exception, ExceptionAction::Handle, mask)
+        }
+    }
+
+    unsafe impl NSObjectProtocol for Delegate {}
+);
+
+impl Delegate {
+    fn new(handler: Handler) -> Retained<Self> {
+        let this = Self::alloc().set_ivars(Ivars { handler });
+        unsafe { msg_send_id![super(this), init] }
+    }
+
+    fn call(&self, exception: &NSException, action: ExceptionAction, mask: NSUInteger) -> Bool {
+        let report = ExceptionReport {
+            exception,
+            action,
+            mask,
+        };
+        Bool::new((self.ivars().handler)(&report))
+    }
+}
+
+fn default_handler() -> Retained<NSExceptionHandler> {
+    unsafe { msg_send_id![NSExceptionHandler::class(), defaultExceptionHandler] }
+}
+
+/// Routes exceptions seen by the default `NSExceptionHandler` to a closure,
+/// for example to forward them to a crash reporter.
+///
+/// The closure is called once when the exception handler decides whether to
+/// log the exception, and once when it decides whether to handle it, and
+/// should return `true` to let the exception handler proceed. It may be
+/// called on any thread.
+///
+/// The exception handler is process-wide, and the previous delegate and mask
+/// are restored when the guard is dropped, unless another delegate has been
+/// set in the meantime. Use [`core::mem::forget`] to keep the closure
+/// installed for the rest of the program.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use objc2_exception_handling::{ExceptionHandlerGuard, ExceptionMask};
+///
+/// let guard = ExceptionHandlerGuard::install(
+///     ExceptionMask::LOG_AND_HANDLE_EVERY_EXCEPTION,
+///     |report| {
+///         eprintln!(
+///             "{:?} exception {}: {:?}",
+///             report.kind(),
+///             report.name(),
+///             report.reason(),
+///         );
+///         for symbol in report.call_stack_symbols() {
+///             eprintln!("    {symbol}");
+///         }
+///         true
+///     },
+/// );
+/// core::mem::forget(guard);
+/// ```
+#[doc(alias = "NSExceptionHandler")]
+#[doc(alias = "setDelegate:")]
+#[must_use = "the previous exception handler delegate is restored when this is dropped"]
+pub struct ExceptionHandlerGuard {
+    delegate: Retained<Delegate>,
+    previous_delegate: Option<Retained<AnyObject>>,
+    previous_mask: NSUInteger,
+}
+
+impl ExceptionHandlerGuard {
+    /// Set the mask of the default exception handler, and call the closure
+    /// for every exception it sees.
+    #[doc(alias = "setExceptionHandlingMask:")]
+    pub fn install(
+        mask: ExceptionMask,
+        handler: impl Fn(&ExceptionReport<'_>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        let delegate = Delegate::new(Box::new(handler));
+        let exception_handler = default_handler();
+        let previous_delegate: Option<Retained<AnyObject>> =
+            unsafe { msg_send_id![&exception_handler, delegate] };
+        let previous_mask: NSUInteger =
+            unsafe { msg_send![&exception_handler, exceptionHandlingMask] };
+        // The delegate is not retained by the exception handler, but is kept
+        // alive by the guard.
+        let _: () = unsafe { msg_send![&exception_handler, setDelegate: &*delegate] };
+        let _: () = unsafe { msg_send![&exception_handler, setExceptionHandlingMask: mask.0] };
+        Self {
+            delegate,
+            previous_delegate,
+            previous_mask,
+        }
+    }
+}
+
+impl Drop for ExceptionHandlerGuard {
+    fn drop(&mut self) {
+        let exception_handler = default_handler();
+        let current: Option<Retained<AnyObject>> =
+            unsafe { msg_send_id![&exception_handler, delegate] };
+        let ours: &AnyObject = &self.delegate;
+        // Leave the exception handler alone if it has since been given
+        // another delegate.
+        if !current.is_some_and(|current| ptr::eq(&*current, ours)) {
+            return;
+        }
+        let previous_delegate = self.previous_delegate.as_deref();
+        let _: () = unsafe { msg_send![&exception_handler, setDelegate: previous_delegate] };
+        let _: () =
+            unsafe { msg_send![&exception_handler, setExceptionHandlingMask: self.previous_mask] };
+    }
+}
+
+impl fmt::Debug for ExceptionHandlerGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExceptionHandlerGuard")
+            .field("previous_mask", &self.previous_mask)
+            .finish_non_exhaustive()
+    }
+}
@@ -15,6 +15,13 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(all(feature = "alloc", feature = "NSExceptionHandler"))]
+mod delegate;
 mod generated;
+
+#[cfg(all(feature = "alloc", feature = "NSExceptionHandler"))]
+pub use self::delegate::{
+    ExceptionAction, ExceptionHandlerGuard, ExceptionKind, ExceptionMask, ExceptionReport,
+};
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
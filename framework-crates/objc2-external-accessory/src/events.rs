@@ -0,0 +1,214 @@
+//! Observing accessories being connected and disconnected.
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::future::Future;
+use core::task::{Context, Poll};
+
+use objc2::rc::Retained;
+use objc2_foundation::channel::{self, Receiver};
+use objc2_foundation::{
+    NSNotification, NSNotificationCenter, NSNotificationName, NSNotificationObserver, NSUInteger,
+};
+
+use crate::{
+    EAAccessory, EAAccessoryDidConnectNotification, EAAccessoryDidDisconnectNotification,
+    EAAccessoryKey, EAAccessoryManager,
+};
+
+/// An accessory was connected or disconnected.
+///
+/// See [`EAAccessoryManager::events`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EAAccessoryEvent {
+    /// An accessory was connected.
+    #[doc(alias = "EAAccessoryDidConnectNotification")]
+    Connected {
+        /// The accessory's connection ID, see
+        /// [`EAAccessoryManager::accessory_with_connection_id`].
+        connection_id: NSUInteger,
+        /// The protocols that the accessory supports.
+        protocol_strings: Vec<String>,
+    },
+    /// An accessory was disconnected.
+    ///
+    /// Any sessions opened to the accessory should be dropped.
+    #[doc(alias = "EAAccessoryDidDisconnectNotification")]
+    Disconnected {
+        /// The connection ID that the accessory had while connected.
+        connection_id: NSUInteger,
+    },
+}
+
+impl EAAccessoryEvent {
+    fn accessory(notification: &NSNotification) -> Option<Retained<EAAccessory>> {
+        let user_info = unsafe { notification.userInfo() }?;
+        let accessory = user_info.objectForKey(unsafe { EAAccessoryKey })?;
+        accessory.downcast::<EAAccessory>().ok()
+    }
+
+    fn connected(notification: &NSNotification) -> Option<Self> {
+        let accessory = Self::accessory(notification)?;
+        let protocol_strings = unsafe { accessory.protocolStrings() }
+            .to_vec()
+            .iter()
+            .map(|protocol| protocol.to_string())
+            .collect();
+        Some(Self::Connected {
+            connection_id: unsafe { accessory.connectionID() },
+            protocol_strings,
+        })
+    }
+
+    fn disconnected(notification: &NSNotification) -> Option<Self> {
+        let accessory = Self::accessory(notification)?;
+        Some(Self::Disconnected {
+            connection_id: unsafe { accessory.connectionID() },
+        })
+    }
+}
+
+/// A stream of accessories being connected and disconnected.
+///
+/// Events are queued from the moment the stream is created, and can be
+/// retrieved either by blocking ([`recv`]), by polling ([`try_next`]), or
+/// asynchronously ([`next`] or [`poll_next`], the latter of which can be used
+/// to implement `futures::Stream`).
+///
+/// The observers are removed when this is dropped.
+///
+/// See [`EAAccessoryManager::events`].
+///
+/// [`recv`]: Self::recv
+/// [`try_next`]: Self::try_next
+/// [`next`]: Self::next
+/// [`poll_next`]: Self::poll_next
+pub struct EAAccessoryEvents {
+    // Dropped first, such that the senders are released before the receiver.
+    _observers: [NSNotificationObserver; 2],
+    receiver: Receiver<EAAccessoryEvent>,
+}
+
+impl EAAccessoryManager {
+    /// Observe accessories being connected and disconnected.
+    ///
+    /// This registers for local notifications on the shared manager for as
+    /// long as the stream is alive.
+    ///
+    /// Notifications are posted on the main thread, so that must be running
+    /// its run loop for events to be delivered.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_external_accessory::{EAAccessoryEvent, EAAccessoryManager};
+    ///
+    /// let manager = unsafe { EAAccessoryManager::sharedAccessoryManager() };
+    /// let events = manager.events();
+    ///
+    /// std::thread::spawn(move || {
+    ///     while let Some(event) = events.recv() {
+    ///         match event {
+    ///             EAAccessoryEvent::Connected { connection_id, .. } => {
+    ///                 println!("accessory {connection_id} connected");
+    ///             }
+    ///             EAAccessoryEvent::Disconnected { connection_id } => {
+    ///                 println!("accessory {connection_id} disconnected");
+    ///             }
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    #[doc(alias = "registerForLocalNotifications")]
+    pub fn events(&self) -> EAAccessoryEvents {
+        // Shared between the two observers, the channel is closed once both
+        // have been removed.
+        let (sender, receiver) = channel::unbounded();
+        let center = unsafe { NSNotificationCenter::defaultCenter() };
+
+        let observe =
+            |name: &NSNotificationName, decode: fn(&NSNotification) -> Option<EAAccessoryEvent>| {
+                let sender = sender.clone();
+                center.add_observer(Some(name), None, move |notification| {
+                    if let Some(event) = decode(notification) {
+                        let _ = sender.send(event);
+                    }
+                })
+            };
+
+        let observers = [
+            observe(
+                unsafe { EAAccessoryDidConnectNotification },
+                EAAccessoryEvent::connected,
+            ),
+            observe(
+                unsafe { EAAccessoryDidDisconnectNotification },
+                EAAccessoryEvent::disconnected,
+            ),
+        ];
+
+        unsafe { self.registerForLocalNotifications() };
+
+        EAAccessoryEvents {
+            _observers: observers,
+            receiver,
+        }
+    }
+
+    /// The connected accessory with the given connection ID.
+    ///
+    /// Returns [`None`] if the accessory has since been disconnected.
+    #[doc(alias = "connectionID")]
+    pub fn accessory_with_connection_id(
+        &self,
+        connection_id: NSUInteger,
+    ) -> Option<Retained<EAAccessory>> {
+        let accessories = unsafe { self.connectedAccessories() };
+        accessories
+            .to_vec()
+            .into_iter()
+            .find(|accessory| unsafe { accessory.connectionID() } == connection_id)
+    }
+}
+
+impl EAAccessoryEvents {
+    /// Get the next event if one has already been received.
+    pub fn try_next(&self) -> Option<EAAccessoryEvent> {
+        self.receiver.try_recv()
+    }
+
+    /// Block the current thread until the next event is received.
+    ///
+    /// Must not be called on the main thread, since that is where the
+    /// events are sent from.
+    pub fn recv(&self) -> Option<EAAccessoryEvent> {
+        self.receiver.recv()
+    }
+
+    /// Poll for the next event.
+    pub fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<EAAccessoryEvent>> {
+        self.receiver.poll_recv(cx)
+    }
+
+    /// Wait asynchronously for the next event.
+    pub fn next(&self) -> impl Future<Output = Option<EAAccessoryEvent>> + '_ {
+        core::future::poll_fn(|cx| self.poll_next(cx))
+    }
+}
+
+impl Drop for EAAccessoryEvents {
+    fn drop(&mut self) {
+        let manager = unsafe { EAAccessoryManager::sharedAccessoryManager() };
+        unsafe { manager.unregisterForLocalNotifications() };
+        // The observers are removed when the fields are dropped.
+    }
+}
+
+impl fmt::Debug for EAAccessoryEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EAAccessoryEvents")
+            .field("receiver", &self.receiver)
+            .finish_non_exhaustive()
+    }
+}
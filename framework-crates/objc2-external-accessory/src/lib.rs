@@ -15,6 +15,35 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "EAAccessory",
+    feature = "EAAccessoryManager"
+))]
+mod events;
 mod generated;
+#[cfg(all(
+    feature = "std",
+    feature = "EAAccessory",
+    feature = "EAAccessoryManager",
+    feature = "EASession"
+))]
+mod session;
+
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "EAAccessory",
+    feature = "EAAccessoryManager"
+))]
+pub use self::events::{EAAccessoryEvent, EAAccessoryEvents};
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(all(
+    feature = "std",
+    feature = "EAAccessory",
+    feature = "EAAccessoryManager",
+    feature = "EASession"
+))]
+pub use self::session::{EASessionReader, EASessionWriter};
@@ -0,0 +1,198 @@
+//! Communicating with an accessory through `std::io`.
+use alloc::string::ToString;
+use core::fmt;
+use core::ptr::NonNull;
+use std::io;
+
+use objc2::rc::Retained;
+use objc2::{AllocAnyThread, Message};
+use objc2_foundation::{NSInputStream, NSOutputStream, NSStream, NSString};
+
+use crate::{EAAccessory, EAAccessoryManager, EASession};
+
+impl EAAccessoryManager {
+    /// The first connected accessory that supports the given protocol.
+    ///
+    /// The protocol must be listed in the `UISupportedExternalAccessoryProtocols`
+    /// key of your app's `Info.plist`, otherwise no accessories will be found.
+    #[doc(alias = "connectedAccessories")]
+    pub fn accessory_for_protocol(&self, protocol: &str) -> Option<Retained<EAAccessory>> {
+        let protocol = NSString::from_str(protocol);
+        let accessories = unsafe { self.connectedAccessories() };
+        accessories.to_vec().into_iter().find(|accessory| {
+            let protocols = unsafe { accessory.protocolStrings() };
+            protocols.to_vec().iter().any(|p| **p == *protocol)
+        })
+    }
+}
+
+impl EASession {
+    /// Open a session to the first connected accessory that supports the
+    /// given protocol.
+    ///
+    /// Returns [`None`] if no such accessory is connected, or if the session
+    /// could not be opened (e.g. because another session is already open for
+    /// the same accessory and protocol).
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::io::{Read, Write};
+    /// use objc2_external_accessory::EASession;
+    ///
+    /// let session = EASession::open("com.example.protocol").expect("accessory connected");
+    /// let (mut reader, mut writer) = session.streams()?;
+    ///
+    /// writer.write_all(b"ping")?;
+    /// let mut buf = [0; 4];
+    /// reader.read_exact(&mut buf)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[doc(alias = "initWithAccessory:forProtocol:")]
+    pub fn open(protocol: &str) -> Option<Retained<Self>> {
+        let manager = unsafe { EAAccessoryManager::sharedAccessoryManager() };
+        let accessory = manager.accessory_for_protocol(protocol)?;
+        Self::open_with(&accessory, protocol)
+    }
+
+    /// Open a session to the given accessory using the given protocol.
+    ///
+    /// Returns [`None`] if the session could not be opened.
+    #[doc(alias = "initWithAccessory:forProtocol:")]
+    pub fn open_with(accessory: &EAAccessory, protocol: &str) -> Option<Retained<Self>> {
+        let protocol = NSString::from_str(protocol);
+        unsafe { Self::initWithAccessory_forProtocol(Self::alloc(), accessory, &protocol) }
+    }
+
+    /// Open the session's input and output streams, and wrap them in types
+    /// that implement [`io::Read`] and [`io::Write`].
+    ///
+    /// The streams are used in polling mode; reads block until at least one
+    /// byte is available, and writes block until there is space available.
+    /// You will usually want to move the reader to a separate thread.
+    ///
+    /// The streams are closed again when the reader and writer are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session has no input or output stream, which
+    /// happens if the accessory was disconnected.
+    #[doc(alias = "inputStream")]
+    #[doc(alias = "outputStream")]
+    pub fn streams(&self) -> io::Result<(EASessionReader, EASessionWriter)> {
+        let not_connected =
+            || io::Error::new(io::ErrorKind::NotConnected, "accessory disconnected");
+        let input = unsafe { self.inputStream() }.ok_or_else(not_connected)?;
+        let output = unsafe { self.outputStream() }.ok_or_else(not_connected)?;
+
+        unsafe { input.open() };
+        unsafe { output.open() };
+
+        Ok((
+            EASessionReader {
+                stream: input,
+                _session: self.retain(),
+            },
+            EASessionWriter {
+                stream: output,
+                _session: self.retain(),
+            },
+        ))
+    }
+}
+
+/// Convert the stream's error to an [`io::Error`].
+fn stream_error(stream: &NSStream) -> io::Error {
+    match unsafe { stream.streamError() } {
+        Some(error) => io::Error::new(
+            io::ErrorKind::Other,
+            error.localizedDescription().to_string(),
+        ),
+        None => io::Error::new(io::ErrorKind::Other, "unknown stream error"),
+    }
+}
+
+/// The reading half of an [`EASession`], see [`EASession::streams`].
+pub struct EASessionReader {
+    stream: Retained<NSInputStream>,
+    // The session must be kept alive while the stream is in use.
+    _session: Retained<EASession>,
+}
+
+impl io::Read for EASessionReader {
+    #[doc(alias = "read:maxLength:")]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let ptr = NonNull::new(buf.as_mut_ptr()).unwrap();
+        // SAFETY: The buffer is valid for writes of `buf.len()` bytes.
+        let n = unsafe { self.stream.read_maxLength(ptr, buf.len()) };
+        if n < 0 {
+            Err(stream_error(&self.stream))
+        } else {
+            Ok(n as usize)
+        }
+    }
+}
+
+impl Drop for EASessionReader {
+    fn drop(&mut self) {
+        unsafe { self.stream.close() };
+    }
+}
+
+impl fmt::Debug for EASessionReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EASessionReader")
+            .field("stream", &self.stream)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The writing half of an [`EASession`], see [`EASession::streams`].
+pub struct EASessionWriter {
+    stream: Retained<NSOutputStream>,
+    // The session must be kept alive while the stream is in use.
+    _session: Retained<EASession>,
+}
+
+impl io::Write for EASessionWriter {
+    #[doc(alias = "write:maxLength:")]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let ptr = NonNull::new(buf.as_ptr().cast_mut()).unwrap();
+        // SAFETY: The buffer is valid for reads of `buf.len()` bytes, and the
+        // stream does not write to it.
+        let n = unsafe { self.stream.write_maxLength(ptr, buf.len()) };
+        if n < 0 {
+            Err(stream_error(&self.stream))
+        } else {
+            // A return value of zero means that the stream has reached its
+            // capacity, which `write_all` turns into `ErrorKind::WriteZero`.
+            Ok(n as usize)
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Written data is handed to the accessory immediately.
+        Ok(())
+    }
+}
+
+impl Drop for EASessionWriter {
+    fn drop(&mut self) {
+        unsafe { self.stream.close() };
+    }
+}
+
+impl fmt::Debug for EASessionWriter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EASessionWriter")
+            .field("stream", &self.stream)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,305 @@
+//! Implementing File Provider UI action extensions in Rust.
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use std::sync::OnceLock;
+
+use objc2::rc::{Allocated, Retained};
+use objc2::runtime::{AnyObject, NSObjectProtocol};
+use objc2::{define_class, msg_send, msg_send_id, ClassType, DefinedClass, MainThreadOnly};
+use objc2_app_kit::{NSResponder, NSViewController};
+use objc2_foundation::{NSArray, NSBundle, NSError, NSInteger, NSObject, NSString};
+
+use crate::{
+    FPUIActionExtensionContext, FPUIActionExtensionViewController, FPUIErrorDomain,
+    FPUIExtensionErrorCode,
+};
+
+/// The name of the view controller class that forwards to the
+/// [`ActionHandler`]s created by [`ActionExtension`].
+///
+/// This must be set as the `NSExtensionPrincipalClass` in the `Info.plist`
+/// of the extension.
+pub const VIEW_CONTROLLER_CLASS_NAME: &str = "RustFPUIActionExtensionViewController";
+
+type Factory = Box<dyn Fn() -> Box<dyn ActionHandler> + Send + Sync>;
+
+static EXTENSION: OnceLock<ActionExtension> = OnceLock::new();
+
+fn extension() -> &'static ActionExtension {
+    EXTENSION
+        .get()
+        .expect("File Provider UI extension used before `ActionExtension::register`")
+}
+
+/// The handler behind a single action extension view controller.
+///
+/// The system creates a new view controller each time the user picks one of
+/// the actions declared in the extension's `Info.plist`, and a new handler
+/// is created along with it. The view controller's lifecycle methods are
+/// forwarded to the handler on the main thread.
+///
+/// Exactly one of [`prepare_for_action`] and [`prepare_for_error`] is called
+/// after the view has loaded. The handler must eventually signal that it is
+/// done using the [`ActionCompletion`] that it is given, which dismisses the
+/// view controller.
+///
+/// [`prepare_for_action`]: Self::prepare_for_action
+/// [`prepare_for_error`]: Self::prepare_for_error
+pub trait ActionHandler: 'static {
+    /// Create the view of the view controller.
+    ///
+    /// This must set the view using `setView`, since the view controller
+    /// does not have a nib to load the view from.
+    #[doc(alias = "loadView")]
+    fn load_view(&self, controller: &FPUIActionExtensionViewController);
+
+    /// Called after the view has been loaded.
+    #[doc(alias = "viewDidLoad")]
+    fn view_did_load(&self, controller: &FPUIActionExtensionViewController) {
+        let _ = controller;
+    }
+
+    /// Prepare the interface for performing the action with the given
+    /// identifier on the given items.
+    ///
+    /// The action identifier is one of those declared under
+    /// `NSExtensionFileProviderActions` in the `Info.plist`.
+    #[doc(alias = "prepareForActionWithIdentifier:itemIdentifiers:")]
+    fn prepare_for_action(
+        &self,
+        controller: &FPUIActionExtensionViewController,
+        action_identifier: &str,
+        item_identifiers: &[String],
+        completion: ActionCompletion,
+    );
+
+    /// Prepare the interface for resolving the given error.
+    ///
+    /// This is called when the File Provider extension reported an error
+    /// that the user must resolve, such as by signing in again.
+    ///
+    /// The default implementation cancels the request.
+    #[doc(alias = "prepareForError:")]
+    fn prepare_for_error(
+        &self,
+        controller: &FPUIActionExtensionViewController,
+        error: &NSError,
+        completion: ActionCompletion,
+    ) {
+        let _ = (controller, error);
+        completion.cancel();
+    }
+}
+
+/// Used to tell the system that the action has finished.
+///
+/// Each of the methods consume the completion, since the request can only be
+/// finished once.
+#[doc(alias = "FPUIActionExtensionContext")]
+pub struct ActionCompletion {
+    context: Retained<FPUIActionExtensionContext>,
+}
+
+impl ActionCompletion {
+    /// The extension context of the view controller.
+    pub fn context(&self) -> &FPUIActionExtensionContext {
+        &self.context
+    }
+
+    /// Finish the request successfully.
+    #[doc(alias = "completeRequest")]
+    pub fn complete(self) {
+        unsafe { self.context.completeRequest() };
+    }
+
+    /// Finish the request because the user cancelled it.
+    #[doc(alias = "FPUIExtensionErrorCodeUserCancelled")]
+    pub fn cancel(self) {
+        self.fail_with_code(FPUIExtensionErrorCode::UserCancelled);
+    }
+
+    /// Finish the request because the action could not be performed.
+    #[doc(alias = "FPUIExtensionErrorCodeFailed")]
+    pub fn fail(self) {
+        self.fail_with_code(FPUIExtensionErrorCode::Failed);
+    }
+
+    /// Finish the request with the given error.
+    ///
+    /// The error should be in the [`FPUIErrorDomain`].
+    #[doc(alias = "cancelRequestWithError:")]
+    pub fn fail_with_error(self, error: &NSError) {
+        unsafe { self.context.cancelRequestWithError(error) };
+    }
+
+    fn fail_with_code(self, code: FPUIExtensionErrorCode) {
+        let error: Retained<NSError> = unsafe {
+            msg_send_id![
+                NSError::class(),
+                errorWithDomain: FPUIErrorDomain,
+                code: code.0 as NSInteger,
+                userInfo: core::ptr::null::<AnyObject>(),
+            ]
+        };
+        self.fail_with_error(&error);
+    }
+}
+
+impl fmt::Debug for ActionCompletion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActionCompletion").finish_non_exhaustive()
+    }
+}
+
+/// The File Provider UI action extension.
+///
+/// Create it at the start of the extension's `main`, before calling
+/// `NSExtensionMain`, and set [`VIEW_CONTROLLER_CLASS_NAME`] as the
+/// `NSExtensionPrincipalClass` in the extension's `Info.plist`.
+///
+///
+/// # Example
+///
+/// ```ignore
+/// use objc2_app_kit::NSView;
+/// use objc2_file_provider_ui::{
+///     ActionCompletion, ActionExtension, ActionHandler, FPUIActionExtensionViewController,
+/// };
+///
+/// struct Share;
+///
+/// impl ActionHandler for Share {
+///     fn load_view(&self, controller: &FPUIActionExtensionViewController) {
+///         let view = NSView::new(controller.mtm());
+///         controller.setView(&view);
+///     }
+///
+///     fn prepare_for_action(
+///         &self,
+///         _controller: &FPUIActionExtensionViewController,
+///         action_identifier: &str,
+///         item_identifiers: &[String],
+///         completion: ActionCompletion,
+///     ) {
+///         println!("{action_identifier}: {item_identifiers:?}");
+///         completion.complete();
+///     }
+/// }
+///
+/// ActionExtension::new(|| Share).register();
+/// // Call NSExtensionMain here.
+/// ```
+pub struct ActionExtension {
+    factory: Factory,
+}
+
+impl ActionExtension {
+    /// Create an extension that uses the given closure to create a handler
+    /// for each view controller.
+    pub fn new<H: ActionHandler>(factory: impl Fn() -> H + Send + Sync + 'static) -> Self {
+        Self {
+            factory: Box::new(move || Box::new(factory())),
+        }
+    }
+
+    /// Register the extension, and the view controller class that forwards
+    /// to it.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if an extension was already registered.
+    pub fn register(self) {
+        if EXTENSION.set(self).is_err() {
+            panic!("a File Provider UI extension was already registered");
+        }
+        // Register the class, such that the extension can find it by name.
+        let _ = ViewController::class();
+    }
+}
+
+impl fmt::Debug for ActionExtension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActionExtension").finish_non_exhaustive()
+    }
+}
+
+struct Ivars {
+    handler: Box<dyn ActionHandler>,
+}
+
+define_class!(
+    // SAFETY:
+    // - The superclass FPUIActionExtensionViewController is designed to be
+    //   subclassed, and we only override the methods it asks us to.
+    // - `ViewController` does not implement `Drop`.
+    #[unsafe(super(FPUIActionExtensionViewController, NSViewController, NSResponder, NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "RustFPUIActionExtensionViewController"]
+    #[ivars = Ivars]
+    struct ViewController;
+
+    unsafe impl ViewController {
+        // Also called by `init`.
+        #[method_id(initWithNibName:bundle:)]
+        fn init_with_nib_name(
+            this: Allocated<Self>,
+            nib_name: Option<&NSString>,
+            bundle: Option<&NSBundle>,
+        ) -> Retained<Self> {
+            let this = this.set_ivars(Ivars {
+                handler: (extension().factory)(),
+            });
+            unsafe { msg_send_id![super(this), initWithNibName: nib_name, bundle: bundle] }
+        }
+
+        #[method(loadView)]
+        fn load_view(&self) {
+            self.ivars().handler.load_view(self);
+        }
+
+        #[method(viewDidLoad)]
+        fn view_did_load(&self) {
+            let _: () = unsafe { msg_send![super(self), viewDidLoad] };
+            self.ivars().handler.view_did_load(self);
+        }
+
+        #[method(prepareForActionWithIdentifier:itemIdentifiers:)]
+        fn prepare_for_action(
+            &self,
+            action_identifier: &NSString,
+            item_identifiers: &NSArray<NSString>,
+        ) {
+            let item_identifiers: Vec<String> = item_identifiers
+                .to_vec()
+                .iter()
+                .map(|identifier| identifier.to_string())
+                .collect();
+            self.ivars().handler.prepare_for_action(
+                self,
+                &action_identifier.to_string(),
+                &item_identifiers,
+                self.completion(),
+            );
+        }
+
+        #[method(prepareForError:)]
+        fn prepare_for_error(&self, error: &NSError) {
+            self.ivars()
+                .handler
+                .prepare_for_error(self, error, self.completion());
+        }
+    }
+
+    unsafe impl NSObjectProtocol for ViewController {}
+);
+
+impl ViewController {
+    fn completion(&self) -> ActionCompletion {
+        ActionCompletion {
+            context: unsafe { self.extensionContext() },
+        }
+    }
+}
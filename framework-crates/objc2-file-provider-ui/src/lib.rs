@@ -15,6 +15,25 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(all(
+    feature = "std",
+    feature = "objc2-app-kit",
+    feature = "FPUIActionExtensionContext",
+    feature = "FPUIActionExtensionViewController",
+    target_os = "macos"
+))]
+mod extension;
 mod generated;
+
+#[cfg(all(
+    feature = "std",
+    feature = "objc2-app-kit",
+    feature = "FPUIActionExtensionContext",
+    feature = "FPUIActionExtensionViewController",
+    target_os = "macos"
+))]
+pub use self::extension::{
+    ActionCompletion, ActionExtension, ActionHandler, VIEW_CONTROLLER_CLASS_NAME,
+};
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
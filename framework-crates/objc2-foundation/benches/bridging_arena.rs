@@ -0,0 +1,80 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use objc2::rc::{autoreleasepool, Retained};
+use objc2_foundation::{BridgingArena, NSArray, NSNumber, NSString};
+
+const COUNT: usize = 100_000;
+const ITERATIONS: u32 = 20;
+
+fn strings() -> Vec<String> {
+    (0..COUNT).map(|i| format!("string number {i}")).collect()
+}
+
+fn string_array_naive() -> usize {
+    let strings = strings();
+    let strings: Vec<Retained<NSString>> = strings.iter().map(|s| NSString::from_str(s)).collect();
+    NSArray::from_retained_slice(&strings).len()
+}
+
+fn string_array_arena() -> usize {
+    let strings = strings();
+    BridgingArena::scope(|arena| arena.string_array(&strings)).len()
+}
+
+fn format_naive() -> usize {
+    autoreleasepool(|_| {
+        (0..COUNT)
+            .map(|i| NSString::from_str(&i.to_string()).len())
+            .sum()
+    })
+}
+
+fn format_arena() -> usize {
+    BridgingArena::scope(|arena| (0..COUNT).map(|i| arena.string_fmt(i).len()).sum())
+}
+
+fn numbers_naive() -> usize {
+    autoreleasepool(|_| {
+        let numbers: Vec<Retained<NSNumber>> =
+            (0..COUNT).map(|i| NSNumber::new_u64(i as u64)).collect();
+        let numbers: Vec<&NSNumber> = numbers.iter().map(|n| &**n).collect();
+        NSArray::from_slice(&numbers).len()
+    })
+}
+
+fn numbers_arena() -> usize {
+    BridgingArena::scope(|arena| {
+        let numbers: Vec<&NSNumber> = (0..COUNT).map(|i| arena.number_u64(i as u64)).collect();
+        NSArray::from_slice(&numbers).len()
+    })
+}
+
+macro_rules! main {
+    ($($f:ident,)+) => {
+        fn main() {
+            $(
+                // Warm up first, among other things to get DYLD to resolve
+                // the stubs on x86_64.
+                black_box($f());
+                let start = Instant::now();
+                for _ in 0..ITERATIONS {
+                    black_box($f());
+                }
+                let per_iter = start.elapsed() / ITERATIONS;
+                println!("{:<24} {:>12?}", stringify!($f), per_iter);
+            )+
+        }
+    };
+}
+
+main! {
+    // Baseline
+    string_array_naive,
+    format_naive,
+    numbers_naive,
+    // Arena
+    string_array_arena,
+    format_arena,
+    numbers_arena,
+}
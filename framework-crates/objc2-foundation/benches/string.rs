@@ -0,0 +1,72 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use objc2::rc::{autoreleasepool, Retained};
+use objc2_foundation::NSString;
+
+const ITERATIONS: u32 = 100_000;
+
+fn ascii() -> Retained<NSString> {
+    NSString::from_str("The quick brown fox jumps over the lazy dog")
+}
+
+fn non_ascii() -> Retained<NSString> {
+    NSString::from_str("Høj bly gom vandt fræk sexquiz på wc")
+}
+
+fn to_str_ascii() -> usize {
+    let s = ascii();
+    autoreleasepool(|pool| unsafe { s.to_str(pool) }.len())
+}
+
+fn as_str_fast_ascii() -> usize {
+    let s = ascii();
+    autoreleasepool(|pool| unsafe { s.as_str_fast(pool) }.map_or(0, str::len))
+}
+
+fn to_string_ascii() -> usize {
+    ascii().to_string().len()
+}
+
+fn to_str_non_ascii() -> usize {
+    let s = non_ascii();
+    autoreleasepool(|pool| unsafe { s.to_str(pool) }.len())
+}
+
+fn as_str_fast_non_ascii() -> usize {
+    let s = non_ascii();
+    autoreleasepool(|pool| unsafe { s.as_str_fast(pool) }.map_or(0, str::len))
+}
+
+fn to_string_non_ascii() -> usize {
+    non_ascii().to_string().len()
+}
+
+macro_rules! main {
+    ($($f:ident,)+) => {
+        fn main() {
+            $(
+                // Warm up first, among other things to get DYLD to resolve
+                // the stubs on x86_64.
+                black_box($f());
+                let start = Instant::now();
+                for _ in 0..ITERATIONS {
+                    black_box($f());
+                }
+                let per_iter = start.elapsed() / ITERATIONS;
+                println!("{:<24} {:>12?}", stringify!($f), per_iter);
+            )+
+        }
+    };
+}
+
+main! {
+    // Baseline
+    to_str_ascii,
+    to_str_non_ascii,
+    to_string_ascii,
+    to_string_non_ascii,
+    // Fast path
+    as_str_fast_ascii,
+    as_str_fast_non_ascii,
+}
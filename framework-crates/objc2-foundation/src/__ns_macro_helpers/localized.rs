@@ -0,0 +1,35 @@
+use alloc::string::{String, ToString};
+
+use crate::{NSBundle, NSString};
+
+/// The implementation of `localized!`.
+pub fn localized(
+    key: &NSString,
+    table: Option<&NSString>,
+    bundle: Option<&NSBundle>,
+    value: Option<&NSString>,
+) -> String {
+    let main_bundle;
+    let bundle = match bundle {
+        Some(bundle) => bundle,
+        None => {
+            main_bundle = NSBundle::mainBundle();
+            &main_bundle
+        }
+    };
+    // SAFETY: The strings are immutable.
+    unsafe { bundle.localizedStringForKey_value_table(key, value, table) }.to_string()
+}
+
+/// Copy the record of a `localized!` invocation into an array, such that it
+/// can be placed in a static.
+pub const fn localized_entry<const N: usize>(entry: &str) -> [u8; N] {
+    let bytes = entry.as_bytes();
+    let mut res = [0; N];
+    let mut i = 0;
+    while i < N {
+        res[i] = bytes[i];
+        i += 1;
+    }
+    res
+}
@@ -1,7 +1,15 @@
 mod cached;
+#[cfg(all(feature = "NSBundle", feature = "NSString"))]
+mod localized;
+#[cfg(feature = "NSString")]
+mod ns_format;
 #[cfg(feature = "NSString")]
 mod ns_string;
 
 pub use self::cached::CachedRetained;
+#[cfg(all(feature = "NSBundle", feature = "NSString"))]
+pub use self::localized::{localized, localized_entry};
+#[cfg(feature = "NSString")]
+pub use self::ns_format::ns_format;
 #[cfg(feature = "NSString")]
 pub use self::ns_string::*;
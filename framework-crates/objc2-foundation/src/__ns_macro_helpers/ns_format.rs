@@ -0,0 +1,18 @@
+use core::fmt;
+
+use objc2::rc::Retained;
+
+use crate::{NSMutableString, NSString};
+
+/// The implementation of `ns_format!`.
+pub fn ns_format(args: fmt::Arguments<'_>) -> Retained<NSString> {
+    // Avoid creating a mutable string when there is nothing to format.
+    if let Some(s) = args.as_str() {
+        return NSString::from_str(s);
+    }
+
+    let string = NSMutableString::new();
+    fmt::Write::write_fmt(&mut &*string, args)
+        .expect("a formatting trait implementation returned an error");
+    Retained::into_super(string)
+}
@@ -0,0 +1,506 @@
+//! Building and sending Apple Events.
+//!
+//! Four-character codes such as event classes, event IDs, keywords and
+//! descriptor types are given as byte arrays, e.g. `*b"core"`, in the order
+//! they are written in the scripting dictionary.
+//!
+//!
+//! # Example
+//!
+//! Ask Finder for the name of its first window.
+//!
+//! ```no_run
+//! use objc2_foundation::{NSAppleEventDescriptor, NSAppleEventObjectKey, NSAppleEventSendOptions};
+//!
+//! let finder = NSAppleEventDescriptor::from_bundle_identifier("com.apple.finder");
+//! let window = NSAppleEventDescriptor::object_specifier(
+//!     *b"cwin",
+//!     NSAppleEventObjectKey::Index(1),
+//!     None,
+//! );
+//! let name = NSAppleEventDescriptor::object_specifier(
+//!     *b"prop",
+//!     NSAppleEventObjectKey::Property(*b"pnam"),
+//!     Some(&window),
+//! );
+//!
+//! let event = NSAppleEventDescriptor::apple_event(*b"core", *b"getd", &finder);
+//! event.set_param(*b"----", &name);
+//! let reply = event.send(NSAppleEventSendOptions::DefaultOptions, None).unwrap();
+//! println!("{:?}", reply.and_then(|reply| reply.to_string()));
+//! ```
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(all(
+    feature = "NSDate",
+    feature = "NSDictionary",
+    feature = "NSError",
+    feature = "NSObject"
+))]
+use core::time::Duration;
+
+use objc2::rc::Retained;
+use objc2::{extern_methods, Message};
+
+use crate::{NSAppleEventDescriptor, NSString};
+#[cfg(all(
+    feature = "NSDate",
+    feature = "NSDictionary",
+    feature = "NSError",
+    feature = "NSObject"
+))]
+use crate::{NSAppleEventSendOptions, NSError};
+
+// Codes from `AEDataModel.h` and `AERegistry.h`.
+const TYPE_NULL: [u8; 4] = *b"null";
+const TYPE_OBJECT_SPECIFIER: [u8; 4] = *b"obj ";
+const KEY_DESIRED_CLASS: [u8; 4] = *b"want";
+const KEY_CONTAINER: [u8; 4] = *b"from";
+const KEY_KEY_FORM: [u8; 4] = *b"form";
+const KEY_KEY_DATA: [u8; 4] = *b"seld";
+#[cfg(all(
+    feature = "NSDate",
+    feature = "NSDictionary",
+    feature = "NSError",
+    feature = "NSObject"
+))]
+const KEY_DIRECT_OBJECT: [u8; 4] = *b"----";
+#[cfg(all(
+    feature = "NSDate",
+    feature = "NSDictionary",
+    feature = "NSError",
+    feature = "NSObject"
+))]
+const KEY_ERROR_NUMBER: [u8; 4] = *b"errn";
+#[cfg(all(
+    feature = "NSDate",
+    feature = "NSDictionary",
+    feature = "NSError",
+    feature = "NSObject"
+))]
+const KEY_ERROR_STRING: [u8; 4] = *b"errs";
+/// `kAutoGenerateReturnID`.
+const AUTO_GENERATE_RETURN_ID: i16 = -1;
+/// `kAnyTransactionID`.
+const ANY_TRANSACTION_ID: i32 = 0;
+
+fn code(code: [u8; 4]) -> u32 {
+    u32::from_be_bytes(code)
+}
+
+// These are skipped in the generated bindings, since the types they use are
+// defined in CoreServices. They're all plain integers though.
+extern_methods!(
+    unsafe impl NSAppleEventDescriptor {
+        #[method_id(appleEventWithEventClass:eventID:targetDescriptor:returnID:transactionID:)]
+        unsafe fn appleEventWithEventClass_eventID_targetDescriptor_returnID_transactionID(
+            event_class: u32,
+            event_id: u32,
+            target_descriptor: Option<&NSAppleEventDescriptor>,
+            return_id: i16,
+            transaction_id: i32,
+        ) -> Retained<NSAppleEventDescriptor>;
+
+        #[method(descriptorType)]
+        unsafe fn descriptorType(&self) -> u32;
+
+        #[method(eventClass)]
+        unsafe fn eventClass(&self) -> u32;
+
+        #[method(eventID)]
+        unsafe fn eventID(&self) -> u32;
+
+        #[method(setParamDescriptor:forKeyword:)]
+        unsafe fn setParamDescriptor_forKeyword(&self, descriptor: &NSAppleEventDescriptor, keyword: u32);
+
+        #[method_id(paramDescriptorForKeyword:)]
+        unsafe fn paramDescriptorForKeyword(&self, keyword: u32) -> Option<Retained<NSAppleEventDescriptor>>;
+
+        #[method(setAttributeDescriptor:forKeyword:)]
+        unsafe fn setAttributeDescriptor_forKeyword(
+            &self,
+            descriptor: &NSAppleEventDescriptor,
+            keyword: u32,
+        );
+
+        #[method_id(attributeDescriptorForKeyword:)]
+        unsafe fn attributeDescriptorForKeyword(
+            &self,
+            keyword: u32,
+        ) -> Option<Retained<NSAppleEventDescriptor>>;
+
+        #[method(setDescriptor:forKeyword:)]
+        unsafe fn setDescriptor_forKeyword(&self, descriptor: &NSAppleEventDescriptor, keyword: u32);
+
+        #[method_id(descriptorForKeyword:)]
+        unsafe fn descriptorForKeyword(&self, keyword: u32) -> Option<Retained<NSAppleEventDescriptor>>;
+
+        #[method(keywordForDescriptorAtIndex:)]
+        unsafe fn keywordForDescriptorAtIndex(&self, index: isize) -> u32;
+
+        #[method_id(coerceToDescriptorType:)]
+        unsafe fn coerceToDescriptorType(
+            &self,
+            descriptor_type: u32,
+        ) -> Option<Retained<NSAppleEventDescriptor>>;
+
+        #[cfg(all(
+            feature = "NSDate",
+            feature = "NSDictionary",
+            feature = "NSError",
+            feature = "NSObject"
+        ))]
+        #[method_id(sendEventWithOptions:timeout:error:_)]
+        unsafe fn sendEventWithOptions_timeout_error(
+            &self,
+            options: NSAppleEventSendOptions,
+            timeout_in_seconds: f64,
+        ) -> Result<Retained<NSAppleEventDescriptor>, Retained<NSError>>;
+    }
+);
+
+/// How an object specifier selects objects in its container.
+///
+/// See [`NSAppleEventDescriptor::object_specifier`].
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub enum NSAppleEventObjectKey<'a> {
+    /// The object at the given one-based index, or counting from the end if
+    /// negative (`formAbsolutePosition`).
+    Index(i32),
+    /// The object with the given name (`formName`).
+    Name(&'a str),
+    /// The object with the given unique ID (`formUniqueID`).
+    UniqueID(&'a NSAppleEventDescriptor),
+    /// The property with the given code (`formPropertyID`).
+    ///
+    /// The class of the specifier should be `*b"prop"`.
+    Property([u8; 4]),
+}
+
+/// Typed construction.
+impl NSAppleEventDescriptor {
+    /// A descriptor with no data (`typeNull`).
+    #[doc(alias = "nullDescriptor")]
+    pub fn null() -> Retained<Self> {
+        unsafe { Self::nullDescriptor() }
+    }
+
+    /// A boolean descriptor.
+    #[doc(alias = "descriptorWithBoolean:")]
+    pub fn from_bool(value: bool) -> Retained<Self> {
+        unsafe { Self::descriptorWithBoolean(value as u8) }
+    }
+
+    /// A signed 32-bit integer descriptor.
+    #[doc(alias = "descriptorWithInt32:")]
+    pub fn from_i32(value: i32) -> Retained<Self> {
+        unsafe { Self::descriptorWithInt32(value) }
+    }
+
+    /// A double-precision floating point descriptor.
+    #[doc(alias = "descriptorWithDouble:")]
+    pub fn from_f64(value: f64) -> Retained<Self> {
+        unsafe { Self::descriptorWithDouble(value) }
+    }
+
+    /// A Unicode text descriptor.
+    #[doc(alias = "descriptorWithString:")]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(value: &str) -> Retained<Self> {
+        unsafe { Self::descriptorWithString(&NSString::from_str(value)) }
+    }
+
+    /// A descriptor containing a type code, e.g. the class of an object.
+    #[doc(alias = "descriptorWithTypeCode:")]
+    pub fn from_type_code(value: [u8; 4]) -> Retained<Self> {
+        unsafe { Self::descriptorWithTypeCode(code(value)) }
+    }
+
+    /// A descriptor containing an enumerated value.
+    #[doc(alias = "descriptorWithEnumCode:")]
+    pub fn from_enum_code(value: [u8; 4]) -> Retained<Self> {
+        unsafe { Self::descriptorWithEnumCode(code(value)) }
+    }
+
+    /// A descriptor targeting the application with the given bundle
+    /// identifier.
+    #[doc(alias = "descriptorWithBundleIdentifier:")]
+    pub fn from_bundle_identifier(bundle_identifier: &str) -> Retained<Self> {
+        unsafe { Self::descriptorWithBundleIdentifier(&NSString::from_str(bundle_identifier)) }
+    }
+
+    /// A list descriptor containing the given items.
+    #[doc(alias = "listDescriptor")]
+    pub fn list(items: &[&Self]) -> Retained<Self> {
+        let list = unsafe { Self::listDescriptor() };
+        for item in items {
+            // Index 0 appends to the list.
+            unsafe { list.insertDescriptor_atIndex(item, 0) };
+        }
+        list
+    }
+
+    /// A record descriptor containing the given keyword-descriptor pairs.
+    #[doc(alias = "recordDescriptor")]
+    pub fn record(fields: &[([u8; 4], &Self)]) -> Retained<Self> {
+        let record = unsafe { Self::recordDescriptor() };
+        for (keyword, descriptor) in fields {
+            record.set_field(*keyword, descriptor);
+        }
+        record
+    }
+
+    /// An object specifier, which refers to objects of the given class in an
+    /// application.
+    ///
+    /// The container is another object specifier, or `None` for the
+    /// application itself.
+    pub fn object_specifier(
+        class: [u8; 4],
+        key: NSAppleEventObjectKey<'_>,
+        container: Option<&Self>,
+    ) -> Retained<Self> {
+        let (form, data) = match key {
+            NSAppleEventObjectKey::Index(index) => (*b"indx", Self::from_i32(index)),
+            NSAppleEventObjectKey::Name(name) => (*b"name", Self::from_str(name)),
+            NSAppleEventObjectKey::UniqueID(id) => (*b"ID  ", id.retain()),
+            NSAppleEventObjectKey::Property(property) => (*b"prop", Self::from_type_code(property)),
+        };
+        let null;
+        let container = match container {
+            Some(container) => container,
+            None => {
+                null = Self::null();
+                &null
+            }
+        };
+        let record = Self::record(&[
+            (KEY_DESIRED_CLASS, &Self::from_type_code(class)),
+            (KEY_CONTAINER, container),
+            (KEY_KEY_FORM, &Self::from_enum_code(form)),
+            (KEY_KEY_DATA, &data),
+        ]);
+        record
+            .coerce(TYPE_OBJECT_SPECIFIER)
+            .expect("records can be coerced to object specifiers")
+    }
+
+    /// An Apple Event with the given event class and ID, to be sent to the
+    /// given target.
+    ///
+    /// Parameters can be added with [`set_param`][Self::set_param], and the
+    /// event can then be sent with [`send`][Self::send].
+    #[doc(alias = "appleEventWithEventClass:eventID:targetDescriptor:returnID:transactionID:")]
+    pub fn apple_event(event_class: [u8; 4], event_id: [u8; 4], target: &Self) -> Retained<Self> {
+        unsafe {
+            Self::appleEventWithEventClass_eventID_targetDescriptor_returnID_transactionID(
+                code(event_class),
+                code(event_id),
+                Some(target),
+                AUTO_GENERATE_RETURN_ID,
+                ANY_TRANSACTION_ID,
+            )
+        }
+    }
+}
+
+/// Typed access.
+impl NSAppleEventDescriptor {
+    /// The type of the descriptor.
+    #[doc(alias = "descriptorType")]
+    pub fn descriptor_type(&self) -> [u8; 4] {
+        unsafe { self.descriptorType() }.to_be_bytes()
+    }
+
+    /// Whether the descriptor contains no data.
+    pub fn is_null(&self) -> bool {
+        self.descriptor_type() == TYPE_NULL
+    }
+
+    /// Coerce the descriptor to another type, if possible.
+    #[doc(alias = "coerceToDescriptorType:")]
+    pub fn coerce(&self, descriptor_type: [u8; 4]) -> Option<Retained<Self>> {
+        unsafe { self.coerceToDescriptorType(code(descriptor_type)) }
+    }
+
+    /// The value of the descriptor as a boolean, coercing it if necessary.
+    #[doc(alias = "booleanValue")]
+    pub fn to_bool(&self) -> Option<bool> {
+        let value = self.coerce(*b"bool")?;
+        Some(unsafe { value.booleanValue() } != 0)
+    }
+
+    /// The value of the descriptor as a signed 32-bit integer, coercing it if
+    /// necessary.
+    #[doc(alias = "int32Value")]
+    pub fn to_i32(&self) -> Option<i32> {
+        let value = self.coerce(*b"long")?;
+        Some(unsafe { value.int32Value() })
+    }
+
+    /// The value of the descriptor as a double, coercing it if necessary.
+    #[doc(alias = "doubleValue")]
+    pub fn to_f64(&self) -> Option<f64> {
+        let value = self.coerce(*b"doub")?;
+        Some(unsafe { value.doubleValue() })
+    }
+
+    /// The value of the descriptor as a string, coercing it if necessary.
+    #[doc(alias = "stringValue")]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> Option<String> {
+        let string = unsafe { self.stringValue() }?;
+        Some(string.to_string())
+    }
+
+    /// The type code of a type descriptor.
+    #[doc(alias = "typeCodeValue")]
+    pub fn to_type_code(&self) -> [u8; 4] {
+        unsafe { self.typeCodeValue() }.to_be_bytes()
+    }
+
+    /// The enumerated value of an enumeration descriptor.
+    #[doc(alias = "enumCodeValue")]
+    pub fn to_enum_code(&self) -> [u8; 4] {
+        unsafe { self.enumCodeValue() }.to_be_bytes()
+    }
+
+    /// The number of items in a list or record descriptor.
+    #[doc(alias = "numberOfItems")]
+    pub fn len(&self) -> usize {
+        // Returns 0 for other descriptors.
+        unsafe { self.numberOfItems() }.max(0) as usize
+    }
+
+    /// Whether a list or record descriptor contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The items of a list descriptor.
+    #[doc(alias = "descriptorAtIndex:")]
+    pub fn items(&self) -> Vec<Retained<Self>> {
+        // The indexes are one-based.
+        (1..=self.len() as isize)
+            .filter_map(|index| unsafe { self.descriptorAtIndex(index) })
+            .collect()
+    }
+
+    /// The keyword-descriptor pairs of a record descriptor.
+    #[doc(alias = "keywordForDescriptorAtIndex:")]
+    pub fn fields(&self) -> Vec<([u8; 4], Retained<Self>)> {
+        (1..=self.len() as isize)
+            .filter_map(|index| {
+                let keyword = unsafe { self.keywordForDescriptorAtIndex(index) };
+                let descriptor = unsafe { self.descriptorForKeyword(keyword) }?;
+                Some((keyword.to_be_bytes(), descriptor))
+            })
+            .collect()
+    }
+
+    /// The descriptor for the given keyword in a record descriptor.
+    #[doc(alias = "descriptorForKeyword:")]
+    pub fn field(&self, keyword: [u8; 4]) -> Option<Retained<Self>> {
+        unsafe { self.descriptorForKeyword(code(keyword)) }
+    }
+
+    /// Set the descriptor for the given keyword in a record descriptor.
+    #[doc(alias = "setDescriptor:forKeyword:")]
+    pub fn set_field(&self, keyword: [u8; 4], descriptor: &Self) {
+        unsafe { self.setDescriptor_forKeyword(descriptor, code(keyword)) }
+    }
+}
+
+/// Apple Events.
+impl NSAppleEventDescriptor {
+    /// The event class of an Apple Event.
+    #[doc(alias = "eventClass")]
+    pub fn event_class(&self) -> [u8; 4] {
+        unsafe { self.eventClass() }.to_be_bytes()
+    }
+
+    /// The event ID of an Apple Event.
+    #[doc(alias = "eventID")]
+    pub fn event_id(&self) -> [u8; 4] {
+        unsafe { self.eventID() }.to_be_bytes()
+    }
+
+    /// The parameter for the given keyword in an Apple Event.
+    ///
+    /// The direct object has the keyword `*b"----"`.
+    #[doc(alias = "paramDescriptorForKeyword:")]
+    pub fn param(&self, keyword: [u8; 4]) -> Option<Retained<Self>> {
+        unsafe { self.paramDescriptorForKeyword(code(keyword)) }
+    }
+
+    /// Set the parameter for the given keyword in an Apple Event.
+    ///
+    /// The direct object has the keyword `*b"----"`.
+    #[doc(alias = "setParamDescriptor:forKeyword:")]
+    pub fn set_param(&self, keyword: [u8; 4], descriptor: &Self) {
+        unsafe { self.setParamDescriptor_forKeyword(descriptor, code(keyword)) }
+    }
+
+    /// The attribute for the given keyword in an Apple Event.
+    #[doc(alias = "attributeDescriptorForKeyword:")]
+    pub fn attribute(&self, keyword: [u8; 4]) -> Option<Retained<Self>> {
+        unsafe { self.attributeDescriptorForKeyword(code(keyword)) }
+    }
+
+    /// Set the attribute for the given keyword in an Apple Event.
+    #[doc(alias = "setAttributeDescriptor:forKeyword:")]
+    pub fn set_attribute(&self, keyword: [u8; 4], descriptor: &Self) {
+        unsafe { self.setAttributeDescriptor_forKeyword(descriptor, code(keyword)) }
+    }
+
+    /// Send the Apple Event, and return the direct object of the reply, if
+    /// any.
+    ///
+    /// The timeout defaults to about a minute if `None`. The current thread
+    /// is blocked until the reply arrives if
+    /// [`NSAppleEventSendOptions::WaitForReply`] is set.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event could not be sent, or if the target
+    /// application replied with an error. In the latter case, the error is
+    /// in the `NSOSStatusErrorDomain` domain, with the error number as its
+    /// code.
+    ///
+    /// Sending events to other applications requires the user's permission,
+    /// and the `NSAppleEventsUsageDescription` key in the `Info.plist` of
+    /// sandboxed or hardened applications.
+    #[cfg(all(
+        feature = "NSDate",
+        feature = "NSDictionary",
+        feature = "NSError",
+        feature = "NSObject"
+    ))]
+    #[doc(alias = "sendEventWithOptions:timeout:error:")]
+    pub fn send(
+        &self,
+        options: NSAppleEventSendOptions,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Retained<Self>>, Retained<NSError>> {
+        // `kAEDefaultTimeout`.
+        let timeout = timeout.map_or(-1.0, |timeout| timeout.as_secs_f64());
+        let reply = unsafe { self.sendEventWithOptions_timeout_error(options, timeout) }?;
+
+        let number = reply.param(KEY_ERROR_NUMBER).and_then(|n| n.to_i32());
+        if let Some(number) = number.filter(|number| *number != 0) {
+            let message = reply.param(KEY_ERROR_STRING).and_then(|s| s.to_string());
+            let domain = unsafe { crate::NSOSStatusErrorDomain };
+            return Err(NSError::new_with(domain, number as isize, |builder| {
+                if let Some(message) = &message {
+                    builder.description(message);
+                }
+            }));
+        }
+
+        Ok(reply
+            .param(KEY_DIRECT_OBJECT)
+            .filter(|result| !result.is_null()))
+    }
+}
@@ -1,16 +1,27 @@
 //! Utilities for the `NSArray` and `NSMutableArray` classes.
 use alloc::vec::Vec;
+#[cfg(feature = "block2")]
+use core::cell::RefCell;
 #[cfg(feature = "NSEnumerator")]
 use core::fmt;
 use core::mem;
+#[cfg(feature = "block2")]
+use core::ops::ControlFlow;
 use core::ptr::NonNull;
 
+#[cfg(feature = "block2")]
+use block2::StackBlock;
+use objc2::__framework_helpers::{ref_ptr_cast_const, retained_ptr_cast_const};
 use objc2::rc::{Retained, RetainedFromIterator};
+#[cfg(feature = "block2")]
+use objc2::runtime::Bool;
 use objc2::{msg_send, AllocAnyThread, Message};
 
 #[cfg(feature = "NSEnumerator")]
 use crate::iter;
-use crate::{util, NSArray, NSMutableArray};
+#[cfg(feature = "block2")]
+use crate::NSUInteger;
+use crate::{NSArray, NSMutableArray};
 
 /// Convenience creation methods.
 impl<ObjectType: Message> NSArray<ObjectType> {
@@ -32,7 +43,7 @@ impl<ObjectType: Message> NSArray<ObjectType> {
     #[doc(alias = "initWithObjects:count:")]
     pub fn from_slice(slice: &[&ObjectType]) -> Retained<Self> {
         let len = slice.len();
-        let ptr = util::ref_ptr_cast_const(slice.as_ptr());
+        let ptr = ref_ptr_cast_const(slice.as_ptr());
         // SAFETY:
         // - All `ObjectType: Message` use interior mutability, and the array
         //   extends the lifetime of them internally by retaining them.
@@ -60,7 +71,7 @@ impl<ObjectType: Message> NSArray<ObjectType> {
     #[doc(alias = "initWithObjects:count:")]
     pub fn from_retained_slice(slice: &[Retained<ObjectType>]) -> Retained<Self> {
         let len = slice.len();
-        let ptr = util::retained_ptr_cast_const(slice.as_ptr());
+        let ptr = retained_ptr_cast_const(slice.as_ptr());
         // SAFETY: Same as `from_slice`, this is just a faster version to
         // avoid creating a new slice if your elements are already retained.
         //
@@ -75,7 +86,7 @@ impl<ObjectType: Message> NSMutableArray<ObjectType> {
     #[doc(alias = "initWithObjects:count:")]
     pub fn from_slice(slice: &[&ObjectType]) -> Retained<Self> {
         let len = slice.len();
-        let ptr = util::ref_ptr_cast_const(slice.as_ptr());
+        let ptr = ref_ptr_cast_const(slice.as_ptr());
         // SAFETY: Same as `NSArray::from_slice`.
         unsafe { Self::initWithObjects_count(Self::alloc(), ptr, len) }
     }
@@ -83,7 +94,7 @@ impl<ObjectType: Message> NSMutableArray<ObjectType> {
     #[doc(alias = "initWithObjects:count:")]
     pub fn from_retained_slice(slice: &[Retained<ObjectType>]) -> Retained<Self> {
         let len = slice.len();
-        let ptr = util::retained_ptr_cast_const(slice.as_ptr());
+        let ptr = retained_ptr_cast_const(slice.as_ptr());
         // SAFETY: Same as `NSArray::from_retained_slice`
         unsafe { Self::initWithObjects_count(Self::alloc(), ptr, len) }
     }
@@ -325,6 +336,111 @@ impl<ObjectType: Message> NSMutableArray<ObjectType> {
     }
 }
 
+/// Block-based enumeration.
+#[cfg(feature = "block2")]
+impl<ObjectType: Message> NSArray<ObjectType> {
+    /// Call the closure with the index of and a reference to each element in
+    /// the array, without retaining the elements.
+    ///
+    /// Enumeration stops early if the closure returns [`ControlFlow::Break`].
+    ///
+    /// Consider using the [`iter`](Self::iter) method instead, unless you're
+    /// seeing performance issues from the retaining.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The array must not be mutated while enumerating, including from
+    /// inside the closure.
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::ops::ControlFlow;
+    /// use objc2_foundation::{NSArray, NSNumber};
+    ///
+    /// let array = NSArray::from_retained_slice(&[
+    ///     NSNumber::new_i32(1),
+    ///     NSNumber::new_i32(2),
+    ///     NSNumber::new_i32(3),
+    /// ]);
+    ///
+    /// let mut sum = 0;
+    /// // SAFETY: The array is not mutated while enumerating.
+    /// unsafe {
+    ///     array.enumerate_objects(|_, number| {
+    ///         sum += number.as_i32();
+    ///         if sum >= 3 {
+    ///             ControlFlow::Break(())
+    ///         } else {
+    ///             ControlFlow::Continue(())
+    ///         }
+    ///     })
+    /// };
+    /// assert_eq!(sum, 3);
+    /// ```
+    #[doc(alias = "enumerateObjectsUsingBlock:")]
+    pub unsafe fn enumerate_objects<F: FnMut(usize, &ObjectType) -> ControlFlow<()>>(&self, f: F) {
+        // The block is only called from the current thread, one element at a
+        // time, so it's fine to give it mutable access to the closure.
+        let f = RefCell::new(f);
+        let block = StackBlock::new(
+            |obj: NonNull<ObjectType>, index: NSUInteger, stop: NonNull<Bool>| {
+                // SAFETY: The array keeps the object alive while enumerating,
+                // since the caller ensures that it is not mutated.
+                let obj = unsafe { obj.as_ref() };
+                if (f.borrow_mut())(index, obj).is_break() {
+                    // SAFETY: The stop pointer is valid for writes.
+                    unsafe { stop.as_ptr().write(Bool::YES) };
+                }
+            },
+        );
+        unsafe { self.enumerateObjectsUsingBlock(&block) };
+    }
+
+    /// Call the closure with the index of and a reference to each element in
+    /// the array, possibly concurrently on several threads.
+    ///
+    /// Enumeration stops early (though other threads may still be processing
+    /// elements) if the closure returns [`ControlFlow::Break`].
+    ///
+    /// This blocks until all elements have been processed.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The array must not be mutated while enumerating, including from
+    /// inside the closure.
+    #[cfg(feature = "NSObjCRuntime")]
+    #[doc(alias = "enumerateObjectsWithOptions:usingBlock:")]
+    #[doc(alias = "NSEnumerationConcurrent")]
+    pub unsafe fn enumerate_objects_concurrent<F>(&self, f: F)
+    where
+        ObjectType: Sync,
+        F: Fn(usize, &ObjectType) -> ControlFlow<()> + Sync,
+    {
+        let block = StackBlock::new(
+            |obj: NonNull<ObjectType>, index: NSUInteger, stop: NonNull<Bool>| {
+                // SAFETY: The array keeps the object alive while enumerating,
+                // since the caller ensures that it is not mutated. The object
+                // is `Sync`, so it's fine to access it from other threads.
+                let obj = unsafe { obj.as_ref() };
+                if f(index, obj).is_break() {
+                    // SAFETY: The stop pointer is valid for writes.
+                    unsafe { stop.as_ptr().write(Bool::YES) };
+                }
+            },
+        );
+        unsafe {
+            self.enumerateObjectsWithOptions_usingBlock(
+                crate::NSEnumerationOptions::Concurrent,
+                &block,
+            )
+        };
+    }
+}
+
 #[cfg(feature = "NSEnumerator")]
 unsafe impl<ObjectType: Message> iter::FastEnumerationHelper for NSArray<ObjectType> {
     type Item = ObjectType;
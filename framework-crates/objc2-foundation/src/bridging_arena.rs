@@ -0,0 +1,220 @@
+//! Batch creation of short-lived bridged objects.
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::{Cell, UnsafeCell};
+use core::fmt::{self, Write};
+
+use objc2::encode::Encode;
+use objc2::rc::{autoreleasepool, AutoreleaseSafe, Retained};
+use objc2::runtime::AnyObject;
+use objc2::Message;
+
+use crate::{NSArray, NSNumber, NSString, NSValue};
+
+/// An arena for creating many short-lived [`NSString`]s, [`NSNumber`]s and
+/// [`NSValue`]s in bulk.
+///
+/// Objects created in the arena are kept alive until the arena is
+/// [cleared][Self::clear] or dropped, and are handed out as plain references,
+/// which avoids the reference-counting traffic of passing [`Retained`]
+/// around.
+///
+/// The arena also recycles its internal buffers, so that formatting values
+/// with [`string_fmt`][Self::string_fmt] or converting collections with
+/// [`string_array`][Self::string_array] does not allocate a new Rust buffer
+/// each time. Use [`BridgingArena::scope`] to additionally drain any
+/// autoreleased temporaries created by Foundation in a single autorelease
+/// pool.
+///
+///
+/// # Example
+///
+/// Convert a lot of Rust strings into an `NSArray`.
+///
+/// ```
+/// use objc2_foundation::BridgingArena;
+///
+/// let names: Vec<String> = (0..1000).map(|i| format!("item {i}")).collect();
+///
+/// let array = BridgingArena::scope(|arena| arena.string_array(&names));
+/// assert_eq!(array.len(), 1000);
+/// assert_eq!(array.objectAtIndex(42).to_string(), "item 42");
+/// ```
+///
+/// Pass temporary objects to Objective-C without retaining each of them.
+///
+/// ```
+/// use objc2_foundation::{BridgingArena, NSMutableDictionary, NSString};
+///
+/// let dict = NSMutableDictionary::<NSString, NSString>::new();
+/// BridgingArena::scope(|arena| {
+///     for i in 0..100 {
+///         dict.insert(arena.string_fmt(i), arena.string_fmt(format_args!("value {i}")));
+///     }
+/// });
+/// assert_eq!(dict.len(), 100);
+/// ```
+pub struct BridgingArena {
+    /// The objects owned by the arena.
+    ///
+    /// Pushing to this may move the `Retained`s, but not the objects that
+    /// they point to, so references to those stay valid until the object is
+    /// removed from the arena (which requires `&mut self`).
+    objects: UnsafeCell<Vec<Retained<AnyObject>>>,
+    /// Recycled buffer for formatting values.
+    buffer: Cell<String>,
+    /// Recycled buffer for building arrays.
+    strings: Cell<Vec<Retained<NSString>>>,
+}
+
+impl BridgingArena {
+    /// Create a new, empty arena.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create a new, empty arena with space for at least `capacity` objects
+    /// before reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            objects: UnsafeCell::new(Vec::with_capacity(capacity)),
+            buffer: Cell::new(String::new()),
+            strings: Cell::new(Vec::new()),
+        }
+    }
+
+    /// Run the closure with a new arena inside a single autorelease pool.
+    ///
+    /// The arena and all objects in it are released when the closure
+    /// returns, along with any objects that Foundation autoreleased while
+    /// creating them.
+    pub fn scope<R, F>(f: F) -> R
+    where
+        F: AutoreleaseSafe + FnOnce(&Self) -> R,
+    {
+        autoreleasepool(|_| f(&Self::new()))
+    }
+
+    /// Keep the object alive for as long as the arena, and return a
+    /// reference to it.
+    pub fn insert<T: Message + 'static>(&self, obj: Retained<T>) -> &T {
+        // SAFETY: All objects can be stored as `AnyObject`, and `T: 'static`,
+        // so there is no data that would have to be kept alive.
+        let obj: Retained<AnyObject> = unsafe { Retained::cast_unchecked(obj) };
+        let ptr: *const AnyObject = &*obj;
+        // SAFETY: The vector is only accessed for the duration of this
+        // call, which doesn't call any user code, so there can be no other
+        // references to it.
+        unsafe { (*self.objects.get()).push(obj) };
+        // SAFETY: The object was created from a `Retained<T>`, and is kept
+        // alive until `clear` or `drop`, both of which require that the
+        // borrow of `self` has ended.
+        unsafe { &*ptr.cast::<T>() }
+    }
+
+    /// Create a string in the arena.
+    #[doc(alias = "initWithBytes:length:encoding:")]
+    pub fn string(&self, string: &str) -> &NSString {
+        self.insert(NSString::from_str(string))
+    }
+
+    /// Format the value into a string in the arena.
+    ///
+    /// The intermediary Rust string is reused between calls.
+    pub fn string_fmt(&self, value: impl fmt::Display) -> &NSString {
+        let mut buffer = self.buffer.take();
+        buffer.clear();
+        write!(buffer, "{value}").expect("a Display implementation returned an error");
+        let string = self.string(&buffer);
+        self.buffer.set(buffer);
+        string
+    }
+
+    /// Create an integer number in the arena.
+    #[doc(alias = "numberWithLongLong:")]
+    pub fn number_i64(&self, value: i64) -> &NSNumber {
+        self.insert(NSNumber::new_i64(value))
+    }
+
+    /// Create an unsigned integer number in the arena.
+    #[doc(alias = "numberWithUnsignedLongLong:")]
+    pub fn number_u64(&self, value: u64) -> &NSNumber {
+        self.insert(NSNumber::new_u64(value))
+    }
+
+    /// Create a floating-point number in the arena.
+    #[doc(alias = "numberWithDouble:")]
+    pub fn number_f64(&self, value: f64) -> &NSNumber {
+        self.insert(NSNumber::new_f64(value))
+    }
+
+    /// Create a boolean number in the arena.
+    #[doc(alias = "numberWithBool:")]
+    pub fn number_bool(&self, value: bool) -> &NSNumber {
+        self.insert(NSNumber::new_bool(value))
+    }
+
+    /// Create a value in the arena.
+    ///
+    /// See [`NSValue::new`] for details.
+    pub fn value<T: 'static + Copy + Encode>(&self, value: T) -> &NSValue {
+        self.insert(NSValue::new(value))
+    }
+
+    /// Convert the strings into an array.
+    ///
+    /// The strings themselves are owned by the returned array, not the
+    /// arena, but the buffer used to collect them is reused between calls.
+    pub fn string_array<I>(&self, strings: I) -> Retained<NSArray<NSString>>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut buffer = self.strings.take();
+        buffer.clear();
+        buffer.extend(
+            strings
+                .into_iter()
+                .map(|string| NSString::from_str(string.as_ref())),
+        );
+        let array = NSArray::from_retained_slice(&buffer);
+        buffer.clear();
+        self.strings.set(buffer);
+        array
+    }
+
+    /// The number of objects in the arena.
+    pub fn len(&self) -> usize {
+        // SAFETY: Only accessed for the duration of this call.
+        unsafe { (*self.objects.get()).len() }
+    }
+
+    /// Whether the arena contains no objects.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Release all objects in the arena.
+    ///
+    /// The arena's buffers are kept, so that the arena can be reused
+    /// without reallocating.
+    pub fn clear(&mut self) {
+        self.objects.get_mut().clear();
+    }
+}
+
+impl Default for BridgingArena {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for BridgingArena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BridgingArena")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,211 @@
+//! A typed wrapper around `NSCache`.
+use alloc::boxed::Box;
+use core::fmt;
+use core::marker::PhantomData;
+
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, NSObject, NSObjectProtocol, ProtocolObject};
+use objc2::{define_class, msg_send_id, AllocAnyThread, DefinedClass, Message};
+
+use crate::{NSCache, NSCacheDelegate};
+
+type EvictionHandler = Box<dyn Fn(&AnyObject) + Send + Sync>;
+
+define_class!(
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - `EvictionDelegate` does not implement `Drop`.
+    #[unsafe(super(NSObject))]
+    #[name = "__RustNSCacheEvictionDelegate"]
+    #[ivars = EvictionHandler]
+    struct EvictionDelegate;
+
+    unsafe impl NSObjectProtocol for EvictionDelegate {}
+
+    unsafe impl NSCacheDelegate for EvictionDelegate {
+        #[method(cache:willEvictObject:)]
+        fn will_evict_object(&self, _cache: &NSCache, obj: &AnyObject) {
+            (self.ivars())(obj);
+        }
+    }
+);
+
+// SAFETY: The handler is `Send + Sync`.
+unsafe impl Send for EvictionDelegate {}
+unsafe impl Sync for EvictionDelegate {}
+
+impl EvictionDelegate {
+    fn new(handler: EvictionHandler) -> Retained<Self> {
+        let this = Self::alloc().set_ivars(handler);
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+/// A purgeable cache mapping keys of type `K` to values of type `V`.
+///
+/// This wraps [`NSCache`], which automatically evicts its contents when the
+/// system is low on memory, or when the configured cost or count limits are
+/// exceeded. Unlike `NSMutableDictionary`, keys are not copied, and the cache
+/// can safely be used from multiple threads at once.
+///
+/// Keys are compared using `-isEqual:` and `-hash`.
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2_foundation::{ns_string, Cache, NSData, NSString};
+///
+/// let cache = Cache::<NSString, NSData>::new();
+/// cache.set_total_cost_limit(1024 * 1024);
+///
+/// let data = NSData::with_bytes(&[1, 2, 3]);
+/// cache.insert_with_cost(ns_string!("key"), &data, data.len());
+/// // The value may already have been evicted.
+/// let _ = cache.get(ns_string!("key"));
+/// ```
+pub struct Cache<K: Message, V: Message> {
+    inner: Retained<NSCache<K, V>>,
+    delegate: Option<Retained<EvictionDelegate>>,
+    _marker: PhantomData<(Retained<K>, Retained<V>)>,
+}
+
+// SAFETY: `NSCache` is thread-safe, and the keys and values are only shared
+// between threads if they are `Send + Sync`.
+unsafe impl<K: Message + Send + Sync, V: Message + Send + Sync> Send for Cache<K, V> {}
+unsafe impl<K: Message + Send + Sync, V: Message + Send + Sync> Sync for Cache<K, V> {}
+
+impl<K: Message, V: Message> Cache<K, V> {
+    /// Create an empty cache without any limits.
+    pub fn new() -> Self {
+        Self {
+            inner: unsafe { NSCache::new() },
+            delegate: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The underlying `NSCache`.
+    pub fn as_ns_cache(&self) -> &NSCache<K, V> {
+        &self.inner
+    }
+
+    /// Get the value for the given key, if it is still in the cache.
+    #[doc(alias = "objectForKey:")]
+    pub fn get(&self, key: &K) -> Option<Retained<V>> {
+        unsafe { self.inner.objectForKey(key) }
+    }
+
+    /// Insert a value with a cost of zero, replacing any existing value for
+    /// the key.
+    #[doc(alias = "setObject:forKey:")]
+    pub fn insert(&self, key: &K, value: &V) {
+        unsafe { self.inner.setObject_forKey(value, key) }
+    }
+
+    /// Insert a value with the given cost, replacing any existing value for
+    /// the key.
+    ///
+    /// The cost is compared against the [total cost
+    /// limit](Self::set_total_cost_limit) to decide when to evict values.
+    /// Usually, this is the size of the value in bytes.
+    #[doc(alias = "setObject:forKey:cost:")]
+    pub fn insert_with_cost(&self, key: &K, value: &V, cost: usize) {
+        unsafe { self.inner.setObject_forKey_cost(value, key, cost) }
+    }
+
+    /// Remove the value for the given key.
+    #[doc(alias = "removeObjectForKey:")]
+    pub fn remove(&self, key: &K) {
+        unsafe { self.inner.removeObjectForKey(key) }
+    }
+
+    /// Remove all values from the cache.
+    #[doc(alias = "removeAllObjects")]
+    pub fn clear(&self) {
+        unsafe { self.inner.removeAllObjects() }
+    }
+
+    /// The maximum total cost of the values in the cache before values are
+    /// evicted, or zero if there is no limit.
+    #[doc(alias = "totalCostLimit")]
+    pub fn total_cost_limit(&self) -> usize {
+        unsafe { self.inner.totalCostLimit() }
+    }
+
+    /// Set the maximum total cost of the values in the cache.
+    ///
+    /// This is not a strict limit; the cache may evict values immediately,
+    /// later, or never, depending on the implementation.
+    #[doc(alias = "setTotalCostLimit:")]
+    pub fn set_total_cost_limit(&self, limit: usize) {
+        unsafe { self.inner.setTotalCostLimit(limit) }
+    }
+
+    /// The maximum number of values in the cache before values are evicted,
+    /// or zero if there is no limit.
+    #[doc(alias = "countLimit")]
+    pub fn count_limit(&self) -> usize {
+        unsafe { self.inner.countLimit() }
+    }
+
+    /// Set the maximum number of values in the cache.
+    ///
+    /// Like the total cost limit, this is not a strict limit.
+    #[doc(alias = "setCountLimit:")]
+    pub fn set_count_limit(&self, limit: usize) {
+        unsafe { self.inner.setCountLimit(limit) }
+    }
+
+    /// Call the given closure with each value that is about to be evicted
+    /// or removed from the cache, replacing any previous closure.
+    ///
+    /// The closure may be called on any thread, while the cache is locked,
+    /// so it must not access the cache itself. For the same reason, the
+    /// values must be `Send + Sync`.
+    #[doc(alias = "setDelegate:")]
+    #[doc(alias = "cache:willEvictObject:")]
+    pub fn on_evict(&mut self, f: impl Fn(&V) + Send + Sync + 'static)
+    where
+        V: Send + Sync + 'static,
+    {
+        let delegate = EvictionDelegate::new(Box::new(move |obj| {
+            // SAFETY: The cache only contains values of type `V`.
+            let value = unsafe { &*(obj as *const AnyObject).cast::<V>() };
+            f(value);
+        }));
+        unsafe {
+            self.inner
+                .setDelegate(Some(ProtocolObject::from_ref(&*delegate)))
+        };
+        // The cache does not retain its delegate, so we keep it alive until
+        // it is replaced or the cache is dropped.
+        self.delegate = Some(delegate);
+    }
+}
+
+impl<K: Message, V: Message> Default for Cache<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Message, V: Message> Drop for Cache<K, V> {
+    fn drop(&mut self) {
+        if self.delegate.is_some() {
+            // The delegate is released below, while the cache itself may
+            // outlive us, since it is exposed in `as_ns_cache`.
+            unsafe { self.inner.setDelegate(None) };
+        }
+    }
+}
+
+impl<K: Message, V: Message> fmt::Debug for Cache<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cache")
+            .field("total_cost_limit", &self.total_cost_limit())
+            .field("count_limit", &self.count_limit())
+            .field("on_evict", &self.delegate.is_some())
+            .finish_non_exhaustive()
+    }
+}
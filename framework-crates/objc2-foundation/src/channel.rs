@@ -0,0 +1,499 @@
+//! Channels for handing values from Objective-C callbacks to Rust.
+//!
+//! Delegate methods, completion handlers and notification observers are
+//! often called on whatever thread or queue the framework chooses. These
+//! channels let such callbacks send values to a Rust consumer, which can
+//! either block until a value arrives, or wait for one asynchronously.
+//!
+//! A [`bounded`] channel has a fixed capacity, so a fast producer cannot
+//! make the queue grow without limit: once the channel is full,
+//! [`Sender::send`] blocks until the consumer catches up, while
+//! [`Sender::try_send`] hands the value back, allowing the callback to drop
+//! or coalesce it instead. An [`unbounded`] channel never blocks the sender,
+//! which is what you want when every value must be delivered, and the
+//! producer cannot be slowed down.
+//!
+//! The channels are synchronized with a [`NSCondition`], and do not need
+//! the `std` feature. Every blocking wait runs inside its own autorelease
+//! pool, so threads without a pool of their own (e.g. threads spawned
+//! directly with `pthread_create`) do not leak objects while waiting.
+//!
+//!
+//! # Example
+//!
+//! Send values from a (here simulated) callback on another thread.
+//!
+//! ```
+//! use objc2_foundation::channel;
+//!
+//! let (sender, receiver) = channel::bounded(2);
+//!
+//! std::thread::spawn(move || {
+//!     for i in 0..10 {
+//!         // Blocks while the receiver is two values behind.
+//!         if sender.send(i).is_err() {
+//!             break; // The receiver was dropped.
+//!         }
+//!     }
+//! });
+//!
+//! let received: Vec<i32> = std::iter::from_fn(|| receiver.recv()).collect();
+//! assert_eq!(received, (0..10).collect::<Vec<_>>());
+//! ```
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::future::Future;
+use core::task::{Context, Poll, Waker};
+#[cfg(feature = "NSDate")]
+use core::time::Duration;
+
+use objc2::rc::{autoreleasepool, Retained};
+
+#[cfg(feature = "NSDate")]
+use crate::NSDate;
+use crate::{NSCondition, NSLockGuard};
+
+struct State<T> {
+    queue: VecDeque<T>,
+    capacity: usize,
+    senders: usize,
+    receiver_alive: bool,
+    waker: Option<Waker>,
+}
+
+struct Shared<T> {
+    condition: Retained<NSCondition>,
+    /// Only accessed while `condition` is locked.
+    state: UnsafeCell<State<T>>,
+}
+
+// SAFETY: The state is only accessed while the condition's lock is held, so
+// the values are never accessed concurrently, only moved between threads.
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+/// The state of the channel, accessible while the lock is held.
+struct Locked<'a, T> {
+    guard: NSLockGuard<'a, NSCondition>,
+    state: &'a UnsafeCell<State<T>>,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> Locked<'_, T> {
+        Locked {
+            guard: self.condition.lock_guard(),
+            state: &self.state,
+        }
+    }
+}
+
+impl<T> Locked<'_, T> {
+    fn state(&mut self) -> &mut State<T> {
+        // SAFETY: The lock is held for as long as `self` is alive, and the
+        // returned reference cannot outlive a call to `wait`, since that
+        // would require borrowing `self` again (waiting releases the lock).
+        unsafe { &mut *self.state.get() }
+    }
+
+    /// Wait for the state to change.
+    fn wait(&self) {
+        autoreleasepool(|_| self.guard.wait());
+    }
+
+    /// Wait for the state to change, or for the deadline to be reached.
+    ///
+    /// Returns `false` if the deadline was reached.
+    #[cfg(feature = "NSDate")]
+    fn wait_until(&self, deadline: &NSDate) -> bool {
+        autoreleasepool(|_| self.guard.wait_until(deadline))
+    }
+
+    /// Wake up everybody waiting on the state, and return the receiver's
+    /// waker, which should be woken after the lock is released.
+    fn notify(&mut self) -> Option<Waker> {
+        self.guard.notify_all();
+        self.state().waker.take()
+    }
+}
+
+/// Wake the task outside the lock, in case the waker runs it inline.
+fn wake(waker: Option<Waker>) {
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
+
+/// The absolute date `timeout` from now.
+#[cfg(feature = "NSDate")]
+fn deadline(timeout: Duration) -> Retained<NSDate> {
+    unsafe { NSDate::dateWithTimeIntervalSinceNow(timeout.as_secs_f64()) }
+}
+
+/// Create a channel that can hold at most `capacity` values.
+///
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+#[doc(alias = "NSCondition")]
+pub fn bounded<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "channel capacity must be non-zero");
+    new(VecDeque::with_capacity(capacity), capacity)
+}
+
+/// Create a channel that can hold any number of values.
+///
+/// Sending on this never blocks, and only fails if the receiver was
+/// dropped.
+#[doc(alias = "NSCondition")]
+pub fn unbounded<T>() -> (Sender<T>, Receiver<T>) {
+    new(VecDeque::new(), usize::MAX)
+}
+
+fn new<T>(queue: VecDeque<T>, capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Shared {
+        // SAFETY: Creating a condition has no preconditions.
+        condition: unsafe { NSCondition::new() },
+        state: UnsafeCell::new(State {
+            queue,
+            capacity,
+            senders: 1,
+            receiver_alive: true,
+            waker: None,
+        }),
+    });
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+/// The sending half of a channel.
+///
+/// This can be cloned to send from several callbacks, and the channel is
+/// closed once all senders have been dropped.
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// The receiving half of a channel.
+///
+/// Once this is dropped, sending fails, and any queued values are dropped.
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// An error returned from [`Sender::send`] when the receiver was dropped.
+///
+/// Contains the value that could not be sent.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sending on a channel whose receiver was dropped")
+    }
+}
+
+#[cfg(feature = "std")] // use core::error::Error from Rust 1.81 once in MSRV.
+impl<T> std::error::Error for SendError<T> {}
+
+/// An error returned from [`Sender::try_send`].
+///
+/// Contains the value that could not be sent.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrySendError<T> {
+    /// The channel is full (or stayed full until the timeout elapsed).
+    Full(T),
+    /// The receiver was dropped.
+    Disconnected(T),
+}
+
+impl<T> TrySendError<T> {
+    /// Get back the value that could not be sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(value) | Self::Disconnected(value) => value,
+        }
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => f.write_str("Full(..)"),
+            Self::Disconnected(_) => f.write_str("Disconnected(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => write!(f, "sending on a full channel"),
+            Self::Disconnected(_) => {
+                write!(f, "sending on a channel whose receiver was dropped")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")] // use core::error::Error from Rust 1.81 once in MSRV.
+impl<T> std::error::Error for TrySendError<T> {}
+
+impl<T> Sender<T> {
+    /// Send a value, blocking the current thread while the channel is full.
+    ///
+    /// Beware that this will deadlock if the receiver is waiting for
+    /// something that the current thread would do after sending, e.g. if
+    /// the receiver runs on the main thread and this is called from a
+    /// callback on the main thread.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns the value back if the receiver was dropped.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut locked = self.shared.lock();
+        loop {
+            if !locked.state().receiver_alive {
+                return Err(SendError(value));
+            }
+            if locked.state().queue.len() < locked.state().capacity {
+                locked.state().queue.push_back(value);
+                let waker = locked.notify();
+                drop(locked);
+                wake(waker);
+                return Ok(());
+            }
+            locked.wait();
+        }
+    }
+
+    /// Send a value if the channel has room for it, without blocking.
+    ///
+    /// This is usually what you want in callbacks that must not block, such
+    /// as callbacks on the main thread.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns the value back if the channel is full, or if the receiver was
+    /// dropped.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        let mut locked = self.shared.lock();
+        if !locked.state().receiver_alive {
+            return Err(TrySendError::Disconnected(value));
+        }
+        if locked.state().queue.len() >= locked.state().capacity {
+            return Err(TrySendError::Full(value));
+        }
+        locked.state().queue.push_back(value);
+        let waker = locked.notify();
+        drop(locked);
+        wake(waker);
+        Ok(())
+    }
+
+    /// Send a value, blocking the current thread for at most `timeout` while
+    /// the channel is full.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns the value back if the channel was still full when the timeout
+    /// elapsed, or if the receiver was dropped.
+    #[cfg(feature = "NSDate")]
+    #[doc(alias = "waitUntilDate:")]
+    pub fn send_timeout(&self, value: T, timeout: Duration) -> Result<(), TrySendError<T>> {
+        // Compute the deadline once, such that spurious wakeups and other
+        // senders racing us for the free slot don't extend the total wait.
+        let deadline = deadline(timeout);
+        let mut locked = self.shared.lock();
+        loop {
+            if !locked.state().receiver_alive {
+                return Err(TrySendError::Disconnected(value));
+            }
+            if locked.state().queue.len() < locked.state().capacity {
+                locked.state().queue.push_back(value);
+                let waker = locked.notify();
+                drop(locked);
+                wake(waker);
+                return Ok(());
+            }
+            if !locked.wait_until(&deadline) {
+                return Err(TrySendError::Full(value));
+            }
+        }
+    }
+
+    /// Whether the receiver was dropped.
+    pub fn is_disconnected(&self) -> bool {
+        !self.shared.lock().state().receiver_alive
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().state().senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut locked = self.shared.lock();
+        locked.state().senders -= 1;
+        let waker = if locked.state().senders == 0 {
+            locked.notify()
+        } else {
+            None
+        };
+        drop(locked);
+        wake(waker);
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut locked = self.shared.lock();
+        f.debug_struct("Sender")
+            .field("len", &locked.state().queue.len())
+            .field("capacity", &locked.state().capacity)
+            .field("disconnected", &!locked.state().receiver_alive)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Take the next value out of the queue, and let a blocked sender know
+    /// that there is room for another value.
+    fn pop(locked: &mut Locked<'_, T>) -> Option<T> {
+        let value = locked.state().queue.pop_front()?;
+        locked.guard.notify_all();
+        Some(value)
+    }
+
+    /// Get the next value if one is available, without blocking.
+    pub fn try_recv(&self) -> Option<T> {
+        Self::pop(&mut self.shared.lock())
+    }
+
+    /// Block the current thread until a value is available.
+    ///
+    /// Returns [`None`] once all senders have been dropped and the queue is
+    /// empty.
+    pub fn recv(&self) -> Option<T> {
+        let mut locked = self.shared.lock();
+        loop {
+            if let Some(value) = Self::pop(&mut locked) {
+                return Some(value);
+            }
+            if locked.state().senders == 0 {
+                return None;
+            }
+            locked.wait();
+        }
+    }
+
+    /// Block the current thread for at most `timeout` until a value is
+    /// available.
+    ///
+    /// Returns [`None`] if the timeout elapsed, or if all senders have been
+    /// dropped and the queue is empty.
+    #[cfg(feature = "NSDate")]
+    #[doc(alias = "waitUntilDate:")]
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<T> {
+        let deadline = deadline(timeout);
+        let mut locked = self.shared.lock();
+        loop {
+            if let Some(value) = Self::pop(&mut locked) {
+                return Some(value);
+            }
+            if locked.state().senders == 0 || !locked.wait_until(&deadline) {
+                return None;
+            }
+        }
+    }
+
+    /// Poll for the next value.
+    ///
+    /// Returns `Poll::Ready(None)` once all senders have been dropped and
+    /// the queue is empty. This can be used to implement `futures::Stream`.
+    pub fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut locked = self.shared.lock();
+        if let Some(value) = Self::pop(&mut locked) {
+            return Poll::Ready(Some(value));
+        }
+        if locked.state().senders == 0 {
+            return Poll::Ready(None);
+        }
+        match &mut locked.state().waker {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            waker => *waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+
+    /// Wait asynchronously for the next value.
+    ///
+    /// Returns [`None`] once all senders have been dropped and the queue is
+    /// empty.
+    pub fn recv_async(&self) -> impl Future<Output = Option<T>> + '_ {
+        core::future::poll_fn(|cx| self.poll_recv(cx))
+    }
+
+    /// The number of values currently in the queue.
+    pub fn len(&self) -> usize {
+        self.shared.lock().state().queue.len()
+    }
+
+    /// Whether the queue is currently empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of values that the queue can hold.
+    ///
+    /// This is `usize::MAX` for [`unbounded`] channels.
+    pub fn capacity(&self) -> usize {
+        self.shared.lock().state().capacity
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut locked = self.shared.lock();
+        locked.state().receiver_alive = false;
+        locked.state().waker = None;
+        let queue = core::mem::take(&mut locked.state().queue);
+        locked.guard.notify_all();
+        drop(locked);
+        // Drop the values outside the lock, since their destructors may
+        // (indirectly) try to use the channel.
+        drop(queue);
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut locked = self.shared.lock();
+        f.debug_struct("Receiver")
+            .field("len", &locked.state().queue.len())
+            .field("capacity", &locked.state().capacity)
+            .field("senders", &locked.state().senders)
+            .finish_non_exhaustive()
+    }
+}
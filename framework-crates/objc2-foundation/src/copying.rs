@@ -75,6 +75,22 @@ unsafe impl<P: ?Sized> MutableCopyingHelper for ProtocolObject<P> {
     type Result = Self;
 }
 
+/// Types that can be used as keys in `NSDictionary`.
+///
+/// Dictionaries copy their keys when inserting, so keys must implement
+/// [`NSCopying`], and the dictionary's key type will be the [`CopyingHelper`]
+/// result; e.g. inserting a `NSMutableString` key will store a `NSString`.
+///
+/// This is automatically implemented for all types that fulfill those
+/// requirements, and is mainly useful as a shorthand in bounds:
+///
+/// ```ignore
+/// fn insert_all<K: NSCopyingKey<Result = NSString>>(keys: &[&K]) { ... }
+/// ```
+pub trait NSCopyingKey: Message + NSCopying + CopyingHelper {}
+
+impl<T: ?Sized + Message + NSCopying + CopyingHelper> NSCopyingKey for T {}
+
 extern_protocol!(
     /// A protocol to provide functional copies of objects.
     ///
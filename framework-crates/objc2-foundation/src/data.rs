@@ -39,6 +39,14 @@ impl NSData {
         unsafe { Self::initWithBytes_length(Self::alloc(), bytes_ptr, bytes.len()) }
     }
 
+    /// Create a new data object that takes ownership of the bytes in the
+    /// given vector, without copying them.
+    ///
+    /// The vector is dropped when the data object is deallocated.
+    ///
+    /// This is useful for passing large payloads to Foundation APIs without
+    /// keeping two copies of them around.
+    #[doc(alias = "initWithBytesNoCopy:length:deallocator:")]
     #[cfg(feature = "block2")]
     #[cfg(feature = "alloc")]
     pub fn from_vec(bytes: Vec<u8>) -> Retained<Self> {
@@ -53,6 +61,12 @@ impl NSMutableData {
         unsafe { Self::initWithBytes_length(Self::alloc(), bytes_ptr, bytes.len()) }
     }
 
+    /// Create a new mutable data object with the bytes in the given vector.
+    ///
+    /// Note that unlike [`NSData::from_vec`], Foundation may choose to copy
+    /// the bytes into its own (growable) storage, in which case the vector
+    /// is dropped immediately.
+    #[doc(alias = "initWithBytesNoCopy:length:deallocator:")]
     #[cfg(feature = "block2")]
     pub fn from_vec(bytes: Vec<u8>) -> Retained<Self> {
         // SAFETY: Same as `NSData::from_vec`
@@ -286,6 +300,9 @@ impl<'a> Extend<&'a u8> for &NSMutableData {
     }
 }
 
+/// Append to the data, growing it as needed.
+///
+/// This also makes `Retained<NSMutableData>` usable as a writer.
 #[cfg(feature = "std")]
 impl std::io::Write for &NSMutableData {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
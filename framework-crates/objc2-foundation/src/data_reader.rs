@@ -0,0 +1,243 @@
+//! Positioned reading of binary payloads stored in [`NSData`].
+use alloc::string::String;
+use alloc::vec;
+use core::fmt;
+use core::ptr::NonNull;
+use core::str::Utf8Error;
+
+use objc2::rc::Retained;
+
+use crate::{NSData, NSRange};
+
+/// A cursor for parsing binary data out of an [`NSData`].
+///
+/// Each read copies only the requested bytes out of the data (using
+/// `getBytes:range:`), so parsing a small header out of a large payload
+/// does not copy the whole buffer, like [`NSData::to_vec`] would.
+///
+/// Reads that would go past the end of the data fail with
+/// [`NSDataReaderError::UnexpectedEnd`], and leave the position untouched.
+///
+///
+/// # Examples
+///
+/// Parse a length-prefixed string from a payload received from an
+/// accessory.
+///
+/// ```
+/// use objc2_foundation::{NSData, NSDataReader};
+///
+/// let data = NSData::with_bytes(&[0x01, 0x00, 0x03, b'f', b'o', b'o', 0xff]);
+/// let mut reader = NSDataReader::new(&data);
+///
+/// assert_eq!(reader.read_u16_be().unwrap(), 0x0100);
+/// let len = reader.read_u8().unwrap() as usize;
+/// assert_eq!(reader.read_utf8(len).unwrap(), "foo");
+/// assert_eq!(reader.remaining(), 1);
+/// assert!(reader.read_u32_le().is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct NSDataReader<'a> {
+    data: &'a NSData,
+    position: usize,
+}
+
+macro_rules! read_numbers {
+    ($($ty:ident => $le:ident, $be:ident, $ne:ident;)*) => {
+        $(
+            #[doc = concat!("Read a little-endian [`", stringify!($ty), "`].")]
+            pub fn $le(&mut self) -> Result<$ty, NSDataReaderError> {
+                self.read_array().map($ty::from_le_bytes)
+            }
+
+            #[doc = concat!("Read a big-endian [`", stringify!($ty), "`].")]
+            pub fn $be(&mut self) -> Result<$ty, NSDataReaderError> {
+                self.read_array().map($ty::from_be_bytes)
+            }
+
+            #[doc = concat!("Read a native-endian [`", stringify!($ty), "`].")]
+            pub fn $ne(&mut self) -> Result<$ty, NSDataReaderError> {
+                self.read_array().map($ty::from_ne_bytes)
+            }
+        )*
+    };
+}
+
+impl<'a> NSDataReader<'a> {
+    /// Create a new reader positioned at the start of the data.
+    #[inline]
+    pub fn new(data: &'a NSData) -> Self {
+        Self { data, position: 0 }
+    }
+
+    /// The data that is being read from.
+    #[inline]
+    pub fn data(&self) -> &'a NSData {
+        self.data
+    }
+
+    /// The current offset into the data.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Move the reader to the given offset.
+    ///
+    /// The offset may be at most the length of the data.
+    pub fn set_position(&mut self, position: usize) -> Result<(), NSDataReaderError> {
+        if position <= self.data.len() {
+            self.position = position;
+            Ok(())
+        } else {
+            Err(NSDataReaderError::UnexpectedEnd {
+                requested: position.saturating_sub(self.position),
+                remaining: self.remaining(),
+            })
+        }
+    }
+
+    /// The number of bytes left to read.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        // Saturate in case the data was shrunk since the last read.
+        self.data.len().saturating_sub(self.position)
+    }
+
+    /// Whether all bytes have been read.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Check that `len` bytes are available, and return the range of them.
+    fn take(&mut self, len: usize) -> Result<NSRange, NSDataReaderError> {
+        let remaining = self.remaining();
+        if len <= remaining {
+            let range = NSRange::new(self.position, len);
+            self.position += len;
+            Ok(range)
+        } else {
+            Err(NSDataReaderError::UnexpectedEnd {
+                requested: len,
+                remaining,
+            })
+        }
+    }
+
+    /// Advance the reader by `len` bytes without reading them.
+    pub fn skip(&mut self, len: usize) -> Result<(), NSDataReaderError> {
+        self.take(len).map(|_| ())
+    }
+
+    /// Fill `buf` with the next bytes of the data.
+    #[doc(alias = "getBytes:range:")]
+    pub fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), NSDataReaderError> {
+        let range = self.take(buf.len())?;
+        if !buf.is_empty() {
+            let ptr = NonNull::new(buf.as_mut_ptr()).unwrap().cast();
+            // SAFETY: The buffer is valid for writing `range.length` bytes,
+            // and the range was checked to be within the bounds of the data.
+            unsafe { self.data.getBytes_range(ptr, range) };
+        }
+        Ok(())
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], NSDataReaderError> {
+        let mut buf = [0; N];
+        self.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Read a single byte.
+    pub fn read_u8(&mut self) -> Result<u8, NSDataReaderError> {
+        self.read_array().map(u8::from_ne_bytes)
+    }
+
+    /// Read a single signed byte.
+    pub fn read_i8(&mut self) -> Result<i8, NSDataReaderError> {
+        self.read_array().map(i8::from_ne_bytes)
+    }
+
+    read_numbers! {
+        u16 => read_u16_le, read_u16_be, read_u16_ne;
+        i16 => read_i16_le, read_i16_be, read_i16_ne;
+        u32 => read_u32_le, read_u32_be, read_u32_ne;
+        i32 => read_i32_le, read_i32_be, read_i32_ne;
+        u64 => read_u64_le, read_u64_be, read_u64_ne;
+        i64 => read_i64_le, read_i64_be, read_i64_ne;
+        f32 => read_f32_le, read_f32_be, read_f32_ne;
+        f64 => read_f64_le, read_f64_be, read_f64_ne;
+    }
+
+    /// Read `len` bytes, and decode them as UTF-8.
+    ///
+    /// If the bytes are not valid UTF-8, the position is still advanced past
+    /// them.
+    pub fn read_utf8(&mut self, len: usize) -> Result<String, NSDataReaderError> {
+        let mut buf = vec![0; len];
+        self.read_exact(&mut buf)?;
+        String::from_utf8(buf).map_err(|err| NSDataReaderError::InvalidUtf8(err.utf8_error()))
+    }
+
+    /// Read the next `len` bytes as a new data object.
+    ///
+    /// Foundation usually avoids copying the bytes for immutable data, and
+    /// instead references the original buffer.
+    #[doc(alias = "subdataWithRange:")]
+    pub fn read_subdata(&mut self, len: usize) -> Result<Retained<NSData>, NSDataReaderError> {
+        let range = self.take(len)?;
+        // SAFETY: The range was checked to be within the bounds of the data.
+        Ok(unsafe { self.data.subdataWithRange(range) })
+    }
+}
+
+/// Read the remaining bytes of the data.
+#[cfg(feature = "std")]
+impl std::io::Read for NSDataReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(self.remaining());
+        // Cannot fail, we just checked the remaining length.
+        let _ = NSDataReader::read_exact(self, &mut buf[..n]);
+        Ok(n)
+    }
+}
+
+/// The error returned when reading from an [`NSDataReader`] fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NSDataReaderError {
+    /// The read would go past the end of the data.
+    UnexpectedEnd {
+        /// The number of bytes that was requested.
+        requested: usize,
+        /// The number of bytes that was left in the data.
+        remaining: usize,
+    },
+    /// The bytes read were not valid UTF-8.
+    InvalidUtf8(Utf8Error),
+}
+
+impl fmt::Display for NSDataReaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd {
+                requested,
+                remaining,
+            } => write!(
+                f,
+                "tried to read {requested} bytes from NSData, but only {remaining} bytes remain",
+            ),
+            Self::InvalidUtf8(err) => write!(f, "NSData contained invalid UTF-8: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NSDataReaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::UnexpectedEnd { .. } => None,
+            Self::InvalidUtf8(err) => Some(err),
+        }
+    }
+}
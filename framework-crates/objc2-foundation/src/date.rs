@@ -0,0 +1,220 @@
+use core::time::Duration;
+
+use objc2::rc::Retained;
+
+use crate::NSDate;
+
+/// Conversions to and from Rust's time types.
+///
+/// `NSDate` stores time as a floating-point number of seconds since
+/// 2001-01-01 00:00:00 UTC, so nanosecond precision may be lost in these
+/// conversions.
+impl NSDate {
+    /// Create a `NSDate` from a [`SystemTime`].
+    ///
+    /// Nanosecond precision may be lost.
+    ///
+    /// [`SystemTime`]: std::time::SystemTime
+    #[cfg(feature = "std")]
+    #[doc(alias = "dateWithTimeIntervalSince1970:")]
+    pub fn from_system_time(time: &std::time::SystemTime) -> Retained<Self> {
+        let since_1970 = match time.duration_since(std::time::UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs_f64(),
+            Err(err) => -err.duration().as_secs_f64(),
+        };
+        unsafe { Self::dateWithTimeIntervalSince1970(since_1970) }
+    }
+
+    /// Try to convert the `NSDate` to a [`SystemTime`].
+    ///
+    /// Nanosecond precision may be lost.
+    ///
+    /// Returns `None` if the date is too far in the past or future to fit
+    /// inside [`SystemTime`] (this is platform-specific).
+    ///
+    /// [`SystemTime`]: std::time::SystemTime
+    #[cfg(feature = "std")]
+    #[doc(alias = "timeIntervalSince1970")]
+    pub fn to_system_time(&self) -> Option<std::time::SystemTime> {
+        let since_1970 = unsafe { self.timeIntervalSince1970() };
+
+        // Times before 1970 are negative, which `Duration` can't represent.
+        if since_1970 < 0.0 {
+            let duration = Duration::try_from_secs_f64(-since_1970).ok()?;
+            std::time::UNIX_EPOCH.checked_sub(duration)
+        } else {
+            let duration = Duration::try_from_secs_f64(since_1970).ok()?;
+            std::time::UNIX_EPOCH.checked_add(duration)
+        }
+    }
+
+    /// The amount of time elapsed from an earlier date to this one.
+    ///
+    /// Returns `None` if `earlier` is later than this date.
+    #[doc(alias = "timeIntervalSinceDate:")]
+    pub fn duration_since(&self, earlier: &NSDate) -> Option<Duration> {
+        let interval = unsafe { self.timeIntervalSinceDate(earlier) };
+        Duration::try_from_secs_f64(interval).ok()
+    }
+
+    /// Create a new date by adding the given duration to this one.
+    #[doc(alias = "dateByAddingTimeInterval:")]
+    pub fn add_duration(&self, duration: Duration) -> Retained<Self> {
+        unsafe { self.dateByAddingTimeInterval(duration.as_secs_f64()) }
+    }
+
+    /// Create a new date by subtracting the given duration from this one.
+    #[doc(alias = "dateByAddingTimeInterval:")]
+    pub fn sub_duration(&self, duration: Duration) -> Retained<Self> {
+        unsafe { self.dateByAddingTimeInterval(-duration.as_secs_f64()) }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<&NSDate> for std::time::SystemTime {
+    /// Convert the date to a [`SystemTime`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the date does not fit inside [`SystemTime`], see
+    /// [`NSDate::to_system_time`] for a non-panicking version.
+    ///
+    /// [`SystemTime`]: std::time::SystemTime
+    fn from(date: &NSDate) -> Self {
+        date.to_system_time().expect("NSDate out of range of SystemTime")
+    }
+}
+
+/// Conversions to and from the [`time`] crate.
+#[cfg(feature = "time")]
+impl NSDate {
+    /// Create a `NSDate` from a [`time::OffsetDateTime`].
+    ///
+    /// The offset is only used to determine the point in time, and is not
+    /// stored in the date (`NSDate` has no notion of time zones).
+    #[doc(alias = "dateWithTimeIntervalSince1970:")]
+    pub fn from_offset_date_time(date: &::time::OffsetDateTime) -> Retained<Self> {
+        let since_1970 = date.unix_timestamp_nanos() as f64 / 1e9;
+        unsafe { Self::dateWithTimeIntervalSince1970(since_1970) }
+    }
+
+    /// Try to convert the `NSDate` to a [`time::OffsetDateTime`] in UTC.
+    ///
+    /// Returns `None` if the date is out of the range supported by `time`.
+    #[doc(alias = "timeIntervalSince1970")]
+    pub fn to_offset_date_time(&self) -> Option<::time::OffsetDateTime> {
+        let since_1970 = unsafe { self.timeIntervalSince1970() };
+        if !since_1970.is_finite() {
+            return None;
+        }
+        let nanos = (since_1970 * 1e9).round() as i128;
+        ::time::OffsetDateTime::from_unix_timestamp_nanos(nanos).ok()
+    }
+
+    /// Try to convert the `NSDate` to a [`time::OffsetDateTime`], with the
+    /// offset that the given time zone has at that date.
+    ///
+    /// This takes daylight saving time into account.
+    ///
+    /// Returns `None` if the date is out of the range supported by `time`.
+    #[cfg(feature = "NSTimeZone")]
+    #[doc(alias = "secondsFromGMTForDate:")]
+    pub fn to_offset_date_time_in(
+        &self,
+        time_zone: &crate::NSTimeZone,
+    ) -> Option<::time::OffsetDateTime> {
+        let offset = time_zone.utc_offset_at(self)?;
+        Some(self.to_offset_date_time()?.to_offset(offset))
+    }
+}
+
+/// Conversions to and from the [`time`] crate.
+#[cfg(all(feature = "time", feature = "NSTimeZone"))]
+impl crate::NSTimeZone {
+    /// Create a time zone with a fixed offset from UTC.
+    #[doc(alias = "timeZoneForSecondsFromGMT:")]
+    pub fn from_utc_offset(offset: ::time::UtcOffset) -> Retained<Self> {
+        let seconds = offset.whole_seconds() as crate::NSInteger;
+        unsafe { Self::timeZoneForSecondsFromGMT(seconds) }
+    }
+
+    /// The time zone's offset from UTC at the given date.
+    ///
+    /// Returns `None` if the offset is out of the range supported by `time`.
+    #[doc(alias = "secondsFromGMTForDate:")]
+    pub fn utc_offset_at(&self, date: &NSDate) -> Option<::time::UtcOffset> {
+        let seconds = unsafe { self.secondsFromGMTForDate(date) };
+        ::time::UtcOffset::from_whole_seconds(seconds.try_into().ok()?).ok()
+    }
+}
+
+/// Conversions to and from the [`chrono`] crate.
+#[cfg(feature = "chrono")]
+impl NSDate {
+    /// Create a `NSDate` from a [`chrono::DateTime`].
+    ///
+    /// The time zone is only used to determine the point in time, and is not
+    /// stored in the date (`NSDate` has no notion of time zones).
+    #[doc(alias = "dateWithTimeIntervalSince1970:")]
+    pub fn from_date_time<Tz: ::chrono::TimeZone>(
+        date: &::chrono::DateTime<Tz>,
+    ) -> Retained<Self> {
+        let since_1970 = date.timestamp() as f64 + date.timestamp_subsec_nanos() as f64 / 1e9;
+        unsafe { Self::dateWithTimeIntervalSince1970(since_1970) }
+    }
+
+    /// Try to convert the `NSDate` to a [`chrono::DateTime`] in UTC.
+    ///
+    /// Returns `None` if the date is out of the range supported by `chrono`.
+    #[doc(alias = "timeIntervalSince1970")]
+    pub fn to_date_time(&self) -> Option<::chrono::DateTime<::chrono::Utc>> {
+        let since_1970 = unsafe { self.timeIntervalSince1970() };
+        if !since_1970.is_finite() {
+            return None;
+        }
+        let secs = since_1970.floor();
+        // Rounding may push the fraction up to a full second.
+        let nanos = (((since_1970 - secs) * 1e9).round() as u32).min(999_999_999);
+        if secs < i64::MIN as f64 || i64::MAX as f64 <= secs {
+            return None;
+        }
+        ::chrono::DateTime::from_timestamp(secs as i64, nanos)
+    }
+
+    /// Try to convert the `NSDate` to a [`chrono::DateTime`], with the offset
+    /// that the given time zone has at that date.
+    ///
+    /// This takes daylight saving time into account.
+    ///
+    /// Returns `None` if the date is out of the range supported by `chrono`.
+    #[cfg(feature = "NSTimeZone")]
+    #[doc(alias = "secondsFromGMTForDate:")]
+    pub fn to_date_time_in(
+        &self,
+        time_zone: &crate::NSTimeZone,
+    ) -> Option<::chrono::DateTime<::chrono::FixedOffset>> {
+        let offset = time_zone.fixed_offset_at(self)?;
+        Some(self.to_date_time()?.with_timezone(&offset))
+    }
+}
+
+/// Conversions to and from the [`chrono`] crate.
+#[cfg(all(feature = "chrono", feature = "NSTimeZone"))]
+impl crate::NSTimeZone {
+    /// Create a time zone with the given fixed offset from UTC.
+    #[doc(alias = "timeZoneForSecondsFromGMT:")]
+    pub fn from_fixed_offset(offset: ::chrono::FixedOffset) -> Retained<Self> {
+        let seconds = offset.local_minus_utc() as crate::NSInteger;
+        unsafe { Self::timeZoneForSecondsFromGMT(seconds) }
+    }
+
+    /// The time zone's offset from UTC at the given date.
+    ///
+    /// Returns `None` if the offset is out of the range supported by
+    /// `chrono`.
+    #[doc(alias = "secondsFromGMTForDate:")]
+    pub fn fixed_offset_at(&self, date: &NSDate) -> Option<::chrono::FixedOffset> {
+        let seconds = unsafe { self.secondsFromGMTForDate(date) };
+        ::chrono::FixedOffset::east_opt(seconds.try_into().ok()?)
+    }
+}
@@ -1,7 +1,27 @@
-use core::ffi::c_ushort;
+use core::ffi::{c_short, c_ushort};
+#[cfg(feature = "NSString")]
+use core::fmt;
+use core::ptr::NonNull;
 
 use objc2::encode::{Encode, Encoding, RefEncode};
 
+#[cfg(feature = "NSObjCRuntime")]
+use crate::NSDecimalCompare;
+#[cfg(feature = "NSString")]
+use crate::NSDecimalString;
+use crate::{
+    NSCalculationError, NSDecimalAdd, NSDecimalCompact, NSDecimalDivide, NSDecimalMultiply,
+    NSDecimalMultiplyByPowerOf10, NSDecimalPower, NSDecimalRound, NSDecimalSubtract, NSInteger,
+    NSRoundingMode, NSUInteger,
+};
+
+/// A fixed-point decimal number, with up to 38 significant digits.
+///
+/// Use [`NSDecimal::new`] or the [`From`] implementations to create one, and
+/// the `checked_*` methods to do arithmetic with it.
+///
+/// Note that the [`PartialEq`] implementation compares the decimals
+/// structurally; use [`compare`][Self::compare] to compare their values.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct NSDecimal {
@@ -31,3 +51,244 @@ unsafe impl Encode for NSDecimal {
 unsafe impl RefEncode for NSDecimal {
     const ENCODING_REF: Encoding = Encoding::Pointer(&Self::ENCODING);
 }
+
+// The layout of the bitfields in `_inner` on Apple platforms (the exponent
+// is stored in the lowest 8 bits).
+const LENGTH_SHIFT: i32 = 8;
+const LENGTH_MASK: i32 = 0xf;
+const IS_NEGATIVE_BIT: i32 = 1 << 12;
+
+impl NSDecimal {
+    /// Create a decimal with the value `mantissa * 10^exponent`, negated if
+    /// `is_negative` is set.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::NSDecimal;
+    ///
+    /// // 12.34
+    /// let decimal = NSDecimal::new(1234, -2, false);
+    /// # let _ = decimal;
+    /// ```
+    pub fn new(mantissa: u64, exponent: i8, is_negative: bool) -> Self {
+        let mut words = [0; 8];
+        for (i, word) in words.iter_mut().take(4).enumerate() {
+            *word = (mantissa >> (16 * i)) as c_ushort;
+        }
+        let length = words
+            .iter()
+            .rposition(|word| *word != 0)
+            .map_or(0, |i| i + 1) as i32;
+
+        let mut inner = (exponent as u8 as i32) | (length << LENGTH_SHIFT);
+        // Zero is never negative, a negative zero-length decimal is NaN.
+        if is_negative && length != 0 {
+            inner |= IS_NEGATIVE_BIT;
+        }
+
+        let mut decimal = Self {
+            _inner: inner,
+            _mantissa: words,
+        };
+        // SAFETY: The decimal is valid.
+        unsafe { NSDecimalCompact(NonNull::from(&mut decimal)) };
+        decimal
+    }
+
+    /// Whether the decimal is "not a number", which is the result of invalid
+    /// calculations such as dividing by zero.
+    #[doc(alias = "NSDecimalIsNotANumber")]
+    pub fn is_nan(&self) -> bool {
+        let length = (self._inner >> LENGTH_SHIFT) & LENGTH_MASK;
+        length == 0 && self._inner & IS_NEGATIVE_BIT != 0
+    }
+
+    fn calculate(
+        &self,
+        f: impl FnOnce(NonNull<Self>, NonNull<Self>) -> NSCalculationError,
+    ) -> Result<Self, NSCalculationError> {
+        let mut result = *self;
+        let mut this = *self;
+        match f(NonNull::from(&mut result), NonNull::from(&mut this)) {
+            NSCalculationError::NoError | NSCalculationError::LossOfPrecision => Ok(result),
+            err => Err(err),
+        }
+    }
+
+    /// Add two decimals.
+    ///
+    /// If the result has more digits than can be stored in a decimal (38),
+    /// it is rounded with the given rounding mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the calculation overflowed or underflowed.
+    #[doc(alias = "NSDecimalAdd")]
+    pub fn checked_add(
+        &self,
+        rhs: &Self,
+        rounding: NSRoundingMode,
+    ) -> Result<Self, NSCalculationError> {
+        let mut rhs = *rhs;
+        self.calculate(|result, this| unsafe {
+            NSDecimalAdd(result, this, NonNull::from(&mut rhs), rounding)
+        })
+    }
+
+    /// Subtract one decimal from another.
+    ///
+    /// See [`checked_add`][Self::checked_add] for details on rounding and
+    /// errors.
+    #[doc(alias = "NSDecimalSubtract")]
+    pub fn checked_sub(
+        &self,
+        rhs: &Self,
+        rounding: NSRoundingMode,
+    ) -> Result<Self, NSCalculationError> {
+        let mut rhs = *rhs;
+        self.calculate(|result, this| unsafe {
+            NSDecimalSubtract(result, this, NonNull::from(&mut rhs), rounding)
+        })
+    }
+
+    /// Multiply two decimals.
+    ///
+    /// See [`checked_add`][Self::checked_add] for details on rounding and
+    /// errors.
+    #[doc(alias = "NSDecimalMultiply")]
+    pub fn checked_mul(
+        &self,
+        rhs: &Self,
+        rounding: NSRoundingMode,
+    ) -> Result<Self, NSCalculationError> {
+        let mut rhs = *rhs;
+        self.calculate(|result, this| unsafe {
+            NSDecimalMultiply(result, this, NonNull::from(&mut rhs), rounding)
+        })
+    }
+
+    /// Divide one decimal by another.
+    ///
+    /// See [`checked_add`][Self::checked_add] for details on rounding.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the calculation overflowed or underflowed, or if
+    /// `rhs` is zero.
+    #[doc(alias = "NSDecimalDivide")]
+    pub fn checked_div(
+        &self,
+        rhs: &Self,
+        rounding: NSRoundingMode,
+    ) -> Result<Self, NSCalculationError> {
+        let mut rhs = *rhs;
+        self.calculate(|result, this| unsafe {
+            NSDecimalDivide(result, this, NonNull::from(&mut rhs), rounding)
+        })
+    }
+
+    /// Raise the decimal to the given power.
+    ///
+    /// See [`checked_add`][Self::checked_add] for details on rounding and
+    /// errors.
+    #[doc(alias = "NSDecimalPower")]
+    pub fn checked_pow(
+        &self,
+        power: NSUInteger,
+        rounding: NSRoundingMode,
+    ) -> Result<Self, NSCalculationError> {
+        self.calculate(|result, this| unsafe { NSDecimalPower(result, this, power, rounding) })
+    }
+
+    /// Multiply the decimal by `10^power`.
+    ///
+    /// See [`checked_add`][Self::checked_add] for details on rounding and
+    /// errors.
+    #[doc(alias = "NSDecimalMultiplyByPowerOf10")]
+    pub fn checked_mul_pow10(
+        &self,
+        power: i16,
+        rounding: NSRoundingMode,
+    ) -> Result<Self, NSCalculationError> {
+        self.calculate(|result, this| unsafe {
+            NSDecimalMultiplyByPowerOf10(result, this, power as c_short, rounding)
+        })
+    }
+
+    /// Round the decimal to the given number of digits after the decimal
+    /// point.
+    ///
+    /// A negative `scale` rounds to the left of the decimal point, e.g.
+    /// rounding `1234` with a scale of `-2` gives `1200`.
+    #[doc(alias = "NSDecimalRound")]
+    pub fn round(&self, scale: NSInteger, rounding: NSRoundingMode) -> Self {
+        let mut result = *self;
+        let mut this = *self;
+        // SAFETY: Both pointers point to valid decimals.
+        unsafe {
+            NSDecimalRound(
+                NonNull::from(&mut result),
+                NonNull::from(&mut this),
+                scale,
+                rounding,
+            )
+        };
+        result
+    }
+
+    /// Compare the values of two decimals.
+    ///
+    /// Unlike the [`PartialEq`] implementation, which compares the decimals
+    /// structurally, this compares their values, so e.g. `1.0` and `1.00`
+    /// compare equal.
+    ///
+    /// Returns [`None`] if either decimal is NaN.
+    #[cfg(feature = "NSObjCRuntime")]
+    #[doc(alias = "NSDecimalCompare")]
+    pub fn compare(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        if self.is_nan() || other.is_nan() {
+            return None;
+        }
+        let mut this = *self;
+        let mut other = *other;
+        // SAFETY: Both pointers point to valid decimals.
+        let res = unsafe { NSDecimalCompare(NonNull::from(&mut this), NonNull::from(&mut other)) };
+        Some(res.into())
+    }
+}
+
+impl Default for NSDecimal {
+    /// Zero.
+    #[inline]
+    fn default() -> Self {
+        Self::new(0, 0, false)
+    }
+}
+
+impl From<u64> for NSDecimal {
+    #[inline]
+    fn from(value: u64) -> Self {
+        Self::new(value, 0, false)
+    }
+}
+
+impl From<i64> for NSDecimal {
+    #[inline]
+    fn from(value: i64) -> Self {
+        Self::new(value.unsigned_abs(), 0, value < 0)
+    }
+}
+
+#[cfg(feature = "NSString")]
+impl fmt::Display for NSDecimal {
+    #[doc(alias = "NSDecimalString")]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut this = *self;
+        // SAFETY: The pointer points to a valid decimal. Passing no locale
+        // uses `.` as the decimal separator.
+        let string = unsafe { NSDecimalString(NonNull::from(&mut this), None) };
+        fmt::Display::fmt(&string, f)
+    }
+}
@@ -0,0 +1,109 @@
+use objc2::rc::Retained;
+
+use crate::{NSCalculationError, NSDecimal, NSDecimalNumber, NSInteger, NSRoundingMode};
+
+/// Arithmetic.
+///
+/// Unlike the `decimalNumberBy*` methods, these report errors instead of
+/// raising an exception (with the default behavior), and are therefore safe.
+/// Results are rounded with [`NSRoundingMode::RoundPlain`], like the default
+/// behavior does.
+impl NSDecimalNumber {
+    /// Create a decimal number from a [`NSDecimal`].
+    #[doc(alias = "decimalNumberWithDecimal:")]
+    pub fn from_decimal(decimal: NSDecimal) -> Retained<Self> {
+        unsafe { Self::decimalNumberWithDecimal(decimal) }
+    }
+
+    /// The decimal number's value as a [`NSDecimal`].
+    #[doc(alias = "decimalValue")]
+    pub fn decimal(&self) -> NSDecimal {
+        unsafe { self.decimalValue() }
+    }
+
+    /// Parse a decimal number from a string, such as `"-12.34e5"`.
+    ///
+    /// The decimal separator is always `.`, regardless of the user's
+    /// locale.
+    ///
+    /// Returns [`None`] if the string is not a valid number.
+    #[cfg(feature = "NSString")]
+    #[doc(alias = "decimalNumberWithString:")]
+    pub fn parse(string: &str) -> Option<Retained<Self>> {
+        let string = crate::NSString::from_str(string);
+        let number = unsafe { Self::decimalNumberWithString(Some(&string)) };
+        if number.decimal().is_nan() {
+            None
+        } else {
+            Some(number)
+        }
+    }
+
+    fn calculate(
+        decimal: Result<NSDecimal, NSCalculationError>,
+    ) -> Result<Retained<Self>, NSCalculationError> {
+        decimal.map(Self::from_decimal)
+    }
+
+    /// Add two decimal numbers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the calculation overflowed or underflowed.
+    #[doc(alias = "decimalNumberByAdding:")]
+    pub fn checked_add(&self, rhs: &Self) -> Result<Retained<Self>, NSCalculationError> {
+        Self::calculate(
+            self.decimal()
+                .checked_add(&rhs.decimal(), NSRoundingMode::RoundPlain),
+        )
+    }
+
+    /// Subtract one decimal number from another.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the calculation overflowed or underflowed.
+    #[doc(alias = "decimalNumberBySubtracting:")]
+    pub fn checked_sub(&self, rhs: &Self) -> Result<Retained<Self>, NSCalculationError> {
+        Self::calculate(
+            self.decimal()
+                .checked_sub(&rhs.decimal(), NSRoundingMode::RoundPlain),
+        )
+    }
+
+    /// Multiply two decimal numbers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the calculation overflowed or underflowed.
+    #[doc(alias = "decimalNumberByMultiplyingBy:")]
+    pub fn checked_mul(&self, rhs: &Self) -> Result<Retained<Self>, NSCalculationError> {
+        Self::calculate(
+            self.decimal()
+                .checked_mul(&rhs.decimal(), NSRoundingMode::RoundPlain),
+        )
+    }
+
+    /// Divide one decimal number by another.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the calculation overflowed or underflowed, or if
+    /// `rhs` is zero.
+    #[doc(alias = "decimalNumberByDividingBy:")]
+    pub fn checked_div(&self, rhs: &Self) -> Result<Retained<Self>, NSCalculationError> {
+        Self::calculate(
+            self.decimal()
+                .checked_div(&rhs.decimal(), NSRoundingMode::RoundPlain),
+        )
+    }
+
+    /// Round the decimal number to the given number of digits after the
+    /// decimal point.
+    ///
+    /// See [`NSDecimal::round`] for details.
+    #[doc(alias = "decimalNumberByRoundingAccordingToBehavior:")]
+    pub fn round(&self, scale: NSInteger, rounding: NSRoundingMode) -> Retained<Self> {
+        Self::from_decimal(self.decimal().round(scale, rounding))
+    }
+}
@@ -3,20 +3,28 @@
 use alloc::vec::Vec;
 use core::fmt;
 use core::mem;
+#[cfg(all(feature = "std", feature = "NSObject"))]
+use core::ops::Deref;
 use core::ptr::NonNull;
 use objc2::msg_send;
 
+#[cfg(feature = "NSObject")]
+use objc2::__framework_helpers::{ref_ptr_cast_const, retained_ptr_cast_const};
 use objc2::rc::Retained;
+#[cfg(all(feature = "alloc", feature = "NSObject"))]
+use objc2::rc::RetainedFromIterator;
 #[cfg(feature = "NSObject")]
 use objc2::runtime::ProtocolObject;
 #[cfg(feature = "NSObject")]
 use objc2::AllocAnyThread;
 use objc2::Message;
+#[cfg(all(feature = "std", feature = "NSObject"))]
+use std::collections::HashMap;
 
 #[cfg(feature = "NSEnumerator")]
 use crate::iter;
 #[cfg(feature = "NSObject")]
-use crate::{util, CopyingHelper, NSCopying};
+use crate::{CopyingHelper, NSCopying, NSCopyingKey};
 use crate::{NSDictionary, NSMutableDictionary};
 
 #[cfg(feature = "NSObject")]
@@ -24,7 +32,7 @@ fn keys_to_ptr<CopiedKey>(keys: &[&CopiedKey]) -> *mut NonNull<ProtocolObject<dy
 where
     CopiedKey: Message + NSCopying,
 {
-    let keys: *mut NonNull<CopiedKey> = util::ref_ptr_cast_const(keys.as_ptr());
+    let keys: *mut NonNull<CopiedKey> = ref_ptr_cast_const(keys.as_ptr());
     // SAFETY: `CopiedKey` is `Message + NSCopying`, and is therefore safe to cast to
     // `ProtocolObject<dyn NSCopying>`.
     let keys: *mut NonNull<ProtocolObject<dyn NSCopying>> = keys.cast();
@@ -72,7 +80,7 @@ impl<KeyType: Message, ObjectType: Message> NSDictionary<KeyType, ObjectType> {
         let count = keys.len();
 
         let keys = keys_to_ptr(keys);
-        let objects = util::ref_ptr_cast_const(objects.as_ptr());
+        let objects = ref_ptr_cast_const(objects.as_ptr());
 
         // SAFETY:
         // - All types that are `Message` use interior mutability, and the
@@ -113,7 +121,7 @@ impl<KeyType: Message, ObjectType: Message> NSDictionary<KeyType, ObjectType> {
         let count = keys.len();
 
         let keys = keys_to_ptr(keys);
-        let objects = util::retained_ptr_cast_const(objects.as_ptr());
+        let objects = retained_ptr_cast_const(objects.as_ptr());
 
         // SAFETY: Same as `from_slices`.
         unsafe { Self::initWithObjects_forKeys_count(Self::alloc(), objects, keys, count) }
@@ -136,7 +144,7 @@ impl<KeyType: Message, ObjectType: Message> NSMutableDictionary<KeyType, ObjectT
         let count = keys.len();
 
         let keys = keys_to_ptr(keys);
-        let objects = util::ref_ptr_cast_const(objects.as_ptr());
+        let objects = ref_ptr_cast_const(objects.as_ptr());
 
         // SAFETY: Same as `NSDictionary::from_slices`.
         unsafe { Self::initWithObjects_forKeys_count(Self::alloc(), objects, keys, count) }
@@ -159,13 +167,68 @@ impl<KeyType: Message, ObjectType: Message> NSMutableDictionary<KeyType, ObjectT
         let count = keys.len();
 
         let keys = keys_to_ptr(keys);
-        let objects = util::retained_ptr_cast_const(objects.as_ptr());
+        let objects = retained_ptr_cast_const(objects.as_ptr());
 
         // SAFETY: Same as `NSDictionary::from_retained_objects`.
         unsafe { Self::initWithObjects_forKeys_count(Self::alloc(), objects, keys, count) }
     }
 }
 
+/// Creation from Rust collections.
+impl<KeyType: Message, ObjectType: Message> NSDictionary<KeyType, ObjectType> {
+    /// Create a new dictionary with the entries of a [`HashMap`].
+    ///
+    /// The map's keys and values can be anything that dereferences to
+    /// Objective-C objects, such as [`Retained`] or normal references.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use objc2_foundation::{NSDictionary, NSNumber, NSString};
+    ///
+    /// let mut map = HashMap::new();
+    /// map.insert(NSString::from_str("a"), NSNumber::new_i32(1));
+    /// map.insert(NSString::from_str("b"), NSNumber::new_i32(2));
+    ///
+    /// let dict = NSDictionary::from_hash_map(&map);
+    /// assert_eq!(dict.len(), 2);
+    /// ```
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[cfg(all(feature = "std", feature = "NSObject"))]
+    pub fn from_hash_map<K, V, S, CopiedKey>(map: &HashMap<K, V, S>) -> Retained<Self>
+    where
+        K: Deref<Target = CopiedKey>,
+        V: Deref<Target = ObjectType>,
+        CopiedKey: NSCopyingKey<Result = KeyType>,
+    {
+        let (keys, objects): (Vec<&CopiedKey>, Vec<&ObjectType>) =
+            map.iter().map(|(key, object)| (&**key, &**object)).unzip();
+        Self::from_slices(&keys, &objects)
+    }
+}
+
+/// Creation from Rust collections.
+impl<KeyType: Message, ObjectType: Message> NSMutableDictionary<KeyType, ObjectType> {
+    /// Create a new mutable dictionary with the entries of a [`HashMap`].
+    ///
+    /// See [`NSDictionary::from_hash_map`] for details.
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[cfg(all(feature = "std", feature = "NSObject"))]
+    pub fn from_hash_map<K, V, S, CopiedKey>(map: &HashMap<K, V, S>) -> Retained<Self>
+    where
+        K: Deref<Target = CopiedKey>,
+        V: Deref<Target = ObjectType>,
+        CopiedKey: NSCopyingKey<Result = KeyType>,
+    {
+        let (keys, objects): (Vec<&CopiedKey>, Vec<&ObjectType>) =
+            map.iter().map(|(key, object)| (&**key, &**object)).unzip();
+        Self::from_slices(&keys, &objects)
+    }
+}
+
 // Note: We'd like to make getter methods take `KeyType: Borrow<KeyType>`
 // like `std::collections::HashMap`, so that e.g. `NSDictionary<NSString, V>`
 // could take a `&NSObject` as input, and still make that work since
@@ -335,6 +398,31 @@ impl<KeyType: Message, ObjectType: Message> NSDictionary<KeyType, ObjectType> {
         Keys(iter::Iter::new(self))
     }
 
+    /// Iterate over the dictionary's key-value pairs.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "NSString", doc = "```")]
+    #[cfg_attr(not(feature = "NSString"), doc = "```ignore")]
+    /// use objc2_foundation::{ns_string, NSDictionary};
+    ///
+    /// let dict = NSDictionary::from_slices(
+    ///     &[ns_string!("a"), ns_string!("b")],
+    ///     &[ns_string!("a"), ns_string!("b")],
+    /// );
+    /// for (key, obj) in dict.iter() {
+    ///     assert_eq!(key, obj);
+    /// }
+    /// ```
+    #[cfg(feature = "NSEnumerator")]
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, KeyType, ObjectType> {
+        Iter {
+            keys: self.keys(),
+            dictionary: self,
+        }
+    }
+
     /// Iterate over the dictionary's objects / values.
     ///
     /// # Examples
@@ -388,6 +476,86 @@ impl<KeyType: Message, ObjectType: Message> NSMutableDictionary<KeyType, ObjectT
         // SAFETY: The key is copied, and then has the correct type `KeyType`.
         unsafe { self.setObject_forKey(object, key) };
     }
+
+    /// Returns the object corresponding to the key, or inserts and returns
+    /// the object returned by `f` if the key is not present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::{ns_string, NSMutableDictionary, NSObject};
+    ///
+    /// let dict = NSMutableDictionary::new();
+    /// let obj = dict.get_or_insert_with(ns_string!("key"), NSObject::new);
+    /// assert_eq!(dict.get_or_insert_with(ns_string!("key"), NSObject::new), obj);
+    /// ```
+    #[cfg(feature = "NSObject")]
+    #[doc(alias = "objectForKey:")]
+    #[doc(alias = "setObject:forKey:")]
+    pub fn get_or_insert_with(
+        &self,
+        key: &KeyType,
+        f: impl FnOnce() -> Retained<ObjectType>,
+    ) -> Retained<ObjectType>
+    where
+        KeyType: NSCopyingKey<Result = KeyType>,
+    {
+        if let Some(object) = self.objectForKey(key) {
+            return object;
+        }
+        // `f` may mutate the dictionary, but that's fine, since we don't
+        // hold any references into it.
+        let object = f();
+        self.insert(key, &object);
+        object
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "NSObject"))]
+impl<
+        'a,
+        KeyType: Message,
+        ObjectType: Message + 'a,
+        CopiedKey: NSCopyingKey<Result = KeyType> + 'a,
+    > RetainedFromIterator<(&'a CopiedKey, &'a ObjectType)> for NSDictionary<KeyType, ObjectType>
+{
+    fn retained_from_iter<I: IntoIterator<Item = (&'a CopiedKey, &'a ObjectType)>>(
+        iter: I,
+    ) -> Retained<Self> {
+        let (keys, objects): (Vec<_>, Vec<_>) = iter.into_iter().unzip();
+        Self::from_slices(&keys, &objects)
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "NSObject"))]
+impl<
+        'a,
+        KeyType: Message,
+        ObjectType: Message + 'a,
+        CopiedKey: NSCopyingKey<Result = KeyType> + 'a,
+    > RetainedFromIterator<(&'a CopiedKey, &'a ObjectType)>
+    for NSMutableDictionary<KeyType, ObjectType>
+{
+    fn retained_from_iter<I: IntoIterator<Item = (&'a CopiedKey, &'a ObjectType)>>(
+        iter: I,
+    ) -> Retained<Self> {
+        let (keys, objects): (Vec<_>, Vec<_>) = iter.into_iter().unzip();
+        Self::from_slices(&keys, &objects)
+    }
+}
+
+#[cfg(feature = "NSObject")]
+impl<
+        'a,
+        KeyType: Message,
+        ObjectType: Message + 'a,
+        CopiedKey: NSCopyingKey<Result = KeyType> + 'a,
+    > Extend<(&'a CopiedKey, &'a ObjectType)> for &NSMutableDictionary<KeyType, ObjectType>
+{
+    fn extend<I: IntoIterator<Item = (&'a CopiedKey, &'a ObjectType)>>(&mut self, iter: I) {
+        iter.into_iter()
+            .for_each(move |(key, object)| self.insert(key, object));
+    }
 }
 
 #[cfg(feature = "NSEnumerator")]
@@ -444,6 +612,37 @@ __impl_iter! {
     impl<'a, KeyType: Message, ObjectType: Message> Iterator<Item = &'a KeyType> for KeysUnchecked<'a, KeyType, ObjectType> { ... }
 }
 
+/// An iterator over the key-value pairs of a dictionary.
+#[derive(Debug)]
+#[cfg(feature = "NSEnumerator")]
+pub struct Iter<'a, KeyType: Message, ObjectType: Message> {
+    keys: Keys<'a, KeyType, ObjectType>,
+    dictionary: &'a NSDictionary<KeyType, ObjectType>,
+}
+
+#[cfg(feature = "NSEnumerator")]
+impl<KeyType: Message, ObjectType: Message> Iterator for Iter<'_, KeyType, ObjectType> {
+    type Item = (Retained<KeyType>, Retained<ObjectType>);
+
+    #[inline]
+    #[track_caller]
+    fn next(&mut self) -> Option<Self::Item> {
+        // The keys iterator panics if the dictionary is mutated while
+        // iterating, so the key will always be present.
+        let key = self.keys.next()?;
+        let object = self
+            .dictionary
+            .objectForKey(&key)
+            .expect("key from dictionary should be present in dictionary");
+        Some((key, object))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
 /// An iterator over the objects / values in a dictionary.
 #[derive(Debug)]
 #[cfg(feature = "NSEnumerator")]
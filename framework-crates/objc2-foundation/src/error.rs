@@ -34,8 +34,239 @@ impl NSError {
     }
 }
 
+/// Creation with user info.
+#[cfg(all(feature = "NSDictionary", feature = "NSObject", feature = "NSString"))]
+impl NSError {
+    /// Construct a new [`NSError`] with the given code in the given domain,
+    /// along with user info configured by the given closure.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::{ns_string, NSError};
+    ///
+    /// let underlying = NSError::new(2, ns_string!("MyDomain"));
+    /// let error = NSError::new_with(ns_string!("MyDomain"), 1, |builder| {
+    ///     builder
+    ///         .description("Could not open the document.")
+    ///         .recovery_suggestion("Try again later.")
+    ///         .underlying_error(&underlying);
+    /// });
+    /// assert_eq!(error.localizedDescription().to_string(), "Could not open the document.");
+    /// ```
+    #[doc(alias = "initWithDomain:code:userInfo:")]
+    pub fn new_with(
+        domain: &crate::NSErrorDomain,
+        code: objc2::ffi::NSInteger,
+        f: impl FnOnce(&mut NSErrorBuilder),
+    ) -> Retained<Self> {
+        use objc2::AllocAnyThread;
+        let mut builder = NSErrorBuilder {
+            user_info: crate::NSMutableDictionary::new(),
+        };
+        f(&mut builder);
+        // SAFETY: Same as in `NSError::new`.
+        unsafe {
+            Self::initWithDomain_code_userInfo(
+                Self::alloc(),
+                domain,
+                code,
+                Some(&builder.user_info),
+            )
+        }
+    }
+
+    /// Convert a Rust error into an [`NSError`], e.g. for returning it to
+    /// Objective-C code.
+    ///
+    /// If the error is already an `NSError`, it is returned as-is.
+    /// Otherwise, the error is placed in the `"RustErrorDomain"` domain with
+    /// code `0`, its [`Display`] output is used as the localized description,
+    /// and its [`source`] (if any) is converted recursively and stored as the
+    /// underlying error.
+    ///
+    /// [`Display`]: fmt::Display
+    /// [`source`]: std::error::Error::source
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::NSError;
+    ///
+    /// let error: Box<dyn std::error::Error> = "invalid input".into();
+    /// let error = NSError::from_error(&*error);
+    /// assert_eq!(error.localizedDescription().to_string(), "invalid input");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_error(error: &(dyn std::error::Error + 'static)) -> Retained<Self> {
+        use objc2::Message;
+        if let Some(error) = error.downcast_ref::<Self>() {
+            return error.retain();
+        }
+        if let Some(error) = error.downcast_ref::<Retained<Self>>() {
+            return error.clone();
+        }
+
+        let description = alloc::string::ToString::to_string(error);
+        Self::new_with(crate::ns_string!("RustErrorDomain"), 0, |builder| {
+            builder.description(&description);
+            if let Some(source) = error.source() {
+                builder.underlying_error(&Self::from_error(source));
+            }
+        })
+    }
+}
+
+/// A builder for the user info of an [`NSError`].
+///
+/// See [`NSError::new_with`].
+#[cfg(all(feature = "NSDictionary", feature = "NSObject", feature = "NSString"))]
+pub struct NSErrorBuilder {
+    user_info:
+        Retained<crate::NSMutableDictionary<crate::NSErrorUserInfoKey, objc2::runtime::AnyObject>>,
+}
+
+#[cfg(all(feature = "NSDictionary", feature = "NSObject", feature = "NSString"))]
+impl NSErrorBuilder {
+    /// Set the localized description of the error.
+    #[doc(alias = "NSLocalizedDescriptionKey")]
+    pub fn description(&mut self, description: &str) -> &mut Self {
+        let key = unsafe { crate::NSLocalizedDescriptionKey };
+        self.insert(key, &crate::NSString::from_str(description))
+    }
+
+    /// Set the localized reason for the failure.
+    #[doc(alias = "NSLocalizedFailureReasonErrorKey")]
+    pub fn failure_reason(&mut self, reason: &str) -> &mut Self {
+        let key = unsafe { crate::NSLocalizedFailureReasonErrorKey };
+        self.insert(key, &crate::NSString::from_str(reason))
+    }
+
+    /// Set a localized suggestion for how to recover from the error.
+    #[doc(alias = "NSLocalizedRecoverySuggestionErrorKey")]
+    pub fn recovery_suggestion(&mut self, suggestion: &str) -> &mut Self {
+        let key = unsafe { crate::NSLocalizedRecoverySuggestionErrorKey };
+        self.insert(key, &crate::NSString::from_str(suggestion))
+    }
+
+    /// Set the error that caused this error.
+    ///
+    /// This is returned from the error's [`source`].
+    ///
+    /// [`source`]: std::error::Error::source
+    #[doc(alias = "NSUnderlyingErrorKey")]
+    pub fn underlying_error(&mut self, error: &NSError) -> &mut Self {
+        let key = unsafe { crate::NSUnderlyingErrorKey };
+        self.insert(key, error)
+    }
+
+    /// Set an arbitrary user info value.
+    ///
+    /// The value should be a property list type, or implement
+    /// `NSSecureCoding`, as the error may be archived and sent to other
+    /// processes.
+    pub fn insert(
+        &mut self,
+        key: &crate::NSErrorUserInfoKey,
+        value: &objc2::runtime::AnyObject,
+    ) -> &mut Self {
+        self.user_info.insert(key, value);
+        self
+    }
+}
+
+#[cfg(all(feature = "NSDictionary", feature = "NSObject", feature = "NSString"))]
+impl fmt::Debug for NSErrorBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSErrorBuilder")
+            .field("user_info", &self.user_info)
+            .finish()
+    }
+}
+
+/// The message is the error's `localizedDescription`, and the [`source`] is
+/// the error stored under `NSUnderlyingErrorKey`, if any.
+///
+/// [`source`]: std::error::Error::source
 #[cfg(feature = "std")]
-impl std::error::Error for NSError {}
+impl std::error::Error for NSError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        #[cfg(all(feature = "NSDictionary", feature = "NSString"))]
+        {
+            use objc2::msg_send;
+            use objc2::runtime::AnyObject;
+
+            // SAFETY: `NSError` is immutable, and stores its user info, so
+            // the dictionary (and therefore the underlying error) lives at
+            // least as long as the error itself.
+            let user_info: Option<&crate::NSDictionary<crate::NSErrorUserInfoKey, AnyObject>> =
+                unsafe { msg_send![self, userInfo] };
+            let underlying =
+                unsafe { user_info?.objectForKey_unchecked(crate::NSUnderlyingErrorKey) }?;
+            let underlying: &NSError = underlying.downcast_ref()?;
+            Some(underlying)
+        }
+        #[cfg(not(all(feature = "NSDictionary", feature = "NSString")))]
+        None
+    }
+}
+
+/// Convert the error to an I/O error.
+///
+/// Errors in `NSPOSIXErrorDomain`, and errors with an underlying error in
+/// that domain, are converted to the corresponding OS error. Common file
+/// errors in `NSCocoaErrorDomain` are mapped to the corresponding
+/// [`ErrorKind`], with the error's `localizedDescription` as the message.
+///
+/// [`ErrorKind`]: std::io::ErrorKind
+#[cfg(all(feature = "std", feature = "NSString"))]
+impl From<&NSError> for std::io::Error {
+    fn from(error: &NSError) -> Self {
+        use std::error::Error;
+        use std::io::ErrorKind;
+        use std::string::ToString;
+
+        let posix_code = |error: &NSError| {
+            if &*error.domain() == unsafe { crate::NSPOSIXErrorDomain } {
+                i32::try_from(error.code()).ok()
+            } else {
+                None
+            }
+        };
+        if let Some(code) = posix_code(error) {
+            return Self::from_raw_os_error(code);
+        }
+        let underlying = error.source().and_then(|source| source.downcast_ref());
+        if let Some(code) = underlying.and_then(posix_code) {
+            return Self::from_raw_os_error(code);
+        }
+
+        #[cfg(feature = "FoundationErrors")]
+        let kind = if &*error.domain() == unsafe { crate::NSCocoaErrorDomain } {
+            match error.code() {
+                crate::NSFileNoSuchFileError | crate::NSFileReadNoSuchFileError => {
+                    ErrorKind::NotFound
+                }
+                crate::NSFileReadNoPermissionError | crate::NSFileWriteNoPermissionError => {
+                    ErrorKind::PermissionDenied
+                }
+                crate::NSFileWriteFileExistsError => ErrorKind::AlreadyExists,
+                crate::NSFileReadInvalidFileNameError | crate::NSFileWriteInvalidFileNameError => {
+                    ErrorKind::InvalidInput
+                }
+                crate::NSFileReadCorruptFileError => ErrorKind::InvalidData,
+                _ => ErrorKind::Other,
+            }
+        } else {
+            ErrorKind::Other
+        };
+        #[cfg(not(feature = "FoundationErrors"))]
+        let kind = ErrorKind::Other;
+
+        Self::new(kind, error.to_string())
+    }
+}
 
 impl fmt::Debug for NSError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -0,0 +1,92 @@
+//! Standard I/O traits for [`NSFileHandle`].
+//!
+//! Errors are converted with the `From<&NSError>` implementation on
+//! [`io::Error`].
+use std::io;
+
+use crate::{NSData, NSFileHandle};
+
+/// Read from the file handle, blocking until data is available.
+///
+/// This also makes `Retained<NSFileHandle>` usable as a reader.
+impl io::Read for &NSFileHandle {
+    #[doc(alias = "readDataUpToLength:error:")]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let data = unsafe { self.readDataUpToLength_error(buf.len()) }
+            .map_err(|error| io::Error::from(&*error))?;
+        // SAFETY: The data was just created by the file handle, and is not
+        // shared with anything that could mutate it.
+        let bytes = unsafe { data.as_bytes_unchecked() };
+        // Guard against the file handle returning more than requested.
+        let n = bytes.len().min(buf.len());
+        buf[..n].copy_from_slice(&bytes[..n]);
+        Ok(n)
+    }
+}
+
+/// Write to the file handle.
+///
+/// This also makes `Retained<NSFileHandle>` usable as a writer.
+impl io::Write for &NSFileHandle {
+    #[doc(alias = "writeData:error:")]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let data = NSData::with_bytes(buf);
+        unsafe { self.writeData_error(&data) }.map_err(|error| io::Error::from(&*error))?;
+        // The data is written in full, or an error is returned.
+        Ok(buf.len())
+    }
+
+    /// Does nothing, since file handles do not buffer written data.
+    ///
+    /// Use `synchronizeAndReturnError` to flush the data to disk.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Seek within the file handle.
+///
+/// This also makes `Retained<NSFileHandle>` seekable.
+impl io::Seek for &NSFileHandle {
+    #[doc(alias = "seekToOffset:error:")]
+    #[doc(alias = "seekToEndReturningOffset:error:")]
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        };
+        let base = match pos {
+            io::SeekFrom::Start(offset) => {
+                unsafe { self.seekToOffset_error(offset) }
+                    .map_err(|error| io::Error::from(&*error))?;
+                return Ok(offset);
+            }
+            io::SeekFrom::End(delta) => {
+                let mut end = 0;
+                unsafe { self.seekToEndReturningOffset_error(&mut end) }
+                    .map_err(|error| io::Error::from(&*error))?;
+                if delta == 0 {
+                    return Ok(end);
+                }
+                (end, delta)
+            }
+            io::SeekFrom::Current(delta) => (self.stream_position()?, delta),
+        };
+        let offset = base.0.checked_add_signed(base.1).ok_or_else(invalid)?;
+        unsafe { self.seekToOffset_error(offset) }.map_err(|error| io::Error::from(&*error))?;
+        Ok(offset)
+    }
+
+    #[doc(alias = "getOffset:error:")]
+    fn stream_position(&mut self) -> io::Result<u64> {
+        let mut offset = 0;
+        unsafe { self.getOffset_error((&mut offset).into()) }
+            .map_err(|error| io::Error::from(&*error))?;
+        Ok(offset)
+    }
+}
@@ -0,0 +1,333 @@
+//! Convenience methods for working with the file system.
+//!
+//! Errors are returned as [`NSError`], which can be converted to
+//! [`std::io::Error`] with `io::Error::from(&*error)`.
+#[cfg(all(feature = "block2", feature = "NSFileCoordinator"))]
+use core::ptr::NonNull;
+
+use objc2::rc::Retained;
+
+use crate::{NSError, NSFileManager, NSURL};
+
+/// File operations.
+impl NSFileManager {
+    /// Copy the item at `from` to `to`, recursively if it is a directory.
+    ///
+    /// Fails if an item already exists at `to`.
+    #[doc(alias = "copyItemAtURL:toURL:error:")]
+    pub fn copy_item(&self, from: &NSURL, to: &NSURL) -> Result<(), Retained<NSError>> {
+        unsafe { self.copyItemAtURL_toURL_error(from, to) }
+    }
+
+    /// Move the item at `from` to `to`.
+    ///
+    /// Fails if an item already exists at `to`.
+    #[doc(alias = "moveItemAtURL:toURL:error:")]
+    pub fn move_item(&self, from: &NSURL, to: &NSURL) -> Result<(), Retained<NSError>> {
+        unsafe { self.moveItemAtURL_toURL_error(from, to) }
+    }
+
+    /// Remove the item at the given URL, recursively if it is a directory.
+    #[doc(alias = "removeItemAtURL:error:")]
+    pub fn remove_item(&self, url: &NSURL) -> Result<(), Retained<NSError>> {
+        unsafe { self.removeItemAtURL_error(url) }
+    }
+
+    /// Move the item at the given URL to the trash.
+    ///
+    /// Returns the URL of the item in the trash, if available.
+    #[doc(alias = "trashItemAtURL:resultingItemURL:error:")]
+    pub fn trash_item(&self, url: &NSURL) -> Result<Option<Retained<NSURL>>, Retained<NSError>> {
+        let mut resulting_url = None;
+        unsafe { self.trashItemAtURL_resultingItemURL_error(url, Some(&mut resulting_url)) }?;
+        Ok(resulting_url)
+    }
+}
+
+#[cfg(all(
+    feature = "block2",
+    feature = "NSArray",
+    feature = "NSEnumerator",
+    feature = "NSString"
+))]
+mod entries {
+    use alloc::collections::VecDeque;
+    use alloc::rc::Rc;
+    use core::cell::RefCell;
+    use core::fmt;
+    use core::ptr::NonNull;
+
+    use objc2::rc::Retained;
+
+    use crate::{
+        NSArray, NSDirectoryEnumerationOptions, NSDirectoryEnumerator, NSError, NSFileManager,
+        NSURLResourceKey, NSURL,
+    };
+
+    /// Directory enumeration.
+    impl NSFileManager {
+        /// Iterate over the contents of the directory at the given URL.
+        ///
+        /// By default, this enumerates the directory recursively; use
+        /// [`NSDirectoryEnumerationOptions::SkipsSubdirectoryDescendants`] to
+        /// only return the immediate contents of the directory.
+        ///
+        /// The resource values for `prefetch_keys` are fetched while
+        /// enumerating, so that retrieving them from the returned URLs (e.g.
+        /// with [`NSURL::resource_value`]) is cheap.
+        ///
+        /// Errors that happen while enumerating (e.g. when a subdirectory
+        /// cannot be read) are returned from the iterator, after which the
+        /// enumeration continues.
+        ///
+        /// Returns [`None`] if the URL is not a directory that can be
+        /// enumerated.
+        ///
+        ///
+        /// # Example
+        ///
+        /// ```no_run
+        /// use objc2_foundation::{NSDirectoryEnumerationOptions, NSFileManager, NSURL, NSURLIsDirectoryKey};
+        ///
+        /// let manager = unsafe { NSFileManager::defaultManager() };
+        /// let url = NSURL::from_path("/tmp".as_ref(), true).unwrap();
+        /// let key = unsafe { NSURLIsDirectoryKey };
+        /// let entries = manager
+        ///     .entries(&url, NSDirectoryEnumerationOptions::SkipsHiddenFiles, &[key])
+        ///     .expect("could not enumerate directory");
+        /// for entry in entries {
+        ///     let entry = entry.expect("failed reading entry");
+        ///     println!("{entry:?}: {:?}", entry.resource_value(key));
+        /// }
+        /// ```
+        #[doc(alias = "enumeratorAtURL:includingPropertiesForKeys:options:errorHandler:")]
+        pub fn entries(
+            &self,
+            url: &NSURL,
+            options: NSDirectoryEnumerationOptions,
+            prefetch_keys: &[&NSURLResourceKey],
+        ) -> Option<NSDirectoryEntries> {
+            use objc2::runtime::Bool;
+
+            let errors: Rc<RefCell<VecDeque<Retained<NSError>>>> = Rc::default();
+            let handler = block2::RcBlock::new({
+                let errors = Rc::clone(&errors);
+                move |_url: NonNull<NSURL>, error: NonNull<NSError>| {
+                    // SAFETY: The error is valid for the duration of the call.
+                    let error = unsafe { Retained::retain(error.as_ptr()) }.unwrap();
+                    errors.borrow_mut().push_back(error);
+                    // Continue enumerating.
+                    Bool::YES
+                }
+            });
+            let keys = NSArray::from_slice(prefetch_keys);
+            let enumerator = unsafe {
+                self.enumeratorAtURL_includingPropertiesForKeys_options_errorHandler(
+                    url,
+                    Some(&keys),
+                    options,
+                    Some(&handler),
+                )
+            }?;
+            Some(NSDirectoryEntries {
+                enumerator,
+                errors,
+                next: None,
+            })
+        }
+    }
+
+    /// An iterator over the contents of a directory.
+    ///
+    /// See [`NSFileManager::entries`].
+    pub struct NSDirectoryEntries {
+        enumerator: Retained<NSDirectoryEnumerator<NSURL>>,
+        // Errors reported by the enumerator's error handler, which is called
+        // from within `nextObject`.
+        errors: Rc<RefCell<VecDeque<Retained<NSError>>>>,
+        // The URL returned after the errors that were reported while fetching
+        // it.
+        next: Option<Retained<NSURL>>,
+    }
+
+    impl NSDirectoryEntries {
+        /// Don't descend into the directory that was most recently returned.
+        #[doc(alias = "skipDescendants")]
+        pub fn skip_descendants(&self) {
+            unsafe { self.enumerator.skipDescendants() }
+        }
+
+        /// The depth of the most recently returned item, relative to the
+        /// directory being enumerated.
+        ///
+        /// Items directly in the directory have level 1.
+        pub fn level(&self) -> usize {
+            unsafe { self.enumerator.level() }
+        }
+
+        /// Whether the most recently returned item is a directory whose
+        /// contents have already been returned.
+        ///
+        /// This only happens with
+        /// [`NSDirectoryEnumerationOptions::IncludesDirectoriesPostOrder`].
+        #[doc(alias = "isEnumeratingDirectoryPostOrder")]
+        pub fn is_post_order(&self) -> bool {
+            unsafe { self.enumerator.isEnumeratingDirectoryPostOrder() }
+        }
+
+        /// The underlying enumerator.
+        pub fn enumerator(&self) -> &NSDirectoryEnumerator<NSURL> {
+            &self.enumerator
+        }
+    }
+
+    impl Iterator for NSDirectoryEntries {
+        type Item = Result<Retained<NSURL>, Retained<NSError>>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.next.is_none() {
+                self.next = self.enumerator.nextObject();
+            }
+            if let Some(error) = self.errors.borrow_mut().pop_front() {
+                return Some(Err(error));
+            }
+            self.next.take().map(Ok)
+        }
+    }
+
+    impl fmt::Debug for NSDirectoryEntries {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("NSDirectoryEntries")
+                .field("enumerator", &self.enumerator)
+                .finish_non_exhaustive()
+        }
+    }
+}
+#[cfg(all(
+    feature = "block2",
+    feature = "NSArray",
+    feature = "NSEnumerator",
+    feature = "NSString"
+))]
+pub use self::entries::NSDirectoryEntries;
+
+/// Resource values.
+#[cfg(feature = "NSString")]
+impl NSURL {
+    /// Get the value of the given resource key for the file that the URL
+    /// refers to.
+    ///
+    /// Returns `Ok(None)` if the resource is not available.
+    #[doc(alias = "getResourceValue:forKey:error:")]
+    pub fn resource_value(
+        &self,
+        key: &crate::NSURLResourceKey,
+    ) -> Result<Option<Retained<objc2::runtime::AnyObject>>, Retained<NSError>> {
+        let mut value = None;
+        unsafe { self.getResourceValue_forKey_error(&mut value, key) }?;
+        Ok(value)
+    }
+}
+
+/// File coordination.
+#[cfg(all(feature = "block2", feature = "NSFileCoordinator"))]
+impl crate::NSFileCoordinator {
+    /// Coordinate reading the item at the given URL with other processes,
+    /// and with file presenters in this process.
+    ///
+    /// The closure is called with the URL to read from, which may differ
+    /// from the given URL if the item was moved. It is called synchronously,
+    /// while blocking writers.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_foundation::{NSFileCoordinator, NSFileCoordinatorReadingOptions, NSURL};
+    ///
+    /// let coordinator = unsafe { NSFileCoordinator::new() };
+    /// let url = NSURL::from_path("/tmp/file.txt".as_ref(), false).unwrap();
+    /// let contents = coordinator
+    ///     .coordinate_reading(&url, NSFileCoordinatorReadingOptions::empty(), |url| {
+    ///         std::fs::read_to_string(url.to_path_buf().unwrap())
+    ///     })
+    ///     .expect("failed coordinating");
+    /// ```
+    #[doc(alias = "coordinateReadingItemAtURL:options:error:byAccessor:")]
+    pub fn coordinate_reading<R>(
+        &self,
+        url: &NSURL,
+        options: crate::NSFileCoordinatorReadingOptions,
+        f: impl FnOnce(&NSURL) -> R,
+    ) -> Result<R, Retained<NSError>> {
+        let mut error = None;
+        let result = call_accessor(f, |block| unsafe {
+            self.coordinateReadingItemAtURL_options_error_byAccessor(
+                url,
+                options,
+                Some(&mut error),
+                block,
+            )
+        });
+        accessor_result(result, error)
+    }
+
+    /// Coordinate writing the item at the given URL with other processes,
+    /// and with file presenters in this process.
+    ///
+    /// The closure is called with the URL to write to, which may differ
+    /// from the given URL if the item was moved. It is called synchronously,
+    /// while blocking other readers and writers.
+    #[doc(alias = "coordinateWritingItemAtURL:options:error:byAccessor:")]
+    pub fn coordinate_writing<R>(
+        &self,
+        url: &NSURL,
+        options: crate::NSFileCoordinatorWritingOptions,
+        f: impl FnOnce(&NSURL) -> R,
+    ) -> Result<R, Retained<NSError>> {
+        let mut error = None;
+        let result = call_accessor(f, |block| unsafe {
+            self.coordinateWritingItemAtURL_options_error_byAccessor(
+                url,
+                options,
+                Some(&mut error),
+                block,
+            )
+        });
+        accessor_result(result, error)
+    }
+}
+
+/// Call `coordinate` with a block that calls `f` at most once.
+#[cfg(all(feature = "block2", feature = "NSFileCoordinator"))]
+fn call_accessor<R>(
+    f: impl FnOnce(&NSURL) -> R,
+    coordinate: impl FnOnce(&block2::Block<dyn Fn(NonNull<NSURL>) + '_>),
+) -> Option<R> {
+    use core::cell::Cell;
+
+    let f = Cell::new(Some(f));
+    let result = Cell::new(None);
+    let block = block2::StackBlock::new(|url: NonNull<NSURL>| {
+        // SAFETY: The URL is valid for the duration of the call.
+        let url = unsafe { url.as_ref() };
+        if let Some(f) = f.take() {
+            result.set(Some(f(url)));
+        }
+    });
+    coordinate(&block);
+    result.into_inner()
+}
+
+#[cfg(all(feature = "block2", feature = "NSFileCoordinator"))]
+fn accessor_result<R>(
+    result: Option<R>,
+    error: Option<Retained<NSError>>,
+) -> Result<R, Retained<NSError>> {
+    match (result, error) {
+        (Some(result), _) => Ok(result),
+        (None, Some(error)) => Err(error),
+        // The accessor is documented to be called unless there is an error.
+        (None, None) => panic!("file coordinator did not call the accessor, nor set an error"),
+    }
+}
@@ -0,0 +1,118 @@
+//! Utilities for the `NSIndexPath` class.
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+use objc2::rc::Retained;
+
+use crate::NSIndexPath;
+
+impl NSIndexPath {
+    /// Create an index path from the given indexes.
+    #[doc(alias = "indexPathWithIndexes:length:")]
+    pub fn from_slice(indexes: &[usize]) -> Retained<Self> {
+        // SAFETY: The indexes are only read from, even though the pointer is
+        // declared as mutable.
+        unsafe { Self::indexPathWithIndexes_length(indexes.as_ptr().cast_mut(), indexes.len()) }
+    }
+
+    /// The number of indexes in the index path.
+    #[doc(alias = "length")]
+    pub fn len(&self) -> usize {
+        unsafe { self.length() }
+    }
+
+    /// Whether the index path contains no indexes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The index at the given position, or [`None`] if the position is out
+    /// of bounds.
+    #[doc(alias = "indexAtPosition:")]
+    pub fn get(&self, position: usize) -> Option<usize> {
+        if position < self.len() {
+            Some(unsafe { self.indexAtPosition(position) })
+        } else {
+            None
+        }
+    }
+
+    /// An iterator over the indexes in the index path.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            path: self,
+            position: 0,
+            len: self.len(),
+        }
+    }
+
+    /// Copy the indexes of the index path into a `Vec`.
+    pub fn to_vec(&self) -> Vec<usize> {
+        self.iter().collect()
+    }
+}
+
+impl From<&NSIndexPath> for Vec<usize> {
+    fn from(path: &NSIndexPath) -> Self {
+        path.to_vec()
+    }
+}
+
+impl PartialEq<[usize]> for NSIndexPath {
+    fn eq(&self, other: &[usize]) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter().copied())
+    }
+}
+
+impl<'a> IntoIterator for &'a NSIndexPath {
+    type Item = usize;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the indexes in an [`NSIndexPath`].
+///
+/// Created by [`NSIndexPath::iter`].
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    path: &'a NSIndexPath,
+    position: usize,
+    len: usize,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.position < self.len {
+            let index = unsafe { self.path.indexAtPosition(self.position) };
+            self.position += 1;
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.position;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<usize> {
+        if self.position < self.len {
+            self.len -= 1;
+            Some(unsafe { self.path.indexAtPosition(self.len) })
+        } else {
+            None
+        }
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
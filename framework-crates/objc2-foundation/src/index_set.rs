@@ -0,0 +1,250 @@
+//! Utilities for the `NSIndexSet` and `NSMutableIndexSet` classes.
+use core::fmt;
+use core::iter::FusedIterator;
+#[cfg(feature = "NSRange")]
+use core::ops::Range;
+
+use objc2::rc::{Retained, RetainedFromIterator};
+
+#[cfg(feature = "NSRange")]
+use crate::NSRange;
+use crate::{NSIndexSet, NSMutableIndexSet};
+
+/// The value of `NSNotFound`, which is returned by the index set methods
+/// when there is no such index.
+const NOT_FOUND: usize = isize::MAX as usize;
+
+fn option_index(index: usize) -> Option<usize> {
+    if index == NOT_FOUND {
+        None
+    } else {
+        Some(index)
+    }
+}
+
+impl NSIndexSet {
+    /// Create an index set containing the indexes in the given range.
+    #[cfg(feature = "NSRange")]
+    #[doc(alias = "indexSetWithIndexesInRange:")]
+    pub fn from_range(range: Range<usize>) -> Retained<Self> {
+        unsafe { Self::indexSetWithIndexesInRange(range.into()) }
+    }
+
+    /// The number of indexes in the set.
+    #[doc(alias = "count")]
+    pub fn len(&self) -> usize {
+        unsafe { self.count() }
+    }
+
+    /// Whether the set contains no indexes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the set contains the given index.
+    #[doc(alias = "containsIndex:")]
+    pub fn contains(&self, index: usize) -> bool {
+        unsafe { self.containsIndex(index) }
+    }
+
+    /// The smallest index in the set.
+    #[doc(alias = "firstIndex")]
+    pub fn first(&self) -> Option<usize> {
+        option_index(unsafe { self.firstIndex() })
+    }
+
+    /// The largest index in the set.
+    #[doc(alias = "lastIndex")]
+    pub fn last(&self) -> Option<usize> {
+        option_index(unsafe { self.lastIndex() })
+    }
+
+    /// An iterator over the indexes in the set, in ascending order.
+    ///
+    /// If the set is mutated while iterating, the iterator continues from
+    /// the first index greater than the previously returned one.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { set: self, next: 0 }
+    }
+
+    /// An iterator over the contiguous ranges of indexes in the set, in
+    /// ascending order.
+    ///
+    /// This is much more efficient than [`iter`](Self::iter) for sets that
+    /// contain large ranges of indexes.
+    #[cfg(feature = "NSRange")]
+    #[doc(alias = "enumerateRangesUsingBlock:")]
+    pub fn ranges(&self) -> Ranges<'_> {
+        Ranges { set: self, next: 0 }
+    }
+
+    /// The length of the run of contiguous indexes starting at `start`,
+    /// which must be in the set.
+    #[cfg(feature = "NSRange")]
+    fn run_len(&self, start: usize) -> usize {
+        // The largest possible index is `NSNotFound - 1`.
+        let max = NOT_FOUND - start;
+        let contains = |len| unsafe { self.containsIndexesInRange(NSRange::new(start, len)) };
+
+        // Find an upper bound by doubling the length, and then binary search
+        // between the last length that was fully contained and that bound.
+        let mut lo = 1;
+        let mut hi = 2;
+        while hi <= max && contains(hi) {
+            lo = hi;
+            hi = hi.saturating_mul(2);
+        }
+        let mut hi = hi.min(max + 1);
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if contains(mid) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+}
+
+impl NSMutableIndexSet {
+    /// Add the given index to the set.
+    #[doc(alias = "addIndex:")]
+    pub fn insert(&self, index: usize) {
+        unsafe { self.addIndex(index) }
+    }
+
+    /// Add the indexes in the given range to the set.
+    #[cfg(feature = "NSRange")]
+    #[doc(alias = "addIndexesInRange:")]
+    pub fn insert_range(&self, range: Range<usize>) {
+        unsafe { self.addIndexesInRange(range.into()) }
+    }
+
+    /// Remove the given index from the set.
+    #[doc(alias = "removeIndex:")]
+    pub fn remove(&self, index: usize) {
+        unsafe { self.removeIndex(index) }
+    }
+
+    /// Remove the indexes in the given range from the set.
+    #[cfg(feature = "NSRange")]
+    #[doc(alias = "removeIndexesInRange:")]
+    pub fn remove_range(&self, range: Range<usize>) {
+        unsafe { self.removeIndexesInRange(range.into()) }
+    }
+
+    /// Remove all indexes from the set.
+    #[doc(alias = "removeAllIndexes")]
+    pub fn clear(&self) {
+        unsafe { self.removeAllIndexes() }
+    }
+}
+
+impl RetainedFromIterator<usize> for NSIndexSet {
+    fn retained_from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Retained<Self> {
+        Retained::into_super(NSMutableIndexSet::retained_from_iter(iter))
+    }
+}
+
+impl RetainedFromIterator<usize> for NSMutableIndexSet {
+    fn retained_from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Retained<Self> {
+        let set = unsafe { Self::new() };
+        for index in iter {
+            set.insert(index);
+        }
+        set
+    }
+}
+
+impl<'a> IntoIterator for &'a NSIndexSet {
+    type Item = usize;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a NSMutableIndexSet {
+    type Item = usize;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the indexes in an [`NSIndexSet`].
+///
+/// Created by [`NSIndexSet::iter`].
+#[derive(Clone)]
+pub struct Iter<'a> {
+    set: &'a NSIndexSet,
+    next: usize,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.next == NOT_FOUND {
+            return None;
+        }
+        let index = unsafe { self.set.indexGreaterThanOrEqualToIndex(self.next) };
+        if index == NOT_FOUND {
+            self.next = NOT_FOUND;
+            None
+        } else {
+            self.next = index + 1;
+            Some(index)
+        }
+    }
+}
+
+impl FusedIterator for Iter<'_> {}
+
+impl fmt::Debug for Iter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the contiguous ranges of indexes in an [`NSIndexSet`].
+///
+/// Created by [`NSIndexSet::ranges`].
+#[cfg(feature = "NSRange")]
+#[derive(Clone)]
+pub struct Ranges<'a> {
+    set: &'a NSIndexSet,
+    next: usize,
+}
+
+#[cfg(feature = "NSRange")]
+impl Iterator for Ranges<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        if self.next == NOT_FOUND {
+            return None;
+        }
+        let start = unsafe { self.set.indexGreaterThanOrEqualToIndex(self.next) };
+        if start == NOT_FOUND {
+            self.next = NOT_FOUND;
+            return None;
+        }
+        let end = start + self.set.run_len(start);
+        self.next = end;
+        Some(start..end)
+    }
+}
+
+#[cfg(feature = "NSRange")]
+impl FusedIterator for Ranges<'_> {}
+
+#[cfg(feature = "NSRange")]
+impl fmt::Debug for Ranges<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
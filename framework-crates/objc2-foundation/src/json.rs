@@ -0,0 +1,233 @@
+//! Converting between [`serde_json`] values and JSON objects.
+//!
+//! JSON objects are the Foundation objects that `NSJSONSerialization`
+//! produces and consumes, and are used by APIs such as `WKScriptMessage`:
+//! - `null` is `NSNull`.
+//! - Booleans and numbers are `NSNumber`.
+//! - Strings are `NSString`.
+//! - Arrays are `NSArray`.
+//! - Objects are `NSDictionary` with `NSString` keys.
+//!
+//!
+//! # Example
+//!
+//! ```
+//! use objc2_foundation::json;
+//! use serde_json::json;
+//!
+//! let value = json!({ "name": "objc2", "tags": ["rust", "apple"], "stars": 3 });
+//!
+//! let object = json::from_value(&value);
+//! assert_eq!(json::to_value(&object).unwrap(), value);
+//! ```
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use objc2::encode::Encoding;
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2::Message;
+#[cfg(all(
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSJSONSerialization"
+))]
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Number, Value};
+
+use crate::{
+    NSArray, NSDictionary, NSMutableArray, NSMutableDictionary, NSNull, NSNumber, NSString,
+};
+#[cfg(all(
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSJSONSerialization"
+))]
+use crate::{NSData, NSJSONReadingOptions, NSJSONSerialization, NSJSONWritingOptions};
+
+/// An error that occurred while converting to or from a JSON object.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Error {
+    message: String,
+}
+
+impl Error {
+    fn new(message: impl fmt::Display) -> Self {
+        Self {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+// This is `std::error::Error` when `serde`'s `std` feature is enabled.
+impl serde::de::StdError for Error {}
+
+fn erase<T: Message>(object: Retained<T>) -> Retained<AnyObject> {
+    // SAFETY: All `Message` types are objects.
+    unsafe { Retained::cast_unchecked(object) }
+}
+
+/// Convert a JSON object to a [`Value`].
+///
+///
+/// # Errors
+///
+/// Returns an error if the object contains objects that cannot be
+/// represented in JSON, such as `NSDate`, dictionaries with keys that are
+/// not strings, or numbers that are not finite.
+pub fn to_value(object: &AnyObject) -> Result<Value, Error> {
+    if NSNull::is_null(object) {
+        Ok(Value::Null)
+    } else if let Some(string) = object.downcast_ref::<NSString>() {
+        Ok(Value::String(string.to_string()))
+    } else if let Some(number) = object.downcast_ref::<NSNumber>() {
+        number_to_value(number)
+    } else if let Some(array) = object.downcast_ref::<NSArray>() {
+        array.iter().map(|object| to_value(&object)).collect()
+    } else if let Some(dictionary) = object.downcast_ref::<NSDictionary>() {
+        let (keys, objects) = dictionary.to_vecs();
+        let mut map = Map::with_capacity(keys.len());
+        for (key, object) in keys.iter().zip(objects) {
+            let key = key
+                .downcast_ref::<NSString>()
+                .ok_or_else(|| Error::new("JSON object keys must be strings"))?;
+            map.insert(key.to_string(), to_value(&object)?);
+        }
+        Ok(Value::Object(map))
+    } else {
+        Err(Error::new(format_args!(
+            "unsupported object of class {} in JSON",
+            object.class().name().to_string_lossy()
+        )))
+    }
+}
+
+fn number_to_value(number: &NSNumber) -> Result<Value, Error> {
+    let number = match number.encoding() {
+        _ if number.is_bool() => return Ok(Value::Bool(number.as_bool())),
+        Encoding::Float | Encoding::Double => Number::from_f64(number.as_f64())
+            .ok_or_else(|| Error::new("JSON numbers must be finite"))?,
+        Encoding::UChar
+        | Encoding::UShort
+        | Encoding::UInt
+        | Encoding::ULong
+        | Encoding::ULongLong => number.as_u64().into(),
+        _ => number.as_i64().into(),
+    };
+    Ok(Value::Number(number))
+}
+
+/// Convert a [`Value`] to a JSON object.
+///
+/// Numbers are stored as 64-bit integers if possible, and as doubles
+/// otherwise.
+pub fn from_value(value: &Value) -> Retained<AnyObject> {
+    match value {
+        Value::Null => erase(unsafe { NSNull::null() }),
+        Value::Bool(value) => erase(NSNumber::new_bool(*value)),
+        Value::Number(number) => {
+            if let Some(value) = number.as_i64() {
+                erase(NSNumber::new_i64(value))
+            } else if let Some(value) = number.as_u64() {
+                erase(NSNumber::new_u64(value))
+            } else {
+                // Always succeeds without the `arbitrary_precision` feature.
+                erase(NSNumber::new_f64(number.as_f64().unwrap_or(f64::NAN)))
+            }
+        }
+        Value::String(string) => erase(NSString::from_str(string)),
+        Value::Array(values) => {
+            let array = NSMutableArray::<AnyObject>::new();
+            for value in values {
+                array.addObject(&from_value(value));
+            }
+            erase(array)
+        }
+        Value::Object(map) => {
+            let dictionary = NSMutableDictionary::<NSString, AnyObject>::new();
+            for (key, value) in map {
+                dictionary.insert(&*NSString::from_str(key), &*from_value(value));
+            }
+            erase(dictionary)
+        }
+    }
+}
+
+/// Serialize a value to a JSON object with `NSJSONSerialization`.
+///
+/// This is useful for passing Rust data structures to APIs that expect JSON
+/// objects.
+///
+///
+/// # Errors
+///
+/// Returns an error if the value's `Serialize` implementation fails, or if it
+/// produces JSON that `NSJSONSerialization` cannot read.
+#[cfg(all(
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSJSONSerialization"
+))]
+#[doc(alias = "JSONObjectWithData:options:error:")]
+pub fn to_object<T: Serialize + ?Sized>(value: &T) -> Result<Retained<AnyObject>, Error> {
+    let bytes = serde_json::to_vec(value).map_err(Error::new)?;
+    let data = NSData::with_bytes(&bytes);
+    unsafe {
+        NSJSONSerialization::JSONObjectWithData_options_error(
+            &data,
+            NSJSONReadingOptions::FragmentsAllowed,
+        )
+    }
+    .map_err(|error| Error::new(error.localizedDescription()))
+}
+
+/// Deserialize a value from a JSON object with `NSJSONSerialization`.
+///
+/// This is useful for reading Rust data structures from APIs that produce
+/// JSON objects.
+///
+///
+/// # Errors
+///
+/// Returns an error if the object is not a valid JSON object, or if it does
+/// not match the structure of the value.
+#[cfg(all(
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSJSONSerialization"
+))]
+#[doc(alias = "dataWithJSONObject:options:error:")]
+pub fn from_object<T: DeserializeOwned>(object: &AnyObject) -> Result<T, Error> {
+    // `dataWithJSONObject:options:error:` throws an exception for invalid
+    // objects, so check that first. Top-level fragments are not considered
+    // valid, so check those by wrapping them in an array.
+    let array;
+    let (object, is_fragment): (&AnyObject, bool) = if object.downcast_ref::<NSArray>().is_some()
+        || object.downcast_ref::<NSDictionary>().is_some()
+    {
+        (object, false)
+    } else {
+        array = NSArray::from_slice(&[object]);
+        (&array, true)
+    };
+    if !unsafe { NSJSONSerialization::isValidJSONObject(object) } {
+        return Err(Error::new("object is not a valid JSON object"));
+    }
+    let data = unsafe {
+        NSJSONSerialization::dataWithJSONObject_options_error(object, NSJSONWritingOptions::empty())
+    }
+    .map_err(|error| Error::new(error.localizedDescription()))?;
+
+    if is_fragment {
+        let [value]: [T; 1] = serde_json::from_slice(&data.to_vec()).map_err(Error::new)?;
+        Ok(value)
+    } else {
+        serde_json::from_slice(&data.to_vec()).map_err(Error::new)
+    }
+}
@@ -0,0 +1,107 @@
+//! Observing key paths with Rust closures.
+// Only used when some of the observing APIs are enabled.
+#![allow(dead_code)]
+use alloc::boxed::Box;
+use core::ffi::c_void;
+use core::ptr;
+
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, NSObject, NSObjectProtocol};
+use objc2::{define_class, msg_send_id, AllocAnyThread, DefinedClass, Message};
+
+use crate::{
+    NSDictionary, NSKeyValueChangeKey, NSKeyValueChangeNewKey, NSKeyValueObservingOptions,
+    NSObjectNSKeyValueObserverRegistration, NSString,
+};
+
+type Handler = Box<dyn Fn(Option<&AnyObject>) + Send + Sync>;
+
+define_class!(
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - `Observer` does not implement `Drop`.
+    #[unsafe(super(NSObject))]
+    #[name = "__RustNSKeyValueObserver"]
+    #[ivars = Handler]
+    struct Observer;
+
+    unsafe impl Observer {
+        #[method(observeValueForKeyPath:ofObject:change:context:)]
+        fn observe_value(
+            &self,
+            _key_path: Option<&NSString>,
+            _object: Option<&AnyObject>,
+            change: Option<&NSDictionary<NSKeyValueChangeKey, AnyObject>>,
+            _context: *mut c_void,
+        ) {
+            let new = change.and_then(|change| {
+                change.get_nonnull(unsafe { NSKeyValueChangeNewKey })
+            });
+            (self.ivars())(new.as_deref());
+        }
+    }
+
+    unsafe impl NSObjectProtocol for Observer {}
+);
+
+impl Observer {
+    fn new(handler: Handler) -> Retained<Self> {
+        let this = Self::alloc().set_ivars(handler);
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+/// An observer of a key path on an object, which is removed when this is
+/// dropped.
+///
+/// The handler is called with the new value of the key path (which may be
+/// `NSNull`), synchronously on the thread that made the change.
+pub(crate) struct KeyValueObservation {
+    object: Retained<NSObject>,
+    key_path: Retained<NSString>,
+    observer: Retained<Observer>,
+}
+
+impl KeyValueObservation {
+    pub(crate) fn new(
+        object: &NSObject,
+        key_path: &NSString,
+        options: NSKeyValueObservingOptions,
+        handler: impl Fn(Option<&AnyObject>) + Send + Sync + 'static,
+    ) -> Self {
+        let observer = Observer::new(Box::new(handler));
+
+        // SAFETY: The observer is removed again before it is released.
+        //
+        // Passing `NULL` as the `context` parameter here is fine, as the
+        // observer does not have any subclasses, and the superclass
+        // (NSObject) is not observing anything.
+        unsafe {
+            object.addObserver_forKeyPath_options_context(
+                &observer,
+                key_path,
+                options,
+                ptr::null_mut(),
+            )
+        };
+
+        Self {
+            object: object.retain(),
+            key_path: key_path.retain(),
+            observer,
+        }
+    }
+
+    pub(crate) fn key_path(&self) -> &NSString {
+        &self.key_path
+    }
+}
+
+impl Drop for KeyValueObservation {
+    fn drop(&mut self) {
+        unsafe {
+            self.object
+                .removeObserver_forKeyPath(&self.observer, &self.key_path)
+        };
+    }
+}
@@ -0,0 +1,289 @@
+//! Archiving object graphs with `NSKeyedArchiver` and `NSKeyedUnarchiver`.
+//!
+//! These helpers always use secure coding, which means that the classes
+//! that may be decoded must be listed up front. This prevents a malicious
+//! archive from instantiating arbitrary classes.
+use alloc::vec::Vec;
+
+use objc2::rc::{Allocated, Retained};
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2::{msg_send_id, ClassType, DefinedClass, DowncastTarget, Message};
+
+use crate::{
+    NSCocoaErrorDomain, NSCoder, NSCoderReadCorruptError, NSData, NSError, NSKeyedArchiver,
+    NSKeyedUnarchiver, NSSecureCoding, NSSet, NSString,
+};
+
+fn as_any_object<T: Message>(object: &T) -> &AnyObject {
+    // SAFETY: All `Message` types are objects.
+    unsafe { &*(object as *const T).cast() }
+}
+
+/// The classes that [`NSKeyedUnarchiver::unarchive`] is allowed to decode.
+///
+/// The class of the root object is always allowed, so this only needs to
+/// list the classes of the objects contained in it (including nested
+/// collections).
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::ClassType;
+/// use objc2_foundation::{NSAllowedClasses, NSData, NSNumber, NSString};
+///
+/// // Allow decoding e.g. an array containing strings and numbers.
+/// let allowed = NSAllowedClasses::new()
+///     .with::<NSString>()
+///     .with::<NSNumber>();
+/// assert!(allowed.contains(NSString::class()));
+/// assert!(!allowed.contains(NSData::class()));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NSAllowedClasses {
+    classes: Vec<&'static AnyClass>,
+}
+
+impl NSAllowedClasses {
+    /// An empty list, allowing only the root object's class.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow decoding instances of `T`.
+    ///
+    /// The class must support secure coding, otherwise unarchiving will
+    /// fail. Note that subclasses of `T` are not allowed unless listed too.
+    #[must_use]
+    pub fn with<T: ClassType>(mut self) -> Self {
+        self.add(T::class());
+        self
+    }
+
+    fn add(&mut self, cls: &'static AnyClass) {
+        if !self.contains(cls) {
+            self.classes.push(cls);
+        }
+    }
+
+    /// Whether instances of the given class are allowed.
+    pub fn contains(&self, cls: &AnyClass) -> bool {
+        self.classes.contains(&cls)
+    }
+
+    /// The allowed classes.
+    pub fn classes(&self) -> &[&'static AnyClass] {
+        &self.classes
+    }
+}
+
+/// Archiving.
+impl NSKeyedArchiver {
+    /// Encode the object graph whose root is the given object, using secure
+    /// coding.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an object in the graph does not support secure
+    /// coding, or otherwise failed to encode itself.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::{ns_string, NSAllowedClasses, NSKeyedArchiver, NSKeyedUnarchiver, NSString};
+    ///
+    /// let data = NSKeyedArchiver::archive(ns_string!("hello")).unwrap();
+    /// let string: objc2::rc::Retained<NSString> =
+    ///     NSKeyedUnarchiver::unarchive(&data, &NSAllowedClasses::new()).unwrap();
+    /// assert_eq!(string.to_string(), "hello");
+    /// ```
+    #[doc(alias = "archivedDataWithRootObject:requiringSecureCoding:error:")]
+    pub fn archive<T: NSSecureCoding + Message>(
+        object: &T,
+    ) -> Result<Retained<NSData>, Retained<NSError>> {
+        // SAFETY: Secure coding is required, so objects that cannot be
+        // encoded securely result in an error instead of an archive that
+        // cannot be decoded again.
+        unsafe {
+            Self::archivedDataWithRootObject_requiringSecureCoding_error(
+                as_any_object(object),
+                true,
+            )
+        }
+    }
+}
+
+/// Unarchiving.
+impl NSKeyedUnarchiver {
+    /// Decode an object graph whose root is an instance of `T`, using
+    /// secure coding.
+    ///
+    /// `allowed` lists the classes that the objects contained in the root
+    /// object may have.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a valid archive, if it contains
+    /// an object whose class is not allowed, or if the root object is not
+    /// an instance of `T`.
+    #[doc(alias = "unarchivedObjectOfClasses:fromData:error:")]
+    pub fn unarchive<T: DowncastTarget>(
+        data: &NSData,
+        allowed: &NSAllowedClasses,
+    ) -> Result<Retained<T>, Retained<NSError>> {
+        let mut allowed = allowed.clone();
+        allowed.add(T::class());
+        let classes = NSSet::from_slice(allowed.classes());
+        // SAFETY: Secure coding is enabled by this method, and the decoding
+        // failure policy is set to return an error instead of throwing.
+        let object = unsafe { Self::unarchivedObjectOfClasses_fromData_error(&classes, data) }?;
+        object.downcast::<T>().map_err(|object| {
+            let description = alloc::format!(
+                "expected the root object to be an instance of {}, found {}",
+                T::NAME,
+                object.class().name().to_string_lossy(),
+            );
+            NSError::new_with(
+                unsafe { NSCocoaErrorDomain },
+                NSCoderReadCorruptError,
+                |builder| {
+                    builder.description(&description);
+                },
+            )
+        })
+    }
+}
+
+/// Keyed coding helpers.
+impl NSCoder {
+    /// Encode an object under the given key.
+    #[doc(alias = "encodeObject:forKey:")]
+    pub fn encode_object<T: NSSecureCoding + Message>(&self, key: &str, object: &T) {
+        let key = NSString::from_str(key);
+        // SAFETY: The object supports secure coding, so it can be encoded
+        // by both secure and non-secure coders.
+        unsafe { self.encodeObject_forKey(Some(as_any_object(object)), &key) };
+    }
+
+    /// Decode an object of the given class that was encoded under the
+    /// given key.
+    ///
+    /// Returns [`None`] if the key is not present, or if the object is not
+    /// an instance of `T`.
+    #[doc(alias = "decodeObjectOfClass:forKey:")]
+    pub fn decode_object<T: DowncastTarget>(&self, key: &str) -> Option<Retained<T>> {
+        let key = NSString::from_str(key);
+        // SAFETY: Only instances of `T` are decoded.
+        let object = unsafe { self.decodeObjectOfClass_forKey(T::class(), &key) }?;
+        object.downcast().ok()
+    }
+}
+
+/// Support for archiving instances of classes declared with
+/// [`define_class!`].
+///
+/// Implement this to describe how the instance variables of the class are
+/// encoded and decoded, and forward the `NSCoding` and `NSSecureCoding`
+/// methods of the class to it, as shown below.
+///
+/// [`define_class!`]: objc2::define_class
+///
+///
+/// # Example
+///
+/// ```
+/// use objc2::rc::{Allocated, Retained};
+/// use objc2::{define_class, AllocAnyThread, DefinedClass};
+/// use objc2_foundation::{
+///     ns_string, NSAllowedClasses, NSCoder, NSCoding, NSKeyedArchiver, NSKeyedCoding,
+///     NSKeyedUnarchiver, NSObject, NSObjectProtocol, NSSecureCoding, NSString,
+/// };
+///
+/// struct Ivars {
+///     name: Retained<NSString>,
+///     count: i64,
+/// }
+///
+/// define_class!(
+///     #[unsafe(super(NSObject))]
+///     #[name = "MyCodableObject"]
+///     #[ivars = Ivars]
+///     struct MyObject;
+///
+///     unsafe impl NSObjectProtocol for MyObject {}
+///
+///     unsafe impl NSCoding for MyObject {
+///         #[method(encodeWithCoder:)]
+///         fn encode_with_coder(&self, coder: &NSCoder) {
+///             NSKeyedCoding::encode(self, coder);
+///         }
+///
+///         #[method_id(initWithCoder:)]
+///         fn init_with_coder(this: Allocated<Self>, coder: &NSCoder) -> Option<Retained<Self>> {
+///             NSKeyedCoding::init_from_coder(this, coder)
+///         }
+///     }
+///
+///     unsafe impl NSSecureCoding for MyObject {
+///         #[method(supportsSecureCoding)]
+///         fn supports_secure_coding() -> bool {
+///             true
+///         }
+///     }
+/// );
+///
+/// impl NSKeyedCoding for MyObject {
+///     fn encode(&self, coder: &NSCoder) {
+///         coder.encode_object("name", &*self.ivars().name);
+///         unsafe { coder.encodeInt64_forKey(self.ivars().count, ns_string!("count")) };
+///     }
+///
+///     fn decode(coder: &NSCoder) -> Option<Ivars> {
+///         Some(Ivars {
+///             name: coder.decode_object("name")?,
+///             count: unsafe { coder.decodeInt64ForKey(ns_string!("count")) },
+///         })
+///     }
+/// }
+///
+/// let object = MyObject::alloc().set_ivars(Ivars {
+///     name: NSString::from_str("abc"),
+///     count: 42,
+/// });
+/// let object: Retained<MyObject> = unsafe { objc2::msg_send_id![super(object), init] };
+///
+/// let data = NSKeyedArchiver::archive(&*object).unwrap();
+/// let allowed = NSAllowedClasses::new().with::<NSString>();
+/// let decoded = NSKeyedUnarchiver::unarchive::<MyObject>(&data, &allowed).unwrap();
+/// assert_eq!(decoded.ivars().name.to_string(), "abc");
+/// assert_eq!(decoded.ivars().count, 42);
+/// ```
+pub trait NSKeyedCoding: DefinedClass + Sized {
+    /// Encode the instance variables with the coder.
+    ///
+    /// Called from `encodeWithCoder:`.
+    fn encode(&self, coder: &NSCoder);
+
+    /// Decode the instance variables that were encoded with
+    /// [`encode`](Self::encode).
+    ///
+    /// Return [`None`] if the archive is invalid.
+    fn decode(coder: &NSCoder) -> Option<Self::Ivars>;
+
+    /// Implementation of `initWithCoder:`.
+    ///
+    /// This decodes the instance variables, and initializes the superclass
+    /// with `init`.
+    #[doc(alias = "initWithCoder:")]
+    fn init_from_coder(this: Allocated<Self>, coder: &NSCoder) -> Option<Retained<Self>>
+    where
+        Self::Super: ClassType,
+    {
+        let ivars = Self::decode(coder)?;
+        let this = this.set_ivars(ivars);
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
@@ -85,20 +85,34 @@ pub mod __ns_macro_helpers;
 #[cfg(feature = "NSEnumerator")]
 #[macro_use]
 mod iter;
+#[cfg(all(feature = "NSAppleEventDescriptor", feature = "NSString"))]
+mod apple_event_descriptor;
 #[cfg(feature = "NSArray")]
 pub mod array;
 #[cfg(feature = "NSAttributedString")]
 mod attributed_string;
+#[cfg(all(feature = "NSArray", feature = "NSString", feature = "NSValue"))]
+mod bridging_arena;
 #[cfg(feature = "NSBundle")]
 mod bundle;
+#[cfg(feature = "NSCache")]
+mod cache;
+#[cfg(feature = "NSLock")]
+pub mod channel;
 #[cfg(feature = "NSObjCRuntime")]
 mod comparison_result;
 #[cfg(feature = "NSObject")]
 mod copying;
 #[cfg(feature = "NSData")]
 mod data;
+#[cfg(all(feature = "NSData", feature = "NSRange"))]
+mod data_reader;
+#[cfg(feature = "NSDate")]
+mod date;
 #[cfg(feature = "NSDecimal")]
 mod decimal;
+#[cfg(all(feature = "NSDecimal", feature = "NSDecimalNumber", feature = "NSValue"))]
+mod decimal_number;
 #[cfg(feature = "NSDictionary")]
 pub mod dictionary;
 #[cfg(feature = "NSEnumerator")]
@@ -109,53 +123,337 @@ mod error;
 mod exception;
 #[cfg(feature = "NSEnumerator")]
 mod fast_enumeration_state;
+#[cfg(all(
+    feature = "std",
+    feature = "NSFileHandle",
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSString"
+))]
+mod file_handle;
+#[cfg(all(feature = "NSFileManager", feature = "NSError", feature = "NSURL"))]
+mod file_manager;
 mod generated;
 #[cfg(feature = "NSGeometry")]
 mod geometry;
+#[cfg(feature = "NSIndexPath")]
+pub mod index_path;
+#[cfg(feature = "NSIndexSet")]
+pub mod index_set;
+#[cfg(all(
+    feature = "serde_json",
+    feature = "NSArray",
+    feature = "NSDictionary",
+    feature = "NSEnumerator",
+    feature = "NSNull",
+    feature = "NSObject",
+    feature = "NSString",
+    feature = "NSValue"
+))]
+pub mod json;
+#[cfg(all(
+    feature = "FoundationErrors",
+    feature = "NSCoder",
+    feature = "NSData",
+    feature = "NSDictionary",
+    feature = "NSError",
+    feature = "NSKeyedArchiver",
+    feature = "NSObject",
+    feature = "NSSet",
+    feature = "NSString"
+))]
+mod keyed_archive;
+#[cfg(all(
+    feature = "std",
+    feature = "NSDictionary",
+    feature = "NSKeyValueObserving",
+    feature = "NSNull",
+    feature = "NSString"
+))]
+mod key_value_observing;
+mod localized;
+#[cfg(feature = "NSLock")]
+mod lock;
 mod macros;
 mod ns_consumed;
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSString"
+))]
+mod notification;
+#[cfg(feature = "NSNull")]
+mod null;
 #[cfg(feature = "NSValue")]
 mod number;
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSLock",
+    feature = "NSOperation"
+))]
+mod operation;
 #[cfg(feature = "NSProcessInfo")]
 mod process_info;
+#[cfg(feature = "NSProgress")]
+mod progress;
+#[cfg(all(
+    feature = "serde",
+    feature = "NSArray",
+    feature = "NSData",
+    feature = "NSDictionary",
+    feature = "NSObject",
+    feature = "NSString",
+    feature = "NSValue"
+))]
+pub mod property_list;
 #[cfg(feature = "NSRange")]
 mod range;
+#[cfg(all(
+    feature = "NSArray",
+    feature = "NSEnumerator",
+    feature = "NSError",
+    feature = "NSRange",
+    feature = "NSRegularExpression",
+    feature = "NSString",
+    feature = "NSTextCheckingResult"
+))]
+pub mod regular_expression;
+#[cfg(all(
+    feature = "NSRunLoop",
+    feature = "NSDate",
+    feature = "NSObjCRuntime",
+    feature = "NSString"
+))]
+mod run_loop;
 #[cfg(feature = "NSSet")]
 pub mod set;
+#[cfg(all(
+    feature = "std",
+    feature = "NSStream",
+    feature = "NSError",
+    feature = "NSString"
+))]
+mod stream;
 #[cfg(feature = "NSString")]
 mod string;
 #[cfg(test)]
 mod tests;
 #[cfg(feature = "NSThread")]
 mod thread;
+#[cfg(all(
+    feature = "objc2-core-foundation",
+    any(
+        feature = "NSArray",
+        feature = "NSData",
+        feature = "NSDate",
+        feature = "NSDictionary",
+        feature = "NSError",
+        feature = "NSString",
+        feature = "NSURL"
+    )
+))]
+mod toll_free;
 #[cfg(feature = "NSObject")]
 mod to_owned;
+#[cfg(feature = "NSUbiquitousKeyValueStore")]
+mod ubiquitous_key_value_store;
+#[cfg(all(
+    feature = "NSURL",
+    any(all(feature = "std", unix), all(feature = "url", feature = "NSString"))
+))]
+mod url;
+#[cfg(all(feature = "NSString", feature = "NSURL", feature = "NSURLRequest"))]
+mod url_request;
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSFileManager",
+    feature = "NSOperation",
+    feature = "NSString",
+    feature = "NSURL",
+    feature = "NSURLSession"
+))]
+mod url_session;
+#[cfg(all(
+    feature = "std",
+    feature = "NSArray",
+    feature = "NSDate",
+    feature = "NSDateInterval",
+    feature = "NSEnumerator",
+    feature = "NSString",
+    feature = "NSURLSession"
+))]
+mod url_session_metrics;
 mod util;
+#[cfg(all(feature = "NSUserDefaults", feature = "NSString"))]
+mod user_defaults;
 #[cfg(feature = "NSUUID")]
 mod uuid;
 #[cfg(feature = "NSValue")]
 mod value;
+#[cfg(all(
+    feature = "block2",
+    feature = "NSError",
+    feature = "NSString",
+    feature = "NSXPCConnection"
+))]
+mod xpc_connection;
 
+#[cfg(all(feature = "NSAppleEventDescriptor", feature = "NSString"))]
+pub use self::apple_event_descriptor::NSAppleEventObjectKey;
+#[cfg(all(feature = "NSArray", feature = "NSString", feature = "NSValue"))]
+pub use self::bridging_arena::BridgingArena;
+#[cfg(feature = "NSCache")]
+pub use self::cache::Cache;
 #[cfg(feature = "NSObjCRuntime")]
 pub use self::comparison_result::NSComparisonResult;
 #[cfg(feature = "NSObject")]
-pub use self::copying::{CopyingHelper, MutableCopyingHelper, NSCopying, NSMutableCopying};
+pub use self::copying::{
+    CopyingHelper, MutableCopyingHelper, NSCopying, NSCopyingKey, NSMutableCopying,
+};
+#[cfg(all(feature = "NSData", feature = "NSRange"))]
+pub use self::data_reader::{NSDataReader, NSDataReaderError};
 #[cfg(feature = "NSDecimal")]
 pub use self::decimal::NSDecimal;
 #[cfg(feature = "NSEnumerator")]
 pub use self::fast_enumeration_state::NSFastEnumerationState;
+#[cfg(all(
+    feature = "block2",
+    feature = "NSArray",
+    feature = "NSEnumerator",
+    feature = "NSError",
+    feature = "NSFileManager",
+    feature = "NSString",
+    feature = "NSURL"
+))]
+pub use self::file_manager::NSDirectoryEntries;
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(all(
+    feature = "NSError",
+    feature = "NSDictionary",
+    feature = "NSObject",
+    feature = "NSString"
+))]
+pub use self::error::NSErrorBuilder;
 #[cfg(feature = "NSGeometry")]
 pub use self::geometry::NSRectEdge;
 #[cfg(all(feature = "NSGeometry", feature = "objc2-core-foundation"))]
 pub use self::geometry::{NSPoint, NSRect, NSSize};
+#[cfg(all(
+    feature = "FoundationErrors",
+    feature = "NSCoder",
+    feature = "NSData",
+    feature = "NSDictionary",
+    feature = "NSError",
+    feature = "NSKeyedArchiver",
+    feature = "NSObject",
+    feature = "NSSet",
+    feature = "NSString"
+))]
+pub use self::keyed_archive::{NSAllowedClasses, NSKeyedCoding};
+pub use self::localized::{LocalizedKey, LocalizedKeys};
+#[cfg(feature = "NSLock")]
+pub use self::lock::NSLockGuard;
 #[cfg(feature = "NSMapTable")]
 pub use self::ns_consumed::NSFreeMapTable;
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSString"
+))]
+pub use self::notification::{NSNotificationObserver, NSNotificationPayload};
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSLock",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSString"
+))]
+pub use self::notification::NSNotificationStream;
+#[cfg(feature = "NSValue")]
+pub use self::number::TryFromNSNumberError;
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSLock",
+    feature = "NSOperation"
+))]
+pub use self::operation::NSOperationTask;
+#[cfg(all(feature = "NSProcessInfo", feature = "NSString"))]
+pub use self::process_info::NSProcessInfoActivity;
+#[cfg(all(
+    feature = "std",
+    feature = "NSLock",
+    feature = "NSProgress",
+    feature = "NSDictionary",
+    feature = "NSKeyValueObserving",
+    feature = "NSNull",
+    feature = "NSString",
+    feature = "NSValue"
+))]
+pub use self::progress::NSProgressChanges;
+#[cfg(all(feature = "block2", feature = "NSProgress", feature = "NSURL"))]
+pub use self::progress::{NSProgressPublication, NSProgressSubscription};
 #[cfg(feature = "NSRange")]
 pub use self::range::NSRange;
 #[cfg(feature = "NSThread")]
 pub use self::thread::*;
+#[cfg(all(feature = "NSUbiquitousKeyValueStore", feature = "NSString"))]
+pub use self::ubiquitous_key_value_store::*;
+#[cfg(all(feature = "NSString", feature = "NSURL", feature = "NSURLRequest"))]
+pub use self::url_request::{NSHTTPMethod, NSURLRequestBuilder, NSURLRequestBuilderError};
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSFileManager",
+    feature = "NSOperation",
+    feature = "NSString",
+    feature = "NSURL",
+    feature = "NSURLSession"
+))]
+pub use self::url_session::{NSBackgroundURLSession, NSURLSessionBackgroundEvent};
+#[cfg(all(
+    feature = "std",
+    feature = "NSArray",
+    feature = "NSDate",
+    feature = "NSDateInterval",
+    feature = "NSEnumerator",
+    feature = "NSString",
+    feature = "NSURLSession"
+))]
+pub use self::url_session_metrics::{NSURLSessionTaskTimings, NSURLSessionTransactionTimings};
+#[cfg(all(
+    feature = "std",
+    feature = "NSLock",
+    feature = "NSUserDefaults",
+    feature = "NSDictionary",
+    feature = "NSKeyValueObserving",
+    feature = "NSNull",
+    feature = "NSString"
+))]
+pub use self::user_defaults::NSUserDefaultsChanges;
+#[cfg(all(feature = "NSUserDefaults", feature = "NSString"))]
+pub use self::user_defaults::NSUserDefaultsValue;
+#[cfg(feature = "NSValue")]
+pub use self::value::NSValueEncodingMismatch;
+#[cfg(all(
+    feature = "block2",
+    feature = "NSError",
+    feature = "NSString",
+    feature = "NSXPCConnection"
+))]
+pub use self::xpc_connection::NSXPCConnectionHandler;
 
 // Available under Foundation, so makes sense here as well:
 // https://developer.apple.com/documentation/foundation/numbers_data_and_basic_values?language=objc
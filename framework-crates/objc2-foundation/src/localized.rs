@@ -0,0 +1,108 @@
+//! Parsing of the keys recorded by `localized!`.
+use core::iter::FusedIterator;
+use core::str;
+
+/// A localized string key recorded by [`localized!`].
+///
+/// See [the macro's documentation][key-extraction] for details.
+///
+/// [`localized!`]: crate::localized!
+/// [key-extraction]: crate::localized!#key-extraction
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LocalizedKey<'a> {
+    /// The strings table that the key is looked up in, or [`None`] for the
+    /// default `Localizable` table.
+    pub table: Option<&'a str>,
+    /// The key.
+    pub key: &'a str,
+    /// The value to use if the key has not been translated, or [`None`] to
+    /// use the key.
+    pub value: Option<&'a str>,
+    /// The comment for translators, if any.
+    pub comment: Option<&'a str>,
+}
+
+impl<'a> LocalizedKey<'a> {
+    /// The segment and section that the keys are placed in on Apple
+    /// platforms.
+    pub const MACHO_SECTION: (&'static str, &'static str) = ("__DATA", "__objc2_l10n");
+
+    /// The section that the keys are placed in on other platforms.
+    pub const ELF_SECTION: &'static str = "objc2_l10n";
+
+    /// Parse the contents of the section that the keys are placed in.
+    ///
+    /// The same key may be recorded several times if the macro is invoked
+    /// in several places.
+    ///
+    /// Parsing stops at the first malformed record, such as if the section
+    /// was truncated or does not contain valid UTF-8.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::LocalizedKey;
+    ///
+    /// let section = b"\0Hello\0\0Greeting\0Sync\0sync.button\0Sync Now\0\0";
+    /// let keys: Vec<_> = LocalizedKey::parse_section(section).collect();
+    /// assert_eq!(keys, [
+    ///     LocalizedKey { table: None, key: "Hello", value: None, comment: Some("Greeting") },
+    ///     LocalizedKey { table: Some("Sync"), key: "sync.button", value: Some("Sync Now"), comment: None },
+    /// ]);
+    /// ```
+    pub fn parse_section(section: &'a [u8]) -> LocalizedKeys<'a> {
+        LocalizedKeys { section }
+    }
+}
+
+/// An iterator over the keys in a section.
+///
+/// Created by [`LocalizedKey::parse_section`].
+#[derive(Clone, Debug)]
+pub struct LocalizedKeys<'a> {
+    section: &'a [u8],
+}
+
+impl<'a> LocalizedKeys<'a> {
+    fn next_field(&mut self) -> Option<&'a str> {
+        let end = self.section.iter().position(|&b| b == 0)?;
+        let field = str::from_utf8(&self.section[..end]).ok()?;
+        self.section = &self.section[end + 1..];
+        Some(field)
+    }
+}
+
+fn non_empty(field: &str) -> Option<&str> {
+    if field.is_empty() {
+        None
+    } else {
+        Some(field)
+    }
+}
+
+impl<'a> Iterator for LocalizedKeys<'a> {
+    type Item = LocalizedKey<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut parse = || {
+            let table = self.next_field()?;
+            let key = self.next_field()?;
+            let value = self.next_field()?;
+            let comment = self.next_field()?;
+            Some(LocalizedKey {
+                table: non_empty(table),
+                key,
+                value: non_empty(value),
+                comment: non_empty(comment),
+            })
+        };
+        let item = parse();
+        if item.is_none() {
+            self.section = &[];
+        }
+        item
+    }
+}
+
+impl FusedIterator for LocalizedKeys<'_> {}
@@ -0,0 +1,185 @@
+//! RAII guards for Foundation's locks.
+//!
+//! Foundation's locks are mostly useful when the lock is shared with
+//! Objective-C code, e.g. if a framework or a delegate implemented in
+//! Objective-C expects you to take a specific `NSLock` before touching some
+//! shared state, or if you need a `NSCondition` that Objective-C code waits
+//! on.
+//!
+//! For locks that are only ever used from Rust, you should prefer the types
+//! in `std::sync`, which are faster, poison on panics, and tie the data
+//! being protected to the lock itself.
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+#[cfg(feature = "NSDate")]
+use core::time::Duration;
+
+use objc2::Message;
+
+#[cfg(feature = "NSDate")]
+use crate::NSDate;
+use crate::{NSCondition, NSConditionLock, NSLock, NSLocking, NSRecursiveLock};
+
+/// An RAII guard that unlocks a Foundation lock when dropped.
+///
+/// Created by e.g. [`NSLock::lock_guard`] or [`NSLock::try_lock`].
+///
+/// Foundation's locks must be unlocked from the same thread that locked
+/// them, so this is neither [`Send`] nor [`Sync`].
+#[must_use = "if unused the lock will immediately unlock"]
+pub struct NSLockGuard<'a, L: NSLocking + Message> {
+    lock: &'a L,
+    // Must not be sent to (or unlocked from) another thread.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<'a, L: NSLocking + Message> NSLockGuard<'a, L> {
+    /// # Safety
+    ///
+    /// The lock must be locked by the current thread, and the guard must be
+    /// the only thing that is going to unlock it.
+    unsafe fn new(lock: &'a L) -> Self {
+        Self {
+            lock,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl<L: NSLocking + Message> Deref for NSLockGuard<'_, L> {
+    type Target = L;
+
+    #[inline]
+    fn deref(&self) -> &L {
+        self.lock
+    }
+}
+
+impl<L: NSLocking + Message> Drop for NSLockGuard<'_, L> {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: The lock was locked by this thread when the guard was
+        // created, and the guard is `!Send`.
+        unsafe { self.lock.unlock() };
+    }
+}
+
+impl<L: NSLocking + Message + fmt::Debug> fmt::Debug for NSLockGuard<'_, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSLockGuard")
+            .field("lock", &self.lock)
+            .finish()
+    }
+}
+
+macro_rules! lock_guard_methods {
+    ($($ty:ident),* $(,)?) => {$(
+        impl $ty {
+            /// Acquire the lock, blocking the current thread until it is
+            /// available.
+            ///
+            /// The lock is released when the returned guard is dropped.
+            #[doc(alias = "lock")]
+            pub fn lock_guard(&self) -> NSLockGuard<'_, Self> {
+                // SAFETY: Locking is always safe.
+                unsafe { self.lock() };
+                // SAFETY: We just locked the lock on this thread.
+                unsafe { NSLockGuard::new(self) }
+            }
+
+            /// Attempt to acquire the lock without blocking.
+            ///
+            /// Returns [`None`] if the lock is currently held.
+            #[doc(alias = "tryLock")]
+            pub fn try_lock(&self) -> Option<NSLockGuard<'_, Self>> {
+                if unsafe { self.tryLock() } {
+                    // SAFETY: We just locked the lock on this thread.
+                    Some(unsafe { NSLockGuard::new(self) })
+                } else {
+                    None
+                }
+            }
+        }
+    )*};
+}
+
+lock_guard_methods!(NSLock, NSRecursiveLock, NSConditionLock);
+
+impl NSCondition {
+    /// Acquire the condition's lock, blocking the current thread until it is
+    /// available.
+    ///
+    /// The lock is released when the returned guard is dropped. Use the
+    /// guard to wait on, or signal, the condition.
+    #[doc(alias = "lock")]
+    pub fn lock_guard(&self) -> NSLockGuard<'_, Self> {
+        // SAFETY: Locking is always safe.
+        unsafe { self.lock() };
+        // SAFETY: We just locked the lock on this thread.
+        unsafe { NSLockGuard::new(self) }
+    }
+}
+
+/// Condition variable helpers.
+///
+/// Waiting on a `NSCondition` atomically releases the lock, and re-acquires
+/// it before returning, so these are only available while the lock is held.
+///
+/// Note that, just as with `std::sync::Condvar`, waits may wake up
+/// spuriously, so you should usually use [`wait_while`] instead of [`wait`].
+///
+/// [`wait_while`]: Self::wait_while
+/// [`wait`]: Self::wait
+impl NSLockGuard<'_, NSCondition> {
+    /// Block the current thread until the condition is signalled.
+    pub fn wait(&self) {
+        // SAFETY: The lock is held by the current thread.
+        unsafe { self.lock.wait() };
+    }
+
+    /// Block the current thread until `condition` returns `false`.
+    pub fn wait_while(&self, mut condition: impl FnMut() -> bool) {
+        while condition() {
+            self.wait();
+        }
+    }
+
+    /// Block the current thread until the condition is signalled, or the
+    /// given timeout elapses.
+    ///
+    /// Returns `false` if the timeout elapsed.
+    #[cfg(feature = "NSDate")]
+    #[doc(alias = "waitUntilDate:")]
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let limit = unsafe { NSDate::dateWithTimeIntervalSinceNow(timeout.as_secs_f64()) };
+        self.wait_until(&limit)
+    }
+
+    /// Block the current thread until the condition is signalled, or the
+    /// given date is reached.
+    ///
+    /// Returns `false` if the date was reached. Since the date is absolute,
+    /// this is useful for retrying after spurious wakeups without extending
+    /// the total time spent waiting.
+    #[cfg(feature = "NSDate")]
+    #[doc(alias = "waitUntilDate:")]
+    pub fn wait_until(&self, limit: &NSDate) -> bool {
+        // SAFETY: The lock is held by the current thread.
+        unsafe { self.lock.waitUntilDate(limit) }
+    }
+
+    /// Wake up one thread waiting on the condition.
+    #[doc(alias = "signal")]
+    pub fn notify_one(&self) {
+        // SAFETY: The lock is held by the current thread, as recommended.
+        unsafe { self.lock.signal() };
+    }
+
+    /// Wake up all threads waiting on the condition.
+    #[doc(alias = "broadcast")]
+    pub fn notify_all(&self) {
+        // SAFETY: The lock is held by the current thread, as recommended.
+        unsafe { self.lock.broadcast() };
+    }
+}
@@ -0,0 +1,108 @@
+/// Look up a localized string, and record the key for extraction tooling.
+///
+/// This is the Rust equivalent of the `NSLocalizedString` family of macros,
+/// and returns the localized string as a [`String`]. The string is looked up
+/// with `-[NSBundle localizedStringForKey:value:table:]`, so the usual
+/// `.strings`, `.stringsdict` and `.xcstrings` files are supported.
+///
+/// The key must be a string literal, and may be followed by these optional
+/// arguments, in this order:
+/// - `table = "..."`: The name of the strings table to search, without the
+///   extension. Defaults to `Localizable`.
+/// - `bundle = expr`: A `&NSBundle` to search in. Defaults to
+///   [`NSBundle::mainBundle`].
+/// - `value = "..."`: The value to return if the key is not found. Defaults
+///   to the key itself.
+/// - `comment = "..."`: A comment for translators.
+///
+/// [`String`]: alloc::string::String
+/// [`NSBundle::mainBundle`]: crate::NSBundle::mainBundle
+///
+///
+/// # Key extraction
+///
+/// Each use of the macro places a record of the table, key, default value
+/// and comment in a dedicated section of the compiled binary; the
+/// `__DATA,__objc2_l10n` section on Apple platforms, and the `objc2_l10n`
+/// section elsewhere. Tooling can read this section from the built binary,
+/// and parse it with [`LocalizedKey::parse_section`] to generate strings
+/// files, similar to what `genstrings` does for Objective-C sources.
+///
+/// [`LocalizedKey::parse_section`]: crate::LocalizedKey::parse_section
+///
+///
+/// # Examples
+///
+/// ```
+/// use objc2_foundation::localized;
+///
+/// let title = localized!("Welcome", comment = "Title of the welcome window");
+/// # assert_eq!(title, "Welcome");
+///
+/// let button = localized!(
+///     "sync.button",
+///     table = "Sync",
+///     value = "Sync Now",
+///     comment = "Button that starts a sync",
+/// );
+/// # assert_eq!(button, "Sync Now");
+/// ```
+///
+/// Look up the string in a specific bundle.
+///
+/// ```
+/// use objc2_foundation::{localized, NSBundle};
+///
+/// let bundle = NSBundle::mainBundle();
+/// let text = localized!("Cancel", bundle = &bundle);
+/// # assert_eq!(text, "Cancel");
+/// ```
+// For auto_doc_cfg
+#[cfg(all(feature = "NSBundle", feature = "NSString"))]
+#[macro_export]
+macro_rules! localized {
+    (
+        $key:literal
+        $(, table = $table:literal)?
+        $(, bundle = $bundle:expr)?
+        $(, value = $value:literal)?
+        $(, comment = $comment:literal)?
+        $(,)?
+    ) => {{
+        $crate::__localized_record!(::core::concat!(
+            $($table,)? "\0",
+            $key, "\0",
+            $($value,)? "\0",
+            $($comment,)? "\0",
+        ));
+        $crate::__ns_macro_helpers::localized(
+            $crate::ns_string!($key),
+            ::core::option::Option::None
+                $(.or(::core::option::Option::Some($crate::ns_string!($table))))?,
+            ::core::option::Option::None
+                $(.or({
+                    let bundle: &$crate::NSBundle = $bundle;
+                    ::core::option::Option::Some(bundle)
+                }))?,
+            ::core::option::Option::None
+                $(.or(::core::option::Option::Some($crate::ns_string!($value))))?,
+        )
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __localized_record {
+    ($entry:expr) => {
+        const ENTRY: &str = $entry;
+
+        #[used]
+        #[cfg_attr(
+            target_vendor = "apple",
+            link_section = "__DATA,__objc2_l10n,regular,no_dead_strip"
+        )]
+        #[cfg_attr(not(target_vendor = "apple"), link_section = "objc2_l10n")]
+        static LOCALIZED_ENTRY: [u8; ENTRY.len()] =
+            $crate::__ns_macro_helpers::localized_entry(ENTRY);
+    };
+}
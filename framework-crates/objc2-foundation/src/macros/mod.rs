@@ -1,2 +1,6 @@
+#[cfg(all(feature = "NSBundle", feature = "NSString"))]
+mod localized;
+#[cfg(feature = "NSString")]
+mod ns_format;
 #[cfg(feature = "NSString")]
 mod ns_string;
@@ -0,0 +1,33 @@
+/// Create a [`NSString`] using interpolation of runtime expressions.
+///
+/// This is the `NSString` equivalent of [`alloc::format!`], and takes the
+/// same arguments. The string is built directly by writing to a
+/// [`NSMutableString`], so no intermediate [`String`] is allocated.
+///
+/// [`NSString`]: crate::NSString
+/// [`NSMutableString`]: crate::NSMutableString
+/// [`String`]: alloc::string::String
+///
+///
+/// # Panics
+///
+/// Panics if a formatting trait implementation returns an error.
+///
+///
+/// # Examples
+///
+/// ```
+/// use objc2_foundation::ns_format;
+///
+/// let name = "world";
+/// let string = ns_format!("Hello, {name}! {}", 42);
+/// assert_eq!(string.to_string(), "Hello, world! 42");
+/// ```
+// For auto_doc_cfg
+#[cfg(feature = "NSString")]
+#[macro_export]
+macro_rules! ns_format {
+    ($($arg:tt)*) => {
+        $crate::__ns_macro_helpers::ns_format(::core::format_args!($($arg)*))
+    };
+}
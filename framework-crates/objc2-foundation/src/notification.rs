@@ -0,0 +1,264 @@
+//! Observing notifications with Rust closures.
+use core::fmt;
+#[cfg(feature = "NSLock")]
+use core::future::Future;
+use core::ptr::NonNull;
+#[cfg(feature = "NSLock")]
+use core::task::{Context, Poll};
+
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2::Message;
+
+#[cfg(feature = "NSLock")]
+use crate::channel::{self, Receiver};
+use crate::{NSNotification, NSNotificationCenter, NSNotificationName};
+
+/// A notification with a known name, whose `userInfo` (and object) can be
+/// decoded into a Rust value.
+///
+/// This is used with [`NSNotificationCenter::add_typed_observer`] and
+/// [`NSNotificationCenter::typed_notifications`], and can be implemented by
+/// other crates for the notifications that their frameworks post.
+pub trait NSNotificationPayload: Sized {
+    /// The name of the notification.
+    fn name() -> &'static NSNotificationName;
+
+    /// Decode the payload of a posted notification with this name.
+    fn from_notification(notification: &NSNotification) -> Self;
+}
+
+/// The token returned by `addObserverForName:object:queue:usingBlock:`.
+struct ObserverToken(Retained<AnyObject>);
+
+// SAFETY: The token is only used to remove the observer again, and
+// `NSNotificationCenter` is thread-safe.
+unsafe impl Send for ObserverToken {}
+unsafe impl Sync for ObserverToken {}
+
+/// A registered notification observer.
+///
+/// The observer is removed from the notification center when this is
+/// dropped, after which the closure is no longer called.
+///
+/// See [`NSNotificationCenter::add_observer`].
+#[must_use = "the observer is removed when this is dropped"]
+pub struct NSNotificationObserver {
+    center: Retained<NSNotificationCenter>,
+    token: ObserverToken,
+}
+
+impl Drop for NSNotificationObserver {
+    #[doc(alias = "removeObserver:")]
+    fn drop(&mut self) {
+        unsafe { self.center.removeObserver(&self.token.0) };
+    }
+}
+
+impl fmt::Debug for NSNotificationObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSNotificationObserver")
+            .field("center", &self.center)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A stream of notifications.
+///
+/// Notifications are queued from the moment the stream is created, and can
+/// be retrieved either by blocking ([`recv`]), by polling ([`try_next`]), or
+/// asynchronously ([`next`] or [`poll_next`], the latter of which can be
+/// used to implement `futures::Stream`).
+///
+/// The observer is removed when this is dropped.
+///
+/// See [`NSNotificationCenter::notifications`].
+///
+/// [`recv`]: Self::recv
+/// [`try_next`]: Self::try_next
+/// [`next`]: Self::next
+/// [`poll_next`]: Self::poll_next
+#[cfg(feature = "NSLock")]
+pub struct NSNotificationStream<T> {
+    // Dropped first, such that the sender is released before the receiver.
+    _observer: NSNotificationObserver,
+    receiver: Receiver<T>,
+}
+
+#[cfg(feature = "NSLock")]
+impl<T> NSNotificationStream<T> {
+    /// Get the next notification if one has already been received.
+    pub fn try_next(&self) -> Option<T> {
+        self.receiver.try_recv()
+    }
+
+    /// Block the current thread until the next notification is received.
+    ///
+    /// Beware that notifications are delivered on the thread that posted
+    /// them, so this will deadlock if that is the current thread.
+    pub fn recv(&self) -> Option<T> {
+        self.receiver.recv()
+    }
+
+    /// Poll for the next notification.
+    pub fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.receiver.poll_recv(cx)
+    }
+
+    /// Wait asynchronously for the next notification.
+    pub fn next(&self) -> impl Future<Output = Option<T>> + '_ {
+        core::future::poll_fn(|cx| self.poll_next(cx))
+    }
+}
+
+#[cfg(feature = "NSLock")]
+impl<T> fmt::Debug for NSNotificationStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSNotificationStream")
+            .field("receiver", &self.receiver)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Observing notifications.
+impl NSNotificationCenter {
+    /// Call the closure whenever a notification with the given name is
+    /// posted by the given object.
+    ///
+    /// If `name` is [`None`], the closure is called for all notifications
+    /// posted by the object, and if `object` is [`None`], it is called for
+    /// notifications posted by any object.
+    ///
+    /// The closure is called synchronously on the thread that posted the
+    /// notification, and must therefore be `Send + Sync`.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use objc2_foundation::{ns_string, NSNotificationCenter};
+    ///
+    /// let center = unsafe { NSNotificationCenter::defaultCenter() };
+    /// let name = ns_string!("MyNotification");
+    ///
+    /// let count = Arc::new(AtomicUsize::new(0));
+    /// let observer = center.add_observer(Some(name), None, {
+    ///     let count = count.clone();
+    ///     move |_| {
+    ///         count.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    /// });
+    ///
+    /// unsafe { center.postNotificationName_object(name, None) };
+    /// assert_eq!(count.load(Ordering::Relaxed), 1);
+    ///
+    /// // Stop observing.
+    /// drop(observer);
+    /// unsafe { center.postNotificationName_object(name, None) };
+    /// assert_eq!(count.load(Ordering::Relaxed), 1);
+    /// ```
+    #[doc(alias = "addObserverForName:object:queue:usingBlock:")]
+    pub fn add_observer<F>(
+        &self,
+        name: Option<&NSNotificationName>,
+        object: Option<&AnyObject>,
+        f: F,
+    ) -> NSNotificationObserver
+    where
+        F: Fn(&NSNotification) + Send + Sync + 'static,
+    {
+        let block = block2::RcBlock::new(move |notification: NonNull<NSNotification>| {
+            // SAFETY: The notification is valid for the duration of the block.
+            f(unsafe { notification.as_ref() });
+        });
+        // SAFETY: The block is `Send + Sync`, since it is called on whichever
+        // thread posts the notification.
+        let token =
+            unsafe { self.addObserverForName_object_queue_usingBlock(name, object, None, &block) };
+        // SAFETY: All protocol objects are objects.
+        let token = unsafe { Retained::cast_unchecked::<AnyObject>(token) };
+
+        NSNotificationObserver {
+            center: self.retain(),
+            token: ObserverToken(token),
+        }
+    }
+
+    /// Receive the notifications with the given name that are posted by the
+    /// given object (or any object if [`None`]) as a stream.
+    ///
+    /// Since notification objects are not thread-safe, the closure is used
+    /// to extract the relevant information from each notification when it
+    /// is posted.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::{ns_string, NSNotificationCenter};
+    ///
+    /// let center = unsafe { NSNotificationCenter::defaultCenter() };
+    /// let name = ns_string!("MyNotification");
+    ///
+    /// let stream = center.notifications(name, None, |notification| {
+    ///     unsafe { notification.name() }.to_string()
+    /// });
+    ///
+    /// unsafe { center.postNotificationName_object(name, None) };
+    /// assert_eq!(stream.try_next().as_deref(), Some("MyNotification"));
+    /// assert_eq!(stream.try_next(), None);
+    /// ```
+    #[cfg(feature = "NSLock")]
+    pub fn notifications<T, F>(
+        &self,
+        name: &NSNotificationName,
+        object: Option<&AnyObject>,
+        f: F,
+    ) -> NSNotificationStream<T>
+    where
+        T: Send + 'static,
+        F: Fn(&NSNotification) -> T + Send + Sync + 'static,
+    {
+        let (sender, receiver) = channel::unbounded();
+        let observer = self.add_observer(Some(name), object, move |notification| {
+            // Nothing to do if the stream was dropped in the meantime.
+            let _ = sender.send(f(notification));
+        });
+        NSNotificationStream {
+            _observer: observer,
+            receiver,
+        }
+    }
+
+    /// Call the closure with the decoded payload whenever the notification
+    /// is posted by the given object (or any object if [`None`]).
+    ///
+    /// See [`add_observer`](Self::add_observer) for details.
+    #[doc(alias = "addObserverForName:object:queue:usingBlock:")]
+    pub fn add_typed_observer<N, F>(
+        &self,
+        object: Option<&AnyObject>,
+        f: F,
+    ) -> NSNotificationObserver
+    where
+        N: NSNotificationPayload,
+        F: Fn(N) + Send + Sync + 'static,
+    {
+        self.add_observer(Some(N::name()), object, move |notification| {
+            f(N::from_notification(notification));
+        })
+    }
+
+    /// Receive the decoded payloads of the notification as a stream.
+    ///
+    /// See [`notifications`](Self::notifications) for details.
+    #[cfg(feature = "NSLock")]
+    pub fn typed_notifications<N>(&self, object: Option<&AnyObject>) -> NSNotificationStream<N>
+    where
+        N: NSNotificationPayload + Send + 'static,
+    {
+        self.notifications(N::name(), object, N::from_notification)
+    }
+}
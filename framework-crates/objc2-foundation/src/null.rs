@@ -0,0 +1,145 @@
+//! Helpers for treating `NSNull` as a missing value.
+//!
+//! Foundation's collections cannot contain `nil`, so `NSNull` is used in its
+//! place, e.g. by `NSJSONSerialization` for JSON `null`. These helpers map
+//! between that and Rust's [`Option`].
+#[cfg(feature = "NSArray")]
+use alloc::vec::Vec;
+
+#[cfg(any(feature = "NSArray", feature = "NSDictionary"))]
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+#[cfg(any(feature = "NSArray", feature = "NSDictionary"))]
+use objc2::Message;
+
+use crate::NSNull;
+
+impl NSNull {
+    /// Whether the object is `NSNull`.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::{NSNull, NSObject};
+    ///
+    /// let null = unsafe { NSNull::null() };
+    /// assert!(NSNull::is_null(&null));
+    /// assert!(!NSNull::is_null(&NSObject::new()));
+    /// ```
+    #[inline]
+    pub fn is_null(obj: &AnyObject) -> bool {
+        obj.downcast_ref::<Self>().is_some()
+    }
+}
+
+/// Helper for checking objects whose static type is not `AnyObject`.
+///
+/// This is useful since collections may contain `NSNull` even if their
+/// generic type says otherwise (e.g. when they were created by
+/// `NSJSONSerialization` and cast to a more specific type).
+#[cfg(any(feature = "NSArray", feature = "NSDictionary"))]
+fn is_null<T: Message>(obj: &T) -> bool {
+    // SAFETY: All `Message` types are objects.
+    let obj: &AnyObject = unsafe { &*(obj as *const T).cast() };
+    NSNull::is_null(obj)
+}
+
+/// `NSNull` handling.
+#[cfg(feature = "NSArray")]
+impl<ObjectType: Message> crate::NSArray<ObjectType> {
+    /// Get the object at the given index, treating `NSNull` as a missing
+    /// value.
+    ///
+    /// Returns [`None`] if the index is out of bounds, or if the object at
+    /// the index is `NSNull`.
+    #[doc(alias = "objectAtIndex:")]
+    pub fn get_nonnull(&self, index: usize) -> Option<Retained<ObjectType>> {
+        if index < self.len() {
+            Some(self.objectAtIndex(index)).filter(|obj| !is_null(&**obj))
+        } else {
+            None
+        }
+    }
+
+    /// Copy the array's objects into a vector, with `NSNull` mapped to
+    /// [`None`].
+    #[doc(alias = "getObjects:")]
+    pub fn to_vec_nonnull(&self) -> Vec<Option<Retained<ObjectType>>> {
+        self.to_vec()
+            .into_iter()
+            .map(|obj| Some(obj).filter(|obj| !is_null(&**obj)))
+            .collect()
+    }
+}
+
+/// `NSNull` handling.
+#[cfg(feature = "NSArray")]
+impl crate::NSMutableArray<AnyObject> {
+    /// Add an object to the end of the array, or `NSNull` if the object is
+    /// [`None`].
+    #[doc(alias = "addObject:")]
+    pub fn push_option(&self, obj: Option<&AnyObject>) {
+        match obj {
+            Some(obj) => self.addObject(obj),
+            None => {
+                let null = unsafe { NSNull::null() };
+                let null: &AnyObject = &null;
+                self.addObject(null);
+            }
+        }
+    }
+}
+
+/// `NSNull` handling.
+#[cfg(feature = "NSDictionary")]
+impl<KeyType: Message, ObjectType: Message> crate::NSDictionary<KeyType, ObjectType> {
+    /// Get the object for the given key, treating `NSNull` as a missing
+    /// value.
+    ///
+    /// Returns [`None`] if the key is not in the dictionary, or if the
+    /// object for the key is `NSNull`.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::{ns_string, NSMutableDictionary, NSNull, NSObject};
+    ///
+    /// let dict = NSMutableDictionary::new();
+    /// dict.insert(ns_string!("a"), &*NSObject::new());
+    /// dict.insert(ns_string!("b"), &*unsafe { NSNull::null() });
+    ///
+    /// assert!(dict.get_nonnull(ns_string!("a")).is_some());
+    /// assert!(dict.get_nonnull(ns_string!("b")).is_none());
+    /// assert!(dict.get_nonnull(ns_string!("c")).is_none());
+    /// ```
+    #[doc(alias = "objectForKey:")]
+    pub fn get_nonnull(&self, key: &KeyType) -> Option<Retained<ObjectType>> {
+        self.objectForKey(key).filter(|obj| !is_null(&**obj))
+    }
+}
+
+/// `NSNull` handling.
+#[cfg(all(feature = "NSDictionary", feature = "NSObject"))]
+impl<KeyType: Message> crate::NSMutableDictionary<KeyType, AnyObject> {
+    /// Insert an object for the given key, or `NSNull` if the object is
+    /// [`None`].
+    ///
+    /// This is useful for building dictionaries that are serialized with
+    /// `NSJSONSerialization`, where `NSNull` becomes JSON `null`.
+    #[doc(alias = "setObject:forKey:")]
+    pub fn insert_option<CopiedKey>(&self, key: &CopiedKey, object: Option<&AnyObject>)
+    where
+        CopiedKey: Message + crate::NSCopying + crate::CopyingHelper<Result = KeyType>,
+    {
+        match object {
+            Some(object) => self.insert(key, object),
+            None => {
+                let null = unsafe { NSNull::null() };
+                let null: &AnyObject = &null;
+                self.insert(key, null);
+            }
+        }
+    }
+}
@@ -20,6 +20,7 @@ use core::cmp::Ordering;
 use core::fmt;
 use core::hash;
 use core::panic::{RefUnwindSafe, UnwindSafe};
+use core::ptr;
 
 use objc2::encode::Encoding;
 use objc2::msg_send_id;
@@ -89,6 +90,11 @@ macro_rules! def_get_fn {
 }
 
 /// Getter methods.
+///
+/// These convert the value like an `as` cast would, e.g. a number containing
+/// `-1` will return `u32::MAX` from [`as_u32`][Self::as_u32]. Use the
+/// [`TryFrom`] implementations on the Rust number types if you need checked
+/// conversions instead.
 impl NSNumber {
     def_get_fn! {
         (as_bool -> bool; boolValue),
@@ -275,3 +281,146 @@ impl fmt::Debug for NSNumber {
         fmt::Debug::fmt(&***self, f)
     }
 }
+
+/// The error returned when converting an [`NSNumber`] to a Rust number type
+/// fails, because the value does not fit in the target type.
+///
+/// See the [`TryFrom`] implementations on the number types.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TryFromNSNumberError {
+    _priv: (),
+}
+
+impl fmt::Display for TryFromNSNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NSNumber value not representable in the target type")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryFromNSNumberError {}
+
+/// The value of a number, as determined by its encoding.
+enum Value {
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+}
+
+impl NSNumber {
+    /// Whether the number was created from a [`bool`].
+    #[allow(dead_code)] // Only used by the serialization modules.
+    pub(crate) fn is_bool(&self) -> bool {
+        // Booleans are stored as `char`s, but `numberWithBool:` returns one
+        // of two shared instances, which we can use to tell them apart from
+        // other numbers.
+        self.encoding() == Encoding::Char
+            && (ptr::eq(self, &*Self::new_bool(true)) || ptr::eq(self, &*Self::new_bool(false)))
+    }
+
+    fn value(&self) -> Value {
+        match self.encoding() {
+            Encoding::Char
+            | Encoding::Short
+            | Encoding::Int
+            | Encoding::Long
+            | Encoding::LongLong => Value::Signed(self.as_i64()),
+            Encoding::UChar
+            | Encoding::UShort
+            | Encoding::UInt
+            | Encoding::ULong
+            | Encoding::ULongLong => Value::Unsigned(self.as_u64()),
+            Encoding::Float | Encoding::Double => Value::Float(self.as_f64()),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Convert a float to an integer, if it has no fractional part and is in
+/// range of `i128` or `u128`.
+fn float_to_int<T: TryFrom<i128> + TryFrom<u128>>(val: f64) -> Option<T> {
+    // 2^127, exactly representable as a float.
+    const I128_LIMIT: f64 = 170141183460469231731687303715884105728.0;
+
+    if !val.is_finite() || val.trunc() != val {
+        return None;
+    }
+    if val < 0.0 {
+        if -I128_LIMIT <= val {
+            T::try_from(val as i128).ok()
+        } else {
+            None
+        }
+    } else if val < I128_LIMIT * 2.0 {
+        T::try_from(val as u128).ok()
+    } else {
+        None
+    }
+}
+
+macro_rules! impl_try_from_int {
+    ($($ty:ty),* $(,)?) => {$(
+        /// Checked conversion, fails if the number's value is out of range,
+        /// or is a float with a fractional part.
+        impl TryFrom<&NSNumber> for $ty {
+            type Error = TryFromNSNumberError;
+
+            fn try_from(number: &NSNumber) -> Result<Self, Self::Error> {
+                let res = match number.value() {
+                    Value::Signed(val) => <$ty>::try_from(val).ok(),
+                    Value::Unsigned(val) => <$ty>::try_from(val).ok(),
+                    Value::Float(val) => float_to_int(val),
+                };
+                res.ok_or(TryFromNSNumberError { _priv: () })
+            }
+        }
+    )*};
+}
+
+impl_try_from_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// Checked conversion, fails if the number is an integer that cannot be
+/// represented exactly as a float.
+impl TryFrom<&NSNumber> for f64 {
+    type Error = TryFromNSNumberError;
+
+    fn try_from(number: &NSNumber) -> Result<Self, Self::Error> {
+        // Compare as `i128`, since e.g. `i64::MAX as f64` would saturate when
+        // cast back to `i64`, and thus incorrectly compare equal.
+        let res = match number.value() {
+            Value::Signed(val) => Some(val as f64).filter(|f| *f as i128 == val as i128),
+            Value::Unsigned(val) => Some(val as f64).filter(|f| *f as i128 == val as i128),
+            Value::Float(val) => Some(val),
+        };
+        res.ok_or(TryFromNSNumberError { _priv: () })
+    }
+}
+
+/// Checked conversion, fails if the number cannot be represented exactly as
+/// a `f32`.
+impl TryFrom<&NSNumber> for f32 {
+    type Error = TryFromNSNumberError;
+
+    fn try_from(number: &NSNumber) -> Result<Self, Self::Error> {
+        let val = f64::try_from(number)?;
+        let res = val as f32;
+        if res as f64 == val || val.is_nan() {
+            Ok(res)
+        } else {
+            Err(TryFromNSNumberError { _priv: () })
+        }
+    }
+}
+
+/// Checked conversion, fails if the number is not `0` or `1`.
+impl TryFrom<&NSNumber> for bool {
+    type Error = TryFromNSNumberError;
+
+    fn try_from(number: &NSNumber) -> Result<Self, Self::Error> {
+        match u8::try_from(number)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(TryFromNSNumberError { _priv: () }),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Running Rust closures on `NSOperationQueue`s.
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use objc2::rc::Retained;
+
+use crate::channel::{self, Receiver};
+use crate::{NSBlockOperation, NSOperation, NSOperationQueue, NSOperationQueuePriority};
+
+/// A Rust closure wrapped in a [`NSBlockOperation`], along with a handle to
+/// its result.
+///
+/// Tasks are created with [`new`] (or [`NSOperationQueue::spawn`]), can be
+/// configured and made to depend on other operations, and are then added to
+/// a queue with [`NSOperationQueue::add_task`].
+///
+/// The result can be retrieved either by blocking ([`wait`]), by polling
+/// ([`try_result`]), or by awaiting the task itself. The result is [`None`]
+/// if the task was cancelled before it started executing.
+///
+/// [`new`]: Self::new
+/// [`wait`]: Self::wait
+/// [`try_result`]: Self::try_result
+///
+///
+/// # Example
+///
+/// Run two closures, where the second one waits for the first one to finish.
+///
+/// ```
+/// use objc2_foundation::{NSOperationQueue, NSOperationTask};
+///
+/// let queue = unsafe { NSOperationQueue::new() };
+///
+/// let first = NSOperationTask::new(|| 1);
+/// let second = NSOperationTask::new(|| 2);
+/// second.add_dependency(first.operation());
+///
+/// queue.add_task(&second);
+/// queue.add_task(&first);
+///
+/// assert_eq!(first.wait(), Some(1));
+/// assert_eq!(second.wait(), Some(2));
+/// ```
+pub struct NSOperationTask<T> {
+    operation: Retained<NSBlockOperation>,
+    receiver: Receiver<T>,
+}
+
+impl<T: Send + 'static> NSOperationTask<T> {
+    /// Wrap the closure in a new operation.
+    ///
+    /// The operation is not started until it is added to a queue.
+    #[doc(alias = "blockOperationWithBlock:")]
+    pub fn new<F>(f: F) -> Self
+    where
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (sender, receiver) = channel::unbounded();
+        // Shared between the operation's block and its completion block, so
+        // that the sender is dropped (and the receiver notified) even if the
+        // operation was cancelled before it executed.
+        let state = Arc::new(Mutex::new(Some((f, sender))));
+
+        let block = block2::RcBlock::new({
+            let state = Arc::clone(&state);
+            move || {
+                let state = state.lock().unwrap_or_else(PoisonError::into_inner).take();
+                if let Some((f, sender)) = state {
+                    // The task may have been dropped without being joined.
+                    let _ = sender.send(f());
+                }
+            }
+        });
+        let completion = block2::RcBlock::new(move || {
+            drop(state.lock().unwrap_or_else(PoisonError::into_inner).take());
+        });
+
+        let operation = unsafe { NSBlockOperation::blockOperationWithBlock(&block) };
+        unsafe { operation.setCompletionBlock(Some(&completion)) };
+
+        Self {
+            operation,
+            receiver,
+        }
+    }
+}
+
+impl<T> NSOperationTask<T> {
+    /// The underlying operation.
+    ///
+    /// This can be used to make other operations depend on this task.
+    pub fn operation(&self) -> &NSBlockOperation {
+        &self.operation
+    }
+
+    /// Make this task wait for the given operation to finish before it
+    /// starts executing.
+    ///
+    /// Dependencies must be added before the task is added to a queue, and
+    /// must not form a cycle (or the operations will never execute).
+    #[doc(alias = "addDependency:")]
+    pub fn add_dependency(&self, operation: &NSOperation) {
+        unsafe { self.operation.addDependency(operation) };
+    }
+
+    /// Set the priority of the task relative to the other operations in the
+    /// queue.
+    #[doc(alias = "setQueuePriority:")]
+    pub fn set_priority(&self, priority: NSOperationQueuePriority) {
+        unsafe { self.operation.setQueuePriority(priority) };
+    }
+
+    /// Set the quality of service that the task is executed with.
+    #[cfg(feature = "NSObjCRuntime")]
+    #[doc(alias = "setQualityOfService:")]
+    pub fn set_quality_of_service(&self, quality_of_service: crate::NSQualityOfService) {
+        unsafe { self.operation.setQualityOfService(quality_of_service) };
+    }
+
+    /// Cancel the task.
+    ///
+    /// This has no effect if the task has already started executing.
+    pub fn cancel(&self) {
+        unsafe { self.operation.cancel() };
+    }
+
+    /// Whether the task was cancelled.
+    #[doc(alias = "isCancelled")]
+    pub fn is_cancelled(&self) -> bool {
+        unsafe { self.operation.isCancelled() }
+    }
+
+    /// Whether the task has finished executing, or was cancelled.
+    #[doc(alias = "isFinished")]
+    pub fn is_finished(&self) -> bool {
+        unsafe { self.operation.isFinished() }
+    }
+
+    /// Get the result of the task if it has finished executing.
+    pub fn try_result(&self) -> Option<T> {
+        self.receiver.try_recv()
+    }
+
+    /// Block the current thread until the task has finished, and return its
+    /// result.
+    ///
+    /// Returns [`None`] if the task was cancelled before it started.
+    ///
+    /// This will block forever if the task has not been added to a queue.
+    #[doc(alias = "waitUntilFinished")]
+    pub fn wait(&self) -> Option<T> {
+        self.receiver.recv()
+    }
+}
+
+impl<T> Future for NSOperationTask<T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl<T> fmt::Debug for NSOperationTask<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSOperationTask")
+            .field("operation", &self.operation)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Running Rust closures.
+impl NSOperationQueue {
+    /// Add a task to the queue.
+    ///
+    /// The task starts executing once all its dependencies have finished.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Objective-C raises an exception if the task has already been added to
+    /// a queue.
+    #[doc(alias = "addOperation:")]
+    pub fn add_task<T>(&self, task: &NSOperationTask<T>) {
+        unsafe { self.addOperation(&task.operation) };
+    }
+
+    /// Run the closure on the queue.
+    ///
+    /// This is a shorthand for [`NSOperationTask::new`] followed by
+    /// [`add_task`](Self::add_task).
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::NSOperationQueue;
+    ///
+    /// let queue = unsafe { NSOperationQueue::new() };
+    /// let task = queue.spawn(|| 40 + 2);
+    /// assert_eq!(task.wait(), Some(42));
+    /// ```
+    pub fn spawn<T, F>(&self, f: F) -> NSOperationTask<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let task = NSOperationTask::new(f);
+        self.add_task(&task);
+        task
+    }
+
+    /// Make the queue report its progress, where each finished operation
+    /// counts as one unit out of the given total.
+    ///
+    /// The returned progress can be used to observe the queue, or be added
+    /// as a child of another progress.
+    ///
+    /// Changing the total while operations are executing may cause the
+    /// progress to move backwards; consider doing it from a barrier block
+    /// instead.
+    #[cfg(feature = "NSProgress")]
+    #[doc(alias = "progress")]
+    pub fn progress_with_total(&self, total_unit_count: i64) -> Retained<crate::NSProgress> {
+        let progress = unsafe { self.progress() };
+        unsafe { progress.setTotalUnitCount(total_unit_count) };
+        progress
+    }
+}
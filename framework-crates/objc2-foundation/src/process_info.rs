@@ -1,7 +1,24 @@
 use core::fmt;
 use core::panic::{RefUnwindSafe, UnwindSafe};
 
-use crate::NSProcessInfo;
+#[cfg(feature = "NSString")]
+use objc2::rc::Retained;
+#[cfg(feature = "NSString")]
+use objc2::runtime::{NSObjectProtocol, ProtocolObject};
+#[cfg(feature = "NSString")]
+use objc2::Message;
+
+#[cfg(feature = "NSString")]
+use crate::{NSActivityOptions, NSString};
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSString"
+))]
+use crate::{NSNotificationCenter, NSNotificationStream};
+use crate::{NSProcessInfo, NSProcessInfoThermalState};
 
 impl UnwindSafe for NSProcessInfo {}
 impl RefUnwindSafe for NSProcessInfo {}
@@ -16,3 +33,165 @@ impl fmt::Debug for NSProcessInfo {
         debug.finish_non_exhaustive()
     }
 }
+
+/// Power and thermal state.
+impl NSProcessInfo {
+    /// The current thermal state of the system.
+    ///
+    /// Long-running computations should reduce their work when this is
+    /// [`Serious`] or higher, to help the system cool down.
+    ///
+    /// [`Serious`]: NSProcessInfoThermalState::Serious
+    #[doc(alias = "thermalState")]
+    pub fn thermal_state(&self) -> NSProcessInfoThermalState {
+        unsafe { self.thermalState() }
+    }
+
+    /// Whether the user has enabled Low Power Mode.
+    ///
+    /// This is always `false` on systems that do not support Low Power Mode.
+    #[doc(alias = "isLowPowerModeEnabled")]
+    pub fn is_low_power_mode_enabled(&self) -> bool {
+        unsafe { self.isLowPowerModeEnabled() }
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSString"
+))]
+impl NSProcessInfo {
+    /// Receive the new thermal state whenever it changes, as a stream.
+    ///
+    /// Notifications are posted on a background thread, so it is fine to
+    /// [`recv`] from any thread (though you should avoid blocking the main
+    /// thread).
+    ///
+    /// [`recv`]: NSNotificationStream::recv
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_foundation::{NSProcessInfo, NSProcessInfoThermalState};
+    ///
+    /// let info = NSProcessInfo::processInfo();
+    /// let changes = info.thermal_state_changes();
+    ///
+    /// while let Some(state) = changes.recv() {
+    ///     if state >= NSProcessInfoThermalState::Serious {
+    ///         println!("system is hot, slowing down");
+    ///     }
+    /// }
+    /// ```
+    #[doc(alias = "NSProcessInfoThermalStateDidChangeNotification")]
+    pub fn thermal_state_changes(&self) -> NSNotificationStream<NSProcessInfoThermalState> {
+        let center = unsafe { NSNotificationCenter::defaultCenter() };
+        let name = unsafe { crate::NSProcessInfoThermalStateDidChangeNotification };
+        center.notifications(name, Some(self), |_| {
+            // The process info is the object of the notification.
+            NSProcessInfo::processInfo().thermal_state()
+        })
+    }
+
+    /// Receive whether Low Power Mode is enabled whenever it changes, as a
+    /// stream.
+    ///
+    /// See [`thermal_state_changes`](Self::thermal_state_changes) for
+    /// details.
+    #[doc(alias = "NSProcessInfoPowerStateDidChangeNotification")]
+    pub fn low_power_mode_changes(&self) -> NSNotificationStream<bool> {
+        let center = unsafe { NSNotificationCenter::defaultCenter() };
+        let name = unsafe { crate::NSProcessInfoPowerStateDidChangeNotification };
+        center.notifications(name, Some(self), |_| {
+            // The process info is the object of the notification.
+            NSProcessInfo::processInfo().is_low_power_mode_enabled()
+        })
+    }
+}
+
+/// An activity started with [`NSProcessInfo::begin_activity`].
+///
+/// The activity is ended when this is dropped.
+#[cfg(feature = "NSString")]
+#[must_use = "the activity is ended when this is dropped"]
+pub struct NSProcessInfoActivity {
+    info: Retained<NSProcessInfo>,
+    token: Retained<ProtocolObject<dyn NSObjectProtocol>>,
+}
+
+// SAFETY: The token is opaque, and is only used to end the activity again,
+// which may be done from any thread.
+#[cfg(feature = "NSString")]
+unsafe impl Send for NSProcessInfoActivity {}
+#[cfg(feature = "NSString")]
+unsafe impl Sync for NSProcessInfoActivity {}
+
+#[cfg(feature = "NSString")]
+impl Drop for NSProcessInfoActivity {
+    #[doc(alias = "endActivity:")]
+    fn drop(&mut self) {
+        unsafe { self.info.endActivity(&self.token) };
+    }
+}
+
+#[cfg(feature = "NSString")]
+impl fmt::Debug for NSProcessInfoActivity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSProcessInfoActivity")
+            .field("token", &self.token)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Activities.
+#[cfg(feature = "NSString")]
+impl NSProcessInfo {
+    /// Tell the system that the process is performing an activity, for as
+    /// long as the returned token is alive.
+    ///
+    /// Depending on the options, this prevents the system from sleeping,
+    /// from terminating the process, or from throttling it with App Nap. The
+    /// reason is used for debugging, e.g. in `pmset -g assertions`.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::{NSActivityOptions, NSProcessInfo};
+    ///
+    /// let info = NSProcessInfo::processInfo();
+    /// let activity = info.begin_activity(NSActivityOptions::UserInitiated, "Exporting data");
+    /// // Perform the long-running computation.
+    /// drop(activity);
+    /// ```
+    #[doc(alias = "beginActivityWithOptions:reason:")]
+    pub fn begin_activity(
+        &self,
+        options: NSActivityOptions,
+        reason: &str,
+    ) -> NSProcessInfoActivity {
+        let reason = NSString::from_str(reason);
+        let token = unsafe { self.beginActivityWithOptions_reason(options, &reason) };
+        NSProcessInfoActivity {
+            info: self.retain(),
+            token,
+        }
+    }
+
+    /// Run the closure as an activity.
+    ///
+    /// See [`begin_activity`](Self::begin_activity) for details.
+    pub fn with_activity<R>(
+        &self,
+        options: NSActivityOptions,
+        reason: &str,
+        f: impl FnOnce() -> R,
+    ) -> R {
+        let _activity = self.begin_activity(options, reason);
+        f()
+    }
+}
@@ -0,0 +1,387 @@
+//! Reporting progress of Rust work with `NSProgress`.
+use objc2::rc::Retained;
+
+use crate::NSProgress;
+
+/// Creation and unit counts.
+impl NSProgress {
+    /// Create a progress object with the given total number of units.
+    ///
+    /// Unlike `progressWithTotalUnitCount:`, this is not implicitly added
+    /// as a child of the current progress. Use [`add_child`] or
+    /// [`new_child`] to build a tree of progress objects.
+    ///
+    /// [`add_child`]: Self::add_child
+    /// [`new_child`]: Self::new_child
+    #[doc(alias = "discreteProgressWithTotalUnitCount:")]
+    pub fn with_total(total_unit_count: i64) -> Retained<Self> {
+        unsafe { Self::discreteProgressWithTotalUnitCount(total_unit_count) }
+    }
+
+    /// Create a child progress object, which makes up the given number of
+    /// units of this progress.
+    ///
+    /// When the child finishes, the pending units are added to the
+    /// completed units of this progress.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::NSProgress;
+    ///
+    /// let parent = NSProgress::with_total(10);
+    /// let download = parent.new_child(1000, 8);
+    /// let unpack = parent.new_child(1, 2);
+    ///
+    /// download.set_completed(500);
+    /// assert!((parent.fraction_completed() - 0.4).abs() < f64::EPSILON);
+    ///
+    /// download.set_completed(1000);
+    /// unpack.set_completed(1);
+    /// assert_eq!(parent.completed(), 10);
+    /// ```
+    #[doc(alias = "progressWithTotalUnitCount:parent:pendingUnitCount:")]
+    pub fn new_child(&self, total_unit_count: i64, pending_unit_count: i64) -> Retained<Self> {
+        unsafe {
+            Self::progressWithTotalUnitCount_parent_pendingUnitCount(
+                total_unit_count,
+                self,
+                pending_unit_count,
+            )
+        }
+    }
+
+    /// Add an existing progress object as a child of this one, making up
+    /// the given number of units of this progress.
+    #[doc(alias = "addChild:withPendingUnitCount:")]
+    pub fn add_child(&self, child: &NSProgress, pending_unit_count: i64) {
+        unsafe { self.addChild_withPendingUnitCount(child, pending_unit_count) };
+    }
+
+    /// Make this the current progress while running the closure.
+    ///
+    /// Progress objects created by Cocoa APIs inside the closure (for
+    /// example with `progressWithTotalUnitCount:`) are implicitly added as
+    /// children of this progress, making up the given number of units.
+    #[doc(alias = "becomeCurrentWithPendingUnitCount:")]
+    #[doc(alias = "resignCurrent")]
+    pub fn with_current<R>(&self, pending_unit_count: i64, f: impl FnOnce() -> R) -> R {
+        struct ResignGuard<'a>(&'a NSProgress);
+
+        impl Drop for ResignGuard<'_> {
+            fn drop(&mut self) {
+                unsafe { self.0.resignCurrent() };
+            }
+        }
+
+        unsafe { self.becomeCurrentWithPendingUnitCount(pending_unit_count) };
+        let _guard = ResignGuard(self);
+        f()
+    }
+
+    /// The total number of units of work.
+    ///
+    /// This is negative if the progress is indeterminate.
+    #[doc(alias = "totalUnitCount")]
+    pub fn total(&self) -> i64 {
+        unsafe { self.totalUnitCount() }
+    }
+
+    /// Set the total number of units of work.
+    #[doc(alias = "setTotalUnitCount:")]
+    pub fn set_total(&self, total_unit_count: i64) {
+        unsafe { self.setTotalUnitCount(total_unit_count) };
+    }
+
+    /// The number of units of work that have been completed.
+    #[doc(alias = "completedUnitCount")]
+    pub fn completed(&self) -> i64 {
+        unsafe { self.completedUnitCount() }
+    }
+
+    /// Set the number of units of work that have been completed.
+    #[doc(alias = "setCompletedUnitCount:")]
+    pub fn set_completed(&self, completed_unit_count: i64) {
+        unsafe { self.setCompletedUnitCount(completed_unit_count) };
+    }
+
+    /// Add to the number of units of work that have been completed.
+    ///
+    /// Note that this is not atomic, so the progress should only be updated
+    /// from one thread at a time.
+    pub fn increment(&self, unit_count: i64) {
+        self.set_completed(self.completed().saturating_add(unit_count));
+    }
+
+    /// The fraction of the work that has been completed, including the work
+    /// of any children.
+    #[doc(alias = "fractionCompleted")]
+    pub fn fraction_completed(&self) -> f64 {
+        unsafe { self.fractionCompleted() }
+    }
+
+    /// Call the given closure when the progress is cancelled, e.g. by the
+    /// user from a progress UI.
+    ///
+    /// The closure may be called on any thread, and is called immediately if
+    /// the progress was already cancelled.
+    #[cfg(feature = "block2")]
+    #[doc(alias = "setCancellationHandler:")]
+    pub fn set_cancellation_handler(&self, handler: impl Fn() + Send + Sync + 'static) {
+        let block = block2::RcBlock::new(handler);
+        unsafe { self.setCancellationHandler(Some(&block)) };
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    feature = "NSDictionary",
+    feature = "NSKeyValueObserving",
+    feature = "NSLock",
+    feature = "NSNull",
+    feature = "NSString",
+    feature = "NSValue"
+))]
+mod changes {
+    use core::fmt;
+    use core::future::Future;
+    use core::marker::PhantomData;
+    use core::task::{Context, Poll};
+
+    use crate::channel::{self, Receiver};
+    use crate::key_value_observing::KeyValueObservation;
+    use crate::{ns_string, NSKeyValueObservingOptions, NSNumber, NSProgress};
+
+    /// A stream of changes to [`NSProgress::fraction_completed`].
+    ///
+    /// Changes can be retrieved either by blocking ([`recv`]), by polling
+    /// ([`try_next`]), or asynchronously ([`next`] or [`poll_next`], the
+    /// latter of which can be used to implement `futures::Stream`).
+    ///
+    /// The observer is removed when this is dropped.
+    ///
+    /// See [`NSProgress::fraction_completed_changes`].
+    ///
+    /// [`recv`]: Self::recv
+    /// [`try_next`]: Self::try_next
+    /// [`next`]: Self::next
+    /// [`poll_next`]: Self::poll_next
+    pub struct NSProgressChanges {
+        receiver: Receiver<f64>,
+        observation: KeyValueObservation,
+        // The observer is not thread-safe.
+        _marker: PhantomData<*const ()>,
+    }
+
+    impl NSProgress {
+        /// Observe changes to the fraction of the work that has been
+        /// completed.
+        ///
+        /// The current fraction is received first. Changes are observed with
+        /// Key-Value Observing, which is notified synchronously on the
+        /// thread that updated the progress (which is often a background
+        /// thread), so UI should be updated by forwarding the values to the
+        /// main thread.
+        ///
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use objc2_foundation::NSProgress;
+        ///
+        /// let progress = NSProgress::with_total(4);
+        /// let changes = progress.fraction_completed_changes();
+        /// assert_eq!(changes.try_next(), Some(0.0));
+        ///
+        /// progress.set_completed(1);
+        /// assert_eq!(changes.try_next(), Some(0.25));
+        /// ```
+        #[doc(alias = "addObserver:forKeyPath:options:context:")]
+        #[doc(alias = "fractionCompleted")]
+        pub fn fraction_completed_changes(&self) -> NSProgressChanges {
+            let (sender, receiver) = channel::unbounded();
+            let observation = KeyValueObservation::new(
+                self,
+                ns_string!("fractionCompleted"),
+                NSKeyValueObservingOptions::Initial | NSKeyValueObservingOptions::New,
+                move |new| {
+                    if let Some(number) = new.and_then(|new| new.downcast_ref::<NSNumber>()) {
+                        let _ = sender.send(number.as_f64());
+                    }
+                },
+            );
+
+            NSProgressChanges {
+                receiver,
+                observation,
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl NSProgressChanges {
+        /// Get the next change if one has already been received.
+        pub fn try_next(&self) -> Option<f64> {
+            self.receiver.try_recv()
+        }
+
+        /// Block the current thread until the next change is received.
+        ///
+        /// Beware that changes are delivered on the thread that made them, so
+        /// this will deadlock if that is the current thread.
+        pub fn recv(&self) -> Option<f64> {
+            self.receiver.recv()
+        }
+
+        /// Poll for the next change.
+        pub fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<f64>> {
+            self.receiver.poll_recv(cx)
+        }
+
+        /// Wait asynchronously for the next change.
+        pub fn next(&self) -> impl Future<Output = Option<f64>> + '_ {
+            core::future::poll_fn(|cx| self.poll_next(cx))
+        }
+    }
+
+    impl fmt::Debug for NSProgressChanges {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("NSProgressChanges")
+                .field("key_path", self.observation.key_path())
+                .field("receiver", &self.receiver)
+                .finish_non_exhaustive()
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    feature = "NSDictionary",
+    feature = "NSKeyValueObserving",
+    feature = "NSLock",
+    feature = "NSNull",
+    feature = "NSString",
+    feature = "NSValue"
+))]
+pub use self::changes::NSProgressChanges;
+
+#[cfg(all(feature = "block2", feature = "NSURL"))]
+mod publishing {
+    use core::fmt;
+    use core::ptr::{self, NonNull};
+
+    use block2::RcBlock;
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use objc2::Message;
+
+    use crate::{NSProgress, NSProgressUnpublishingHandler, NSURL};
+
+    /// A published progress object, see [`NSProgress::published`].
+    ///
+    /// The progress is unpublished when this is dropped.
+    pub struct NSProgressPublication {
+        progress: Retained<NSProgress>,
+    }
+
+    /// A subscription to progress published for a file, see
+    /// [`NSProgress::subscribe`].
+    ///
+    /// The subscription is removed when this is dropped.
+    pub struct NSProgressSubscription {
+        subscriber: Retained<AnyObject>,
+    }
+
+    /// Publishing and subscribing.
+    ///
+    /// These are only available on macOS.
+    impl NSProgress {
+        /// Publish the progress, making it visible to other processes, such
+        /// as Finder showing a progress bar on the file being downloaded.
+        ///
+        /// The kind and file URL of the progress (`setKind:` and
+        /// `setFileURL:`) should be set before publishing it.
+        #[doc(alias = "publish")]
+        #[doc(alias = "unpublish")]
+        #[must_use = "the progress is unpublished when the publication is dropped"]
+        pub fn published(&self) -> NSProgressPublication {
+            unsafe { self.publish() };
+            NSProgressPublication {
+                progress: self.retain(),
+            }
+        }
+
+        /// Subscribe to progress published for the given file URL (or for
+        /// files within it, if it is a directory).
+        ///
+        /// The closure is called with each published progress, and may
+        /// return a block that is called when that progress is unpublished.
+        #[doc(alias = "addSubscriberForFileURL:withPublishingHandler:")]
+        #[doc(alias = "removeSubscriber:")]
+        #[must_use = "the subscription is removed when it is dropped"]
+        pub fn subscribe(
+            url: &NSURL,
+            on_publish: impl Fn(&NSProgress) -> Option<RcBlock<dyn Fn()>> + Send + Sync + 'static,
+        ) -> NSProgressSubscription {
+            let handler = RcBlock::new(
+                move |progress: NonNull<NSProgress>| -> NSProgressUnpublishingHandler {
+                    // SAFETY: The progress is valid for the duration of the call.
+                    let progress = unsafe { progress.as_ref() };
+                    match on_publish(progress) {
+                        Some(block) => {
+                            // SAFETY: Blocks are Objective-C objects, and
+                            // the handler returns an autoreleased block.
+                            let block = RcBlock::into_raw(block).cast::<AnyObject>();
+                            let block = unsafe { Retained::from_raw(block) }.unwrap();
+                            Retained::autorelease_ptr(block).cast()
+                        }
+                        None => ptr::null_mut(),
+                    }
+                },
+            );
+            let subscriber = unsafe {
+                Self::addSubscriberForFileURL_withPublishingHandler(url, RcBlock::as_ptr(&handler))
+            };
+            NSProgressSubscription { subscriber }
+        }
+    }
+
+    impl NSProgressPublication {
+        /// The published progress.
+        pub fn progress(&self) -> &NSProgress {
+            &self.progress
+        }
+    }
+
+    impl Drop for NSProgressPublication {
+        fn drop(&mut self) {
+            unsafe { self.progress.unpublish() };
+        }
+    }
+
+    impl fmt::Debug for NSProgressPublication {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("NSProgressPublication")
+                .field("progress", &self.progress)
+                .finish()
+        }
+    }
+
+    impl Drop for NSProgressSubscription {
+        fn drop(&mut self) {
+            unsafe { NSProgress::removeSubscriber(&self.subscriber) };
+        }
+    }
+
+    impl fmt::Debug for NSProgressSubscription {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("NSProgressSubscription")
+                .field("subscriber", &self.subscriber)
+                .finish()
+        }
+    }
+}
+
+#[cfg(all(feature = "block2", feature = "NSURL"))]
+pub use self::publishing::{NSProgressPublication, NSProgressSubscription};
@@ -0,0 +1,689 @@
+//! Converting between [`serde`] data structures and property list objects.
+//!
+//! Property list objects are the Foundation objects that can be stored in
+//! `NSUserDefaults`, written with `NSPropertyListSerialization`, and so on.
+//!
+//! Rust values are mapped as follows:
+//! - Booleans, integers and floats become `NSNumber`.
+//! - Strings and characters become `NSString`.
+//! - Byte buffers (e.g. `serde_bytes`) become `NSData`.
+//! - Sequences, tuples and tuple structs become `NSArray`.
+//! - Maps and structs become `NSDictionary` with `NSString` keys.
+//! - Unit variants become the name of the variant, while other enum
+//!   variants become a dictionary with the name of the variant as the only
+//!   key, following serde's default externally tagged representation.
+//!
+//! Property lists cannot contain `nil`, so `None` and `()` are omitted from
+//! dictionaries, and cannot be stored at the top level or in arrays.
+//!
+//! This mirrors `objc2_core_foundation::property_list`, which works with
+//! CoreFoundation types instead and can also parse and serialize property
+//! lists. See that module for why both exist.
+//!
+//!
+//! # Example
+//!
+//! ```
+//! use objc2_foundation::property_list;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Settings {
+//!     volume: f64,
+//!     recent_files: Vec<String>,
+//!     theme: Option<String>,
+//! }
+//!
+//! let settings = Settings {
+//!     volume: 0.5,
+//!     recent_files: vec!["a.txt".into()],
+//!     theme: None,
+//! };
+//!
+//! let object = property_list::to_object(&settings).unwrap();
+//! let decoded: Settings = property_list::from_object(&object).unwrap();
+//! assert_eq!(settings, decoded);
+//! ```
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use objc2::encode::Encoding;
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2::Message;
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize};
+
+use crate::{
+    NSArray, NSData, NSDictionary, NSMutableArray, NSMutableDictionary, NSNumber, NSString,
+};
+
+/// An error that occurred while converting to or from a property list.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Error {
+    message: String,
+}
+
+impl Error {
+    fn new(message: impl fmt::Display) -> Self {
+        Self {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+// This is `std::error::Error` when `serde`'s `std` feature is enabled.
+impl serde::de::StdError for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::new(msg)
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::new(msg)
+    }
+}
+
+/// Convert a value to a property list object.
+///
+///
+/// # Errors
+///
+/// Returns an error if the value (or one of its elements) is `None` or `()`
+/// outside of a struct or map, if a map has keys that are not strings, or if
+/// the value's `Serialize` implementation fails.
+pub fn to_object<T: Serialize + ?Sized>(value: &T) -> Result<Retained<AnyObject>, Error> {
+    value
+        .serialize(Serializer)?
+        .ok_or_else(|| Error::new("property lists cannot contain null values"))
+}
+
+/// Convert a property list object to a value.
+///
+///
+/// # Errors
+///
+/// Returns an error if the object does not match the structure of the value,
+/// or if it contains objects that are not supported (such as `NSDate`).
+pub fn from_object<T: DeserializeOwned>(object: &AnyObject) -> Result<T, Error> {
+    T::deserialize(Deserializer { object })
+}
+
+fn erase<T: Message>(object: Retained<T>) -> Retained<AnyObject> {
+    // SAFETY: All `Message` types are objects.
+    unsafe { Retained::cast_unchecked(object) }
+}
+
+fn required(object: Option<Retained<AnyObject>>) -> Result<Retained<AnyObject>, Error> {
+    object.ok_or_else(|| {
+        Error::new("property list arrays and enum variants cannot contain null values")
+    })
+}
+
+/// Serializes to a property list object, or [`None`] if the value is `None`
+/// or `()` (such that it can be omitted from dictionaries).
+struct Serializer;
+
+type Output = Option<Retained<AnyObject>>;
+
+impl ser::Serializer for Serializer {
+    type Ok = Output;
+    type Error = Error;
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeVariant<SerializeArray>;
+    type SerializeMap = SerializeDictionary;
+    type SerializeStruct = SerializeDictionary;
+    type SerializeStructVariant = SerializeVariant<SerializeDictionary>;
+
+    fn serialize_bool(self, v: bool) -> Result<Output, Error> {
+        Ok(Some(erase(NSNumber::new_bool(v))))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Output, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Output, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Output, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Output, Error> {
+        Ok(Some(erase(NSNumber::new_i64(v))))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Output, Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Output, Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Output, Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Output, Error> {
+        Ok(Some(erase(NSNumber::new_u64(v))))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Output, Error> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Output, Error> {
+        Ok(Some(erase(NSNumber::new_f64(v))))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Output, Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Output, Error> {
+        Ok(Some(erase(NSString::from_str(v))))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Output, Error> {
+        Ok(Some(erase(NSData::with_bytes(v))))
+    }
+
+    fn serialize_none(self) -> Result<Output, Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Output, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Output, Error> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Output, Error> {
+        Ok(None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Output, Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Output, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Output, Error> {
+        wrap_variant(variant, value.serialize(Serializer)?)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<SerializeArray, Error> {
+        Ok(SerializeArray(NSMutableArray::new()))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeArray>, Error> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeDictionary, Error> {
+        Ok(SerializeDictionary {
+            dictionary: NSMutableDictionary::new(),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeDictionary, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeDictionary>, Error> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+struct SerializeArray(Retained<NSMutableArray<AnyObject>>);
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Output;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let value = required(value.serialize(Serializer)?)?;
+        self.0.addObject(&value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Output, Error> {
+        Ok(Some(erase(self.0)))
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Output;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Output, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Output;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Output, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+struct SerializeDictionary {
+    dictionary: Retained<NSMutableDictionary<NSString, AnyObject>>,
+    key: Option<Retained<NSString>>,
+}
+
+impl SerializeDictionary {
+    fn insert(&self, key: &NSString, value: Output) {
+        // `None` values are omitted.
+        if let Some(value) = value {
+            self.dictionary.insert(key, &*value);
+        }
+    }
+}
+
+impl ser::SerializeMap for SerializeDictionary {
+    type Ok = Output;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        let key = key
+            .serialize(Serializer)?
+            .and_then(|key| key.downcast::<NSString>().ok())
+            .ok_or_else(|| Error::new("property list dictionary keys must be strings"))?;
+        self.key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+        self.insert(&key, value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Output, Error> {
+        Ok(Some(erase(self.dictionary)))
+    }
+}
+
+impl ser::SerializeStruct for SerializeDictionary {
+    type Ok = Output;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.insert(&NSString::from_str(key), value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Output, Error> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+/// Wraps the serialized value in a dictionary with the variant as the key.
+struct SerializeVariant<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+fn wrap_variant(variant: &str, value: Output) -> Result<Output, Error> {
+    let dictionary = NSMutableDictionary::<NSString, AnyObject>::new();
+    dictionary.insert(&*NSString::from_str(variant), &*required(value)?);
+    Ok(Some(erase(dictionary)))
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeArray> {
+    type Ok = Output;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Output, Error> {
+        wrap_variant(self.variant, ser::SerializeSeq::end(self.inner)?)
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeDictionary> {
+    type Ok = Output;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Output, Error> {
+        wrap_variant(self.variant, ser::SerializeMap::end(self.inner)?)
+    }
+}
+
+/// Deserializes from a property list object.
+struct Deserializer<'a> {
+    object: &'a AnyObject,
+}
+
+impl Deserializer<'_> {
+    fn unsupported(&self) -> Error {
+        Error::new(format_args!(
+            "unsupported property list object of class {:?}",
+            self.object.class().name()
+        ))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if let Some(string) = self.object.downcast_ref::<NSString>() {
+            visitor.visit_string(string.to_string())
+        } else if let Some(number) = self.object.downcast_ref::<NSNumber>() {
+            match number.encoding() {
+                _ if number.is_bool() => visitor.visit_bool(number.as_bool()),
+                Encoding::Float | Encoding::Double => visitor.visit_f64(number.as_f64()),
+                Encoding::UChar
+                | Encoding::UShort
+                | Encoding::UInt
+                | Encoding::ULong
+                | Encoding::ULongLong => visitor.visit_u64(number.as_u64()),
+                _ => visitor.visit_i64(number.as_i64()),
+            }
+        } else if let Some(data) = self.object.downcast_ref::<NSData>() {
+            visitor.visit_byte_buf(data.to_vec())
+        } else if let Some(array) = self.object.downcast_ref::<NSArray>() {
+            visitor.visit_seq(ArrayAccess {
+                objects: array.to_vec().into_iter(),
+            })
+        } else if let Some(dictionary) = self.object.downcast_ref::<NSDictionary>() {
+            let (keys, objects) = dictionary.to_vecs();
+            visitor.visit_map(DictionaryAccess {
+                entries: keys
+                    .into_iter()
+                    .zip(objects)
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+                value: None,
+            })
+        } else {
+            Err(self.unsupported())
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.object.downcast_ref::<NSNumber>() {
+            Some(number) => visitor.visit_bool(number.as_bool()),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        // Allow deserializing data as `Vec<u8>`.
+        match self.object.downcast_ref::<NSData>() {
+            Some(data) => visitor.visit_seq(data.to_vec().into_deserializer()),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        // Missing values are handled by the absence of dictionary keys.
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if let Some(string) = self.object.downcast_ref::<NSString>() {
+            return visitor.visit_enum(string.to_string().into_deserializer());
+        }
+        if let Some(dictionary) = self.object.downcast_ref::<NSDictionary>() {
+            let (keys, objects) = dictionary.to_vecs();
+            if let ([key], [object]) = (&*keys, &*objects) {
+                if let Some(variant) = key.downcast_ref::<NSString>() {
+                    return visitor.visit_enum(EnumAccess {
+                        variant: variant.to_string(),
+                        object,
+                    });
+                }
+            }
+        }
+        Err(Error::new(
+            "expected a string or a dictionary with a single key for an enum",
+        ))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf tuple_struct map struct identifier
+    }
+}
+
+struct ArrayAccess {
+    objects: alloc::vec::IntoIter<Retained<AnyObject>>,
+}
+
+impl<'de> de::SeqAccess<'de> for ArrayAccess {
+    type Error = Error;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self.objects.next() {
+            Some(object) => seed.deserialize(Deserializer { object: &object }).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.objects.len())
+    }
+}
+
+type Entry = (Retained<AnyObject>, Retained<AnyObject>);
+
+struct DictionaryAccess {
+    entries: alloc::vec::IntoIter<Entry>,
+    value: Option<Retained<AnyObject>>,
+}
+
+impl<'de> de::MapAccess<'de> for DictionaryAccess {
+    type Error = Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(Deserializer { object: &key }).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(Deserializer { object: &value })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct EnumAccess<'a> {
+    variant: String,
+    object: &'a AnyObject,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for EnumAccess<'a> {
+    type Error = Error;
+    type Variant = Deserializer<'a>;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Deserializer<'a>), Error> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((
+            variant,
+            Deserializer {
+                object: self.object,
+            },
+        ))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Deserializer<'_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+}
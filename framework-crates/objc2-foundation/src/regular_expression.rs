@@ -0,0 +1,376 @@
+//! Utilities for matching `NSRegularExpression` against Rust strings.
+//!
+//! `NSRegularExpression` reports matches as ranges of UTF-16 code units in
+//! an `NSString`. The helpers in this module convert those to byte ranges in
+//! the original `&str`, so that matches can be sliced out directly.
+//!
+//!
+//! # Example
+//!
+//! ```
+//! use objc2_foundation::NSRegularExpression;
+//!
+//! let regex = NSRegularExpression::from_pattern(r"(\w+)@(\w+)\.com").unwrap();
+//! let haystack = "Contact: ærlig@example.com, bob@test.com";
+//!
+//! let users: Vec<&str> = regex
+//!     .captures_iter(haystack)
+//!     .map(|captures| captures.get(1).unwrap().as_str())
+//!     .collect();
+//! assert_eq!(users, ["ærlig", "bob"]);
+//!
+//! let replaced = regex.replace_all(haystack, |captures| {
+//!     format!("{} at {}", &captures[1], &captures[2])
+//! });
+//! assert_eq!(replaced, "Contact: ærlig at example, bob at test");
+//! ```
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::{Index, Range};
+
+use objc2::rc::Retained;
+
+use crate::{
+    NSArray, NSError, NSMatchingOptions, NSRange, NSRegularExpression, NSRegularExpressionOptions,
+    NSString, NSTextCheckingResult,
+};
+
+/// The location of ranges that did not participate in a match.
+const NOT_FOUND: usize = isize::MAX as usize;
+
+/// Converts UTF-16 offsets in a string to UTF-8 byte offsets.
+#[derive(Debug)]
+struct Offsets {
+    /// The byte offset of each UTF-16 code unit, plus one for the end of the
+    /// string. Empty if the string is ASCII, in which case the offsets are
+    /// the same.
+    utf8: Vec<usize>,
+}
+
+impl Offsets {
+    fn new(haystack: &str) -> Self {
+        let mut utf8 = Vec::new();
+        if !haystack.is_ascii() {
+            utf8.reserve(haystack.len() + 1);
+            for (i, c) in haystack.char_indices() {
+                for _ in 0..c.len_utf16() {
+                    utf8.push(i);
+                }
+            }
+            utf8.push(haystack.len());
+        }
+        Self { utf8 }
+    }
+
+    fn byte_range(&self, range: NSRange) -> Option<Range<usize>> {
+        if range.location == NOT_FOUND {
+            return None;
+        }
+        let range = Range::from(range);
+        if self.utf8.is_empty() {
+            Some(range)
+        } else {
+            Some(self.utf8[range.start]..self.utf8[range.end])
+        }
+    }
+}
+
+impl NSRegularExpression {
+    /// Compile a regular expression with the default options.
+    ///
+    /// See [`with_options`][Self::with_options] for details.
+    pub fn from_pattern(pattern: &str) -> Result<Retained<Self>, Retained<NSError>> {
+        Self::with_options(pattern, NSRegularExpressionOptions::empty())
+    }
+
+    /// Compile a regular expression with the given options.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is not a valid ICU regular
+    /// expression.
+    #[doc(alias = "regularExpressionWithPattern:options:error:")]
+    pub fn with_options(
+        pattern: &str,
+        options: NSRegularExpressionOptions,
+    ) -> Result<Retained<Self>, Retained<NSError>> {
+        let pattern = NSString::from_str(pattern);
+        unsafe { Self::regularExpressionWithPattern_options_error(&pattern, options) }
+    }
+
+    /// Whether the regular expression matches anywhere in the string.
+    pub fn is_match(&self, haystack: &str) -> bool {
+        let string = NSString::from_str(haystack);
+        let range = NSRange::new(0, string.len_utf16());
+        let found = unsafe {
+            self.rangeOfFirstMatchInString_options_range(&string, NSMatchingOptions::empty(), range)
+        };
+        found.location != NOT_FOUND
+    }
+
+    /// Find the first match in the string.
+    pub fn find<'s>(&self, haystack: &'s str) -> Option<Match<'s>> {
+        self.captures(haystack).map(|captures| captures.get_match())
+    }
+
+    /// Iterate over the non-overlapping matches in the string.
+    pub fn find_iter<'s>(&self, haystack: &'s str) -> impl Iterator<Item = Match<'s>> + 's {
+        self.captures_iter(haystack)
+            .map(|captures| captures.get_match())
+    }
+
+    /// Find the first match in the string, along with its capture groups.
+    #[doc(alias = "firstMatchInString:options:range:")]
+    pub fn captures<'s>(&self, haystack: &'s str) -> Option<Captures<'s>> {
+        let string = NSString::from_str(haystack);
+        let range = NSRange::new(0, string.len_utf16());
+        let result = unsafe {
+            self.firstMatchInString_options_range(&string, NSMatchingOptions::empty(), range)
+        }?;
+        Some(Captures::new(
+            haystack,
+            &Rc::new(Offsets::new(haystack)),
+            result,
+        ))
+    }
+
+    /// Iterate over the non-overlapping matches in the string, along with
+    /// their capture groups.
+    #[doc(alias = "matchesInString:options:range:")]
+    pub fn captures_iter<'s>(&self, haystack: &'s str) -> CapturesIter<'s> {
+        let string = NSString::from_str(haystack);
+        let range = NSRange::new(0, string.len_utf16());
+        let results = unsafe {
+            self.matchesInString_options_range(&string, NSMatchingOptions::empty(), range)
+        };
+        CapturesIter {
+            haystack,
+            offsets: Rc::new(Offsets::new(haystack)),
+            results,
+            index: 0,
+        }
+    }
+
+    /// Replace every match in the string with the result of calling the
+    /// closure with the match's capture groups.
+    ///
+    /// Unlike `stringByReplacingMatchesInString:options:range:withTemplate:`,
+    /// the replacement is inserted as-is, and `$` and `\` have no special
+    /// meaning.
+    pub fn replace_all<F, R>(&self, haystack: &str, mut replacer: F) -> String
+    where
+        F: FnMut(&Captures<'_>) -> R,
+        R: AsRef<str>,
+    {
+        let mut replaced = String::with_capacity(haystack.len());
+        let mut last = 0;
+        for captures in self.captures_iter(haystack) {
+            let range = captures.get_match().range();
+            replaced.push_str(&haystack[last..range.start]);
+            replaced.push_str(replacer(&captures).as_ref());
+            last = range.end;
+        }
+        replaced.push_str(&haystack[last..]);
+        replaced
+    }
+}
+
+/// A single match of a regular expression or capture group.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Match<'s> {
+    haystack: &'s str,
+    start: usize,
+    end: usize,
+}
+
+impl<'s> Match<'s> {
+    /// The byte offset of the start of the match.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The byte offset of the end of the match.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// The byte range of the match in the original string.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// The matched text.
+    pub fn as_str(&self) -> &'s str {
+        &self.haystack[self.range()]
+    }
+
+    /// Whether the match is empty.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// The length of the match in bytes.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+impl fmt::Debug for Match<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Match")
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .field("string", &self.as_str())
+            .finish()
+    }
+}
+
+/// The capture groups of a single match of a regular expression.
+///
+/// Group `0` is the entire match.
+pub struct Captures<'s> {
+    haystack: &'s str,
+    offsets: Rc<Offsets>,
+    result: Retained<NSTextCheckingResult>,
+    groups: Vec<Option<Range<usize>>>,
+}
+
+impl<'s> Captures<'s> {
+    fn new(
+        haystack: &'s str,
+        offsets: &Rc<Offsets>,
+        result: Retained<NSTextCheckingResult>,
+    ) -> Self {
+        let groups = (0..unsafe { result.numberOfRanges() })
+            .map(|i| offsets.byte_range(unsafe { result.rangeAtIndex(i) }))
+            .collect();
+        Self {
+            haystack,
+            offsets: offsets.clone(),
+            result,
+            groups,
+        }
+    }
+
+    fn to_match(&self, range: &Range<usize>) -> Match<'s> {
+        Match {
+            haystack: self.haystack,
+            start: range.start,
+            end: range.end,
+        }
+    }
+
+    /// The match of the entire regular expression.
+    pub fn get_match(&self) -> Match<'s> {
+        self.get(0).expect("the entire match must have a range")
+    }
+
+    /// The match of the capture group with the given index.
+    ///
+    /// Returns `None` if the index is out of bounds, or if the group did not
+    /// participate in the match.
+    #[doc(alias = "rangeAtIndex:")]
+    pub fn get(&self, index: usize) -> Option<Match<'s>> {
+        let range = self.groups.get(index)?.as_ref()?;
+        Some(self.to_match(range))
+    }
+
+    /// The match of the capture group with the given name.
+    ///
+    /// Returns `None` if there is no such group, or if the group did not
+    /// participate in the match.
+    #[doc(alias = "rangeWithName:")]
+    pub fn name(&self, name: &str) -> Option<Match<'s>> {
+        // Looking up a name that is not a valid group name throws an
+        // exception, so check that first.
+        let mut bytes = name.bytes();
+        let starts_with_letter = bytes.next().is_some_and(|b| b.is_ascii_alphabetic());
+        if !starts_with_letter || !bytes.all(|b| b.is_ascii_alphanumeric()) {
+            return None;
+        }
+        let range = unsafe { self.result.rangeWithName(&NSString::from_str(name)) };
+        let range = self.offsets.byte_range(range)?;
+        Some(self.to_match(&range))
+    }
+
+    /// The number of capture groups, including the entire match.
+    #[allow(clippy::len_without_is_empty)] // Never empty.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Iterate over the capture groups, including the entire match.
+    ///
+    /// Groups that did not participate in the match are `None`.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Option<Match<'s>>> + '_ {
+        self.groups
+            .iter()
+            .map(|range| range.as_ref().map(|range| self.to_match(range)))
+    }
+
+    /// The underlying text checking result.
+    pub fn result(&self) -> &NSTextCheckingResult {
+        &self.result
+    }
+}
+
+impl Index<usize> for Captures<'_> {
+    type Output = str;
+
+    /// Get the text of the given capture group.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no group with the given index, or if the group did
+    /// not participate in the match.
+    fn index(&self, index: usize) -> &str {
+        match self.get(index) {
+            Some(m) => m.as_str(),
+            None => panic!("no group at index {index}"),
+        }
+    }
+}
+
+impl fmt::Debug for Captures<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the matches of a regular expression in a string.
+///
+/// Created by [`NSRegularExpression::captures_iter`].
+#[derive(Debug)]
+pub struct CapturesIter<'s> {
+    haystack: &'s str,
+    offsets: Rc<Offsets>,
+    results: Retained<NSArray<NSTextCheckingResult>>,
+    index: usize,
+}
+
+impl<'s> Iterator for CapturesIter<'s> {
+    type Item = Captures<'s>;
+
+    fn next(&mut self) -> Option<Captures<'s>> {
+        if self.index >= self.results.len() {
+            return None;
+        }
+        let result = self.results.objectAtIndex(self.index);
+        self.index += 1;
+        Some(Captures::new(self.haystack, &self.offsets, result))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.results.len() - self.index;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for CapturesIter<'_> {}
+
+impl FusedIterator for CapturesIter<'_> {}
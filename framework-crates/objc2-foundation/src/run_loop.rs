@@ -0,0 +1,322 @@
+//! Running the current thread's run loop.
+//!
+//! This is useful in tests and command-line tools that need Cocoa callbacks
+//! (such as timers, notifications or completion handlers scheduled on the run
+//! loop) to be delivered, but that don't use `NSApplication`.
+use core::ptr;
+use core::time::Duration;
+
+use objc2::rc::Retained;
+
+use crate::{NSDate, NSDefaultRunLoopMode, NSRunLoop, NSRunLoopMode};
+
+/// Running the run loop.
+///
+/// `NSRunLoop` is not thread-safe, so these methods may only be used on the
+/// run loop of the current thread, and will panic otherwise.
+impl NSRunLoop {
+    /// The run loop of the current thread.
+    ///
+    /// A run loop is created for the thread if it didn't have one already.
+    #[doc(alias = "currentRunLoop")]
+    pub fn current() -> Retained<Self> {
+        unsafe { Self::currentRunLoop() }
+    }
+
+    #[track_caller]
+    fn assert_current(&self) {
+        assert!(
+            ptr::eq(self, &*Self::current()),
+            "can only use the run loop of the current thread"
+        );
+    }
+
+    /// Run the run loop once in the given mode, blocking until an input
+    /// source has been processed or until the given date.
+    ///
+    /// Returns `false` without waiting if there are no input sources or
+    /// timers attached to the run loop in that mode.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not the run loop of the current thread.
+    #[doc(alias = "runMode:beforeDate:")]
+    #[track_caller]
+    pub fn run_once(&self, mode: &NSRunLoopMode, before: &NSDate) -> bool {
+        self.assert_current();
+        unsafe { self.runMode_beforeDate(mode, before) }
+    }
+
+    /// Run the run loop in the default mode for the given duration.
+    ///
+    /// This returns early if there are no input sources or timers attached
+    /// to the run loop.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not the run loop of the current thread.
+    #[doc(alias = "runUntilDate:")]
+    #[track_caller]
+    pub fn run_for(&self, duration: Duration) {
+        self.assert_current();
+        let deadline = unsafe { NSDate::dateWithTimeIntervalSinceNow(duration.as_secs_f64()) };
+        unsafe { self.runUntilDate(&deadline) };
+    }
+
+    /// Run the run loop in the default mode until the predicate returns
+    /// `true`, or until the timeout expires.
+    ///
+    /// The predicate is checked each time an input source has been
+    /// processed. Returns the last result of the predicate.
+    ///
+    /// This returns early if there are no input sources or timers attached
+    /// to the run loop.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not the run loop of the current thread.
+    ///
+    ///
+    /// # Example
+    ///
+    /// Wait for a timer to fire.
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    /// use std::time::Duration;
+    /// use objc2_foundation::{NSDefaultRunLoopMode, NSRunLoop, NSTimer};
+    ///
+    /// let fired = Rc::new(Cell::new(false));
+    /// let timer = NSTimer::with_block(Duration::from_millis(10), false, {
+    ///     let fired = fired.clone();
+    ///     move |_| fired.set(true)
+    /// });
+    ///
+    /// let run_loop = NSRunLoop::current();
+    /// run_loop.add_timer(&timer, unsafe { NSDefaultRunLoopMode });
+    /// assert!(run_loop.run_until(Duration::from_secs(10), || fired.get()));
+    /// ```
+    #[track_caller]
+    pub fn run_until(&self, timeout: Duration, mut predicate: impl FnMut() -> bool) -> bool {
+        self.assert_current();
+        let deadline = unsafe { NSDate::dateWithTimeIntervalSinceNow(timeout.as_secs_f64()) };
+        let mode = unsafe { NSDefaultRunLoopMode };
+        loop {
+            if predicate() {
+                return true;
+            }
+            if unsafe { deadline.timeIntervalSinceNow() } <= 0.0 {
+                return false;
+            }
+            if !unsafe { self.runMode_beforeDate(mode, &deadline) } {
+                return predicate();
+            }
+        }
+    }
+
+    /// Attach a timer to the run loop in the given mode.
+    ///
+    /// The timer is removed from the run loop again when it is invalidated
+    /// (which non-repeating timers are after they have fired).
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not the run loop of the current thread.
+    #[cfg(feature = "NSTimer")]
+    #[doc(alias = "addTimer:forMode:")]
+    #[track_caller]
+    pub fn add_timer(&self, timer: &crate::NSTimer, mode: &NSRunLoopMode) {
+        self.assert_current();
+        unsafe { self.addTimer_forMode(timer, mode) };
+    }
+
+    /// Attach a port (such as a `NSMachPort`) to the run loop as an input
+    /// source in the given mode.
+    ///
+    /// Messages sent to the port are delivered to its delegate while the
+    /// run loop is running in that mode.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not the run loop of the current thread.
+    #[cfg(feature = "NSPort")]
+    #[doc(alias = "addPort:forMode:")]
+    #[track_caller]
+    pub fn add_port(&self, port: &crate::NSPort, mode: &NSRunLoopMode) {
+        self.assert_current();
+        unsafe { self.addPort_forMode(port, mode) };
+    }
+
+    /// Remove a port that was previously attached with [`add_port`].
+    ///
+    /// [`add_port`]: Self::add_port
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not the run loop of the current thread.
+    #[cfg(feature = "NSPort")]
+    #[doc(alias = "removePort:forMode:")]
+    #[track_caller]
+    pub fn remove_port(&self, port: &crate::NSPort, mode: &NSRunLoopMode) {
+        self.assert_current();
+        unsafe { self.removePort_forMode(port, mode) };
+    }
+}
+
+#[cfg(all(feature = "NSTimer", feature = "block2"))]
+impl crate::NSTimer {
+    /// Create a timer that calls the given closure each time it fires.
+    ///
+    /// The timer must be attached to a run loop with
+    /// [`NSRunLoop::add_timer`] before it will fire. If `repeats` is
+    /// `false`, the timer is invalidated after it has fired once.
+    #[doc(alias = "timerWithTimeInterval:repeats:block:")]
+    pub fn with_block(
+        interval: Duration,
+        repeats: bool,
+        block: impl Fn(&Self) + 'static,
+    ) -> Retained<Self> {
+        let block = block2::RcBlock::new(move |timer: ptr::NonNull<Self>| {
+            // SAFETY: The timer is valid for the duration of the block.
+            block(unsafe { timer.as_ref() });
+        });
+        unsafe {
+            Self::timerWithTimeInterval_repeats_block(interval.as_secs_f64(), repeats, &block)
+        }
+    }
+}
+
+#[cfg(all(feature = "std", feature = "NSPort", feature = "NSThread"))]
+mod block_on {
+    use core::future::Future;
+    use core::pin::pin;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use core::task::{Context, Poll, Waker};
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyObject, NSObject};
+    use objc2::{msg_send, sel};
+
+    use crate::{NSDate, NSDefaultRunLoopMode, NSPort, NSRunLoop, NSThread};
+
+    /// Wakes up the run loop of a thread by sending it a message.
+    struct RunLoopWaker {
+        woken: AtomicBool,
+        thread: Retained<NSThread>,
+        // The receiver of the (no-op) message.
+        target: Retained<NSObject>,
+    }
+
+    // SAFETY: `NSObject` is only used as the receiver of
+    // `performSelector:onThread:withObject:waitUntilDone:`, which is
+    // thread-safe, and `NSThread` is `Send + Sync`.
+    unsafe impl Send for RunLoopWaker {}
+    unsafe impl Sync for RunLoopWaker {}
+
+    impl Wake for RunLoopWaker {
+        fn wake(self: Arc<Self>) {
+            self.wake_by_ref();
+        }
+
+        fn wake_by_ref(self: &Arc<Self>) {
+            if !self.woken.swap(true, Ordering::AcqRel) {
+                // Performing a selector on the thread is processed as an
+                // input source, and thus makes `runMode:beforeDate:` return.
+                let _: () = unsafe {
+                    msg_send![
+                        &self.target,
+                        performSelector: sel!(self),
+                        onThread: &*self.thread,
+                        withObject: None::<&AnyObject>,
+                        waitUntilDone: false,
+                    ]
+                };
+            }
+        }
+    }
+
+    /// Removes the port from the run loop again, even when panicking.
+    struct PortGuard<'a> {
+        run_loop: &'a NSRunLoop,
+        port: Retained<NSPort>,
+    }
+
+    impl Drop for PortGuard<'_> {
+        fn drop(&mut self) {
+            unsafe {
+                self.run_loop
+                    .removePort_forMode(&self.port, NSDefaultRunLoopMode)
+            };
+        }
+    }
+
+    impl NSRunLoop {
+        /// Run the run loop in the default mode until the given future
+        /// completes.
+        ///
+        /// The future is polled on the current thread, so it does not need
+        /// to be `Send`. Run loop callbacks such as timers and notifications
+        /// are delivered while waiting, and may be used to wake the future.
+        ///
+        /// When used on the main thread, this pumps the main run loop, which
+        /// allows receiving callbacks that are delivered there without
+        /// running `NSApplication`.
+        ///
+        ///
+        /// # Panics
+        ///
+        /// Panics if this is not the run loop of the current thread.
+        ///
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use objc2_foundation::NSRunLoop;
+        ///
+        /// let run_loop = NSRunLoop::current();
+        /// let result = run_loop.block_on(async { 1 + 2 });
+        /// assert_eq!(result, 3);
+        /// ```
+        #[track_caller]
+        pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+            self.assert_current();
+
+            // Attach a port that never receives messages, to prevent the run
+            // loop from returning immediately when it has no other sources.
+            let guard = PortGuard {
+                run_loop: self,
+                port: unsafe { NSPort::port() },
+            };
+            unsafe { self.addPort_forMode(&guard.port, NSDefaultRunLoopMode) };
+
+            let inner = Arc::new(RunLoopWaker {
+                woken: AtomicBool::new(true),
+                thread: NSThread::currentThread(),
+                target: NSObject::new(),
+            });
+            let waker = Waker::from(Arc::clone(&inner));
+            let mut cx = Context::from_waker(&waker);
+            let mut future = pin!(future);
+
+            let distant_future = unsafe { NSDate::distantFuture() };
+            loop {
+                if inner.woken.swap(false, Ordering::AcqRel) {
+                    if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                        return output;
+                    }
+                } else {
+                    unsafe { self.runMode_beforeDate(NSDefaultRunLoopMode, &distant_future) };
+                }
+            }
+        }
+    }
+}
@@ -3,12 +3,13 @@ use alloc::vec::Vec;
 #[cfg(feature = "NSEnumerator")]
 use core::fmt;
 
+use objc2::__framework_helpers::{ref_ptr_cast_const, retained_ptr_cast_const};
 use objc2::rc::{Retained, RetainedFromIterator};
 use objc2::{msg_send, AllocAnyThread, Message};
 
 #[cfg(feature = "NSEnumerator")]
 use crate::iter;
-use crate::{util, NSMutableSet, NSSet};
+use crate::{NSMutableSet, NSSet};
 
 /// Convenience creation methods.
 impl<ObjectType: Message> NSSet<ObjectType> {
@@ -24,14 +25,14 @@ impl<ObjectType: Message> NSSet<ObjectType> {
     /// ```
     pub fn from_retained_slice(slice: &[Retained<ObjectType>]) -> Retained<Self> {
         let len = slice.len();
-        let ptr = util::retained_ptr_cast_const(slice.as_ptr());
+        let ptr = retained_ptr_cast_const(slice.as_ptr());
         // SAFETY: Same as `NSArray::from_retained_slice`
         unsafe { Self::initWithObjects_count(Self::alloc(), ptr, len) }
     }
 
     pub fn from_slice(slice: &[&ObjectType]) -> Retained<Self> {
         let len = slice.len();
-        let ptr = util::ref_ptr_cast_const(slice.as_ptr());
+        let ptr = ref_ptr_cast_const(slice.as_ptr());
         // SAFETY: Same as `NSArray::from_slice`.
         unsafe { Self::initWithObjects_count(Self::alloc(), ptr, len) }
     }
@@ -51,14 +52,14 @@ impl<ObjectType: Message> NSMutableSet<ObjectType> {
     /// ```
     pub fn from_retained_slice(slice: &[Retained<ObjectType>]) -> Retained<Self> {
         let len = slice.len();
-        let ptr = util::retained_ptr_cast_const(slice.as_ptr());
+        let ptr = retained_ptr_cast_const(slice.as_ptr());
         // SAFETY: Same as `NSArray::from_retained_slice`
         unsafe { Self::initWithObjects_count(Self::alloc(), ptr, len) }
     }
 
     pub fn from_slice(slice: &[&ObjectType]) -> Retained<Self> {
         let len = slice.len();
-        let ptr = util::ref_ptr_cast_const(slice.as_ptr());
+        let ptr = ref_ptr_cast_const(slice.as_ptr());
         // SAFETY: Same as `NSArray::from_slice`.
         unsafe { Self::initWithObjects_count(Self::alloc(), ptr, len) }
     }
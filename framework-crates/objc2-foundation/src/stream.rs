@@ -0,0 +1,101 @@
+//! Standard I/O traits for Foundation streams.
+//!
+//! The streams must be opened with [`NSStream::open`] before use. When a
+//! stream is not scheduled on a run loop, it operates in "polling mode",
+//! where reads block until at least one byte is available, and writes block
+//! until there is space available. Use [`NSInputStream::try_read`] and
+//! [`NSOutputStream::try_write`] to avoid blocking.
+use std::io;
+use std::ptr::NonNull;
+
+use crate::{NSInputStream, NSOutputStream, NSStream, NSStreamStatus};
+
+/// Convert the stream's error to an [`io::Error`].
+fn stream_error(stream: &NSStream) -> io::Error {
+    if let Some(error) = unsafe { stream.streamError() } {
+        return io::Error::from(&*error);
+    }
+    match unsafe { stream.streamStatus() } {
+        NSStreamStatus::NotOpen => io::Error::new(io::ErrorKind::NotConnected, "stream not open"),
+        NSStreamStatus::Closed => io::Error::new(io::ErrorKind::NotConnected, "stream closed"),
+        _ => io::Error::new(io::ErrorKind::Other, "unknown stream error"),
+    }
+}
+
+impl NSInputStream {
+    /// Read from the stream without blocking.
+    ///
+    /// Fails with [`io::ErrorKind::WouldBlock`] if no bytes are available,
+    /// and the stream has not reached its end.
+    #[doc(alias = "hasBytesAvailable")]
+    pub fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        let status = unsafe { self.streamStatus() };
+        if status != NSStreamStatus::AtEnd && !unsafe { self.hasBytesAvailable() } {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        io::Read::read(&mut &*self, buf)
+    }
+}
+
+/// Read from the stream, blocking until at least one byte is available.
+///
+/// This also makes `Retained<NSInputStream>` usable as a reader.
+impl io::Read for &NSInputStream {
+    #[doc(alias = "read:maxLength:")]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let ptr = NonNull::new(buf.as_mut_ptr()).unwrap();
+        // SAFETY: The buffer is valid for writes of `buf.len()` bytes.
+        let n = unsafe { self.read_maxLength(ptr, buf.len()) };
+        if n < 0 {
+            Err(stream_error(self))
+        } else {
+            Ok(n as usize)
+        }
+    }
+}
+
+impl NSOutputStream {
+    /// Write to the stream without blocking.
+    ///
+    /// Fails with [`io::ErrorKind::WouldBlock`] if the stream has no space
+    /// available.
+    #[doc(alias = "hasSpaceAvailable")]
+    pub fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
+        if !unsafe { self.hasSpaceAvailable() } {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        io::Write::write(&mut &*self, buf)
+    }
+}
+
+/// Write to the stream, blocking until there is space available.
+///
+/// This also makes `Retained<NSOutputStream>` usable as a writer.
+impl io::Write for &NSOutputStream {
+    #[doc(alias = "write:maxLength:")]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let ptr = NonNull::new(buf.as_ptr().cast_mut()).unwrap();
+        // SAFETY: The buffer is valid for reads of `buf.len()` bytes, and the
+        // stream does not write to it.
+        let n = unsafe { self.write_maxLength(ptr, buf.len()) };
+        if n < 0 {
+            Err(stream_error(self))
+        } else {
+            // A return value of zero means that a fixed-size stream has
+            // reached its capacity, which `write_all` turns into
+            // `ErrorKind::WriteZero`.
+            Ok(n as usize)
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Streams do not buffer written data.
+        Ok(())
+    }
+}
@@ -1,15 +1,17 @@
 #[cfg(feature = "NSObjCRuntime")]
 use core::cmp;
-use core::ffi::c_void;
+use core::ffi::{c_char, c_void};
 use core::fmt;
 use core::ops::AddAssign;
 use core::panic::RefUnwindSafe;
 use core::panic::UnwindSafe;
+use core::slice;
 use core::str;
 
-use objc2::msg_send_id;
-use objc2::rc::{autoreleasepool_leaking, Allocated, AutoreleasePool, Retained};
+use objc2::__framework_helpers::with_nsstring_str;
+use objc2::rc::{Allocated, AutoreleasePool, Retained};
 use objc2::runtime::__nsstring::{nsstring_len, nsstring_to_str, UTF8_ENCODING};
+use objc2::{msg_send, msg_send_id};
 use objc2::{AllocAnyThread, Message};
 
 use crate::util;
@@ -100,6 +102,93 @@ impl NSString {
         unsafe { nsstring_to_str(self, pool) }
     }
 
+    /// Convert the string into a [string slice](`prim@str`), avoiding
+    /// allocations when possible.
+    ///
+    /// If the string stores its contents contiguously in an UTF-8 compatible
+    /// encoding (which is usually the case for immutable ASCII strings),
+    /// this returns a slice pointing directly into that storage, without
+    /// sending any messages to `UTF8String` and without allocating. This
+    /// requires the `"objc2-core-foundation"` feature.
+    ///
+    /// Otherwise, this falls back to the same code-path as [`to_str`], which
+    /// copies the string into a buffer that is released with the pool.
+    ///
+    /// Returns [`None`] if the string cannot be represented as UTF-8, for
+    /// example if it contains unpaired UTF-16 surrogates.
+    ///
+    /// [`to_str`]: Self::to_str
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The pool must be the innermost pool, see [the documentation on
+    /// `autoreleasepool`][autoreleasepool].
+    ///
+    /// The string must not be mutated while the returned slice is alive.
+    ///
+    /// [autoreleasepool]: objc2::rc::autoreleasepool
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::NSString;
+    /// use objc2::rc::autoreleasepool;
+    ///
+    /// let string = NSString::from_str("foo");
+    /// autoreleasepool(|pool| {
+    ///     // SAFETY: The str is not used outside the autorelease pool, and
+    ///     // the string is not mutated.
+    ///     assert_eq!(unsafe { string.as_str_fast(pool) }, Some("foo"));
+    /// });
+    /// ```
+    #[doc(alias = "CFStringGetCStringPtr")]
+    pub unsafe fn as_str_fast<'r, 's: 'r, 'p: 'r>(
+        &'s self,
+        pool: AutoreleasePool<'p>,
+    ) -> Option<&'r str> {
+        // The pool is only needed to bound the lifetime of the returned
+        // slice when taking the fallback path.
+        let _ = pool;
+
+        #[cfg(all(target_vendor = "apple", feature = "objc2-core-foundation"))]
+        {
+            use core::ffi::CStr;
+            use objc2_core_foundation::{
+                CFStringEncoding, CFStringGetCStringPtr, CFStringGetLength,
+            };
+
+            let string = self.as_cf_string();
+            let ptr = unsafe { CFStringGetCStringPtr(string, CFStringEncoding::UTF8) };
+            if !ptr.is_null() {
+                // SAFETY: The pointer points to the string's internal
+                // NUL-terminated storage, which is valid for as long as the
+                // string is alive and not mutated (upheld by the caller).
+                let bytes = unsafe { CStr::from_ptr(ptr) }.to_bytes();
+                // `CFStringGetCStringPtr` ignores the requested encoding for
+                // some strings, so only trust it if the result is ASCII with
+                // the same length as the string.
+                let len = unsafe { CFStringGetLength(string) };
+                if bytes.is_ascii() && bytes.len() as isize == len {
+                    // SAFETY: ASCII is valid UTF-8.
+                    return Some(unsafe { str::from_utf8_unchecked(bytes) });
+                }
+            }
+        }
+
+        let ptr: *const c_char = unsafe { msg_send![self, UTF8String] };
+        if ptr.is_null() {
+            return None;
+        }
+        // The string may contain NUL bytes, so the length cannot be taken
+        // from the C string here.
+        //
+        // SAFETY: `UTF8String` either returns a pointer to internal storage,
+        // or to a buffer that is autoreleased into the innermost pool.
+        Some(unsafe { utf8_from_raw_parts(ptr, self.len()) })
+    }
+
     // TODO: Allow usecases where the NUL byte from `UTF8String` is kept?
 
     /// Creates an immutable `NSString` by copying the given string slice.
@@ -140,6 +229,25 @@ unsafe fn init_with_str<T: Message>(obj: Allocated<T>, string: &str) -> Retained
     }
 }
 
+/// # Safety
+///
+/// `ptr` must point to `len` bytes of valid UTF-8 that are alive for `'r`.
+unsafe fn utf8_from_raw_parts<'r>(ptr: *const c_char, len: usize) -> &'r str {
+    // SAFETY: Upheld by caller.
+    let bytes: &'r [u8] = unsafe { slice::from_raw_parts(ptr.cast(), len) };
+
+    // SAFETY: The bytes are valid UTF-8.
+    #[cfg(not(debug_assertions))]
+    unsafe {
+        str::from_utf8_unchecked(bytes)
+    }
+
+    #[cfg(debug_assertions)]
+    {
+        str::from_utf8(bytes).expect("invalid UTF-8 in NSString")
+    }
+}
+
 impl PartialEq<NSString> for NSMutableString {
     #[inline]
     fn eq(&self, other: &NSString) -> bool {
@@ -221,11 +329,8 @@ impl fmt::Display for NSString {
 
 impl fmt::Debug for NSString {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // SAFETY: Same as for `display_string`:
-        // - The object is an instance of `NSString`.
-        // - We control the scope in which the string is alive, so we know
-        //   it is not moved outside the current autorelease pool.
-        autoreleasepool_leaking(|pool| fmt::Debug::fmt(unsafe { nsstring_to_str(self, pool) }, f))
+        // SAFETY: The object is an instance of `NSString`.
+        unsafe { with_nsstring_str(self, |string| fmt::Debug::fmt(string, f)) }
     }
 }
 
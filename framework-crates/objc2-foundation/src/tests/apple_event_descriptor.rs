@@ -0,0 +1,95 @@
+#![cfg(all(feature = "NSAppleEventDescriptor", feature = "NSString"))]
+use alloc::vec::Vec;
+
+use crate::{NSAppleEventDescriptor, NSAppleEventObjectKey};
+
+#[test]
+#[cfg_attr(not(target_vendor = "apple"), ignore = "only on Apple")]
+fn values() {
+    assert!(NSAppleEventDescriptor::null().is_null());
+    assert_eq!(
+        NSAppleEventDescriptor::from_bool(true).to_bool(),
+        Some(true)
+    );
+    assert_eq!(NSAppleEventDescriptor::from_i32(-7).to_i32(), Some(-7));
+    assert_eq!(NSAppleEventDescriptor::from_f64(1.5).to_f64(), Some(1.5));
+    assert_eq!(NSAppleEventDescriptor::from_i32(3).to_f64(), Some(3.0));
+
+    let string = NSAppleEventDescriptor::from_str("hellø");
+    assert_eq!(string.descriptor_type(), *b"utxt");
+    assert_eq!(string.to_string().as_deref(), Some("hellø"));
+    assert_eq!(string.to_i32(), None);
+
+    let type_code = NSAppleEventDescriptor::from_type_code(*b"cwin");
+    assert_eq!(type_code.descriptor_type(), *b"type");
+    assert_eq!(type_code.to_type_code(), *b"cwin");
+
+    let enum_code = NSAppleEventDescriptor::from_enum_code(*b"yes ");
+    assert_eq!(enum_code.descriptor_type(), *b"enum");
+    assert_eq!(enum_code.to_enum_code(), *b"yes ");
+}
+
+#[test]
+#[cfg_attr(not(target_vendor = "apple"), ignore = "only on Apple")]
+fn list_and_record() {
+    let list = NSAppleEventDescriptor::list(&[
+        &NSAppleEventDescriptor::from_i32(1),
+        &NSAppleEventDescriptor::from_str("two"),
+    ]);
+    assert_eq!(list.descriptor_type(), *b"list");
+    assert_eq!(list.len(), 2);
+    let items = list.items();
+    assert_eq!(items[0].to_i32(), Some(1));
+    assert_eq!(items[1].to_string().as_deref(), Some("two"));
+
+    let record = NSAppleEventDescriptor::record(&[
+        (*b"pnam", &NSAppleEventDescriptor::from_str("name")),
+        (*b"pidx", &NSAppleEventDescriptor::from_i32(4)),
+    ]);
+    assert_eq!(record.len(), 2);
+    assert_eq!(record.field(*b"pidx").unwrap().to_i32(), Some(4));
+    assert!(record.field(*b"xxxx").is_none());
+    let keywords: Vec<_> = record.fields().into_iter().map(|(k, _)| k).collect();
+    assert_eq!(keywords, [*b"pnam", *b"pidx"]);
+
+    assert!(NSAppleEventDescriptor::list(&[]).is_empty());
+    assert!(NSAppleEventDescriptor::from_i32(1).items().is_empty());
+}
+
+#[test]
+#[cfg_attr(not(target_vendor = "apple"), ignore = "only on Apple")]
+fn object_specifier() {
+    let window =
+        NSAppleEventDescriptor::object_specifier(*b"cwin", NSAppleEventObjectKey::Index(1), None);
+    assert_eq!(window.descriptor_type(), *b"obj ");
+    assert_eq!(window.field(*b"want").unwrap().to_type_code(), *b"cwin");
+    assert_eq!(window.field(*b"form").unwrap().to_enum_code(), *b"indx");
+    assert_eq!(window.field(*b"seld").unwrap().to_i32(), Some(1));
+    assert!(window.field(*b"from").unwrap().is_null());
+
+    let name = NSAppleEventDescriptor::object_specifier(
+        *b"prop",
+        NSAppleEventObjectKey::Property(*b"pnam"),
+        Some(&window),
+    );
+    assert_eq!(name.field(*b"seld").unwrap().to_type_code(), *b"pnam");
+    let container = name.field(*b"from").unwrap();
+    assert_eq!(container.descriptor_type(), *b"obj ");
+}
+
+#[test]
+#[cfg_attr(not(target_vendor = "apple"), ignore = "only on Apple")]
+fn apple_event() {
+    let target = NSAppleEventDescriptor::from_bundle_identifier("com.apple.finder");
+    let event = NSAppleEventDescriptor::apple_event(*b"core", *b"getd", &target);
+    assert_eq!(event.descriptor_type(), *b"aevt");
+    assert_eq!(event.event_class(), *b"core");
+    assert_eq!(event.event_id(), *b"getd");
+
+    assert!(event.param(*b"----").is_none());
+    event.set_param(*b"----", &NSAppleEventDescriptor::from_str("abc"));
+    assert_eq!(
+        event.param(*b"----").unwrap().to_string().as_deref(),
+        Some("abc")
+    );
+}
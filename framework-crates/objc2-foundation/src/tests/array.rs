@@ -187,3 +187,50 @@ fn test_access_anyobject() {
     for _ in unsafe { array.iter_unchecked() } {}
     for _ in array {}
 }
+
+#[test]
+#[cfg(feature = "block2")]
+fn test_enumerate_objects() {
+    use core::ops::ControlFlow;
+
+    let array = sample_number_array(5);
+
+    let mut seen = Vec::new();
+    unsafe {
+        array.enumerate_objects(|index, obj| {
+            seen.push((index, obj.as_u8()));
+            ControlFlow::Continue(())
+        })
+    };
+    assert_eq!(seen, [(0, 0), (1, 1), (2, 2), (3, 3), (4, 4)]);
+
+    let mut count = 0;
+    unsafe {
+        array.enumerate_objects(|index, _| {
+            count += 1;
+            if index == 1 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+    };
+    assert_eq!(count, 2);
+}
+
+#[test]
+#[cfg(all(feature = "block2", feature = "NSObjCRuntime"))]
+fn test_enumerate_objects_concurrent() {
+    use core::ops::ControlFlow;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    let array = sample_number_array(100);
+    let sum = AtomicUsize::new(0);
+    unsafe {
+        array.enumerate_objects_concurrent(|_, obj| {
+            sum.fetch_add(obj.as_u8() as usize, Ordering::Relaxed);
+            ControlFlow::Continue(())
+        })
+    };
+    assert_eq!(sum.into_inner(), (0..100).sum::<usize>());
+}
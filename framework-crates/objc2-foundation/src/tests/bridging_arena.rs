@@ -0,0 +1,82 @@
+#![cfg(all(feature = "NSArray", feature = "NSString", feature = "NSValue"))]
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use objc2::runtime::NSObjectProtocol;
+
+use crate::{BridgingArena, NSObject, NSString};
+
+#[test]
+fn create() {
+    let arena = BridgingArena::new();
+    assert!(arena.is_empty());
+
+    let string = arena.string("foo");
+    let number = arena.number_i64(-42);
+    let float = arena.number_f64(1.5);
+    let boolean = arena.number_bool(true);
+    let value = arena.value(13u32);
+
+    assert_eq!(arena.len(), 5);
+    assert_eq!(string.to_string(), "foo");
+    assert_eq!(number.as_i64(), -42);
+    assert_eq!(float.as_f64(), 1.5);
+    assert!(boolean.as_bool());
+    assert_eq!(unsafe { value.get::<u32>() }, 13);
+}
+
+#[test]
+fn string_fmt() {
+    let arena = BridgingArena::new();
+    let a = arena.string_fmt(42);
+    let b = arena.string_fmt(format_args!("{}-{}", "a", 1.5));
+    let c = arena.string_fmt("a much longer string that the buffer must grow for");
+    assert_eq!(a.to_string(), "42");
+    assert_eq!(b.to_string(), "a-1.5");
+    assert_eq!(
+        c.to_string(),
+        "a much longer string that the buffer must grow for"
+    );
+}
+
+#[test]
+fn insert_keeps_alive() {
+    let arena = BridgingArena::new();
+    let obj = NSObject::new();
+    let inserted = arena.insert(obj.clone());
+    assert_eq!(inserted, &*obj);
+    assert_eq!(obj.retainCount(), 2);
+    drop(arena);
+    assert_eq!(obj.retainCount(), 1);
+}
+
+#[test]
+fn string_array() {
+    let strings: Vec<_> = (0..100).map(|i| format!("{i}")).collect();
+    let array = BridgingArena::scope(|arena| {
+        let array = arena.string_array(&strings);
+        // Reuses the buffer.
+        let other = arena.string_array(["a", "b"]);
+        assert_eq!(other.len(), 2);
+        // The strings are owned by the array.
+        assert!(arena.is_empty());
+        array
+    });
+    assert_eq!(array.len(), 100);
+    for (i, string) in strings.iter().enumerate() {
+        assert_eq!(array.objectAtIndex(i).to_string(), *string);
+    }
+}
+
+#[test]
+fn clear() {
+    let mut arena = BridgingArena::with_capacity(10);
+    let string: &NSString = arena.string("foo");
+    assert_eq!(string.to_string(), "foo");
+    arena.clear();
+    assert!(arena.is_empty());
+    let _ = arena.string("bar");
+    assert_eq!(arena.len(), 1);
+    assert_eq!(format!("{arena:?}"), "BridgingArena { len: 1, .. }");
+}
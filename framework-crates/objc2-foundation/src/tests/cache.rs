@@ -0,0 +1,56 @@
+#![cfg(all(feature = "NSCache", feature = "NSString"))]
+use crate::{ns_string, Cache, NSString};
+
+#[test]
+fn insert_get_remove() {
+    let cache = Cache::<NSString, NSString>::new();
+    let key = ns_string!("key");
+    assert_eq!(cache.get(key), None);
+
+    cache.insert(key, ns_string!("value"));
+    assert_eq!(cache.get(key).as_deref(), Some(ns_string!("value")));
+
+    cache.insert_with_cost(key, ns_string!("other"), 10);
+    assert_eq!(cache.get(key).as_deref(), Some(ns_string!("other")));
+
+    cache.remove(key);
+    assert_eq!(cache.get(key), None);
+}
+
+#[test]
+fn limits() {
+    let cache = Cache::<NSString, NSString>::new();
+    assert_eq!(cache.total_cost_limit(), 0);
+    assert_eq!(cache.count_limit(), 0);
+
+    cache.set_total_cost_limit(100);
+    cache.set_count_limit(2);
+    assert_eq!(cache.total_cost_limit(), 100);
+    assert_eq!(cache.count_limit(), 2);
+}
+
+#[test]
+#[cfg(feature = "NSValue")]
+fn on_evict() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use crate::NSNumber;
+
+    let evicted = Arc::new(AtomicUsize::new(0));
+    let mut cache = Cache::<NSString, NSNumber>::new();
+    cache.on_evict({
+        let evicted = Arc::clone(&evicted);
+        move |value| {
+            assert_eq!(value.as_i32(), 42);
+            evicted.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    cache.insert(ns_string!("key"), &NSNumber::new_i32(42));
+    cache.remove(ns_string!("key"));
+    assert_eq!(evicted.load(Ordering::SeqCst), 1);
+
+    drop(cache);
+    assert_eq!(evicted.load(Ordering::SeqCst), 1);
+}
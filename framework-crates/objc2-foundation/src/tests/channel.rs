@@ -0,0 +1,112 @@
+#![cfg(all(feature = "std", feature = "NSLock"))]
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+#[cfg(feature = "NSDate")]
+use std::time::Duration;
+
+use crate::channel::{bounded, unbounded, SendError, TrySendError};
+
+#[test]
+fn try_send_full() {
+    let (sender, receiver) = bounded(2);
+    assert_eq!(sender.try_send(1), Ok(()));
+    assert_eq!(sender.try_send(2), Ok(()));
+    assert_eq!(sender.try_send(3), Err(TrySendError::Full(3)));
+    assert_eq!(receiver.len(), 2);
+
+    assert_eq!(receiver.try_recv(), Some(1));
+    assert_eq!(sender.try_send(3), Ok(()));
+    assert_eq!(receiver.try_recv(), Some(2));
+    assert_eq!(receiver.try_recv(), Some(3));
+    assert_eq!(receiver.try_recv(), None);
+}
+
+#[test]
+fn unbounded_never_full() {
+    let (sender, receiver) = unbounded();
+    for i in 0..1000 {
+        assert_eq!(sender.try_send(i), Ok(()));
+    }
+    assert_eq!(receiver.len(), 1000);
+    assert_eq!(receiver.capacity(), usize::MAX);
+    drop(sender);
+
+    let received: Vec<i32> = std::iter::from_fn(|| receiver.recv()).collect();
+    assert_eq!(received, (0..1000).collect::<Vec<_>>());
+}
+
+#[test]
+fn disconnect() {
+    let (sender, receiver) = bounded(1);
+    let sender2 = sender.clone();
+    sender.send(1).unwrap();
+    drop(sender);
+    assert_eq!(receiver.recv(), Some(1));
+    drop(sender2);
+    assert_eq!(receiver.recv(), None);
+
+    let (sender, receiver) = bounded(1);
+    drop(receiver);
+    assert!(sender.is_disconnected());
+    assert_eq!(sender.send(1), Err(SendError(1)));
+    assert_eq!(sender.try_send(2), Err(TrySendError::Disconnected(2)));
+}
+
+#[test]
+fn dropping_receiver_drops_queued_values() {
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let drops = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = bounded(4);
+    sender.send(Counted(drops.clone())).unwrap();
+    sender.send(Counted(drops.clone())).unwrap();
+    drop(receiver);
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn backpressure() {
+    let (sender, receiver) = bounded(1);
+    let sent = Arc::new(AtomicUsize::new(0));
+
+    let handle = thread::spawn({
+        let sent = sent.clone();
+        move || {
+            for i in 0..100 {
+                sender.send(i).unwrap();
+                sent.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    });
+
+    for i in 0..100 {
+        assert_eq!(receiver.recv(), Some(i));
+        // The sender can be at most one value ahead (plus the value that it
+        // is currently blocked on).
+        assert!(sent.load(Ordering::SeqCst) <= i + 2);
+    }
+    assert_eq!(receiver.recv(), None);
+    handle.join().unwrap();
+}
+
+#[test]
+#[cfg(feature = "NSDate")]
+fn timeouts() {
+    let (sender, receiver) = bounded(1);
+    assert_eq!(receiver.recv_timeout(Duration::from_millis(10)), None);
+
+    sender.send(1).unwrap();
+    assert_eq!(
+        sender.send_timeout(2, Duration::from_millis(10)),
+        Err(TrySendError::Full(2))
+    );
+    assert_eq!(receiver.recv_timeout(Duration::from_millis(10)), Some(1));
+}
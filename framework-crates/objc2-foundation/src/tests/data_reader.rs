@@ -0,0 +1,77 @@
+#![cfg(all(feature = "NSData", feature = "NSRange"))]
+use crate::{NSData, NSDataReader, NSDataReaderError, NSMutableData};
+
+#[test]
+fn test_numbers() {
+    let data = NSData::with_bytes(&[
+        0x12, 0x34, 0x12, 0x34, 0xff, 0xff, 0xff, 0xfe, 0x00, 0x00, 0x80, 0x3f,
+    ]);
+    let mut reader = NSDataReader::new(&data);
+    assert_eq!(reader.read_u16_le().unwrap(), 0x3412);
+    assert_eq!(reader.read_u16_be().unwrap(), 0x1234);
+    assert_eq!(reader.read_i32_be().unwrap(), -2);
+    assert_eq!(reader.read_f32_le().unwrap(), 1.0);
+    assert!(reader.is_empty());
+    assert_eq!(reader.position(), 12);
+}
+
+#[test]
+fn test_unexpected_end() {
+    let data = NSData::with_bytes(&[1, 2, 3]);
+    let mut reader = NSDataReader::new(&data);
+    assert_eq!(reader.read_u8().unwrap(), 1);
+    assert_eq!(
+        reader.read_u32_le(),
+        Err(NSDataReaderError::UnexpectedEnd {
+            requested: 4,
+            remaining: 2,
+        })
+    );
+    // The position is unchanged by the failed read.
+    assert_eq!(reader.position(), 1);
+    assert_eq!(reader.read_i16_be().unwrap(), 0x0203);
+    assert!(reader.set_position(4).is_err());
+    reader.set_position(0).unwrap();
+    assert_eq!(reader.remaining(), 3);
+}
+
+#[test]
+fn test_empty() {
+    let data = NSData::new();
+    let mut reader = NSDataReader::new(&data);
+    assert!(reader.is_empty());
+    reader.read_exact(&mut []).unwrap();
+    assert_eq!(reader.read_utf8(0).unwrap(), "");
+    assert!(reader.read_u8().is_err());
+}
+
+#[test]
+fn test_strings_and_subdata() {
+    let data = NSMutableData::with_bytes(b"abc\xffdef");
+    let mut reader = NSDataReader::new(&data);
+    assert_eq!(reader.read_utf8(3).unwrap(), "abc");
+    assert!(matches!(
+        reader.read_utf8(1),
+        Err(NSDataReaderError::InvalidUtf8(_))
+    ));
+    let sub = reader.read_subdata(2).unwrap();
+    assert_eq!(sub.to_vec(), b"de");
+    reader.skip(1).unwrap();
+    assert!(reader.skip(1).is_err());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_io_read() {
+    use std::io::Read;
+
+    let data = NSData::with_bytes(&[1, 2, 3, 4, 5]);
+    let mut reader = NSDataReader::new(&data);
+    reader.skip(1).unwrap();
+    let mut buf = [0; 3];
+    assert_eq!(reader.read(&mut buf).unwrap(), 3);
+    assert_eq!(buf, [2, 3, 4]);
+    let mut rest = std::vec::Vec::new();
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, [5]);
+}
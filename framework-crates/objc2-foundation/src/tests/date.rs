@@ -0,0 +1,116 @@
+#![cfg(feature = "NSDate")]
+use core::time::Duration;
+
+use crate::NSDate;
+
+#[test]
+fn duration() {
+    let date = unsafe { NSDate::dateWithTimeIntervalSinceReferenceDate(0.0) };
+    let later = date.add_duration(Duration::from_millis(1500));
+    assert_eq!(
+        later.duration_since(&date),
+        Some(Duration::from_millis(1500))
+    );
+    assert_eq!(date.duration_since(&later), None);
+    assert_eq!(
+        unsafe {
+            later
+                .sub_duration(Duration::from_millis(1500))
+                .timeIntervalSinceReferenceDate()
+        },
+        0.0
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn system_time() {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let time = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    let date = NSDate::from_system_time(&time);
+    assert_eq!(unsafe { date.timeIntervalSince1970() }, 1_000_000_000.0);
+    assert_eq!(date.to_system_time(), Some(time));
+    assert_eq!(SystemTime::from(&*date), time);
+
+    // Before 1970.
+    let time = UNIX_EPOCH - Duration::from_secs(86400);
+    let date = NSDate::from_system_time(&time);
+    assert_eq!(unsafe { date.timeIntervalSince1970() }, -86400.0);
+    assert_eq!(date.to_system_time(), Some(time));
+}
+
+#[test]
+#[cfg(feature = "time")]
+fn time() {
+    use time::{OffsetDateTime, UtcOffset};
+
+    let datetime = OffsetDateTime::from_unix_timestamp(1_000_000_000)
+        .unwrap()
+        .to_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+    let date = NSDate::from_offset_date_time(&datetime);
+    assert_eq!(unsafe { date.timeIntervalSince1970() }, 1_000_000_000.0);
+    let utc = date.to_offset_date_time().unwrap();
+    assert_eq!(utc, datetime);
+    assert_eq!(utc.offset(), UtcOffset::UTC);
+
+    let date = unsafe { NSDate::dateWithTimeIntervalSince1970(-0.25) };
+    let utc = date.to_offset_date_time().unwrap();
+    assert_eq!(utc.unix_timestamp_nanos(), -250_000_000);
+}
+
+#[test]
+#[cfg(all(feature = "time", feature = "NSTimeZone"))]
+fn time_with_time_zone() {
+    use time::UtcOffset;
+
+    use crate::NSTimeZone;
+
+    let offset = UtcOffset::from_hms(-5, -30, 0).unwrap();
+    let time_zone = NSTimeZone::from_utc_offset(offset);
+    let date = unsafe { NSDate::dateWithTimeIntervalSince1970(1_000_000_000.0) };
+    assert_eq!(time_zone.utc_offset_at(&date), Some(offset));
+
+    let datetime = date.to_offset_date_time_in(&time_zone).unwrap();
+    assert_eq!(datetime.offset(), offset);
+    assert_eq!(datetime.unix_timestamp(), 1_000_000_000);
+    assert_eq!(
+        unsafe { NSDate::from_offset_date_time(&datetime).timeIntervalSince1970() },
+        1_000_000_000.0
+    );
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn chrono() {
+    use chrono::{DateTime, FixedOffset};
+
+    let datetime = DateTime::from_timestamp(1_000_000_000, 500_000_000)
+        .unwrap()
+        .with_timezone(&FixedOffset::east_opt(3600).unwrap());
+    let date = NSDate::from_date_time(&datetime);
+    assert_eq!(unsafe { date.timeIntervalSince1970() }, 1_000_000_000.5);
+    assert_eq!(date.to_date_time().unwrap(), datetime);
+
+    let date = unsafe { NSDate::dateWithTimeIntervalSince1970(-0.25) };
+    let utc = date.to_date_time().unwrap();
+    assert_eq!(utc.timestamp(), -1);
+    assert_eq!(utc.timestamp_subsec_nanos(), 750_000_000);
+}
+
+#[test]
+#[cfg(all(feature = "chrono", feature = "NSTimeZone"))]
+fn chrono_with_time_zone() {
+    use chrono::FixedOffset;
+
+    use crate::NSTimeZone;
+
+    let offset = FixedOffset::west_opt(8 * 3600).unwrap();
+    let time_zone = NSTimeZone::from_fixed_offset(offset);
+    let date = unsafe { NSDate::dateWithTimeIntervalSince1970(1_000_000_000.0) };
+    assert_eq!(time_zone.fixed_offset_at(&date), Some(offset));
+
+    let datetime = date.to_date_time_in(&time_zone).unwrap();
+    assert_eq!(*datetime.offset(), offset);
+    assert_eq!(datetime.timestamp(), 1_000_000_000);
+}
@@ -21,3 +21,111 @@ fn test_decimal_encoding() {
     let obj = unsafe { NSDecimalNumber::initWithDecimal(NSDecimalNumber::alloc(), decimal) };
     assert_eq!(decimal, unsafe { obj.decimalValue() });
 }
+
+#[test]
+#[cfg_attr(
+    feature = "gnustep-1-7",
+    ignore = "has different encoding, yet unsupported"
+)]
+fn test_decimal_new() {
+    let number = NSDecimalNumber::from_decimal(NSDecimal::new(1234, -2, true));
+    assert_eq!(number, unsafe {
+        NSDecimalNumber::decimalNumberWithMantissa_exponent_isNegative(1234, -2, true)
+    });
+    assert_eq!(number.as_f64(), -12.34);
+
+    assert_eq!(
+        NSDecimalNumber::from_decimal(NSDecimal::default()),
+        unsafe { NSDecimalNumber::zero() }
+    );
+    assert_eq!(
+        NSDecimalNumber::from_decimal(NSDecimal::from(-7i64)).as_i64(),
+        -7
+    );
+    assert!(!NSDecimal::default().is_nan());
+    assert!(unsafe { NSDecimalNumber::notANumber() }.decimal().is_nan());
+}
+
+#[test]
+#[cfg_attr(
+    feature = "gnustep-1-7",
+    ignore = "has different encoding, yet unsupported"
+)]
+fn test_decimal_arithmetic() {
+    use crate::{NSCalculationError, NSRoundingMode};
+
+    let mode = NSRoundingMode::RoundPlain;
+    let a = NSDecimal::new(15, -1, false); // 1.5
+    let b = NSDecimal::from(2u64);
+
+    let sum = a.checked_add(&b, mode).unwrap();
+    assert_eq!(NSDecimalNumber::from_decimal(sum).as_f64(), 3.5);
+    let difference = a.checked_sub(&b, mode).unwrap();
+    assert_eq!(NSDecimalNumber::from_decimal(difference).as_f64(), -0.5);
+    let product = a.checked_mul(&b, mode).unwrap();
+    assert_eq!(NSDecimalNumber::from_decimal(product).as_f64(), 3.0);
+    let quotient = a.checked_div(&b, mode).unwrap();
+    assert_eq!(NSDecimalNumber::from_decimal(quotient).as_f64(), 0.75);
+    let power = b.checked_pow(10, mode).unwrap();
+    assert_eq!(NSDecimalNumber::from_decimal(power).as_u64(), 1024);
+    let scaled = a.checked_mul_pow10(3, mode).unwrap();
+    assert_eq!(NSDecimalNumber::from_decimal(scaled).as_u64(), 1500);
+
+    assert_eq!(
+        a.checked_div(&NSDecimal::default(), mode),
+        Err(NSCalculationError::DivideByZero)
+    );
+
+    let third = NSDecimal::from(1u64)
+        .checked_div(&NSDecimal::from(3u64), mode)
+        .unwrap();
+    let rounded = third.round(2, NSRoundingMode::RoundUp);
+    assert_eq!(NSDecimalNumber::from_decimal(rounded).as_f64(), 0.34);
+}
+
+#[test]
+#[cfg(feature = "NSObjCRuntime")]
+#[cfg_attr(
+    feature = "gnustep-1-7",
+    ignore = "has different encoding, yet unsupported"
+)]
+fn test_decimal_compare() {
+    use core::cmp::Ordering;
+
+    let one = NSDecimal::from(1u64);
+    let one_point_zero = NSDecimal::new(10, -1, false);
+    assert_eq!(one.compare(&one_point_zero), Some(Ordering::Equal));
+    assert_eq!(one.compare(&NSDecimal::from(2u64)), Some(Ordering::Less));
+    let nan = unsafe { NSDecimalNumber::notANumber() }.decimal();
+    assert_eq!(one.compare(&nan), None);
+}
+
+#[test]
+#[cfg(feature = "NSString")]
+#[cfg_attr(
+    feature = "gnustep-1-7",
+    ignore = "has different encoding, yet unsupported"
+)]
+fn test_decimal_number_parse() {
+    use alloc::string::ToString;
+
+    let a = NSDecimalNumber::parse("0.1").unwrap();
+    let b = NSDecimalNumber::parse("0.2").unwrap();
+    let sum = a.checked_add(&b).unwrap();
+    // Exact, unlike with floats.
+    assert_eq!(sum.decimal().to_string(), "0.3");
+    assert_eq!(sum, NSDecimalNumber::parse("0.3").unwrap());
+
+    assert!(NSDecimalNumber::parse("not a number").is_none());
+    assert!(a
+        .checked_div(&NSDecimalNumber::parse("0").unwrap())
+        .is_err());
+    assert_eq!(
+        NSDecimalNumber::parse("2.345")
+            .unwrap()
+            .round(2, crate::NSRoundingMode::RoundDown)
+            .decimal()
+            .to_string(),
+        "2.34"
+    );
+}
@@ -13,7 +13,7 @@ use objc2::{
     sel,
 };
 
-use crate::{ns_string, NSDictionary, NSObject, NSString, NSUInteger};
+use crate::{ns_string, NSDictionary, NSMutableDictionary, NSObject, NSString, NSUInteger};
 
 fn sample_dict(key: &str) -> Retained<NSDictionary<NSString, NSObject>> {
     let string = NSString::from_str(key);
@@ -74,6 +74,53 @@ fn test_arrays() {
     assert_eq!(objs.len(), 1);
 }
 
+#[test]
+#[cfg(feature = "NSEnumerator")]
+fn test_iter() {
+    let dict = NSDictionary::from_slices(
+        &[ns_string!("a"), ns_string!("b")],
+        &[ns_string!("a"), ns_string!("b")],
+    );
+    let mut count = 0;
+    for (key, obj) in dict.iter() {
+        assert_eq!(key, obj);
+        count += 1;
+    }
+    assert_eq!(count, 2);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_from_hash_map() {
+    let mut map = std::collections::HashMap::new();
+    map.insert(NSString::from_str("a"), NSObject::new());
+    map.insert(NSString::from_str("b"), NSObject::new());
+
+    let dict = NSDictionary::from_hash_map(&map);
+    assert_eq!(dict.len(), 2);
+    for (key, obj) in &map {
+        assert_eq!(&dict.objectForKey(key).unwrap(), obj);
+    }
+
+    let dict: Retained<NSMutableDictionary<NSString, NSObject>> =
+        map.iter().map(|(key, obj)| (&**key, &**obj)).collect();
+    assert_eq!(dict.len(), 2);
+}
+
+#[test]
+fn test_get_or_insert_with() {
+    let dict = NSMutableDictionary::<NSString, NSObject>::new();
+    let obj = dict.get_or_insert_with(ns_string!("a"), NSObject::new);
+    assert_eq!(dict.len(), 1);
+
+    let same = dict.get_or_insert_with(ns_string!("a"), || unreachable!());
+    assert_eq!(obj, same);
+
+    let mut dict_ref = &*dict;
+    dict_ref.extend([(ns_string!("b"), &*obj)]);
+    assert_eq!(dict.len(), 2);
+}
+
 #[test]
 fn test_debug() {
     let key = ns_string!("a");
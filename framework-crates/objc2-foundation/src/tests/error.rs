@@ -27,3 +27,61 @@ fn custom_domain() {
     };
     assert_eq!(format!("{error}"), expected);
 }
+
+#[test]
+#[cfg(all(feature = "NSDictionary", feature = "NSObject"))]
+fn new_with() {
+    let underlying = NSError::new(1, ns_string!("Underlying"));
+    let error = NSError::new_with(ns_string!("MyDomain"), 42, |builder| {
+        builder
+            .description("Something went wrong")
+            .failure_reason("Reasons")
+            .underlying_error(&underlying);
+    });
+    assert_eq!(error.code(), 42);
+    assert_eq!(format!("{error}"), "Something went wrong");
+    let reason = unsafe { error.localizedFailureReason() }.unwrap();
+    assert_eq!(&*reason, ns_string!("Reasons"));
+
+    #[cfg(feature = "std")]
+    {
+        use std::error::Error;
+        let source = error.source().unwrap().downcast_ref::<NSError>().unwrap();
+        assert_eq!(source, &*underlying);
+        assert!(source.source().is_none());
+    }
+}
+
+#[test]
+#[cfg(all(feature = "std", feature = "NSDictionary", feature = "NSObject"))]
+fn from_error() {
+    use alloc::boxed::Box;
+    use std::error::Error;
+    use std::fmt;
+
+    #[derive(Debug)]
+    struct Outer(std::io::Error);
+
+    impl fmt::Display for Outer {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("outer")
+        }
+    }
+
+    impl Error for Outer {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    let error: Box<dyn Error + Send + Sync> = Box::new(Outer(std::io::Error::other("inner")));
+    let error = NSError::from_error(&*error);
+    assert_eq!(&*error.domain(), ns_string!("RustErrorDomain"));
+    assert_eq!(format!("{error}"), "outer");
+    assert_eq!(format!("{}", error.source().unwrap()), "inner");
+
+    // Existing errors are passed through.
+    let existing = NSError::new(42, ns_string!("MyDomain"));
+    let error: Box<dyn Error> = Box::new(existing.clone());
+    assert_eq!(NSError::from_error(&*error), existing);
+}
@@ -0,0 +1,40 @@
+#![cfg(all(
+    feature = "std",
+    unix,
+    feature = "NSFileHandle",
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSString",
+    feature = "NSURL"
+))]
+use alloc::string::String;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use crate::{NSFileHandle, NSURL};
+
+#[test]
+fn read_write_seek() {
+    let path = std::env::temp_dir().join("objc2-foundation-file-handle.txt");
+    fs::write(&path, "").unwrap();
+    let url = NSURL::from_path(&path, false).unwrap();
+    let handle = unsafe { NSFileHandle::fileHandleForUpdatingURL_error(&url) }.unwrap();
+
+    (&*handle).write_all(b"hello world").unwrap();
+    assert_eq!((&*handle).stream_position().unwrap(), 11);
+
+    assert_eq!((&*handle).seek(SeekFrom::Start(6)).unwrap(), 6);
+    let mut contents = String::new();
+    (&*handle).read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "world");
+
+    assert_eq!((&*handle).seek(SeekFrom::End(-5)).unwrap(), 6);
+    assert_eq!((&*handle).seek(SeekFrom::Current(-6)).unwrap(), 0);
+    assert!((&*handle).seek(SeekFrom::Current(-1)).is_err());
+    let mut buf = [0; 5];
+    (&*handle).read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+
+    drop(handle);
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,138 @@
+#![cfg(all(
+    feature = "std",
+    unix,
+    feature = "NSFileManager",
+    feature = "NSError",
+    feature = "NSURL",
+    feature = "NSString"
+))]
+use alloc::format;
+use std::fs;
+use std::path::PathBuf;
+
+use objc2::rc::Retained;
+
+use crate::NSURL;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("objc2-foundation-{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn url(path: &std::path::Path) -> Retained<NSURL> {
+    NSURL::from_path(path, path.is_dir()).unwrap()
+}
+
+#[test]
+#[cfg(feature = "FoundationErrors")]
+fn copy_move_remove() {
+    use std::io;
+
+    use crate::NSFileManager;
+
+    let dir = temp_dir("copy_move_remove");
+    let manager = unsafe { NSFileManager::defaultManager() };
+    fs::write(dir.join("a.txt"), "hello").unwrap();
+
+    manager
+        .copy_item(&url(&dir.join("a.txt")), &url(&dir.join("b.txt")))
+        .unwrap();
+    assert_eq!(fs::read_to_string(dir.join("b.txt")).unwrap(), "hello");
+
+    // Copying over an existing file fails.
+    let err = manager
+        .copy_item(&url(&dir.join("a.txt")), &url(&dir.join("b.txt")))
+        .unwrap_err();
+    assert_eq!(io::Error::from(&*err).kind(), io::ErrorKind::AlreadyExists);
+
+    manager
+        .move_item(&url(&dir.join("b.txt")), &url(&dir.join("c.txt")))
+        .unwrap();
+    assert!(!dir.join("b.txt").exists());
+    assert!(dir.join("c.txt").exists());
+
+    manager.remove_item(&url(&dir)).unwrap();
+    assert!(!dir.exists());
+
+    let err = manager.remove_item(&url(&dir)).unwrap_err();
+    assert_eq!(io::Error::from(&*err).kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+#[cfg(all(feature = "block2", feature = "NSArray", feature = "NSEnumerator"))]
+fn entries() {
+    use alloc::vec::Vec;
+
+    use crate::{NSDirectoryEnumerationOptions, NSFileManager};
+
+    let dir = temp_dir("entries");
+    fs::create_dir(dir.join("sub")).unwrap();
+    fs::write(dir.join("a.txt"), "").unwrap();
+    fs::write(dir.join("sub").join("b.txt"), "").unwrap();
+    fs::write(dir.join(".hidden"), "").unwrap();
+
+    let manager = unsafe { NSFileManager::defaultManager() };
+    let names = |options| {
+        let mut names: Vec<_> = manager
+            .entries(&url(&dir), options, &[])
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().to_path_buf().unwrap();
+                path.strip_prefix(dir.canonicalize().unwrap())
+                    .or_else(|_| path.strip_prefix(&dir))
+                    .unwrap()
+                    .to_path_buf()
+            })
+            .collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(
+        names(NSDirectoryEnumerationOptions::SkipsHiddenFiles),
+        [
+            PathBuf::from("a.txt"),
+            PathBuf::from("sub"),
+            PathBuf::from("sub/b.txt")
+        ]
+    );
+    assert_eq!(
+        names(
+            NSDirectoryEnumerationOptions::SkipsHiddenFiles
+                | NSDirectoryEnumerationOptions::SkipsSubdirectoryDescendants
+        ),
+        [PathBuf::from("a.txt"), PathBuf::from("sub")]
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(all(feature = "block2", feature = "NSFileCoordinator"))]
+fn coordinate() {
+    use crate::{
+        NSFileCoordinator, NSFileCoordinatorReadingOptions, NSFileCoordinatorWritingOptions,
+    };
+
+    let dir = temp_dir("coordinate");
+    let file = url(&dir.join("file.txt"));
+    let coordinator = unsafe { NSFileCoordinator::new() };
+
+    coordinator
+        .coordinate_writing(&file, NSFileCoordinatorWritingOptions::empty(), |url| {
+            fs::write(url.to_path_buf().unwrap(), "contents")
+        })
+        .unwrap()
+        .unwrap();
+    let contents = coordinator
+        .coordinate_reading(&file, NSFileCoordinatorReadingOptions::empty(), |url| {
+            fs::read_to_string(url.to_path_buf().unwrap())
+        })
+        .unwrap()
+        .unwrap();
+    assert_eq!(contents, "contents");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
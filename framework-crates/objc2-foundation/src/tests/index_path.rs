@@ -0,0 +1,23 @@
+#![cfg(feature = "NSIndexPath")]
+use alloc::vec::Vec;
+
+use crate::NSIndexPath;
+
+#[test]
+fn from_slice() {
+    let path = NSIndexPath::from_slice(&[1, 4, 2]);
+    assert_eq!(path.len(), 3);
+    assert_eq!(path.get(1), Some(4));
+    assert_eq!(path.get(3), None);
+    assert_eq!(path.to_vec(), [1, 4, 2]);
+    assert_eq!(Vec::from(&*path), [1, 4, 2]);
+    assert_eq!(*path, [1, 4, 2][..]);
+    assert_eq!(path.iter().rev().collect::<Vec<_>>(), [2, 4, 1]);
+}
+
+#[test]
+fn empty() {
+    let path = NSIndexPath::from_slice(&[]);
+    assert!(path.is_empty());
+    assert_eq!(path.iter().len(), 0);
+}
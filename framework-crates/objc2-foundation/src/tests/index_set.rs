@@ -0,0 +1,67 @@
+#![cfg(all(feature = "NSIndexSet", feature = "NSRange"))]
+use alloc::vec::Vec;
+
+use objc2::rc::Retained;
+
+use crate::{NSIndexSet, NSMutableIndexSet};
+
+#[test]
+fn empty() {
+    let set = unsafe { NSIndexSet::new() };
+    assert!(set.is_empty());
+    assert_eq!(set.first(), None);
+    assert_eq!(set.last(), None);
+    assert_eq!(set.iter().next(), None);
+    assert_eq!(set.ranges().next(), None);
+}
+
+#[test]
+fn from_iter() {
+    let set: Retained<NSIndexSet> = [5, 1, 3, 2, 3].into_iter().collect();
+    assert_eq!(set.len(), 4);
+    assert!(set.contains(5));
+    assert!(!set.contains(4));
+    assert_eq!(set.first(), Some(1));
+    assert_eq!(set.last(), Some(5));
+    assert_eq!(set.iter().collect::<Vec<_>>(), [1, 2, 3, 5]);
+    assert_eq!(set.ranges().collect::<Vec<_>>(), [1..4, 5..6]);
+}
+
+#[test]
+fn from_range() {
+    let set = NSIndexSet::from_range(10..20);
+    assert_eq!(set.len(), 10);
+    assert_eq!(
+        (&*set).into_iter().collect::<Vec<_>>(),
+        (10..20).collect::<Vec<_>>()
+    );
+    let mut ranges = set.ranges();
+    assert_eq!(ranges.next(), Some(10..20));
+    assert_eq!(ranges.next(), None);
+
+    assert!(NSIndexSet::from_range(3..3).is_empty());
+}
+
+#[test]
+fn large_ranges() {
+    let set = unsafe { NSMutableIndexSet::new() };
+    set.insert_range(0..1000);
+    set.insert_range(1001..1_000_000);
+    set.insert(usize::MAX / 4);
+    assert_eq!(
+        set.ranges().collect::<Vec<_>>(),
+        [0..1000, 1001..1_000_000, usize::MAX / 4..usize::MAX / 4 + 1]
+    );
+}
+
+#[test]
+fn mutate() {
+    let set = unsafe { NSMutableIndexSet::new() };
+    set.insert_range(0..10);
+    set.remove(3);
+    set.remove_range(5..8);
+    assert_eq!(set.iter().collect::<Vec<_>>(), [0, 1, 2, 4, 8, 9]);
+
+    set.clear();
+    assert!(set.is_empty());
+}
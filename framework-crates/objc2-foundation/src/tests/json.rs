@@ -0,0 +1,125 @@
+#![cfg(all(
+    feature = "serde_json",
+    feature = "NSArray",
+    feature = "NSDictionary",
+    feature = "NSEnumerator",
+    feature = "NSNull",
+    feature = "NSObject",
+    feature = "NSString",
+    feature = "NSValue"
+))]
+use alloc::string::ToString;
+
+use serde_json::json;
+
+use crate::json;
+use crate::{NSArray, NSDictionary, NSNull, NSNumber, NSString};
+
+#[test]
+fn round_trip() {
+    let values = [
+        json!(null),
+        json!(true),
+        json!(false),
+        json!(0),
+        json!(-42),
+        json!(u64::MAX),
+        json!(1.5),
+        json!("abc"),
+        json!([]),
+        json!({}),
+        json!([1, "two", null, [3.0]]),
+        json!({ "a": { "b": [true, false] }, "c": "" }),
+    ];
+    for value in values {
+        let object = json::from_value(&value);
+        assert_eq!(json::to_value(&object).unwrap(), value);
+    }
+}
+
+#[test]
+fn from_value_types() {
+    let object = json::from_value(&json!(null));
+    assert!(NSNull::is_null(&object));
+
+    let object = json::from_value(&json!("abc"));
+    assert_eq!(
+        object.downcast_ref::<NSString>().unwrap().to_string(),
+        "abc"
+    );
+
+    let object = json::from_value(&json!(3));
+    assert_eq!(object.downcast_ref::<NSNumber>().unwrap().as_i64(), 3);
+
+    let object = json::from_value(&json!([1, 2]));
+    assert_eq!(object.downcast_ref::<NSArray>().unwrap().len(), 2);
+
+    let object = json::from_value(&json!({ "a": 1 }));
+    assert_eq!(object.downcast_ref::<NSDictionary>().unwrap().len(), 1);
+}
+
+#[test]
+fn numbers() {
+    assert_eq!(
+        json::to_value(&NSNumber::new_bool(true)).unwrap(),
+        json!(true)
+    );
+    assert_eq!(json::to_value(&NSNumber::new_u8(200)).unwrap(), json!(200));
+    assert_eq!(json::to_value(&NSNumber::new_i8(-3)).unwrap(), json!(-3));
+    assert_eq!(json::to_value(&NSNumber::new_f32(0.5)).unwrap(), json!(0.5));
+    assert!(json::to_value(&NSNumber::new_f64(f64::NAN)).is_err());
+    assert!(json::to_value(&NSNumber::new_f64(f64::INFINITY)).is_err());
+}
+
+#[test]
+fn unsupported() {
+    let object = NSArray::from_retained_slice(&[NSArray::<NSString>::new()]);
+    assert_eq!(json::to_value(&object).unwrap(), json!([[]]));
+
+    let key = NSNumber::new_i32(1);
+    let dictionary = NSDictionary::from_slices(&[&*key], &[&*NSString::from_str("a")]);
+    assert!(json::to_value(&dictionary).is_err());
+}
+
+#[test]
+#[cfg(all(
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSJSONSerialization"
+))]
+fn serialize() {
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use serde_json::Value;
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Message {
+        name: String,
+        ids: Vec<u32>,
+        reply: Option<bool>,
+    }
+
+    let message = Message {
+        name: "hello".into(),
+        ids: vec![1, 2, 3],
+        reply: None,
+    };
+    let object = json::to_object(&message).unwrap();
+    assert_eq!(
+        json::to_value(&object).unwrap(),
+        json!({ "name": "hello", "ids": [1, 2, 3], "reply": null })
+    );
+    assert_eq!(json::from_object::<Message>(&object).unwrap(), message);
+
+    // Top-level fragments.
+    let object = json::to_object("fragment").unwrap();
+    assert_eq!(json::from_object::<String>(&object).unwrap(), "fragment");
+    let object = json::to_object(&42).unwrap();
+    assert_eq!(json::from_object::<u8>(&object).unwrap(), 42);
+    assert_eq!(json::from_object::<Value>(&object).unwrap(), json!(42));
+
+    // Mismatched structure.
+    assert!(json::from_object::<Message>(&object).is_err());
+}
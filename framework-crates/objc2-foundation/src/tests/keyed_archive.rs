@@ -0,0 +1,137 @@
+#![cfg(all(
+    feature = "FoundationErrors",
+    feature = "NSCoder",
+    feature = "NSData",
+    feature = "NSDictionary",
+    feature = "NSError",
+    feature = "NSKeyedArchiver",
+    feature = "NSObject",
+    feature = "NSSet",
+    feature = "NSString",
+    feature = "NSArray",
+    feature = "NSValue"
+))]
+use alloc::string::ToString;
+
+use objc2::rc::{Allocated, Retained};
+use objc2::runtime::AnyObject;
+use objc2::{define_class, msg_send_id, AllocAnyThread, ClassType, DefinedClass};
+
+use crate::{
+    NSAllowedClasses, NSArray, NSCocoaErrorDomain, NSCoder, NSCoderReadCorruptError, NSCoding,
+    NSKeyedArchiver, NSKeyedCoding, NSKeyedUnarchiver, NSNumber, NSObject, NSObjectProtocol,
+    NSSecureCoding, NSString,
+};
+
+struct Ivars {
+    name: Retained<NSString>,
+    value: Retained<NSNumber>,
+}
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    #[name = "TestKeyedArchiveObject"]
+    #[ivars = Ivars]
+    struct TestObject;
+
+    unsafe impl NSObjectProtocol for TestObject {}
+
+    unsafe impl NSCoding for TestObject {
+        #[method(encodeWithCoder:)]
+        fn encode_with_coder(&self, coder: &NSCoder) {
+            NSKeyedCoding::encode(self, coder);
+        }
+
+        #[method_id(initWithCoder:)]
+        fn init_with_coder(this: Allocated<Self>, coder: &NSCoder) -> Option<Retained<Self>> {
+            NSKeyedCoding::init_from_coder(this, coder)
+        }
+    }
+
+    unsafe impl NSSecureCoding for TestObject {
+        #[method(supportsSecureCoding)]
+        fn supports_secure_coding() -> bool {
+            true
+        }
+    }
+);
+
+impl NSKeyedCoding for TestObject {
+    fn encode(&self, coder: &NSCoder) {
+        coder.encode_object("name", &*self.ivars().name);
+        coder.encode_object("value", &*self.ivars().value);
+    }
+
+    fn decode(coder: &NSCoder) -> Option<Ivars> {
+        Some(Ivars {
+            name: coder.decode_object("name")?,
+            value: coder.decode_object("value")?,
+        })
+    }
+}
+
+impl TestObject {
+    fn new(name: &str, value: i32) -> Retained<Self> {
+        let this = Self::alloc().set_ivars(Ivars {
+            name: NSString::from_str(name),
+            value: NSNumber::new_i32(value),
+        });
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+#[test]
+fn allowed_classes() {
+    let allowed = NSAllowedClasses::new()
+        .with::<NSString>()
+        .with::<NSNumber>()
+        .with::<NSString>();
+    assert_eq!(allowed.classes().len(), 2);
+    assert!(allowed.contains(NSString::class()));
+    assert!(allowed.contains(NSNumber::class()));
+    assert!(!allowed.contains(NSArray::<AnyObject>::class()));
+}
+
+#[test]
+fn round_trip_defined_class() {
+    let object = TestObject::new("abc", 42);
+    let data = NSKeyedArchiver::archive(&*object).unwrap();
+
+    let allowed = NSAllowedClasses::new()
+        .with::<NSString>()
+        .with::<NSNumber>();
+    let decoded = NSKeyedUnarchiver::unarchive::<TestObject>(&data, &allowed).unwrap();
+    assert_eq!(decoded.ivars().name.to_string(), "abc");
+    assert_eq!(decoded.ivars().value.as_i32(), 42);
+}
+
+#[test]
+fn round_trip_array() {
+    let array = NSArray::from_retained_slice(&[NSString::from_str("a"), NSString::from_str("b")]);
+    let data = NSKeyedArchiver::archive(&*array).unwrap();
+
+    let allowed = NSAllowedClasses::new().with::<NSString>();
+    let decoded = NSKeyedUnarchiver::unarchive::<NSArray>(&data, &allowed).unwrap();
+    assert_eq!(decoded.count(), 2);
+
+    // Unarchiving fails when the contents are not allowed.
+    let res = NSKeyedUnarchiver::unarchive::<NSArray>(&data, &NSAllowedClasses::new());
+    assert!(res.is_err());
+}
+
+#[test]
+fn wrong_root_class() {
+    let data = NSKeyedArchiver::archive(&*NSString::from_str("abc")).unwrap();
+
+    let err =
+        NSKeyedUnarchiver::unarchive::<NSNumber>(&data, &NSAllowedClasses::new()).unwrap_err();
+    assert_eq!(&*err.domain(), unsafe { NSCocoaErrorDomain });
+    assert_eq!(err.code(), NSCoderReadCorruptError);
+}
+
+#[test]
+fn invalid_data() {
+    let data = crate::NSData::with_bytes(b"not an archive");
+    let res = NSKeyedUnarchiver::unarchive::<NSString>(&data, &NSAllowedClasses::new());
+    assert!(res.is_err());
+}
@@ -0,0 +1,85 @@
+use alloc::vec::Vec;
+
+use crate::LocalizedKey;
+
+#[test]
+fn parse_section() {
+    let keys: Vec<_> = LocalizedKey::parse_section(b"").collect();
+    assert_eq!(keys, []);
+
+    let section = b"\0a\0\0\0Table\0b\0B\0Comment\0";
+    let keys: Vec<_> = LocalizedKey::parse_section(section).collect();
+    assert_eq!(
+        keys,
+        [
+            LocalizedKey {
+                table: None,
+                key: "a",
+                value: None,
+                comment: None,
+            },
+            LocalizedKey {
+                table: Some("Table"),
+                key: "b",
+                value: Some("B"),
+                comment: Some("Comment"),
+            },
+        ]
+    );
+
+    // Truncated and invalid records stop parsing.
+    let keys: Vec<_> = LocalizedKey::parse_section(b"\0a\0\0\0\0b\0").collect();
+    assert_eq!(keys.len(), 1);
+    let mut iter = LocalizedKey::parse_section(b"\0\xff\0\0\0\0a\0\0\0");
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+#[cfg(all(feature = "NSBundle", feature = "NSString"))]
+fn record_format() {
+    const ENTRY: &str = concat!("Table", "\0", "key", "\0", "\0", "A comment", "\0");
+    let entry: [u8; ENTRY.len()] = crate::__ns_macro_helpers::localized_entry(ENTRY);
+    let keys: Vec<_> = LocalizedKey::parse_section(&entry).collect();
+    assert_eq!(
+        keys,
+        [LocalizedKey {
+            table: Some("Table"),
+            key: "key",
+            value: None,
+            comment: Some("A comment"),
+        }]
+    );
+}
+
+#[test]
+#[cfg(all(feature = "NSBundle", feature = "NSString"))]
+fn lookup_untranslated() {
+    use crate::NSBundle;
+
+    // The test binary has no strings files, so the key or the default value
+    // is returned.
+    assert_eq!(crate::localized!("Hello"), "Hello");
+    assert_eq!(crate::localized!("Hello", comment = "A greeting"), "Hello");
+    assert_eq!(
+        crate::localized!(
+            "greeting",
+            table = "Greetings",
+            value = "Hi",
+            comment = "A greeting"
+        ),
+        "Hi"
+    );
+
+    let bundle = NSBundle::mainBundle();
+    assert_eq!(crate::localized!("Hello", bundle = &bundle,), "Hello");
+    assert_eq!(
+        crate::localized!(
+            "Hello",
+            table = "Greetings",
+            bundle = &*bundle,
+            value = "Hi"
+        ),
+        "Hi"
+    );
+}
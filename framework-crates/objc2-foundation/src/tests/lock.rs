@@ -1,5 +1,8 @@
 #![cfg(feature = "NSLock")]
-use crate::{NSLock, NSLocking};
+#[cfg(feature = "NSDate")]
+use core::time::Duration;
+
+use crate::{NSCondition, NSLock, NSLocking, NSRecursiveLock};
 
 #[test]
 fn lock_unlock() {
@@ -12,3 +15,45 @@ fn lock_unlock() {
         lock.unlock();
     }
 }
+
+#[test]
+fn lock_guard() {
+    let lock = NSLock::new();
+    {
+        let _guard = lock.lock_guard();
+        assert!(lock.try_lock().is_none());
+    }
+    let guard = lock.try_lock().expect("lock was released");
+    drop(guard);
+    assert!(lock.try_lock().is_some());
+}
+
+#[test]
+fn recursive_lock_guard() {
+    // SAFETY: Creating a lock has no preconditions.
+    let lock = unsafe { NSRecursiveLock::new() };
+    let _guard1 = lock.lock_guard();
+    let _guard2 = lock.lock_guard();
+    assert!(lock.try_lock().is_some());
+}
+
+#[test]
+fn condition_guard() {
+    // SAFETY: Creating a condition has no preconditions.
+    let condition = unsafe { NSCondition::new() };
+    let guard = condition.lock_guard();
+    guard.notify_one();
+    guard.notify_all();
+    // Doesn't block when the condition is already satisfied.
+    guard.wait_while(|| false);
+}
+
+#[test]
+#[cfg(feature = "NSDate")]
+fn condition_wait_timeout() {
+    // SAFETY: Creating a condition has no preconditions.
+    let condition = unsafe { NSCondition::new() };
+    let guard = condition.lock_guard();
+    guard.notify_all();
+    assert!(!guard.wait_timeout(Duration::from_millis(10)));
+}
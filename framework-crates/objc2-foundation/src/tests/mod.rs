@@ -1,24 +1,50 @@
+mod apple_event_descriptor;
 mod array;
 mod attributed_string;
 mod auto_traits;
+mod bridging_arena;
 mod bundle;
+mod cache;
+mod channel;
 mod data;
+mod data_reader;
+mod date;
 mod decimal_number;
 mod dictionary;
 mod error;
 mod exception;
+mod file_handle;
+mod file_manager;
+mod index_path;
+mod index_set;
+mod json;
+mod keyed_archive;
+mod localized;
 mod lock;
 mod mutable_array;
 mod mutable_data;
 mod mutable_dictionary;
 mod mutable_set;
 mod mutable_string;
+mod notification;
+mod null;
 mod number;
+mod operation;
 mod process_info;
+mod progress;
 mod proxy;
+mod regular_expression;
+mod run_loop;
 mod set;
+mod stream;
 mod string;
 mod task;
 mod thread;
+mod toll_free;
+mod ubiquitous_key_value_store;
+mod url;
+mod url_request;
+mod user_defaults;
 mod uuid;
 mod value;
+mod xpc_connection;
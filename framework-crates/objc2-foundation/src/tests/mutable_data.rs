@@ -56,7 +56,7 @@ fn test_from_data() {
 #[test]
 fn test_with_capacity() {
     let data = NSMutableData::dataWithCapacity(5).unwrap();
-    assert_eq!(data.to_vec(), &[]);
+    assert_eq!(data.to_vec(), &[0u8; 0]);
     data.extend_from_slice(&[1, 2, 3, 4, 5]);
     assert_eq!(data.to_vec(), &[1, 2, 3, 4, 5]);
     data.extend_from_slice(&[6, 7]);
@@ -71,3 +71,15 @@ fn test_extend() {
     (&data).extend(&*NSData::with_bytes(&[6, 7]));
     assert_eq!(data.to_vec(), &[1, 2, 3, 4, 5, 6, 7]);
 }
+
+#[test]
+#[cfg(feature = "std")]
+fn test_io_write() {
+    use std::io::Write;
+
+    let mut data = NSMutableData::new();
+    write!(data, "{}-{}", 1, 2).unwrap();
+    data.write_all(b"abc").unwrap();
+    data.flush().unwrap();
+    assert_eq!(data.to_vec(), b"1-2abc");
+}
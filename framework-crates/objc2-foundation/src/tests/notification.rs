@@ -0,0 +1,92 @@
+#![cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSString"
+))]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use objc2::runtime::NSObject;
+
+use crate::{ns_string, NSNotificationCenter};
+
+#[test]
+fn observer_filters_by_object() {
+    let center = unsafe { NSNotificationCenter::new() };
+    let name = ns_string!("ObserverFiltersByObject");
+    let object = NSObject::new();
+    let other = NSObject::new();
+
+    let count = Arc::new(AtomicUsize::new(0));
+    let observer = center.add_observer(Some(name), Some(&object), {
+        let count = Arc::clone(&count);
+        move |_| {
+            count.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    unsafe { center.postNotificationName_object(name, Some(&object)) };
+    unsafe { center.postNotificationName_object(name, Some(&other)) };
+    unsafe { center.postNotificationName_object(ns_string!("Other"), Some(&object)) };
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+
+    drop(observer);
+    unsafe { center.postNotificationName_object(name, Some(&object)) };
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+#[cfg(feature = "NSLock")]
+fn stream() {
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+
+    let center = unsafe { NSNotificationCenter::new() };
+    let name = ns_string!("Stream");
+
+    let stream = center.notifications(name, None, |notification| {
+        let object = unsafe { notification.object() };
+        object.map(|object| Retained::as_ptr(&object) as usize)
+    });
+    assert_eq!(stream.try_next(), None);
+
+    let object = NSObject::new();
+    unsafe { center.postNotificationName_object(name, None) };
+    unsafe { center.postNotificationName_object(name, Some(&object)) };
+
+    assert_eq!(stream.try_next(), Some(None));
+    let ptr: *const AnyObject = Retained::as_ptr(&object).cast();
+    assert_eq!(stream.recv(), Some(Some(ptr as usize)));
+    assert_eq!(stream.try_next(), None);
+}
+
+#[test]
+#[cfg(feature = "NSLock")]
+fn typed() {
+    use crate::{NSNotification, NSNotificationName, NSNotificationPayload};
+
+    #[derive(Debug, PartialEq)]
+    struct Typed {
+        has_object: bool,
+    }
+
+    impl NSNotificationPayload for Typed {
+        fn name() -> &'static NSNotificationName {
+            ns_string!("Typed")
+        }
+
+        fn from_notification(notification: &NSNotification) -> Self {
+            Self {
+                has_object: unsafe { notification.object() }.is_some(),
+            }
+        }
+    }
+
+    let center = unsafe { NSNotificationCenter::new() };
+    let stream = center.typed_notifications::<Typed>(None);
+
+    unsafe { center.postNotificationName_object(Typed::name(), None) };
+    assert_eq!(stream.try_next(), Some(Typed { has_object: false }));
+}
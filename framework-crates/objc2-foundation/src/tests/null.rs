@@ -0,0 +1,61 @@
+#![cfg(feature = "NSNull")]
+#![cfg(feature = "NSObject")]
+#[cfg(any(feature = "NSArray", feature = "NSDictionary"))]
+use objc2::rc::Retained;
+#[cfg(any(feature = "NSArray", feature = "NSDictionary"))]
+use objc2::runtime::AnyObject;
+
+use crate::{NSNull, NSObject};
+
+#[cfg(any(feature = "NSArray", feature = "NSDictionary"))]
+fn is_obj(obj: Option<Retained<AnyObject>>, expected: &NSObject) -> bool {
+    obj.is_some_and(|obj| core::ptr::eq(&*obj, &**expected))
+}
+
+#[test]
+fn is_null() {
+    let null = unsafe { NSNull::null() };
+    assert!(NSNull::is_null(&null));
+    assert!(!NSNull::is_null(&NSObject::new()));
+}
+
+#[test]
+#[cfg(feature = "NSArray")]
+fn array() {
+    use crate::NSMutableArray;
+
+    let obj = NSObject::new();
+    let array = NSMutableArray::<AnyObject>::new();
+    array.push_option(Some(&obj));
+    array.push_option(None);
+    assert_eq!(array.len(), 2);
+    assert!(NSNull::is_null(&array.objectAtIndex(1)));
+
+    assert!(is_obj(array.get_nonnull(0), &obj));
+    assert!(array.get_nonnull(1).is_none());
+    assert!(array.get_nonnull(2).is_none());
+
+    let mut objects = array.to_vec_nonnull().into_iter();
+    assert!(is_obj(objects.next().unwrap(), &obj));
+    assert!(objects.next().unwrap().is_none());
+    assert!(objects.next().is_none());
+}
+
+#[test]
+#[cfg(all(feature = "NSDictionary", feature = "NSString"))]
+fn dictionary() {
+    use crate::{ns_string, NSMutableDictionary, NSString};
+
+    let obj = NSObject::new();
+    let dict = NSMutableDictionary::<NSString, AnyObject>::new();
+    dict.insert_option(ns_string!("a"), Some(&obj));
+    dict.insert_option(ns_string!("b"), None);
+    assert_eq!(dict.len(), 2);
+
+    assert!(is_obj(dict.get_nonnull(ns_string!("a")), &obj));
+    assert!(dict.get_nonnull(ns_string!("b")).is_none());
+    assert!(dict.get_nonnull(ns_string!("c")).is_none());
+    assert!(NSNull::is_null(
+        &dict.objectForKey(ns_string!("b")).unwrap()
+    ));
+}
@@ -129,3 +129,73 @@ fn display_debug() {
     assert_display_debug(NSNumber::new_bool(true), "1");
     assert_display_debug(NSNumber::new_bool(false), "0");
 }
+
+#[test]
+fn try_from() {
+    let number = NSNumber::new_i32(-1);
+    assert_eq!(i8::try_from(&*number), Ok(-1));
+    assert_eq!(i128::try_from(&*number), Ok(-1));
+    assert!(u8::try_from(&*number).is_err());
+    assert!(usize::try_from(&*number).is_err());
+    assert_eq!(f32::try_from(&*number), Ok(-1.0));
+
+    let number = NSNumber::new_u64(u64::MAX);
+    assert_eq!(u64::try_from(&*number), Ok(u64::MAX));
+    assert_eq!(u128::try_from(&*number), Ok(u64::MAX as u128));
+    assert!(i64::try_from(&*number).is_err());
+    // Not exactly representable.
+    assert!(f64::try_from(&*number).is_err());
+
+    let number = NSNumber::new_i64(i64::MAX);
+    assert!(f64::try_from(&*number).is_err());
+    let number = NSNumber::new_i64(1 << 53);
+    assert_eq!(f64::try_from(&*number), Ok((1u64 << 53) as f64));
+
+    let number = NSNumber::new_u16(300);
+    assert_eq!(u16::try_from(&*number), Ok(300));
+    assert!(u8::try_from(&*number).is_err());
+    assert!(i8::try_from(&*number).is_err());
+}
+
+#[test]
+fn try_from_float() {
+    let number = NSNumber::new_f64(42.0);
+    assert_eq!(u8::try_from(&*number), Ok(42));
+    assert_eq!(i64::try_from(&*number), Ok(42));
+    assert_eq!(f32::try_from(&*number), Ok(42.0));
+
+    let number = NSNumber::new_f64(-2.5);
+    assert!(i32::try_from(&*number).is_err());
+    assert_eq!(f64::try_from(&*number), Ok(-2.5));
+    assert_eq!(f32::try_from(&*number), Ok(-2.5));
+
+    let number = NSNumber::new_f64(0.1);
+    assert!(f32::try_from(&*number).is_err());
+
+    let number = NSNumber::new_f64(1e30);
+    assert!(u64::try_from(&*number).is_err());
+    assert!(i128::try_from(&*number).is_err());
+    assert_eq!(u128::try_from(&*number), Ok(1e30 as u128));
+
+    for val in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        let number = NSNumber::new_f64(val);
+        assert!(i32::try_from(&*number).is_err());
+        assert!(u128::try_from(&*number).is_err());
+    }
+    assert!(f32::try_from(&*NSNumber::new_f64(f64::NAN))
+        .unwrap()
+        .is_nan());
+    assert_eq!(
+        f32::try_from(&*NSNumber::new_f64(f64::INFINITY)),
+        Ok(f32::INFINITY)
+    );
+}
+
+#[test]
+fn try_from_bool() {
+    assert_eq!(bool::try_from(&*NSNumber::new_bool(true)), Ok(true));
+    assert_eq!(bool::try_from(&*NSNumber::new_bool(false)), Ok(false));
+    assert_eq!(bool::try_from(&*NSNumber::new_f32(1.0)), Ok(true));
+    assert!(bool::try_from(&*NSNumber::new_i32(2)).is_err());
+    assert!(bool::try_from(&*NSNumber::new_i32(-1)).is_err());
+}
@@ -0,0 +1,56 @@
+#![cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSLock",
+    feature = "NSOperation"
+))]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::{NSOperationQueue, NSOperationTask};
+
+#[test]
+fn spawn() {
+    let queue = unsafe { NSOperationQueue::new() };
+    let task = queue.spawn(|| 42);
+    assert_eq!(task.wait(), Some(42));
+    assert!(task.is_finished());
+    assert!(!task.is_cancelled());
+    // The result is only returned once.
+    assert_eq!(task.try_result(), None);
+}
+
+#[test]
+fn dependencies() {
+    let queue = unsafe { NSOperationQueue::new() };
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    let first = NSOperationTask::new({
+        let counter = Arc::clone(&counter);
+        move || counter.fetch_add(1, Ordering::SeqCst)
+    });
+    let second = NSOperationTask::new({
+        let counter = Arc::clone(&counter);
+        move || counter.fetch_add(1, Ordering::SeqCst)
+    });
+    second.add_dependency(first.operation());
+
+    queue.add_task(&second);
+    queue.add_task(&first);
+
+    assert_eq!(second.wait(), Some(1));
+    assert_eq!(first.wait(), Some(0));
+}
+
+#[test]
+fn cancelled() {
+    let queue = unsafe { NSOperationQueue::new() };
+    unsafe { queue.setSuspended(true) };
+
+    let task = queue.spawn(|| 42);
+    task.cancel();
+    assert!(task.is_cancelled());
+
+    unsafe { queue.setSuspended(false) };
+    assert_eq!(task.wait(), None);
+}
@@ -1,7 +1,9 @@
 #![cfg(feature = "NSProcessInfo")]
 use alloc::format;
 
-use crate::NSProcessInfo;
+#[cfg(feature = "NSString")]
+use crate::NSActivityOptions;
+use crate::{NSProcessInfo, NSProcessInfoThermalState};
 
 #[test]
 fn debug() {
@@ -25,3 +27,25 @@ fn encoding_operating_system_version() {
     let info = NSProcessInfo::processInfo();
     let _version = info.operatingSystemVersion();
 }
+
+#[test]
+#[cfg_attr(not(target_vendor = "apple"), ignore = "only on Apple")]
+fn thermal_state() {
+    let info = NSProcessInfo::processInfo();
+    let state = info.thermal_state();
+    assert!(NSProcessInfoThermalState::Nominal <= state);
+    assert!(state <= NSProcessInfoThermalState::Critical);
+    let _ = info.is_low_power_mode_enabled();
+}
+
+#[test]
+#[cfg(feature = "NSString")]
+#[cfg_attr(not(target_vendor = "apple"), ignore = "only on Apple")]
+fn activity() {
+    let info = NSProcessInfo::processInfo();
+    let activity = info.begin_activity(NSActivityOptions::UserInitiated, "Testing");
+    drop(activity);
+
+    let res = info.with_activity(NSActivityOptions::Background, "Testing", || 42);
+    assert_eq!(res, 42);
+}
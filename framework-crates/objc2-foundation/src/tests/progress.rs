@@ -0,0 +1,61 @@
+#![cfg(feature = "NSProgress")]
+use crate::NSProgress;
+
+#[test]
+fn test_children() {
+    let parent = NSProgress::with_total(10);
+    let child = parent.new_child(4, 6);
+    assert_eq!(parent.total(), 10);
+    assert_eq!(parent.completed(), 0);
+
+    child.set_completed(2);
+    assert_eq!(parent.completed(), 0);
+    assert!((parent.fraction_completed() - 0.3).abs() < f64::EPSILON);
+
+    child.increment(2);
+    assert_eq!(parent.completed(), 6);
+
+    let other = NSProgress::with_total(1);
+    parent.add_child(&other, 4);
+    other.set_completed(1);
+    assert_eq!(parent.completed(), 10);
+}
+
+#[test]
+fn test_with_current() {
+    let parent = NSProgress::with_total(2);
+    let child = parent.with_current(2, || {
+        assert_eq!(
+            unsafe { NSProgress::currentProgress() }.as_deref(),
+            Some(&*parent)
+        );
+        unsafe { NSProgress::progressWithTotalUnitCount(1) }
+    });
+    assert_eq!(unsafe { NSProgress::currentProgress() }, None);
+
+    child.set_completed(1);
+    assert_eq!(parent.completed(), 2);
+}
+
+#[test]
+#[cfg(all(
+    feature = "std",
+    feature = "NSDictionary",
+    feature = "NSKeyValueObserving",
+    feature = "NSLock",
+    feature = "NSNull",
+    feature = "NSString",
+    feature = "NSValue"
+))]
+fn test_fraction_completed_changes() {
+    let progress = NSProgress::with_total(2);
+    let changes = progress.fraction_completed_changes();
+    assert_eq!(changes.try_next(), Some(0.0));
+    assert_eq!(changes.try_next(), None);
+
+    progress.increment(1);
+    assert_eq!(changes.try_next(), Some(0.5));
+
+    drop(changes);
+    progress.increment(1);
+}
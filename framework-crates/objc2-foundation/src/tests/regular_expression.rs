@@ -0,0 +1,92 @@
+#![cfg(all(
+    feature = "NSArray",
+    feature = "NSEnumerator",
+    feature = "NSError",
+    feature = "NSRange",
+    feature = "NSRegularExpression",
+    feature = "NSString",
+    feature = "NSTextCheckingResult"
+))]
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::{NSRegularExpression, NSRegularExpressionOptions};
+
+#[test]
+fn invalid_pattern() {
+    assert!(NSRegularExpression::from_pattern("(unclosed").is_err());
+}
+
+#[test]
+fn find_ascii() {
+    let regex = NSRegularExpression::from_pattern("[0-9]+").unwrap();
+    let haystack = "a1 b22 c333";
+    assert!(regex.is_match(haystack));
+    assert!(!regex.is_match("abc"));
+
+    let m = regex.find(haystack).unwrap();
+    assert_eq!(m.range(), 1..2);
+    assert_eq!(m.as_str(), "1");
+
+    let matches: Vec<_> = regex.find_iter(haystack).map(|m| m.as_str()).collect();
+    assert_eq!(matches, ["1", "22", "333"]);
+    assert_eq!(regex.find("abc"), None);
+}
+
+#[test]
+fn find_non_ascii() {
+    let regex = NSRegularExpression::from_pattern("b+").unwrap();
+    // "é" is two bytes and one UTF-16 code unit, "🦀" is four bytes and two
+    // UTF-16 code units.
+    let haystack = "é🦀bb🦀b";
+    let ranges: Vec<_> = regex.find_iter(haystack).map(|m| m.range()).collect();
+    assert_eq!(ranges, [6..8, 12..13]);
+
+    let regex = NSRegularExpression::from_pattern("🦀.").unwrap();
+    let matches: Vec<_> = regex.find_iter(haystack).map(|m| m.as_str()).collect();
+    assert_eq!(matches, ["🦀b", "🦀b"]);
+}
+
+#[test]
+fn captures() {
+    let regex = NSRegularExpression::from_pattern(r"(?<key>\w+)=(\d+)?").unwrap();
+    let captures = regex.captures("ø: size=12").unwrap();
+    assert_eq!(captures.len(), 3);
+    assert_eq!(&captures[0], "size=12");
+    assert_eq!(&captures[1], "size");
+    assert_eq!(captures.get(2).unwrap().range(), 9..11);
+    assert_eq!(captures.get(3), None);
+    assert_eq!(captures.name("key").unwrap().as_str(), "size");
+    assert_eq!(captures.name("missing"), None);
+    assert_eq!(captures.name("not valid"), None);
+
+    let captures = regex.captures("name=").unwrap();
+    assert_eq!(&captures[1], "name");
+    assert_eq!(captures.get(2), None);
+    let groups: Vec<_> = captures.iter().map(|m| m.map(|m| m.as_str())).collect();
+    assert_eq!(groups, [Some("name="), Some("name"), None]);
+}
+
+#[test]
+fn options() {
+    let regex =
+        NSRegularExpression::with_options("abc", NSRegularExpressionOptions::CaseInsensitive)
+            .unwrap();
+    assert_eq!(regex.find("xABC").unwrap().as_str(), "ABC");
+}
+
+#[test]
+fn replace_all() {
+    let regex = NSRegularExpression::from_pattern(r"(\w)(\w*)").unwrap();
+    let replaced = regex.replace_all("hello wørld", |captures| {
+        format!("{}{}", captures[1].to_uppercase(), &captures[2])
+    });
+    assert_eq!(replaced, "Hello Wørld");
+
+    // Templates are not expanded.
+    let replaced = regex.replace_all("a b", |_| "$1");
+    assert_eq!(replaced, "$1 $1");
+
+    let replaced = regex.replace_all("  ", |_| "x");
+    assert_eq!(replaced, "  ");
+}
@@ -0,0 +1,70 @@
+#![cfg(all(
+    feature = "NSRunLoop",
+    feature = "NSDate",
+    feature = "NSObjCRuntime",
+    feature = "NSString"
+))]
+use core::time::Duration;
+
+use crate::NSRunLoop;
+
+#[test]
+fn run_until_without_sources() {
+    let run_loop = NSRunLoop::current();
+    assert!(run_loop.run_until(Duration::from_secs(10), || true));
+    // Returns immediately since there is nothing to wait for.
+    assert!(!run_loop.run_until(Duration::from_secs(10), || false));
+}
+
+#[test]
+#[cfg(all(feature = "NSTimer", feature = "block2"))]
+fn timer() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use crate::{NSDefaultRunLoopMode, NSTimer};
+
+    let count = Rc::new(Cell::new(0));
+    let timer = NSTimer::with_block(Duration::from_millis(1), true, {
+        let count = count.clone();
+        move |_| count.set(count.get() + 1)
+    });
+
+    let run_loop = NSRunLoop::current();
+    run_loop.add_timer(&timer, unsafe { NSDefaultRunLoopMode });
+    assert!(run_loop.run_until(Duration::from_secs(10), || count.get() >= 3));
+
+    unsafe { timer.invalidate() };
+    assert!(!unsafe { timer.isValid() });
+}
+
+#[test]
+#[cfg(all(feature = "std", feature = "NSPort", feature = "NSThread"))]
+fn block_on() {
+    use std::sync::mpsc;
+    use std::task::Poll;
+
+    let run_loop = NSRunLoop::current();
+    assert_eq!(run_loop.block_on(async { 42 }), 42);
+
+    // Wake the future from another thread.
+    let (sender, receiver) = mpsc::channel();
+    let mut spawned = false;
+    let result = run_loop.block_on(core::future::poll_fn(|cx| {
+        if let Ok(value) = receiver.try_recv() {
+            return Poll::Ready(value);
+        }
+        if !spawned {
+            spawned = true;
+            let sender = sender.clone();
+            let waker = cx.waker().clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(10));
+                sender.send(7).unwrap();
+                waker.wake();
+            });
+        }
+        Poll::Pending
+    }));
+    assert_eq!(result, 7);
+}
@@ -0,0 +1,41 @@
+#![cfg(all(
+    feature = "std",
+    feature = "NSStream",
+    feature = "NSData",
+    feature = "NSError",
+    feature = "NSString"
+))]
+use alloc::vec::Vec;
+use std::io::{self, Read, Write};
+
+use crate::{NSData, NSInputStream, NSOutputStream, NSStreamDataWrittenToMemoryStreamKey};
+
+#[test]
+fn read_write_memory() {
+    let data = NSData::with_bytes(b"hello world");
+    let input = unsafe { NSInputStream::inputStreamWithData(&data) }.unwrap();
+    unsafe { input.open() };
+    let mut contents = Vec::new();
+    (&*input).read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, b"hello world");
+    // Reading at the end never blocks.
+    assert_eq!(input.try_read(&mut [0; 4]).unwrap(), 0);
+    unsafe { input.close() };
+
+    let output = unsafe { NSOutputStream::outputStreamToMemory() };
+    unsafe { output.open() };
+    (&*output).write_all(b"hello ").unwrap();
+    output.try_write(b"world").unwrap();
+    let written = unsafe { output.propertyForKey(NSStreamDataWrittenToMemoryStreamKey) }.unwrap();
+    let written: &NSData = written.downcast_ref().unwrap();
+    assert_eq!(written.to_vec(), b"hello world");
+    unsafe { output.close() };
+}
+
+#[test]
+fn not_open() {
+    let data = NSData::with_bytes(b"hello");
+    let input = unsafe { NSInputStream::inputStreamWithData(&data) }.unwrap();
+    let err = (&*input).read(&mut [0; 4]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotConnected);
+}
@@ -5,7 +5,7 @@ use alloc::string::ToString;
 use objc2::rc::autoreleasepool;
 use objc2::{msg_send, sel, ClassType};
 
-use crate::{ns_string, NSObjectProtocol, NSString};
+use crate::{ns_format, ns_string, NSMutableString, NSObjectProtocol, NSString};
 
 #[test]
 fn test_equality() {
@@ -55,6 +55,20 @@ fn test_utf8() {
     assert_eq!(s.to_string(), expected);
 }
 
+#[test]
+fn test_as_str_fast() {
+    let ascii = NSString::from_str("Hello, World!");
+    let utf8 = NSString::from_str("ประเทศไทย中华Việt Nam");
+    let mutable = NSMutableString::from_str("abc");
+    autoreleasepool(|pool| unsafe {
+        assert_eq!(ascii.as_str_fast(pool), Some("Hello, World!"));
+        assert_eq!(utf8.as_str_fast(pool), Some("ประเทศไทย中华Việt Nam"));
+        assert_eq!(mutable.as_str_fast(pool), Some("abc"));
+        assert_eq!(NSString::new().as_str_fast(pool), Some(""));
+        assert_eq!(NSString::from_str("a\0b").as_str_fast(pool), Some("a\0b"));
+    });
+}
+
 #[test]
 fn test_nul() {
     let expected = "\0";
@@ -258,6 +272,18 @@ fn test_macro() {
     }
 }
 
+#[test]
+fn test_format_macro() {
+    assert_eq!(ns_format!("abc").to_string(), "abc");
+    assert_eq!(ns_format!("").to_string(), "");
+
+    let s = ns_format!("{} + {} = {:?}", 1, 2.5, "3.5");
+    assert_eq!(s.to_string(), "1 + 2.5 = \"3.5\"");
+
+    let s = ns_format!("{}{}", NSString::from_str("ประเทศไทย"), "中华");
+    assert_eq!(s.to_string(), "ประเทศไทย中华");
+}
+
 #[test]
 fn test_macro_in_unsafe() {
     // Test that the `unused_unsafe` lint doesn't trigger
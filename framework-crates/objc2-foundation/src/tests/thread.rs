@@ -55,3 +55,30 @@ fn test_debug() {
         "Expected one of {expected:?}, got {actual:?}",
     );
 }
+
+#[test]
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSLock",
+    feature = "NSObjCRuntime",
+    feature = "NSString"
+))]
+fn test_spawn() {
+    use alloc::string::ToString;
+
+    use crate::NSQualityOfService;
+
+    let handle = NSThread::spawn("objc2-test", NSQualityOfService::Utility, || {
+        let current = NSThread::currentThread();
+        assert!(NSThread::main_thread_marker().is_none());
+        current.name().unwrap().to_string()
+    });
+    assert_eq!(handle.join().unwrap(), "objc2-test");
+
+    let handle = NSThread::spawn("objc2-test-panic", NSQualityOfService::Default, || {
+        panic!("oh no");
+    });
+    let payload = handle.join().unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"oh no"));
+}
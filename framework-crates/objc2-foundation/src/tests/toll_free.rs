@@ -0,0 +1,78 @@
+#![cfg(feature = "objc2-core-foundation")]
+#![cfg(feature = "NSString")]
+use objc2::rc::Retained;
+use objc2::runtime::NSObjectProtocol;
+use objc2_core_foundation::{CFRetained, CFString};
+
+use crate::{ns_string, NSString};
+
+#[test]
+fn test_string_roundtrip() {
+    let string = NSString::from_str("abc");
+    let cf: &CFString = string.as_cf_string();
+    let ns: &NSString = cf.as_ref();
+    assert_eq!(ns, &*string);
+
+    let cf: CFRetained<CFString> = NSString::into_cf_string(string);
+    let string: Retained<NSString> = NSString::from_cf_string(cf);
+    assert_eq!(&*string, ns_string!("abc"));
+}
+
+#[test]
+fn test_retain_count_preserved() {
+    let string = NSString::from_str("a string that is not a tagged pointer");
+    let copy = string.clone();
+    let rc = copy.retainCount();
+
+    let cf = NSString::into_cf_string(copy);
+    assert_eq!(string.retainCount(), rc);
+    let copy = NSString::from_cf_string(cf);
+    assert_eq!(copy.retainCount(), rc);
+
+    let retained: CFRetained<CFString> = string.as_cf_string().into();
+    assert_eq!(string.retainCount(), rc + 1);
+    drop(retained);
+    assert_eq!(string.retainCount(), rc);
+}
+
+#[test]
+#[cfg(feature = "NSArray")]
+fn test_array() {
+    use crate::NSArray;
+
+    let array = NSArray::from_retained_slice(&[NSString::from_str("a"), NSString::from_str("b")]);
+    assert_eq!(array.as_cf_array().len(), 2);
+
+    let cf = NSArray::into_cf_array(array);
+    // SAFETY: The array was created by Foundation, and contains strings.
+    let array: Retained<NSArray<NSString>> = unsafe { NSArray::from_cf_array_unchecked(cf) };
+    assert_eq!(&*array.objectAtIndex(1), ns_string!("b"));
+}
+
+#[test]
+#[cfg(feature = "NSData")]
+fn test_data() {
+    use crate::NSData;
+
+    let data = NSData::with_bytes(&[1, 2, 3]);
+    assert_eq!(data.as_cf_data().len(), 3);
+    let data = NSData::from_cf_data(NSData::into_cf_data(data));
+    assert_eq!(data.to_vec(), [1, 2, 3]);
+}
+
+#[test]
+#[cfg(all(feature = "NSError", feature = "NSDictionary"))]
+fn test_error() {
+    use crate::NSError;
+
+    let error = NSError::new(2, ns_string!("MyDomain"));
+    let cf = NSError::into_cf_error(error);
+    assert_eq!(cf.code(), 2);
+    assert_eq!(
+        &*NSString::from_cf_string(cf.domain()),
+        ns_string!("MyDomain")
+    );
+
+    let error = NSError::from_cf_error(cf);
+    assert_eq!(error.code(), 2);
+}
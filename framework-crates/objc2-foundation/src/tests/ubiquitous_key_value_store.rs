@@ -0,0 +1,19 @@
+#![cfg(all(feature = "NSUbiquitousKeyValueStore", feature = "NSString"))]
+use crate::{
+    NSUbiquitousKeyValueStoreAccountChange, NSUbiquitousKeyValueStoreChangeReason,
+    NSUbiquitousKeyValueStoreServerChange,
+};
+
+#[test]
+fn change_reason_raw() {
+    let reason =
+        NSUbiquitousKeyValueStoreChangeReason::from_raw(NSUbiquitousKeyValueStoreServerChange);
+    assert_eq!(reason, NSUbiquitousKeyValueStoreChangeReason::ServerChange);
+
+    let reason = NSUbiquitousKeyValueStoreChangeReason::AccountChange;
+    assert_eq!(reason.to_raw(), NSUbiquitousKeyValueStoreAccountChange);
+
+    let reason = NSUbiquitousKeyValueStoreChangeReason::from_raw(42);
+    assert_eq!(reason, NSUbiquitousKeyValueStoreChangeReason::Unknown(42));
+    assert_eq!(reason.to_raw(), 42);
+}
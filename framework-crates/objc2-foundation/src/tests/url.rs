@@ -0,0 +1,49 @@
+#![cfg(feature = "NSURL")]
+#![cfg(feature = "std")]
+#![cfg(unix)]
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+use crate::NSURL;
+
+#[test]
+fn path_roundtrip() {
+    let path = Path::new("/tmp/hello world/ü.txt");
+    let url = NSURL::from_path(path, false).unwrap();
+    assert!(unsafe { url.isFileURL() });
+    assert_eq!(url.to_path_buf().unwrap(), path);
+}
+
+#[test]
+fn non_utf8_path() {
+    let path = Path::new(OsStr::from_bytes(b"/tmp/\xff"));
+    let url = NSURL::from_path(path, false).unwrap();
+    assert_eq!(url.to_path_buf().unwrap(), path);
+}
+
+#[test]
+fn path_with_nul() {
+    assert!(NSURL::from_path(Path::new("/tmp/a\0b"), false).is_none());
+}
+
+#[test]
+#[cfg(feature = "NSString")]
+fn not_file_url() {
+    let url = unsafe { NSURL::URLWithString(crate::ns_string!("https://example.com/")) }.unwrap();
+    assert_eq!(url.to_path_buf(), None);
+}
+
+#[test]
+#[cfg(all(feature = "url", feature = "NSString"))]
+fn url_crate_roundtrip() {
+    let url = url::Url::parse("https://example.com/a%20b?c=d#e").unwrap();
+    let ns_url = NSURL::from_url(&url).unwrap();
+    assert_eq!(url::Url::try_from(&*ns_url).unwrap(), url);
+
+    let path = Path::new("/tmp/hello world");
+    let ns_url = NSURL::from_path(path, true).unwrap();
+    let url = ns_url.to_url().unwrap();
+    assert_eq!(url.as_str(), "file:///tmp/hello%20world/");
+    assert_eq!(url.to_file_path().unwrap(), path);
+}
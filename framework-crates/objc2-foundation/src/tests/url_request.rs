@@ -0,0 +1,74 @@
+#![cfg(all(
+    feature = "NSData",
+    feature = "NSDictionary",
+    feature = "NSString",
+    feature = "NSURL",
+    feature = "NSURLRequest"
+))]
+use alloc::string::ToString;
+
+use crate::{
+    ns_string, NSHTTPMethod, NSString, NSURLRequestBuilder, NSURLRequestBuilderError,
+    NSURLRequestCachePolicy, NSURL,
+};
+
+fn url() -> objc2::rc::Retained<NSURL> {
+    unsafe { NSURL::URLWithString(ns_string!("https://example.com/path")) }.unwrap()
+}
+
+#[test]
+fn build() {
+    let request = NSURLRequestBuilder::new(&url())
+        .method(NSHTTPMethod::Put)
+        .header("Accept", "text/plain")
+        .header("X-Multiple", "a")
+        .header("X-Multiple", "b")
+        .body_bytes(b"hello")
+        .cache_policy(NSURLRequestCachePolicy::ReloadIgnoringLocalCacheData)
+        .build()
+        .unwrap();
+
+    assert_eq!(unsafe { request.HTTPMethod() }.to_string(), "PUT");
+    let value = |name: &str| unsafe { request.valueForHTTPHeaderField(&NSString::from_str(name)) };
+    assert_eq!(value("accept").unwrap().to_string(), "text/plain");
+    assert_eq!(value("X-Multiple").unwrap().to_string(), "a,b");
+    assert_eq!(unsafe { request.HTTPBody() }.unwrap().to_vec(), b"hello");
+    assert_eq!(
+        unsafe { request.cachePolicy() },
+        NSURLRequestCachePolicy::ReloadIgnoringLocalCacheData
+    );
+}
+
+#[test]
+fn invalid() {
+    let err = NSURLRequestBuilder::new(&url())
+        .header("Bad Name", "value")
+        .header("X-Other", "bad\r\nvalue")
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        NSURLRequestBuilderError::InvalidHeaderName("Bad Name".into())
+    );
+
+    let err = NSURLRequestBuilder::new(&url())
+        .header("X-Other", "bad\r\nvalue")
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        NSURLRequestBuilderError::InvalidHeaderValue("X-Other".into())
+    );
+
+    let err = NSURLRequestBuilder::new(&url())
+        .custom_method("")
+        .build()
+        .unwrap_err();
+    assert_eq!(err, NSURLRequestBuilderError::InvalidMethod("".into()));
+
+    let request = NSURLRequestBuilder::new(&url())
+        .custom_method("PROPFIND")
+        .build()
+        .unwrap();
+    assert_eq!(unsafe { request.HTTPMethod() }.to_string(), "PROPFIND");
+}
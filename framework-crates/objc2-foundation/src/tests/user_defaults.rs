@@ -0,0 +1,113 @@
+#![cfg(all(
+    feature = "NSUserDefaults",
+    feature = "NSString",
+    feature = "NSArray",
+    feature = "NSValue"
+))]
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::NSUserDefaults;
+
+#[test]
+fn get_set() {
+    let defaults = NSUserDefaults::standard();
+    let key = "objc2-foundation.tests.user_defaults.get_set";
+
+    assert_eq!(defaults.get::<i64>(key), None);
+
+    defaults.set(key, &42i64);
+    assert_eq!(defaults.get::<i64>(key), Some(42));
+    assert_eq!(defaults.get::<u8>(key), None);
+    assert_eq!(defaults.get::<String>(key), None);
+
+    defaults.set(key, &true);
+    assert_eq!(defaults.get::<bool>(key), Some(true));
+
+    defaults.set(key, &1.5f64);
+    assert_eq!(defaults.get::<f64>(key), Some(1.5));
+
+    defaults.set(key, "abc");
+    assert_eq!(defaults.get::<String>(key).as_deref(), Some("abc"));
+    assert_eq!(defaults.get::<i64>(key), None);
+
+    defaults.remove(key);
+    assert_eq!(defaults.get::<String>(key), None);
+}
+
+#[test]
+fn arrays() {
+    let defaults = NSUserDefaults::standard();
+    let key = "objc2-foundation.tests.user_defaults.arrays";
+
+    defaults.set(key, &["a".to_string(), "b".to_string()][..]);
+    assert_eq!(
+        defaults.get::<Vec<String>>(key),
+        Some(vec!["a".to_string(), "b".to_string()])
+    );
+    // Elements of the wrong type.
+    assert_eq!(defaults.get::<Vec<i32>>(key), None);
+
+    defaults.set(key, &Vec::<i32>::new());
+    assert_eq!(defaults.get::<Vec<i32>>(key), Some(vec![]));
+
+    defaults.remove(key);
+}
+
+#[test]
+#[cfg(all(
+    feature = "std",
+    feature = "NSDictionary",
+    feature = "NSKeyValueObserving",
+    feature = "NSLock",
+    feature = "NSNull"
+))]
+fn changes() {
+    let defaults = NSUserDefaults::standard();
+    let key = "objc2FoundationTestsUserDefaultsChanges";
+
+    let changes = defaults.changes::<i32>(key);
+    assert_eq!(changes.try_next(), None);
+
+    defaults.set(key, &1i32);
+    defaults.set(key, "not a number");
+    defaults.remove(key);
+    assert_eq!(changes.try_next(), Some(Some(1)));
+    assert_eq!(changes.try_next(), Some(None));
+    assert_eq!(changes.try_next(), Some(None));
+    assert_eq!(changes.try_next(), None);
+
+    drop(changes);
+    defaults.set(key, &2i32);
+    defaults.remove(key);
+}
+
+#[test]
+#[cfg(all(
+    feature = "serde",
+    feature = "NSData",
+    feature = "NSDictionary",
+    feature = "NSObject"
+))]
+fn serialized() {
+    use alloc::collections::BTreeMap;
+
+    let defaults = NSUserDefaults::standard();
+    let key = "objc2-foundation.tests.user_defaults.serialized";
+
+    assert_eq!(
+        defaults.get_deserialized::<BTreeMap<String, u32>>(key),
+        Ok(None)
+    );
+
+    let value = BTreeMap::from([("a".to_string(), vec![1u32, 2]), ("b".to_string(), vec![])]);
+    defaults.set_serialized(key, &value).unwrap();
+    assert_eq!(defaults.get_deserialized(key), Ok(Some(value)));
+    assert!(defaults.get_deserialized::<Vec<u32>>(key).is_err());
+
+    // Not representable as a property list.
+    assert!(defaults.set_serialized(key, &()).is_err());
+
+    defaults.remove(key);
+}
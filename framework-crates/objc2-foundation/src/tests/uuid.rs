@@ -1,5 +1,6 @@
 #![cfg(feature = "NSUUID")]
 use alloc::format;
+use core::cmp::Ordering;
 
 use crate::NSUUID;
 use objc2::rc::Retained;
@@ -41,9 +42,37 @@ fn display_debug() {
     assert_eq!(format!("{uuid:?}"), expected);
 }
 
-// #[test]
-// fn test_compare() {
-//     let uuid1 = NSUUID::from_bytes([10; 16]);
-//     let uuid2 = NSUUID::from_bytes([9; 16]);
-//     assert!(uuid1 > uuid2);
-// }
+#[test]
+#[cfg_attr(
+    not(target_arch = "aarch64"),
+    ignore = "encoding depends on Foundation version"
+)]
+fn test_compare() {
+    let uuid1 = NSUUID::from_bytes([10; 16]);
+    let uuid2 = NSUUID::from_bytes([9; 16]);
+    assert!(uuid1 > uuid2);
+    assert_eq!(uuid1.cmp(&NSUUID::from_bytes([10; 16])), Ordering::Equal);
+
+    let mut uuid3 = [10; 16];
+    uuid3[15] = 11;
+    let uuid3 = NSUUID::from_bytes(uuid3);
+    assert!(uuid1 < uuid3);
+    assert_eq!(<[u8; 16]>::from(&*uuid3)[15], 11);
+}
+
+#[test]
+#[cfg(feature = "uuid")]
+#[cfg_attr(
+    not(target_arch = "aarch64"),
+    ignore = "encoding depends on Foundation version"
+)]
+fn test_uuid() {
+    let uuid = ::uuid::Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef);
+    let obj = NSUUID::from_uuid(uuid);
+    assert_eq!(obj.as_bytes(), *uuid.as_bytes());
+    assert_eq!(obj.to_uuid(), uuid);
+    assert_eq!(::uuid::Uuid::from(&*obj), uuid);
+
+    let nil = NSUUID::nil();
+    assert_eq!(nil.to_uuid(), ::uuid::Uuid::nil());
+}
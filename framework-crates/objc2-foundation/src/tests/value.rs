@@ -5,6 +5,8 @@ use core::ffi::CStr;
 use core::slice;
 use core::str;
 
+use objc2::encode::Encode;
+
 use crate::NSValue;
 
 #[test]
@@ -13,6 +15,16 @@ fn basic() {
     assert_eq!(unsafe { val.get::<u32>() }, 13);
 }
 
+#[test]
+fn try_get() {
+    let val = NSValue::new(13u32);
+    assert_eq!(unsafe { val.try_get::<u32>() }, Ok(13));
+
+    let err = unsafe { val.try_get::<i64>() }.unwrap_err();
+    assert_eq!(err.actual(), "I");
+    assert_eq!(*err.expected(), i64::ENCODING);
+}
+
 #[test]
 fn test_equality() {
     let val1 = NSValue::new(123u32);
@@ -0,0 +1,50 @@
+#![cfg(all(
+    feature = "block2",
+    feature = "NSError",
+    feature = "NSString",
+    feature = "NSXPCConnection"
+))]
+use objc2::runtime::NSObjectProtocol;
+use objc2::ProtocolType;
+
+use crate::{NSCopying, NSXPCConnection, NSXPCInterface, NSXPCListener};
+
+#[test]
+#[cfg_attr(not(target_vendor = "apple"), ignore = "only on Apple")]
+fn interface_from_protocol() {
+    let interface = NSXPCInterface::from_protocol::<dyn NSCopying>();
+    let protocol = unsafe { interface.protocol() };
+    assert_eq!(Some(&*protocol), <dyn NSCopying>::protocol());
+}
+
+#[test]
+#[cfg_attr(not(target_vendor = "apple"), ignore = "only on Apple")]
+#[should_panic = "remote interface must be set"]
+fn proxy_without_interface() {
+    let listener = unsafe { NSXPCListener::anonymousListener() };
+    let endpoint = unsafe { listener.endpoint() };
+    let connection = NSXPCConnection::with_endpoint(&endpoint);
+    let _ = connection.remote_object_proxy::<dyn NSObjectProtocol>(|_| {});
+}
+
+#[test]
+#[cfg_attr(not(target_vendor = "apple"), ignore = "only on Apple")]
+#[should_panic = "remote interface is for protocol NSCopying, not NSObject"]
+fn proxy_with_wrong_interface() {
+    let listener = unsafe { NSXPCListener::anonymousListener() };
+    let endpoint = unsafe { listener.endpoint() };
+    let connection = NSXPCConnection::with_endpoint(&endpoint);
+    let interface = NSXPCInterface::from_protocol::<dyn NSCopying>();
+    unsafe { connection.setRemoteObjectInterface(Some(&interface)) };
+    let _ = connection.remote_object_proxy::<dyn NSObjectProtocol>(|_| {});
+}
+
+#[test]
+#[cfg_attr(not(target_vendor = "apple"), ignore = "only on Apple")]
+fn connection_handler() {
+    let listener = unsafe { NSXPCListener::anonymousListener() };
+    let handler = listener.set_connection_handler(|_| false);
+    assert!(unsafe { listener.delegate() }.is_some());
+    drop(handler);
+    assert!(unsafe { listener.delegate() }.is_none());
+}
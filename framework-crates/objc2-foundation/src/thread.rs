@@ -24,6 +24,152 @@ pub fn is_main_thread() -> bool {
     MainThreadMarker::new().is_some()
 }
 
+impl NSThread {
+    /// Get a [`MainThreadMarker`] if the current thread is the main thread,
+    /// as determined by `+[NSThread isMainThread]`.
+    ///
+    /// This is mostly equivalent to [`MainThreadMarker::new`], but uses
+    /// Foundation's notion of the main thread, which may be useful on
+    /// platforms where the two could disagree.
+    #[doc(alias = "isMainThread")]
+    pub fn main_thread_marker() -> Option<MainThreadMarker> {
+        if Self::isMainThread_class() {
+            // SAFETY: Just checked that we are on the main thread.
+            Some(unsafe { MainThreadMarker::new_unchecked() })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSLock",
+    feature = "NSObjCRuntime",
+    feature = "NSString"
+))]
+mod spawn {
+    use core::fmt;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::{Mutex, PoisonError};
+    use std::thread;
+
+    use objc2::rc::{autoreleasepool, Retained};
+    use objc2::AllocAnyThread;
+
+    use crate::channel::{self, Receiver};
+    use crate::{NSQualityOfService, NSString, NSThread};
+
+    /// Spawning threads.
+    impl NSThread {
+        /// Run the closure on a new `NSThread`.
+        ///
+        /// This is similar to [`std::thread::spawn`], but creates the thread
+        /// through Foundation, for APIs that require an `NSThread` instead of
+        /// any thread, such as those that schedule sources on the thread's
+        /// `NSRunLoop`. The closure runs inside an autorelease pool, and can
+        /// use `NSThread::currentThread().threadDictionary()` to store
+        /// thread-local objects.
+        ///
+        /// Panics in the closure are caught, and returned from
+        /// [`NSThreadJoinHandle::join`].
+        ///
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use objc2_foundation::{NSQualityOfService, NSThread};
+        ///
+        /// let handle = NSThread::spawn("worker", NSQualityOfService::Utility, || {
+        ///     let thread = NSThread::currentThread();
+        ///     assert!(!thread.isMainThread());
+        ///     40 + 2
+        /// });
+        /// assert_eq!(handle.join().unwrap(), 42);
+        /// ```
+        #[doc(alias = "initWithBlock:")]
+        pub fn spawn<F, T>(
+            name: &str,
+            quality_of_service: NSQualityOfService,
+            f: F,
+        ) -> NSThreadJoinHandle<T>
+        where
+            F: FnOnce() -> T + Send + 'static,
+            T: Send + 'static,
+        {
+            let (sender, receiver) = channel::unbounded();
+            // The block is `Fn`, but is only called once.
+            let state = Mutex::new(Some((f, sender)));
+            let block = block2::RcBlock::new(move || {
+                let state = state.lock().unwrap_or_else(PoisonError::into_inner).take();
+                if let Some((f, sender)) = state {
+                    // Unwinding into Objective-C is not allowed, so catch the
+                    // panic and hand it to the joining thread instead.
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| autoreleasepool(|_| f())));
+                    // The handle may have been dropped without being joined.
+                    let _ = sender.send(result);
+                }
+            });
+
+            let thread = unsafe { NSThread::initWithBlock(NSThread::alloc(), &block) };
+            unsafe { thread.setName(Some(&NSString::from_str(name))) };
+            unsafe { thread.setQualityOfService(quality_of_service) };
+            unsafe { thread.start() };
+
+            NSThreadJoinHandle { thread, receiver }
+        }
+    }
+
+    /// A handle to a thread spawned with [`NSThread::spawn`].
+    ///
+    /// The thread is detached if this is dropped.
+    pub struct NSThreadJoinHandle<T> {
+        thread: Retained<NSThread>,
+        receiver: Receiver<thread::Result<T>>,
+    }
+
+    impl<T> NSThreadJoinHandle<T> {
+        /// The underlying thread.
+        pub fn thread(&self) -> &NSThread {
+            &self.thread
+        }
+
+        /// Whether the closure has finished running.
+        pub fn is_finished(&self) -> bool {
+            unsafe { self.thread.isFinished() }
+        }
+
+        /// Block until the closure has finished, and return its result.
+        ///
+        /// # Errors
+        ///
+        /// If the closure panicked, the panic payload is returned as the
+        /// [`Err`] type.
+        pub fn join(self) -> thread::Result<T> {
+            self.receiver
+                .recv()
+                .expect("thread exited without running the closure")
+        }
+    }
+
+    impl<T> fmt::Debug for NSThreadJoinHandle<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("NSThreadJoinHandle")
+                .field("thread", &self.thread)
+                .finish_non_exhaustive()
+        }
+    }
+}
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSLock",
+    feature = "NSObjCRuntime",
+    feature = "NSString"
+))]
+pub use self::spawn::NSThreadJoinHandle;
+
 #[allow(unused)]
 fn make_multithreaded() {
     let thread = unsafe { NSThread::new() };
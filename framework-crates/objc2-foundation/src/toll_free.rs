@@ -0,0 +1,202 @@
+//! Toll-free bridging between Foundation and CoreFoundation types.
+//!
+//! Many Foundation classes share their memory layout with a CoreFoundation
+//! type, such that a pointer to one can be used as a pointer to the other.
+//! See [Apple's documentation on toll-free bridging][toll-free].
+//!
+//! The conversions here are zero-cost casts. The owned variants move the
+//! existing +1 retain count between [`Retained`] and [`CFRetained`], while
+//! the reference variants (exposed as [`AsRef`] and `as_cf_*` methods) do not
+//! touch the retain count at all.
+//!
+//! Collections (`NSArray` and `NSDictionary`) only convert safely in the
+//! Foundation to CoreFoundation direction, since CoreFoundation collections
+//! can be created with custom callbacks that make them store values that are
+//! not objects.
+//!
+//! [toll-free]: https://developer.apple.com/library/archive/documentation/CoreFoundation/Conceptual/CFDesignConcepts/Articles/tollFreeBridgedTypes.html
+use core::ptr::NonNull;
+
+use objc2::rc::Retained;
+use objc2::Message;
+use objc2_core_foundation::CFRetained;
+
+/// # Safety
+///
+/// The types must be toll-free bridged.
+#[inline]
+unsafe fn cast_ref<T: ?Sized, U>(obj: &T) -> &U {
+    let ptr: *const T = obj;
+    let ptr: *const U = ptr.cast();
+    // SAFETY: Upheld by the caller.
+    unsafe { &*ptr }
+}
+
+/// # Safety
+///
+/// The types must be toll-free bridged.
+#[inline]
+unsafe fn into_cf<T: Message, U: objc2_core_foundation::Type>(obj: Retained<T>) -> CFRetained<U> {
+    let ptr: *mut U = Retained::into_raw(obj).cast();
+    // SAFETY: The pointer came from `Retained`, so it is non-null.
+    let ptr = unsafe { NonNull::new_unchecked(ptr) };
+    // SAFETY: The types are toll-free bridged (upheld by the caller), and
+    // the pointer has +1 retain count, which is transferred to `CFRetained`.
+    unsafe { CFRetained::from_raw(ptr) }
+}
+
+/// # Safety
+///
+/// The types must be toll-free bridged, and the contents of the object must
+/// be valid for `U`.
+#[inline]
+unsafe fn from_cf<T: objc2_core_foundation::Type, U: Message>(obj: CFRetained<T>) -> Retained<U> {
+    let ptr: *mut U = CFRetained::into_raw(obj).as_ptr().cast();
+    // SAFETY: The types are toll-free bridged (upheld by the caller), and
+    // the pointer has +1 retain count, which is transferred to `Retained`.
+    unsafe { Retained::from_raw(ptr) }.unwrap()
+}
+
+/// Bridge types whose CoreFoundation counterpart always contains objects,
+/// and which can thus be converted in both directions.
+macro_rules! bridge {
+    ($(
+        #[cfg($($cfg:tt)*)]
+        $ns:ident <=> $cf:ident, $as_cf:ident, $into_cf:ident, $from_cf:ident;
+    )*) => {$(
+        #[cfg($($cfg)*)]
+        impl AsRef<objc2_core_foundation::$cf> for crate::$ns {
+            #[inline]
+            fn as_ref(&self) -> &objc2_core_foundation::$cf {
+                self.$as_cf()
+            }
+        }
+
+        #[cfg($($cfg)*)]
+        impl AsRef<crate::$ns> for objc2_core_foundation::$cf {
+            #[inline]
+            fn as_ref(&self) -> &crate::$ns {
+                // SAFETY: The types are toll-free bridged.
+                unsafe { cast_ref(self) }
+            }
+        }
+
+        #[cfg($($cfg)*)]
+        impl crate::$ns {
+            #[doc = concat!("View this as a [`", stringify!($cf), "`].")]
+            ///
+            /// This is a free cast, since the types are toll-free bridged.
+            ///
+            #[doc = concat!("[`", stringify!($cf), "`]: objc2_core_foundation::", stringify!($cf))]
+            #[inline]
+            pub fn $as_cf(&self) -> &objc2_core_foundation::$cf {
+                // SAFETY: The types are toll-free bridged.
+                unsafe { cast_ref(self) }
+            }
+
+            #[doc = concat!("Convert this into a [`", stringify!($cf), "`], without retaining it again.")]
+            ///
+            #[doc = concat!("[`", stringify!($cf), "`]: objc2_core_foundation::", stringify!($cf))]
+            #[inline]
+            pub fn $into_cf(this: Retained<Self>) -> CFRetained<objc2_core_foundation::$cf> {
+                // SAFETY: The types are toll-free bridged.
+                unsafe { into_cf(this) }
+            }
+
+            #[doc = concat!("Convert a [`", stringify!($cf), "`] into this type, without retaining it again.")]
+            ///
+            #[doc = concat!("[`", stringify!($cf), "`]: objc2_core_foundation::", stringify!($cf))]
+            #[inline]
+            pub fn $from_cf(obj: CFRetained<objc2_core_foundation::$cf>) -> Retained<Self> {
+                // SAFETY: The types are toll-free bridged.
+                unsafe { from_cf(obj) }
+            }
+        }
+    )*};
+}
+
+bridge! {
+    #[cfg(all(feature = "NSString", feature = "objc2-core-foundation"))]
+    NSString <=> CFString, as_cf_string, into_cf_string, from_cf_string;
+    #[cfg(all(feature = "NSString", feature = "objc2-core-foundation"))]
+    NSMutableString <=> CFMutableString, as_cf_mutable_string, into_cf_mutable_string, from_cf_mutable_string;
+    #[cfg(all(feature = "NSData", feature = "objc2-core-foundation"))]
+    NSData <=> CFData, as_cf_data, into_cf_data, from_cf_data;
+    #[cfg(all(feature = "NSData", feature = "objc2-core-foundation"))]
+    NSMutableData <=> CFMutableData, as_cf_mutable_data, into_cf_mutable_data, from_cf_mutable_data;
+    #[cfg(all(feature = "NSDate", feature = "objc2-core-foundation"))]
+    NSDate <=> CFDate, as_cf_date, into_cf_date, from_cf_date;
+    #[cfg(all(feature = "NSURL", feature = "objc2-core-foundation"))]
+    NSURL <=> CFURL, as_cf_url, into_cf_url, from_cf_url;
+    #[cfg(all(feature = "NSError", feature = "objc2-core-foundation"))]
+    NSError <=> CFError, as_cf_error, into_cf_error, from_cf_error;
+}
+
+/// Bridge collections, which can only be safely converted from Foundation to
+/// CoreFoundation.
+macro_rules! bridge_collection {
+    ($(
+        #[cfg($($cfg:tt)*)]
+        $ns:ident<$($generic:ident),*> => $cf:ident, $as_cf:ident, $into_cf:ident, $from_cf:ident;
+    )*) => {$(
+        #[cfg($($cfg)*)]
+        impl<$($generic: ?Sized + Message),*> AsRef<objc2_core_foundation::$cf> for crate::$ns<$($generic),*> {
+            #[inline]
+            fn as_ref(&self) -> &objc2_core_foundation::$cf {
+                self.$as_cf()
+            }
+        }
+
+        #[cfg($($cfg)*)]
+        impl<$($generic: ?Sized + Message),*> crate::$ns<$($generic),*> {
+            #[doc = concat!("View this as a [`", stringify!($cf), "`].")]
+            ///
+            /// This is a free cast, since the types are toll-free bridged.
+            ///
+            #[doc = concat!("[`", stringify!($cf), "`]: objc2_core_foundation::", stringify!($cf))]
+            #[inline]
+            pub fn $as_cf(&self) -> &objc2_core_foundation::$cf {
+                // SAFETY: The types are toll-free bridged.
+                unsafe { cast_ref(self) }
+            }
+
+            #[doc = concat!("Convert this into a [`", stringify!($cf), "`], without retaining it again.")]
+            ///
+            #[doc = concat!("[`", stringify!($cf), "`]: objc2_core_foundation::", stringify!($cf))]
+            #[inline]
+            pub fn $into_cf(this: Retained<Self>) -> CFRetained<objc2_core_foundation::$cf> {
+                // SAFETY: The types are toll-free bridged.
+                unsafe { into_cf(this) }
+            }
+
+            #[doc = concat!("Convert a [`", stringify!($cf), "`] into this type, without retaining it again.")]
+            ///
+            ///
+            /// # Safety
+            ///
+            /// The collection must have been created with the default
+            /// CoreFoundation callbacks for objects (such as
+            /// `kCFTypeArrayCallBacks`), and the contained objects must be of
+            /// the specified generic types.
+            ///
+            #[doc = concat!("[`", stringify!($cf), "`]: objc2_core_foundation::", stringify!($cf))]
+            #[inline]
+            pub unsafe fn $from_cf(obj: CFRetained<objc2_core_foundation::$cf>) -> Retained<Self> {
+                // SAFETY: The types are toll-free bridged, and the caller
+                // upholds that the contents are valid objects.
+                unsafe { from_cf(obj) }
+            }
+        }
+    )*};
+}
+
+bridge_collection! {
+    #[cfg(all(feature = "NSArray", feature = "objc2-core-foundation"))]
+    NSArray<T> => CFArray, as_cf_array, into_cf_array, from_cf_array_unchecked;
+    #[cfg(all(feature = "NSArray", feature = "objc2-core-foundation"))]
+    NSMutableArray<T> => CFMutableArray, as_cf_mutable_array, into_cf_mutable_array, from_cf_mutable_array_unchecked;
+    #[cfg(all(feature = "NSDictionary", feature = "objc2-core-foundation"))]
+    NSDictionary<K, V> => CFDictionary, as_cf_dictionary, into_cf_dictionary, from_cf_dictionary_unchecked;
+    #[cfg(all(feature = "NSDictionary", feature = "objc2-core-foundation"))]
+    NSMutableDictionary<K, V> => CFMutableDictionary, as_cf_mutable_dictionary, into_cf_mutable_dictionary, from_cf_mutable_dictionary_unchecked;
+}
@@ -0,0 +1,328 @@
+#![cfg(feature = "NSString")]
+#[cfg(feature = "NSData")]
+use alloc::vec::Vec;
+
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+
+use crate::{NSString, NSUbiquitousKeyValueStore};
+
+/// Typed accessors.
+///
+/// Unlike the raw `*ForKey:` getters, these return [`None`] if the key is
+/// not present in the store (or if the stored value has a different type),
+/// instead of returning a default value.
+impl NSUbiquitousKeyValueStore {
+    fn object(&self, key: &str) -> Option<Retained<AnyObject>> {
+        let key = NSString::from_str(key);
+        unsafe { self.objectForKey(&key) }
+    }
+
+    /// Get the boolean value associated with the given key.
+    #[cfg(feature = "NSValue")]
+    #[doc(alias = "boolForKey:")]
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        let number = self.object(key)?.downcast::<crate::NSNumber>().ok()?;
+        Some(number.as_bool())
+    }
+
+    /// Get the integer value associated with the given key.
+    #[cfg(feature = "NSValue")]
+    #[doc(alias = "longLongForKey:")]
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        let number = self.object(key)?.downcast::<crate::NSNumber>().ok()?;
+        Some(number.as_i64())
+    }
+
+    /// Get the floating-point value associated with the given key.
+    #[cfg(feature = "NSValue")]
+    #[doc(alias = "doubleForKey:")]
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        let number = self.object(key)?.downcast::<crate::NSNumber>().ok()?;
+        Some(number.as_f64())
+    }
+
+    /// Get the string associated with the given key.
+    #[doc(alias = "stringForKey:")]
+    pub fn get_string(&self, key: &str) -> Option<Retained<NSString>> {
+        self.object(key)?.downcast::<NSString>().ok()
+    }
+
+    /// Get a copy of the bytes associated with the given key.
+    #[cfg(feature = "NSData")]
+    #[doc(alias = "dataForKey:")]
+    pub fn get_bytes(&self, key: &str) -> Option<Vec<u8>> {
+        let data = self.object(key)?.downcast::<crate::NSData>().ok()?;
+        Some(data.to_vec())
+    }
+
+    /// Associate a boolean value with the given key.
+    #[doc(alias = "setBool:forKey:")]
+    pub fn set_bool(&self, key: &str, value: bool) {
+        let key = NSString::from_str(key);
+        unsafe { self.setBool_forKey(value, &key) };
+    }
+
+    /// Associate an integer value with the given key.
+    #[doc(alias = "setLongLong:forKey:")]
+    pub fn set_i64(&self, key: &str, value: i64) {
+        let key = NSString::from_str(key);
+        unsafe { self.setLongLong_forKey(value as _, &key) };
+    }
+
+    /// Associate a floating-point value with the given key.
+    #[doc(alias = "setDouble:forKey:")]
+    pub fn set_f64(&self, key: &str, value: f64) {
+        let key = NSString::from_str(key);
+        unsafe { self.setDouble_forKey(value, &key) };
+    }
+
+    /// Associate a string with the given key.
+    #[doc(alias = "setString:forKey:")]
+    pub fn set_str(&self, key: &str, value: &str) {
+        let key = NSString::from_str(key);
+        let value = NSString::from_str(value);
+        unsafe { self.setString_forKey(Some(&value), &key) };
+    }
+
+    /// Associate a copy of the given bytes with the given key.
+    #[cfg(feature = "NSData")]
+    #[doc(alias = "setData:forKey:")]
+    pub fn set_bytes(&self, key: &str, value: &[u8]) {
+        let key = NSString::from_str(key);
+        let value = crate::NSData::with_bytes(value);
+        unsafe { self.setData_forKey(Some(&value), &key) };
+    }
+
+    /// Remove the value associated with the given key.
+    #[doc(alias = "removeObjectForKey:")]
+    pub fn remove(&self, key: &str) {
+        let key = NSString::from_str(key);
+        unsafe { self.removeObjectForKey(&key) };
+    }
+}
+
+/// The reason that the key-value store changed externally.
+///
+/// See [`NSUbiquitousKeyValueStoreChangeReasonKey`].
+///
+/// [`NSUbiquitousKeyValueStoreChangeReasonKey`]: crate::NSUbiquitousKeyValueStoreChangeReasonKey
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NSUbiquitousKeyValueStoreChangeReason {
+    /// A value changed in iCloud, e.g. because another device changed it.
+    #[doc(alias = "NSUbiquitousKeyValueStoreServerChange")]
+    ServerChange,
+    /// The initial download from iCloud happened, e.g. on first launch.
+    #[doc(alias = "NSUbiquitousKeyValueStoreInitialSyncChange")]
+    InitialSyncChange,
+    /// The app's key-value store has exceeded its space quota on iCloud.
+    #[doc(alias = "NSUbiquitousKeyValueStoreQuotaViolationChange")]
+    QuotaViolationChange,
+    /// The user changed the primary iCloud account.
+    #[doc(alias = "NSUbiquitousKeyValueStoreAccountChange")]
+    AccountChange,
+    /// A reason that is not known to this version of the crate.
+    Unknown(crate::NSInteger),
+}
+
+impl NSUbiquitousKeyValueStoreChangeReason {
+    /// Decode the reason from its raw value.
+    pub fn from_raw(raw: crate::NSInteger) -> Self {
+        match raw {
+            crate::NSUbiquitousKeyValueStoreServerChange => Self::ServerChange,
+            crate::NSUbiquitousKeyValueStoreInitialSyncChange => Self::InitialSyncChange,
+            crate::NSUbiquitousKeyValueStoreQuotaViolationChange => Self::QuotaViolationChange,
+            crate::NSUbiquitousKeyValueStoreAccountChange => Self::AccountChange,
+            raw => Self::Unknown(raw),
+        }
+    }
+
+    /// The raw value of the reason.
+    pub fn to_raw(self) -> crate::NSInteger {
+        match self {
+            Self::ServerChange => crate::NSUbiquitousKeyValueStoreServerChange,
+            Self::InitialSyncChange => crate::NSUbiquitousKeyValueStoreInitialSyncChange,
+            Self::QuotaViolationChange => crate::NSUbiquitousKeyValueStoreQuotaViolationChange,
+            Self::AccountChange => crate::NSUbiquitousKeyValueStoreAccountChange,
+            Self::Unknown(raw) => raw,
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSArray",
+    feature = "NSDictionary",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSValue"
+))]
+mod external_changes {
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use core::fmt;
+    use core::future::Future;
+    use core::task::{Context, Poll};
+
+    use super::NSUbiquitousKeyValueStoreChangeReason;
+    use crate::{
+        NSArray, NSNotification, NSNotificationCenter, NSNotificationName, NSNotificationPayload,
+        NSNotificationStream, NSNumber, NSString, NSUbiquitousKeyValueStore,
+    };
+
+    /// A decoded `NSUbiquitousKeyValueStoreDidChangeExternallyNotification`.
+    ///
+    /// See [`NSUbiquitousKeyValueStore::external_changes`].
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct NSUbiquitousKeyValueStoreChange {
+        /// Why the store changed, if the notification contained a reason.
+        pub reason: Option<NSUbiquitousKeyValueStoreChangeReason>,
+        /// The keys whose values changed.
+        pub changed_keys: Vec<String>,
+    }
+
+    impl NSNotificationPayload for NSUbiquitousKeyValueStoreChange {
+        fn name() -> &'static NSNotificationName {
+            unsafe { crate::NSUbiquitousKeyValueStoreDidChangeExternallyNotification }
+        }
+
+        fn from_notification(notification: &NSNotification) -> Self {
+            let user_info = unsafe { notification.userInfo() };
+
+            let reason = user_info.as_ref().and_then(|user_info| {
+                let key = unsafe { crate::NSUbiquitousKeyValueStoreChangeReasonKey };
+                let reason = user_info.objectForKey(key)?;
+                let reason = reason.downcast::<NSNumber>().ok()?;
+                Some(NSUbiquitousKeyValueStoreChangeReason::from_raw(
+                    reason.as_isize(),
+                ))
+            });
+
+            let changed_keys = user_info
+                .as_ref()
+                .and_then(|user_info| {
+                    let key = unsafe { crate::NSUbiquitousKeyValueStoreChangedKeysKey };
+                    let keys = user_info.objectForKey(key)?;
+                    let keys = keys.downcast::<NSArray>().ok()?;
+                    Some(
+                        keys.to_vec()
+                            .into_iter()
+                            .filter_map(|key| key.downcast::<NSString>().ok())
+                            .map(|key| key.to_string())
+                            .collect(),
+                    )
+                })
+                .unwrap_or_default();
+
+            Self {
+                reason,
+                changed_keys,
+            }
+        }
+    }
+
+    /// A stream of external changes to a [`NSUbiquitousKeyValueStore`].
+    ///
+    /// Changes are queued from the moment the stream is created, and can be
+    /// retrieved either by blocking ([`recv`]), by polling ([`try_next`]), or
+    /// asynchronously ([`next`] or [`poll_next`], the latter of which can be used
+    /// to implement `futures::Stream`).
+    ///
+    /// The observer is removed when this is dropped.
+    ///
+    /// See [`NSUbiquitousKeyValueStore::external_changes`].
+    ///
+    /// [`recv`]: Self::recv
+    /// [`try_next`]: Self::try_next
+    /// [`next`]: Self::next
+    /// [`poll_next`]: Self::poll_next
+    pub struct NSUbiquitousKeyValueStoreChanges {
+        stream: NSNotificationStream<NSUbiquitousKeyValueStoreChange>,
+    }
+
+    impl NSUbiquitousKeyValueStore {
+        /// Observe changes made to the store by other devices or by iCloud.
+        ///
+        /// Note that you will not receive changes that happen before you call
+        /// [`synchronize`], so you should usually call that after creating the
+        /// stream.
+        ///
+        /// [`synchronize`]: Self::synchronize
+        ///
+        ///
+        /// # Example
+        ///
+        /// ```no_run
+        /// use objc2_foundation::NSUbiquitousKeyValueStore;
+        ///
+        /// let store = unsafe { NSUbiquitousKeyValueStore::defaultStore() };
+        /// let changes = store.external_changes();
+        /// unsafe { store.synchronize() };
+        ///
+        /// while let Some(change) = changes.recv() {
+        ///     for key in &change.changed_keys {
+        ///         println!("{key} changed: {:?}", store.get_string(key));
+        ///     }
+        /// }
+        /// ```
+        #[doc(alias = "NSUbiquitousKeyValueStoreDidChangeExternallyNotification")]
+        pub fn external_changes(&self) -> NSUbiquitousKeyValueStoreChanges {
+            let center = unsafe { NSNotificationCenter::defaultCenter() };
+            NSUbiquitousKeyValueStoreChanges {
+                stream: center.typed_notifications(Some(self)),
+            }
+        }
+    }
+
+    impl NSUbiquitousKeyValueStoreChanges {
+        /// Get the next change if one has already been received.
+        pub fn try_next(&self) -> Option<NSUbiquitousKeyValueStoreChange> {
+            self.stream.try_next()
+        }
+
+        /// Block the current thread until the next change is received.
+        ///
+        /// Notifications are posted on a background thread, so this is fine to
+        /// call from any thread (though you should avoid blocking the main
+        /// thread).
+        pub fn recv(&self) -> Option<NSUbiquitousKeyValueStoreChange> {
+            self.stream.recv()
+        }
+
+        /// Poll for the next change.
+        pub fn poll_next(
+            &self,
+            cx: &mut Context<'_>,
+        ) -> Poll<Option<NSUbiquitousKeyValueStoreChange>> {
+            self.stream.poll_next(cx)
+        }
+
+        /// Wait asynchronously for the next change.
+        pub fn next(&self) -> impl Future<Output = Option<NSUbiquitousKeyValueStoreChange>> + '_ {
+            core::future::poll_fn(|cx| self.poll_next(cx))
+        }
+    }
+
+    impl fmt::Debug for NSUbiquitousKeyValueStoreChanges {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("NSUbiquitousKeyValueStoreChanges")
+                .field("stream", &self.stream)
+                .finish_non_exhaustive()
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "NSArray",
+    feature = "NSDictionary",
+    feature = "NSNotification",
+    feature = "NSOperation",
+    feature = "NSValue"
+))]
+pub use self::external_changes::{
+    NSUbiquitousKeyValueStoreChange, NSUbiquitousKeyValueStoreChanges,
+};
@@ -0,0 +1,122 @@
+#[cfg(all(feature = "std", unix))]
+use std::path::{Path, PathBuf};
+
+use objc2::rc::Retained;
+
+use crate::NSURL;
+
+/// Conversion to and from file system paths.
+#[cfg(all(feature = "std", unix))]
+impl NSURL {
+    /// Create a file URL from a file system path.
+    ///
+    /// Relative paths are resolved against the current working directory.
+    /// If `is_directory` is `true`, the URL is marked as referring to a
+    /// directory (and will have a trailing slash).
+    ///
+    /// The path is percent-encoded as required, and does not need to be
+    /// valid UTF-8.
+    ///
+    /// Returns [`None`] if the path contains a NUL byte.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use objc2_foundation::NSURL;
+    ///
+    /// let url = NSURL::from_path(Path::new("/tmp/hello world.txt"), false).unwrap();
+    /// assert_eq!(url.to_path_buf().unwrap(), Path::new("/tmp/hello world.txt"));
+    /// ```
+    #[doc(alias = "fileURLWithFileSystemRepresentation:isDirectory:relativeToURL:")]
+    pub fn from_path(path: &Path, is_directory: bool) -> Option<Retained<Self>> {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+        let ptr = core::ptr::NonNull::new(path.as_ptr().cast_mut()).unwrap();
+        // SAFETY: The path is a valid, NUL-terminated C string, and the URL
+        // copies it.
+        Some(unsafe {
+            Self::fileURLWithFileSystemRepresentation_isDirectory_relativeToURL(
+                ptr,
+                is_directory,
+                None,
+            )
+        })
+    }
+
+    /// Get the file system path that the URL refers to.
+    ///
+    /// File reference URLs (`file:///.file/id=...`) are resolved to the
+    /// current path of the file they refer to, and percent-encoding is
+    /// decoded.
+    ///
+    /// Returns [`None`] if this is not a file URL, or if it is a file
+    /// reference URL to a file that no longer exists.
+    #[doc(alias = "fileSystemRepresentation")]
+    #[doc(alias = "filePathURL")]
+    pub fn to_path_buf(&self) -> Option<PathBuf> {
+        use std::ffi::{CStr, OsStr};
+        use std::os::unix::ffi::OsStrExt;
+
+        if !unsafe { self.isFileURL() } {
+            return None;
+        }
+
+        let path_url;
+        let url = if unsafe { self.isFileReferenceURL() } {
+            path_url = unsafe { self.filePathURL() }?;
+            &*path_url
+        } else {
+            self
+        };
+
+        // SAFETY: The URL is a file URL, so this returns a valid C string.
+        let ptr = unsafe { url.fileSystemRepresentation() };
+        // The string is autoreleased, so copy it out immediately.
+        let bytes = unsafe { CStr::from_ptr(ptr.as_ptr()) }.to_bytes();
+        Some(PathBuf::from(OsStr::from_bytes(bytes)))
+    }
+}
+
+/// Conversion to and from [`url::Url`].
+#[cfg(all(feature = "url", feature = "NSString"))]
+impl NSURL {
+    /// Create a `NSURL` from a [`url::Url`].
+    ///
+    /// Returns [`None`] if Foundation fails to parse the URL; Foundation is
+    /// stricter than the `url` crate on some older OS versions.
+    #[doc(alias = "URLWithString:")]
+    pub fn from_url(url: &::url::Url) -> Option<Retained<Self>> {
+        let string = crate::NSString::from_str(url.as_str());
+        unsafe { Self::URLWithString(&string) }
+    }
+
+    /// Convert the `NSURL` to a [`url::Url`].
+    ///
+    /// This uses the URL's absolute string, so relative URLs are resolved
+    /// against their base URL.
+    #[doc(alias = "absoluteString")]
+    pub fn to_url(&self) -> Result<::url::Url, ::url::ParseError> {
+        use alloc::string::ToString;
+
+        // `absoluteString` only returns `nil` for malformed URLs, in which
+        // case parsing the empty string will fail too.
+        let string = unsafe { self.absoluteString() }
+            .map(|string| string.to_string())
+            .unwrap_or_default();
+        ::url::Url::parse(&string)
+    }
+}
+
+#[cfg(all(feature = "url", feature = "NSString"))]
+impl TryFrom<&NSURL> for ::url::Url {
+    type Error = ::url::ParseError;
+
+    #[inline]
+    fn try_from(url: &NSURL) -> Result<Self, Self::Error> {
+        url.to_url()
+    }
+}
@@ -0,0 +1,331 @@
+//! Building URL requests from Rust types.
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "NSDate")]
+use core::time::Duration;
+
+use objc2::rc::Retained;
+use objc2::Message;
+
+#[cfg(feature = "NSData")]
+use crate::NSData;
+#[cfg(feature = "NSStream")]
+use crate::NSInputStream;
+use crate::{NSMutableURLRequest, NSString, NSURLRequestCachePolicy, NSURL};
+
+/// An HTTP request method.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NSHTTPMethod {
+    /// `GET`, the default method.
+    #[default]
+    Get,
+    /// `HEAD`.
+    Head,
+    /// `POST`.
+    Post,
+    /// `PUT`.
+    Put,
+    /// `PATCH`.
+    Patch,
+    /// `DELETE`.
+    Delete,
+    /// `OPTIONS`.
+    Options,
+}
+
+impl NSHTTPMethod {
+    /// The name of the method, as sent in the request line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Head => "HEAD",
+            Self::Post => "POST",
+            Self::Put => "PUT",
+            Self::Patch => "PATCH",
+            Self::Delete => "DELETE",
+            Self::Options => "OPTIONS",
+        }
+    }
+}
+
+impl fmt::Display for NSHTTPMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An error returned from [`NSURLRequestBuilder::build`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NSURLRequestBuilderError {
+    /// A custom method name was empty or contained characters that are not
+    /// allowed in an HTTP token.
+    InvalidMethod(String),
+    /// A header name was empty or contained characters that are not allowed
+    /// in an HTTP token.
+    InvalidHeaderName(String),
+    /// The value of the given header contained a line break or a NUL byte.
+    InvalidHeaderValue(String),
+}
+
+impl fmt::Display for NSURLRequestBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMethod(method) => write!(f, "invalid HTTP method {method:?}"),
+            Self::InvalidHeaderName(name) => write!(f, "invalid HTTP header name {name:?}"),
+            Self::InvalidHeaderValue(name) => {
+                write!(f, "invalid value for HTTP header {name:?}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NSURLRequestBuilderError {}
+
+/// Whether the string is a `token` as defined in RFC 9110.
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes().all(|b| {
+            b.is_ascii_alphanumeric()
+                || matches!(
+                    b,
+                    b'!' | b'#'
+                        | b'$'
+                        | b'%'
+                        | b'&'
+                        | b'\''
+                        | b'*'
+                        | b'+'
+                        | b'-'
+                        | b'.'
+                        | b'^'
+                        | b'_'
+                        | b'`'
+                        | b'|'
+                        | b'~'
+                )
+        })
+}
+
+fn is_valid_header_value(s: &str) -> bool {
+    !s.bytes().any(|b| matches!(b, b'\r' | b'\n' | b'\0'))
+}
+
+#[derive(Debug)]
+enum Body {
+    #[cfg(feature = "NSData")]
+    Data(Retained<NSData>),
+    #[cfg(feature = "NSStream")]
+    Stream(Retained<NSInputStream>),
+}
+
+impl Body {
+    #[cfg_attr(
+        not(any(feature = "NSData", feature = "NSStream")),
+        allow(unused_variables)
+    )]
+    fn apply(&self, request: &NSMutableURLRequest) {
+        match *self {
+            #[cfg(feature = "NSData")]
+            Self::Data(ref data) => unsafe { request.setHTTPBody(Some(data)) },
+            #[cfg(feature = "NSStream")]
+            Self::Stream(ref stream) => unsafe { request.setHTTPBodyStream(Some(stream)) },
+        }
+    }
+}
+
+/// A builder for [`NSMutableURLRequest`].
+///
+/// Invalid methods and headers are reported when the request is
+/// [built](Self::build), instead of being silently sent or dropped.
+///
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use objc2_foundation::{NSHTTPMethod, NSString, NSURLRequestBuilder, NSURL};
+///
+/// let url = unsafe { NSURL::URLWithString(&NSString::from_str("https://example.com/api")) };
+/// let url = url.unwrap();
+/// let request = NSURLRequestBuilder::new(&url)
+///     .method(NSHTTPMethod::Post)
+///     .header("Content-Type", "application/json")
+///     .body_bytes(br#"{"hello":"world"}"#)
+///     .timeout(Duration::from_secs(30))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct NSURLRequestBuilder {
+    url: Retained<NSURL>,
+    method: Option<String>,
+    headers: Vec<(String, String)>,
+    body: Option<Body>,
+    cache_policy: Option<NSURLRequestCachePolicy>,
+    #[cfg(feature = "NSDate")]
+    timeout: Option<Duration>,
+    error: Option<NSURLRequestBuilderError>,
+}
+
+impl NSURLRequestBuilder {
+    /// Start building a `GET` request for the given URL.
+    pub fn new(url: &NSURL) -> Self {
+        Self {
+            url: url.retain(),
+            method: None,
+            headers: Vec::new(),
+            body: None,
+            cache_policy: None,
+            #[cfg(feature = "NSDate")]
+            timeout: None,
+            error: None,
+        }
+    }
+
+    fn set_error(&mut self, error: NSURLRequestBuilderError) {
+        // Only report the first error.
+        self.error.get_or_insert(error);
+    }
+
+    /// Set the request method.
+    #[doc(alias = "setHTTPMethod:")]
+    pub fn method(mut self, method: NSHTTPMethod) -> Self {
+        self.method = Some(method.as_str().to_string());
+        self
+    }
+
+    /// Set a request method that is not covered by [`NSHTTPMethod`].
+    ///
+    /// The method must be a valid HTTP token, otherwise building the request
+    /// fails.
+    #[doc(alias = "setHTTPMethod:")]
+    pub fn custom_method(mut self, method: &str) -> Self {
+        if is_token(method) {
+            self.method = Some(method.to_string());
+        } else {
+            self.set_error(NSURLRequestBuilderError::InvalidMethod(method.to_string()));
+        }
+        self
+    }
+
+    /// Add a header to the request.
+    ///
+    /// If a header with the same name was added before, the values are
+    /// combined into a comma-separated list, as per the HTTP specification.
+    /// Header names are case-insensitive.
+    ///
+    /// Note that some headers, such as `Content-Length` and `Host`, are
+    /// managed by the URL loading system, and are ignored if set here.
+    #[doc(alias = "addValue:forHTTPHeaderField:")]
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        if !is_token(name) {
+            self.set_error(NSURLRequestBuilderError::InvalidHeaderName(
+                name.to_string(),
+            ));
+        } else if !is_valid_header_value(value) {
+            self.set_error(NSURLRequestBuilderError::InvalidHeaderValue(
+                name.to_string(),
+            ));
+        } else {
+            self.headers.push((name.to_string(), value.to_string()));
+        }
+        self
+    }
+
+    /// Add multiple headers to the request.
+    ///
+    /// See [`header`](Self::header) for details.
+    pub fn headers<'a>(mut self, headers: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        for (name, value) in headers {
+            self = self.header(name, value);
+        }
+        self
+    }
+
+    /// Send the given bytes as the body of the request.
+    #[cfg(feature = "NSData")]
+    #[doc(alias = "setHTTPBody:")]
+    pub fn body_bytes(self, bytes: &[u8]) -> Self {
+        self.body_data(&NSData::with_bytes(bytes))
+    }
+
+    /// Send the given data as the body of the request.
+    ///
+    /// This replaces any previously set body.
+    #[cfg(feature = "NSData")]
+    #[doc(alias = "setHTTPBody:")]
+    pub fn body_data(mut self, data: &NSData) -> Self {
+        self.body = Some(Body::Data(data.retain()));
+        self
+    }
+
+    /// Stream the body of the request from the given input stream.
+    ///
+    /// The stream must not have been opened. This replaces any previously
+    /// set body.
+    #[cfg(feature = "NSStream")]
+    #[doc(alias = "setHTTPBodyStream:")]
+    pub fn body_stream(mut self, stream: &NSInputStream) -> Self {
+        self.body = Some(Body::Stream(stream.retain()));
+        self
+    }
+
+    /// Set how the request uses the URL loading system's cache.
+    #[doc(alias = "setCachePolicy:")]
+    pub fn cache_policy(mut self, cache_policy: NSURLRequestCachePolicy) -> Self {
+        self.cache_policy = Some(cache_policy);
+        self
+    }
+
+    /// Set the maximum time the request may be idle before it times out.
+    ///
+    /// Defaults to 60 seconds.
+    #[cfg(feature = "NSDate")]
+    #[doc(alias = "setTimeoutInterval:")]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Create the request.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid method or header that was given to the
+    /// builder.
+    pub fn build(self) -> Result<Retained<NSMutableURLRequest>, NSURLRequestBuilderError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let request = unsafe { NSMutableURLRequest::requestWithURL(&self.url) };
+        unsafe {
+            if let Some(method) = &self.method {
+                request.setHTTPMethod(&NSString::from_str(method));
+            }
+            for (name, value) in &self.headers {
+                request.addValue_forHTTPHeaderField(
+                    &NSString::from_str(value),
+                    &NSString::from_str(name),
+                );
+            }
+            if let Some(body) = &self.body {
+                body.apply(&request);
+            }
+            if let Some(cache_policy) = self.cache_policy {
+                request.setCachePolicy(cache_policy);
+            }
+            #[cfg(feature = "NSDate")]
+            if let Some(timeout) = self.timeout {
+                request.setTimeoutInterval(timeout.as_secs_f64());
+            }
+        }
+        Ok(request)
+    }
+}
@@ -0,0 +1,426 @@
+//! Background URL sessions, whose transfers continue while the application
+//! is suspended or terminated.
+//!
+//! When a transfer in a background session finishes while the application
+//! is not running, the system relaunches it (in the background on iOS), and
+//! calls `application:handleEventsForBackgroundURLSession:completionHandler:`
+//! on the application delegate. The application must then recreate the
+//! session with the same identifier, which causes the events to be
+//! delivered to the session's delegate.
+//!
+//! [`NSBackgroundURLSession`] implements that delegate, and forwards the
+//! events to a Rust closure. Events that arrive before the closure has been
+//! set are queued, and replayed once it is, and downloaded files are moved
+//! out of their temporary location before the event is queued, so no data
+//! is lost in between.
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use core::fmt;
+use std::sync::{Mutex, MutexGuard};
+
+use block2::{Block, RcBlock};
+use objc2::rc::Retained;
+use objc2::runtime::{NSObject, NSObjectProtocol, ProtocolObject};
+use objc2::{define_class, msg_send_id, AllocAnyThread, DefinedClass, Message};
+
+use crate::{
+    NSData, NSError, NSFileManager, NSOperationQueue, NSString, NSURLSession,
+    NSURLSessionConfiguration, NSURLSessionDelegate, NSURLSessionDownloadDelegate,
+    NSURLSessionDownloadTask, NSURLSessionTask, NSURLSessionTaskDelegate,
+    NSURLSessionTransferSizeUnknown, NSURL,
+};
+
+/// An event delivered to the closure of an [`NSBackgroundURLSession`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum NSURLSessionBackgroundEvent {
+    /// Data was written for a download.
+    DownloadProgress {
+        /// The download task.
+        task: Retained<NSURLSessionDownloadTask>,
+        /// The total number of bytes downloaded so far.
+        total_bytes_written: i64,
+        /// The expected size of the download, if known.
+        total_bytes_expected: Option<i64>,
+    },
+    /// A download finished, and the downloaded file was moved to the
+    /// session's downloads directory.
+    ///
+    /// The application is responsible for moving the file from there, or
+    /// deleting it.
+    DownloadFinished {
+        /// The download task.
+        task: Retained<NSURLSessionDownloadTask>,
+        /// The URL of the downloaded file, or the error that occurred while
+        /// moving it out of its temporary location.
+        location: Result<Retained<NSURL>, Retained<NSError>>,
+    },
+    /// A task completed, successfully or with an error.
+    ///
+    /// For downloads, this is delivered after
+    /// [`DownloadFinished`][Self::DownloadFinished].
+    TaskCompleted {
+        /// The task.
+        task: Retained<NSURLSessionTask>,
+        /// The error that the task failed with, if any.
+        error: Option<Retained<NSError>>,
+    },
+    /// The timings of a task were collected, e.g. to report them to a
+    /// monitoring service.
+    ///
+    /// This is delivered before [`TaskCompleted`][Self::TaskCompleted].
+    #[cfg(all(feature = "NSArray", feature = "NSDate", feature = "NSDateInterval"))]
+    TaskMetrics {
+        /// The task.
+        task: Retained<NSURLSessionTask>,
+        /// The timings of the task.
+        timings: crate::NSURLSessionTaskTimings,
+    },
+    /// All events that were enqueued for the session while the application
+    /// was not running have been delivered.
+    ///
+    /// The completion handler set with
+    /// [`NSBackgroundURLSession::set_background_events_completion_handler`]
+    /// is called after this has been handled.
+    BackgroundEventsFinished,
+}
+
+type Handler = Box<dyn FnMut(NSURLSessionBackgroundEvent) + Send>;
+
+/// The completion handler from
+/// `application:handleEventsForBackgroundURLSession:completionHandler:`.
+struct CompletionHandler(RcBlock<dyn Fn()>);
+
+// SAFETY: The completion handler is only ever called on the main thread,
+// and blocks are reference-counted atomically.
+unsafe impl Send for CompletionHandler {}
+
+struct State {
+    handler: Option<Handler>,
+    // Whether the handler has been taken out to be called.
+    handler_busy: bool,
+    queue: VecDeque<NSURLSessionBackgroundEvent>,
+    completion_handler: Option<CompletionHandler>,
+}
+
+struct Ivars {
+    downloads_directory: Retained<NSURL>,
+    state: Mutex<State>,
+}
+
+define_class!(
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - `SessionDelegate` does not implement `Drop`.
+    #[unsafe(super(NSObject))]
+    #[name = "__RustNSBackgroundURLSessionDelegate"]
+    #[ivars = Ivars]
+    struct SessionDelegate;
+
+    unsafe impl NSObjectProtocol for SessionDelegate {}
+
+    unsafe impl NSURLSessionDelegate for SessionDelegate {
+        #[method(URLSessionDidFinishEventsForBackgroundURLSession:)]
+        fn did_finish_events(&self, _session: &NSURLSession) {
+            self.send(NSURLSessionBackgroundEvent::BackgroundEventsFinished);
+        }
+    }
+
+    unsafe impl NSURLSessionTaskDelegate for SessionDelegate {
+        #[method(URLSession:task:didCompleteWithError:)]
+        fn did_complete(
+            &self,
+            _session: &NSURLSession,
+            task: &NSURLSessionTask,
+            error: Option<&NSError>,
+        ) {
+            self.send(NSURLSessionBackgroundEvent::TaskCompleted {
+                task: task.retain(),
+                error: error.map(|error| error.retain()),
+            });
+        }
+
+        #[cfg(all(feature = "NSArray", feature = "NSDate", feature = "NSDateInterval"))]
+        #[method(URLSession:task:didFinishCollectingMetrics:)]
+        fn did_finish_collecting_metrics(
+            &self,
+            _session: &NSURLSession,
+            task: &NSURLSessionTask,
+            metrics: &crate::NSURLSessionTaskMetrics,
+        ) {
+            self.send(NSURLSessionBackgroundEvent::TaskMetrics {
+                task: task.retain(),
+                timings: metrics.timings(),
+            });
+        }
+    }
+
+    unsafe impl NSURLSessionDownloadDelegate for SessionDelegate {
+        #[method(URLSession:downloadTask:didFinishDownloadingToURL:)]
+        fn did_finish_downloading(
+            &self,
+            _session: &NSURLSession,
+            task: &NSURLSessionDownloadTask,
+            location: &NSURL,
+        ) {
+            // The file at `location` is deleted when this method returns,
+            // so it must be moved before the event is queued.
+            let location = self.persist_download(location);
+            self.send(NSURLSessionBackgroundEvent::DownloadFinished {
+                task: task.retain(),
+                location,
+            });
+        }
+
+        #[method(URLSession:downloadTask:didWriteData:totalBytesWritten:totalBytesExpectedToWrite:)]
+        fn did_write_data(
+            &self,
+            _session: &NSURLSession,
+            task: &NSURLSessionDownloadTask,
+            _bytes_written: i64,
+            total_bytes_written: i64,
+            total_bytes_expected: i64,
+        ) {
+            let unknown = unsafe { NSURLSessionTransferSizeUnknown };
+            self.send(NSURLSessionBackgroundEvent::DownloadProgress {
+                task: task.retain(),
+                total_bytes_written,
+                total_bytes_expected: (total_bytes_expected != unknown)
+                    .then_some(total_bytes_expected),
+            });
+        }
+    }
+);
+
+impl SessionDelegate {
+    fn new(downloads_directory: Retained<NSURL>) -> Retained<Self> {
+        let this = Self::alloc().set_ivars(Ivars {
+            downloads_directory,
+            state: Mutex::new(State {
+                handler: None,
+                handler_busy: false,
+                queue: VecDeque::new(),
+                completion_handler: None,
+            }),
+        });
+        unsafe { msg_send_id![super(this), init] }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        // The state is never left inconsistent by a panic.
+        self.ivars()
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn persist_download(&self, location: &NSURL) -> Result<Retained<NSURL>, Retained<NSError>> {
+        // The temporary file name is unique.
+        let name = unsafe { location.lastPathComponent() }
+            .unwrap_or_else(|| NSString::from_str("download"));
+        let destination = unsafe {
+            self.ivars()
+                .downloads_directory
+                .URLByAppendingPathComponent(&name)
+        }
+        .expect("failed creating download destination URL");
+        let manager = unsafe { NSFileManager::defaultManager() };
+        manager.move_item(location, &destination)?;
+        Ok(destination)
+    }
+
+    fn send(&self, event: NSURLSessionBackgroundEvent) {
+        self.state().queue.push_back(event);
+        self.drain();
+    }
+
+    /// Deliver queued events to the handler, if there is one and it is not
+    /// already being called on another thread.
+    fn drain(&self) {
+        loop {
+            let mut state = self.state();
+            if state.handler_busy || state.queue.is_empty() {
+                return;
+            }
+            let Some(mut handler) = state.handler.take() else {
+                return;
+            };
+            let event = state.queue.pop_front().unwrap();
+            state.handler_busy = true;
+            // Don't hold the lock while calling the handler, so that it can
+            // replace itself.
+            drop(state);
+
+            let finished = matches!(event, NSURLSessionBackgroundEvent::BackgroundEventsFinished);
+            handler(event);
+
+            let mut state = self.state();
+            state.handler_busy = false;
+            if state.handler.is_none() {
+                state.handler = Some(handler);
+            }
+            if finished {
+                if let Some(CompletionHandler(completion_handler)) = state.completion_handler.take()
+                {
+                    drop(state);
+                    // The completion handler must be called on the main
+                    // thread.
+                    let block = RcBlock::new(move || completion_handler.call(()));
+                    unsafe { NSOperationQueue::mainQueue().addOperationWithBlock(&block) };
+                }
+            }
+        }
+    }
+}
+
+/// A background URL session, which forwards its events to a closure.
+///
+/// See the [module-level documentation][self] for details.
+///
+/// Only a single session with a given identifier may exist in a process at
+/// a time. The session, and the delegate that receives its events, remain
+/// alive until the session is invalidated; dropping this does not cancel
+/// any transfers.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use objc2_foundation::{ns_string, NSBackgroundURLSession, NSURLSessionBackgroundEvent, NSURL};
+///
+/// // Also done from `application:handleEventsForBackgroundURLSession:completionHandler:`
+/// // after the application has been relaunched.
+/// let downloads_dir = NSURL::from_path("/tmp/downloads".as_ref(), true).unwrap();
+/// let session = NSBackgroundURLSession::new("com.example.downloads", &downloads_dir);
+/// session.set_event_handler(|event| match event {
+///     NSURLSessionBackgroundEvent::DownloadFinished { location, .. } => {
+///         println!("downloaded to {location:?}");
+///     }
+///     _ => {}
+/// });
+///
+/// let url = unsafe { NSURL::URLWithString(ns_string!("https://example.com/large-file")) };
+/// session.download(&url.unwrap());
+/// ```
+pub struct NSBackgroundURLSession {
+    session: Retained<NSURLSession>,
+    delegate: Retained<SessionDelegate>,
+}
+
+impl NSBackgroundURLSession {
+    /// Create a background session with the given identifier.
+    ///
+    /// The session is configured to relaunch the application when its
+    /// transfers finish. Downloaded files are moved to
+    /// `downloads_directory`, which must exist.
+    #[doc(alias = "backgroundSessionConfigurationWithIdentifier:")]
+    pub fn new(identifier: &str, downloads_directory: &NSURL) -> Self {
+        let identifier = NSString::from_str(identifier);
+        let configuration = unsafe {
+            NSURLSessionConfiguration::backgroundSessionConfigurationWithIdentifier(&identifier)
+        };
+        unsafe { configuration.setSessionSendsLaunchEvents(true) };
+        Self::with_configuration(&configuration, downloads_directory)
+    }
+
+    /// Create a session with the given configuration, which should have been
+    /// created with `backgroundSessionConfigurationWithIdentifier:`.
+    ///
+    /// Downloaded files are moved to `downloads_directory`, which must
+    /// exist.
+    #[doc(alias = "sessionWithConfiguration:delegate:delegateQueue:")]
+    pub fn with_configuration(
+        configuration: &NSURLSessionConfiguration,
+        downloads_directory: &NSURL,
+    ) -> Self {
+        let delegate = SessionDelegate::new(downloads_directory.retain());
+        // The session retains the delegate until it is invalidated. Events
+        // are delivered on a serial queue created by the session.
+        let session = unsafe {
+            NSURLSession::sessionWithConfiguration_delegate_delegateQueue(
+                configuration,
+                Some(ProtocolObject::from_ref(&*delegate)),
+                None,
+            )
+        };
+        Self { session, delegate }
+    }
+
+    /// Set the closure that receives the session's events.
+    ///
+    /// Events that were queued before the closure was set are delivered to
+    /// it immediately, on the current thread. Later events are delivered on
+    /// the session's delegate queue.
+    pub fn set_event_handler(
+        &self,
+        handler: impl FnMut(NSURLSessionBackgroundEvent) + Send + 'static,
+    ) {
+        self.delegate.state().handler = Some(Box::new(handler));
+        self.delegate.drain();
+    }
+
+    /// Set the completion handler that was passed to
+    /// `application:handleEventsForBackgroundURLSession:completionHandler:`.
+    ///
+    /// It is called on the main thread once
+    /// [`NSURLSessionBackgroundEvent::BackgroundEventsFinished`] has been
+    /// handled, which tells the system that the application can be
+    /// suspended again.
+    pub fn set_background_events_completion_handler(&self, completion_handler: &Block<dyn Fn()>) {
+        self.delegate.state().completion_handler =
+            Some(CompletionHandler(completion_handler.copy()));
+    }
+
+    /// Start downloading the resource at the given URL.
+    #[doc(alias = "downloadTaskWithURL:")]
+    pub fn download(&self, url: &NSURL) -> Retained<NSURLSessionDownloadTask> {
+        let task = unsafe { self.session.downloadTaskWithURL(url) };
+        unsafe { task.resume() };
+        task
+    }
+
+    /// Resume a download from the resume data of a cancelled or failed
+    /// download.
+    ///
+    /// The resume data of a failed download is available in the error's
+    /// user info under `NSURLSessionDownloadTaskResumeData`.
+    #[doc(alias = "downloadTaskWithResumeData:")]
+    pub fn resume_download(&self, resume_data: &NSData) -> Retained<NSURLSessionDownloadTask> {
+        let task = unsafe { self.session.downloadTaskWithResumeData(resume_data) };
+        unsafe { task.resume() };
+        task
+    }
+
+    /// The underlying session.
+    pub fn session(&self) -> &NSURLSession {
+        &self.session
+    }
+
+    /// Invalidate the session once its current transfers have finished.
+    ///
+    /// Events for the current transfers are still delivered.
+    #[doc(alias = "finishTasksAndInvalidate")]
+    pub fn finish_and_invalidate(self) {
+        unsafe { self.session.finishTasksAndInvalidate() };
+    }
+
+    /// Cancel the session's transfers, and invalidate it.
+    #[doc(alias = "invalidateAndCancel")]
+    pub fn cancel_and_invalidate(self) {
+        unsafe { self.session.invalidateAndCancel() };
+    }
+}
+
+impl fmt::Debug for NSBackgroundURLSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.delegate.state();
+        f.debug_struct("NSBackgroundURLSession")
+            .field("session", &self.session)
+            .field(
+                "downloads_directory",
+                &self.delegate.ivars().downloads_directory,
+            )
+            .field("queued_events", &state.queue.len())
+            .finish_non_exhaustive()
+    }
+}
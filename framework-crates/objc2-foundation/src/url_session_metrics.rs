@@ -0,0 +1,144 @@
+//! Typed timings of URL session tasks, e.g. for feeding into monitoring.
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::time::Duration;
+use std::time::SystemTime;
+
+use objc2::rc::Retained;
+
+use crate::{
+    NSDate, NSURLSessionTaskMetrics, NSURLSessionTaskMetricsResourceFetchType,
+    NSURLSessionTaskTransactionMetrics,
+};
+
+/// The duration between two dates, if both are known.
+fn phase(start: Option<Retained<NSDate>>, end: Option<Retained<NSDate>>) -> Option<Duration> {
+    end?.duration_since(&*start?)
+}
+
+/// The timings of a single request-response transaction of a task.
+///
+/// A task performs a transaction for the initial request and for each
+/// redirect. Phases that did not happen, such as connecting when a
+/// connection was reused, or anything but the fetch when the response was
+/// loaded from the cache, are [`None`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+#[doc(alias = "NSURLSessionTaskTransactionMetrics")]
+pub struct NSURLSessionTransactionTimings {
+    /// When the task started fetching the resource.
+    pub fetch_start: Option<SystemTime>,
+    /// The time spent resolving the host name (DNS).
+    pub domain_lookup: Option<Duration>,
+    /// The time spent establishing the connection, including the TLS
+    /// handshake.
+    pub connect: Option<Duration>,
+    /// The time spent on the TLS handshake.
+    pub secure_connection: Option<Duration>,
+    /// The time spent sending the request.
+    pub request: Option<Duration>,
+    /// The time from sending the request until the first byte of the
+    /// response was received, i.e. the server's processing time.
+    pub waiting: Option<Duration>,
+    /// The time spent receiving the response.
+    pub response: Option<Duration>,
+    /// The time from the start of the fetch until the response was
+    /// received.
+    pub total: Option<Duration>,
+    /// The network protocol used, as an ALPN protocol ID such as `"h2"`.
+    pub network_protocol: Option<String>,
+    /// Whether a persistent connection was reused.
+    pub reused_connection: bool,
+    /// Whether the connection went through a proxy.
+    pub proxy_connection: bool,
+    /// How the resource was loaded.
+    pub fetch_type: NSURLSessionTaskMetricsResourceFetchType,
+    /// The number of bytes of headers and body sent.
+    pub bytes_sent: u64,
+    /// The number of bytes of headers and body received.
+    pub bytes_received: u64,
+}
+
+/// The timings of a task, as delivered to
+/// `URLSession:task:didFinishCollectingMetrics:`.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+#[doc(alias = "NSURLSessionTaskMetrics")]
+pub struct NSURLSessionTaskTimings {
+    /// When the task was created.
+    pub start: Option<SystemTime>,
+    /// The time from creating the task until it completed.
+    pub duration: Duration,
+    /// The number of redirects that were followed.
+    pub redirect_count: usize,
+    /// The transactions of the task, in the order they were performed.
+    pub transactions: Vec<NSURLSessionTransactionTimings>,
+}
+
+/// Conversion to typed timings.
+impl NSURLSessionTaskTransactionMetrics {
+    /// The timings of the transaction.
+    pub fn timings(&self) -> NSURLSessionTransactionTimings {
+        unsafe {
+            NSURLSessionTransactionTimings {
+                fetch_start: self.fetchStartDate().and_then(|d| d.to_system_time()),
+                domain_lookup: phase(self.domainLookupStartDate(), self.domainLookupEndDate()),
+                connect: phase(self.connectStartDate(), self.connectEndDate()),
+                secure_connection: phase(
+                    self.secureConnectionStartDate(),
+                    self.secureConnectionEndDate(),
+                ),
+                request: phase(self.requestStartDate(), self.requestEndDate()),
+                waiting: phase(self.requestEndDate(), self.responseStartDate()),
+                response: phase(self.responseStartDate(), self.responseEndDate()),
+                total: phase(self.fetchStartDate(), self.responseEndDate()),
+                network_protocol: self.networkProtocolName().map(|name| name.to_string()),
+                reused_connection: self.isReusedConnection(),
+                proxy_connection: self.isProxyConnection(),
+                fetch_type: self.resourceFetchType(),
+                bytes_sent: (self.countOfRequestHeaderBytesSent()
+                    + self.countOfRequestBodyBytesSent())
+                .max(0) as u64,
+                bytes_received: (self.countOfResponseHeaderBytesReceived()
+                    + self.countOfResponseBodyBytesReceived())
+                .max(0) as u64,
+            }
+        }
+    }
+}
+
+/// Conversion to typed timings.
+impl NSURLSessionTaskMetrics {
+    /// The timings of the task and its transactions.
+    pub fn timings(&self) -> NSURLSessionTaskTimings {
+        let interval = unsafe { self.taskInterval() };
+        NSURLSessionTaskTimings {
+            start: unsafe { interval.startDate() }.to_system_time(),
+            duration: Duration::try_from_secs_f64(unsafe { interval.duration() })
+                .unwrap_or_default(),
+            redirect_count: unsafe { self.redirectCount() },
+            transactions: unsafe { self.transactionMetrics() }
+                .iter()
+                .map(|metrics| metrics.timings())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase() {
+        let start = unsafe { NSDate::now() };
+        let end = start.add_duration(Duration::from_millis(250));
+        let duration = phase(Some(start.clone()), Some(end.clone())).unwrap();
+        assert!(Duration::from_millis(249) < duration && duration < Duration::from_millis(251));
+
+        assert_eq!(phase(None, Some(end.clone())), None);
+        assert_eq!(phase(Some(start.clone()), None), None);
+        // Out of order.
+        assert_eq!(phase(Some(end), Some(start)), None);
+    }
+}
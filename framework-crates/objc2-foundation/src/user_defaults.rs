@@ -0,0 +1,349 @@
+//! Typed access to `NSUserDefaults`.
+use alloc::string::{String, ToString};
+#[cfg(feature = "NSArray")]
+use alloc::vec::Vec;
+
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2::Message;
+
+use crate::{NSString, NSUserDefaults};
+
+/// A value that can be stored in [`NSUserDefaults`].
+///
+/// This is implemented for booleans, numbers, strings and arrays of those,
+/// see [`NSUserDefaults::get`] and [`NSUserDefaults::set`]. Other types can
+/// be stored with `get_deserialized` and `set_serialized` when the `serde`
+/// feature is enabled.
+pub trait NSUserDefaultsValue {
+    /// Convert the property list object read from the defaults to the value.
+    ///
+    /// Returns [`None`] if the object has a different type.
+    fn from_object(object: &AnyObject) -> Option<Self>
+    where
+        Self: Sized;
+
+    /// Convert the value to a property list object to be stored in the
+    /// defaults.
+    fn to_object(&self) -> Retained<AnyObject>;
+}
+
+fn erase<T: Message>(object: Retained<T>) -> Retained<AnyObject> {
+    // SAFETY: All `Message` types are objects.
+    unsafe { Retained::cast_unchecked(object) }
+}
+
+#[cfg(feature = "NSValue")]
+macro_rules! impl_number {
+    ($($ty:ty => $new:ident),* $(,)?) => {$(
+        impl NSUserDefaultsValue for $ty {
+            fn from_object(object: &AnyObject) -> Option<Self> {
+                let number = object.downcast_ref::<crate::NSNumber>()?;
+                Self::try_from(number).ok()
+            }
+
+            fn to_object(&self) -> Retained<AnyObject> {
+                erase(crate::NSNumber::$new(*self))
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "NSValue")]
+impl_number! {
+    bool => new_bool,
+    i8 => new_i8,
+    u8 => new_u8,
+    i16 => new_i16,
+    u16 => new_u16,
+    i32 => new_i32,
+    u32 => new_u32,
+    i64 => new_i64,
+    u64 => new_u64,
+    isize => new_isize,
+    usize => new_usize,
+    f32 => new_f32,
+    f64 => new_f64,
+}
+
+impl NSUserDefaultsValue for str {
+    fn to_object(&self) -> Retained<AnyObject> {
+        erase(NSString::from_str(self))
+    }
+}
+
+impl NSUserDefaultsValue for String {
+    fn from_object(object: &AnyObject) -> Option<Self> {
+        Some(object.downcast_ref::<NSString>()?.to_string())
+    }
+
+    fn to_object(&self) -> Retained<AnyObject> {
+        (**self).to_object()
+    }
+}
+
+#[cfg(feature = "NSArray")]
+impl<T: NSUserDefaultsValue> NSUserDefaultsValue for [T] {
+    fn to_object(&self) -> Retained<AnyObject> {
+        let objects: Vec<_> = self.iter().map(T::to_object).collect();
+        erase(crate::NSArray::from_retained_slice(&objects))
+    }
+}
+
+/// Fails if any of the elements have a different type.
+#[cfg(feature = "NSArray")]
+impl<T: NSUserDefaultsValue> NSUserDefaultsValue for Vec<T> {
+    fn from_object(object: &AnyObject) -> Option<Self> {
+        let array = object.downcast_ref::<crate::NSArray>()?;
+        array
+            .to_vec()
+            .iter()
+            .map(|object| T::from_object(object))
+            .collect()
+    }
+
+    fn to_object(&self) -> Retained<AnyObject> {
+        (**self).to_object()
+    }
+}
+
+/// Typed accessors.
+///
+/// Unlike the raw `*ForKey:` getters, these return [`None`] if the key is
+/// not present in the defaults (or if the stored value has a different
+/// type), instead of returning a default value.
+impl NSUserDefaults {
+    /// The shared defaults object of the current user.
+    #[doc(alias = "standardUserDefaults")]
+    pub fn standard() -> Retained<Self> {
+        unsafe { Self::standardUserDefaults() }
+    }
+
+    /// Get the value associated with the given key.
+    ///
+    /// The value is looked up in all the domains of the defaults (including
+    /// command-line arguments, and values registered with
+    /// `registerDefaults:`).
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use objc2_foundation::NSUserDefaults;
+    ///
+    /// let defaults = NSUserDefaults::standard();
+    /// defaults.set("objc2.example.launch_count", &3i64);
+    /// assert_eq!(defaults.get::<i64>("objc2.example.launch_count"), Some(3));
+    /// assert_eq!(defaults.get::<String>("objc2.example.launch_count"), None);
+    /// defaults.remove("objc2.example.launch_count");
+    /// ```
+    #[doc(alias = "objectForKey:")]
+    pub fn get<T: NSUserDefaultsValue>(&self, key: &str) -> Option<T> {
+        let key = NSString::from_str(key);
+        let object = unsafe { self.objectForKey(&key) }?;
+        T::from_object(&object)
+    }
+
+    /// Associate a value with the given key in the application's domain.
+    #[doc(alias = "setObject:forKey:")]
+    pub fn set<T: NSUserDefaultsValue + ?Sized>(&self, key: &str, value: &T) {
+        let key = NSString::from_str(key);
+        unsafe { self.setObject_forKey(Some(&value.to_object()), &key) };
+    }
+
+    /// Remove the value associated with the given key from the
+    /// application's domain.
+    #[doc(alias = "removeObjectForKey:")]
+    pub fn remove(&self, key: &str) {
+        let key = NSString::from_str(key);
+        unsafe { self.removeObjectForKey(&key) };
+    }
+}
+
+/// Serde support.
+#[cfg(all(
+    feature = "serde",
+    feature = "NSArray",
+    feature = "NSData",
+    feature = "NSDictionary",
+    feature = "NSObject",
+    feature = "NSValue"
+))]
+impl NSUserDefaults {
+    /// Get the value associated with the given key, and deserialize it from
+    /// a property list.
+    ///
+    /// Returns `Ok(None)` if the key is not present in the defaults.
+    ///
+    /// See [`property_list`](crate::property_list) for how values are
+    /// represented.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stored value could not be deserialized.
+    #[doc(alias = "objectForKey:")]
+    pub fn get_deserialized<T: serde::de::DeserializeOwned>(
+        &self,
+        key: &str,
+    ) -> Result<Option<T>, crate::property_list::Error> {
+        let key = NSString::from_str(key);
+        match unsafe { self.objectForKey(&key) } {
+            Some(object) => crate::property_list::from_object(&object).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Serialize the value to a property list, and associate it with the
+    /// given key in the application's domain.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value could not be serialized, in which case
+    /// the defaults are left unchanged.
+    #[doc(alias = "setObject:forKey:")]
+    pub fn set_serialized<T: serde::Serialize + ?Sized>(
+        &self,
+        key: &str,
+        value: &T,
+    ) -> Result<(), crate::property_list::Error> {
+        let object = crate::property_list::to_object(value)?;
+        let key = NSString::from_str(key);
+        unsafe { self.setObject_forKey(Some(&object), &key) };
+        Ok(())
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    feature = "NSDictionary",
+    feature = "NSKeyValueObserving",
+    feature = "NSLock",
+    feature = "NSNull"
+))]
+mod changes {
+    use core::fmt;
+    use core::future::Future;
+    use core::marker::PhantomData;
+    use core::task::{Context, Poll};
+
+    use super::NSUserDefaultsValue;
+    use crate::channel::{self, Receiver};
+    use crate::key_value_observing::KeyValueObservation;
+    use crate::{NSKeyValueObservingOptions, NSString, NSUserDefaults};
+
+    /// A stream of changes to a value in [`NSUserDefaults`].
+    ///
+    /// Each item is the new value, or [`None`] if the key was removed (or
+    /// the new value has a different type). Changes are queued from the
+    /// moment the stream is created, and can be retrieved either by blocking
+    /// ([`recv`]), by polling ([`try_next`]), or asynchronously ([`next`] or
+    /// [`poll_next`], the latter of which can be used to implement
+    /// `futures::Stream`).
+    ///
+    /// The observer is removed when this is dropped.
+    ///
+    /// See [`NSUserDefaults::changes`].
+    ///
+    /// [`recv`]: Self::recv
+    /// [`try_next`]: Self::try_next
+    /// [`next`]: Self::next
+    /// [`poll_next`]: Self::poll_next
+    pub struct NSUserDefaultsChanges<T> {
+        receiver: Receiver<Option<T>>,
+        observation: KeyValueObservation,
+        // The observer is not thread-safe.
+        _marker: PhantomData<*const ()>,
+    }
+
+    impl NSUserDefaults {
+        /// Observe changes to the value associated with the given key.
+        ///
+        /// Changes are observed with Key-Value Observing, which is notified
+        /// synchronously on the thread that made the change, including for
+        /// changes made by other processes.
+        ///
+        /// Note that key-value observing treats `.` as a key path separator,
+        /// so the key must not contain `.` or start with `@`.
+        ///
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use objc2_foundation::NSUserDefaults;
+        ///
+        /// let defaults = NSUserDefaults::standard();
+        /// let changes = defaults.changes::<String>("objc2ExampleTheme");
+        ///
+        /// defaults.set("objc2ExampleTheme", "dark");
+        /// assert_eq!(changes.try_next(), Some(Some("dark".to_string())));
+        ///
+        /// defaults.remove("objc2ExampleTheme");
+        /// assert_eq!(changes.try_next(), Some(None));
+        /// ```
+        #[doc(alias = "addObserver:forKeyPath:options:context:")]
+        #[doc(alias = "observeValueForKeyPath:ofObject:change:context:")]
+        pub fn changes<T>(&self, key: &str) -> NSUserDefaultsChanges<T>
+        where
+            T: NSUserDefaultsValue + Send + 'static,
+        {
+            let key = NSString::from_str(key);
+            let (sender, receiver) = channel::unbounded();
+            // The new value is `NSNull` if the key was removed.
+            let observation =
+                KeyValueObservation::new(self, &key, NSKeyValueObservingOptions::New, move |new| {
+                    let _ = sender.send(new.and_then(T::from_object));
+                });
+
+            NSUserDefaultsChanges {
+                receiver,
+                observation,
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl<T> NSUserDefaultsChanges<T> {
+        /// Get the next change if one has already been received.
+        pub fn try_next(&self) -> Option<Option<T>> {
+            self.receiver.try_recv()
+        }
+
+        /// Block the current thread until the next change is received.
+        ///
+        /// Beware that changes are delivered on the thread that made them, so
+        /// this will deadlock if that is the current thread.
+        pub fn recv(&self) -> Option<Option<T>> {
+            self.receiver.recv()
+        }
+
+        /// Poll for the next change.
+        pub fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<Option<T>>> {
+            self.receiver.poll_recv(cx)
+        }
+
+        /// Wait asynchronously for the next change.
+        pub fn next(&self) -> impl Future<Output = Option<Option<T>>> + '_ {
+            core::future::poll_fn(|cx| self.poll_next(cx))
+        }
+    }
+
+    impl<T> fmt::Debug for NSUserDefaultsChanges<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("NSUserDefaultsChanges")
+                .field("key", self.observation.key_path())
+                .field("receiver", &self.receiver)
+                .finish_non_exhaustive()
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    feature = "NSDictionary",
+    feature = "NSKeyValueObserving",
+    feature = "NSLock",
+    feature = "NSNull"
+))]
+pub use self::changes::NSUserDefaultsChanges;
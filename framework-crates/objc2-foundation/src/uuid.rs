@@ -1,3 +1,4 @@
+use core::cmp;
 use core::fmt;
 use core::panic::{RefUnwindSafe, UnwindSafe};
 
@@ -65,11 +66,16 @@ impl NSUUID {
         Self::initWithUUIDBytes(Self::alloc(), &bytes)
     }
 
+    /// Parse a `NSUUID` from a string such as
+    /// `"E621E1F8-C36C-495A-93FC-0C247A3E6E5F"`.
+    ///
+    /// Returns [`None`] if the string is not a valid UUID.
     #[cfg(feature = "NSString")]
     pub fn from_string(string: &crate::NSString) -> Option<Retained<Self>> {
         Self::initWithUUIDString(Self::alloc(), string)
     }
 
+    /// The bytes of the UUID.
     pub fn as_bytes(&self) -> [u8; 16] {
         let mut bytes = UuidBytes([0; 16]);
         self.getUUIDBytes(&mut bytes);
@@ -77,6 +83,34 @@ impl NSUUID {
     }
 }
 
+impl From<&NSUUID> for [u8; 16] {
+    #[inline]
+    fn from(uuid: &NSUUID) -> Self {
+        uuid.as_bytes()
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl NSUUID {
+    /// Create a `NSUUID` from a [`uuid::Uuid`].
+    pub fn from_uuid(uuid: ::uuid::Uuid) -> Retained<Self> {
+        Self::from_bytes(uuid.into_bytes())
+    }
+
+    /// Convert the `NSUUID` to a [`uuid::Uuid`].
+    pub fn to_uuid(&self) -> ::uuid::Uuid {
+        ::uuid::Uuid::from_bytes(self.as_bytes())
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<&NSUUID> for ::uuid::Uuid {
+    #[inline]
+    fn from(uuid: &NSUUID) -> Self {
+        uuid.to_uuid()
+    }
+}
+
 impl fmt::Display for NSUUID {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let string: Retained<NSObject> = unsafe { msg_send_id![self, UUIDString] };
@@ -95,18 +129,17 @@ impl fmt::Debug for NSUUID {
     }
 }
 
-// UUID `compare:` is broken for some reason?
-
-// impl PartialOrd for NSUUID {
-//     #[inline]
-//     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-//         Some(self.cmp(other))
-//     }
-// }
-
-// impl Ord for NSUUID {
-//     fn cmp(&self, other: &Self) -> cmp::Ordering {
-//         let res: NSComparisonResult = unsafe { msg_send![self, compare: other] };
-//         res.into()
-//     }
-// }
+// `compare:` is broken on some Foundation versions, so compare the bytes
+// instead. This is consistent with `isEqual:`, and with `uuid::Uuid`.
+impl PartialOrd for NSUUID {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NSUUID {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_bytes().cmp(&other.as_bytes())
+    }
+}
@@ -1,5 +1,5 @@
 use alloc::ffi::CString;
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
 use core::ffi::CStr;
 use core::fmt;
 use core::hash;
@@ -7,7 +7,7 @@ use core::mem::MaybeUninit;
 use core::ptr::NonNull;
 use core::str;
 
-use objc2::encode::Encode;
+use objc2::encode::{Encode, Encoding};
 use objc2::rc::Retained;
 use objc2::AllocAnyThread;
 
@@ -99,6 +99,51 @@ impl NSValue {
         unsafe { value.assume_init() }
     }
 
+    /// Retrieve the data contained in the `NSValue`, if it has the encoding
+    /// of `T`.
+    ///
+    /// This makes it possible to store values of different types in the
+    /// same collection, and recover them again afterwards.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the encoding of the contained data is not
+    /// equivalent to the encoding of `T`.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// Any safety invariants that `T` has beyond its encoding must be
+    /// upheld by the contained data. This is trivially the case for plain
+    /// numbers and structs of those, but not for e.g. `NonNull<T>`, see
+    /// [`get`](Self::get).
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use objc2_foundation::{NSPoint, NSRange, NSValue};
+    ///
+    /// let val = NSValue::new(NSRange::new(2, 3));
+    /// // SAFETY: `NSRange` is valid for any pair of integers.
+    /// assert_eq!(unsafe { val.try_get::<NSRange>() }, Ok(NSRange::new(2, 3)));
+    /// assert!(unsafe { val.try_get::<NSPoint>() }.is_err());
+    /// ```
+    #[doc(alias = "getValue:size:")]
+    pub unsafe fn try_get<T: 'static + Copy + Encode>(&self) -> Result<T, NSValueEncodingMismatch> {
+        if self.contains_encoding::<T>() {
+            // SAFETY: The encoding was just checked, and the caller upholds
+            // any further invariants.
+            Ok(unsafe { self.get::<T>() })
+        } else {
+            Err(NSValueEncodingMismatch {
+                expected: T::ENCODING,
+                actual: self.encoding().unwrap_or("").into(),
+            })
+        }
+    }
+
     #[cfg(feature = "NSRange")]
     pub fn get_range(&self) -> Option<crate::NSRange> {
         if self.contains_encoding::<crate::NSRange>() {
@@ -153,6 +198,39 @@ impl NSValue {
     }
 }
 
+/// The error returned by [`NSValue::try_get`] when the `NSValue` contains a
+/// different type than the requested one.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NSValueEncodingMismatch {
+    expected: Encoding,
+    actual: String,
+}
+
+impl NSValueEncodingMismatch {
+    /// The encoding of the requested type.
+    pub fn expected(&self) -> &Encoding {
+        &self.expected
+    }
+
+    /// The encoding of the data contained in the `NSValue`.
+    pub fn actual(&self) -> &str {
+        &self.actual
+    }
+}
+
+impl fmt::Display for NSValueEncodingMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "NSValue contains data with encoding {}, but the requested type has encoding {}",
+            self.actual, self.expected,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NSValueEncodingMismatch {}
+
 impl hash::Hash for NSValue {
     #[inline]
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
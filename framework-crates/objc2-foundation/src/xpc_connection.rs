@@ -0,0 +1,429 @@
+//! Communicating with other processes over XPC.
+//!
+//! The interface of an XPC connection is described by an Objective-C
+//! protocol, which in Rust is declared with [`extern_protocol!`]. The
+//! helpers in this module use the protocol's type to create the interface,
+//! and to type the exported object and the remote object proxy, so that
+//! messages sent over the connection are checked against the protocol.
+//!
+//! Objects passed over the connection are encoded with secure coding, so
+//! any classes besides the standard property list classes must be allowed
+//! with [`NSXPCInterface::set_allowed_classes`]. Classes declared with
+//! [`define_class!`] can support this by implementing [`NSKeyedCoding`].
+//!
+//! [`extern_protocol!`]: objc2::extern_protocol
+//! [`define_class!`]: objc2::define_class
+//! [`NSKeyedCoding`]: crate::NSKeyedCoding
+use alloc::boxed::Box;
+use core::fmt;
+use core::ptr::NonNull;
+
+use block2::RcBlock;
+use objc2::rc::Retained;
+#[cfg(all(
+    feature = "FoundationErrors",
+    feature = "NSCoder",
+    feature = "NSData",
+    feature = "NSDictionary",
+    feature = "NSKeyedArchiver",
+    feature = "NSObject",
+    feature = "NSSet"
+))]
+use objc2::runtime::Sel;
+use objc2::runtime::{AnyObject, ImplementedBy, NSObject, NSObjectProtocol, ProtocolObject};
+use objc2::{define_class, msg_send_id, AllocAnyThread, DefinedClass, Message, ProtocolType};
+
+#[cfg(all(
+    feature = "FoundationErrors",
+    feature = "NSCoder",
+    feature = "NSData",
+    feature = "NSDictionary",
+    feature = "NSKeyedArchiver",
+    feature = "NSObject",
+    feature = "NSSet"
+))]
+use crate::{NSAllowedClasses, NSSet};
+use crate::{
+    NSError, NSString, NSXPCConnection, NSXPCConnectionOptions, NSXPCInterface, NSXPCListener,
+    NSXPCListenerDelegate, NSXPCListenerEndpoint,
+};
+
+/// Check that the interface was created for the protocol `P`.
+fn assert_protocol<P: ?Sized + ProtocolType>(interface: Option<&NSXPCInterface>, what: &str) {
+    let interface = interface.unwrap_or_else(|| panic!("{what} interface must be set"));
+    let protocol = unsafe { interface.protocol() };
+    assert!(
+        P::protocol() == Some(&*protocol),
+        "{what} interface is for protocol {}, not {}",
+        protocol.name().to_string_lossy(),
+        P::NAME,
+    );
+}
+
+fn error_handler(
+    handler: impl Fn(&NSError) + Send + Sync + 'static,
+) -> RcBlock<dyn Fn(NonNull<NSError>)> {
+    RcBlock::new(move |error: NonNull<NSError>| {
+        // SAFETY: The error is valid for the duration of the block.
+        handler(unsafe { error.as_ref() });
+    })
+}
+
+/// Creating interfaces.
+impl NSXPCInterface {
+    /// Create an interface for the protocol `P`.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the protocol is not registered with the runtime. Note that
+    /// protocols declared in Objective-C are only registered if something
+    /// in the program references them with `@protocol(...)`.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2::extern_protocol;
+    /// use objc2::runtime::NSObjectProtocol;
+    /// use objc2_foundation::{NSString, NSXPCInterface};
+    ///
+    /// extern_protocol!(
+    ///     // SAFETY: The protocol inherits from `NSObjectProtocol`, and the
+    ///     // method is correctly specified.
+    ///     pub unsafe trait HelperProtocol: NSObjectProtocol {
+    ///         #[method(greet:reply:)]
+    ///         unsafe fn greet_reply(&self, name: &NSString, reply: &block2::Block<dyn Fn(*mut NSString)>);
+    ///     }
+    /// );
+    ///
+    /// let interface = NSXPCInterface::from_protocol::<dyn HelperProtocol>();
+    /// ```
+    #[doc(alias = "interfaceWithProtocol:")]
+    pub fn from_protocol<P: ?Sized + ProtocolType>() -> Retained<Self> {
+        let protocol =
+            P::protocol().unwrap_or_else(|| panic!("protocol {} is not registered", P::NAME));
+        // SAFETY: The protocol is valid.
+        unsafe { Self::interfaceWithProtocol(protocol) }
+    }
+
+    /// Allow instances of the given classes to be passed as an argument to
+    /// the given method, or as an argument to its reply block if `of_reply`
+    /// is `true`.
+    ///
+    /// Objects are decoded with secure coding, so the classes must support
+    /// it.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the protocol of the interface does not have the method, or
+    /// if it does not have an object argument at the given index.
+    #[cfg(all(
+        feature = "FoundationErrors",
+        feature = "NSCoder",
+        feature = "NSData",
+        feature = "NSDictionary",
+        feature = "NSKeyedArchiver",
+        feature = "NSObject",
+        feature = "NSSet"
+    ))]
+    #[doc(alias = "setClasses:forSelector:argumentIndex:ofReply:")]
+    pub fn set_allowed_classes(
+        &self,
+        sel: Sel,
+        argument_index: usize,
+        of_reply: bool,
+        allowed: &NSAllowedClasses,
+    ) {
+        let classes = NSSet::from_slice(allowed.classes());
+        // SAFETY: Invalid selectors and indices throw an exception instead
+        // of causing undefined behaviour.
+        unsafe {
+            self.setClasses_forSelector_argumentIndex_ofReply(
+                &classes,
+                sel,
+                argument_index,
+                of_reply,
+            );
+        }
+    }
+}
+
+/// Creating connections.
+impl NSXPCConnection {
+    /// Connect to the XPC service with the given bundle identifier.
+    ///
+    /// The service must be embedded in the application's bundle. The
+    /// connection is created suspended, and must be [resumed] after it has
+    /// been configured.
+    ///
+    /// [resumed]: Self::resume
+    #[doc(alias = "initWithServiceName:")]
+    pub fn with_service_name(name: &str) -> Retained<Self> {
+        let name = NSString::from_str(name);
+        unsafe { Self::initWithServiceName(Self::alloc(), &name) }
+    }
+
+    /// Connect to the Mach service with the given name, such as a launchd
+    /// agent or a privileged helper tool.
+    ///
+    /// Use [`NSXPCConnectionOptions::Privileged`] to connect to a service
+    /// running as root. The connection is created suspended, and must be
+    /// [resumed] after it has been configured.
+    ///
+    /// [resumed]: Self::resume
+    #[doc(alias = "initWithMachServiceName:options:")]
+    pub fn with_mach_service_name(name: &str, options: NSXPCConnectionOptions) -> Retained<Self> {
+        let name = NSString::from_str(name);
+        unsafe { Self::initWithMachServiceName_options(Self::alloc(), &name, options) }
+    }
+
+    /// Connect to the listener with the given endpoint, usually an
+    /// [anonymous listener] in another process.
+    ///
+    /// [anonymous listener]: NSXPCListener::anonymousListener
+    #[doc(alias = "initWithListenerEndpoint:")]
+    pub fn with_endpoint(endpoint: &NSXPCListenerEndpoint) -> Retained<Self> {
+        unsafe { Self::initWithListenerEndpoint(Self::alloc(), endpoint) }
+    }
+}
+
+/// Typed objects.
+impl NSXPCConnection {
+    /// Export an object to the other side of the connection.
+    ///
+    /// The interface must have been created for the protocol `P` with
+    /// [`NSXPCInterface::from_protocol`]. Messages from the other side are
+    /// delivered on a private queue, so the object must be thread-safe.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interface is not for the protocol `P`.
+    #[doc(alias = "setExportedInterface:")]
+    #[doc(alias = "setExportedObject:")]
+    pub fn set_exported_object<P, T>(&self, interface: &NSXPCInterface, object: &T)
+    where
+        P: ?Sized + ProtocolType + ImplementedBy<T>,
+        T: ?Sized + Message + Send + Sync,
+    {
+        assert_protocol::<P>(Some(interface), "exported");
+        let object: &ProtocolObject<P> = ProtocolObject::from_ref(object);
+        // SAFETY: All protocol objects are objects.
+        let object: &AnyObject = unsafe { &*(object as *const ProtocolObject<P>).cast() };
+        // SAFETY: The object implements the interface's protocol, and is
+        // thread-safe.
+        unsafe {
+            self.setExportedInterface(Some(interface));
+            self.setExportedObject(Some(object));
+        }
+    }
+
+    /// Get a proxy for the object exported by the other side of the
+    /// connection.
+    ///
+    /// Messages sent to the proxy are delivered asynchronously. If a message
+    /// cannot be delivered, e.g. because the connection was interrupted, the
+    /// error handler is called instead of the message's reply block.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [remote object interface] is not set to an interface
+    /// for the protocol `P`.
+    ///
+    /// [remote object interface]: Self::setRemoteObjectInterface
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use objc2_foundation::{NSXPCConnection, NSXPCConnectionOptions, NSXPCInterface};
+    ///
+    /// let connection = NSXPCConnection::with_mach_service_name(
+    ///     "com.example.helper",
+    ///     NSXPCConnectionOptions::Privileged,
+    /// );
+    /// let interface = NSXPCInterface::from_protocol::<dyn HelperProtocol>();
+    /// unsafe { connection.setRemoteObjectInterface(Some(&interface)) };
+    /// unsafe { connection.resume() };
+    ///
+    /// let proxy = connection.remote_object_proxy::<dyn HelperProtocol>(|error| {
+    ///     eprintln!("failed sending message: {error}");
+    /// });
+    /// let reply = block2::RcBlock::new(|greeting| { /* ... */ });
+    /// unsafe { proxy.greet_reply(ns_string!("world"), &reply) };
+    /// ```
+    #[doc(alias = "remoteObjectProxyWithErrorHandler:")]
+    pub fn remote_object_proxy<P: ?Sized + ProtocolType>(
+        &self,
+        error_handler: impl Fn(&NSError) + Send + Sync + 'static,
+    ) -> Retained<ProtocolObject<P>> {
+        assert_protocol::<P>(unsafe { self.remoteObjectInterface() }.as_deref(), "remote");
+        let handler = self::error_handler(error_handler);
+        let proxy = unsafe { self.remoteObjectProxyWithErrorHandler(&handler) };
+        // SAFETY: The proxy forwards messages from the interface's protocol,
+        // which was checked above.
+        unsafe { Retained::cast_unchecked(proxy) }
+    }
+
+    /// Get a proxy for the object exported by the other side of the
+    /// connection, whose messages wait for their reply before returning.
+    ///
+    /// See [`remote_object_proxy`](Self::remote_object_proxy) for details.
+    #[doc(alias = "synchronousRemoteObjectProxyWithErrorHandler:")]
+    pub fn synchronous_remote_object_proxy<P: ?Sized + ProtocolType>(
+        &self,
+        error_handler: impl Fn(&NSError) + Send + Sync + 'static,
+    ) -> Retained<ProtocolObject<P>> {
+        assert_protocol::<P>(unsafe { self.remoteObjectInterface() }.as_deref(), "remote");
+        let handler = self::error_handler(error_handler);
+        let proxy = unsafe { self.synchronousRemoteObjectProxyWithErrorHandler(&handler) };
+        // SAFETY: Same as above.
+        unsafe { Retained::cast_unchecked(proxy) }
+    }
+}
+
+/// Connection lifecycle.
+impl NSXPCConnection {
+    /// Call the closure when the other side of the connection exits or
+    /// crashes.
+    ///
+    /// The connection may still be used afterwards, in which case the
+    /// service is relaunched.
+    #[doc(alias = "setInterruptionHandler:")]
+    pub fn set_interruption_handler(&self, handler: impl Fn() + Send + Sync + 'static) {
+        let block = RcBlock::new(handler);
+        // SAFETY: The block is `Send + Sync`, since it is called on a
+        // private queue.
+        unsafe { self.setInterruptionHandler(Some(&block)) };
+    }
+
+    /// Call the closure when the connection is invalidated, after which it
+    /// can no longer be used.
+    ///
+    /// This happens if the service could not be found, or if the connection
+    /// was invalidated by either side.
+    #[doc(alias = "setInvalidationHandler:")]
+    pub fn set_invalidation_handler(&self, handler: impl Fn() + Send + Sync + 'static) {
+        let block = RcBlock::new(handler);
+        // SAFETY: Same as above.
+        unsafe { self.setInvalidationHandler(Some(&block)) };
+    }
+}
+
+type ConnectionHandler = Box<dyn Fn(&NSXPCConnection) -> bool + Send + Sync>;
+
+define_class!(
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - `ListenerDelegate` does not implement `Drop`.
+    #[unsafe(super(NSObject))]
+    #[name = "__RustNSXPCListenerDelegate"]
+    #[ivars = ConnectionHandler]
+    struct ListenerDelegate;
+
+    unsafe impl NSObjectProtocol for ListenerDelegate {}
+
+    unsafe impl NSXPCListenerDelegate for ListenerDelegate {
+        #[method(listener:shouldAcceptNewConnection:)]
+        fn should_accept_new_connection(
+            &self,
+            _listener: &NSXPCListener,
+            new_connection: &NSXPCConnection,
+        ) -> bool {
+            (self.ivars())(new_connection)
+        }
+    }
+);
+
+// SAFETY: The handler is `Send + Sync`.
+unsafe impl Send for ListenerDelegate {}
+unsafe impl Sync for ListenerDelegate {}
+
+impl ListenerDelegate {
+    fn new(handler: ConnectionHandler) -> Retained<Self> {
+        let this = Self::alloc().set_ivars(handler);
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+/// A connection handler registered with
+/// [`NSXPCListener::set_connection_handler`].
+///
+/// The handler is removed from the listener when this is dropped, after
+/// which new connections are rejected.
+#[must_use = "the handler is removed when this is dropped"]
+pub struct NSXPCConnectionHandler {
+    listener: Retained<NSXPCListener>,
+    delegate: Retained<ListenerDelegate>,
+}
+
+impl Drop for NSXPCConnectionHandler {
+    fn drop(&mut self) {
+        // Only remove the delegate if it hasn't been replaced.
+        let current = unsafe { self.listener.delegate() };
+        let ours: &ProtocolObject<dyn NSXPCListenerDelegate> =
+            ProtocolObject::from_ref(&*self.delegate);
+        if current
+            .as_deref()
+            .is_some_and(|current| core::ptr::eq(current, ours))
+        {
+            unsafe { self.listener.setDelegate(None) };
+        }
+    }
+}
+
+impl fmt::Debug for NSXPCConnectionHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSXPCConnectionHandler")
+            .field("listener", &self.listener)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Accepting connections.
+impl NSXPCListener {
+    /// Call the closure whenever a new connection is made to the listener.
+    ///
+    /// The closure should configure the connection's exported object and
+    /// [resume] it, and then return `true` to accept it, or return `false`
+    /// to reject it. It is called on a private queue, and must therefore be
+    /// `Send + Sync`.
+    ///
+    /// The listener does not retain its delegate, so the handler is kept
+    /// alive by the returned value.
+    ///
+    /// [resume]: NSXPCConnection::resume
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use objc2_foundation::{NSXPCInterface, NSXPCListener};
+    ///
+    /// let listener = unsafe { NSXPCListener::serviceListener() };
+    /// let _handler = listener.set_connection_handler(|connection| {
+    ///     let interface = NSXPCInterface::from_protocol::<dyn HelperProtocol>();
+    ///     connection.set_exported_object::<dyn HelperProtocol, _>(&interface, &*Helper::new());
+    ///     unsafe { connection.resume() };
+    ///     true
+    /// });
+    /// // Never returns.
+    /// unsafe { listener.resume() };
+    /// ```
+    #[doc(alias = "setDelegate:")]
+    #[doc(alias = "listener:shouldAcceptNewConnection:")]
+    pub fn set_connection_handler(
+        &self,
+        handler: impl Fn(&NSXPCConnection) -> bool + Send + Sync + 'static,
+    ) -> NSXPCConnectionHandler {
+        let delegate = ListenerDelegate::new(Box::new(handler));
+        unsafe { self.setDelegate(Some(ProtocolObject::from_ref(&*delegate))) };
+        NSXPCConnectionHandler {
+            listener: self.retain(),
+            delegate,
+        }
+    }
+}
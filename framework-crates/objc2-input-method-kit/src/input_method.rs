@@ -0,0 +1,481 @@
+//! Implementing input methods in Rust.
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+use std::sync::OnceLock;
+
+use objc2::rc::{Allocated, Retained};
+use objc2::runtime::{AnyObject, NSObjectProtocol};
+use objc2::{
+    define_class, msg_send, msg_send_id, AllocAnyThread, ClassType, DefinedClass, MainThreadMarker,
+    MainThreadOnly,
+};
+use objc2_foundation::{
+    NSArray, NSAttributedString, NSBundle, NSInteger, NSObject, NSRange, NSString, NSUInteger,
+};
+
+#[cfg(all(feature = "objc2-app-kit", feature = "IMKCandidates"))]
+use crate::IMKCandidates;
+use crate::{IMKInputController, IMKServer};
+
+/// The name of the input controller class that forwards to [`InputMethod`].
+///
+/// This must be set as the `InputMethodServerControllerClass` in the
+/// `Info.plist` of the input method bundle.
+pub const INPUT_CONTROLLER_CLASS_NAME: &str = "RustIMKInputController";
+
+/// The value of `NSNotFound`.
+const NOT_FOUND: usize = isize::MAX as usize;
+
+type Factory = Box<dyn Fn() -> Box<dyn InputMethod> + Send + Sync>;
+
+static FACTORY: OnceLock<Factory> = OnceLock::new();
+
+/// The text handling of an input method.
+///
+/// An instance is created by the factory given to [`InputMethodServer::new`]
+/// for each input session, i.e. usually once for each text field that the
+/// user types in while the input method is selected.
+///
+/// All methods are called on the main thread, and have default
+/// implementations that do nothing.
+pub trait InputMethod: 'static {
+    /// Handle a key press.
+    ///
+    /// `text` contains the characters generated by the key, `key_code` is
+    /// the virtual key code, and `modifiers` are the raw modifier flags, see
+    /// `NSEventModifierFlags`.
+    ///
+    /// Return `true` if the key was handled, or `false` to pass it on to the
+    /// client application.
+    #[doc(alias = "inputText:key:modifiers:client:")]
+    fn input_text(
+        &self,
+        text: &NSString,
+        key_code: NSInteger,
+        modifiers: NSUInteger,
+        client: &TextInputClient<'_>,
+    ) -> bool {
+        let _ = (text, key_code, modifiers, client);
+        false
+    }
+
+    /// The text that is currently being composed, if any.
+    ///
+    /// This is inserted into the client by the default implementation of
+    /// [`commit_composition`](Self::commit_composition).
+    #[doc(alias = "composedString:")]
+    fn composed_string(&self, client: &TextInputClient<'_>) -> Option<Retained<NSString>> {
+        let _ = client;
+        None
+    }
+
+    /// Finish the current composition, e.g. because the user clicked
+    /// somewhere else in the document.
+    ///
+    /// Defaults to inserting the [composed string](Self::composed_string)
+    /// into the client.
+    #[doc(alias = "commitComposition:")]
+    fn commit_composition(&self, client: &TextInputClient<'_>) {
+        if let Some(text) = self.composed_string(client) {
+            client.insert_text(&text);
+        }
+    }
+
+    /// The candidates to show in the candidate window.
+    #[doc(alias = "candidates:")]
+    fn candidates(&self, client: &TextInputClient<'_>) -> Vec<Retained<NSString>> {
+        let _ = client;
+        Vec::new()
+    }
+
+    /// The user selected a candidate in the candidate window.
+    #[doc(alias = "candidateSelected:")]
+    fn candidate_selected(&self, candidate: &NSAttributedString) {
+        let _ = candidate;
+    }
+
+    /// The user moved the selection in the candidate window.
+    #[doc(alias = "candidateSelectionChanged:")]
+    fn candidate_selection_changed(&self, candidate: &NSAttributedString) {
+        let _ = candidate;
+    }
+
+    /// The input method was selected in, or the user switched to, the
+    /// client.
+    #[doc(alias = "activateServer:")]
+    fn activate(&self, client: &TextInputClient<'_>) {
+        let _ = client;
+    }
+
+    /// The input method was deselected, or the user switched away from the
+    /// client.
+    #[doc(alias = "deactivateServer:")]
+    fn deactivate(&self, client: &TextInputClient<'_>) {
+        let _ = client;
+    }
+}
+
+/// The text input client that an input method is sending text to.
+///
+/// This is the object conforming to the `IMKTextInput` protocol that is
+/// passed to the methods of [`InputMethod`].
+#[derive(Clone, Copy)]
+pub struct TextInputClient<'a> {
+    client: &'a AnyObject,
+}
+
+impl<'a> TextInputClient<'a> {
+    /// Wrap an object conforming to `IMKTextInput`.
+    ///
+    ///
+    /// # Safety
+    ///
+    /// The object must conform to the `IMKTextInput` protocol.
+    pub unsafe fn new(client: &'a AnyObject) -> Self {
+        Self { client }
+    }
+
+    /// The underlying client object.
+    pub fn as_object(&self) -> &'a AnyObject {
+        self.client
+    }
+
+    /// Insert text at the insertion point, replacing any marked text.
+    #[doc(alias = "insertText:replacementRange:")]
+    pub fn insert_text(&self, text: &NSString) {
+        let replacement = NSRange::new(NOT_FOUND, 0);
+        unsafe { msg_send![self.client, insertText: text, replacementRange: replacement] }
+    }
+
+    /// Show text that is still being composed at the insertion point,
+    /// replacing any previously marked text.
+    ///
+    /// `selection` is the range within `text` that should be selected.
+    #[doc(alias = "setMarkedText:selectionRange:replacementRange:")]
+    pub fn set_marked_text(&self, text: &NSString, selection: Range<usize>) {
+        let selection = NSRange::from(selection);
+        let replacement = NSRange::new(NOT_FOUND, 0);
+        unsafe {
+            msg_send![
+                self.client,
+                setMarkedText: text,
+                selectionRange: selection,
+                replacementRange: replacement,
+            ]
+        }
+    }
+
+    /// The range of the marked text in the document, if any.
+    #[doc(alias = "markedRange")]
+    pub fn marked_range(&self) -> Option<Range<usize>> {
+        let range: NSRange = unsafe { msg_send![self.client, markedRange] };
+        (range.location != NOT_FOUND).then(|| range.into())
+    }
+
+    /// The range of the selected text in the document, if any.
+    #[doc(alias = "selectedRange")]
+    pub fn selected_range(&self) -> Option<Range<usize>> {
+        let range: NSRange = unsafe { msg_send![self.client, selectedRange] };
+        (range.location != NOT_FOUND).then(|| range.into())
+    }
+
+    /// The bundle identifier of the client application.
+    #[doc(alias = "bundleIdentifier")]
+    pub fn bundle_identifier(&self) -> Option<Retained<NSString>> {
+        unsafe { msg_send_id![self.client, bundleIdentifier] }
+    }
+}
+
+impl fmt::Debug for TextInputClient<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TextInputClient")
+            .field(&self.client)
+            .finish()
+    }
+}
+
+fn with_client<R>(
+    sender: Option<&AnyObject>,
+    f: impl FnOnce(&TextInputClient<'_>) -> R,
+) -> Option<R> {
+    // SAFETY: Input Method Kit passes the client as the sender.
+    sender.map(|client| f(&unsafe { TextInputClient::new(client) }))
+}
+
+define_class!(
+    // SAFETY:
+    // - The superclass IMKInputController does not have any subclassing
+    //   requirements, other than that `activateServer:` and
+    //   `deactivateServer:` must call `super`, which we do.
+    // - `InputController` does not implement `Drop`.
+    #[unsafe(super(IMKInputController, NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "RustIMKInputController"]
+    #[ivars = Box<dyn InputMethod>]
+    struct InputController;
+
+    unsafe impl InputController {
+        #[method_id(initWithServer:delegate:client:)]
+        fn init_with_server(
+            this: Allocated<Self>,
+            server: Option<&IMKServer>,
+            delegate: Option<&AnyObject>,
+            client: Option<&AnyObject>,
+        ) -> Option<Retained<Self>> {
+            let factory = FACTORY
+                .get()
+                .expect("input controller created before `InputMethodServer::new`");
+            let this = this.set_ivars(factory());
+            unsafe {
+                msg_send_id![super(this), initWithServer: server, delegate: delegate, client: client]
+            }
+        }
+
+        #[method(inputText:key:modifiers:client:)]
+        fn input_text(
+            &self,
+            text: Option<&NSString>,
+            key_code: NSInteger,
+            modifiers: NSUInteger,
+            sender: Option<&AnyObject>,
+        ) -> bool {
+            match (text, sender) {
+                (Some(text), Some(client)) => {
+                    // SAFETY: Input Method Kit passes the client as the sender.
+                    let client = unsafe { TextInputClient::new(client) };
+                    self.ivars().input_text(text, key_code, modifiers, &client)
+                }
+                _ => false,
+            }
+        }
+
+        #[method_id(composedString:)]
+        fn composed_string(&self, sender: Option<&AnyObject>) -> Option<Retained<NSString>> {
+            with_client(sender, |client| self.ivars().composed_string(client)).flatten()
+        }
+
+        #[method(commitComposition:)]
+        fn commit_composition(&self, sender: Option<&AnyObject>) {
+            with_client(sender, |client| self.ivars().commit_composition(client));
+        }
+
+        #[method_id(candidates:)]
+        fn candidates(&self, sender: Option<&AnyObject>) -> Option<Retained<NSArray<NSString>>> {
+            with_client(sender, |client| {
+                NSArray::from_retained_slice(&self.ivars().candidates(client))
+            })
+        }
+
+        #[method(candidateSelected:)]
+        fn candidate_selected(&self, candidate: Option<&NSAttributedString>) {
+            if let Some(candidate) = candidate {
+                self.ivars().candidate_selected(candidate);
+            }
+        }
+
+        #[method(candidateSelectionChanged:)]
+        fn candidate_selection_changed(&self, candidate: Option<&NSAttributedString>) {
+            if let Some(candidate) = candidate {
+                self.ivars().candidate_selection_changed(candidate);
+            }
+        }
+
+        #[method(activateServer:)]
+        fn activate_server(&self, sender: Option<&AnyObject>) {
+            let _: () = unsafe { msg_send![super(self), activateServer: sender] };
+            with_client(sender, |client| self.ivars().activate(client));
+        }
+
+        #[method(deactivateServer:)]
+        fn deactivate_server(&self, sender: Option<&AnyObject>) {
+            with_client(sender, |client| self.ivars().deactivate(client));
+            let _: () = unsafe { msg_send![super(self), deactivateServer: sender] };
+        }
+    }
+
+    unsafe impl NSObjectProtocol for InputController {}
+);
+
+/// The server of an input method, which creates an [`InputMethod`] for
+/// each input session.
+///
+/// The input method bundle's `Info.plist` must contain the
+/// `InputMethodConnectionName` that is passed to [`new`](Self::new), and
+/// [`INPUT_CONTROLLER_CLASS_NAME`] as the `InputMethodServerControllerClass`.
+///
+///
+/// # Example
+///
+/// ```ignore
+/// use objc2::MainThreadMarker;
+/// use objc2_app_kit::NSApplication;
+/// use objc2_foundation::NSString;
+/// use objc2_input_method_kit::{InputMethod, InputMethodServer, TextInputClient};
+///
+/// struct Uppercase;
+///
+/// impl InputMethod for Uppercase {
+///     fn input_text(
+///         &self,
+///         text: &NSString,
+///         _key_code: isize,
+///         _modifiers: usize,
+///         client: &TextInputClient<'_>,
+///     ) -> bool {
+///         client.insert_text(&NSString::from_str(&text.to_string().to_uppercase()));
+///         true
+///     }
+/// }
+///
+/// let mtm = MainThreadMarker::new().unwrap();
+/// let _server = InputMethodServer::new("Uppercase_1_Connection", || Uppercase, mtm);
+/// NSApplication::sharedApplication(mtm).run();
+/// ```
+#[derive(Debug)]
+pub struct InputMethodServer {
+    server: Retained<IMKServer>,
+}
+
+impl InputMethodServer {
+    /// Start the input method server with the given connection name.
+    ///
+    /// `factory` is called to create the [`InputMethod`] of each input
+    /// session. The application's run loop must be running for the server
+    /// to receive any input.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if a server was already created, or if the server could not
+    /// be started.
+    #[doc(alias = "initWithName:bundleIdentifier:")]
+    pub fn new<H, F>(connection_name: &str, factory: F, mtm: MainThreadMarker) -> Self
+    where
+        H: InputMethod,
+        F: Fn() -> H + Send + Sync + 'static,
+    {
+        let _ = mtm;
+        let factory: Factory = Box::new(move || Box::new(factory()));
+        if FACTORY.set(factory).is_err() {
+            panic!("an input method server was already created");
+        }
+        // Register the class, such that the server can find it by name.
+        let _ = InputController::class();
+
+        let name = NSString::from_str(connection_name);
+        let bundle_identifier = unsafe { NSBundle::mainBundle().bundleIdentifier() };
+        let server: Option<Retained<IMKServer>> = unsafe {
+            msg_send_id![
+                IMKServer::alloc(),
+                initWithName: &*name,
+                bundleIdentifier: bundle_identifier.as_deref(),
+            ]
+        };
+        Self {
+            server: server.expect("failed starting input method server"),
+        }
+    }
+
+    /// The underlying `IMKServer`.
+    pub fn as_server(&self) -> &IMKServer {
+        &self.server
+    }
+}
+
+/// The layout of a [`CandidateWindow`].
+#[cfg(all(feature = "objc2-app-kit", feature = "IMKCandidates"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CandidatePanelStyle {
+    /// A single column that scrolls vertically.
+    #[default]
+    #[doc(alias = "kIMKSingleColumnScrollingCandidatePanel")]
+    SingleColumn,
+    /// A grid that scrolls vertically.
+    #[doc(alias = "kIMKScrollingGridCandidatePanel")]
+    Grid,
+    /// A single row that pages horizontally.
+    #[doc(alias = "kIMKSingleRowSteppingCandidatePanel")]
+    SingleRow,
+}
+
+#[cfg(all(feature = "objc2-app-kit", feature = "IMKCandidates"))]
+impl CandidatePanelStyle {
+    fn panel_type(self) -> NSUInteger {
+        match self {
+            Self::SingleColumn => 1,
+            Self::Grid => 2,
+            Self::SingleRow => 3,
+        }
+    }
+}
+
+/// A window showing the [candidates](InputMethod::candidates) of the
+/// current input session.
+///
+/// The selected candidate is reported to
+/// [`InputMethod::candidate_selected`].
+#[cfg(all(feature = "objc2-app-kit", feature = "IMKCandidates"))]
+#[derive(Clone, Debug)]
+pub struct CandidateWindow {
+    candidates: Retained<IMKCandidates>,
+}
+
+#[cfg(all(feature = "objc2-app-kit", feature = "IMKCandidates"))]
+impl CandidateWindow {
+    /// Create a hidden candidate window for the given server.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if the window could not be created.
+    #[doc(alias = "initWithServer:panelType:")]
+    pub fn new(
+        server: &InputMethodServer,
+        style: CandidatePanelStyle,
+        mtm: MainThreadMarker,
+    ) -> Self {
+        let candidates: Option<Retained<IMKCandidates>> = unsafe {
+            msg_send_id![
+                mtm.alloc::<IMKCandidates>(),
+                initWithServer: server.as_server(),
+                panelType: style.panel_type(),
+            ]
+        };
+        Self {
+            candidates: candidates.expect("failed creating candidate window"),
+        }
+    }
+
+    /// Fetch the candidates from the current input session again.
+    #[doc(alias = "updateCandidates")]
+    pub fn update(&self) {
+        unsafe { msg_send![&self.candidates, updateCandidates] }
+    }
+
+    /// Show the window below the insertion point.
+    #[doc(alias = "show:")]
+    pub fn show(&self) {
+        // kIMKLocateCandidatesBelowHint
+        let hint: NSUInteger = 2;
+        unsafe { msg_send![&self.candidates, show: hint] }
+    }
+
+    /// Hide the window.
+    pub fn hide(&self) {
+        unsafe { msg_send![&self.candidates, hide] }
+    }
+
+    /// Whether the window is currently shown.
+    #[doc(alias = "isVisible")]
+    pub fn is_visible(&self) -> bool {
+        unsafe { msg_send![&self.candidates, isVisible] }
+    }
+
+    /// The underlying `IMKCandidates`.
+    pub fn as_candidates(&self) -> &IMKCandidates {
+        &self.candidates
+    }
+}
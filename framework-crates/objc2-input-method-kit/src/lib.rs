@@ -17,8 +17,23 @@ extern crate alloc;
 extern crate std;
 
 mod generated;
+#[cfg(all(feature = "std", feature = "IMKInputController", feature = "IMKServer"))]
+mod input_method;
+
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(all(
+    feature = "std",
+    feature = "IMKInputController",
+    feature = "IMKServer",
+    feature = "objc2-app-kit",
+    feature = "IMKCandidates"
+))]
+pub use self::input_method::{CandidatePanelStyle, CandidateWindow};
+#[cfg(all(feature = "std", feature = "IMKInputController", feature = "IMKServer"))]
+pub use self::input_method::{
+    InputMethod, InputMethodServer, TextInputClient, INPUT_CONTROLLER_CLASS_NAME,
+};
 
 extern "C" {
     #[cfg(feature = "IMKInputController")]
@@ -16,5 +16,20 @@ extern crate alloc;
 extern crate std;
 
 mod generated;
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "LPLinkMetadata",
+    feature = "LPMetadataProvider"
+))]
+mod link_preview;
+
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "LPLinkMetadata",
+    feature = "LPMetadataProvider"
+))]
+pub use self::link_preview::{LPLinkPreview, LPLinkPreviewFetch};
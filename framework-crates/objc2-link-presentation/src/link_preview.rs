@@ -0,0 +1,290 @@
+//! Fetching link metadata into Rust types.
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use std::sync::Mutex;
+
+use block2::RcBlock;
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2::AllocAnyThread;
+use objc2_foundation::channel::{self, Receiver, Sender};
+use objc2_foundation::{ns_string, NSData, NSError, NSItemProvider, NSString, NSURL};
+
+use crate::{LPLinkMetadata, LPMetadataProvider};
+
+/// The metadata of a link, as shown in a rich link preview.
+///
+/// This can be fetched with [`LPLinkPreview::fetch`], stored (e.g. in a
+/// database), and later turned back into an [`LPLinkMetadata`] with
+/// [`LPLinkPreview::to_metadata`] to show it in an `LPLinkView`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LPLinkPreview {
+    /// The URL that was requested.
+    pub original_url: Option<String>,
+    /// The URL that the metadata was fetched from, after following
+    /// redirects.
+    pub url: Option<String>,
+    /// The title of the page.
+    pub title: Option<String>,
+    /// The data of the page's icon, usually a PNG or ICO image.
+    pub icon: Option<Vec<u8>>,
+    /// The data of the page's representative image.
+    pub image: Option<Vec<u8>>,
+    /// The URL of a video that represents the page.
+    pub remote_video_url: Option<String>,
+}
+
+fn url_string(url: Option<Retained<NSURL>>) -> Option<String> {
+    url.and_then(|url| unsafe { url.absoluteString() })
+        .map(|string| string.to_string())
+}
+
+fn url_from_string(string: &str) -> Option<Retained<NSURL>> {
+    unsafe { NSURL::URLWithString(&NSString::from_str(string)) }
+}
+
+/// The type identifier used for loading and storing images.
+fn image_type() -> &'static NSString {
+    ns_string!("public.image")
+}
+
+fn image_provider(data: &[u8]) -> Retained<NSItemProvider> {
+    let data = NSData::with_bytes(data);
+    let item = ProtocolObject::from_ref(&*data);
+    unsafe {
+        NSItemProvider::initWithItem_typeIdentifier(
+            NSItemProvider::alloc(),
+            Some(item),
+            Some(image_type()),
+        )
+    }
+}
+
+impl LPLinkPreview {
+    /// Fetch the metadata of the page at the given URL, including the icon
+    /// and image data.
+    ///
+    /// Subresources such as the icon and image are only fetched if they are
+    /// used by the page. The timeout applies to fetching the page itself,
+    /// and defaults to 30 seconds if `None`.
+    ///
+    /// The returned [`LPLinkPreviewFetch`] can be used to wait for the fetch
+    /// to finish, either by blocking, or as a [`Future`].
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_foundation::{ns_string, NSURL};
+    /// use objc2_link_presentation::LPLinkPreview;
+    ///
+    /// let url = unsafe { NSURL::URLWithString(ns_string!("https://www.rust-lang.org")) }.unwrap();
+    /// let preview = LPLinkPreview::fetch(&url, None).wait()?;
+    /// println!("{:?}", preview.title);
+    /// # Ok::<(), objc2::rc::Retained<objc2_foundation::NSError>>(())
+    /// ```
+    #[doc(alias = "startFetchingMetadataForURL:completionHandler:")]
+    pub fn fetch(url: &NSURL, timeout: Option<Duration>) -> LPLinkPreviewFetch {
+        let (sender, receiver) = channel::unbounded();
+
+        let provider = unsafe { LPMetadataProvider::new() };
+        if let Some(timeout) = timeout {
+            unsafe { provider.setTimeout(timeout.as_secs_f64()) };
+        }
+
+        let completion_handler =
+            RcBlock::new(move |metadata: *mut LPLinkMetadata, error: *mut NSError| {
+                // SAFETY: The metadata and error are either NULL or valid.
+                let metadata = unsafe { Retained::retain(metadata) };
+                let error = unsafe { Retained::retain(error) };
+                match (metadata, error) {
+                    (Some(metadata), _) => load_images(&metadata, sender.clone()),
+                    (None, error) => {
+                        let _ = sender.send(Err(error));
+                    }
+                }
+            });
+        // The provider keeps the completion handler alive until it is called.
+        unsafe { provider.startFetchingMetadataForURL_completionHandler(url, &completion_handler) };
+
+        LPLinkPreviewFetch { receiver }
+    }
+
+    /// Get the metadata without loading the icon or image.
+    pub fn from_metadata(metadata: &LPLinkMetadata) -> Self {
+        unsafe {
+            Self {
+                original_url: url_string(metadata.originalURL()),
+                url: url_string(metadata.URL()),
+                title: metadata.title().map(|title| title.to_string()),
+                icon: None,
+                image: None,
+                remote_video_url: url_string(metadata.remoteVideoURL()),
+            }
+        }
+    }
+
+    /// Create an [`LPLinkMetadata`] from the stored metadata, e.g. to show
+    /// it in an `LPLinkView` without fetching it again.
+    ///
+    /// URLs that cannot be parsed by Foundation are left unset.
+    pub fn to_metadata(&self) -> Retained<LPLinkMetadata> {
+        let metadata = unsafe { LPLinkMetadata::new() };
+        let original_url = self.original_url.as_deref().and_then(url_from_string);
+        let url = self.url.as_deref().and_then(url_from_string);
+        let title = self.title.as_deref().map(NSString::from_str);
+        let icon = self.icon.as_deref().map(image_provider);
+        let image = self.image.as_deref().map(image_provider);
+        let remote_video_url = self.remote_video_url.as_deref().and_then(url_from_string);
+        unsafe {
+            metadata.setOriginalURL(original_url.as_deref());
+            metadata.setURL(url.as_deref());
+            metadata.setTitle(title.as_deref());
+            metadata.setIconProvider(icon.as_deref());
+            metadata.setImageProvider(image.as_deref());
+            metadata.setRemoteVideoURL(remote_video_url.as_deref());
+        }
+        metadata
+    }
+}
+
+/// The result sent from the completion handlers to the fetch.
+///
+/// The error is [`None`] if the completion handler was called without
+/// either metadata or an error.
+type FetchResult = Result<LPLinkPreview, Option<Retained<NSError>>>;
+
+/// The preview being assembled while the icon and image are loading.
+struct Pending {
+    preview: LPLinkPreview,
+    remaining: usize,
+    sender: Sender<FetchResult>,
+}
+
+impl Pending {
+    /// Record that a load finished, and send the preview if it was the last.
+    fn finish(&mut self) {
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            let _ = self.sender.send(Ok(core::mem::take(&mut self.preview)));
+        }
+    }
+}
+
+/// Load the icon and image of the metadata, and send the preview once both
+/// have finished loading.
+fn load_images(metadata: &LPLinkMetadata, sender: Sender<FetchResult>) {
+    let icon_provider = unsafe { metadata.iconProvider() };
+    let image_provider = unsafe { metadata.imageProvider() };
+    let mut pending = Pending {
+        preview: LPLinkPreview::from_metadata(metadata),
+        // One extra, so that the preview isn't sent until all loads have
+        // been started.
+        remaining: 1,
+        sender,
+    };
+    pending.remaining += icon_provider.is_some() as usize + image_provider.is_some() as usize;
+    let pending = Arc::new(Mutex::new(pending));
+
+    for (provider, is_icon) in [(icon_provider, true), (image_provider, false)] {
+        let Some(provider) = provider else { continue };
+        let pending = pending.clone();
+        let completion_handler = RcBlock::new(move |data: *mut NSData, _error: *mut NSError| {
+            // SAFETY: The data is either NULL or a valid `NSData`.
+            //
+            // Failing to load an image is not fatal, the preview just won't
+            // have it.
+            let data = unsafe { Retained::retain(data) }.map(|data| data.to_vec());
+            let mut pending = pending.lock().unwrap_or_else(|err| err.into_inner());
+            if is_icon {
+                pending.preview.icon = data;
+            } else {
+                pending.preview.image = data;
+            }
+            pending.finish();
+        });
+        // The returned progress could be used for cancelling, which is not
+        // supported.
+        let _ = unsafe {
+            provider.loadDataRepresentationForTypeIdentifier_completionHandler(
+                image_type(),
+                &completion_handler,
+            )
+        };
+    }
+
+    pending
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .finish();
+}
+
+/// An in-progress fetch started with [`LPLinkPreview::fetch`].
+///
+/// Dropping this does not cancel the fetch.
+pub struct LPLinkPreviewFetch {
+    receiver: Receiver<FetchResult>,
+}
+
+impl LPLinkPreviewFetch {
+    fn map(result: Option<FetchResult>) -> Result<LPLinkPreview, Retained<NSError>> {
+        match result {
+            Some(Ok(preview)) => Ok(preview),
+            Some(Err(Some(error))) => Err(error),
+            Some(Err(None)) | None => {
+                unreachable!("metadata completion handler was not called with a result")
+            }
+        }
+    }
+
+    /// Whether the fetch has finished.
+    ///
+    /// Returns [`None`] if it is still in progress.
+    pub fn try_result(&self) -> Option<Result<LPLinkPreview, Retained<NSError>>> {
+        self.receiver
+            .try_recv()
+            .map(|result| Self::map(Some(result)))
+    }
+
+    /// Block the current thread until the fetch has finished.
+    ///
+    /// The completion handlers are called on a background queue, so this is
+    /// safe to call from any thread, but should be avoided on the main
+    /// thread.
+    pub fn wait(self) -> Result<LPLinkPreview, Retained<NSError>> {
+        Self::map(self.receiver.recv())
+    }
+}
+
+impl Future for LPLinkPreviewFetch {
+    type Output = Result<LPLinkPreview, Retained<NSError>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.receiver.poll_recv(cx).map(Self::map)
+    }
+}
+
+impl fmt::Debug for LPLinkPreviewFetch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LPLinkPreviewFetch")
+            .field("receiver", &self.receiver)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Showing stored metadata.
+#[cfg(all(target_os = "macos", feature = "objc2-app-kit", feature = "LPLinkView"))]
+impl crate::LPLinkView {
+    /// Create a link view showing the given preview.
+    #[doc(alias = "initWithMetadata:")]
+    pub fn from_preview(preview: &LPLinkPreview, mtm: objc2::MainThreadMarker) -> Retained<Self> {
+        let metadata = preview.to_metadata();
+        unsafe { Self::initWithMetadata(Self::alloc(mtm), &metadata) }
+    }
+}
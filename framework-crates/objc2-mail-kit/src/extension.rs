@@ -0,0 +1,613 @@
+//! Implementing Mail extensions in Rust.
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::task::Wake;
+use alloc::vec::Vec;
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Waker};
+use std::error::Error;
+use std::sync::{Mutex, OnceLock};
+
+use block2::{Block, RcBlock};
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, NSObjectProtocol};
+use objc2::{define_class, msg_send_id, AllocAnyThread, ClassType, Message};
+use objc2_foundation::{ns_string, NSArray, NSData, NSError, NSObject, NSString};
+
+use crate::{
+    MEComposeContext, MEContentBlocker, MEDecodedMessage, MEEncodedOutgoingMessage, MEExtension,
+    MEMessage, MEMessageAction, MEMessageActionDecision, MEMessageActionHandler, MEMessageDecoder,
+    MEMessageEncoder, MEMessageEncodingResult, MEMessageSecurityHandler,
+    MEOutgoingMessageEncodingStatus,
+};
+
+/// The name of the extension class that forwards to the handlers registered
+/// with [`MailExtension`].
+///
+/// This must be set as the `NSExtensionPrincipalClass` in the `Info.plist`
+/// of the extension.
+pub const EXTENSION_CLASS_NAME: &str = "RustMEExtension";
+
+/// A boxed future returned from the handler traits.
+///
+/// The future is polled on whichever thread it is woken from, so it must be
+/// [`Send`]. Mail is notified of the result once it completes.
+pub type MailFuture<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
+
+/// The error type returned from the handler traits.
+///
+/// The error is converted to an `NSError` with its [`Display`] output as the
+/// localized description before being passed to Mail.
+///
+/// [`Display`]: fmt::Display
+pub type MailError = Box<dyn Error + Send + Sync>;
+
+static HANDLERS: OnceLock<MailExtension> = OnceLock::new();
+
+fn handlers() -> &'static MailExtension {
+    HANDLERS
+        .get()
+        .expect("Mail extension used before `MailExtension::register`")
+}
+
+/// A content blocker, which blocks remote content from loading in messages.
+#[doc(alias = "MEContentBlocker")]
+pub trait ContentBlocker: Send + Sync + 'static {
+    /// The content blocking rules, in the JSON format used by Safari content
+    /// blockers.
+    #[doc(alias = "contentRulesJSON")]
+    fn content_rules_json(&self) -> Vec<u8>;
+}
+
+/// An action that Mail can apply to an incoming message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[doc(alias = "MEMessageAction")]
+pub enum MessageAction {
+    /// Move the message to the trash mailbox.
+    #[doc(alias = "moveToTrashAction")]
+    MoveToTrash,
+    /// Move the message to the archive mailbox.
+    #[doc(alias = "moveToArchiveAction")]
+    MoveToArchive,
+    /// Move the message to the junk mailbox.
+    #[doc(alias = "moveToJunkAction")]
+    MoveToJunk,
+    /// Mark the message as read.
+    #[doc(alias = "markAsReadAction")]
+    MarkAsRead,
+    /// Mark the message as unread.
+    #[doc(alias = "markAsUnreadAction")]
+    MarkAsUnread,
+}
+
+impl MessageAction {
+    fn to_action(self) -> Retained<MEMessageAction> {
+        let cls = MEMessageAction::class();
+        unsafe {
+            match self {
+                Self::MoveToTrash => msg_send_id![cls, moveToTrashAction],
+                Self::MoveToArchive => msg_send_id![cls, moveToArchiveAction],
+                Self::MoveToJunk => msg_send_id![cls, moveToJunkAction],
+                Self::MarkAsRead => msg_send_id![cls, markAsReadAction],
+                Self::MarkAsUnread => msg_send_id![cls, markAsUnreadAction],
+            }
+        }
+    }
+}
+
+/// The decision of a [`MessageActionHandler`] for an incoming message.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[doc(alias = "MEMessageActionDecision")]
+pub enum ActionDecision {
+    /// Leave the message as-is.
+    #[default]
+    None,
+    /// Apply the given actions to the message.
+    ///
+    /// Applying more than one action requires macOS 13.0.
+    #[doc(alias = "actionDecision:")]
+    #[doc(alias = "decisionApplyingActions:")]
+    Apply(Vec<MessageAction>),
+    /// Ask Mail to call the handler again once the message body has been
+    /// downloaded.
+    #[doc(alias = "invokeAgainWithBody")]
+    InvokeAgainWithBody,
+}
+
+impl ActionDecision {
+    fn to_decision(&self) -> Option<Retained<MEMessageActionDecision>> {
+        let cls = MEMessageActionDecision::class();
+        match self {
+            Self::None => None,
+            Self::Apply(actions) => match &**actions {
+                [] => None,
+                [action] => {
+                    let action = action.to_action();
+                    unsafe { msg_send_id![cls, actionDecision: &*action] }
+                }
+                actions => {
+                    let actions: Vec<_> = actions.iter().map(|action| action.to_action()).collect();
+                    let actions = NSArray::from_retained_slice(&actions);
+                    unsafe { msg_send_id![cls, decisionApplyingActions: &*actions] }
+                }
+            },
+            Self::InvokeAgainWithBody => unsafe { msg_send_id![cls, invokeAgainWithBody] },
+        }
+    }
+}
+
+/// A handler that decides which actions to apply to incoming messages.
+///
+/// The decision is made asynchronously; the message should be inspected
+/// before returning the future, since it cannot be sent to other threads.
+///
+///
+/// # Example
+///
+/// ```ignore
+/// use objc2_mail_kit::{ActionDecision, MailFuture, MEMessage, MessageAction, MessageActionHandler};
+///
+/// struct ArchiveNewsletters;
+///
+/// impl MessageActionHandler for ArchiveNewsletters {
+///     fn decide_action(&self, message: &MEMessage) -> MailFuture<ActionDecision> {
+///         let subject = unsafe { message.subject() }.to_string();
+///         Box::pin(async move {
+///             if subject.contains("Newsletter") {
+///                 ActionDecision::Apply(vec![MessageAction::MoveToArchive])
+///             } else {
+///                 ActionDecision::None
+///             }
+///         })
+///     }
+/// }
+/// ```
+#[doc(alias = "MEMessageActionHandler")]
+pub trait MessageActionHandler: Send + Sync + 'static {
+    /// Decide which actions to apply to the incoming message.
+    #[doc(alias = "decideActionForMessage:completionHandler:")]
+    fn decide_action(&self, message: &MEMessage) -> MailFuture<ActionDecision>;
+
+    /// Additional message headers that must be downloaded before
+    /// [`decide_action`](Self::decide_action) is called.
+    #[doc(alias = "requiredHeaders")]
+    fn required_headers(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Whether an outgoing message can be signed and encrypted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[doc(alias = "MEOutgoingMessageEncodingStatus")]
+pub struct EncodingStatus {
+    /// Whether the message can be signed.
+    pub can_sign: bool,
+    /// Whether the message can be encrypted for all recipients.
+    pub can_encrypt: bool,
+}
+
+/// The raw data of an encoded outgoing message.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[doc(alias = "MEEncodedOutgoingMessage")]
+pub struct EncodedMessage {
+    /// The RFC 822 data of the message.
+    pub data: Vec<u8>,
+    /// Whether the message was signed.
+    pub is_signed: bool,
+    /// Whether the message was encrypted.
+    pub is_encrypted: bool,
+}
+
+/// A handler that signs, encrypts and decodes messages.
+///
+/// All methods have default implementations that leave messages unchanged.
+#[doc(alias = "MEMessageSecurityHandler")]
+pub trait MessageSecurityHandler: Send + Sync + 'static {
+    /// Whether the outgoing message can be signed and encrypted.
+    ///
+    /// This is called while the user is composing the message.
+    #[doc(alias = "getEncodingStatusForMessage:composeContext:completionHandler:")]
+    fn encoding_status(
+        &self,
+        message: &MEMessage,
+        context: &MEComposeContext,
+    ) -> MailFuture<Result<EncodingStatus, MailError>> {
+        let _ = (message, context);
+        Box::pin(core::future::ready(Ok(EncodingStatus::default())))
+    }
+
+    /// Sign and encrypt the outgoing message.
+    ///
+    /// Return `Ok(None)` to send the message unchanged.
+    #[doc(alias = "encodeMessage:composeContext:completionHandler:")]
+    fn encode(
+        &self,
+        message: &MEMessage,
+        context: &MEComposeContext,
+    ) -> MailFuture<Result<Option<EncodedMessage>, MailError>> {
+        let _ = (message, context);
+        Box::pin(core::future::ready(Ok(None)))
+    }
+
+    /// Decode the raw data of an incoming message.
+    ///
+    /// Return [`None`] if the message is not signed or encrypted by this
+    /// extension.
+    #[doc(alias = "decodedMessageForMessageData:")]
+    fn decode(&self, data: &NSData) -> Option<Retained<MEDecodedMessage>> {
+        let _ = data;
+        None
+    }
+}
+
+/// The handlers of a Mail extension.
+///
+/// Create it at the start of the extension's `main`, before calling
+/// `NSExtensionMain`, and set [`EXTENSION_CLASS_NAME`] as the
+/// `NSExtensionPrincipalClass` in the extension's `Info.plist`. Mail only
+/// asks for the handlers whose capabilities are declared in the
+/// `Info.plist`.
+///
+///
+/// # Example
+///
+/// ```ignore
+/// use objc2_mail_kit::{ContentBlocker, MailExtension};
+///
+/// struct BlockTrackers;
+///
+/// impl ContentBlocker for BlockTrackers {
+///     fn content_rules_json(&self) -> Vec<u8> {
+///         br#"[{"trigger": {"url-filter": "tracker"}, "action": {"type": "block"}}]"#.to_vec()
+///     }
+/// }
+///
+/// MailExtension::new().content_blocker(BlockTrackers).register();
+/// // Call NSExtensionMain here.
+/// ```
+#[derive(Default)]
+pub struct MailExtension {
+    content_blocker: Option<Box<dyn ContentBlocker>>,
+    message_actions: Option<Box<dyn MessageActionHandler>>,
+    message_security: Option<Box<dyn MessageSecurityHandler>>,
+}
+
+impl MailExtension {
+    /// Create an extension without any handlers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the content blocker of the extension.
+    #[doc(alias = "handlerForContentBlocker")]
+    pub fn content_blocker(mut self, handler: impl ContentBlocker) -> Self {
+        self.content_blocker = Some(Box::new(handler));
+        self
+    }
+
+    /// Set the message action handler of the extension.
+    #[doc(alias = "handlerForMessageActions")]
+    pub fn message_actions(mut self, handler: impl MessageActionHandler) -> Self {
+        self.message_actions = Some(Box::new(handler));
+        self
+    }
+
+    /// Set the message security handler of the extension.
+    #[doc(alias = "handlerForMessageSecurity")]
+    pub fn message_security(mut self, handler: impl MessageSecurityHandler) -> Self {
+        self.message_security = Some(Box::new(handler));
+        self
+    }
+
+    /// Register the handlers, and the extension class that forwards to
+    /// them.
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if an extension was already registered.
+    pub fn register(self) {
+        if HANDLERS.set(self).is_err() {
+            panic!("a Mail extension was already registered");
+        }
+        // Register the class, such that the extension can find it by name.
+        let _ = Extension::class();
+    }
+}
+
+impl fmt::Debug for MailExtension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MailExtension")
+            .field("content_blocker", &self.content_blocker.is_some())
+            .field("message_actions", &self.message_actions.is_some())
+            .field("message_security", &self.message_security.is_some())
+            .finish()
+    }
+}
+
+/// A completion handler that Mail passed to one of the handlers.
+struct Completion<T: 'static>(RcBlock<dyn Fn(*mut T)>);
+
+// SAFETY: MailKit's completion handlers may be called from any thread.
+unsafe impl<T> Send for Completion<T> {}
+
+impl<T: Message> Completion<T> {
+    fn new(block: &Block<dyn Fn(*mut T)>) -> Self {
+        Self(block.copy())
+    }
+
+    fn complete(self, value: Option<&T>) {
+        let ptr = value.map_or(core::ptr::null_mut(), |value| {
+            let ptr: *const T = value;
+            ptr.cast_mut()
+        });
+        self.0.call((ptr,));
+    }
+}
+
+/// A future that is polled whenever it is woken, until it completes.
+struct Task {
+    future: Mutex<Option<MailFuture<()>>>,
+    notified: AtomicBool,
+}
+
+impl Task {
+    fn run(self: &Arc<Self>) {
+        loop {
+            // If another thread is polling the future, it will see that the
+            // task was notified, and poll it again.
+            let Ok(mut future) = self.future.try_lock() else {
+                return;
+            };
+            self.notified.store(false, Ordering::SeqCst);
+            let Some(inner) = future.as_mut() else {
+                return;
+            };
+            let waker = Waker::from(Arc::clone(self));
+            if inner
+                .as_mut()
+                .poll(&mut Context::from_waker(&waker))
+                .is_ready()
+            {
+                *future = None;
+                return;
+            }
+            drop(future);
+            if !self.notified.load(Ordering::SeqCst) {
+                return;
+            }
+        }
+    }
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.notified.store(true, Ordering::SeqCst);
+        self.run();
+    }
+}
+
+/// Drive the future to completion, and pass its output to `f`.
+fn spawn<T: 'static>(future: MailFuture<T>, f: impl FnOnce(T) + Send + 'static) {
+    let task = Arc::new(Task {
+        future: Mutex::new(Some(Box::pin(async move { f(future.await) }))),
+        notified: AtomicBool::new(false),
+    });
+    task.run();
+}
+
+fn to_error(error: &MailError) -> Retained<NSError> {
+    let description = error.to_string();
+    NSError::new_with(ns_string!("RustErrorDomain"), 0, |builder| {
+        builder.description(&description);
+    })
+}
+
+define_class!(
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - `Extension` does not implement `Drop`.
+    #[unsafe(super(NSObject))]
+    #[name = "RustMEExtension"]
+    struct Extension;
+
+    unsafe impl NSObjectProtocol for Extension {}
+
+    unsafe impl MEExtension for Extension {
+        #[method_id(handlerForContentBlocker)]
+        fn handler_for_content_blocker(&self) -> Retained<ContentBlockerHandler> {
+            assert!(
+                handlers().content_blocker.is_some(),
+                "no content blocker was registered",
+            );
+            unsafe { msg_send_id![ContentBlockerHandler::alloc(), init] }
+        }
+
+        #[method_id(handlerForMessageActions)]
+        fn handler_for_message_actions(&self) -> Retained<ActionHandler> {
+            assert!(
+                handlers().message_actions.is_some(),
+                "no message action handler was registered",
+            );
+            unsafe { msg_send_id![ActionHandler::alloc(), init] }
+        }
+
+        #[method_id(handlerForMessageSecurity)]
+        fn handler_for_message_security(&self) -> Retained<SecurityHandler> {
+            assert!(
+                handlers().message_security.is_some(),
+                "no message security handler was registered",
+            );
+            unsafe { msg_send_id![SecurityHandler::alloc(), init] }
+        }
+    }
+);
+
+define_class!(
+    // SAFETY: Same as `Extension`.
+    #[unsafe(super(NSObject))]
+    #[name = "RustMEContentBlocker"]
+    struct ContentBlockerHandler;
+
+    unsafe impl NSObjectProtocol for ContentBlockerHandler {}
+
+    unsafe impl MEContentBlocker for ContentBlockerHandler {
+        #[method_id(contentRulesJSON)]
+        fn content_rules_json(&self) -> Retained<NSData> {
+            let handler = handlers().content_blocker.as_ref().unwrap();
+            NSData::with_bytes(&handler.content_rules_json())
+        }
+    }
+);
+
+define_class!(
+    // SAFETY: Same as `Extension`.
+    #[unsafe(super(NSObject))]
+    #[name = "RustMEMessageActionHandler"]
+    struct ActionHandler;
+
+    unsafe impl NSObjectProtocol for ActionHandler {}
+
+    unsafe impl MEMessageActionHandler for ActionHandler {
+        #[method(decideActionForMessage:completionHandler:)]
+        fn decide_action(
+            &self,
+            message: &MEMessage,
+            completion_handler: &Block<dyn Fn(*mut MEMessageActionDecision)>,
+        ) {
+            let handler = handlers().message_actions.as_ref().unwrap();
+            let completion = Completion::new(completion_handler);
+            spawn(handler.decide_action(message), move |decision| {
+                completion.complete(decision.to_decision().as_deref());
+            });
+        }
+
+        #[method_id(requiredHeaders)]
+        fn required_headers(&self) -> Retained<NSArray<NSString>> {
+            let handler = handlers().message_actions.as_ref().unwrap();
+            let headers: Vec<_> = handler
+                .required_headers()
+                .iter()
+                .map(|header| NSString::from_str(header))
+                .collect();
+            NSArray::from_retained_slice(&headers)
+        }
+    }
+);
+
+define_class!(
+    // SAFETY: Same as `Extension`.
+    #[unsafe(super(NSObject))]
+    #[name = "RustMEMessageSecurityHandler"]
+    struct SecurityHandler;
+
+    unsafe impl NSObjectProtocol for SecurityHandler {}
+
+    unsafe impl MEMessageDecoder for SecurityHandler {
+        #[method_id(decodedMessageForMessageData:)]
+        fn decoded_message(&self, data: &NSData) -> Option<Retained<MEDecodedMessage>> {
+            let handler = handlers().message_security.as_ref().unwrap();
+            handler.decode(data)
+        }
+    }
+
+    unsafe impl MEMessageEncoder for SecurityHandler {
+        #[method(getEncodingStatusForMessage:composeContext:completionHandler:)]
+        fn encoding_status(
+            &self,
+            message: &MEMessage,
+            context: &MEComposeContext,
+            completion_handler: &Block<dyn Fn(*mut MEOutgoingMessageEncodingStatus)>,
+        ) {
+            let handler = handlers().message_security.as_ref().unwrap();
+            let completion = Completion::new(completion_handler);
+            spawn(handler.encoding_status(message, context), move |status| {
+                let (status, error) = match status {
+                    Ok(status) => (status, None),
+                    Err(error) => (EncodingStatus::default(), Some(to_error(&error))),
+                };
+                let addresses = NSArray::<AnyObject>::new();
+                let status: Retained<MEOutgoingMessageEncodingStatus> = unsafe {
+                    msg_send_id![
+                        MEOutgoingMessageEncodingStatus::alloc(),
+                        initWithCanSign: status.can_sign,
+                        canEncrypt: status.can_encrypt,
+                        securityError: error.as_deref(),
+                        addressesFailingEncryption: &*addresses,
+                    ]
+                };
+                completion.complete(Some(&status));
+            });
+        }
+
+        #[method(encodeMessage:composeContext:completionHandler:)]
+        fn encode(
+            &self,
+            message: &MEMessage,
+            context: &MEComposeContext,
+            completion_handler: &Block<dyn Fn(*mut MEMessageEncodingResult)>,
+        ) {
+            let handler = handlers().message_security.as_ref().unwrap();
+            let completion = Completion::new(completion_handler);
+            spawn(handler.encode(message, context), move |result| {
+                let (message, error) = match result {
+                    Ok(Some(message)) => {
+                        let data = NSData::with_bytes(&message.data);
+                        let message: Retained<MEEncodedOutgoingMessage> = unsafe {
+                            msg_send_id![
+                                MEEncodedOutgoingMessage::alloc(),
+                                initWithRawData: &*data,
+                                isSigned: message.is_signed,
+                                isEncrypted: message.is_encrypted,
+                            ]
+                        };
+                        (Some(message), None)
+                    }
+                    Ok(None) => (None, None),
+                    Err(error) => (None, Some(to_error(&error))),
+                };
+                // The error is reported for both signing and encryption.
+                let result: Retained<MEMessageEncodingResult> = unsafe {
+                    msg_send_id![
+                        MEMessageEncodingResult::alloc(),
+                        initWithEncodedMessage: message.as_deref(),
+                        signingError: error.as_deref(),
+                        encryptionError: error.as_deref(),
+                    ]
+                };
+                completion.complete(Some(&result));
+            });
+        }
+    }
+
+    unsafe impl MEMessageSecurityHandler for SecurityHandler {
+        #[method_id(extensionViewControllerForMessageSigners:)]
+        fn view_controller_for_signers(&self, _signers: &AnyObject) -> Option<Retained<AnyObject>> {
+            None
+        }
+
+        #[method_id(extensionViewControllerForMessageContext:)]
+        fn view_controller_for_context(&self, _context: &AnyObject) -> Option<Retained<AnyObject>> {
+            None
+        }
+
+        #[method(primaryActionClickedForMessageContext:completionHandler:)]
+        fn primary_action_clicked(
+            &self,
+            _context: &AnyObject,
+            completion_handler: &Block<dyn Fn(*mut AnyObject, *mut NSError)>,
+        ) {
+            completion_handler.call((core::ptr::null_mut(), core::ptr::null_mut()));
+        }
+    }
+);
@@ -15,6 +15,48 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "MEComposeContext",
+    feature = "MEContentBlocker",
+    feature = "MEDecodedMessage",
+    feature = "MEEncodedOutgoingMessage",
+    feature = "MEExtension",
+    feature = "MEMessage",
+    feature = "MEMessageAction",
+    feature = "MEMessageActionDecision",
+    feature = "MEMessageActionHandler",
+    feature = "MEMessageDecoder",
+    feature = "MEMessageEncoder",
+    feature = "MEMessageEncodingResult",
+    feature = "MEMessageSecurityHandler",
+    feature = "MEOutgoingMessageEncodingStatus"
+))]
+mod extension;
 mod generated;
+
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "MEComposeContext",
+    feature = "MEContentBlocker",
+    feature = "MEDecodedMessage",
+    feature = "MEEncodedOutgoingMessage",
+    feature = "MEExtension",
+    feature = "MEMessage",
+    feature = "MEMessageAction",
+    feature = "MEMessageActionDecision",
+    feature = "MEMessageActionHandler",
+    feature = "MEMessageDecoder",
+    feature = "MEMessageEncoder",
+    feature = "MEMessageEncodingResult",
+    feature = "MEMessageSecurityHandler",
+    feature = "MEOutgoingMessageEncodingStatus"
+))]
+pub use self::extension::{
+    ActionDecision, ContentBlocker, EncodedMessage, EncodingStatus, MailError, MailExtension,
+    MailFuture, MessageAction, MessageActionHandler, MessageSecurityHandler, EXTENSION_CLASS_NAME,
+};
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
@@ -16,5 +16,30 @@ extern crate alloc;
 extern crate std;
 
 mod generated;
+#[cfg(all(
+    feature = "alloc",
+    feature = "MPMediaItem",
+    feature = "MPNowPlayingInfoCenter"
+))]
+mod now_playing;
+#[cfg(all(
+    feature = "block2",
+    feature = "MPRemoteCommand",
+    feature = "MPRemoteCommandEvent"
+))]
+mod remote_command;
+
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(all(
+    feature = "alloc",
+    feature = "MPMediaItem",
+    feature = "MPNowPlayingInfoCenter"
+))]
+pub use self::now_playing::MPNowPlayingInfo;
+#[cfg(all(
+    feature = "block2",
+    feature = "MPRemoteCommand",
+    feature = "MPRemoteCommandEvent"
+))]
+pub use self::remote_command::MPRemoteCommandHandle;
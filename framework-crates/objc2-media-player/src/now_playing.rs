@@ -0,0 +1,198 @@
+//! Publishing now playing metadata.
+use alloc::string::String;
+use core::time::Duration;
+
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2::Message;
+use objc2_foundation::{NSDictionary, NSMutableDictionary, NSNumber, NSString};
+
+use crate::{
+    MPMediaItemArtwork, MPMediaItemPropertyAlbumTitle, MPMediaItemPropertyArtist,
+    MPMediaItemPropertyArtwork, MPMediaItemPropertyPlaybackDuration, MPMediaItemPropertyTitle,
+    MPNowPlayingInfoCenter, MPNowPlayingInfoPropertyElapsedPlaybackTime,
+    MPNowPlayingInfoPropertyPlaybackRate,
+};
+
+/// Metadata about the currently playing item.
+///
+/// This is shown by the system in e.g. Control Center and on the lock
+/// screen, see [`MPNowPlayingInfoCenter::set_info`].
+///
+/// The system extrapolates the playback position from the elapsed time and
+/// the playback rate, so the info only needs to be updated when playback
+/// starts, pauses or seeks.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct MPNowPlayingInfo {
+    /// The title of the item.
+    #[doc(alias = "MPMediaItemPropertyTitle")]
+    pub title: Option<String>,
+    /// The artist performing the item.
+    #[doc(alias = "MPMediaItemPropertyArtist")]
+    pub artist: Option<String>,
+    /// The title of the album containing the item.
+    #[doc(alias = "MPMediaItemPropertyAlbumTitle")]
+    pub album_title: Option<String>,
+    /// The artwork for the item.
+    #[doc(alias = "MPMediaItemPropertyArtwork")]
+    pub artwork: Option<Retained<MPMediaItemArtwork>>,
+    /// The total duration of the item.
+    #[doc(alias = "MPMediaItemPropertyPlaybackDuration")]
+    pub duration: Option<Duration>,
+    /// The current playback position within the item.
+    #[doc(alias = "MPNowPlayingInfoPropertyElapsedPlaybackTime")]
+    pub elapsed: Option<Duration>,
+    /// The playback rate, where `1.0` is normal speed and `0.0` is paused.
+    #[doc(alias = "MPNowPlayingInfoPropertyPlaybackRate")]
+    pub playback_rate: Option<f64>,
+}
+
+impl MPNowPlayingInfo {
+    /// Create empty now playing info.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the title of the item.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set the artist performing the item.
+    pub fn artist(mut self, artist: impl Into<String>) -> Self {
+        self.artist = Some(artist.into());
+        self
+    }
+
+    /// Set the title of the album containing the item.
+    pub fn album_title(mut self, album_title: impl Into<String>) -> Self {
+        self.album_title = Some(album_title.into());
+        self
+    }
+
+    /// Set the artwork for the item.
+    pub fn artwork(mut self, artwork: &MPMediaItemArtwork) -> Self {
+        self.artwork = Some(artwork.retain());
+        self
+    }
+
+    /// Set the total duration of the item.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Set the current playback position, and the playback rate.
+    pub fn position(mut self, elapsed: Duration, playback_rate: f64) -> Self {
+        self.elapsed = Some(elapsed);
+        self.playback_rate = Some(playback_rate);
+        self
+    }
+
+    /// Convert the info to the dictionary used by
+    /// `MPNowPlayingInfoCenter.nowPlayingInfo`.
+    pub fn to_dictionary(&self) -> Retained<NSDictionary<NSString, AnyObject>> {
+        let dict = NSMutableDictionary::<NSString, AnyObject>::new();
+        let strings = [
+            (unsafe { MPMediaItemPropertyTitle }, &self.title),
+            (unsafe { MPMediaItemPropertyArtist }, &self.artist),
+            (unsafe { MPMediaItemPropertyAlbumTitle }, &self.album_title),
+        ];
+        for (key, value) in strings {
+            if let Some(value) = value {
+                dict.insert(key, &NSString::from_str(value));
+            }
+        }
+        let numbers = [
+            (
+                unsafe { MPMediaItemPropertyPlaybackDuration },
+                self.duration.map(|duration| duration.as_secs_f64()),
+            ),
+            (
+                unsafe { MPNowPlayingInfoPropertyElapsedPlaybackTime },
+                self.elapsed.map(|elapsed| elapsed.as_secs_f64()),
+            ),
+            (
+                unsafe { MPNowPlayingInfoPropertyPlaybackRate },
+                self.playback_rate,
+            ),
+        ];
+        for (key, value) in numbers {
+            if let Some(value) = value {
+                dict.insert(key, &NSNumber::new_f64(value));
+            }
+        }
+        if let Some(artwork) = &self.artwork {
+            dict.insert(unsafe { MPMediaItemPropertyArtwork }, artwork);
+        }
+        dict.into_super()
+    }
+}
+
+impl MPNowPlayingInfoCenter {
+    /// Publish metadata about the currently playing item.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use objc2_media_player::{MPNowPlayingInfo, MPNowPlayingInfoCenter};
+    ///
+    /// let center = unsafe { MPNowPlayingInfoCenter::defaultCenter() };
+    /// center.set_info(
+    ///     &MPNowPlayingInfo::new()
+    ///         .title("Episode 42")
+    ///         .artist("My Podcast")
+    ///         .duration(Duration::from_secs(3600))
+    ///         .position(Duration::ZERO, 1.0),
+    /// );
+    /// ```
+    #[doc(alias = "setNowPlayingInfo:")]
+    pub fn set_info(&self, info: &MPNowPlayingInfo) {
+        unsafe { self.setNowPlayingInfo(Some(&info.to_dictionary())) };
+    }
+
+    /// Clear the now playing metadata, e.g. when playback has stopped.
+    pub fn clear_info(&self) {
+        unsafe { self.setNowPlayingInfo(None) };
+    }
+}
+
+/// Creating artwork from images.
+#[cfg(all(
+    target_os = "macos",
+    feature = "block2",
+    feature = "objc2-app-kit",
+    feature = "objc2-core-foundation",
+    feature = "objc2-core-graphics"
+))]
+impl MPMediaItemArtwork {
+    /// Create artwork from the given image.
+    ///
+    /// The image is returned for all requested sizes, and scaled by the
+    /// system as needed.
+    #[doc(alias = "initWithBoundsSize:requestHandler:")]
+    pub fn from_cg_image(image: &objc2_core_graphics::CGImage) -> Retained<Self> {
+        use core::ptr::NonNull;
+
+        use objc2::AllocAnyThread;
+        use objc2_app_kit::NSImage;
+        use objc2_core_foundation::CGSize;
+
+        // A zero size makes the image use the pixel dimensions of the
+        // `CGImage`.
+        let image = unsafe {
+            NSImage::initWithCGImage_size(NSImage::alloc(), image, CGSize::new(0.0, 0.0))
+        };
+        let bounds_size = unsafe { image.size() };
+        let handler = block2::RcBlock::new(move |_size: CGSize| -> NonNull<NSImage> {
+            // The handler returns an autoreleased image.
+            let image = Retained::autorelease_ptr(image.retain());
+            NonNull::new(image).unwrap()
+        });
+        unsafe { Self::initWithBoundsSize_requestHandler(Self::alloc(), bounds_size, &handler) }
+    }
+}
@@ -0,0 +1,110 @@
+//! Handling remote commands with closures.
+use core::fmt;
+use core::ptr::NonNull;
+use core::time::Duration;
+
+use block2::RcBlock;
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2::Message;
+
+use crate::{
+    MPChangePlaybackPositionCommand, MPChangePlaybackPositionCommandEvent, MPRemoteCommand,
+    MPRemoteCommandEvent, MPRemoteCommandHandlerStatus,
+};
+
+impl MPRemoteCommand {
+    /// Call the given closure when the command is received, e.g. from
+    /// Control Center, headphones or a connected car.
+    ///
+    /// The closure is called on the main thread, and is removed again when
+    /// the returned handle is dropped.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_media_player::{MPRemoteCommandCenter, MPRemoteCommandHandlerStatus};
+    ///
+    /// let center = unsafe { MPRemoteCommandCenter::sharedCommandCenter() };
+    /// let _play = unsafe { center.playCommand() }.add_handler(|_event| {
+    ///     // Start playback...
+    ///     MPRemoteCommandHandlerStatus::Success
+    /// });
+    /// let _pause = unsafe { center.pauseCommand() }.add_handler(|_event| {
+    ///     // Pause playback...
+    ///     MPRemoteCommandHandlerStatus::Success
+    /// });
+    /// ```
+    #[doc(alias = "addTargetWithHandler:")]
+    #[must_use = "the handler is removed when the handle is dropped"]
+    pub fn add_handler(
+        &self,
+        handler: impl Fn(&MPRemoteCommandEvent) -> MPRemoteCommandHandlerStatus + 'static,
+    ) -> MPRemoteCommandHandle {
+        let block = RcBlock::new(move |event: NonNull<MPRemoteCommandEvent>| {
+            // SAFETY: The event is valid for the duration of the call.
+            handler(unsafe { event.as_ref() })
+        });
+        let target = unsafe { self.addTargetWithHandler(&block) };
+        MPRemoteCommandHandle {
+            command: self.retain(),
+            target,
+        }
+    }
+}
+
+impl MPChangePlaybackPositionCommand {
+    /// Call the given closure with the requested playback position when the
+    /// user seeks, e.g. by dragging the scrubber in Control Center.
+    ///
+    /// The closure is called on the main thread, and is removed again when
+    /// the returned handle is dropped.
+    #[doc(alias = "positionTime")]
+    #[must_use = "the handler is removed when the handle is dropped"]
+    pub fn add_seek_handler(
+        &self,
+        handler: impl Fn(Duration) -> MPRemoteCommandHandlerStatus + 'static,
+    ) -> MPRemoteCommandHandle {
+        self.add_handler(move |event| {
+            let Some(event) = event.downcast_ref::<MPChangePlaybackPositionCommandEvent>() else {
+                return MPRemoteCommandHandlerStatus::CommandFailed;
+            };
+            let position = unsafe { event.positionTime() };
+            match Duration::try_from_secs_f64(position) {
+                Ok(position) => handler(position),
+                Err(_) => MPRemoteCommandHandlerStatus::CommandFailed,
+            }
+        })
+    }
+}
+
+/// A handler registered with [`MPRemoteCommand::add_handler`].
+///
+/// The handler is removed from the command when this is dropped.
+pub struct MPRemoteCommandHandle {
+    command: Retained<MPRemoteCommand>,
+    target: Retained<AnyObject>,
+}
+
+impl MPRemoteCommandHandle {
+    /// The command that the handler is registered with.
+    pub fn command(&self) -> &MPRemoteCommand {
+        &self.command
+    }
+}
+
+impl Drop for MPRemoteCommandHandle {
+    #[doc(alias = "removeTarget:")]
+    fn drop(&mut self) {
+        unsafe { self.command.removeTarget(Some(&self.target)) };
+    }
+}
+
+impl fmt::Debug for MPRemoteCommandHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MPRemoteCommandHandle")
+            .field("command", &self.command)
+            .finish_non_exhaustive()
+    }
+}
@@ -62,6 +62,8 @@ mod packed;
 mod private;
 #[cfg(feature = "MTLResource")]
 mod resource;
+#[cfg(feature = "MTLEvent")]
+mod shared_event;
 mod slice;
 #[cfg(feature = "MTLTexture")]
 mod texture;
@@ -78,6 +80,8 @@ pub use self::packed::MTLPackedFloat3;
 pub use self::private::MTLDevicePrivate;
 #[cfg(feature = "MTLResource")]
 pub use self::resource::*;
+#[cfg(feature = "MTLEvent")]
+pub use self::shared_event::MTLSharedEventExt;
 #[cfg(all(feature = "MTLRenderCommandEncoder", feature = "MTLCommandEncoder"))]
 pub use self::slice::MTLRenderCommandEncoderSliceExt;
 #[cfg(feature = "MTLTexture")]
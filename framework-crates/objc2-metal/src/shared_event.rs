@@ -0,0 +1,134 @@
+//! Helpers for synchronizing with `MTLSharedEvent`.
+use core::time::Duration;
+
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2::{ClassType, Message};
+use objc2_foundation::{NSData, NSError, NSKeyedArchiver, NSKeyedUnarchiver};
+
+use crate::{MTLSharedEvent, MTLSharedEventHandle, MTLSharedEventListener};
+
+impl MTLSharedEventListener {
+    /// Create a listener that delivers notifications on the given dispatch
+    /// queue.
+    #[cfg(feature = "dispatch2")]
+    #[doc(alias = "initWithDispatchQueue:")]
+    pub fn with_dispatch_queue(queue: &dispatch2::Queue) -> Retained<Self> {
+        use objc2::AllocAnyThread;
+
+        // SAFETY: The queue is a valid dispatch queue, which is retained by
+        // the listener.
+        unsafe {
+            objc2::msg_send_id![
+                Self::alloc(),
+                initWithDispatchQueue: queue.as_raw(),
+            ]
+        }
+    }
+}
+
+/// Helpers for signaling and waiting on a [`MTLSharedEvent`] from the CPU.
+///
+/// This is implemented for all types that implement [`MTLSharedEvent`],
+/// including `ProtocolObject<dyn MTLSharedEvent>`.
+pub trait MTLSharedEventExt: MTLSharedEvent + Message {
+    /// The current value of the event.
+    #[doc(alias = "signaledValue")]
+    fn signaled_value(&self) -> u64;
+
+    /// Set the value of the event, which signals any GPU work or listeners
+    /// that are waiting for a value less than or equal to it.
+    ///
+    /// Values should be monotonically increasing.
+    #[doc(alias = "setSignaledValue:")]
+    fn signal(&self, value: u64);
+
+    /// Block the current thread until the event reaches the given value, or
+    /// until the timeout expires.
+    ///
+    /// Returns `true` if the event reached the value.
+    #[doc(alias = "waitUntilSignaledValue:timeoutMS:")]
+    fn wait_until_signaled(&self, value: u64, timeout: Duration) -> bool;
+
+    /// Call the closure once the event reaches the given value.
+    ///
+    /// The closure is called on the listener's dispatch queue with the event
+    /// and its value at that time. The closure (and anything it captures) is
+    /// kept alive until then, and is released afterwards.
+    #[cfg(feature = "block2")]
+    #[doc(alias = "notifyListener:atValue:block:")]
+    fn notify<F>(&self, listener: &MTLSharedEventListener, value: u64, f: F)
+    where
+        F: Fn(&ProtocolObject<dyn MTLSharedEvent>, u64) + Send + 'static;
+
+    /// Create a handle to the event, which can be serialized and sent to
+    /// another process, see [`MTLSharedEventHandle::to_data`].
+    #[doc(alias = "newSharedEventHandle")]
+    fn new_handle(&self) -> Retained<MTLSharedEventHandle>;
+}
+
+impl<P: MTLSharedEvent + Message> MTLSharedEventExt for P {
+    fn signaled_value(&self) -> u64 {
+        unsafe { self.signaledValue() }
+    }
+
+    fn signal(&self, value: u64) {
+        unsafe { self.setSignaledValue(value) }
+    }
+
+    fn wait_until_signaled(&self, value: u64, timeout: Duration) -> bool {
+        let timeout_ms = u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX);
+        unsafe { self.waitUntilSignaledValue_timeoutMS(value, timeout_ms) }
+    }
+
+    #[cfg(feature = "block2")]
+    fn notify<F>(&self, listener: &MTLSharedEventListener, value: u64, f: F)
+    where
+        F: Fn(&ProtocolObject<dyn MTLSharedEvent>, u64) + Send + 'static,
+    {
+        let block = block2::RcBlock::new(
+            move |event: core::ptr::NonNull<ProtocolObject<dyn MTLSharedEvent>>, value: u64| {
+                // SAFETY: The event is valid for the duration of the block.
+                f(unsafe { event.as_ref() }, value);
+            },
+        );
+        // SAFETY: The block is `Send`, since it may be called on another
+        // thread, and `'static`, since it is retained by the event.
+        unsafe { self.notifyListener_atValue_block(listener, value, &block) }
+    }
+
+    fn new_handle(&self) -> Retained<MTLSharedEventHandle> {
+        unsafe { self.newSharedEventHandle() }
+    }
+}
+
+/// Serialization.
+///
+/// Handles are usually sent to another process over XPC, but can also be
+/// archived into bytes with these, and recreated in the other process with
+/// `MTLDevice::newSharedEventWithHandle`.
+impl MTLSharedEventHandle {
+    /// Archive the handle with `NSKeyedArchiver`.
+    ///
+    /// The archive is only valid in processes on the same machine, and only
+    /// as long as the event is alive.
+    #[doc(alias = "archivedDataWithRootObject:requiringSecureCoding:error:")]
+    pub fn to_data(&self) -> Result<Retained<NSData>, Retained<NSError>> {
+        unsafe {
+            NSKeyedArchiver::archivedDataWithRootObject_requiringSecureCoding_error(self, true)
+        }
+    }
+
+    /// Unarchive a handle previously archived with [`to_data`].
+    ///
+    /// [`to_data`]: Self::to_data
+    #[doc(alias = "unarchivedObjectOfClass:fromData:error:")]
+    pub fn from_data(data: &NSData) -> Result<Retained<Self>, Retained<NSError>> {
+        let obj = unsafe {
+            NSKeyedUnarchiver::unarchivedObjectOfClass_fromData_error(Self::class(), data)
+        }?;
+        // SAFETY: Secure coding ensures that the object is of the requested
+        // class.
+        Ok(unsafe { Retained::cast_unchecked(obj) })
+    }
+}
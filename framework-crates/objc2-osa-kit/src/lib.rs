@@ -16,8 +16,17 @@ extern crate alloc;
 extern crate std;
 
 mod generated;
+#[cfg(feature = "OSAScript")]
+mod script;
+#[cfg(all(feature = "alloc", feature = "OSAScript"))]
+mod value;
+
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(feature = "OSAScript")]
+pub use self::script::OSAScriptError;
+#[cfg(all(feature = "alloc", feature = "OSAScript"))]
+pub use self::value::{OSARecordKey, OSAValue};
 
 #[allow(unused)]
 pub(crate) type OSType = u32;
@@ -0,0 +1,263 @@
+//! Compiling and executing scripts.
+use core::fmt;
+
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2::AllocAnyThread;
+use objc2_foundation::{
+    NSAppleEventDescriptor, NSArray, NSDictionary, NSNumber, NSRange, NSString, NSValue,
+};
+
+use crate::{
+    OSAScript, OSAScriptErrorAppNameKey, OSAScriptErrorBriefMessageKey, OSAScriptErrorMessageKey,
+    OSAScriptErrorNumberKey, OSAScriptErrorPartialResultKey, OSAScriptErrorRangeKey,
+};
+
+/// An error that occurred while compiling or executing an [`OSAScript`].
+///
+/// This wraps the error info dictionary returned by OSAKit.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct OSAScriptError {
+    info: Retained<NSDictionary<NSString, AnyObject>>,
+}
+
+impl OSAScriptError {
+    fn from_info(info: Option<Retained<NSDictionary<NSString, AnyObject>>>) -> Self {
+        Self {
+            info: info.unwrap_or_default(),
+        }
+    }
+
+    fn get(&self, key: &NSString) -> Option<Retained<AnyObject>> {
+        self.info.objectForKey(key)
+    }
+
+    /// The underlying error info dictionary.
+    pub fn info(&self) -> &NSDictionary<NSString, AnyObject> {
+        &self.info
+    }
+
+    /// A detailed description of the error.
+    #[doc(alias = "OSAScriptErrorMessageKey")]
+    pub fn message(&self) -> Option<Retained<NSString>> {
+        self.get(unsafe { OSAScriptErrorMessageKey })?
+            .downcast()
+            .ok()
+    }
+
+    /// A short description of the error.
+    #[doc(alias = "OSAScriptErrorBriefMessageKey")]
+    pub fn brief_message(&self) -> Option<Retained<NSString>> {
+        self.get(unsafe { OSAScriptErrorBriefMessageKey })?
+            .downcast()
+            .ok()
+    }
+
+    /// The error number, such as `-2753` for an undefined variable.
+    #[doc(alias = "OSAScriptErrorNumberKey")]
+    pub fn number(&self) -> Option<isize> {
+        let number = self.get(unsafe { OSAScriptErrorNumberKey })?;
+        Some(number.downcast_ref::<NSNumber>()?.as_isize())
+    }
+
+    /// The range in the script's source where the error occurred.
+    ///
+    /// The range is in UTF-16 code units.
+    #[doc(alias = "OSAScriptErrorRangeKey")]
+    pub fn range(&self) -> Option<NSRange> {
+        let range = self.get(unsafe { OSAScriptErrorRangeKey })?;
+        range.downcast_ref::<NSValue>()?.get_range()
+    }
+
+    /// The name of the application that caused the error.
+    #[doc(alias = "OSAScriptErrorAppNameKey")]
+    pub fn app_name(&self) -> Option<Retained<NSString>> {
+        self.get(unsafe { OSAScriptErrorAppNameKey })?
+            .downcast()
+            .ok()
+    }
+
+    /// The result of the script up until the point where the error
+    /// occurred, if any.
+    #[doc(alias = "OSAScriptErrorPartialResultKey")]
+    pub fn partial_result(&self) -> Option<Retained<NSAppleEventDescriptor>> {
+        self.get(unsafe { OSAScriptErrorPartialResultKey })?
+            .downcast()
+            .ok()
+    }
+}
+
+impl fmt::Debug for OSAScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OSAScriptError")
+            .field("message", &self.message())
+            .field("number", &self.number())
+            .field("range", &self.range())
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for OSAScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.message() {
+            Some(message) => write!(f, "{message}")?,
+            None => write!(f, "script failed")?,
+        }
+        if let Some(number) = self.number() {
+            write!(f, " ({number})")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OSAScriptError {}
+
+/// Compiling and executing.
+impl OSAScript {
+    /// Compile AppleScript source code.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source code is not valid AppleScript.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_osa_kit::OSAScript;
+    ///
+    /// let script = OSAScript::compile("2 + 3").unwrap();
+    /// let result = script.execute().unwrap();
+    /// assert_eq!(unsafe { result.int32Value() }, 5);
+    /// ```
+    #[doc(alias = "initWithSource:")]
+    #[doc(alias = "compileAndReturnError:")]
+    pub fn compile(source: &str) -> Result<Retained<Self>, OSAScriptError> {
+        let source = NSString::from_str(source);
+        let script = unsafe { Self::initWithSource(Self::alloc(), &source) };
+        script.compile_source()?;
+        Ok(script)
+    }
+
+    /// Compile source code in the given language, such as
+    /// [`OSALanguage::javascript`].
+    ///
+    /// [`OSALanguage::javascript`]: crate::OSALanguage::javascript
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source code is not valid in the language.
+    #[cfg(feature = "OSALanguage")]
+    #[doc(alias = "initWithSource:language:")]
+    #[doc(alias = "compileAndReturnError:")]
+    pub fn compile_with_language(
+        source: &str,
+        language: &crate::OSALanguage,
+    ) -> Result<Retained<Self>, OSAScriptError> {
+        let source = NSString::from_str(source);
+        let script =
+            unsafe { Self::initWithSource_language(Self::alloc(), &source, Some(language)) };
+        script.compile_source()?;
+        Ok(script)
+    }
+
+    fn compile_source(&self) -> Result<(), OSAScriptError> {
+        let mut info = None;
+        if unsafe { self.compileAndReturnError(Some(&mut info)) } {
+            Ok(())
+        } else {
+            Err(OSAScriptError::from_info(info))
+        }
+    }
+
+    fn result(
+        descriptor: Option<Retained<NSAppleEventDescriptor>>,
+        info: Option<Retained<NSDictionary<NSString, AnyObject>>>,
+    ) -> Result<Retained<NSAppleEventDescriptor>, OSAScriptError> {
+        match (descriptor, info) {
+            (_, Some(info)) => Err(OSAScriptError::from_info(Some(info))),
+            (Some(descriptor), None) => Ok(descriptor),
+            // Scripts that don't produce a value may return `nil`.
+            (None, None) => Ok(unsafe { NSAppleEventDescriptor::nullDescriptor() }),
+        }
+    }
+
+    /// Execute the script, and return its result.
+    ///
+    /// The script is compiled first if it isn't already. Use
+    /// [`OSAValue::from_descriptor`] to convert the result to a Rust value.
+    ///
+    /// [`OSAValue::from_descriptor`]: crate::OSAValue::from_descriptor
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the script failed to compile or raised an error.
+    #[doc(alias = "executeAndReturnError:")]
+    pub fn execute(&self) -> Result<Retained<NSAppleEventDescriptor>, OSAScriptError> {
+        let mut info = None;
+        let descriptor = unsafe { self.executeAndReturnError(Some(&mut info)) };
+        Self::result(descriptor, info)
+    }
+
+    /// Call a handler (a function) defined in the script with the given
+    /// arguments, and return its result.
+    ///
+    /// Note that AppleScript handler names are case-insensitive, and must be
+    /// given in lowercase here, while JavaScript function names are
+    /// case-sensitive.
+    ///
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the script failed to compile, if the handler does
+    /// not exist, or if it raised an error.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_osa_kit::{OSAScript, OSAValue};
+    ///
+    /// let script = OSAScript::compile("on add(a, b)\n  return a + b\nend add").unwrap();
+    /// let args = [OSAValue::from(2).to_descriptor(), OSAValue::from(3).to_descriptor()];
+    /// let result = script.call("add", &[&args[0], &args[1]]).unwrap();
+    /// assert_eq!(OSAValue::from_descriptor(&result), OSAValue::Integer(5));
+    /// ```
+    #[doc(alias = "executeHandlerWithName:arguments:error:")]
+    pub fn call(
+        &self,
+        handler: &str,
+        arguments: &[&NSAppleEventDescriptor],
+    ) -> Result<Retained<NSAppleEventDescriptor>, OSAScriptError> {
+        let handler = NSString::from_str(handler);
+        let arguments = NSArray::from_slice(arguments);
+        // SAFETY: Upcasting the element type to `AnyObject` is sound, as the
+        // array is immutable.
+        let arguments: Retained<NSArray> = unsafe { Retained::cast_unchecked(arguments) };
+        let mut info = None;
+        let descriptor = unsafe {
+            self.executeHandlerWithName_arguments_error(&handler, &arguments, Some(&mut info))
+        };
+        Self::result(descriptor, info)
+    }
+}
+
+/// Scripting languages.
+#[cfg(feature = "OSALanguage")]
+impl crate::OSALanguage {
+    /// The AppleScript language.
+    #[doc(alias = "languageForName:")]
+    pub fn apple_script() -> Option<Retained<Self>> {
+        unsafe { Self::languageForName(objc2_foundation::ns_string!("AppleScript")) }
+    }
+
+    /// The JavaScript for Automation (JXA) language.
+    #[doc(alias = "languageForName:")]
+    pub fn javascript() -> Option<Retained<Self>> {
+        unsafe { Self::languageForName(objc2_foundation::ns_string!("JavaScript")) }
+    }
+}
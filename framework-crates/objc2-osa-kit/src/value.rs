@@ -0,0 +1,234 @@
+//! Converting between Apple Event descriptors and Rust values.
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use objc2::rc::Retained;
+use objc2::{msg_send, Message};
+use objc2_foundation::{NSAppleEventDescriptor, NSInteger, NSString};
+
+use crate::OSType;
+
+const fn code(s: &[u8; 4]) -> OSType {
+    OSType::from_be_bytes(*s)
+}
+
+const TYPE_NULL: OSType = code(b"null");
+const TYPE_BOOLEAN: OSType = code(b"bool");
+const TYPE_TRUE: OSType = code(b"true");
+const TYPE_FALSE: OSType = code(b"fals");
+const TYPE_SINT16: OSType = code(b"shor");
+const TYPE_SINT32: OSType = code(b"long");
+const TYPE_IEEE32: OSType = code(b"sing");
+const TYPE_IEEE64: OSType = code(b"doub");
+const TYPE_UNICODE_TEXT: OSType = code(b"utxt");
+const TYPE_UTF8_TEXT: OSType = code(b"utf8");
+const TYPE_CHAR: OSType = code(b"TEXT");
+const TYPE_LIST: OSType = code(b"list");
+const TYPE_RECORD: OSType = code(b"reco");
+/// The keyword under which AppleScript stores record fields with
+/// user-defined labels, as a list of alternating labels and values.
+const KEY_USER_RECORD_FIELDS: OSType = code(b"usrf");
+
+/// The label of a field in an [`OSAValue::Record`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OSARecordKey {
+    /// A four-character keyword, such as `pnam` for the `name` property.
+    Keyword(u32),
+    /// A user-defined label, such as `foo` in `{foo: 1}`.
+    User(String),
+}
+
+/// A Rust representation of the value of an [`NSAppleEventDescriptor`].
+///
+/// This covers the types that scripts commonly produce; other descriptors
+/// (such as object specifiers, dates and aliases) are kept as-is in
+/// [`OSAValue::Other`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum OSAValue {
+    /// `missing value` / `null`.
+    Null,
+    /// A boolean.
+    Bool(bool),
+    /// An integer.
+    Integer(i32),
+    /// A floating point number.
+    Real(f64),
+    /// A string.
+    String(String),
+    /// A list of values.
+    List(Vec<OSAValue>),
+    /// A record with labelled fields, in the order they appear in the
+    /// descriptor.
+    Record(Vec<(OSARecordKey, OSAValue)>),
+    /// A descriptor of a different type.
+    Other(Retained<NSAppleEventDescriptor>),
+}
+
+fn descriptor_type(descriptor: &NSAppleEventDescriptor) -> OSType {
+    // SAFETY: `descriptorType` returns a `DescType`, a four-character code.
+    unsafe { msg_send![descriptor, descriptorType] }
+}
+
+fn items(
+    descriptor: &NSAppleEventDescriptor,
+) -> impl Iterator<Item = (NSInteger, Retained<NSAppleEventDescriptor>)> + '_ {
+    let count = unsafe { descriptor.numberOfItems() };
+    // Descriptor indices are one-based.
+    (1..=count).filter_map(move |i| Some((i, unsafe { descriptor.descriptorAtIndex(i) }?)))
+}
+
+impl OSAValue {
+    /// Convert a descriptor, such as the result of [`OSAScript::execute`],
+    /// to a Rust value.
+    ///
+    /// Lists and records are converted recursively.
+    ///
+    /// [`OSAScript::execute`]: crate::OSAScript::execute
+    pub fn from_descriptor(descriptor: &NSAppleEventDescriptor) -> Self {
+        match descriptor_type(descriptor) {
+            TYPE_NULL => Self::Null,
+            TYPE_BOOLEAN | TYPE_TRUE | TYPE_FALSE => {
+                Self::Bool(unsafe { descriptor.booleanValue() } != 0)
+            }
+            TYPE_SINT16 | TYPE_SINT32 => Self::Integer(unsafe { descriptor.int32Value() }),
+            TYPE_IEEE32 | TYPE_IEEE64 => Self::Real(unsafe { descriptor.doubleValue() }),
+            TYPE_UNICODE_TEXT | TYPE_UTF8_TEXT | TYPE_CHAR => {
+                match unsafe { descriptor.stringValue() } {
+                    Some(string) => Self::String(string.to_string()),
+                    None => Self::Other(descriptor.retain()),
+                }
+            }
+            TYPE_LIST => Self::List(
+                items(descriptor)
+                    .map(|(_, item)| Self::from_descriptor(&item))
+                    .collect(),
+            ),
+            TYPE_RECORD => {
+                let mut fields = Vec::new();
+                for (i, item) in items(descriptor) {
+                    // SAFETY: The index is in bounds.
+                    let keyword: OSType =
+                        unsafe { msg_send![descriptor, keywordForDescriptorAtIndex: i] };
+                    if keyword == KEY_USER_RECORD_FIELDS {
+                        let mut user_fields = items(&item);
+                        while let (Some((_, label)), Some((_, value))) =
+                            (user_fields.next(), user_fields.next())
+                        {
+                            let label = unsafe { label.stringValue() }
+                                .map(|label| label.to_string())
+                                .unwrap_or_default();
+                            fields.push((OSARecordKey::User(label), Self::from_descriptor(&value)));
+                        }
+                    } else {
+                        fields.push((OSARecordKey::Keyword(keyword), Self::from_descriptor(&item)));
+                    }
+                }
+                Self::Record(fields)
+            }
+            _ => Self::Other(descriptor.retain()),
+        }
+    }
+
+    /// Convert the value to a descriptor, for example to pass it as an
+    /// argument to [`OSAScript::call`].
+    ///
+    /// [`OSAScript::call`]: crate::OSAScript::call
+    pub fn to_descriptor(&self) -> Retained<NSAppleEventDescriptor> {
+        match self {
+            Self::Null => unsafe { NSAppleEventDescriptor::nullDescriptor() },
+            Self::Bool(value) => unsafe {
+                NSAppleEventDescriptor::descriptorWithBoolean(u8::from(*value))
+            },
+            Self::Integer(value) => unsafe { NSAppleEventDescriptor::descriptorWithInt32(*value) },
+            Self::Real(value) => unsafe { NSAppleEventDescriptor::descriptorWithDouble(*value) },
+            Self::String(value) => unsafe {
+                NSAppleEventDescriptor::descriptorWithString(&NSString::from_str(value))
+            },
+            Self::List(values) => {
+                let list = unsafe { NSAppleEventDescriptor::listDescriptor() };
+                insert_all(&list, values.iter().map(Self::to_descriptor));
+                list
+            }
+            Self::Record(fields) => {
+                let record = unsafe { NSAppleEventDescriptor::recordDescriptor() };
+                let user_fields = unsafe { NSAppleEventDescriptor::listDescriptor() };
+                for (key, value) in fields {
+                    match key {
+                        OSARecordKey::Keyword(keyword) => {
+                            set_keyword(&record, *keyword, &value.to_descriptor());
+                        }
+                        OSARecordKey::User(label) => insert_all(
+                            &user_fields,
+                            [
+                                unsafe {
+                                    NSAppleEventDescriptor::descriptorWithString(
+                                        &NSString::from_str(label),
+                                    )
+                                },
+                                value.to_descriptor(),
+                            ],
+                        ),
+                    }
+                }
+                if unsafe { user_fields.numberOfItems() } > 0 {
+                    set_keyword(&record, KEY_USER_RECORD_FIELDS, &user_fields);
+                }
+                record
+            }
+            Self::Other(descriptor) => descriptor.clone(),
+        }
+    }
+}
+
+fn insert_all(
+    list: &NSAppleEventDescriptor,
+    items: impl IntoIterator<Item = Retained<NSAppleEventDescriptor>>,
+) {
+    for item in items {
+        // Index 0 appends to the end of the list.
+        unsafe { list.insertDescriptor_atIndex(&item, 0) };
+    }
+}
+
+fn set_keyword(record: &NSAppleEventDescriptor, keyword: OSType, value: &NSAppleEventDescriptor) {
+    // SAFETY: The descriptor is a record, and the keyword is a four-character
+    // code.
+    let _: () = unsafe { msg_send![record, setDescriptor: value, forKeyword: keyword] };
+}
+
+impl From<bool> for OSAValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i32> for OSAValue {
+    fn from(value: i32) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<f64> for OSAValue {
+    fn from(value: f64) -> Self {
+        Self::Real(value)
+    }
+}
+
+impl From<&str> for OSAValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.into())
+    }
+}
+
+impl From<String> for OSAValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl<T: Into<OSAValue>> From<Vec<T>> for OSAValue {
+    fn from(values: Vec<T>) -> Self {
+        Self::List(values.into_iter().map(Into::into).collect())
+    }
+}
@@ -0,0 +1,147 @@
+//! Observing changes to the photo library.
+use core::fmt;
+use core::future::Future;
+use core::task::{Context, Poll};
+
+use objc2::rc::Retained;
+use objc2::runtime::{NSObject, NSObjectProtocol, ProtocolObject};
+use objc2::{define_class, msg_send_id, AllocAnyThread, DefinedClass, Message};
+use objc2_foundation::channel::{self, Receiver, Sender};
+
+use crate::{PHChange, PHPhotoLibrary, PHPhotoLibraryChangeObserver};
+
+/// A change sent from the observer to the stream.
+struct SendChange(Retained<PHChange>);
+
+// SAFETY: `PHChange` is immutable, and is explicitly designed to be handed
+// from the background queue that Photos notifies observers on to e.g. the
+// main queue (this is what Apple's own sample code does).
+unsafe impl Send for SendChange {}
+
+define_class!(
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - `ChangeObserver` does not implement `Drop`.
+    #[unsafe(super(NSObject))]
+    #[name = "__RustPHPhotoLibraryChangeObserver"]
+    #[ivars = Sender<SendChange>]
+    struct ChangeObserver;
+
+    unsafe impl NSObjectProtocol for ChangeObserver {}
+
+    unsafe impl PHPhotoLibraryChangeObserver for ChangeObserver {
+        #[method(photoLibraryDidChange:)]
+        fn photo_library_did_change(&self, change: &PHChange) {
+            let _ = self.ivars().send(SendChange(change.retain()));
+        }
+    }
+);
+
+impl ChangeObserver {
+    fn new(sender: Sender<SendChange>) -> Retained<Self> {
+        let this = Self::alloc().set_ivars(sender);
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+/// A stream of changes to a [`PHPhotoLibrary`].
+///
+/// Changes are queued from the moment the stream is created, and can be
+/// retrieved either by blocking ([`recv`]), by polling ([`try_next`]), or
+/// asynchronously ([`next`] or [`poll_next`], the latter of which can be used
+/// to implement `futures::Stream`).
+///
+/// Use [`PHChange::changeDetailsForFetchResult`] or
+/// [`PHChange::changeDetailsForObject`] on the received changes to find out
+/// what changed in the objects you're interested in.
+///
+/// The observer is unregistered when this is dropped.
+///
+/// See [`PHPhotoLibrary::changes`].
+///
+/// [`recv`]: Self::recv
+/// [`try_next`]: Self::try_next
+/// [`next`]: Self::next
+/// [`poll_next`]: Self::poll_next
+pub struct PHPhotoLibraryChanges {
+    receiver: Receiver<SendChange>,
+    library: Retained<PHPhotoLibrary>,
+    observer: Retained<ChangeObserver>,
+}
+
+impl PHPhotoLibrary {
+    /// Observe changes made to the photo library, whether by this app,
+    /// another app, or iCloud.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use objc2_photos::PHPhotoLibrary;
+    ///
+    /// let library = unsafe { PHPhotoLibrary::sharedPhotoLibrary() };
+    /// let changes = library.changes();
+    ///
+    /// while let Some(change) = changes.recv() {
+    ///     println!("library changed: {change:?}");
+    /// }
+    /// ```
+    #[doc(alias = "registerChangeObserver:")]
+    #[doc(alias = "photoLibraryDidChange:")]
+    pub fn changes(&self) -> PHPhotoLibraryChanges {
+        let (sender, receiver) = channel::unbounded();
+        let observer = ChangeObserver::new(sender);
+        unsafe { self.registerChangeObserver(ProtocolObject::from_ref(&*observer)) };
+
+        PHPhotoLibraryChanges {
+            receiver,
+            library: self.retain(),
+            observer,
+        }
+    }
+}
+
+impl PHPhotoLibraryChanges {
+    /// Get the next change if one has already been received.
+    pub fn try_next(&self) -> Option<Retained<PHChange>> {
+        self.receiver.try_recv().map(|change| change.0)
+    }
+
+    /// Block the current thread until the next change is received.
+    ///
+    /// Photos notifies observers on a background queue, so this is fine to
+    /// call from any thread (though you should avoid blocking the main
+    /// thread).
+    pub fn recv(&self) -> Option<Retained<PHChange>> {
+        self.receiver.recv().map(|change| change.0)
+    }
+
+    /// Poll for the next change.
+    pub fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<Retained<PHChange>>> {
+        self.receiver
+            .poll_recv(cx)
+            .map(|change| change.map(|change| change.0))
+    }
+
+    /// Wait asynchronously for the next change.
+    pub fn next(&self) -> impl Future<Output = Option<Retained<PHChange>>> + '_ {
+        core::future::poll_fn(|cx| self.poll_next(cx))
+    }
+}
+
+impl Drop for PHPhotoLibraryChanges {
+    fn drop(&mut self) {
+        unsafe {
+            self.library
+                .unregisterChangeObserver(ProtocolObject::from_ref(&*self.observer))
+        };
+    }
+}
+
+impl fmt::Debug for PHPhotoLibraryChanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PHPhotoLibraryChanges")
+            .field("receiver", &self.receiver)
+            .finish_non_exhaustive()
+    }
+}
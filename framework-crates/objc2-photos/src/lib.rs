@@ -18,6 +18,29 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(all(feature = "std", feature = "PHChange", feature = "PHPhotoLibrary"))]
+mod change_observer;
 mod generated;
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "PHAsset",
+    feature = "PHAssetResource",
+    feature = "PHAssetResourceManager",
+    feature = "PHObject"
+))]
+mod resource_export;
+
+#[cfg(all(feature = "std", feature = "PHChange", feature = "PHPhotoLibrary"))]
+pub use self::change_observer::PHPhotoLibraryChanges;
 #[allow(unused_imports, unreachable_pub)]
 pub use self::generated::*;
+#[cfg(all(
+    feature = "std",
+    feature = "block2",
+    feature = "PHAsset",
+    feature = "PHAssetResource",
+    feature = "PHAssetResourceManager",
+    feature = "PHObject"
+))]
+pub use self::resource_export::PHAssetResourceExport;
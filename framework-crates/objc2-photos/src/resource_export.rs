@@ -0,0 +1,163 @@
+//! Exporting asset data to the file system.
+use alloc::ffi::CString;
+use core::ffi::c_double;
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::ptr::NonNull;
+use core::task::{Context, Poll};
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+use block2::RcBlock;
+use objc2::rc::Retained;
+use objc2_foundation::channel::{self, Receiver};
+use objc2_foundation::{NSError, NSURL};
+
+use crate::{
+    PHAsset, PHAssetResource, PHAssetResourceManager, PHAssetResourceRequestOptions,
+    PHAssetResourceType,
+};
+
+impl PHAsset {
+    /// The resource containing the asset's original photo, video or audio
+    /// data, as it was when it was first added to the library.
+    ///
+    /// Returns [`None`] if the asset has no original resource (which should
+    /// not happen for assets fetched from the library).
+    ///
+    /// For Live Photos, this is the original photo; use
+    /// [`PHAssetResource::assetResourcesForAsset`] if you need the paired
+    /// video as well.
+    #[doc(alias = "assetResourcesForAsset:")]
+    pub fn original_resource(&self) -> Option<Retained<PHAssetResource>> {
+        let resources = unsafe { PHAssetResource::assetResourcesForAsset(self) };
+        resources.to_vec().into_iter().find(|resource| {
+            matches!(
+                unsafe { resource.r#type() },
+                PHAssetResourceType::Photo
+                    | PHAssetResourceType::Video
+                    | PHAssetResourceType::Audio
+            )
+        })
+    }
+}
+
+impl PHAssetResource {
+    /// Write the resource's data to a file at the given path.
+    ///
+    /// The data is downloaded from iCloud if necessary. `progress` is called
+    /// with a value between `0.0` and `1.0` as the download progresses (it
+    /// is not called if the data is already available locally), and may be
+    /// called from any thread.
+    ///
+    /// The returned [`PHAssetResourceExport`] can be used to wait for the
+    /// export to finish, either by blocking, or as a [`Future`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path contains a NUL byte. Photos fails the
+    /// export if a file already exists at the path.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// # use objc2_photos::PHAsset;
+    /// # let asset: &PHAsset = todo!();
+    ///
+    /// let resource = asset.original_resource().expect("asset has original");
+    /// let export = resource.export_to_path(Path::new("/tmp/IMG_0001.HEIC"), |progress| {
+    ///     println!("downloading: {:.0}%", progress * 100.0);
+    /// })?;
+    /// export.wait()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[doc(alias = "writeDataForAssetResource:toFile:options:completionHandler:")]
+    pub fn export_to_path(
+        &self,
+        path: &Path,
+        progress: impl Fn(f64) + Send + Sync + 'static,
+    ) -> io::Result<PHAssetResourceExport> {
+        let path = CString::new(path.as_os_str().as_bytes())?;
+        let ptr = NonNull::new(path.as_ptr().cast_mut()).unwrap();
+        // SAFETY: The path is a valid, NUL-terminated C string, and the URL
+        // copies it.
+        let url = unsafe {
+            NSURL::fileURLWithFileSystemRepresentation_isDirectory_relativeToURL(ptr, false, None)
+        };
+
+        let progress = RcBlock::new(move |value: c_double| progress(value));
+        let options = unsafe { PHAssetResourceRequestOptions::new() };
+        unsafe { options.setNetworkAccessAllowed(true) };
+        // The block is copied by the setter.
+        unsafe { options.setProgressHandler(RcBlock::as_ptr(&progress)) };
+
+        let (sender, receiver) = channel::unbounded();
+        let completion_handler = RcBlock::new(move |error: *mut NSError| {
+            // SAFETY: The error is either NULL or a valid error.
+            let error = unsafe { Retained::retain(error) };
+            let _ = sender.send(error);
+        });
+
+        let manager = unsafe { PHAssetResourceManager::defaultManager() };
+        unsafe {
+            manager.writeDataForAssetResource_toFile_options_completionHandler(
+                self,
+                &url,
+                Some(&options),
+                &completion_handler,
+            )
+        };
+
+        Ok(PHAssetResourceExport { receiver })
+    }
+}
+
+/// An in-progress export started with [`PHAssetResource::export_to_path`].
+///
+/// Dropping this does not cancel the export.
+pub struct PHAssetResourceExport {
+    receiver: Receiver<Option<Retained<NSError>>>,
+}
+
+impl PHAssetResourceExport {
+    fn map(error: Option<Option<Retained<NSError>>>) -> Result<(), Retained<NSError>> {
+        match error {
+            Some(Some(error)) => Err(error),
+            Some(None) => Ok(()),
+            // The completion handler was released without being called.
+            None => unreachable!("export completion handler was not called"),
+        }
+    }
+
+    /// Whether the export has finished.
+    ///
+    /// Returns [`None`] if it is still in progress.
+    pub fn try_result(&self) -> Option<Result<(), Retained<NSError>>> {
+        self.receiver.try_recv().map(|error| Self::map(Some(error)))
+    }
+
+    /// Block the current thread until the export has finished.
+    pub fn wait(self) -> Result<(), Retained<NSError>> {
+        Self::map(self.receiver.recv())
+    }
+}
+
+impl Future for PHAssetResourceExport {
+    type Output = Result<(), Retained<NSError>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.receiver.poll_recv(cx).map(Self::map)
+    }
+}
+
+impl fmt::Debug for PHAssetResourceExport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PHAssetResourceExport")
+            .field("receiver", &self.receiver)
+            .finish_non_exhaustive()
+    }
+}